@@ -0,0 +1,38 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use raum::neural::chip_firing::ChipFiringGraph;
+
+const SIDES: [usize; 3] = [16, 32, 64];
+
+/// A `side` x `side` grid with a sink in the corner, in the maximal stable configuration
+/// plus one chip at the centre, which sets off an avalanche across the whole grid
+fn critical_grid(side: usize) -> ChipFiringGraph {
+    let mut graph = ChipFiringGraph::new_grid(side, side, vec![0; side * side]).unwrap();
+    graph.set_sink(Some(0)).unwrap();
+    let configuration = (0..side * side)
+        .map(|v| graph.neighbors(v).len() as i32 - 1)
+        .collect();
+    graph.set_configuration(configuration).unwrap();
+    graph.add_chips((side / 2) * side + side / 2, 1).unwrap();
+    graph
+}
+
+fn stabilize_grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stabilize_grid");
+    // Avalanches on the largest grid take a good fraction of a second
+    group.sample_size(20);
+    for side in SIDES {
+        let graph = critical_grid(side);
+        group.bench_with_input(BenchmarkId::from_parameter(side), &side, |b, _| {
+            b.iter_batched(
+                || graph.clone(),
+                |mut graph| graph.stabilize(black_box(usize::MAX)).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, stabilize_grid);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use raum::neural::hopfield::{HopfieldNetwork, SweepOrder, TrainingRule};
+
+const SIZES: [usize; 3] = [64, 256, 1024];
+const SWEEPS: usize = 10;
+const PATTERNS: usize = 5;
+
+fn random_patterns(count: usize, n: usize, rng: &mut StdRng) -> Vec<Vec<f64>> {
+    (0..count)
+        .map(|_| (0..n).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+        .collect()
+}
+
+/// A network of `n` neurons trained with the Hebbian rule, and the patterns it stores
+fn trained_network(n: usize, rng: &mut StdRng) -> (HopfieldNetwork, Vec<Vec<f64>>) {
+    let patterns = random_patterns(PATTERNS, n, rng);
+    let mut net = HopfieldNetwork::new(n);
+    net.train(&patterns, TrainingRule::Hebbian).unwrap();
+    (net, patterns)
+}
+
+fn train(c: &mut Criterion) {
+    let mut group = c.benchmark_group("train");
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(0);
+        let patterns = random_patterns(PATTERNS, n, &mut rng);
+        for (name, rule) in [("hebbian", TrainingRule::Hebbian), ("pseudo_inverse", TrainingRule::PseudoInverse)] {
+            group.bench_with_input(BenchmarkId::new(name, n), &n, |b, &n| {
+                b.iter(|| {
+                    let mut net = HopfieldNetwork::new(n);
+                    net.train(black_box(&patterns), rule).unwrap();
+                    net
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn sync_sweep(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sync_sweep");
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (net, patterns) = trained_network(n, &mut rng);
+        let single = net.to_precision::<f32>();
+        group.bench_with_input(BenchmarkId::new("f64", n), &n, |b, _| {
+            b.iter(|| net.update_step(black_box(&patterns[0]), 1.0, &mut rng).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("f32", n), &n, |b, _| {
+            b.iter(|| single.update_step(black_box(&patterns[0]), 1.0, &mut rng).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Asynchronous sweeps at low temperature, started from a stored pattern so that few
+/// neurons flip: the cached local fields of `run_async` against a full dot product per
+/// update with `update_step_async`
+fn async_sweep(c: &mut Criterion) {
+    let mut group = c.benchmark_group("async_sweep");
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (net, patterns) = trained_network(n, &mut rng);
+        let beta = 1.0;
+
+        group.bench_with_input(BenchmarkId::new("cached_fields", n), &n, |b, _| {
+            b.iter(|| {
+                net.run_async(black_box(&patterns[0]), SWEEPS, beta, SweepOrder::RandomWithReplacement, &mut rng)
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("dot_product", n), &n, |b, _| {
+            b.iter(|| {
+                let mut state = black_box(&patterns[0]).clone();
+                for _ in 0..SWEEPS * n {
+                    net.update_step_async(&mut state, beta, &mut rng).unwrap();
+                }
+                state
+            })
+        });
+    }
+    group.finish();
+}
+
+fn energy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("energy");
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (net, patterns) = trained_network(n, &mut rng);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter(|| net.energy(black_box(&patterns[0])).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, train, sync_sweep, async_sweep, energy);
+criterion_main!(benches);
@@ -0,0 +1,6 @@
+pub mod output;
+pub mod sonification;
+
+// Re-exports
+pub use output::{AudioError, AudioOutput};
+pub use sonification::{SonificationSettings, SonificationSource, Tone};
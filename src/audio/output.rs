@@ -0,0 +1,148 @@
+use std::error::Error;
+use std::fmt;
+
+use super::sonification::Tone;
+
+/// Error types for audio output
+#[derive(Debug)]
+pub enum AudioError {
+    /// Built without the `audio` feature
+    Unavailable,
+    Device(String),
+    Stream(String),
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::Unavailable => write!(f, "Audio output requires building with `--features audio`"),
+            AudioError::Device(msg) => write!(f, "Audio device error: {}", msg),
+            AudioError::Stream(msg) => write!(f, "Audio stream error: {}", msg),
+        }
+    }
+}
+
+impl Error for AudioError {}
+
+#[cfg(feature = "audio")]
+mod device {
+    use std::f32::consts::TAU;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use cpal::{FromSample, SampleFormat, SizedSample, StreamConfig};
+
+    use super::{AudioError, Tone};
+
+    /// Fraction of the remaining distance to the target amplitude covered per sample,
+    /// which ramps the volume over a few milliseconds instead of clicking
+    const AMPLITUDE_SMOOTHING: f32 = 0.002;
+
+    /// Tone shared with the audio thread, as f32 bit patterns
+    #[derive(Default)]
+    struct SharedTone {
+        frequency: AtomicU32,
+        amplitude: AtomicU32,
+    }
+
+    impl SharedTone {
+        fn store(&self, tone: Tone) {
+            self.frequency.store(tone.frequency.to_bits(), Ordering::Relaxed);
+            self.amplitude.store(tone.amplitude.to_bits(), Ordering::Relaxed);
+        }
+
+        fn load(&self) -> Tone {
+            Tone {
+                frequency: f32::from_bits(self.frequency.load(Ordering::Relaxed)),
+                amplitude: f32::from_bits(self.amplitude.load(Ordering::Relaxed)),
+            }
+        }
+    }
+
+    /// A sine oscillator playing on the default output device
+    pub struct AudioOutput {
+        tone: Arc<SharedTone>,
+        _stream: cpal::Stream,
+    }
+
+    impl AudioOutput {
+        pub const AVAILABLE: bool = true;
+
+        /// Opens the default output device and starts playing silence
+        pub fn new() -> Result<Self, AudioError> {
+            let device = cpal::default_host()
+                .default_output_device()
+                .ok_or_else(|| AudioError::Device("No output device".to_string()))?;
+            let supported = device
+                .default_output_config()
+                .map_err(|e| AudioError::Device(e.to_string()))?;
+            let config = supported.config();
+            let tone = Arc::new(SharedTone::default());
+            let stream = match supported.sample_format() {
+                SampleFormat::F32 => build_stream::<f32>(&device, &config, tone.clone()),
+                SampleFormat::I16 => build_stream::<i16>(&device, &config, tone.clone()),
+                SampleFormat::U16 => build_stream::<u16>(&device, &config, tone.clone()),
+                format => Err(AudioError::Device(format!("Unsupported sample format {}", format))),
+            }?;
+            stream.play().map_err(|e| AudioError::Stream(e.to_string()))?;
+            Ok(AudioOutput { tone, _stream: stream })
+        }
+
+        pub fn set_tone(&self, tone: Tone) {
+            self.tone.store(tone);
+        }
+    }
+
+    fn build_stream<T>(
+        device: &cpal::Device,
+        config: &StreamConfig,
+        tone: Arc<SharedTone>,
+    ) -> Result<cpal::Stream, AudioError>
+    where
+        T: SizedSample + FromSample<f32>,
+    {
+        let sample_rate = config.sample_rate.0 as f32;
+        let channels = config.channels as usize;
+        let mut phase = 0.0f32;
+        let mut amplitude = 0.0f32;
+        device
+            .build_output_stream(
+                config,
+                move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                    let target = tone.load();
+                    for frame in data.chunks_mut(channels) {
+                        amplitude += (target.amplitude - amplitude) * AMPLITUDE_SMOOTHING;
+                        phase = (phase + target.frequency / sample_rate).fract();
+                        let value = T::from_sample(amplitude * (TAU * phase).sin());
+                        frame.fill(value);
+                    }
+                },
+                |e| eprintln!("Audio stream error: {}", e),
+                None,
+            )
+            .map_err(|e| AudioError::Stream(e.to_string()))
+    }
+}
+
+#[cfg(not(feature = "audio"))]
+mod device {
+    use super::{AudioError, Tone};
+
+    /// Stand-in for the audio output when built without the `audio` feature
+    pub struct AudioOutput {
+        _private: (),
+    }
+
+    impl AudioOutput {
+        pub const AVAILABLE: bool = false;
+
+        pub fn new() -> Result<Self, AudioError> {
+            Err(AudioError::Unavailable)
+        }
+
+        pub fn set_tone(&self, _tone: Tone) {}
+    }
+}
+
+pub use device::AudioOutput;
@@ -0,0 +1,132 @@
+use crate::core::metrics::hamming_distance;
+use crate::core::SimulationHistory;
+
+/// Quantity of a recorded run that is turned into sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SonificationSource {
+    /// |ΔE| between consecutive recorded states
+    EnergyChange,
+    /// Number of neurons that flipped between consecutive recorded states
+    FlippedNeurons,
+}
+
+impl SonificationSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SonificationSource::EnergyChange => "Energy Change",
+            SonificationSource::FlippedNeurons => "Flipped Neurons",
+        }
+    }
+}
+
+/// A sine tone; an amplitude of 0 is silence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    /// Frequency in Hz
+    pub frequency: f32,
+    /// Amplitude in [0, 1]
+    pub amplitude: f32,
+}
+
+impl Tone {
+    pub const SILENCE: Tone = Tone { frequency: 0.0, amplitude: 0.0 };
+}
+
+/// Mapping from activity to pitch and volume
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SonificationSettings {
+    pub source: SonificationSource,
+    /// Pitch of the smallest nonzero activity in Hz
+    pub low_frequency: f32,
+    /// Pitch of the largest activity in Hz
+    pub high_frequency: f32,
+    /// Master volume in [0, 1]
+    pub volume: f32,
+}
+
+impl Default for SonificationSettings {
+    fn default() -> Self {
+        SonificationSettings {
+            source: SonificationSource::EnergyChange,
+            low_frequency: 220.0,
+            high_frequency: 880.0,
+            volume: 0.3,
+        }
+    }
+}
+
+impl SonificationSettings {
+    /// Activity of every recorded step, scaled so the busiest step of the run is 1.0.
+    /// Entry i is the change from state i - 1 to state i; the initial state has none.
+    pub fn activity(&self, states: &SimulationHistory<Vec<f64>>, energies: &SimulationHistory<f64>) -> Vec<f64> {
+        let values: Vec<&Vec<f64>> = states.states().collect();
+        let flipped: Vec<usize> = std::iter::once(0)
+            .chain(values.windows(2).map(|w| hamming_distance(w[0], w[1])))
+            .collect();
+        self.activity_with_flips(&flipped, energies)
+    }
+
+    /// Like `activity`, with the number of neurons flipped into every recorded state
+    /// already known, e.g. from a flip-list history
+    pub fn activity_with_flips(&self, flipped: &[usize], energies: &SimulationHistory<f64>) -> Vec<f64> {
+        let raw: Vec<f64> = match self.source {
+            SonificationSource::EnergyChange => {
+                let values: Vec<f64> = energies.iter().map(|entry| entry.state).collect();
+                std::iter::once(0.0).chain(values.windows(2).map(|w| (w[1] - w[0]).abs())).collect()
+            },
+            SonificationSource::FlippedNeurons => flipped.iter().map(|&n| n as f64).collect(),
+        };
+        let max = raw.iter().fold(0.0f64, |m, &x| m.max(x));
+        if max <= 0.0 {
+            return vec![0.0; raw.len()];
+        }
+        raw.iter().map(|x| x / max).collect()
+    }
+
+    /// Tone for an activity in [0, 1]: pitch rises exponentially from `low_frequency` to
+    /// `high_frequency` and the tone fades out as the network settles. No activity is silent.
+    pub fn tone(&self, activity: f64) -> Tone {
+        let level = activity.clamp(0.0, 1.0) as f32;
+        if level == 0.0 {
+            return Tone::SILENCE;
+        }
+        let low = self.low_frequency.max(1.0);
+        let ratio = self.high_frequency.max(low) / low;
+        Tone {
+            frequency: low * ratio.powf(level),
+            amplitude: self.volume.clamp(0.0, 1.0) * (0.25 + 0.75 * level),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_is_scaled_to_the_busiest_step() {
+        let mut states = SimulationHistory::new(vec![1.0, 1.0, 1.0, 1.0]);
+        let mut energies = SimulationHistory::new(-1.0);
+        for (i, (state, energy)) in [
+            (vec![-1.0, -1.0, 1.0, 1.0], -3.0),
+            (vec![-1.0, -1.0, -1.0, 1.0], -4.0),
+            (vec![-1.0, -1.0, -1.0, 1.0], -4.0),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            states.push(i + 1, state);
+            energies.push(i + 1, energy);
+        }
+
+        let mut settings = SonificationSettings::default();
+        assert_eq!(settings.activity(&states, &energies), vec![0.0, 1.0, 0.5, 0.0]);
+        settings.source = SonificationSource::FlippedNeurons;
+        assert_eq!(settings.activity(&states, &energies), vec![0.0, 1.0, 0.5, 0.0]);
+
+        assert_eq!(settings.tone(0.0), Tone::SILENCE);
+        let (quiet, loud) = (settings.tone(0.1), settings.tone(1.0));
+        assert!(quiet.frequency < loud.frequency && quiet.amplitude < loud.amplitude);
+        assert!((loud.frequency - settings.high_frequency).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Environment variable naming a directory to load assets from
+pub const ASSETS_ENV_VAR: &str = "RAUM_ASSETS";
+
+/// A data file shipped with the application.
+///
+/// A default copy is embedded in the binary, so the application works when launched
+/// from any directory; a file of the same name found in one of the asset directories
+/// (see `search_dirs`) takes precedence.
+pub struct Asset {
+    /// File name inside an asset directory
+    pub name: &'static str,
+    embedded: &'static [u8],
+}
+
+/// Font used to render the Hopfield letter patterns
+pub const FONT: Asset = Asset {
+    name: "font.otf",
+    embedded: include_bytes!("../../assets/font.otf"),
+};
+
+/// Application icon
+pub const ICON: Asset = Asset {
+    name: "icon.png",
+    embedded: include_bytes!("../../assets/icon.png"),
+};
+
+/// Where an asset was loaded from
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetSource {
+    File(PathBuf),
+    Embedded,
+}
+
+/// Asset directory supplied by the user, e.g. on the command line
+static USER_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Sets (or clears) the user-supplied asset directory, which is searched first
+pub fn set_user_dir(dir: Option<PathBuf>) {
+    if let Ok(mut user_dir) = USER_DIR.write() {
+        *user_dir = dir;
+    }
+}
+
+/// Directories searched for asset files, in order of precedence: the user-supplied
+/// directory, `$RAUM_ASSETS`, `assets/` next to the executable, and `assets/` in the
+/// working directory.
+pub fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = USER_DIR.read().ok().and_then(|dir| dir.clone()) {
+        dirs.push(dir);
+    }
+    if let Some(dir) = std::env::var_os(ASSETS_ENV_VAR) {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+        dirs.push(exe_dir.join("assets"));
+    }
+    dirs.push(PathBuf::from("assets"));
+    dirs
+}
+
+/// The first file called `name` in `dirs`
+fn find_in(dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    dirs.iter().map(|dir| dir.join(name)).find(|path| path.is_file())
+}
+
+impl Asset {
+    /// The copy of the asset compiled into the binary
+    pub fn embedded(&self) -> &'static [u8] {
+        self.embedded
+    }
+
+    /// Path of the file that overrides the embedded copy, if any
+    pub fn resolve(&self) -> Option<PathBuf> {
+        find_in(&search_dirs(), self.name)
+    }
+
+    /// Loads the asset from the first asset directory containing it, falling back to the
+    /// embedded copy if there is none or it cannot be read.
+    ///
+    /// # Returns
+    ///
+    /// The asset contents and where they were loaded from
+    pub fn load(&self) -> (Cow<'static, [u8]>, AssetSource) {
+        if let Some(path) = self.resolve() {
+            match std::fs::read(&path) {
+                Ok(data) => return (Cow::Owned(data), AssetSource::File(path)),
+                Err(e) => eprintln!("Failed to read asset '{}': {}. Using embedded copy.", path.display(), e),
+            }
+        }
+        (Cow::Borrowed(self.embedded), AssetSource::Embedded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in_prefers_earlier_dirs() {
+        let root = std::env::temp_dir().join(format!("raum_assets_test_{}", std::process::id()));
+        let (first, second) = (root.join("first"), root.join("second"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        std::fs::write(second.join("font.otf"), b"second").unwrap();
+
+        let dirs = vec![root.join("missing"), first.clone(), second.clone()];
+        assert_eq!(find_in(&dirs, "font.otf"), Some(second.join("font.otf")));
+        std::fs::write(first.join("font.otf"), b"first").unwrap();
+        assert_eq!(find_in(&dirs, "font.otf"), Some(first.join("font.otf")));
+        assert_eq!(find_in(&dirs, "icon.png"), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_embedded_assets_are_present() {
+        assert!(!FONT.embedded().is_empty());
+        assert!(image::load_from_memory(ICON.embedded()).is_ok());
+    }
+}
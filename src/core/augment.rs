@@ -0,0 +1,112 @@
+/// Transformed copies of grid patterns added to a training set, to show how a network
+/// that has no notion of translation or rotation spends its capacity on every variant
+/// of the same shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Augmentation {
+    /// Shifts by one cell left, right, up and down; cells shifted in are off (-1)
+    pub shifts: bool,
+    /// Mirror image about the vertical axis
+    pub mirror: bool,
+    /// Rotations by 90, 180 and 270 degrees, for square grids
+    pub rotations: bool,
+}
+
+impl Augmentation {
+    pub fn is_enabled(&self) -> bool {
+        self.shifts || self.mirror || self.rotations
+    }
+
+    /// The distinct variants of `pattern`, a `width` x `height` grid stored row by row,
+    /// that differ from the pattern itself
+    pub fn variants(&self, pattern: &[f64], width: usize, height: usize) -> Vec<Vec<f64>> {
+        if pattern.len() != width * height {
+            return Vec::new();
+        }
+        let mut variants: Vec<Vec<f64>> = Vec::new();
+        if self.shifts {
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                variants.push(transform(width, height, |x, y| {
+                    let (sx, sy) = (x as isize - dx, y as isize - dy);
+                    let inside = sx >= 0 && sy >= 0 && (sx as usize) < width && (sy as usize) < height;
+                    if inside { pattern[sy as usize * width + sx as usize] } else { -1.0 }
+                }));
+            }
+        }
+        if self.mirror {
+            variants.push(transform(width, height, |x, y| pattern[y * width + (width - 1 - x)]));
+        }
+        if self.rotations && width == height {
+            let n = width;
+            let mut rotated = pattern.to_vec();
+            for _ in 0..3 {
+                // Quarter turn clockwise: the new row y is the old column y read bottom up
+                let previous = rotated;
+                rotated = transform(n, n, |x, y| previous[(n - 1 - x) * n + y]);
+                variants.push(rotated.clone());
+            }
+        }
+
+        let mut distinct: Vec<Vec<f64>> = Vec::with_capacity(variants.len());
+        for variant in variants {
+            if variant != pattern && !distinct.contains(&variant) {
+                distinct.push(variant);
+            }
+        }
+        distinct
+    }
+
+    /// `patterns` followed by the variants of each, with the label of the pattern each
+    /// variant came from. The originals keep their indices.
+    pub fn apply<L: Clone>(
+        &self,
+        patterns: &[Vec<f64>],
+        labels: &[L],
+        width: usize,
+        height: usize,
+    ) -> (Vec<Vec<f64>>, Vec<L>) {
+        let mut augmented = patterns.to_vec();
+        let mut augmented_labels = labels.to_vec();
+        for (pattern, label) in patterns.iter().zip(labels) {
+            for variant in self.variants(pattern, width, height) {
+                if !augmented.contains(&variant) {
+                    augmented.push(variant);
+                    augmented_labels.push(label.clone());
+                }
+            }
+        }
+        (augmented, augmented_labels)
+    }
+}
+
+/// The `width` x `height` grid with the value `value(x, y)` at each cell
+fn transform(width: usize, height: usize, value: impl Fn(usize, usize) -> f64) -> Vec<f64> {
+    (0..width * height).map(|i| value(i % width, i / width)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variants_of_an_asymmetric_shape() {
+        // An L in the top left corner of a 3x3 grid
+        let pattern = vec![1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0, -1.0, -1.0];
+        let shifts = Augmentation { shifts: true, ..Default::default() }.variants(&pattern, 3, 3);
+        assert_eq!(shifts.len(), 4);
+        assert_eq!(shifts[1], vec![-1.0, 1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0, -1.0]); // Right
+
+        let rotations = Augmentation { rotations: true, ..Default::default() }.variants(&pattern, 3, 3);
+        assert_eq!(rotations[0], vec![-1.0, 1.0, 1.0, -1.0, 1.0, -1.0, -1.0, -1.0, -1.0]); // Clockwise
+        assert_eq!(rotations.len(), 3);
+
+        let all = Augmentation { shifts: true, mirror: true, rotations: true };
+        let (patterns, labels) = all.apply(std::slice::from_ref(&pattern), &['L'], 3, 3);
+        assert_eq!(patterns[0], pattern);
+        assert_eq!(patterns.len(), labels.len());
+        assert!(patterns.len() > 1 && labels.iter().all(|&c| c == 'L'));
+
+        // A symmetric pattern has no distinct mirror image
+        let symmetric = vec![1.0, -1.0, 1.0];
+        assert!(Augmentation { mirror: true, ..Default::default() }.variants(&symmetric, 3, 1).is_empty());
+    }
+}
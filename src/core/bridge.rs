@@ -0,0 +1,99 @@
+use crate::error::{RaumError, Result};
+
+/// A bipolar pattern (+1 / -1 per cell) on a `width` x `height` grid in row order: the
+/// format in which one model's state is handed to another, e.g. the vertices a sandpile
+/// avalanche fired as the initial state of a Hopfield network on the same grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridPattern {
+    pub width: usize,
+    pub height: usize,
+    pub values: Vec<f64>,
+}
+
+impl GridPattern {
+    /// The cells with a source value, error if `len` values cannot cover the grid
+    fn cells(len: usize, width: usize, height: usize) -> Result<usize> {
+        let cells = width * height;
+        if cells == 0 || len < cells {
+            return Err(RaumError::Message(format!(
+                "{} values cannot fill a {}x{} grid",
+                len, width, height
+            )));
+        }
+        Ok(cells)
+    }
+
+    /// +1 for the cells with a positive value and -1 for the others, e.g. from a
+    /// Hopfield state
+    pub fn from_values(values: &[f64], width: usize, height: usize) -> Result<Self> {
+        let cells = Self::cells(values.len(), width, height)?;
+        let values = values[..cells].iter().map(|&v| if v > 0.0 { 1.0 } else { -1.0 }).collect();
+        Ok(GridPattern { width, height, values })
+    }
+
+    /// +1 for the vertices that fired at least `threshold` times, e.g. during one
+    /// avalanche. Vertices after the grid, like the sink of a sandpile, are ignored.
+    pub fn from_firings(firing_counts: &[u64], width: usize, height: usize, threshold: u64) -> Result<Self> {
+        let cells = Self::cells(firing_counts.len(), width, height)?;
+        let values = firing_counts[..cells]
+            .iter()
+            .map(|&count| if count >= threshold.max(1) { 1.0 } else { -1.0 })
+            .collect();
+        Ok(GridPattern { width, height, values })
+    }
+
+    /// +1 for the vertices holding at least `threshold` chips
+    pub fn from_chips(configuration: &[i32], width: usize, height: usize, threshold: i32) -> Result<Self> {
+        let cells = Self::cells(configuration.len(), width, height)?;
+        let values = configuration[..cells].iter().map(|&chips| if chips >= threshold { 1.0 } else { -1.0 }).collect();
+        Ok(GridPattern { width, height, values })
+    }
+
+    /// Chip configuration with `on` chips on the +1 cells and `off` on the others, so a
+    /// pattern can seed a chip-firing graph on the same grid
+    pub fn to_chips(&self, on: i32, off: i32) -> Vec<i32> {
+        self.values.iter().map(|&v| if v > 0.0 { on } else { off }).collect()
+    }
+
+    /// The pattern on a `width` x `height` grid, taking the nearest cell, for models
+    /// on grids of different sizes
+    pub fn resample(&self, width: usize, height: usize) -> Self {
+        let values = (0..height)
+            .flat_map(|row| (0..width).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let x = (column * self.width) / width.max(1);
+                let y = (row * self.height) / height.max(1);
+                self.values[y * self.width + x]
+            })
+            .collect();
+        GridPattern { width, height, values }
+    }
+
+    /// Number of +1 cells
+    pub fn active(&self) -> usize {
+        self.values.iter().filter(|&&v| v > 0.0).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_firings_ignores_sink() {
+        // A 2x2 sandpile with its sink as the fifth vertex
+        let pattern = GridPattern::from_firings(&[0, 2, 1, 0, 9], 2, 2, 2).unwrap();
+        assert_eq!(pattern.values, vec![-1.0, 1.0, -1.0, -1.0]);
+        assert_eq!(pattern.active(), 1);
+        assert_eq!(pattern.to_chips(3, 0), vec![0, 3, 0, 0]);
+        assert!(GridPattern::from_firings(&[0, 1], 2, 2, 1).is_err());
+    }
+
+    #[test]
+    fn test_resample_takes_nearest_cell() {
+        let pattern = GridPattern::from_values(&[1.0, -1.0, -1.0, 1.0], 2, 2).unwrap();
+        let larger = pattern.resample(4, 4);
+        assert_eq!(larger.values[..4], [1.0, 1.0, -1.0, -1.0]);
+        assert_eq!(larger.resample(2, 2), pattern);
+    }
+}
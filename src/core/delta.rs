@@ -0,0 +1,210 @@
+use super::history::{spread_indices, HistoryEntry, SimulationHistory};
+
+/// Replays the change between two consecutive recorded states
+pub trait DeltaCodec {
+    type State: Clone;
+    type Delta;
+
+    /// Turns a state into its successor
+    fn apply(&self, state: &mut Self::State, delta: &Self::Delta);
+}
+
+/// Recorded states of a simulation run stored as the changes between them.
+///
+/// Only the initial state and a full copy every `keyframe_interval` entries are kept;
+/// any other frame is rebuilt on demand by replaying the deltas from the nearest
+/// keyframe before it. Runs that change little per step, such as a nearly converged
+/// Hopfield network, then take a fraction of the memory of a `SimulationHistory`.
+#[derive(Debug, Clone)]
+pub struct DeltaHistory<C: DeltaCodec> {
+    codec: C,
+
+    /// Iteration of every entry, starting with 0 for the initial state
+    iterations: Vec<usize>,
+
+    /// Change leading into entry i + 1
+    deltas: Vec<C::Delta>,
+
+    /// Full states of entries 0, k, 2k, ... for k = `keyframe_interval`
+    keyframes: Vec<C::State>,
+    keyframe_interval: usize,
+
+    /// The most recent state
+    current: C::State,
+}
+
+impl<C: DeltaCodec> DeltaHistory<C> {
+    /// Creates a history starting with `initial` at iteration 0 that stores a full state
+    /// every `keyframe_interval` entries (at least 1)
+    pub fn new(codec: C, initial: C::State, keyframe_interval: usize) -> Self {
+        DeltaHistory {
+            codec,
+            iterations: vec![0],
+            deltas: Vec::new(),
+            keyframes: vec![initial.clone()],
+            keyframe_interval: keyframe_interval.max(1),
+            current: initial,
+        }
+    }
+
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Record the state reached by applying `delta` to the latest one at `iteration`
+    pub fn push(&mut self, iteration: usize, delta: C::Delta) {
+        self.codec.apply(&mut self.current, &delta);
+        self.deltas.push(delta);
+        self.iterations.push(iteration);
+        if (self.iterations.len() - 1).is_multiple_of(self.keyframe_interval) {
+            self.keyframes.push(self.current.clone());
+        }
+    }
+
+    /// Number of recorded entries, including the initial state
+    pub fn len(&self) -> usize {
+        self.iterations.len()
+    }
+
+    /// Always false: the initial state is recorded on creation
+    pub fn is_empty(&self) -> bool {
+        self.iterations.is_empty()
+    }
+
+    /// Iteration the entry at `index` was recorded at
+    pub fn iteration(&self, index: usize) -> Option<usize> {
+        self.iterations.get(index).copied()
+    }
+
+    /// Iteration of the most recent entry
+    pub fn last_iteration(&self) -> usize {
+        self.iterations.last().copied().unwrap_or(0)
+    }
+
+    /// Change that led to the entry at `index` (None for the initial state)
+    pub fn delta(&self, index: usize) -> Option<&C::Delta> {
+        index.checked_sub(1).and_then(|i| self.deltas.get(i))
+    }
+
+    /// The initial state
+    pub fn first(&self) -> &C::State {
+        &self.keyframes[0]
+    }
+
+    /// The most recent state
+    pub fn last(&self) -> &C::State {
+        &self.current
+    }
+
+    /// State of the entry at `index`, rebuilt from the nearest keyframe
+    pub fn frame(&self, index: usize) -> Option<C::State> {
+        if index >= self.len() {
+            return None;
+        }
+        let keyframe = index / self.keyframe_interval;
+        let mut state = self.keyframes[keyframe].clone();
+        for delta in &self.deltas[keyframe * self.keyframe_interval..index] {
+            self.codec.apply(&mut state, delta);
+        }
+        Some(state)
+    }
+
+    /// Entries at the sorted `indices`, replayed in a single pass
+    pub fn frames(&self, indices: &[usize]) -> Vec<HistoryEntry<C::State>> {
+        let mut frames = Vec::with_capacity(indices.len());
+        let mut cursor: Option<(usize, C::State)> = None;
+        for &index in indices.iter().filter(|&&i| i < self.len()) {
+            // Continue from the previous frame unless a keyframe is closer
+            let (mut at, mut state) = match cursor.take() {
+                Some((at, state)) if at <= index && index / self.keyframe_interval * self.keyframe_interval <= at => {
+                    (at, state)
+                }
+                _ => {
+                    let keyframe = index / self.keyframe_interval;
+                    (keyframe * self.keyframe_interval, self.keyframes[keyframe].clone())
+                }
+            };
+            while at < index {
+                self.codec.apply(&mut state, &self.deltas[at]);
+                at += 1;
+            }
+            frames.push(HistoryEntry { iteration: self.iterations[index], state: state.clone() });
+            cursor = Some((at, state));
+        }
+        frames
+    }
+
+    /// At most `max_points` entries spread evenly over the history, e.g. for plotting
+    pub fn downsample(&self, max_points: usize) -> Vec<HistoryEntry<C::State>> {
+        self.frames(&spread_indices(self.len(), max_points))
+    }
+
+    /// A history of `f` applied to every frame, replaying the deltas once
+    pub fn map<U>(&self, mut f: impl FnMut(&C::State) -> U) -> SimulationHistory<U> {
+        self.try_map(|state| Ok::<U, std::convert::Infallible>(f(state)))
+            .unwrap_or_else(|never| match never {})
+    }
+
+    /// Like `map`, but stops at the first error
+    pub fn try_map<U, E>(&self, mut f: impl FnMut(&C::State) -> Result<U, E>) -> Result<SimulationHistory<U>, E> {
+        let mut state = self.first().clone();
+        let mut history = SimulationHistory::new(f(&state)?);
+        for (delta, &iteration) in self.deltas.iter().zip(&self.iterations[1..]) {
+            self.codec.apply(&mut state, delta);
+            history.push(iteration, f(&state)?);
+        }
+        Ok(history)
+    }
+
+    /// Every frame as a full history
+    pub fn to_history(&self) -> SimulationHistory<C::State> {
+        self.map(|state| state.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deltas that add to single entries of a vector
+    struct Increments;
+
+    impl DeltaCodec for Increments {
+        type State = Vec<i32>;
+        type Delta = (usize, i32);
+
+        fn apply(&self, state: &mut Vec<i32>, &(index, amount): &(usize, i32)) {
+            state[index] += amount;
+        }
+    }
+
+    #[test]
+    fn test_frames_match_full_history() {
+        let mut deltas = DeltaHistory::new(Increments, vec![0; 4], 3);
+        let mut full = SimulationHistory::new(vec![0; 4]);
+        let mut state = vec![0; 4];
+        for step in 1..=10 {
+            let delta = (step % 4, step as i32);
+            state[delta.0] += delta.1;
+            deltas.push(2 * step, delta);
+            full.push(2 * step, state.clone());
+        }
+
+        assert_eq!(deltas.len(), full.len());
+        assert_eq!(deltas.last(), full.last().unwrap());
+        for i in 0..full.len() {
+            assert_eq!(deltas.frame(i).as_ref(), full.get(i));
+        }
+        assert!(deltas.frame(full.len()).is_none());
+        assert_eq!(deltas.to_history(), full);
+
+        let indices = [0, 2, 3, 7, 10];
+        let frames = deltas.frames(&indices);
+        for (frame, &i) in frames.iter().zip(&indices) {
+            assert_eq!(Some(frame), full.entry(i));
+        }
+        let sampled = deltas.downsample(4);
+        assert_eq!(sampled.first().map(|e| e.iteration), Some(0));
+        assert_eq!(sampled.last().map(|e| e.iteration), Some(20));
+    }
+}
@@ -0,0 +1,500 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use nalgebra::DMatrix;
+use rand::Rng;
+
+/// Error types for graph construction and parsing
+#[derive(Debug)]
+pub enum GraphError {
+    InvalidGraphStructure(String),
+    Parse(String),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::InvalidGraphStructure(msg) => write!(f, "Invalid graph structure: {}", msg),
+            GraphError::Parse(msg) => write!(f, "Parse error: {}", msg),
+        }
+    }
+}
+
+impl Error for GraphError {}
+
+/// An undirected edge (from, to, multiplicity)
+pub type WeightedEdge = (usize, usize, u32);
+
+/// An undirected multigraph on vertices 0..n, stored as an edge list.
+///
+/// This is the model-independent description of a topology: chip-firing graphs,
+/// rotor routers and sparse Hopfield networks are all built from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph {
+    num_vertices: usize,
+    edges: Vec<WeightedEdge>,
+}
+
+impl Graph {
+    /// Creates a graph without edges
+    pub fn empty(num_vertices: usize) -> Self {
+        Graph { num_vertices, edges: Vec::new() }
+    }
+
+    /// Creates a graph from undirected edges; repeated pairs add up
+    pub fn from_edges(num_vertices: usize, edges: Vec<WeightedEdge>) -> Result<Self, GraphError> {
+        if let Some(&(from, to, _)) = edges.iter().find(|&&(from, to, _)| from >= num_vertices || to >= num_vertices) {
+            return Err(GraphError::InvalidGraphStructure(format!(
+                "Edge ({}, {}) references vertex outside range 0..{}",
+                from, to, num_vertices
+            )));
+        }
+        Ok(Graph { num_vertices, edges })
+    }
+
+    /// A width × height grid with 4-connectivity, vertices in row-major order
+    pub fn grid(width: usize, height: usize) -> Self {
+        let mut graph = Graph::empty(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let idx = y * width + x;
+                if y > 0 {
+                    graph.edges.push((idx, idx - width, 1));
+                }
+                if x > 0 {
+                    graph.edges.push((idx, idx - 1, 1));
+                }
+            }
+        }
+        graph
+    }
+
+    /// The cycle 0 - 1 - ... - (n-1) - 0
+    pub fn cycle(n: usize) -> Self {
+        let mut graph = Graph::empty(n);
+        graph.edges = (0..n).map(|i| (i, (i + 1) % n, 1)).collect();
+        graph
+    }
+
+    /// Every pair of distinct vertices joined by one edge
+    pub fn complete(n: usize) -> Self {
+        let mut graph = Graph::empty(n);
+        graph.edges = (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j, 1))).collect();
+        graph
+    }
+
+    /// Vertex 0 joined to every other vertex
+    pub fn star(n: usize) -> Self {
+        let mut graph = Graph::empty(n);
+        graph.edges = (1..n).map(|i| (0, i, 1)).collect();
+        graph
+    }
+
+    /// Erdős-Rényi G(n, p): each pair i < j is joined with probability `p`, drawn in
+    /// row-major order of the upper triangle
+    pub fn erdos_renyi(n: usize, p: f64, rng: &mut impl Rng) -> Self {
+        let mut graph = Graph::empty(n);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if rng.gen::<f64>() <= p {
+                    graph.edges.push((i, j, 1));
+                }
+            }
+        }
+        graph
+    }
+
+    /// Parses edges written as `from,to` or `from,to,multiplicity`, separated by
+    /// whitespace, e.g. "0,1 1,2,3 2,0". The graph has max index + 1 vertices.
+    pub fn parse_edges(text: &str) -> Result<Self, GraphError> {
+        let mut edges = Vec::new();
+        let mut max_vertex = 0;
+
+        for edge_str in text.split_whitespace() {
+            let parts: Vec<&str> = edge_str.split(',').collect();
+            if parts.len() != 2 && parts.len() != 3 {
+                return Err(GraphError::Parse(format!(
+                    "Invalid edge format: '{}'. Use 'from,to' or 'from,to,weight' format.",
+                    edge_str
+                )));
+            }
+
+            let vertex = |part: &str| {
+                part.parse::<usize>().map_err(|_| {
+                    GraphError::Parse(format!("Invalid vertex index: '{}' in edge '{}'", part, edge_str))
+                })
+            };
+            let from = vertex(parts[0])?;
+            let to = vertex(parts[1])?;
+
+            // The optional weight is the number of parallel edges
+            let weight = match parts.get(2) {
+                Some(part) => part.parse::<u32>().ok().filter(|&w| w > 0).ok_or_else(|| {
+                    GraphError::Parse(format!(
+                        "Invalid weight: '{}' in edge '{}'. Use a positive whole number.",
+                        part, edge_str
+                    ))
+                })?,
+                None => 1,
+            };
+
+            edges.push((from, to, weight));
+            max_vertex = max_vertex.max(from).max(to);
+        }
+
+        if edges.is_empty() {
+            return Err(GraphError::Parse("No valid edges provided".to_string()));
+        }
+
+        Ok(Graph { num_vertices: max_vertex + 1, edges })
+    }
+
+    pub fn num_vertices(&self) -> usize {
+        self.num_vertices
+    }
+
+    pub fn edges(&self) -> &[WeightedEdge] {
+        &self.edges
+    }
+
+    /// For each vertex, its (neighbor, multiplicity) entries, one per edge end. A
+    /// self-loop appears twice in its vertex's list.
+    pub fn neighbor_lists(&self) -> Vec<Vec<(usize, u32)>> {
+        let mut lists = vec![Vec::new(); self.num_vertices];
+        for &(from, to, count) in &self.edges {
+            lists[from].push((to, count));
+            lists[to].push((from, count));
+        }
+        lists
+    }
+
+    /// The Laplacian matrix L = D - A, with edge multiplicities as weights
+    pub fn laplacian(&self) -> DMatrix<f64> {
+        laplacian(&self.neighbor_lists())
+    }
+
+    pub fn laplacian_spectrum(&self) -> LaplacianSpectrum {
+        LaplacianSpectrum::new(self.laplacian())
+    }
+
+    pub fn statistics(&self) -> GraphStatistics {
+        GraphStatistics::new(&self.neighbor_lists())
+    }
+
+    /// Whether `i` and `j` are joined by at least one edge
+    pub fn adjacency(&self) -> Vec<Vec<bool>> {
+        let mut adjacency = vec![vec![false; self.num_vertices]; self.num_vertices];
+        for &(from, to, count) in &self.edges {
+            if count > 0 {
+                adjacency[from][to] = true;
+                adjacency[to][from] = true;
+            }
+        }
+        adjacency
+    }
+}
+
+/// The Laplacian L = D - A of a graph given by per-vertex (neighbor, multiplicity)
+/// lists, as in [`Graph::neighbor_lists`]
+pub fn laplacian(neighbor_lists: &[Vec<(usize, u32)>]) -> DMatrix<f64> {
+    let n = neighbor_lists.len();
+    let mut matrix = DMatrix::zeros(n, n);
+    for (i, list) in neighbor_lists.iter().enumerate() {
+        for &(j, count) in list {
+            matrix[(i, i)] += count as f64;
+            matrix[(i, j)] -= count as f64;
+        }
+    }
+    matrix
+}
+
+/// Degree distribution, connectivity and distances of a graph, with edges taken as
+/// undirected and distances counted in hops
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphStatistics {
+    /// Number of vertices of each degree that occurs (edge multiplicities counted)
+    pub degree_histogram: BTreeMap<u64, usize>,
+
+    /// Sizes of the connected components, largest first
+    pub component_sizes: Vec<usize>,
+
+    /// Longest shortest path within a component
+    pub diameter: usize,
+
+    /// Mean shortest path length over pairs of distinct vertices in the same component,
+    /// or 0 if there are none
+    pub average_path_length: f64,
+}
+
+impl GraphStatistics {
+    /// Statistics of the graph given by per-vertex (neighbor, multiplicity) lists, as in
+    /// [`Graph::neighbor_lists`]. Distances take a breadth-first search from every
+    /// vertex, so this is quadratic in the size of the graph.
+    pub fn new(neighbor_lists: &[Vec<(usize, u32)>]) -> Self {
+        let n = neighbor_lists.len();
+        let mut degree_histogram = BTreeMap::new();
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (i, list) in neighbor_lists.iter().enumerate() {
+            let degree: u64 = list.iter().map(|&(_, count)| u64::from(count)).sum();
+            *degree_histogram.entry(degree).or_insert(0) += 1;
+            for &(j, count) in list {
+                if count > 0 && j < n && j != i {
+                    adjacency[i].push(j);
+                    adjacency[j].push(i);
+                }
+            }
+        }
+
+        let mut component = vec![usize::MAX; n];
+        let mut component_sizes = Vec::new();
+        let mut diameter = 0;
+        let (mut path_sum, mut pairs) = (0u64, 0u64);
+        let mut distance = vec![usize::MAX; n];
+        let mut queue = VecDeque::new();
+        for source in 0..n {
+            distance.iter_mut().for_each(|d| *d = usize::MAX);
+            distance[source] = 0;
+            queue.push_back(source);
+            let mut reached = 0;
+            while let Some(vertex) = queue.pop_front() {
+                reached += 1;
+                diameter = diameter.max(distance[vertex]);
+                path_sum += distance[vertex] as u64;
+                for &neighbor in &adjacency[vertex] {
+                    if distance[neighbor] == usize::MAX {
+                        distance[neighbor] = distance[vertex] + 1;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            pairs += reached as u64 - 1;
+            // The first search to reach a component labels it
+            if component[source] == usize::MAX {
+                for (vertex, &d) in distance.iter().enumerate() {
+                    if d != usize::MAX {
+                        component[vertex] = component_sizes.len();
+                    }
+                }
+                component_sizes.push(reached);
+            }
+        }
+        component_sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+        GraphStatistics {
+            degree_histogram,
+            component_sizes,
+            diameter,
+            average_path_length: if pairs > 0 { path_sum as f64 / pairs as f64 } else { 0.0 },
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.component_sizes.len() <= 1
+    }
+}
+
+/// Eigenvalues of a graph Laplacian, with the eigenvector of the second smallest one.
+///
+/// For a connected graph λ₁ = 0 and the spectral gap λ₂ (algebraic connectivity)
+/// sets how fast random walks mix and how quickly chip-firing and diffusive dynamics
+/// relax. For the reduced Laplacian, with a sink's row and column removed, all
+/// eigenvalues are positive on a connected graph and their product counts spanning
+/// trees, the order of the sandpile group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaplacianSpectrum {
+    /// Eigenvalues in ascending order
+    pub eigenvalues: Vec<f64>,
+
+    /// Eigenvector of the second smallest eigenvalue (None for fewer than two vertices)
+    pub fiedler_vector: Option<Vec<f64>>,
+}
+
+/// Eigenvalues below this count as zero
+const ZERO_TOLERANCE: f64 = 1e-9;
+
+impl LaplacianSpectrum {
+    /// Diagonalizes the symmetric matrix `laplacian`
+    pub fn new(laplacian: DMatrix<f64>) -> Self {
+        let eigen = laplacian.symmetric_eigen();
+        let mut order: Vec<usize> = (0..eigen.eigenvalues.len()).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[a].total_cmp(&eigen.eigenvalues[b]));
+
+        let eigenvalues = order.iter().map(|&k| eigen.eigenvalues[k]).collect();
+        let fiedler_vector = order
+            .get(1)
+            .map(|&k| eigen.eigenvectors.column(k).iter().copied().collect());
+        LaplacianSpectrum { eigenvalues, fiedler_vector }
+    }
+
+    /// Spectrum of the reduced Laplacian, without the row and column of `sink`
+    pub fn reduced(laplacian: DMatrix<f64>, sink: usize) -> Self {
+        Self::new(laplacian.remove_row(sink).remove_column(sink))
+    }
+
+    /// Number of (numerically) zero eigenvalues: the number of connected components
+    /// for a full Laplacian
+    pub fn zero_count(&self) -> usize {
+        self.eigenvalues.iter().filter(|&&l| l.abs() < ZERO_TOLERANCE).count()
+    }
+
+    /// Second smallest eigenvalue λ₂, or 0 for fewer than two vertices
+    pub fn spectral_gap(&self) -> f64 {
+        self.eigenvalues.get(1).copied().unwrap_or(0.0)
+    }
+
+    pub fn smallest(&self) -> f64 {
+        self.eigenvalues.first().copied().unwrap_or(0.0)
+    }
+
+    pub fn largest(&self) -> f64 {
+        self.eigenvalues.last().copied().unwrap_or(0.0)
+    }
+
+    /// log₁₀ of the product of the eigenvalues, i.e. of the determinant; for a reduced
+    /// Laplacian this is log₁₀ of the number of spanning trees
+    pub fn log10_determinant(&self) -> f64 {
+        self.eigenvalues.iter().map(|l| l.log10()).sum()
+    }
+}
+
+/// Predefined graph families selectable in the UI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphType {
+    Grid,
+    Cycle,
+    Complete,
+    Star,
+    Custom,
+}
+
+impl GraphType {
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphType::Grid => "Grid",
+            GraphType::Cycle => "Cycle",
+            GraphType::Complete => "Complete",
+            GraphType::Star => "Star",
+            GraphType::Custom => "Custom",
+        }
+    }
+
+    /// Builds a graph of this type
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Number of vertices of cycle, complete and star graphs
+    /// * `grid_width`, `grid_height` - Dimensions of grid graphs
+    /// * `custom_edges` - Edge list of custom graphs, in the format of [`Graph::parse_edges`]
+    pub fn build(self, size: usize, grid_width: usize, grid_height: usize, custom_edges: &str) -> Result<Graph, GraphError> {
+        let too_small = |name: &str, min: usize| {
+            GraphError::InvalidGraphStructure(format!("{} graph needs at least {} vertices", name, min))
+        };
+        match self {
+            GraphType::Grid => {
+                if grid_width == 0 || grid_height == 0 {
+                    return Err(GraphError::InvalidGraphStructure(
+                        "Grid dimensions must be greater than 0".to_string()
+                    ));
+                }
+                Ok(Graph::grid(grid_width, grid_height))
+            },
+            GraphType::Cycle if size < 3 => Err(too_small("Cycle", 3)),
+            GraphType::Cycle => Ok(Graph::cycle(size)),
+            GraphType::Complete if size < 2 => Err(too_small("Complete", 2)),
+            GraphType::Complete => Ok(Graph::complete(size)),
+            GraphType::Star if size < 3 => Err(too_small("Star", 3)),
+            GraphType::Star => Ok(Graph::star(size)),
+            GraphType::Custom => Graph::parse_edges(custom_edges),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn degrees(graph: &Graph) -> Vec<u32> {
+        graph.neighbor_lists().iter().map(|list| list.iter().map(|&(_, c)| c).sum()).collect()
+    }
+
+    #[test]
+    fn test_builders_have_expected_degrees() {
+        assert_eq!(degrees(&Graph::grid(3, 2)), vec![2, 3, 2, 2, 3, 2]);
+        assert_eq!(degrees(&Graph::cycle(5)), vec![2; 5]);
+        assert_eq!(degrees(&Graph::complete(4)), vec![3; 4]);
+        assert_eq!(degrees(&Graph::star(4)), vec![3, 1, 1, 1]);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(Graph::erdos_renyi(10, 1.0, &mut rng).edges().len(), 45);
+        assert!(Graph::erdos_renyi(10, 0.0, &mut rng).edges().is_empty());
+        assert!(GraphType::Cycle.build(2, 0, 0, "").is_err());
+        assert!(GraphType::Grid.build(0, 0, 3, "").is_err());
+    }
+
+    #[test]
+    fn test_parse_edges() {
+        let graph = Graph::parse_edges("0,1 1,2,3  2,0").unwrap();
+        assert_eq!(graph.num_vertices(), 3);
+        assert_eq!(graph.edges(), &[(0, 1, 1), (1, 2, 3), (2, 0, 1)]);
+        assert_eq!(degrees(&graph), vec![2, 4, 4]);
+        assert!(graph.adjacency()[2][1]);
+
+        assert!(Graph::parse_edges("").is_err());
+        assert!(Graph::parse_edges("0-1").is_err());
+        assert!(Graph::parse_edges("0,1,0").is_err());
+        assert!(Graph::from_edges(2, vec![(0, 2, 1)]).is_err());
+    }
+
+    #[test]
+    fn test_statistics_of_known_graphs() {
+        let stats = Graph::grid(3, 3).statistics();
+        assert_eq!(stats.degree_histogram, BTreeMap::from([(2, 4), (3, 4), (4, 1)]));
+        assert!(stats.is_connected());
+        assert_eq!(stats.diameter, 4);
+        // Mean Manhattan distance between distinct cells of a 3x3 grid
+        assert!((stats.average_path_length - 2.0).abs() < 1e-12);
+
+        let disjoint = Graph::from_edges(5, vec![(0, 1, 1), (1, 2, 1), (3, 4, 2)]).unwrap().statistics();
+        assert_eq!(disjoint.component_sizes, vec![3, 2]);
+        assert_eq!(disjoint.degree_histogram, BTreeMap::from([(1, 2), (2, 3)]));
+        assert!((disjoint.average_path_length - 10.0 / 8.0).abs() < 1e-12);
+
+        // Huge multiplicities neither allocate a bucket per degree nor overflow the sum
+        let heavy = GraphStatistics::new(&[vec![(1, 4_000_000_000), (1, 4_000_000_000)], vec![]]);
+        assert_eq!(heavy.degree_histogram, BTreeMap::from([(0, 1), (8_000_000_000, 1)]));
+        assert!(!disjoint.is_connected());
+        assert_eq!(disjoint.diameter, 2);
+        // Pairs (0,1), (1,2) at 1 and (0,2) at 2 in both directions, (3,4) at 1
+    }
+
+    #[test]
+    fn test_laplacian_spectra_of_known_graphs() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        // Cycle C_n: 2 - 2 cos(2πk/n)
+        let n = 8;
+        let spectrum = Graph::cycle(n).laplacian_spectrum();
+        let mut expected: Vec<f64> = (0..n)
+            .map(|k| 2.0 - 2.0 * (2.0 * std::f64::consts::PI * k as f64 / n as f64).cos())
+            .collect();
+        expected.sort_by(f64::total_cmp);
+        assert!(spectrum.eigenvalues.iter().zip(&expected).all(|(&a, &b)| close(a, b)));
+        assert_eq!(spectrum.zero_count(), 1);
+
+        // Complete K_n: 0 once and n with multiplicity n - 1
+        let spectrum = Graph::complete(5).laplacian_spectrum();
+        assert!(close(spectrum.smallest(), 0.0) && close(spectrum.spectral_gap(), 5.0) && close(spectrum.largest(), 5.0));
+
+        // Two components give two zero eigenvalues
+        let disjoint = Graph::from_edges(4, vec![(0, 1, 1), (2, 3, 1)]).unwrap();
+        assert_eq!(disjoint.laplacian_spectrum().zero_count(), 2);
+
+        // Matrix-tree theorem: K_4 has 4^2 = 16 spanning trees, C_8 has 8
+        let reduced = LaplacianSpectrum::reduced(Graph::complete(4).laplacian(), 0);
+        assert!(close(reduced.log10_determinant(), 16f64.log10()));
+        let reduced = LaplacianSpectrum::reduced(Graph::cycle(8).laplacian(), 3);
+        assert!(close(reduced.log10_determinant(), 8f64.log10()));
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::VecDeque;
+use std::ops::Index;
+
+/// A recorded simulation state together with the iteration it was taken at
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry<T> {
+    /// Simulation step (or sweep) at which the state was recorded
+    pub iteration: usize,
+    pub state: T,
+}
+
+/// Which entries a bounded history drops once it is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Retention {
+    /// Drop the oldest entry after the initial one, like a ring buffer
+    #[default]
+    Recent,
+    /// Keep the newest half of the capacity at full resolution and thin out the older
+    /// entries, so their spacing grows roughly exponentially with age and the whole run
+    /// stays browsable
+    Thinned,
+}
+
+impl Retention {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Retention::Recent => "Recent",
+            Retention::Thinned => "Thinned",
+        }
+    }
+}
+
+/// Recorded states of a simulation run.
+///
+/// The first entry is always the initial state. With a capacity set, entries are
+/// dropped according to the `Retention` policy once the history is full. Each entry
+/// keeps the iteration it was recorded at, so sampled or truncated histories still map
+/// back to simulation time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationHistory<T> {
+    entries: VecDeque<HistoryEntry<T>>,
+
+    /// Maximum number of entries kept (None = unbounded)
+    capacity: Option<usize>,
+
+    /// How entries are dropped to respect the capacity
+    retention: Retention,
+
+    /// Number of entries dropped to respect the capacity
+    evicted: usize,
+}
+
+impl<T> SimulationHistory<T> {
+    /// Creates an unbounded history starting with `initial` at iteration 0
+    pub fn new(initial: T) -> Self {
+        let mut entries = VecDeque::new();
+        entries.push_back(HistoryEntry { iteration: 0, state: initial });
+        SimulationHistory {
+            entries,
+            capacity: None,
+            retention: Retention::Recent,
+            evicted: 0,
+        }
+    }
+
+    /// Creates a history starting with `initial` that keeps at most `capacity` entries
+    pub fn with_capacity(initial: T, capacity: usize) -> Self {
+        let mut history = Self::new(initial);
+        history.set_capacity(Some(capacity));
+        history
+    }
+
+    /// Maximum number of entries kept, if bounded
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Change the capacity, dropping entries right away if the history is now over it.
+    /// Capacities below 2 are raised to 2 so the initial and latest states are always kept.
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity.map(|c| c.max(2));
+        self.enforce_capacity();
+    }
+
+    pub fn retention(&self) -> Retention {
+        self.retention
+    }
+
+    /// Change how entries are dropped; entries already dropped are not restored
+    pub fn set_retention(&mut self, retention: Retention) {
+        self.retention = retention;
+        self.enforce_capacity();
+    }
+
+    /// Number of entries dropped so far because of the capacity
+    pub fn evicted(&self) -> usize {
+        self.evicted
+    }
+
+    fn enforce_capacity(&mut self) {
+        let Some(capacity) = self.capacity else {
+            return;
+        };
+        while self.entries.len() > capacity {
+            let removed = match self.retention {
+                Retention::Recent => 0,
+                Retention::Thinned => self.thin(capacity - capacity / 2),
+            };
+            if removed == 0 {
+                self.entries.remove(1);
+                self.evicted += 1;
+            }
+        }
+    }
+
+    /// Drops every second entry in the older half of the entries between the initial
+    /// state and the newest `recent` ones. Old entries go through more of these passes
+    /// than new ones, which thins them geometrically.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries dropped
+    fn thin(&mut self, recent: usize) -> usize {
+        let middle_end = self.entries.len().saturating_sub(recent).max(1);
+        let older_end = 1 + (middle_end - 1) / 2;
+        let before = self.entries.len();
+        let mut index = 0;
+        self.entries.retain(|_| {
+            let keep = index == 0 || index >= older_end || (index - 1).is_multiple_of(2);
+            index += 1;
+            keep
+        });
+        let removed = before - self.entries.len();
+        self.evicted += removed;
+        removed
+    }
+
+    /// Record `state` at `iteration`
+    pub fn push(&mut self, iteration: usize, state: T) {
+        self.entries.push_back(HistoryEntry { iteration, state });
+        self.enforce_capacity();
+    }
+
+    /// Number of recorded entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if nothing is recorded (only possible after `truncate(0)` or `split_off(0)`)
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// State of the entry at `index`
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.entries.get(index).map(|entry| &entry.state)
+    }
+
+    /// Entry (state and iteration) at `index`
+    pub fn entry(&self, index: usize) -> Option<&HistoryEntry<T>> {
+        self.entries.get(index)
+    }
+
+    /// The initial state
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// The most recent state
+    pub fn last(&self) -> Option<&T> {
+        self.entries.back().map(|entry| &entry.state)
+    }
+
+    /// Iteration of the most recent entry (0 if empty)
+    pub fn last_iteration(&self) -> usize {
+        self.entries.back().map_or(0, |entry| entry.iteration)
+    }
+
+    /// Iterates over all entries, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &HistoryEntry<T>> + ExactSizeIterator + '_ {
+        self.entries.iter()
+    }
+
+    /// Iterates over all states, oldest first
+    pub fn states(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + '_ {
+        self.entries.iter().map(|entry| &entry.state)
+    }
+
+    /// Consumes the history, returning the states in order
+    pub fn into_states(self) -> Vec<T> {
+        self.entries.into_iter().map(|entry| entry.state).collect()
+    }
+
+    /// Keep only the first `len` entries
+    pub fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+
+    /// Remove and return all entries from `at` onwards
+    pub fn split_off(&mut self, at: usize) -> Vec<HistoryEntry<T>> {
+        self.entries.split_off(at.min(self.entries.len())).into()
+    }
+
+    /// Append previously removed entries
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = HistoryEntry<T>>) {
+        self.entries.extend(entries);
+        self.enforce_capacity();
+    }
+
+    /// Drop all entries and start again from `initial` at iteration 0, keeping the capacity
+    pub fn reset(&mut self, initial: T) {
+        self.entries.clear();
+        self.entries.push_back(HistoryEntry { iteration: 0, state: initial });
+        self.evicted = 0;
+    }
+
+    /// Indices of at most `max_points` entries spread evenly over the history.
+    /// The first and last entries are always included.
+    pub fn downsample_indices(&self, max_points: usize) -> Vec<usize> {
+        spread_indices(self.entries.len(), max_points)
+    }
+
+    /// At most `max_points` entries spread evenly over the history, e.g. for plotting
+    pub fn downsample(&self, max_points: usize) -> Vec<&HistoryEntry<T>> {
+        self.downsample_indices(max_points)
+            .into_iter()
+            .map(|i| &self.entries[i])
+            .collect()
+    }
+
+    /// A history with `f` applied to every state, keeping iterations and capacity
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> SimulationHistory<U> {
+        SimulationHistory {
+            entries: self
+                .entries
+                .iter()
+                .map(|entry| HistoryEntry { iteration: entry.iteration, state: f(&entry.state) })
+                .collect(),
+            capacity: self.capacity,
+            retention: self.retention,
+            evicted: self.evicted,
+        }
+    }
+
+    /// Like `map`, but stops at the first error
+    pub fn try_map<U, E>(&self, mut f: impl FnMut(&T) -> Result<U, E>) -> Result<SimulationHistory<U>, E> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| Ok(HistoryEntry { iteration: entry.iteration, state: f(&entry.state)? }))
+            .collect::<Result<VecDeque<_>, E>>()?;
+        Ok(SimulationHistory {
+            entries,
+            capacity: self.capacity,
+            retention: self.retention,
+            evicted: self.evicted,
+        })
+    }
+}
+
+/// Indices of at most `max_points` of `len` entries spread evenly, always including the
+/// first and last
+pub(crate) fn spread_indices(len: usize, max_points: usize) -> Vec<usize> {
+    if len <= max_points.max(2) {
+        return (0..len).collect();
+    }
+
+    let max_points = max_points.max(2);
+    let mut indices: Vec<usize> = (0..max_points)
+        .map(|k| k * (len - 1) / (max_points - 1))
+        .collect();
+    indices.dedup();
+    indices
+}
+
+impl<T> Index<usize> for SimulationHistory<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.entries[index].state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_keeps_initial_state() {
+        let mut history = SimulationHistory::with_capacity(0, 4);
+        for i in 1..=10 {
+            history.push(i, i * 10);
+        }
+
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.evicted(), 7);
+        assert_eq!(history.first(), Some(&0));
+        assert_eq!(history.iter().map(|e| e.iteration).collect::<Vec<_>>(), vec![0, 8, 9, 10]);
+        assert_eq!(history[3], 100);
+    }
+
+    #[test]
+    fn test_thinned_history_spaces_old_entries_out() {
+        let mut history = SimulationHistory::with_capacity(0, 20);
+        history.set_retention(Retention::Thinned);
+        for i in 1..=1000 {
+            history.push(i, i);
+        }
+
+        assert!(history.len() <= 20);
+        assert_eq!(history.len() + history.evicted(), 1001);
+        let iterations: Vec<usize> = history.iter().map(|e| e.iteration).collect();
+        assert_eq!(iterations[0], 0);
+        // The newest entries are kept at full resolution
+        assert!(iterations.ends_with(&[996, 997, 998, 999, 1000]));
+        // and the gaps between older entries are larger than between newer ones
+        let gaps: Vec<usize> = iterations.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(gaps[1] > 4 * gaps[gaps.len() - 1]);
+    }
+
+    #[test]
+    fn test_downsample_includes_endpoints() {
+        let mut history = SimulationHistory::new(0);
+        for i in 1..100 {
+            history.push(i, i);
+        }
+
+        let sampled = history.downsample(10);
+        assert_eq!(sampled.len(), 10);
+        assert_eq!(sampled.first().unwrap().iteration, 0);
+        assert_eq!(sampled.last().unwrap().iteration, 99);
+        assert_eq!(history.downsample(500).len(), 100);
+    }
+
+    #[test]
+    fn test_split_off_and_extend_round_trip() {
+        let mut history = SimulationHistory::new('a');
+        history.push(1, 'b');
+        history.push(2, 'c');
+
+        let tail = history.split_off(1);
+        assert_eq!(history.len(), 1);
+        history.extend(tail);
+        assert_eq!(history.states().copied().collect::<String>(), "abc");
+        assert_eq!(history.last_iteration(), 2);
+    }
+}
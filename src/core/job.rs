@@ -0,0 +1,194 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+/// Messages sent from a worker thread to its `BackgroundJob`
+enum JobMessage<T> {
+    Progress(usize),
+    Finished(T),
+}
+
+/// State of a background job after polling
+#[derive(Debug)]
+pub enum JobStatus<T> {
+    /// Still running
+    Running,
+    /// Finished (possibly early, after a cancel) with the worker's result
+    Finished(T),
+    /// The worker thread stopped without producing a result (it panicked)
+    Failed,
+}
+
+/// Handle passed to the work closure for reporting progress and checking for cancellation
+pub struct JobContext<T> {
+    sender: Sender<JobMessage<T>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T> JobContext<T> {
+    /// Report that `done` units of work out of the job's total are complete
+    pub fn report(&self, done: usize) {
+        // The receiver is gone if the job was dropped; the work will see the cancel flag
+        let _ = self.sender.send(JobMessage::Progress(done));
+    }
+
+    /// True once the owner asked the job to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// A simulation running on a worker thread.
+///
+/// The work closure owns its data (usually a clone of the model) and returns it when
+/// done; the UI polls the job once per frame, so long runs never block rendering.
+/// Dropping the job cancels it.
+pub struct BackgroundJob<T> {
+    /// Name of the running action, shown next to the progress bar
+    label: String,
+
+    /// Units of work the job expects to perform
+    total: usize,
+
+    /// Units of work reported as done so far
+    done: usize,
+
+    receiver: Receiver<JobMessage<T>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T: Send + 'static> BackgroundJob<T> {
+    /// Start `work` on a new thread
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - Name of the action, for display
+    /// * `total` - Units of work expected, used to compute the progress fraction
+    /// * `work` - The computation; it should call `report` as it goes and return early
+    ///   once `is_cancelled` is true
+    pub fn spawn(
+        label: impl Into<String>,
+        total: usize,
+        work: impl FnOnce(&JobContext<T>) -> T + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let context = JobContext {
+            sender,
+            cancelled: Arc::clone(&cancelled),
+        };
+
+        thread::spawn(move || {
+            let result = work(&context);
+            let _ = context.sender.send(JobMessage::Finished(result));
+        });
+
+        BackgroundJob {
+            label: label.into(),
+            total,
+            done: 0,
+            receiver,
+            cancelled,
+        }
+    }
+}
+
+impl<T> BackgroundJob<T> {
+    /// Process all messages from the worker without blocking
+    pub fn poll(&mut self) -> JobStatus<T> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(JobMessage::Progress(done)) => self.done = done,
+                Ok(JobMessage::Finished(result)) => return JobStatus::Finished(result),
+                Err(TryRecvError::Empty) => return JobStatus::Running,
+                Err(TryRecvError::Disconnected) => return JobStatus::Failed,
+            }
+        }
+    }
+
+    /// Ask the worker to stop; its (partial) result still arrives through `poll`
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// True once `cancel` was called
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Units of work reported as done so far
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Fraction of the work done, from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.done as f32 / self.total as f32).min(1.0)
+    }
+}
+
+impl<T> Drop for BackgroundJob<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn wait<T>(job: &mut BackgroundJob<T>) -> JobStatus<T> {
+        for _ in 0..1000 {
+            match job.poll() {
+                JobStatus::Running => thread::sleep(Duration::from_millis(5)),
+                status => return status,
+            }
+        }
+        panic!("job did not finish");
+    }
+
+    #[test]
+    fn test_job_reports_progress_and_result() {
+        let mut job = BackgroundJob::spawn("Sum", 10, |ctx| {
+            let mut sum = 0;
+            for i in 1..=10 {
+                sum += i;
+                ctx.report(i);
+            }
+            sum
+        });
+
+        assert!(matches!(wait(&mut job), JobStatus::Finished(55)));
+        assert_eq!(job.done(), 10);
+        assert_eq!(job.fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_cancel_stops_work() {
+        let mut job = BackgroundJob::spawn("Spin", usize::MAX, |ctx| {
+            let mut iterations = 0usize;
+            while !ctx.is_cancelled() {
+                iterations += 1;
+                thread::sleep(Duration::from_millis(1));
+            }
+            iterations
+        });
+
+        job.cancel();
+        assert!(job.is_cancelled());
+        assert!(matches!(wait(&mut job), JobStatus::Finished(_)));
+    }
+}
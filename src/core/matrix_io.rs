@@ -0,0 +1,373 @@
+use std::path::Path;
+
+use thiserror::Error;
+
+/// Errors of reading and writing matrix files
+#[derive(Debug, Error)]
+pub enum MatrixFileError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A valid file using a feature this reader does not implement
+    #[error("Unsupported file: {0}")]
+    Unsupported(String),
+
+    #[error("Malformed file: {0}")]
+    Malformed(String),
+}
+
+/// A matrix as a list of equally long rows
+pub type Matrix = Vec<Vec<f64>>;
+
+/// Matrix file formats, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixFormat {
+    /// NumPy `.npy`: a single array per file
+    Npy,
+    /// MATLAB `.mat` (Level 5, uncompressed): named variables
+    Mat,
+}
+
+impl MatrixFormat {
+    pub fn from_path(path: &Path) -> Result<Self, MatrixFileError> {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("npy") => Ok(MatrixFormat::Npy),
+            Some("mat") => Ok(MatrixFormat::Mat),
+            _ => Err(MatrixFileError::Unsupported(format!("{} is neither a .npy nor a .mat file", path.display()))),
+        }
+    }
+}
+
+/// Writes `variables` to `path`. A `.mat` file holds all of them under their names; a
+/// `.npy` file holds a single array, so exactly one variable must be given.
+pub fn write_matrices(path: &Path, variables: &[(&str, &[Vec<f64>])]) -> Result<(), MatrixFileError> {
+    let bytes = match MatrixFormat::from_path(path)? {
+        MatrixFormat::Npy => match variables {
+            [(_, matrix)] => encode_npy(matrix)?,
+            _ => return Err(MatrixFileError::Unsupported("a .npy file holds exactly one array".to_string())),
+        },
+        MatrixFormat::Mat => encode_mat(variables)?,
+    };
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Reads the matrices of a `.npy` or `.mat` file with their names. The array of a
+/// `.npy` file is named after the file.
+pub fn read_matrices(path: &Path) -> Result<Vec<(String, Matrix)>, MatrixFileError> {
+    let bytes = std::fs::read(path)?;
+    match MatrixFormat::from_path(path)? {
+        MatrixFormat::Npy => {
+            let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+            Ok(vec![(name, decode_npy(&bytes)?)])
+        },
+        MatrixFormat::Mat => decode_mat(&bytes),
+    }
+}
+
+/// Number of columns of `matrix`, checking that all rows have it
+fn columns_of(matrix: &[Vec<f64>]) -> Result<usize, MatrixFileError> {
+    let columns = matrix.first().map_or(0, Vec::len);
+    if matrix.iter().any(|row| row.len() != columns) {
+        return Err(MatrixFileError::Malformed("rows of the matrix differ in length".to_string()));
+    }
+    Ok(columns)
+}
+
+/// NumPy format version 1.0 of a 2D float64 array in C order
+pub fn encode_npy(matrix: &[Vec<f64>]) -> Result<Vec<u8>, MatrixFileError> {
+    let columns = columns_of(matrix)?;
+    let mut header = format!("{{'descr': '<f8', 'fortran_order': False, 'shape': ({}, {}), }}", matrix.len(), columns);
+    // Magic, version and length take 10 bytes; the header ends in a newline and pads
+    // the data start to a multiple of 64
+    while (10 + header.len() + 1) % 64 != 0 {
+        header.push(' ');
+    }
+    header.push('\n');
+
+    let mut bytes = Vec::with_capacity(10 + header.len() + 8 * matrix.len() * columns);
+    bytes.extend_from_slice(b"\x93NUMPY\x01\x00");
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for value in matrix.iter().flatten() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    Ok(bytes)
+}
+
+/// Value of `key` in the header dictionary of a `.npy` file, up to the next comma at
+/// nesting level 0
+fn npy_header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, MatrixFileError> {
+    let missing = || MatrixFileError::Malformed(format!("the .npy header has no '{}'", key));
+    let start = header.find(&format!("'{}'", key)).ok_or_else(missing)? + key.len() + 2;
+    let rest = header[start..].trim_start().strip_prefix(':').ok_or_else(missing)?.trim_start();
+    let mut depth = 0;
+    let end = rest
+        .char_indices()
+        .find(|&(_, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {},
+            }
+            (c == ',' && depth == 0) || c == '}'
+        })
+        .map_or(rest.len(), |(i, _)| i);
+    Ok(rest[..end].trim())
+}
+
+/// Reads a numeric `.npy` array of up to two dimensions; a vector becomes a single row
+pub fn decode_npy(bytes: &[u8]) -> Result<Matrix, MatrixFileError> {
+    let malformed = |msg: &str| MatrixFileError::Malformed(msg.to_string());
+    if bytes.len() < 10 || &bytes[..6] != b"\x93NUMPY" {
+        return Err(malformed("missing the .npy magic string"));
+    }
+    let (header_len, header_start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, 12),
+        version => return Err(MatrixFileError::Unsupported(format!(".npy format version {}", version))),
+    };
+    let data = bytes.get(header_start + header_len..).ok_or_else(|| malformed("truncated header"))?;
+    let header = std::str::from_utf8(&bytes[header_start..header_start + header_len]).map_err(|_| malformed("header is not text"))?;
+
+    let descr = npy_header_value(header, "descr")?.trim_matches(|c| c == '\'' || c == '"');
+    let fortran_order = npy_header_value(header, "fortran_order")? == "True";
+    let shape: Vec<usize> = npy_header_value(header, "shape")?
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>().map_err(|_| malformed("invalid shape")))
+        .collect::<Result<_, _>>()?;
+    let (rows, columns) = match shape[..] {
+        [] => (1, 1),
+        [n] => (1, n),
+        [rows, columns] => (rows, columns),
+        _ => return Err(MatrixFileError::Unsupported(format!("arrays of {} dimensions", shape.len()))),
+    };
+    let size = rows.checked_mul(columns).ok_or_else(|| malformed("shape out of range"))?;
+
+    let (big_endian, kind) = match descr.split_at(1.min(descr.len())) {
+        ("<" | "|" | "=", kind) => (false, kind),
+        (">", kind) => (true, kind),
+        _ => return Err(malformed("invalid dtype")),
+    };
+    let width = match kind {
+        "f8" | "i8" | "u8" => 8,
+        "f4" | "i4" | "u4" => 4,
+        "i2" | "u2" => 2,
+        "i1" | "u1" | "b1" => 1,
+        _ => return Err(MatrixFileError::Unsupported(format!("dtype '{}'", descr))),
+    };
+    let values: Vec<f64> = data
+        .chunks_exact(width)
+        .take(size)
+        .map(|chunk| {
+            let mut raw = [0u8; 8];
+            raw[..width].copy_from_slice(chunk);
+            if big_endian {
+                raw[..width].reverse();
+            }
+            match kind {
+                "f8" => f64::from_le_bytes(raw),
+                "f4" => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+                "i8" => i64::from_le_bytes(raw) as f64,
+                "i4" => i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+                "i2" => i16::from_le_bytes([raw[0], raw[1]]) as f64,
+                "i1" => raw[0] as i8 as f64,
+                _ => u64::from_le_bytes(raw) as f64,
+            }
+        })
+        .collect();
+    if values.len() < size {
+        return Err(malformed("the data is shorter than the shape says"));
+    }
+
+    let at = |r: usize, c: usize| if fortran_order { values[c * rows + r] } else { values[r * columns + c] };
+    Ok((0..rows).map(|r| (0..columns).map(|c| at(r, c)).collect()).collect())
+}
+
+/// MAT-file data types used by `encode_mat` and `decode_mat`
+const MI_INT8: u32 = 1;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_DOUBLE: u32 = 9;
+const MI_MATRIX: u32 = 14;
+const MI_COMPRESSED: u32 = 15;
+
+const MX_DOUBLE_CLASS: u32 = 6;
+
+/// Appends a data element: tag, data and padding to a multiple of 8 bytes
+fn push_mat_element(bytes: &mut Vec<u8>, data_type: u32, data: &[u8]) {
+    bytes.extend_from_slice(&data_type.to_le_bytes());
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.resize(bytes.len().next_multiple_of(8), 0);
+}
+
+/// MAT-file Level 5 with every variable as an uncompressed double matrix, readable by
+/// MATLAB, Octave and `scipy.io.loadmat`
+pub fn encode_mat(variables: &[(&str, &[Vec<f64>])]) -> Result<Vec<u8>, MatrixFileError> {
+    let mut bytes = format!("{:<116}", "MATLAB 5.0 MAT-file, written by RAUM").into_bytes();
+    bytes.extend_from_slice(&[0; 8]);
+    bytes.extend_from_slice(&0x0100u16.to_le_bytes());
+    bytes.extend_from_slice(b"IM");
+
+    for (name, matrix) in variables {
+        let valid_name = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(MatrixFileError::Malformed(format!("'{}' is not a valid MATLAB variable name", name)));
+        }
+        let columns = columns_of(matrix)?;
+        let mut element = Vec::new();
+        push_mat_element(&mut element, MI_UINT32, &[MX_DOUBLE_CLASS.to_le_bytes(), [0; 4]].concat());
+        push_mat_element(&mut element, MI_INT32, &[(matrix.len() as i32).to_le_bytes(), (columns as i32).to_le_bytes()].concat());
+        push_mat_element(&mut element, MI_INT8, name.as_bytes());
+        // MATLAB stores matrices column by column
+        let data: Vec<u8> = (0..columns).flat_map(|c| matrix.iter().flat_map(move |row| row[c].to_le_bytes())).collect();
+        push_mat_element(&mut element, MI_DOUBLE, &data);
+        push_mat_element(&mut bytes, MI_MATRIX, &element);
+    }
+    Ok(bytes)
+}
+
+/// Reads the tag of the data element at `offset`, in the normal or the small (packed)
+/// format
+///
+/// # Returns
+///
+/// The data type, the data and the offset of the next element
+fn read_mat_element(bytes: &[u8], offset: usize) -> Result<(u32, &[u8], usize), MatrixFileError> {
+    let word = |at: usize| {
+        bytes
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| MatrixFileError::Malformed("truncated data element".to_string()))
+    };
+    let first = word(offset)?;
+    if first >> 16 != 0 {
+        let size = (first >> 16) as usize;
+        let data = bytes.get(offset + 4..offset + 4 + size.min(4)).ok_or_else(|| MatrixFileError::Malformed("truncated data element".to_string()))?;
+        return Ok((first & 0xffff, data, offset + 8));
+    }
+    let size = word(offset + 4)? as usize;
+    let data = bytes.get(offset + 8..offset + 8 + size).ok_or_else(|| MatrixFileError::Malformed("truncated data element".to_string()))?;
+    Ok((first, data, offset + 8 + size.next_multiple_of(8)))
+}
+
+/// Numeric values of a MAT-file data element of type `data_type`
+fn mat_values(data_type: u32, data: &[u8]) -> Result<Vec<f64>, MatrixFileError> {
+    let values = match data_type {
+        1 => data.iter().map(|&b| b as i8 as f64).collect(),
+        2 => data.iter().map(|&b| b as f64).collect(),
+        3 => data.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]) as f64).collect(),
+        4 => data.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]]) as f64).collect(),
+        5 => data.chunks_exact(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64).collect(),
+        6 => data.chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64).collect(),
+        7 => data.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64).collect(),
+        9 => data.chunks_exact(8).map(|b| f64::from_le_bytes(b.try_into().unwrap_or([0; 8]))).collect(),
+        12 => data.chunks_exact(8).map(|b| i64::from_le_bytes(b.try_into().unwrap_or([0; 8])) as f64).collect(),
+        13 => data.chunks_exact(8).map(|b| u64::from_le_bytes(b.try_into().unwrap_or([0; 8])) as f64).collect(),
+        other => return Err(MatrixFileError::Unsupported(format!("MAT data type {}", other))),
+    };
+    Ok(values)
+}
+
+/// Reads the real numeric 2D matrices of an uncompressed little-endian MAT-file Level 5
+/// (`save -v6` in MATLAB); variables of other classes are skipped
+pub fn decode_mat(bytes: &[u8]) -> Result<Vec<(String, Matrix)>, MatrixFileError> {
+    if bytes.len() < 128 || !bytes[..116].starts_with(b"MATLAB 5.0 MAT-file") {
+        return Err(MatrixFileError::Unsupported("not a MAT-file Level 5 (MATLAB 7.3 files are HDF5)".to_string()));
+    }
+    if &bytes[126..128] != b"IM" {
+        return Err(MatrixFileError::Unsupported("big-endian MAT-files".to_string()));
+    }
+
+    let mut variables = Vec::new();
+    let mut offset = 128;
+    while offset < bytes.len() {
+        let (data_type, element, next) = read_mat_element(bytes, offset)?;
+        offset = next;
+        match data_type {
+            MI_MATRIX => {},
+            MI_COMPRESSED => {
+                return Err(MatrixFileError::Unsupported("compressed MAT-files; save with -v6".to_string()));
+            },
+            _ => continue,
+        }
+
+        let (_, flags, at) = read_mat_element(element, 0)?;
+        let class = flags.first().copied().unwrap_or(0) as u32;
+        let complex = flags.get(1).is_some_and(|&f| f & 0x08 != 0);
+        // Classes 6 to 15 are the numeric ones: double, single and the integer types
+        if !(6..=15).contains(&class) || complex {
+            continue;
+        }
+        let (_, dims, at) = read_mat_element(element, at)?;
+        let dims = mat_values(MI_INT32, dims)?;
+        let (_, name, at) = read_mat_element(element, at)?;
+        let (real_type, real, _) = read_mat_element(element, at)?;
+        let [rows, columns] = dims[..] else {
+            continue;
+        };
+        let (rows, columns) = (rows as usize, columns as usize);
+        let values = mat_values(real_type, real)?;
+        if rows.checked_mul(columns) != Some(values.len()) {
+            return Err(MatrixFileError::Malformed("matrix data does not match its dimensions".to_string()));
+        }
+        let matrix = (0..rows).map(|r| (0..columns).map(|c| values[c * rows + r]).collect()).collect();
+        variables.push((String::from_utf8_lossy(name).into_owned(), matrix));
+    }
+    Ok(variables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npy_round_trip_and_fortran_order() {
+        let matrix = vec![vec![1.0, -2.5, 3.0], vec![0.0, 4.0, -1.0]];
+        let bytes = encode_npy(&matrix).unwrap();
+        assert_eq!(bytes.iter().position(|&b| b == b'\n').map(|i| (i + 1) % 64), Some(0));
+        assert_eq!(decode_npy(&bytes).unwrap(), matrix);
+
+        // A column-major int32 array as written by numpy.asfortranarray
+        let header = "{'descr': '<i4', 'fortran_order': True, 'shape': (2, 2), }\n";
+        let mut fortran = b"\x93NUMPY\x01\x00".to_vec();
+        fortran.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        fortran.extend_from_slice(header.as_bytes());
+        for value in [1i32, 3, 2, 4] {
+            fortran.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(decode_npy(&fortran).unwrap(), vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+
+        // A shape whose size overflows is an error, not a panic
+        let header = "{'descr': '<f8', 'fortran_order': False, 'shape': (4294967296, 4294967296), }\n";
+        let mut huge = b"\x93NUMPY\x01\x00".to_vec();
+        huge.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        huge.extend_from_slice(header.as_bytes());
+        assert!(matches!(decode_npy(&huge), Err(MatrixFileError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_mat_round_trip_with_small_elements() {
+        let weights = vec![vec![0.0, 0.5], vec![0.5, 0.0]];
+        let patterns = vec![vec![1.0, -1.0]];
+        let bytes = encode_mat(&[("W", &weights), ("patterns", &patterns)]).unwrap();
+        let variables = decode_mat(&bytes).unwrap();
+        assert_eq!(variables, vec![("W".to_string(), weights), ("patterns".to_string(), patterns)]);
+        assert!(encode_mat(&[("2x", &[vec![1.0]])]).is_err());
+
+        // MATLAB packs short names and small integer data into small elements
+        let mut element = Vec::new();
+        push_mat_element(&mut element, MI_UINT32, &[MX_DOUBLE_CLASS.to_le_bytes(), [0; 4]].concat());
+        push_mat_element(&mut element, MI_INT32, &[1i32.to_le_bytes(), 2i32.to_le_bytes()].concat());
+        element.extend_from_slice(&[1, 0, 1, 0, b'x', 0, 0, 0]);
+        element.extend_from_slice(&[2, 0, 2, 0, 7, 9, 0, 0]);
+        let mut file = bytes[..128].to_vec();
+        push_mat_element(&mut file, MI_MATRIX, &element);
+        assert_eq!(decode_mat(&file).unwrap(), vec![("x".to_string(), vec![vec![7.0, 9.0]])]);
+    }
+}
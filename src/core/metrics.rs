@@ -0,0 +1,234 @@
+/// Number of positions at which `a` and `b` differ
+pub fn hamming_distance(a: &[f64], b: &[f64]) -> usize {
+    a.iter().zip(b).filter(|(x, y)| x != y).count()
+}
+
+/// Overlap m = (1/N) Σ_i a_i b_i between two bipolar states (1.0 = identical, -1.0 = inverted)
+pub fn overlap(a: &[f64], b: &[f64]) -> f64 {
+    if a.is_empty() {
+        return 0.0;
+    }
+    a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>() / a.len() as f64
+}
+
+/// Overlap of `a` and `b` over the cells where `mask` is set, e.g. the region of a
+/// partial cue the network had to fill in
+pub fn masked_overlap(a: &[f64], b: &[f64], mask: &[bool]) -> f64 {
+    let (sum, count) = a
+        .iter()
+        .zip(b)
+        .zip(mask)
+        .filter(|&(_, &masked)| masked)
+        .fold((0.0, 0usize), |(sum, count), ((x, y), _)| (sum + x * y, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+/// Shannon entropy (in nats) of the symbol distribution given by `counts` out of `total`
+fn entropy(counts: impl Iterator<Item = usize>, total: f64) -> f64 {
+    counts
+        .filter(|&c| c > 0)
+        .map(|c| {
+            let p = c as f64 / total;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+/// Normalized mutual information I(A;B) / sqrt(H(A) H(B)) between the symbols of `a` and `b`.
+///
+/// Unlike the overlap, it is 1.0 for an inverted state as well as for an identical one,
+/// and close to 0.0 for unrelated states. Two constant states give 1.0, a constant and a
+/// varying state give 0.0.
+pub fn normalized_mutual_information(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return 0.0;
+    }
+
+    // Joint counts over the distinct symbols of each state
+    let mut symbols_a: Vec<f64> = Vec::new();
+    let mut symbols_b: Vec<f64> = Vec::new();
+    let mut joint: Vec<(usize, usize, usize)> = Vec::new();
+    for (&x, &y) in a.iter().zip(b) {
+        let i = symbol_index(&mut symbols_a, x);
+        let j = symbol_index(&mut symbols_b, y);
+        match joint.iter_mut().find(|(ji, jj, _)| *ji == i && *jj == j) {
+            Some((_, _, count)) => *count += 1,
+            None => joint.push((i, j, 1)),
+        }
+    }
+
+    let total = n as f64;
+    let marginal = |pick: fn(&(usize, usize, usize)) -> usize, num_symbols: usize| -> Vec<usize> {
+        let mut counts = vec![0; num_symbols];
+        for entry in &joint {
+            counts[pick(entry)] += entry.2;
+        }
+        counts
+    };
+    let counts_a = marginal(|e| e.0, symbols_a.len());
+    let counts_b = marginal(|e| e.1, symbols_b.len());
+
+    let h_a = entropy(counts_a.iter().copied(), total);
+    let h_b = entropy(counts_b.iter().copied(), total);
+    if h_a == 0.0 || h_b == 0.0 {
+        return if h_a == h_b { 1.0 } else { 0.0 };
+    }
+
+    let h_joint = entropy(joint.iter().map(|e| e.2), total);
+    let mutual_information = h_a + h_b - h_joint;
+    (mutual_information / (h_a * h_b).sqrt()).clamp(0.0, 1.0)
+}
+
+fn symbol_index(symbols: &mut Vec<f64>, value: f64) -> usize {
+    match symbols.iter().position(|&s| s == value) {
+        Some(i) => i,
+        None => {
+            symbols.push(value);
+            symbols.len() - 1
+        },
+    }
+}
+
+/// The stored pattern a state is closest to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Retrieval {
+    /// Index of the closest pattern
+    pub pattern_index: usize,
+    /// Number of positions differing from that pattern (or from its inverse)
+    pub hamming_distance: usize,
+    /// Fraction of matching positions, from 0.0 to 1.0
+    pub match_fraction: f64,
+    /// The state is closest to the inverted pattern (a spurious mirror state)
+    pub inverted: bool,
+}
+
+/// Classifies `state` by the pattern with the smallest Hamming distance.
+///
+/// Hopfield networks also store the inverse of every pattern, so if `allow_inverted`
+/// is set the inverse of each pattern is considered as well.
+///
+/// # Returns
+///
+/// The closest pattern, or None if `patterns` is empty
+pub fn classify(state: &[f64], patterns: &[Vec<f64>], allow_inverted: bool) -> Option<Retrieval> {
+    let n = state.len();
+    patterns
+        .iter()
+        .enumerate()
+        .flat_map(|(pattern_index, pattern)| {
+            let distance = hamming_distance(state, pattern);
+            let direct = Retrieval {
+                pattern_index,
+                hamming_distance: distance,
+                match_fraction: 0.0,
+                inverted: false,
+            };
+            // Every differing position of a bipolar state matches the inverse
+            let mirrored = Retrieval {
+                hamming_distance: n - distance,
+                inverted: true,
+                ..direct
+            };
+            std::iter::once(direct).chain(allow_inverted.then_some(mirrored))
+        })
+        .min_by_key(|r| r.hamming_distance)
+        .map(|r| Retrieval {
+            match_fraction: if n == 0 { 0.0 } else { 1.0 - r.hamming_distance as f64 / n as f64 },
+            ..r
+        })
+}
+
+/// Mean and (population) standard deviation of `values`; (0, 0) for an empty slice
+pub fn mean_std(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+/// Pointwise mean and standard deviation of curves of different lengths. Shorter
+/// curves are extended with their last value, so runs that stopped early (e.g. after
+/// converging) keep contributing their final value.
+pub fn mean_std_curves(curves: &[Vec<f64>]) -> Vec<(f64, f64)> {
+    let length = curves.iter().map(Vec::len).max().unwrap_or(0);
+    (0..length)
+        .map(|t| {
+            let values: Vec<f64> = curves
+                .iter()
+                .filter_map(|c| c.get(t).or(c.last()).copied())
+                .collect();
+            mean_std(&values)
+        })
+        .collect()
+}
+
+/// Counts of `values` in `bins` equal bins over [min, max]; values outside the range
+/// are counted in the first or last bin
+pub fn histogram(values: &[f64], bins: usize, min: f64, max: f64) -> Vec<usize> {
+    let mut counts = vec![0; bins.max(1)];
+    let width = (max - min) / counts.len() as f64;
+    for &v in values {
+        let bin = if width > 0.0 { ((v - min) / width).floor().max(0.0) as usize } else { 0 };
+        counts[bin.min(bins.max(1) - 1)] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distances_and_overlap() {
+        let a = [1.0, 1.0, -1.0, -1.0];
+        let b = [1.0, -1.0, -1.0, 1.0];
+        assert_eq!(hamming_distance(&a, &b), 2);
+        assert_eq!(overlap(&a, &a), 1.0);
+        assert_eq!(overlap(&a, &b), 0.0);
+        assert_eq!(masked_overlap(&a, &b, &[true, false, true, false]), 1.0);
+        assert_eq!(masked_overlap(&a, &b, &[false; 4]), 0.0);
+
+        let inverted: Vec<f64> = a.iter().map(|x| -x).collect();
+        assert_eq!(overlap(&a, &inverted), -1.0);
+        assert!((normalized_mutual_information(&a, &inverted) - 1.0).abs() < 1e-12);
+        assert!(normalized_mutual_information(&a, &b).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_classify() {
+        let patterns = vec![vec![1.0, 1.0, 1.0, 1.0], vec![1.0, -1.0, 1.0, -1.0]];
+
+        let noisy = [-1.0, -1.0, 1.0, -1.0];
+        let retrieval = classify(&noisy, &patterns, false).unwrap();
+        assert_eq!(retrieval.pattern_index, 1);
+        assert_eq!(retrieval.hamming_distance, 1);
+        assert_eq!(retrieval.match_fraction, 0.75);
+
+        let mirror = [-1.0, -1.0, -1.0, -1.0];
+        let retrieval = classify(&mirror, &patterns, true).unwrap();
+        assert_eq!(retrieval.pattern_index, 0);
+        assert!(retrieval.inverted);
+        assert_eq!(retrieval.match_fraction, 1.0);
+
+        assert!(classify(&noisy, &[], true).is_none());
+    }
+
+    #[test]
+    fn test_summary_statistics() {
+        assert_eq!(mean_std(&[1.0, 3.0]), (2.0, 1.0));
+        assert_eq!(mean_std(&[]), (0.0, 0.0));
+
+        let curves = vec![vec![4.0, 2.0, 0.0], vec![2.0]];
+        assert_eq!(mean_std_curves(&curves), vec![(3.0, 1.0), (2.0, 0.0), (1.0, 1.0)]);
+
+        assert_eq!(histogram(&[-1.0, -0.2, 0.0, 0.9, 1.0, 5.0], 4, -1.0, 1.0), vec![1, 1, 1, 3]);
+    }
+}
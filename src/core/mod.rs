@@ -0,0 +1,18 @@
+pub mod assets;
+pub mod augment;
+pub mod bridge;
+pub mod delta;
+pub mod graph;
+pub mod history;
+pub mod job;
+pub mod matrix_io;
+pub mod metrics;
+pub mod noise;
+pub mod projection;
+pub mod safetensors;
+pub mod sweep;
+pub mod recorder;
+
+pub use delta::{DeltaCodec, DeltaHistory};
+pub use history::{HistoryEntry, Retention, SimulationHistory};
+pub use job::{BackgroundJob, JobContext, JobStatus};
@@ -0,0 +1,297 @@
+use rand::Rng;
+
+/// Ways of corrupting a bipolar grid state, e.g. to make cues for pattern completion.
+///
+/// Regions are given as fractions of the grid size, so a model keeps its meaning when
+/// the grid is resized. Cells that are hidden rather than flipped are set to -1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseModel {
+    /// Flip every cell independently with `probability`
+    BitFlip { probability: f64 },
+    /// Hide the rectangle with top-left corner (x, y)
+    Mask { x: f64, y: f64, width: f64, height: f64 },
+    /// Hide the bottom `fraction` of the rows
+    Occlusion { fraction: f64 },
+    /// Set about `density` of the cells to +1 (salt) or -1 (pepper) in blobs of
+    /// roughly `radius` cells
+    SaltAndPepper { density: f64, radius: usize },
+    /// Gaussian blur with standard deviation `sigma` (in cells), then threshold at 0
+    Blur { sigma: f64 },
+}
+
+impl NoiseModel {
+    /// One model of each kind with default parameters, in the order shown in the UI
+    pub const DEFAULTS: [NoiseModel; 5] = [
+        NoiseModel::BitFlip { probability: 0.1 },
+        NoiseModel::Mask { x: 0.25, y: 0.25, width: 0.5, height: 0.5 },
+        NoiseModel::Occlusion { fraction: 0.5 },
+        NoiseModel::SaltAndPepper { density: 0.2, radius: 2 },
+        NoiseModel::Blur { sigma: 1.0 },
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoiseModel::BitFlip { .. } => "Bit Flip",
+            NoiseModel::Mask { .. } => "Mask Region",
+            NoiseModel::Occlusion { .. } => "Occlusion",
+            NoiseModel::SaltAndPepper { .. } => "Salt and Pepper",
+            NoiseModel::Blur { .. } => "Blur and Threshold",
+        }
+    }
+
+    /// Name and parameters, for labels
+    pub fn describe(&self) -> String {
+        match *self {
+            NoiseModel::BitFlip { probability } => format!("Bit Flip p = {:.2}", probability),
+            NoiseModel::Mask { x, y, width, height } => {
+                format!("Mask {:.0}%x{:.0}% at ({:.2}, {:.2})", width * 100.0, height * 100.0, x, y)
+            },
+            NoiseModel::Occlusion { fraction } => format!("Occlusion of lower {:.0}%", fraction * 100.0),
+            NoiseModel::SaltAndPepper { density, radius } => {
+                format!("Salt and Pepper {:.0}%, radius {}", density * 100.0, radius)
+            },
+            NoiseModel::Blur { sigma } => format!("Blur σ = {:.1}", sigma),
+        }
+    }
+
+    /// Returns a corrupted copy of `state`, a `width` x `height` grid stored row by row
+    pub fn apply(&self, state: &[f64], width: usize, height: usize, rng: &mut impl Rng) -> Vec<f64> {
+        let mut output = state.to_vec();
+        if state.len() != width * height {
+            return output;
+        }
+
+        match *self {
+            NoiseModel::BitFlip { probability } => output = flip_bits(state, probability, rng),
+            NoiseModel::Mask { x, y, width: w, height: h } => {
+                let columns = fraction_range(x, w, width);
+                for row in fraction_range(y, h, height) {
+                    for column in columns.clone() {
+                        output[row * width + column] = -1.0;
+                    }
+                }
+            },
+            NoiseModel::Occlusion { fraction } => {
+                let hidden = (fraction.clamp(0.0, 1.0) * height as f64).round() as usize;
+                for value in &mut output[(height - hidden) * width..] {
+                    *value = -1.0;
+                }
+            },
+            NoiseModel::SaltAndPepper { density, radius } => {
+                // Smoothed white noise: its highest values form blobs, and a second
+                // smoothed field decides whether each blob is salt or pepper
+                let sigma = radius.max(1) as f64 / 2.0;
+                let mut field = vec![0.0; state.len()];
+                let mut color = vec![0.0; state.len()];
+                for (f, c) in field.iter_mut().zip(&mut color) {
+                    *f = rng.gen::<f64>();
+                    *c = rng.gen::<f64>() - 0.5;
+                }
+                let field = gaussian_blur(&field, width, height, sigma);
+                let color = gaussian_blur(&color, width, height, sigma);
+
+                let count = (density.clamp(0.0, 1.0) * state.len() as f64).round() as usize;
+                let mut order: Vec<usize> = (0..state.len()).collect();
+                order.sort_by(|&a, &b| field[b].total_cmp(&field[a]));
+                for &i in order.iter().take(count) {
+                    output[i] = if color[i] >= 0.0 { 1.0 } else { -1.0 };
+                }
+            },
+            NoiseModel::Blur { sigma } => {
+                let blurred = gaussian_blur(state, width, height, sigma);
+                for (value, &b) in output.iter_mut().zip(&blurred) {
+                    // Cells balanced exactly between on and off keep their value
+                    if b != 0.0 {
+                        *value = b.signum();
+                    }
+                }
+            },
+        }
+        output
+    }
+}
+
+/// A region of a grid drawn by the user, whose cells are replaced by random values to
+/// make a partial cue: the network has to complete the region from the rest of the grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionMask {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl RegionMask {
+    /// The cells of a `width` x `height` grid between the corners `a` and `b`, given in
+    /// cell coordinates (a cell is covered if its center lies inside)
+    pub fn rectangle(width: usize, height: usize, a: (f64, f64), b: (f64, f64)) -> Self {
+        let (x0, x1) = (a.0.min(b.0), a.0.max(b.0));
+        let (y0, y1) = (a.1.min(b.1), a.1.max(b.1));
+        Self::from_fn(width, height, |x, y| x >= x0 && x <= x1 && y >= y0 && y <= y1)
+    }
+
+    /// The cells whose centers lie inside the closed polygon through `points`, given in
+    /// cell coordinates
+    pub fn lasso(width: usize, height: usize, points: &[(f64, f64)]) -> Self {
+        Self::from_fn(width, height, |x, y| {
+            // Even-odd rule: count the polygon edges crossed by a ray to the right
+            let mut inside = false;
+            for (i, &(xi, yi)) in points.iter().enumerate() {
+                let (xj, yj) = points[(i + points.len() - 1) % points.len()];
+                if (yi > y) != (yj > y) && x < xi + (y - yi) * (xj - xi) / (yj - yi) {
+                    inside = !inside;
+                }
+            }
+            inside
+        })
+    }
+
+    fn from_fn(width: usize, height: usize, covers: impl Fn(f64, f64) -> bool) -> Self {
+        let cells = (0..width * height)
+            .map(|i| covers((i % width) as f64 + 0.5, (i / width) as f64 + 0.5))
+            .collect();
+        RegionMask { width, height, cells }
+    }
+
+    /// Whether the mask was drawn on a `width` x `height` grid
+    pub fn fits(&self, width: usize, height: usize) -> bool {
+        self.width == width && self.height == height
+    }
+
+    /// Whether each cell, row by row, is masked
+    pub fn cells(&self) -> &[bool] {
+        &self.cells
+    }
+
+    /// Number of masked cells
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|&&masked| masked).count()
+    }
+
+    /// Returns a copy of `state` with every masked cell set to a random ±1
+    pub fn apply(&self, state: &[f64], rng: &mut impl Rng) -> Vec<f64> {
+        let mut output = state.to_vec();
+        if state.len() != self.cells.len() {
+            return output;
+        }
+        for (value, &masked) in output.iter_mut().zip(&self.cells) {
+            if masked {
+                *value = if rng.gen::<bool>() { 1.0 } else { -1.0 };
+            }
+        }
+        output
+    }
+}
+
+/// Flips every value independently with `probability`
+pub fn flip_bits(state: &[f64], probability: f64, rng: &mut impl Rng) -> Vec<f64> {
+    state
+        .iter()
+        .map(|&val| if rng.gen::<f64>() < probability { -val } else { val })
+        .collect()
+}
+
+/// A sample of the standard normal distribution (Box-Muller transform)
+pub fn standard_normal(rng: &mut impl Rng) -> f64 {
+    let u: f64 = 1.0 - rng.gen::<f64>(); // In (0, 1], so the logarithm is finite
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+}
+
+/// The cells covered by the span from `start` of length `length` (fractions of `size`)
+fn fraction_range(start: f64, length: f64, size: usize) -> std::ops::Range<usize> {
+    let first = (start.clamp(0.0, 1.0) * size as f64).round() as usize;
+    let last = ((start + length).clamp(0.0, 1.0) * size as f64).round() as usize;
+    first..last.max(first)
+}
+
+/// Separable Gaussian blur of a grid, truncated at 3 sigma and renormalized at the borders
+fn gaussian_blur(values: &[f64], width: usize, height: usize, sigma: f64) -> Vec<f64> {
+    if sigma <= 0.0 {
+        return values.to_vec();
+    }
+    let radius = (3.0 * sigma).ceil() as isize;
+    let kernel: Vec<f64> = (-radius..=radius)
+        .map(|d| (-((d * d) as f64) / (2.0 * sigma * sigma)).exp())
+        .collect();
+
+    let pass = |input: &[f64], horizontal: bool| -> Vec<f64> {
+        let mut output = vec![0.0; input.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let (mut sum, mut weight) = (0.0, 0.0);
+                for (k, &kw) in kernel.iter().enumerate() {
+                    let d = k as isize - radius;
+                    let (sx, sy) = if horizontal { (x as isize + d, y as isize) } else { (x as isize, y as isize + d) };
+                    if sx >= 0 && sy >= 0 && (sx as usize) < width && (sy as usize) < height {
+                        sum += kw * input[sy as usize * width + sx as usize];
+                        weight += kw;
+                    }
+                }
+                output[y * width + x] = sum / weight;
+            }
+        }
+        output
+    };
+    pass(&pass(values, true), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_mask_and_occlusion_hide_regions() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let state = vec![1.0; 16];
+
+        let masked = NoiseModel::Mask { x: 0.25, y: 0.25, width: 0.5, height: 0.5 }.apply(&state, 4, 4, &mut rng);
+        let hidden: Vec<usize> = (0..16).filter(|&i| masked[i] == -1.0).collect();
+        assert_eq!(hidden, vec![5, 6, 9, 10]);
+
+        let occluded = NoiseModel::Occlusion { fraction: 0.5 }.apply(&state, 4, 4, &mut rng);
+        assert!(occluded[..8].iter().all(|&v| v == 1.0));
+        assert!(occluded[8..].iter().all(|&v| v == -1.0));
+    }
+
+    #[test]
+    fn test_region_masks_cover_cell_centers() {
+        let rectangle = RegionMask::rectangle(4, 4, (3.0, 1.0), (1.0, 3.0));
+        let covered: Vec<usize> = (0..16).filter(|&i| rectangle.cells()[i]).collect();
+        assert_eq!(covered, vec![5, 6, 9, 10]);
+
+        // A triangle over the top left half of the grid, diagonal excluded
+        let lasso = RegionMask::lasso(4, 4, &[(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)]);
+        assert_eq!(lasso.count(), 6);
+        assert!(lasso.cells()[0] && lasso.cells()[2] && !lasso.cells()[3]);
+        assert!(lasso.fits(4, 4) && !lasso.fits(2, 8));
+
+        let mut rng = StdRng::seed_from_u64(2);
+        let state = vec![1.0; 16];
+        let cue = rectangle.apply(&state, &mut rng);
+        assert!((0..16).filter(|&i| !rectangle.cells()[i]).all(|i| cue[i] == 1.0));
+        assert!(cue.iter().all(|&v| v == 1.0 || v == -1.0));
+    }
+
+    #[test]
+    fn test_salt_and_pepper_density_and_blur() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let state = vec![-1.0; 400];
+        let noisy = NoiseModel::SaltAndPepper { density: 0.25, radius: 2 }.apply(&state, 20, 20, &mut rng);
+        let salt = noisy.iter().filter(|&&v| v == 1.0).count();
+        assert!(salt > 0 && salt <= 100);
+
+        // A lone on cell is smoothed away, a solid block survives
+        let mut grid = vec![-1.0; 100];
+        grid[11] = 1.0;
+        for y in 4..9 {
+            for x in 4..9 {
+                grid[y * 10 + x] = 1.0;
+            }
+        }
+        let blurred = NoiseModel::Blur { sigma: 1.0 }.apply(&grid, 10, 10, &mut rng);
+        assert_eq!(blurred[11], -1.0);
+        assert_eq!(blurred[6 * 10 + 6], 1.0);
+    }
+}
@@ -0,0 +1,92 @@
+use nalgebra::{DMatrix, SymmetricEigen};
+
+use crate::core::metrics::overlap;
+
+/// A linear map of states onto two principal components
+#[derive(Debug, Clone)]
+pub struct Projection {
+    mean: Vec<f64>,
+    axes: [Vec<f64>; 2],
+    /// Fraction of the sample variance along each axis
+    pub explained_variance: [f64; 2],
+}
+
+impl Projection {
+    /// Principal component analysis of `samples`, all of the same length.
+    ///
+    /// The components are found from the M x M Gram matrix of the centered samples
+    /// rather than the N x N covariance matrix, which is much cheaper for a few hundred
+    /// samples of a large network.
+    ///
+    /// # Returns
+    ///
+    /// None if there are no samples. If the samples span fewer than two dimensions, the
+    /// missing axis projects everything to 0.
+    pub fn pca(samples: &[Vec<f64>]) -> Option<Self> {
+        let m = samples.len();
+        let n = samples.first()?.len();
+        if samples.iter().any(|s| s.len() != n) {
+            return None;
+        }
+
+        let mut mean = vec![0.0; n];
+        for sample in samples {
+            for (acc, &x) in mean.iter_mut().zip(sample) {
+                *acc += x / m as f64;
+            }
+        }
+        let centered = DMatrix::from_fn(m, n, |i, j| samples[i][j] - mean[j]);
+        let gram = &centered * centered.transpose();
+        let eigen = SymmetricEigen::new(gram);
+
+        let mut order: Vec<usize> = (0..m).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+        let total: f64 = eigen.eigenvalues.iter().map(|v| v.max(0.0)).sum();
+
+        let mut axes = [vec![0.0; n], vec![0.0; n]];
+        let mut explained_variance = [0.0; 2];
+        for (k, &index) in order.iter().take(2).enumerate() {
+            // Axis u = X^T v / |X^T v| for an eigenvector v of X X^T
+            let axis = centered.transpose() * eigen.eigenvectors.column(index);
+            let norm = axis.norm();
+            if norm > 1e-9 {
+                axes[k] = axis.iter().map(|x| x / norm).collect();
+                explained_variance[k] = eigen.eigenvalues[index].max(0.0) / total;
+            }
+        }
+        Some(Projection { mean, axes, explained_variance })
+    }
+
+    /// Coordinates of `state` along the two principal axes
+    pub fn project(&self, state: &[f64]) -> [f64; 2] {
+        let along = |axis: &[f64]| -> f64 {
+            axis.iter().zip(state).zip(&self.mean).map(|((a, x), m)| a * (x - m)).sum()
+        };
+        [along(&self.axes[0]), along(&self.axes[1])]
+    }
+}
+
+/// Coordinates of `state` given by its overlaps with two patterns
+pub fn overlap_coordinates(state: &[f64], first: &[f64], second: &[f64]) -> [f64; 2] {
+    [overlap(state, first), overlap(state, second)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pca_finds_main_direction() {
+        // Points on the line through (1, 1, 0) with a small offset along z
+        let samples: Vec<Vec<f64>> = (-2..=2)
+            .map(|t| vec![t as f64, t as f64, if t == 0 { 0.1 } else { 0.0 }])
+            .collect();
+        let projection = Projection::pca(&samples).unwrap();
+        assert!(projection.explained_variance[0] > 0.99);
+
+        let [a, _] = projection.project(&[2.0, 2.0, 0.0]);
+        let [b, _] = projection.project(&[-2.0, -2.0, 0.0]);
+        assert!(((a - b).abs() - 2.0 * 8.0f64.sqrt()).abs() < 1e-3);
+        assert!(Projection::pca(&[]).is_none());
+    }
+}
@@ -0,0 +1,320 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Error types for run recordings
+#[derive(Debug)]
+pub enum RecorderError {
+    Io(io::Error),
+    /// Error from the file format encoder
+    Encoding(String),
+    /// The format was not compiled in
+    Unavailable(String),
+    DimensionMismatch(String),
+}
+
+impl fmt::Display for RecorderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecorderError::Io(e) => write!(f, "I/O error: {}", e),
+            RecorderError::Encoding(msg) => write!(f, "Encoding error: {}", msg),
+            RecorderError::Unavailable(msg) => write!(f, "Unavailable: {}", msg),
+            RecorderError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+        }
+    }
+}
+
+impl Error for RecorderError {}
+
+impl From<io::Error> for RecorderError {
+    fn from(e: io::Error) -> Self {
+        RecorderError::Io(e)
+    }
+}
+
+/// File format of a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// One row per record: iteration, metrics, then one column per state component
+    Csv,
+    /// Columns `iteration`, one per metric, and `state` as a fixed-size list, written in
+    /// row groups of `ROWS_PER_BATCH` records. Requires the `parquet` feature.
+    Parquet,
+}
+
+impl RecordFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecordFormat::Csv => "CSV",
+            RecordFormat::Parquet => "Parquet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordFormat::Csv => "csv",
+            RecordFormat::Parquet => "parquet",
+        }
+    }
+
+    /// Whether this build can write the format
+    pub fn is_available(&self) -> bool {
+        match self {
+            RecordFormat::Csv => true,
+            RecordFormat::Parquet => cfg!(feature = "parquet"),
+        }
+    }
+}
+
+/// Which quantities of a run are recorded, and how often
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecorderConfig {
+    pub format: RecordFormat,
+    /// Record every `stride`-th iteration (iteration 0 is always recorded)
+    pub stride: usize,
+    /// Record the full state vector, not only the metrics
+    pub record_state: bool,
+    /// Names of the scalar metrics passed to `record`, in order
+    pub metrics: Vec<String>,
+}
+
+/// Records buffered before a Parquet row group is written
+pub const ROWS_PER_BATCH: usize = 1024;
+
+enum RecordWriter {
+    Csv(BufWriter<File>),
+    #[cfg(feature = "parquet")]
+    Parquet(Box<parquet_writer::ParquetWriter>),
+}
+
+/// Writes states and metrics of a running simulation to a file as they are produced,
+/// so a long run only ever holds one batch of records in memory
+pub struct Recorder {
+    config: RecorderConfig,
+    state_len: usize,
+    writer: RecordWriter,
+    rows: usize,
+}
+
+impl Recorder {
+    /// Creates the file at `path` for states of `state_len` components
+    pub fn create(path: impl AsRef<Path>, config: RecorderConfig, state_len: usize) -> Result<Self, RecorderError> {
+        if !config.format.is_available() {
+            return Err(RecorderError::Unavailable(format!(
+                "{} recording requires building with `--features parquet`",
+                config.format.name()
+            )));
+        }
+        let file = File::create(path)?;
+        let writer = match config.format {
+            RecordFormat::Csv => {
+                let mut writer = BufWriter::new(file);
+                let mut header = vec!["iteration".to_string()];
+                header.extend(config.metrics.iter().cloned());
+                if config.record_state {
+                    header.extend((0..state_len).map(|i| format!("s{}", i)));
+                }
+                writeln!(writer, "{}", header.join(","))?;
+                RecordWriter::Csv(writer)
+            },
+            #[cfg(feature = "parquet")]
+            RecordFormat::Parquet => {
+                let state_len = config.record_state.then_some(state_len);
+                RecordWriter::Parquet(Box::new(parquet_writer::ParquetWriter::new(file, &config.metrics, state_len)?))
+            },
+            #[cfg(not(feature = "parquet"))]
+            RecordFormat::Parquet => unreachable!("checked by is_available"),
+        };
+        Ok(Recorder { config, state_len, writer, rows: 0 })
+    }
+
+    /// Whether `iteration` falls on the stride; callers can skip computing metrics otherwise
+    pub fn is_due(&self, iteration: usize) -> bool {
+        iteration.is_multiple_of(self.config.stride.max(1))
+    }
+
+    /// Records `state` and `metrics` (in the order of `RecorderConfig::metrics`) if
+    /// `iteration` is due
+    pub fn record(&mut self, iteration: usize, state: &[f64], metrics: &[f64]) -> Result<(), RecorderError> {
+        if !self.is_due(iteration) {
+            return Ok(());
+        }
+        if state.len() != self.state_len || metrics.len() != self.config.metrics.len() {
+            return Err(RecorderError::DimensionMismatch(format!(
+                "Expected a state of {} and {} metrics, got {} and {}",
+                self.state_len, self.config.metrics.len(), state.len(), metrics.len()
+            )));
+        }
+        let state = if self.config.record_state { state } else { &[] };
+        match &mut self.writer {
+            RecordWriter::Csv(writer) => {
+                write!(writer, "{}", iteration)?;
+                for value in metrics.iter().chain(state) {
+                    write!(writer, ",{}", value)?;
+                }
+                writeln!(writer)?;
+            },
+            #[cfg(feature = "parquet")]
+            RecordWriter::Parquet(writer) => writer.push(iteration, state, metrics)?,
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Records written so far
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Flushes buffered records and closes the file
+    ///
+    /// # Returns
+    ///
+    /// Result with the number of records written
+    pub fn finish(self) -> Result<usize, RecorderError> {
+        match self.writer {
+            RecordWriter::Csv(mut writer) => writer.flush()?,
+            #[cfg(feature = "parquet")]
+            RecordWriter::Parquet(writer) => writer.finish()?,
+        }
+        Ok(self.rows)
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_writer {
+    use std::fs::File;
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, FixedSizeListArray, Float64Array, RecordBatch, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema, SchemaRef};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+
+    use super::{RecorderError, ROWS_PER_BATCH};
+
+    fn encoding_error(e: impl ToString) -> RecorderError {
+        RecorderError::Encoding(e.to_string())
+    }
+
+    /// Column buffers for one row group
+    pub(super) struct ParquetWriter {
+        writer: ArrowWriter<File>,
+        schema: SchemaRef,
+        state_len: Option<usize>,
+        iterations: Vec<u64>,
+        metrics: Vec<Vec<f64>>,
+        states: Vec<f64>,
+    }
+
+    impl ParquetWriter {
+        pub(super) fn new(file: File, metric_names: &[String], state_len: Option<usize>) -> Result<Self, RecorderError> {
+            let mut fields = vec![Field::new("iteration", DataType::UInt64, false)];
+            fields.extend(metric_names.iter().map(|name| Field::new(name, DataType::Float64, false)));
+            if let Some(n) = state_len {
+                let item = Arc::new(Field::new("item", DataType::Float64, false));
+                fields.push(Field::new("state", DataType::FixedSizeList(item, n as i32), false));
+            }
+            let schema = Arc::new(Schema::new(fields));
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .set_max_row_group_size(ROWS_PER_BATCH)
+                .build();
+            let writer = ArrowWriter::try_new(file, Arc::clone(&schema), Some(properties)).map_err(encoding_error)?;
+            Ok(ParquetWriter {
+                writer,
+                schema,
+                state_len,
+                iterations: Vec::with_capacity(ROWS_PER_BATCH),
+                metrics: vec![Vec::with_capacity(ROWS_PER_BATCH); metric_names.len()],
+                states: Vec::new(),
+            })
+        }
+
+        pub(super) fn push(&mut self, iteration: usize, state: &[f64], metrics: &[f64]) -> Result<(), RecorderError> {
+            self.iterations.push(iteration as u64);
+            for (column, &value) in self.metrics.iter_mut().zip(metrics) {
+                column.push(value);
+            }
+            self.states.extend_from_slice(state);
+            if self.iterations.len() >= ROWS_PER_BATCH {
+                self.write_batch()?;
+            }
+            Ok(())
+        }
+
+        fn write_batch(&mut self) -> Result<(), RecorderError> {
+            if self.iterations.is_empty() {
+                return Ok(());
+            }
+            let mut columns: Vec<ArrayRef> = vec![Arc::new(UInt64Array::from(std::mem::take(&mut self.iterations)))];
+            for column in &mut self.metrics {
+                columns.push(Arc::new(Float64Array::from(std::mem::take(column))));
+            }
+            if let Some(n) = self.state_len {
+                let item = Arc::new(Field::new("item", DataType::Float64, false));
+                let values = Arc::new(Float64Array::from(std::mem::take(&mut self.states)));
+                let states = FixedSizeListArray::try_new(item, n as i32, values, None).map_err(encoding_error)?;
+                columns.push(Arc::new(states));
+            }
+            let batch = RecordBatch::try_new(Arc::clone(&self.schema), columns).map_err(encoding_error)?;
+            self.writer.write(&batch).map_err(encoding_error)
+        }
+
+        pub(super) fn finish(mut self) -> Result<(), RecorderError> {
+            self.write_batch()?;
+            self.writer.close().map_err(encoding_error)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(format: RecordFormat) -> RecorderConfig {
+        RecorderConfig {
+            format,
+            stride: 2,
+            record_state: true,
+            metrics: vec!["energy".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_csv_recording_keeps_strided_rows() {
+        let path = std::env::temp_dir().join("raum_recorder_test.csv");
+        let mut recorder = Recorder::create(&path, config(RecordFormat::Csv), 2).unwrap();
+        for i in 0..5 {
+            recorder.record(i, &[1.0, -1.0], &[0.5 * i as f64]).unwrap();
+        }
+        assert!(recorder.record(6, &[1.0], &[0.0]).is_err());
+        assert_eq!(recorder.finish().unwrap(), 3);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(text, "iteration,energy,s0,s1\n0,0,1,-1\n2,1,1,-1\n4,2,1,-1\n");
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_recording_round_trips() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let path = std::env::temp_dir().join("raum_recorder_test.parquet");
+        let mut recorder = Recorder::create(&path, config(RecordFormat::Parquet), 3).unwrap();
+        for i in 0..(2 * ROWS_PER_BATCH + 10) {
+            recorder.record(i, &[1.0, 0.0, -1.0], &[i as f64]).unwrap();
+        }
+        assert_eq!(recorder.finish().unwrap(), ROWS_PER_BATCH + 5);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap().build().unwrap();
+        let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(rows, ROWS_PER_BATCH + 5);
+    }
+}
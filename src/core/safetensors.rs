@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use super::matrix_io::{Matrix, MatrixFileError};
+
+/// An n-dimensional array of numbers in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tensor {
+    pub shape: Vec<usize>,
+    pub data: Vec<f64>,
+}
+
+impl Tensor {
+    pub fn vector(values: &[f64]) -> Self {
+        Tensor { shape: vec![values.len()], data: values.to_vec() }
+    }
+
+    /// A rows × columns tensor; all rows must have the same length
+    pub fn matrix(rows: &[Vec<f64>]) -> Self {
+        let columns = rows.first().map_or(0, Vec::len);
+        Tensor { shape: vec![rows.len(), columns], data: rows.iter().flatten().copied().collect() }
+    }
+
+    /// The rows of a two-dimensional tensor
+    pub fn to_rows(&self) -> Option<Matrix> {
+        match self.shape[..] {
+            [_, 0] => Some(vec![Vec::new(); self.shape[0]]),
+            [_, columns] => Some(self.data.chunks(columns).map(<[f64]>::to_vec).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// Contents of a safetensors file: named tensors and string metadata. Tensors are
+/// written as F64; F32 and integer tensors written by other tools are read as well.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SafeTensors {
+    pub tensors: BTreeMap<String, Tensor>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl SafeTensors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tensor(mut self, name: &str, tensor: Tensor) -> Self {
+        self.tensors.insert(name.to_string(), tensor);
+        self
+    }
+
+    pub fn with_metadata(mut self, key: &str, value: impl ToString) -> Self {
+        self.metadata.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// The tensor called `name`, or an error naming it
+    pub fn tensor(&self, name: &str) -> Result<&Tensor, MatrixFileError> {
+        self.tensors
+            .get(name)
+            .ok_or_else(|| MatrixFileError::Malformed(format!("the file has no tensor '{}'", name)))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), MatrixFileError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, MatrixFileError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// The file: the header length as a little-endian u64, the JSON header, then the
+    /// data of every tensor in the order of the header
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut entries = Vec::new();
+        if !self.metadata.is_empty() {
+            entries.push(format!("\"__metadata__\":{}", json!(self.metadata)));
+        }
+        let mut data = Vec::new();
+        for (name, tensor) in &self.tensors {
+            let start = data.len();
+            for value in &tensor.data {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            let shape: Vec<String> = tensor.shape.iter().map(usize::to_string).collect();
+            entries.push(format!(
+                "{}:{{\"dtype\":\"F64\",\"shape\":[{}],\"data_offsets\":[{},{}]}}",
+                Value::from(name.as_str()),
+                shape.join(","),
+                start,
+                data.len()
+            ));
+        }
+        // The header is padded with spaces so that the data starts 8-byte aligned
+        let mut header = format!("{{{}}}", entries.join(","));
+        while header.len() % 8 != 0 {
+            header.push(' ');
+        }
+
+        let mut bytes = Vec::with_capacity(8 + header.len() + data.len());
+        bytes.extend_from_slice(&(header.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(&data);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MatrixFileError> {
+        let malformed = |msg: &str| MatrixFileError::Malformed(msg.to_string());
+        let length = bytes.get(..8).map(|b| u64::from_le_bytes(b.try_into().unwrap_or([0; 8])) as usize).ok_or_else(|| malformed("missing header length"))?;
+        // The length comes from the file, so it may be anything
+        let header_end = length.checked_add(8).ok_or_else(|| malformed("header length out of range"))?;
+        let header = bytes.get(8..header_end).ok_or_else(|| malformed("truncated header"))?;
+        let data = &bytes[header_end..];
+        let header = std::str::from_utf8(header).map_err(|_| malformed("header is not UTF-8"))?;
+        let header: Value = serde_json::from_str(header)
+            .map_err(|e| MatrixFileError::Malformed(format!("invalid JSON header: {}", e)))?;
+        let Value::Object(entries) = header else {
+            return Err(malformed("header is not a JSON object"));
+        };
+
+        let mut file = SafeTensors::new();
+        for (name, entry) in entries {
+            if name == "__metadata__" {
+                let Value::Object(fields) = entry else {
+                    return Err(malformed("metadata is not an object"));
+                };
+                for (key, value) in fields {
+                    if let Value::String(value) = value {
+                        file.metadata.insert(key, value);
+                    }
+                }
+                continue;
+            }
+            let field = |key: &str| entry.get(key).ok_or_else(|| MatrixFileError::Malformed(format!("tensor '{}' has no {}", name, key)));
+            let Value::String(dtype) = field("dtype")? else {
+                return Err(malformed("dtype is not a string"));
+            };
+            let shape = as_numbers(field("shape")?)?;
+            let offsets = as_numbers(field("data_offsets")?)?;
+            let [start, end] = offsets[..] else {
+                return Err(malformed("data_offsets must hold two numbers"));
+            };
+            let raw = data.get(start..end).ok_or_else(|| malformed("data offsets lie outside the file"))?;
+            let data = decode_values(dtype, raw)?;
+            let size = shape.iter().try_fold(1usize, |size, &dim| size.checked_mul(dim));
+            if size != Some(data.len()) {
+                return Err(MatrixFileError::Malformed(format!("tensor '{}' does not match its shape", name)));
+            }
+            file.tensors.insert(name, Tensor { shape, data });
+        }
+        Ok(file)
+    }
+}
+
+/// Values of raw little-endian tensor data of a safetensors `dtype`
+fn decode_values(dtype: &str, raw: &[u8]) -> Result<Vec<f64>, MatrixFileError> {
+    let values = match dtype {
+        "F64" => raw.chunks_exact(8).map(|b| f64::from_le_bytes(b.try_into().unwrap_or([0; 8]))).collect(),
+        "F32" => raw.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64).collect(),
+        "I64" => raw.chunks_exact(8).map(|b| i64::from_le_bytes(b.try_into().unwrap_or([0; 8])) as f64).collect(),
+        "I32" => raw.chunks_exact(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64).collect(),
+        "I16" => raw.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]) as f64).collect(),
+        "I8" => raw.iter().map(|&b| b as i8 as f64).collect(),
+        "U8" | "BOOL" => raw.iter().map(|&b| b as f64).collect(),
+        other => return Err(MatrixFileError::Unsupported(format!("tensor dtype {}", other))),
+    };
+    Ok(values)
+}
+
+/// An array of non-negative integers, such as a shape
+fn as_numbers(value: &Value) -> Result<Vec<usize>, MatrixFileError> {
+    let invalid = || MatrixFileError::Malformed("expected an array of non-negative integers".to_string());
+    let Value::Array(items) = value else {
+        return Err(invalid());
+    };
+    items
+        .iter()
+        .map(|item| item.as_u64().and_then(|n| usize::try_from(n).ok()).ok_or_else(invalid))
+        .collect()
+}
+
+/// `time` as an ISO 8601 UTC timestamp, e.g. 2024-03-01T12:00:00Z
+pub fn utc_timestamp(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, day_seconds) = (seconds / 86_400, seconds % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        day_seconds / 3600,
+        day_seconds / 60 % 60,
+        day_seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_safetensors_round_trip() {
+        let file = SafeTensors::new()
+            .with_tensor("weights", Tensor::matrix(&[vec![0.0, -0.5], vec![0.5, 0.0]]))
+            .with_tensor("biases", Tensor::vector(&[1.0, 2.0]))
+            .with_metadata("rule", "Hebbian \"quoted\"");
+        let bytes = file.to_bytes();
+        let header_length = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+        assert_eq!((8 + header_length) % 8, 0);
+
+        let read = SafeTensors::from_bytes(&bytes).unwrap();
+        assert_eq!(read, file);
+        assert_eq!(read.tensor("weights").unwrap().to_rows().unwrap()[1], vec![0.5, 0.0]);
+        assert!(read.tensor("missing").is_err());
+    }
+
+    #[test]
+    fn test_rejects_overflowing_lengths() {
+        assert!(matches!(SafeTensors::from_bytes(&u64::MAX.to_le_bytes()), Err(MatrixFileError::Malformed(_))));
+
+        let header = r#"{"x": {"dtype": "F32", "shape": [1099511627776, 1099511627776], "data_offsets": [0, 0]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        assert!(matches!(SafeTensors::from_bytes(&bytes), Err(MatrixFileError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_rejects_deeply_nested_header() {
+        let header = format!("{{\"x\": {}{}}}", "[".repeat(1_000_000), "]".repeat(1_000_000));
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        assert!(matches!(SafeTensors::from_bytes(&bytes), Err(MatrixFileError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_reads_foreign_f32_tensors() {
+        let header = r#"{"x": {"dtype": "F32", "shape": [3], "data_offsets": [0, 12]}, "__metadata__": {"format": "pt"}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        for value in [1.5f32, -2.0, 0.25] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        let file = SafeTensors::from_bytes(&bytes).unwrap();
+        assert_eq!(file.tensor("x").unwrap(), &Tensor::vector(&[1.5, -2.0, 0.25]));
+        assert_eq!(file.metadata["format"], "pt");
+        assert_eq!(utc_timestamp(UNIX_EPOCH + Duration::from_secs(951_827_696)), "2000-02-29T12:34:56Z");
+    }
+}
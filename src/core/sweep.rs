@@ -0,0 +1,216 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// A swept parameter and the values it takes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterRange {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+impl ParameterRange {
+    /// `steps` evenly spaced values from `start` to `end`; a single step takes `start`
+    pub fn linear(name: impl Into<String>, start: f64, end: f64, steps: usize) -> Self {
+        let values = match steps {
+            0 | 1 => vec![start],
+            _ => (0..steps).map(|i| start + (end - start) * i as f64 / (steps - 1) as f64).collect(),
+        };
+        ParameterRange { name: name.into(), values }
+    }
+}
+
+/// Every combination of the parameter values, with the last parameter varying fastest
+pub fn combinations(ranges: &[ParameterRange]) -> Vec<Vec<f64>> {
+    ranges.iter().fold(vec![Vec::new()], |points, range| {
+        points
+            .iter()
+            .flat_map(|point| {
+                range.values.iter().map(move |&value| {
+                    let mut point = point.clone();
+                    point.push(value);
+                    point
+                })
+            })
+            .collect()
+    })
+}
+
+/// A grid search: the ranges of the swept parameters and the names of the metrics the
+/// evaluation returns for each combination
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
+    pub ranges: Vec<ParameterRange>,
+    pub metrics: Vec<String>,
+}
+
+impl Sweep {
+    /// Number of parameter combinations
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|range| range.values.len()).product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Evaluates every combination on `threads` worker threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluate` - Metrics at a combination, given its index in `combinations` (to
+    ///   derive a seed, so results do not depend on which thread ran what) and its values
+    /// * `progress` - Called with the number of combinations done; the sweep stops
+    ///   early once it returns false
+    ///
+    /// # Returns
+    ///
+    /// The results of the evaluated combinations, in order
+    pub fn run<E, P>(&self, threads: usize, evaluate: E, progress: P) -> SweepResults
+    where
+        E: Fn(usize, &[f64]) -> Vec<f64> + Sync,
+        P: Fn(usize) -> bool + Sync,
+    {
+        let points = combinations(&self.ranges);
+        let next = AtomicUsize::new(0);
+        let done = AtomicUsize::new(0);
+        let stopped = AtomicBool::new(false);
+        let rows = Mutex::new(Vec::with_capacity(points.len()));
+
+        thread::scope(|scope| {
+            for _ in 0..threads.clamp(1, points.len().max(1)) {
+                scope.spawn(|| {
+                    while !stopped.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(point) = points.get(index) else {
+                            break;
+                        };
+                        let metrics = evaluate(index, point);
+                        rows.lock().unwrap_or_else(|e| e.into_inner()).push(SweepRow {
+                            index,
+                            parameters: point.clone(),
+                            metrics,
+                        });
+                        if !progress(done.fetch_add(1, Ordering::Relaxed) + 1) {
+                            stopped.store(true, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut rows = rows.into_inner().unwrap_or_else(|e| e.into_inner());
+        rows.sort_by_key(|row| row.index);
+        SweepResults { sweep: self.clone(), rows }
+    }
+}
+
+/// Metrics at one parameter combination
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepRow {
+    /// Index of the combination in `combinations`
+    pub index: usize,
+    pub parameters: Vec<f64>,
+    pub metrics: Vec<f64>,
+}
+
+/// Mean of a metric over a pair of swept parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatmap {
+    pub x_values: Vec<f64>,
+    pub y_values: Vec<f64>,
+    /// `values[y][x]`, averaged over the other parameters; None where nothing was evaluated
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
+impl Heatmap {
+    /// Smallest and largest value, or None if the map is empty
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.values.iter().flatten().flatten().fold(None, |range, &value| match range {
+            None => Some((value, value)),
+            Some((min, max)) => Some((f64::min(min, value), f64::max(max, value))),
+        })
+    }
+}
+
+/// The rows of a finished (or cancelled) sweep
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepResults {
+    pub sweep: Sweep,
+    pub rows: Vec<SweepRow>,
+}
+
+impl SweepResults {
+    /// Column names of `table`: the parameters, then the metrics
+    pub fn header(&self) -> Vec<&str> {
+        let parameters = self.sweep.ranges.iter().map(|range| range.name.as_str());
+        parameters.chain(self.sweep.metrics.iter().map(String::as_str)).collect()
+    }
+
+    /// One row per evaluated combination, for CSV export
+    pub fn table(&self) -> Vec<Vec<f64>> {
+        self.rows.iter().map(|row| row.parameters.iter().chain(&row.metrics).copied().collect()).collect()
+    }
+
+    /// Mean of `metric` for every pair of values of parameters `x` and `y`
+    pub fn heatmap(&self, x: usize, y: usize, metric: usize) -> Heatmap {
+        let x_values = self.sweep.ranges[x].values.clone();
+        let y_values = self.sweep.ranges[y].values.clone();
+        let mut sums = vec![vec![(0.0, 0usize); x_values.len()]; y_values.len()];
+        for row in &self.rows {
+            let column = x_values.iter().position(|&v| v == row.parameters[x]);
+            let line = y_values.iter().position(|&v| v == row.parameters[y]);
+            if let (Some(column), Some(line), Some(&value)) = (column, line, row.metrics.get(metric)) {
+                sums[line][column].0 += value;
+                sums[line][column].1 += 1;
+            }
+        }
+        let values = sums
+            .into_iter()
+            .map(|line| line.into_iter().map(|(sum, count)| (count > 0).then(|| sum / count as f64)).collect())
+            .collect();
+        Heatmap { x_values, y_values, values }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sweep() -> Sweep {
+        Sweep {
+            ranges: vec![ParameterRange::linear("a", 0.0, 1.0, 2), ParameterRange::linear("b", 1.0, 3.0, 3)],
+            metrics: vec!["sum".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_combinations_vary_last_fastest() {
+        let points = combinations(&sweep().ranges);
+        assert_eq!(points.len(), sweep().len());
+        assert_eq!(points[0], vec![0.0, 1.0]);
+        assert_eq!(points[1], vec![0.0, 2.0]);
+        assert_eq!(points[3], vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_run_collects_rows_in_order() {
+        let results = sweep().run(3, |_, point| vec![point.iter().sum()], |_| true);
+        assert_eq!(results.rows.len(), 6);
+        assert!(results.rows.iter().enumerate().all(|(i, row)| row.index == i));
+        assert_eq!(results.header(), vec!["a", "b", "sum"]);
+        assert_eq!(results.table()[5], vec![1.0, 3.0, 4.0]);
+
+        // Averaged over nothing else, the heatmap is the metric itself
+        let map = results.heatmap(1, 0, 0);
+        assert_eq!(map.values[1][2], Some(4.0));
+        assert_eq!(map.range(), Some((1.0, 4.0)));
+    }
+
+    #[test]
+    fn test_run_stops_when_progress_declines() {
+        let results = sweep().run(1, |_, _| vec![0.0], |done| done < 2);
+        assert_eq!(results.rows.len(), 2);
+        assert_eq!(results.heatmap(0, 1, 0).values[2][1], None);
+    }
+}
@@ -0,0 +1,72 @@
+use thiserror::Error;
+
+use crate::core::graph::GraphError;
+use crate::core::matrix_io::MatrixFileError;
+use crate::graphics::export::ExportError;
+use crate::neural::chip_firing::ChipFiringError;
+use crate::neural::hopfield::HopfieldError;
+
+/// Errors of any part of Raum, so that code combining several models, files and
+/// exports can use `?` throughout and report a single type
+#[derive(Debug, Error)]
+pub enum RaumError {
+    #[error("Hopfield network: {0}")]
+    Hopfield(#[from] HopfieldError),
+
+    #[error("Chip firing: {0}")]
+    ChipFiring(#[from] ChipFiringError),
+
+    #[error("Graph: {0}")]
+    Graph(#[from] GraphError),
+
+    #[error("Export: {0}")]
+    Export(#[from] ExportError),
+
+    #[error("Matrix file: {0}")]
+    MatrixFile(#[from] MatrixFileError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A failure described in words, e.g. a missing selection in the UI
+    #[error("{0}")]
+    Message(String),
+}
+
+impl From<String> for RaumError {
+    fn from(message: String) -> Self {
+        RaumError::Message(message)
+    }
+}
+
+impl From<&str> for RaumError {
+    fn from(message: &str) -> Self {
+        RaumError::Message(message.to_string())
+    }
+}
+
+/// Result type of functions that can fail in several subsystems
+pub type Result<T> = std::result::Result<T, RaumError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::neural::hopfield::HopfieldNetwork;
+
+    fn energy_of_missing_file(path: &str) -> Result<f64> {
+        let text = std::fs::read_to_string(path)?;
+        let state: Vec<f64> = text.split(',').filter_map(|x| x.trim().parse().ok()).collect();
+        Ok(HopfieldNetwork::new(4).energy(&state)?)
+    }
+
+    #[test]
+    fn test_errors_convert_and_keep_their_source() {
+        let err = energy_of_missing_file("/nonexistent/raum/state.csv").unwrap_err();
+        assert!(matches!(err, RaumError::Io(_)));
+        assert!(std::error::Error::source(&err).is_some());
+
+        let err: RaumError = HopfieldNetwork::new(4).energy(&[1.0]).unwrap_err().into();
+        assert!(err.to_string().starts_with("Hopfield network: Dimension mismatch"));
+        assert_eq!(RaumError::from("Select a pattern first").to_string(), "Select a pattern first");
+    }
+}
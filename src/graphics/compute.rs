@@ -0,0 +1,177 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::mpsc;
+use std::sync::Arc;
+use eframe::egui_wgpu;
+use eframe::wgpu;
+use eframe::wgpu::util::DeviceExt;
+
+/// Error types for GPU compute kernels
+#[derive(Debug)]
+pub enum ComputeError {
+    /// No wgpu device, e.g. when the app runs on the glow backend
+    Unavailable(String),
+    /// The problem does not fit in the buffers the device allows
+    TooLarge(String),
+    DimensionMismatch(String),
+    /// Reading results back from the device failed
+    Readback(String),
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeError::Unavailable(msg) => write!(f, "GPU unavailable: {}", msg),
+            ComputeError::TooLarge(msg) => write!(f, "Too large for the GPU: {}", msg),
+            ComputeError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+            ComputeError::Readback(msg) => write!(f, "Readback failed: {}", msg),
+        }
+    }
+}
+
+impl Error for ComputeError {}
+
+/// The wgpu device and queue that compute kernels run on, shared with the egui renderer
+#[derive(Clone)]
+pub struct GpuContext {
+    pub device: Arc<wgpu::Device>,
+    pub queue: Arc<wgpu::Queue>,
+    /// Name of the adapter, for display
+    pub adapter_name: String,
+}
+
+impl GpuContext {
+    pub fn from_render_state(render_state: &egui_wgpu::RenderState) -> Self {
+        GpuContext {
+            device: Arc::clone(&render_state.device),
+            queue: Arc::clone(&render_state.queue),
+            adapter_name: render_state.adapter.get_info().name,
+        }
+    }
+
+    /// Fails unless a storage buffer of `bytes` bytes can be bound
+    pub fn check_buffer_size(&self, bytes: u64) -> Result<(), ComputeError> {
+        let limit = self.device.limits().max_storage_buffer_binding_size as u64;
+        if bytes > limit {
+            return Err(ComputeError::TooLarge(format!("a buffer of {} bytes exceeds the limit of {}", bytes, limit)));
+        }
+        Ok(())
+    }
+
+    /// A storage buffer holding `contents`, which can also be copied from
+    pub fn storage_buffer(&self, label: &str, contents: &[u8]) -> wgpu::Buffer {
+        self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(label),
+            contents,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+        })
+    }
+
+    /// A compute pipeline for the entry point `main` of a WGSL shader, with the bind
+    /// group layout derived from the shader
+    pub fn compute_pipeline(&self, label: &str, wgsl: &str) -> wgpu::ComputePipeline {
+        let module = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+        });
+        self.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: None,
+            module: &module,
+            entry_point: "main",
+            compilation_options: Default::default(),
+        })
+    }
+
+    /// A bind group binding `buffers` to bindings 0, 1, ... of group 0 of `pipeline`
+    pub fn bind_buffers(&self, label: &str, pipeline: &wgpu::ComputePipeline, buffers: &[&wgpu::Buffer]) -> wgpu::BindGroup {
+        let entries: Vec<wgpu::BindGroupEntry> = buffers
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| wgpu::BindGroupEntry { binding: i as u32, resource: buffer.as_entire_binding() })
+            .collect();
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(label),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        })
+    }
+
+    /// Submits `encoder` after copying `source` into a staging buffer, waits for the
+    /// device and returns the contents of `source`
+    pub fn submit_and_read(&self, mut encoder: wgpu::CommandEncoder, source: &wgpu::Buffer) -> Result<Vec<u8>, ComputeError> {
+        let size = source.size();
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(source, 0, &staging, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        match receiver.recv() {
+            Ok(Ok(())) => {},
+            Ok(Err(e)) => return Err(ComputeError::Readback(e.to_string())),
+            Err(_) => return Err(ComputeError::Readback("the device was lost".to_string())),
+        }
+        let bytes = slice.get_mapped_range().to_vec();
+        staging.unmap();
+        Ok(bytes)
+    }
+
+    pub fn encoder(&self, label: &str) -> wgpu::CommandEncoder {
+        self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some(label) })
+    }
+}
+
+/// Little-endian bytes of `values`, the layout of a WGSL `array<f32>`
+pub fn f32_bytes(values: impl IntoIterator<Item = f32>) -> Vec<u8> {
+    values.into_iter().flat_map(f32::to_le_bytes).collect()
+}
+
+/// The values of a WGSL `array<f32>` read back as bytes
+pub fn bytes_to_f32(bytes: &[u8]) -> Vec<f32> {
+    bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+/// Little-endian bytes of `values`, the layout of a WGSL `array<i32>`
+pub fn i32_bytes(values: impl IntoIterator<Item = i32>) -> Vec<u8> {
+    values.into_iter().flat_map(i32::to_le_bytes).collect()
+}
+
+/// The values of a WGSL `array<i32>` read back as bytes
+pub fn bytes_to_i32(bytes: &[u8]) -> Vec<i32> {
+    bytes.chunks_exact(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+/// Little-endian bytes of `values`, the layout of a WGSL `array<u32>`
+pub fn u32_bytes(values: impl IntoIterator<Item = u32>) -> Vec<u8> {
+    values.into_iter().flat_map(u32::to_le_bytes).collect()
+}
+
+/// The values of a WGSL `array<u32>` read back as bytes
+pub fn bytes_to_u32(bytes: &[u8]) -> Vec<u32> {
+    bytes.chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f32_bytes_round_trip() {
+        let values = [1.0, -1.0, 0.5, f32::MAX];
+        let bytes = f32_bytes(values);
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(bytes_to_f32(&bytes), values);
+        assert_eq!(bytes_to_i32(&i32_bytes([-3, 7])), vec![-3, 7]);
+        assert_eq!(bytes_to_u32(&u32_bytes([0, u32::MAX])), vec![0, u32::MAX]);
+    }
+}
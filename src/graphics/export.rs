@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageFormat, RgbaImage};
+
+/// Error types for image and animation export
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Encoding(image::ImageError),
+    NoFrames(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "I/O error: {}", e),
+            ExportError::Encoding(e) => write!(f, "Encoding error: {}", e),
+            ExportError::NoFrames(msg) => write!(f, "Nothing to export: {}", msg),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for ExportError {
+    fn from(e: image::ImageError) -> Self {
+        ExportError::Encoding(e)
+    }
+}
+
+/// Writes `frames` to `path` as a looping animated GIF.
+///
+/// # Arguments
+///
+/// * `path` - Output file
+/// * `frames` - Frame images; all should have the same size
+/// * `fps` - Playback rate in frames per second (GIF delays have 10 ms resolution)
+pub fn write_gif(path: impl AsRef<Path>, frames: Vec<RgbaImage>, fps: f32) -> Result<(), ExportError> {
+    if frames.is_empty() {
+        return Err(ExportError::NoFrames("the animation has no frames".to_string()));
+    }
+
+    let delay_ms = (1000.0 / fps.max(0.1)).round() as u32;
+    let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+    encoder.set_repeat(Repeat::Infinite)?;
+    encoder.encode_frames(frames.into_iter().map(|image| {
+        Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1))
+    }))?;
+    Ok(())
+}
+
+/// Writes a single image to `path` as a PNG
+pub fn write_png(path: impl AsRef<Path>, image: &RgbaImage) -> Result<(), ExportError> {
+    image.save_with_format(path, ImageFormat::Png)?;
+    Ok(())
+}
+
+/// Writes a table of numbers to `path` as CSV with a header row
+pub fn write_csv(path: impl AsRef<Path>, header: &[&str], rows: &[Vec<f64>]) -> Result<(), ExportError> {
+    if rows.is_empty() {
+        return Err(ExportError::NoFrames("the table has no rows".to_string()));
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "{}", header.join(","))?;
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,453 @@
+use std::error::Error;
+use eframe::wgpu;
+use eframe::wgpu::util::DeviceExt;
+use nalgebra::{Matrix4, Perspective3, Point3, Vector3};
+
+use super::compute::{bytes_to_f32, f32_bytes, u32_bytes};
+use super::renderer::{nearest_sampler, texture_bind_group, texture_pipeline, RenderError, Renderer, STATE_TEXTURE_FORMAT};
+
+/// Format of the depth buffer the surface is drawn with
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Height of the tallest column as a fraction of the longer side of the grid
+const RELIEF: f32 = 0.35;
+
+/// Color behind the surface
+const BACKGROUND: wgpu::Color = wgpu::Color { r: 0.06, g: 0.06, b: 0.08, a: 1.0 };
+
+/// One vertex per cell, lifted by its height and lit from above. The grid is scaled
+/// so that its longer side spans one unit, centered on the origin.
+const SURFACE_SHADER: &str = r#"
+struct Uniforms {
+    view_projection: mat4x4<f32>,
+    columns: u32,
+    rows: u32,
+    height_scale: f32,
+    max_height: f32,
+};
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var<storage, read> heights: array<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+    @location(1) level: f32,
+};
+
+fn lifted(x: i32, z: i32) -> f32 {
+    let cx = clamp(x, 0, i32(uniforms.columns) - 1);
+    let cz = clamp(z, 0, i32(uniforms.rows) - 1);
+    return heights[u32(cz) * uniforms.columns + u32(cx)] * uniforms.height_scale;
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = i32(index % uniforms.columns);
+    let z = i32(index / uniforms.columns);
+    let unit = 1.0 / f32(max(uniforms.columns, uniforms.rows));
+    let position = vec3<f32>(
+        (f32(x) - f32(uniforms.columns - 1u) * 0.5) * unit,
+        lifted(x, z),
+        (f32(z) - f32(uniforms.rows - 1u) * 0.5) * unit,
+    );
+    let normal = vec3<f32>(lifted(x - 1, z) - lifted(x + 1, z), 2.0 * unit, lifted(x, z - 1) - lifted(x, z + 1));
+
+    var out: VertexOutput;
+    out.position = uniforms.view_projection * vec4<f32>(position, 1.0);
+    out.normal = normal;
+    out.level = select(0.0, heights[index] / uniforms.max_height, uniforms.max_height > 0.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let light = normalize(vec3<f32>(0.4, 1.0, 0.3));
+    let shade = 0.35 + 0.65 * max(dot(normalize(in.normal), light), 0.0);
+    let low = vec3<f32>(0.16, 0.24, 0.55);
+    let high = vec3<f32>(0.98, 0.85, 0.25);
+    return vec4<f32>(mix(low, high, clamp(in.level, 0.0, 1.0)) * shade, 1.0);
+}
+"#;
+
+/// Maps the OpenGL clip depth range [-1, 1] produced by nalgebra to wgpu's [0, 1]
+fn opengl_to_wgpu() -> Matrix4<f32> {
+    Matrix4::new(
+        1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 0.5, 0.5,
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+/// A camera circling the center of the grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitCamera {
+    /// Angle around the vertical axis, in radians
+    pub yaw: f32,
+    /// Angle above the grid plane, in radians
+    pub pitch: f32,
+    /// Distance from the center, in units of the grid's longer side
+    pub distance: f32,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        OrbitCamera { yaw: 0.8, pitch: 0.6, distance: 1.8 }
+    }
+}
+
+impl OrbitCamera {
+    const MIN_PITCH: f32 = 0.05;
+    const MAX_PITCH: f32 = 1.5;
+    const MIN_DISTANCE: f32 = 0.3;
+    const MAX_DISTANCE: f32 = 10.0;
+
+    /// Turns by `yaw` and `pitch` radians, keeping the camera above the grid
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = (self.yaw + yaw).rem_euclid(std::f32::consts::TAU);
+        self.pitch = (self.pitch + pitch).clamp(Self::MIN_PITCH, Self::MAX_PITCH);
+    }
+
+    /// Moves closer for factors above 1 and away for factors below
+    pub fn zoom(&mut self, factor: f32) {
+        self.distance = (self.distance / factor.max(1e-3)).clamp(Self::MIN_DISTANCE, Self::MAX_DISTANCE);
+    }
+
+    pub fn eye(&self) -> Point3<f32> {
+        Point3::new(
+            self.distance * self.pitch.cos() * self.yaw.cos(),
+            self.distance * self.pitch.sin(),
+            self.distance * self.pitch.cos() * self.yaw.sin(),
+        )
+    }
+
+    /// Clip-space transform for a view of width / height `aspect`, in wgpu's depth range
+    pub fn view_projection(&self, aspect: f32) -> Matrix4<f32> {
+        let view = Matrix4::look_at_rh(&self.eye(), &Point3::origin(), &Vector3::y());
+        let projection = Perspective3::new(aspect.max(1e-3), 45f32.to_radians(), 0.01, 50.0);
+        opengl_to_wgpu() * projection.to_homogeneous() * view
+    }
+}
+
+/// Indices of two triangles per grid square of a `columns` x `rows` vertex grid
+pub fn grid_indices(columns: u32, rows: u32) -> Vec<u32> {
+    let mut indices = Vec::with_capacity(6 * (columns.saturating_sub(1) * rows.saturating_sub(1)) as usize);
+    for z in 0..rows.saturating_sub(1) {
+        for x in 0..columns.saturating_sub(1) {
+            let a = z * columns + x;
+            let (b, c) = (a + 1, a + columns);
+            indices.extend_from_slice(&[a, c, b, b, c, c + 1]);
+        }
+    }
+    indices
+}
+
+/// Pipelines and buffers created by `initialize`
+struct SurfaceResources {
+    surface_pipeline: wgpu::RenderPipeline,
+    blit_pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+    uniforms: wgpu::Buffer,
+}
+
+/// Buffers sized for the grid
+struct GridBuffers {
+    heights: wgpu::Buffer,
+    indices: wgpu::Buffer,
+    index_count: u32,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Offscreen color and depth textures sized for the view
+struct RenderTargets {
+    color: wgpu::TextureView,
+    depth: wgpu::TextureView,
+    blit_bind_group: wgpu::BindGroup,
+}
+
+/// Renderer drawing a grid of heights, e.g. chip counts, as a lit surface seen by an
+/// orbit camera. The surface needs a depth buffer, which egui's render pass lacks, so
+/// `render_offscreen` draws it into the renderer's own textures and `paint` copies the
+/// result into an egui paint callback.
+pub struct HeightFieldRenderer {
+    /// Format of the views `paint` draws into
+    target_format: wgpu::TextureFormat,
+    /// Size of the offscreen textures in pixels
+    width: u32,
+    height: u32,
+    columns: u32,
+    rows: u32,
+    /// Scale and largest value of the last uploaded heights
+    height_scale: f32,
+    max_height: f32,
+
+    pub camera: OrbitCamera,
+
+    resources: Option<SurfaceResources>,
+    grid: Option<GridBuffers>,
+    targets: Option<RenderTargets>,
+}
+
+impl HeightFieldRenderer {
+    pub fn new(target_format: wgpu::TextureFormat, columns: u32, rows: u32) -> Self {
+        HeightFieldRenderer {
+            target_format,
+            width: 1,
+            height: 1,
+            columns: columns.max(2),
+            rows: rows.max(2),
+            height_scale: 0.0,
+            max_height: 0.0,
+            camera: OrbitCamera::default(),
+            resources: None,
+            grid: None,
+            targets: None,
+        }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.resources.is_some()
+    }
+
+    pub fn grid_size(&self) -> (u32, u32) {
+        (self.columns, self.rows)
+    }
+
+    /// Recreates the grid buffers for `columns` x `rows` heights; the heights are zero
+    /// until the next `update`
+    pub fn set_grid(&mut self, device: &wgpu::Device, columns: u32, rows: u32) {
+        let (columns, rows) = (columns.max(2), rows.max(2));
+        if (columns, rows) == (self.columns, self.rows) && self.grid.is_some() {
+            return;
+        }
+        self.columns = columns;
+        self.rows = rows;
+        self.create_grid(device);
+    }
+
+    fn create_grid(&mut self, device: &wgpu::Device) {
+        let Some(resources) = &self.resources else {
+            return;
+        };
+        let heights = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("height_field_heights"),
+            size: (self.columns * self.rows * 4) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let indices = grid_indices(self.columns, self.rows);
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("height_field_indices"),
+            contents: &u32_bytes(indices.iter().copied()),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("height_field"),
+            layout: &resources.surface_pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: resources.uniforms.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: heights.as_entire_binding() },
+            ],
+        });
+        self.grid = Some(GridBuffers { heights, indices: index_buffer, index_count: indices.len() as u32, bind_group });
+        self.max_height = 0.0;
+        self.height_scale = 0.0;
+    }
+
+    fn create_targets(&mut self, device: &wgpu::Device) {
+        let Some(resources) = &self.resources else {
+            return;
+        };
+        let size = wgpu::Extent3d { width: self.width, height: self.height, depth_or_array_layers: 1 };
+        let target = |label, format, usage| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        let color = target(
+            "height_field_color",
+            STATE_TEXTURE_FORMAT,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        let depth = target("height_field_depth", DEPTH_FORMAT, wgpu::TextureUsages::RENDER_ATTACHMENT);
+        let blit_bind_group = texture_bind_group(device, &resources.blit_pipeline, &color, &resources.sampler);
+        self.targets = Some(RenderTargets { color, depth, blit_bind_group });
+    }
+
+    /// Draws the surface into the offscreen textures
+    pub fn render_offscreen(&self, device: &wgpu::Device, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(targets) = &self.targets {
+            self.render(device, queue, encoder, &targets.color);
+        }
+    }
+
+    /// Copies the last `render_offscreen` result into `render_pass`, covering its viewport
+    pub fn paint<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        let (Some(resources), Some(targets)) = (&self.resources, &self.targets) else {
+            return;
+        };
+        render_pass.set_pipeline(&resources.blit_pipeline);
+        render_pass.set_bind_group(0, &targets.blit_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+impl Renderer for HeightFieldRenderer {
+    fn initialize(&mut self, device: &wgpu::Device, _queue: &wgpu::Queue) -> Result<(), Box<dyn Error>> {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("height_field"),
+            source: wgpu::ShaderSource::Wgsl(SURFACE_SHADER.into()),
+        });
+        let surface_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("height_field"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            // Both sides are drawn, so the winding order does not matter
+            primitive: wgpu::PrimitiveState { cull_mode: None, ..Default::default() },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: STATE_TEXTURE_FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("height_field_uniforms"),
+            size: 80,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.resources = Some(SurfaceResources {
+            surface_pipeline,
+            blit_pipeline: texture_pipeline(device, self.target_format),
+            sampler: nearest_sampler(device),
+            uniforms,
+        });
+        self.create_grid(device);
+        self.create_targets(device);
+        Ok(())
+    }
+
+    /// Draws the surface into `view`, which must have the size passed to `resize` and
+    /// the state texture format
+    fn render(
+        &self,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView
+    ) {
+        let (Some(resources), Some(grid), Some(targets)) = (&self.resources, &self.grid, &self.targets) else {
+            return;
+        };
+        let view_projection = self.camera.view_projection(self.width as f32 / self.height as f32);
+        let mut uniforms = f32_bytes(view_projection.as_slice().iter().copied());
+        uniforms.extend(u32_bytes([self.columns, self.rows]));
+        uniforms.extend(f32_bytes([self.height_scale, self.max_height]));
+        queue.write_buffer(&resources.uniforms, 0, &uniforms);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("height_field"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Clear(BACKGROUND), store: wgpu::StoreOp::Store },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &targets.depth,
+                depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Clear(1.0), store: wgpu::StoreOp::Discard }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&resources.surface_pipeline);
+        render_pass.set_bind_group(0, &grid.bind_group, &[]);
+        render_pass.set_index_buffer(grid.indices.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..grid.index_count, 0, 0..1);
+    }
+
+    /// Uploads `columns * rows` heights as little-endian f32, row by row
+    fn update(&mut self, queue: &wgpu::Queue, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let Some(grid) = &self.grid else {
+            return Err(Box::new(RenderError::NotInitialized));
+        };
+        let expected = (self.columns * self.rows * 4) as usize;
+        if data.len() != expected {
+            return Err(Box::new(RenderError::DimensionMismatch(format!(
+                "{} bytes given for a {}x{} grid of {} bytes",
+                data.len(),
+                self.columns,
+                self.rows,
+                expected
+            ))));
+        }
+        queue.write_buffer(&grid.heights, 0, data);
+        self.max_height = bytes_to_f32(data).into_iter().fold(0.0, f32::max);
+        self.height_scale = if self.max_height > 0.0 { RELIEF / self.max_height } else { 0.0 };
+        Ok(())
+    }
+
+    /// Recreates the offscreen textures for a view of `width` x `height` pixels
+    fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height) == (self.width, self.height) && self.targets.is_some() {
+            return;
+        }
+        self.width = width;
+        self.height = height;
+        self.create_targets(device);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_indices_cover_every_square() {
+        let indices = grid_indices(3, 2);
+        assert_eq!(indices, vec![0, 3, 1, 1, 3, 4, 1, 4, 2, 2, 4, 5]);
+        assert!(grid_indices(1, 5).is_empty());
+    }
+
+    #[test]
+    fn test_orbit_camera_looks_at_center() {
+        let mut camera = OrbitCamera::default();
+        camera.orbit(0.0, 10.0);
+        assert_eq!(camera.pitch, OrbitCamera::MAX_PITCH);
+
+        let clip = camera.view_projection(1.5) * nalgebra::Vector4::new(0.0, 0.0, 0.0, 1.0);
+        assert!((clip.x / clip.w).abs() < 1e-5 && (clip.y / clip.w).abs() < 1e-5);
+        let depth = clip.z / clip.w;
+        assert!((0.0..=1.0).contains(&depth));
+    }
+}
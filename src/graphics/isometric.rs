@@ -0,0 +1,126 @@
+/// Horizontal and vertical extent of a unit step along a grid axis in an isometric view
+const COS_30: f32 = 0.866_025_4;
+const SIN_30: f32 = 0.5;
+
+/// Faces of one cell drawn as a block, in projected coordinates with y pointing down
+#[derive(Debug, Clone, PartialEq)]
+pub struct IsometricBlock {
+    /// (column, row) of the cell in the unrotated grid
+    pub cell: (usize, usize),
+    pub top: [[f32; 2]; 4],
+    /// The two sides facing the viewer
+    pub left: [[f32; 2]; 4],
+    pub right: [[f32; 2]; 4],
+}
+
+/// Isometric view of a grid of columns standing on the plane at height 0, turned by
+/// `rotation` quarter turns. Cells are one unit wide; heights are in the same units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsometricProjection {
+    pub columns: usize,
+    pub rows: usize,
+    pub rotation: u8,
+}
+
+impl IsometricProjection {
+    pub fn new(columns: usize, rows: usize, rotation: u8) -> Self {
+        IsometricProjection { columns, rows, rotation: rotation % 4 }
+    }
+
+    /// Cell coordinates after rotating the grid, so that larger x + z is nearer
+    fn rotate(&self, x: f32, z: f32) -> (f32, f32) {
+        let (w, h) = (self.columns as f32, self.rows as f32);
+        match self.rotation {
+            1 => (h - z, x),
+            2 => (w - x, h - z),
+            3 => (z, w - x),
+            _ => (x, z),
+        }
+    }
+
+    /// Projected position of the point at (`x`, `z`) in cell coordinates (the corner of
+    /// cell (c, r) is at (c, r)), lifted by `height`
+    pub fn project(&self, x: f32, z: f32, height: f32) -> [f32; 2] {
+        let (x, z) = self.rotate(x, z);
+        [(x - z) * COS_30, (x + z) * SIN_30 - height]
+    }
+
+    /// Blocks for `heights`, `columns` per row, ordered from back to front so that
+    /// drawing them in turn hides what lies behind
+    pub fn blocks(&self, heights: &[f32]) -> Vec<IsometricBlock> {
+        let mut order: Vec<(usize, usize)> = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (column, row)))
+            .filter(|&(column, row)| row * self.columns + column < heights.len())
+            .collect();
+        order.sort_by(|&(c1, r1), &(c2, r2)| {
+            let depth = |c: usize, r: usize| {
+                let (x, z) = self.rotate(c as f32 + 0.5, r as f32 + 0.5);
+                x + z
+            };
+            depth(c1, r1).total_cmp(&depth(c2, r2))
+        });
+
+        order
+            .into_iter()
+            .map(|(column, row)| {
+                let height = heights[row * self.columns + column];
+                // Corners of the rotated cell, from its back corner
+                let (cx, cz) = self.rotate(column as f32 + 0.5, row as f32 + 0.5);
+                let (x0, z0, x1, z1) = (cx - 0.5, cz - 0.5, cx + 0.5, cz + 0.5);
+                let p = |x: f32, z: f32, h: f32| [(x - z) * COS_30, (x + z) * SIN_30 - h];
+                IsometricBlock {
+                    cell: (column, row),
+                    top: [p(x0, z0, height), p(x1, z0, height), p(x1, z1, height), p(x0, z1, height)],
+                    left: [p(x0, z1, height), p(x1, z1, height), p(x1, z1, 0.0), p(x0, z1, 0.0)],
+                    right: [p(x1, z0, height), p(x1, z1, height), p(x1, z1, 0.0), p(x1, z0, 0.0)],
+                }
+            })
+            .collect()
+    }
+}
+
+/// Shrinks a grid of `columns` x `rows` values by an integer factor until neither side
+/// exceeds `max_side`, keeping the minimum of every block of cells
+///
+/// # Returns
+///
+/// The values, their columns and rows, and the factor
+pub fn downsample_min(values: &[f64], columns: usize, rows: usize, max_side: usize) -> (Vec<f64>, usize, usize, usize) {
+    let factor = columns.max(rows).div_ceil(max_side.max(1)).max(1);
+    let (new_columns, new_rows) = (columns.div_ceil(factor), rows.div_ceil(factor));
+    let mut result = vec![f64::INFINITY; new_columns * new_rows];
+    for row in 0..rows {
+        for column in 0..columns {
+            let cell = &mut result[(row / factor) * new_columns + column / factor];
+            *cell = cell.min(values[row * columns + column]);
+        }
+    }
+    (result, new_columns, new_rows, factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_are_ordered_back_to_front() {
+        let projection = IsometricProjection::new(2, 2, 0);
+        let blocks = projection.blocks(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(blocks.first().unwrap().cell, (0, 0));
+        assert_eq!(blocks.last().unwrap().cell, (1, 1));
+
+        // Turning by half a revolution puts the far corner in front
+        let turned = IsometricProjection::new(2, 2, 2).blocks(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(turned.first().unwrap().cell, (1, 1));
+        // Raising a block moves its top up the screen
+        assert!(blocks[0].top[0][1] < projection.project(0.0, 0.0, 0.0)[1]);
+    }
+
+    #[test]
+    fn test_downsample_keeps_block_minimum() {
+        let values = [4.0, 3.0, 9.0, 2.0, 1.0, 8.0];
+        let (result, columns, rows, factor) = downsample_min(&values, 3, 2, 2);
+        assert_eq!((columns, rows, factor), (2, 1, 2));
+        assert_eq!(result, vec![1.0, 8.0]);
+    }
+}
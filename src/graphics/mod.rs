@@ -1,6 +1,11 @@
+pub mod compute;
+pub mod export;
+pub mod height_field;
+pub mod isometric;
 pub mod pipeline;
+pub mod raster;
 pub mod renderer;
 
 // Re-exports
 pub use pipeline::Pipeline;
-pub use renderer::Renderer;
\ No newline at end of file
+pub use renderer::Renderer;
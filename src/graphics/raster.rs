@@ -0,0 +1,238 @@
+use image::{Rgba, RgbaImage};
+
+pub const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+pub const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+pub const GRAY: Rgba<u8> = Rgba([128, 128, 128, 255]);
+pub const DARK_GRAY: Rgba<u8> = Rgba([96, 96, 96, 255]);
+
+/// A CPU-side RGBA image with simple drawing primitives, used to render
+/// simulation states for file export without going through egui.
+pub struct Canvas {
+    image: RgbaImage,
+}
+
+impl Canvas {
+    /// Creates a canvas filled with `background`
+    pub fn new(width: u32, height: u32, background: Rgba<u8>) -> Self {
+        Canvas {
+            image: RgbaImage::from_pixel(width.max(1), height.max(1), background),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    /// Fills the axis-aligned rectangle with top-left corner (x, y), clipped to the canvas
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Rgba<u8>) {
+        let x0 = x.max(0.0).round() as u32;
+        let y0 = y.max(0.0).round() as u32;
+        let x1 = ((x + width).round().max(0.0) as u32).min(self.width());
+        let y1 = ((y + height).round().max(0.0) as u32).min(self.height());
+        for py in y0..y1 {
+            for px in x0..x1 {
+                self.image.put_pixel(px, py, color);
+            }
+        }
+    }
+
+    /// Draws a rectangle outline of the given thickness inside the rectangle
+    pub fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32, thickness: f32, color: Rgba<u8>) {
+        self.fill_rect(x, y, width, thickness, color);
+        self.fill_rect(x, y + height - thickness, width, thickness, color);
+        self.fill_rect(x, y, thickness, height, color);
+        self.fill_rect(x + width - thickness, y, thickness, height, color);
+    }
+
+    /// Sets every pixel whose center satisfies `inside`, within the bounding box
+    fn fill_where(&mut self, min: (f32, f32), max: (f32, f32), inside: impl Fn(f32, f32) -> bool, color: Rgba<u8>) {
+        let x0 = min.0.floor().max(0.0) as u32;
+        let y0 = min.1.floor().max(0.0) as u32;
+        let x1 = (max.0.ceil().max(0.0) as u32).min(self.width());
+        let y1 = (max.1.ceil().max(0.0) as u32).min(self.height());
+        for py in y0..y1 {
+            for px in x0..x1 {
+                if inside(px as f32 + 0.5, py as f32 + 0.5) {
+                    self.image.put_pixel(px, py, color);
+                }
+            }
+        }
+    }
+
+    /// Fills a disc centered at (cx, cy)
+    pub fn fill_circle(&mut self, cx: f32, cy: f32, radius: f32, color: Rgba<u8>) {
+        let r2 = radius * radius;
+        self.fill_where(
+            (cx - radius, cy - radius),
+            (cx + radius, cy + radius),
+            |x, y| (x - cx).powi(2) + (y - cy).powi(2) <= r2,
+            color,
+        );
+    }
+
+    /// Draws a circle outline of the given thickness inside the radius
+    pub fn stroke_circle(&mut self, cx: f32, cy: f32, radius: f32, thickness: f32, color: Rgba<u8>) {
+        let outer = radius * radius;
+        let inner = (radius - thickness).max(0.0).powi(2);
+        self.fill_where(
+            (cx - radius, cy - radius),
+            (cx + radius, cy + radius),
+            |x, y| {
+                let d2 = (x - cx).powi(2) + (y - cy).powi(2);
+                d2 <= outer && d2 >= inner
+            },
+            color,
+        );
+    }
+
+    /// Draws a line segment of the given thickness
+    pub fn draw_line(&mut self, from: (f32, f32), to: (f32, f32), thickness: f32, color: Rgba<u8>) {
+        let half = (thickness * 0.5).max(0.5);
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length2 = dx * dx + dy * dy;
+        self.fill_where(
+            (from.0.min(to.0) - half, from.1.min(to.1) - half),
+            (from.0.max(to.0) + half, from.1.max(to.1) + half),
+            |x, y| {
+                // Distance from the pixel center to the closest point of the segment
+                let t = if length2 > 0.0 {
+                    (((x - from.0) * dx + (y - from.1) * dy) / length2).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let (px, py) = (from.0 + t * dx, from.1 + t * dy);
+                (x - px).powi(2) + (y - py).powi(2) <= half * half
+            },
+            color,
+        );
+    }
+
+    pub fn into_image(self) -> RgbaImage {
+        self.image
+    }
+}
+
+/// Renders a bipolar state as a grid of `scale`-pixel cells, black for +1 and white
+/// for -1, matching the grid widget used in the UI
+pub fn render_bipolar_grid(state: &[f64], width: usize, height: usize, scale: u32) -> RgbaImage {
+    let cell = scale.max(1) as f32;
+    let mut canvas = Canvas::new(width as u32 * scale.max(1), height as u32 * scale.max(1), WHITE);
+    for y in 0..height {
+        for x in 0..width {
+            let value = state.get(y * width + x).copied().unwrap_or(0.0);
+            let color = if value == 1.0 {
+                BLACK
+            } else if value == -1.0 {
+                WHITE
+            } else {
+                GRAY
+            };
+            canvas.fill_rect(x as f32 * cell, y as f32 * cell, cell, cell, color);
+            if scale >= 4 {
+                canvas.stroke_rect(x as f32 * cell, y as f32 * cell, cell, cell, 1.0, DARK_GRAY);
+            }
+        }
+    }
+    canvas.into_image()
+}
+
+/// Renders a matrix as a heatmap of `scale`-pixel cells on a diverging scale: blue for
+/// negative, white for zero and red for positive values, relative to the largest magnitude
+pub fn render_heatmap(values: &[Vec<f64>], scale: u32) -> RgbaImage {
+    let max_abs = values.iter().flatten().fold(0.0f64, |m, v| m.max(v.abs()));
+    render_value_grid(values, scale, |value| {
+        let t = if max_abs > 0.0 { (value / max_abs).clamp(-1.0, 1.0) } else { 0.0 };
+        let fade = (255.0 * (1.0 - t.abs())).round() as u8;
+        if t >= 0.0 {
+            Rgba([255, fade, fade, 255])
+        } else {
+            Rgba([fade, fade, 255, 255])
+        }
+    })
+}
+
+/// Renders a matrix as `scale`-pixel cells filled with `color(value)`, e.g. a colormap
+/// chosen in the UI
+pub fn render_value_grid(values: &[Vec<f64>], scale: u32, color: impl Fn(f64) -> Rgba<u8>) -> RgbaImage {
+    let rows = values.len();
+    let cols = values.iter().map(|row| row.len()).max().unwrap_or(0);
+    let cell = scale.max(1) as f32;
+    let mut canvas = Canvas::new(cols as u32 * scale.max(1), rows as u32 * scale.max(1), WHITE);
+    for (y, row) in values.iter().enumerate() {
+        for (x, &value) in row.iter().enumerate() {
+            canvas.fill_rect(x as f32 * cell, y as f32 * cell, cell, cell, color(value));
+        }
+    }
+    canvas.into_image()
+}
+
+/// Renders `points` as a black polyline inside a framed plot area of `width` x `height`
+/// pixels, with both axes scaled to the data range
+pub fn render_line_plot(points: &[[f64; 2]], width: u32, height: u32) -> RgbaImage {
+    let mut canvas = Canvas::new(width, height, WHITE);
+    let margin = 10.0;
+    let (w, h) = (canvas.width() as f32, canvas.height() as f32);
+    canvas.stroke_rect(margin, margin, w - 2.0 * margin, h - 2.0 * margin, 1.0, DARK_GRAY);
+
+    let bounds = points.iter().fold(
+        (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY),
+        |(x0, x1, y0, y1), p| (x0.min(p[0]), x1.max(p[0]), y0.min(p[1]), y1.max(p[1])),
+    );
+    let (x_min, x_max, y_min, y_max) = bounds;
+    let span = |min: f64, max: f64| if max > min { max - min } else { 1.0 };
+    let (x_span, y_span) = (span(x_min, x_max), span(y_min, y_max));
+    let inner = (w - 4.0 * margin, h - 4.0 * margin);
+    let to_pixel = |p: &[f64; 2]| {
+        (
+            2.0 * margin + ((p[0] - x_min) / x_span) as f32 * inner.0,
+            h - 2.0 * margin - ((p[1] - y_min) / y_span) as f32 * inner.1,
+        )
+    };
+
+    for pair in points.windows(2) {
+        canvas.draw_line(to_pixel(&pair[0]), to_pixel(&pair[1]), 2.0, BLACK);
+    }
+    if let [single] = points {
+        let (x, y) = to_pixel(single);
+        canvas.fill_circle(x, y, 2.0, BLACK);
+    }
+    canvas.into_image()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bipolar_grid_colors_cells() {
+        let image = render_bipolar_grid(&[1.0, -1.0, -1.0, 1.0], 2, 2, 3);
+        assert_eq!(image.dimensions(), (6, 6));
+        assert_eq!(*image.get_pixel(1, 1), BLACK);
+        assert_eq!(*image.get_pixel(4, 1), WHITE);
+        assert_eq!(*image.get_pixel(4, 4), BLACK);
+    }
+
+    #[test]
+    fn test_heatmap_is_diverging() {
+        let image = render_heatmap(&[vec![2.0, -2.0], vec![0.0, 1.0]], 1);
+        assert_eq!(*image.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(1, 0), Rgba([0, 0, 255, 255]));
+        assert_eq!(*image.get_pixel(0, 1), WHITE);
+        assert_eq!(*image.get_pixel(1, 1), Rgba([255, 128, 128, 255]));
+    }
+
+    #[test]
+    fn test_shapes_are_clipped() {
+        let mut canvas = Canvas::new(10, 10, WHITE);
+        canvas.fill_circle(0.0, 0.0, 4.0, BLACK);
+        canvas.draw_line((-5.0, 9.5), (20.0, 9.5), 1.0, BLACK);
+        let image = canvas.into_image();
+        assert_eq!(*image.get_pixel(0, 0), BLACK);
+        assert_eq!(*image.get_pixel(9, 0), WHITE);
+        assert_eq!(*image.get_pixel(5, 9), BLACK);
+    }
+}
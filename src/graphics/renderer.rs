@@ -1,4 +1,5 @@
 use std::error::Error;
+use std::fmt;
 use eframe::wgpu;
 
 /// Basic renderer trait
@@ -20,4 +21,276 @@ pub trait Renderer {
     
     /// Resizes the renderer's resources
     fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32);
-}
\ No newline at end of file
+}
+
+/// Error types for renderers
+#[derive(Debug)]
+pub enum RenderError {
+    /// `initialize` has not been called yet
+    NotInitialized,
+    DimensionMismatch(String),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::NotInitialized => write!(f, "Renderer not initialized"),
+            RenderError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+/// Format of the state textures: one RGBA byte quadruple per cell
+pub const STATE_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// Draws a sampled texture over the whole viewport with one triangle
+const TEXTURE_SHADER: &str = r#"
+@group(0) @binding(0) var state: texture_2d<f32>;
+@group(0) @binding(1) var state_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(state, state_sampler, in.uv);
+}
+"#;
+
+/// A pipeline drawing a texture bound with `texture_bind_group` over the whole
+/// viewport, into views of `target_format`
+pub(crate) fn texture_pipeline(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> wgpu::RenderPipeline {
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("texture"),
+        source: wgpu::ShaderSource::Wgsl(TEXTURE_SHADER.into()),
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("texture"),
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: &module,
+            entry_point: "vs_main",
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        fragment: Some(wgpu::FragmentState {
+            module: &module,
+            entry_point: "fs_main",
+            compilation_options: Default::default(),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: target_format,
+                blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        multiview: None,
+    })
+}
+
+/// A sampler that draws cells as sharp blocks
+pub(crate) fn nearest_sampler(device: &wgpu::Device) -> wgpu::Sampler {
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("nearest"),
+        mag_filter: wgpu::FilterMode::Nearest,
+        min_filter: wgpu::FilterMode::Nearest,
+        ..Default::default()
+    })
+}
+
+/// Binds `view` for drawing with a pipeline from `texture_pipeline`
+pub(crate) fn texture_bind_group(
+    device: &wgpu::Device,
+    pipeline: &wgpu::RenderPipeline,
+    view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("texture"),
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(view) },
+            wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(sampler) },
+        ],
+    })
+}
+
+/// One of the two state textures and the bind group sampling it
+struct StateTexture {
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Renderer for per-frame simulation state uploaded as an RGBA image.
+///
+/// The state lives in two textures. `update` writes the new frame into the back
+/// texture and then swaps, so the upload never targets the texture that frames
+/// already submitted are still sampling, and animating a large grid does not stall
+/// the queue. The textures are also storage textures, so compute kernels can write
+/// to them directly.
+pub struct StateTextureRenderer {
+    /// Format of the views drawn into
+    target_format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+
+    pipeline: Option<wgpu::RenderPipeline>,
+    sampler: Option<wgpu::Sampler>,
+    /// Empty until initialized, then the two textures
+    textures: Vec<StateTexture>,
+    /// Index of the texture that is drawn
+    front: usize,
+}
+
+impl StateTextureRenderer {
+    pub fn new(target_format: wgpu::TextureFormat, width: u32, height: u32) -> Self {
+        StateTextureRenderer {
+            target_format,
+            width: width.max(1),
+            height: height.max(1),
+            pipeline: None,
+            sampler: None,
+            textures: Vec::new(),
+            front: 0,
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.pipeline.is_some()
+    }
+
+    /// The texture currently drawn, for kernels writing the state on the device
+    pub fn front_texture(&self) -> Option<&wgpu::Texture> {
+        self.textures.get(self.front).map(|t| &t.texture)
+    }
+
+    /// Draws the front texture into `render_pass`, e.g. from an egui paint callback,
+    /// covering its viewport
+    pub fn paint<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        let (Some(pipeline), Some(front)) = (&self.pipeline, self.textures.get(self.front)) else {
+            return;
+        };
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &front.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn create_textures(&mut self, device: &wgpu::Device) {
+        let (Some(pipeline), Some(sampler)) = (&self.pipeline, &self.sampler) else {
+            return;
+        };
+        self.textures = (0..2)
+            .map(|_| {
+                let texture = device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("state_texture"),
+                    size: wgpu::Extent3d { width: self.width, height: self.height, depth_or_array_layers: 1 },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: STATE_TEXTURE_FORMAT,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING
+                        | wgpu::TextureUsages::COPY_DST
+                        | wgpu::TextureUsages::STORAGE_BINDING,
+                    view_formats: &[],
+                });
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                let bind_group = texture_bind_group(device, pipeline, &view, sampler);
+                StateTexture { texture, bind_group }
+            })
+            .collect();
+        self.front = 0;
+    }
+}
+
+impl Renderer for StateTextureRenderer {
+    fn initialize(&mut self, device: &wgpu::Device, _queue: &wgpu::Queue) -> Result<(), Box<dyn Error>> {
+        self.pipeline = Some(texture_pipeline(device, self.target_format));
+        self.sampler = Some(nearest_sampler(device));
+        self.create_textures(device);
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("state_texture"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        self.paint(&mut render_pass);
+    }
+
+    /// Uploads `data`, `width * height` RGBA pixels row by row, into the back texture
+    /// and makes it the front
+    fn update(&mut self, queue: &wgpu::Queue, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        if self.textures.is_empty() {
+            return Err(Box::new(RenderError::NotInitialized));
+        }
+        let expected = (self.width * self.height * 4) as usize;
+        if data.len() != expected {
+            return Err(Box::new(RenderError::DimensionMismatch(format!(
+                "{} bytes given for a {}x{} texture of {} bytes",
+                data.len(),
+                self.width,
+                self.height,
+                expected
+            ))));
+        }
+        let back = 1 - self.front;
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.textures[back].texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            data,
+            wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(self.width * 4), rows_per_image: Some(self.height) },
+            wgpu::Extent3d { width: self.width, height: self.height, depth_or_array_layers: 1 },
+        );
+        self.front = back;
+        Ok(())
+    }
+
+    /// Recreates both textures at the new size; their contents are lost until the
+    /// next `update`
+    fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height) == (self.width, self.height) && !self.textures.is_empty() {
+            return;
+        }
+        self.width = width;
+        self.height = height;
+        self.create_textures(device);
+    }
+}
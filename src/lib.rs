@@ -1,9 +1,16 @@
 // Raum - Spatial Processing System
 
 // Module declarations
+pub mod audio;
+pub mod core;
+pub mod error;
 pub mod neural;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod scripting;
 pub mod graphics;
 pub mod ui;
 
 // Re-exports for convenience
+pub use error::RaumError;
 pub use neural::hopfield::HopfieldNetwork;
\ No newline at end of file
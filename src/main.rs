@@ -1,10 +1,13 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 use eframe::{self, egui};
+use raum::core::assets;
+use raum::ui::plugins::PluginRegistry;
 use raum::ui::RaumApp;
 
-fn load_icon(path: &str) -> Result<egui::IconData, Box<dyn std::error::Error>> {
-    let image = image::open(path)?.to_rgba8();
+fn load_icon() -> Result<egui::IconData, Box<dyn std::error::Error>> {
+    let (data, _) = assets::ICON.load();
+    let image = image::load_from_memory(&data)?.to_rgba8();
     let (width, height) = image.dimensions();
     Ok(egui::IconData {
         rgba: image.into_raw(),
@@ -17,8 +20,46 @@ fn main() -> Result<(), eframe::Error> {
     // Initialize logger
     env_logger::init();
 
-    let icon = load_icon("assets/icon.png")
-        .expect("Failed to load application icon from assets/icon.png");
+    // Optional asset directory: `raum --assets <dir>`
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(dir) = args.iter().position(|arg| arg == "--assets").and_then(|i| args.get(i + 1)) {
+        assets::set_user_dir(Some(dir.into()));
+    }
+
+    // Optional remote control server: `raum --remote 127.0.0.1:8080`
+    #[cfg(feature = "remote")]
+    let _remote = args.iter().position(|arg| arg == "--remote").and_then(|i| args.get(i + 1)).and_then(|address| {
+        match raum::remote::RemoteServer::start(address.as_str()) {
+            Ok(server) => {
+                println!("Remote control server listening on {}", server.address());
+                Some(server)
+            },
+            Err(e) => {
+                eprintln!("Failed to start remote control server: {}", e);
+                None
+            },
+        }
+    });
+
+    // Plugin libraries: `raum --plugins <dir>`, or the "plugins" directory beside the executable
+    #[allow(unused_mut)]
+    let mut plugins = PluginRegistry::new();
+    #[cfg(feature = "dylib-plugins")]
+    {
+        let dir = match args.iter().position(|arg| arg == "--plugins").and_then(|i| args.get(i + 1)) {
+            Some(dir) => Some(std::path::PathBuf::from(dir)),
+            None => std::env::current_exe().ok().and_then(|exe| exe.parent().map(|dir| dir.join("plugins"))),
+        };
+        if let Some(dir) = dir {
+            let loaded = plugins.load_directory(&dir);
+            if loaded > 0 {
+                println!("Loaded {} plugins from {}", loaded, dir.display());
+            }
+        }
+    }
+
+    let icon = load_icon()
+        .expect("Failed to load application icon");
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -30,6 +71,6 @@ fn main() -> Result<(), eframe::Error> {
     eframe::run_native(
         "Raum",
         options,
-        Box::new(|cc| Ok(Box::new(RaumApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(RaumApp::with_plugins(cc, plugins)))),
     )
 }
\ No newline at end of file
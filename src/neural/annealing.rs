@@ -0,0 +1,73 @@
+/// How the inverse temperature β moves from its start to its end value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScheduleKind {
+    /// β changes by the same amount at every stage
+    Linear,
+    /// β changes by the same factor at every stage (T falls exponentially)
+    Geometric,
+}
+
+/// Annealing schedule for stochastic networks, given in inverse temperature β = 1/T.
+///
+/// The dynamics are run for `sweeps_per_stage` sweeps at each of `stages` values of β
+/// going from `start_beta` (hot) to `end_beta` (cold).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnnealingSchedule {
+    pub start_beta: f64,
+    pub end_beta: f64,
+    pub stages: usize,
+    pub sweeps_per_stage: usize,
+    pub kind: ScheduleKind,
+}
+
+impl AnnealingSchedule {
+    /// A schedule that stays at a single β for `sweeps` sweeps
+    pub fn constant(beta: f64, sweeps: usize) -> Self {
+        AnnealingSchedule {
+            start_beta: beta,
+            end_beta: beta,
+            stages: 1,
+            sweeps_per_stage: sweeps,
+            kind: ScheduleKind::Linear,
+        }
+    }
+
+    /// The β value used at each stage
+    pub fn betas(&self) -> Vec<f64> {
+        let stages = self.stages.max(1);
+        if stages == 1 {
+            return vec![self.end_beta];
+        }
+
+        (0..stages)
+            .map(|k| {
+                let t = k as f64 / (stages - 1) as f64;
+                match self.kind {
+                    ScheduleKind::Linear => self.start_beta + t * (self.end_beta - self.start_beta),
+                    ScheduleKind::Geometric if self.start_beta > 0.0 && self.end_beta > 0.0 => {
+                        self.start_beta * (self.end_beta / self.start_beta).powf(t)
+                    },
+                    // A geometric schedule needs positive endpoints; fall back to linear
+                    ScheduleKind::Geometric => self.start_beta + t * (self.end_beta - self.start_beta),
+                }
+            })
+            .collect()
+    }
+
+    /// Total number of sweeps over the whole schedule
+    pub fn total_sweeps(&self) -> usize {
+        self.stages.max(1) * self.sweeps_per_stage
+    }
+}
+
+impl Default for AnnealingSchedule {
+    fn default() -> Self {
+        AnnealingSchedule {
+            start_beta: 0.1,
+            end_beta: 2.0,
+            stages: 10,
+            sweeps_per_stage: 2,
+            kind: ScheduleKind::Geometric,
+        }
+    }
+}
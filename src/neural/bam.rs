@@ -0,0 +1,258 @@
+use std::error::Error;
+use std::fmt;
+use rand::Rng;
+
+use super::AssociativeMemory;
+
+/// Error types for bidirectional associative memories
+#[derive(Debug)]
+pub enum BamError {
+    DimensionMismatch(String),
+    InvalidStateValue(String),
+    InvalidSize(String),
+}
+
+impl fmt::Display for BamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BamError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+            BamError::InvalidStateValue(msg) => write!(f, "Invalid state value: {}", msg),
+            BamError::InvalidSize(msg) => write!(f, "Invalid size: {}", msg),
+        }
+    }
+}
+
+impl Error for BamError {}
+
+/// Direction of a recall: which layer is given as the cue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecallDirection {
+    /// Cue the X layer and read the associated Y pattern
+    Forward,
+    /// Cue the Y layer and read the associated X pattern
+    Backward,
+}
+
+/// Result of a bidirectional recall
+#[derive(Debug, Clone, PartialEq)]
+pub struct BamRecall {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Energy after the first step from the cue and after every pass
+    pub energies: Vec<f64>,
+    /// Number of full passes X → Y → X until the layers agreed
+    pub iterations: usize,
+}
+
+/// A bidirectional associative memory (Kosko 1988): a bipartite Hopfield network that
+/// stores pairs (x, y) of ±1 patterns of possibly different sizes and recalls either
+/// one from the other.
+///
+/// Pairs are stored with the correlation rule W = Σ_p y^p (x^p)ᵀ. Recall alternates
+/// y = sgn(W x) and x = sgn(Wᵀ y), a unit keeping its value when its input is zero,
+/// which never increases the energy E = -yᵀ W x.
+#[derive(Debug, Clone)]
+pub struct BidirectionalMemory {
+    x_size: usize,
+    y_size: usize,
+
+    /// y_size rows of x_size weights
+    weights: Vec<Vec<f64>>,
+}
+
+impl BidirectionalMemory {
+    /// Creates a memory with zero weights
+    ///
+    /// # Arguments
+    ///
+    /// * `x_size` - Number of units in the X layer. Must be greater than 0.
+    /// * `y_size` - Number of units in the Y layer. Must be greater than 0.
+    pub fn try_new(x_size: usize, y_size: usize) -> Result<Self, BamError> {
+        if x_size == 0 || y_size == 0 {
+            return Err(BamError::InvalidSize(format!("both layers need units, got {} and {}", x_size, y_size)));
+        }
+        Ok(BidirectionalMemory { x_size, y_size, weights: vec![vec![0.0; x_size]; y_size] })
+    }
+
+    pub fn x_size(&self) -> usize {
+        self.x_size
+    }
+
+    pub fn y_size(&self) -> usize {
+        self.y_size
+    }
+
+    pub fn weights(&self) -> &[Vec<f64>] {
+        &self.weights
+    }
+
+    fn validate(&self, state: &[f64], size: usize, layer: &str) -> Result<(), BamError> {
+        if state.len() != size {
+            return Err(BamError::DimensionMismatch(format!(
+                "{} layer has {} units, got a state of length {}",
+                layer,
+                size,
+                state.len()
+            )));
+        }
+        if state.iter().any(|&s| s != 1.0 && s != -1.0) {
+            return Err(BamError::InvalidStateValue(format!("{} layer states must be ±1", layer)));
+        }
+        Ok(())
+    }
+
+    /// Adds the pairs (x, y) to the stored associations
+    pub fn train(&mut self, pairs: &[(Vec<f64>, Vec<f64>)]) -> Result<(), BamError> {
+        for (x, y) in pairs {
+            self.validate(x, self.x_size, "X")?;
+            self.validate(y, self.y_size, "Y")?;
+        }
+        for (x, y) in pairs {
+            for (row, &yi) in self.weights.iter_mut().zip(y) {
+                for (w, &xj) in row.iter_mut().zip(x) {
+                    *w += yi * xj;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The Y layer driven by `x`, starting from `previous`
+    fn forward_from(&self, x: &[f64], previous: &[f64]) -> Vec<f64> {
+        self.weights
+            .iter()
+            .zip(previous)
+            .map(|(row, &old)| {
+                let input: f64 = row.iter().zip(x).map(|(w, xj)| w * xj).sum();
+                if input > 0.0 { 1.0 } else if input < 0.0 { -1.0 } else { old }
+            })
+            .collect()
+    }
+
+    /// The X layer driven by `y`, starting from `previous`
+    fn backward_from(&self, y: &[f64], previous: &[f64]) -> Vec<f64> {
+        (0..self.x_size)
+            .map(|j| {
+                let input: f64 = self.weights.iter().zip(y).map(|(row, yi)| row[j] * yi).sum();
+                if input > 0.0 { 1.0 } else if input < 0.0 { -1.0 } else { previous[j] }
+            })
+            .collect()
+    }
+
+    /// The Y pattern associated with `x` in one step; units without input are -1
+    pub fn forward(&self, x: &[f64]) -> Result<Vec<f64>, BamError> {
+        self.validate(x, self.x_size, "X")?;
+        Ok(self.forward_from(x, &vec![-1.0; self.y_size]))
+    }
+
+    /// The X pattern associated with `y` in one step; units without input are -1
+    pub fn backward(&self, y: &[f64]) -> Result<Vec<f64>, BamError> {
+        self.validate(y, self.y_size, "Y")?;
+        Ok(self.backward_from(y, &vec![-1.0; self.x_size]))
+    }
+
+    /// Energy E = -yᵀ W x of a pair of layer states
+    pub fn energy(&self, x: &[f64], y: &[f64]) -> Result<f64, BamError> {
+        self.validate(x, self.x_size, "X")?;
+        self.validate(y, self.y_size, "Y")?;
+        Ok(-self
+            .weights
+            .iter()
+            .zip(y)
+            .map(|(row, yi)| yi * row.iter().zip(x).map(|(w, xj)| w * xj).sum::<f64>())
+            .sum::<f64>())
+    }
+
+    /// Recalls the pair closest to `cue`, the X pattern for `Forward` and the Y pattern
+    /// for `Backward`, by updating the layers in turn until neither changes or
+    /// `max_iterations` passes were made
+    pub fn recall(&self, cue: &[f64], direction: RecallDirection, max_iterations: usize) -> Result<BamRecall, BamError> {
+        let (mut x, mut y) = match direction {
+            RecallDirection::Forward => {
+                let x = cue.to_vec();
+                let y = self.forward(&x)?;
+                (x, y)
+            },
+            RecallDirection::Backward => {
+                let y = cue.to_vec();
+                let x = self.backward(&y)?;
+                (x, y)
+            },
+        };
+
+        let mut energies = vec![self.energy(&x, &y)?];
+        let mut iterations = 0;
+        while iterations < max_iterations {
+            iterations += 1;
+            let (next_x, next_y) = match direction {
+                RecallDirection::Forward => {
+                    let next_x = self.backward_from(&y, &x);
+                    let next_y = self.forward_from(&next_x, &y);
+                    (next_x, next_y)
+                },
+                RecallDirection::Backward => {
+                    let next_y = self.forward_from(&x, &y);
+                    let next_x = self.backward_from(&next_y, &x);
+                    (next_x, next_y)
+                },
+            };
+            let settled = next_x == x && next_y == y;
+            x = next_x;
+            y = next_y;
+            energies.push(self.energy(&x, &y)?);
+            if settled {
+                break;
+            }
+        }
+        Ok(BamRecall { x, y, energies, iterations })
+    }
+}
+
+impl AssociativeMemory for BidirectionalMemory {
+    type Pattern = (Vec<f64>, Vec<f64>);
+    type Error = BamError;
+
+    fn size(&self) -> usize {
+        self.x_size + self.y_size
+    }
+
+    fn store(&mut self, patterns: &[Self::Pattern]) -> Result<(), Self::Error> {
+        self.train(patterns)
+    }
+
+    // Recalls from the X half of the cue; the Y half is ignored
+    fn recall(&self, cue: &Self::Pattern, _rng: &mut impl Rng) -> Result<Self::Pattern, Self::Error> {
+        let recall = BidirectionalMemory::recall(self, &cue.0, RecallDirection::Forward, 100)?;
+        Ok((recall.x, recall.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recalls_pairs_in_both_directions() {
+        let pairs = vec![
+            (vec![1.0, 1.0, 1.0, -1.0, -1.0, -1.0], vec![1.0, -1.0, 1.0, -1.0]),
+            (vec![1.0, -1.0, 1.0, -1.0, 1.0, -1.0], vec![1.0, 1.0, -1.0, -1.0]),
+        ];
+        let mut bam = BidirectionalMemory::try_new(6, 4).unwrap();
+        bam.train(&pairs).unwrap();
+
+        for (x, y) in &pairs {
+            assert_eq!(&bam.forward(x).unwrap(), y);
+            assert_eq!(&bam.backward(y).unwrap(), x);
+        }
+
+        // One flipped bit of the first X pattern is corrected on the way back
+        let mut cue = pairs[0].0.clone();
+        cue[0] = -1.0;
+        let recall = bam.recall(&cue, RecallDirection::Forward, 10).unwrap();
+        assert_eq!((&recall.x, &recall.y), (&pairs[0].0, &pairs[0].1));
+        assert!(recall.energies.windows(2).all(|w| w[1] <= w[0]));
+
+        assert!(bam.forward(&[1.0; 4]).is_err());
+        assert!(BidirectionalMemory::try_new(0, 3).is_err());
+    }
+}
@@ -0,0 +1,389 @@
+use std::error::Error;
+use std::fmt;
+use rand::Rng;
+
+use super::annealing::AnnealingSchedule;
+use super::{AssociativeMemory, TrainableParameters};
+use crate::core::safetensors::{SafeTensors, Tensor};
+use crate::error::RaumError;
+
+/// Error types for Boltzmann machines
+#[derive(Debug)]
+pub enum BoltzmannError {
+    DimensionMismatch(String),
+    InvalidStateValue(String),
+}
+
+impl fmt::Display for BoltzmannError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoltzmannError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+            BoltzmannError::InvalidStateValue(msg) => write!(f, "Invalid state value: {}", msg),
+        }
+    }
+}
+
+impl Error for BoltzmannError {}
+
+/// A general (fully connected) Boltzmann machine with visible and hidden units.
+///
+/// Units are bipolar (±1.0) like Hopfield neurons and every pair of units may be connected,
+/// including hidden-hidden and visible-visible pairs. Units 0..num_visible are visible,
+/// the rest hidden. Training follows Ackley, Hinton & Sejnowski (1985):
+/// Δw_ij = η (<s_i s_j>_clamped - <s_i s_j>_free), with both correlations
+/// measured after simulated annealing.
+#[derive(Debug, Clone)]
+pub struct BoltzmannMachine {
+    pub num_visible: usize,
+    pub num_hidden: usize,
+
+    /// Symmetric weight matrix over all units; W_ii = 0
+    weights: Vec<Vec<f64>>,
+
+    /// Bias of each unit
+    biases: Vec<f64>,
+
+    /// Learning rate η
+    pub learning_rate: f64,
+
+    /// Annealing schedule used to reach equilibrium in both training phases
+    pub schedule: AnnealingSchedule,
+
+    /// Sweeps at the final temperature over which correlations are averaged
+    pub statistics_sweeps: usize,
+}
+
+impl BoltzmannMachine {
+    /// Creates a machine with small random weights
+    ///
+    /// # Arguments
+    ///
+    /// * `num_visible` - Number of visible units (the pattern size). Must be greater than 0.
+    /// * `num_hidden` - Number of hidden units (may be 0)
+    pub fn new(num_visible: usize, num_hidden: usize, rng: &mut impl Rng) -> Result<Self, BoltzmannError> {
+        if num_visible == 0 {
+            return Err(BoltzmannError::DimensionMismatch(
+                "A Boltzmann machine needs at least one visible unit".to_string()
+            ));
+        }
+
+        let n = num_visible + num_hidden;
+        let mut weights = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let w = 0.01 * (2.0 * rng.gen::<f64>() - 1.0);
+                weights[i][j] = w;
+                weights[j][i] = w;
+            }
+        }
+
+        Ok(BoltzmannMachine {
+            num_visible,
+            num_hidden,
+            weights,
+            biases: vec![0.0; n],
+            learning_rate: 0.1,
+            schedule: AnnealingSchedule::default(),
+            statistics_sweeps: 10,
+        })
+    }
+
+    /// Total number of units
+    pub fn num_units(&self) -> usize {
+        self.num_visible + self.num_hidden
+    }
+
+    /// Weight between units `i` and `j`
+    pub fn weight(&self, i: usize, j: usize) -> f64 {
+        self.weights[i][j]
+    }
+
+    /// Bias of unit `i`
+    pub fn bias(&self, i: usize) -> f64 {
+        self.biases[i]
+    }
+
+    fn validate_visible(&self, visible: &[f64]) -> Result<(), BoltzmannError> {
+        if visible.len() != self.num_visible {
+            return Err(BoltzmannError::DimensionMismatch(format!(
+                "Visible state has length {} but expected {}",
+                visible.len(), self.num_visible
+            )));
+        }
+        if let Some(&val) = visible.iter().find(|&&v| v != 1.0 && v != -1.0) {
+            return Err(BoltzmannError::InvalidStateValue(format!(
+                "State contains value {} which is not +1.0 or -1.0",
+                val
+            )));
+        }
+        Ok(())
+    }
+
+    /// Energy E = -1/2 Σ_ij W_ij s_i s_j - Σ_i b_i s_i of a full state
+    pub fn energy(&self, state: &[f64]) -> f64 {
+        let mut energy = 0.0;
+        for (i, row) in self.weights.iter().enumerate() {
+            let field: f64 = row.iter().zip(state).map(|(w, s)| w * s).sum();
+            energy -= 0.5 * state[i] * field + self.biases[i] * state[i];
+        }
+        energy
+    }
+
+    /// Stochastically update unit `i`: P(s_i = +1) = 1 / (1 + exp(-2 β h_i))
+    fn update_unit(&self, state: &mut [f64], i: usize, beta: f64, rng: &mut impl Rng) {
+        let field: f64 = self.weights[i].iter().zip(state.iter()).map(|(w, s)| w * s).sum::<f64>() + self.biases[i];
+        let prob_plus_one = 1.0 / (1.0 + (-2.0 * beta * field).exp());
+        state[i] = if rng.gen::<f64>() < prob_plus_one { 1.0 } else { -1.0 };
+    }
+
+    /// One sweep of random single-unit updates over the units in `free` at inverse temperature `beta`
+    fn sweep(&self, state: &mut [f64], free: std::ops::Range<usize>, beta: f64, rng: &mut impl Rng) {
+        if free.is_empty() {
+            return;
+        }
+        for _ in 0..free.len() {
+            let i = rng.gen_range(free.clone());
+            self.update_unit(state, i, beta, rng);
+        }
+    }
+
+    /// Anneal the units in `free` along `schedule`, recording the state after each sweep
+    fn anneal(
+        &self,
+        state: &mut [f64],
+        free: std::ops::Range<usize>,
+        schedule: &AnnealingSchedule,
+        rng: &mut impl Rng,
+        mut record: impl FnMut(&[f64]),
+    ) {
+        for beta in schedule.betas() {
+            for _ in 0..schedule.sweeps_per_stage {
+                self.sweep(state, free.clone(), beta, rng);
+                record(state);
+            }
+        }
+    }
+
+    /// Average <s_i s_j> (and <s_i> on the diagonal) after annealing, with the
+    /// visible units clamped to `clamp` if given
+    fn correlations(&self, clamp: Option<&[f64]>, rng: &mut impl Rng) -> Vec<Vec<f64>> {
+        let n = self.num_units();
+        let mut state: Vec<f64> = (0..n).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect();
+        let free = match clamp {
+            Some(visible) => {
+                state[..self.num_visible].copy_from_slice(visible);
+                self.num_visible..n
+            },
+            None => 0..n,
+        };
+
+        self.anneal(&mut state, free.clone(), &self.schedule, rng, |_| {});
+
+        let mut stats = vec![vec![0.0; n]; n];
+        let final_beta = self.schedule.betas().last().copied().unwrap_or(1.0);
+        let samples = self.statistics_sweeps.max(1);
+        for _ in 0..samples {
+            self.sweep(&mut state, free.clone(), final_beta, rng);
+            for i in 0..n {
+                stats[i][i] += state[i];
+                for j in (i + 1)..n {
+                    stats[i][j] += state[i] * state[j];
+                }
+            }
+        }
+
+        let norm = 1.0 / samples as f64;
+        stats.iter_mut().flatten().for_each(|s| *s *= norm);
+        stats
+    }
+
+    /// Run one epoch of Boltzmann learning over `patterns`.
+    ///
+    /// # Returns
+    ///
+    /// Result with the mean absolute weight change of the epoch
+    pub fn train_epoch(&mut self, patterns: &[Vec<f64>], rng: &mut impl Rng) -> Result<f64, BoltzmannError> {
+        for pattern in patterns {
+            self.validate_visible(pattern)?;
+        }
+        if patterns.is_empty() {
+            return Ok(0.0);
+        }
+
+        let n = self.num_units();
+        let mut clamped = vec![vec![0.0; n]; n];
+        let mut free = vec![vec![0.0; n]; n];
+        for pattern in patterns {
+            let c = self.correlations(Some(pattern), rng);
+            let f = self.correlations(None, rng);
+            for i in 0..n {
+                for j in i..n {
+                    clamped[i][j] += c[i][j];
+                    free[i][j] += f[i][j];
+                }
+            }
+        }
+
+        let scale = self.learning_rate / patterns.len() as f64;
+        let mut total_change = 0.0;
+        for i in 0..n {
+            self.biases[i] += scale * (clamped[i][i] - free[i][i]);
+            for j in (i + 1)..n {
+                let dw = scale * (clamped[i][j] - free[i][j]);
+                self.weights[i][j] += dw;
+                self.weights[j][i] += dw;
+                total_change += dw.abs();
+            }
+        }
+
+        let num_pairs = (n * (n - 1) / 2).max(1);
+        Ok(total_change / num_pairs as f64)
+    }
+
+    /// Train for `epochs` epochs, returning the mean weight change of each epoch
+    pub fn train(&mut self, patterns: &[Vec<f64>], epochs: usize, rng: &mut impl Rng) -> Result<Vec<f64>, BoltzmannError> {
+        (0..epochs).map(|_| self.train_epoch(patterns, rng)).collect()
+    }
+
+    /// Recall from a visible cue: all units evolve freely along `schedule`, starting from
+    /// `input` on the visible units and random hidden units.
+    ///
+    /// # Returns
+    ///
+    /// Result with the visible state after each sweep, starting with `input`
+    pub fn recall(
+        &self,
+        input: &[f64],
+        schedule: &AnnealingSchedule,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<Vec<f64>>, BoltzmannError> {
+        self.validate_visible(input)?;
+
+        let n = self.num_units();
+        let mut state = input.to_vec();
+        state.extend((self.num_visible..n).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }));
+
+        let mut history = vec![input.to_vec()];
+        let num_visible = self.num_visible;
+        self.anneal(&mut state, 0..n, schedule, rng, |s| history.push(s[..num_visible].to_vec()));
+
+        Ok(history)
+    }
+}
+
+// Implement the AssociativeMemory trait for BoltzmannMachine
+impl TrainableParameters for BoltzmannMachine {
+    const MODEL: &'static str = "boltzmann";
+
+    fn parameters(&self) -> SafeTensors {
+        SafeTensors::new()
+            .with_tensor("weights", Tensor::matrix(&self.weights))
+            .with_tensor("biases", Tensor::vector(&self.biases))
+            .with_metadata("num_visible", self.num_visible)
+            .with_metadata("num_hidden", self.num_hidden)
+            .with_metadata("learning_rate", self.learning_rate)
+    }
+
+    /// Training settings other than the learning rate take their defaults
+    fn from_parameters(parameters: &SafeTensors) -> Result<Self, RaumError> {
+        let count = |key: &str| -> Result<usize, RaumError> {
+            parameters
+                .metadata
+                .get(key)
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| format!("The file has no valid '{}' entry", key).into())
+        };
+        let (num_visible, num_hidden) = (count("num_visible")?, count("num_hidden")?);
+        let n = num_visible + num_hidden;
+        let weights = parameters.tensor("weights")?.to_rows().unwrap_or_default();
+        let biases = parameters.tensor("biases")?.data.clone();
+        if num_visible == 0 || weights.len() != n || weights.iter().any(|row| row.len() != n) || biases.len() != n {
+            return Err(format!("Expected {} × {} weights and {} biases for {} visible and {} hidden units", n, n, n, num_visible, num_hidden).into());
+        }
+
+        Ok(BoltzmannMachine {
+            num_visible,
+            num_hidden,
+            weights,
+            biases,
+            learning_rate: parameters.metadata.get("learning_rate").and_then(|rate| rate.parse().ok()).unwrap_or(0.1),
+            schedule: AnnealingSchedule::default(),
+            statistics_sweeps: 10,
+        })
+    }
+}
+
+impl AssociativeMemory for BoltzmannMachine {
+    type Pattern = Vec<f64>;
+    type Error = BoltzmannError;
+
+    fn size(&self) -> usize {
+        self.num_visible
+    }
+
+    // One epoch of contrastive learning; store again to reinforce the patterns
+    fn store(&mut self, patterns: &[Self::Pattern]) -> Result<(), Self::Error> {
+        let mut rng = rand::thread_rng();
+        self.train_epoch(patterns, &mut rng)?;
+        Ok(())
+    }
+
+    // The visible units at the end of the machine's own annealing schedule
+    fn recall(&self, cue: &Self::Pattern, rng: &mut impl Rng) -> Result<Self::Pattern, Self::Error> {
+        let mut history = BoltzmannMachine::recall(self, cue, &self.schedule, rng)?;
+        Ok(history.pop().unwrap_or_else(|| cue.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_learns_single_pattern() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let pattern = vec![1.0, -1.0, 1.0, 1.0, -1.0, -1.0];
+        let mut machine = BoltzmannMachine::new(6, 2, &mut rng).unwrap();
+        machine.train(std::slice::from_ref(&pattern), 100, &mut rng).unwrap();
+
+        // Weights stay symmetric with an empty diagonal
+        for i in 0..machine.num_units() {
+            assert_eq!(machine.weight(i, i), 0.0);
+            for j in 0..machine.num_units() {
+                assert_eq!(machine.weight(i, j), machine.weight(j, i));
+            }
+        }
+
+        // Recall from a corrupted cue at low temperature
+        let mut cue = pattern.clone();
+        cue[0] = -cue[0];
+        let history = machine.recall(&cue, &AnnealingSchedule::constant(3.0, 10), &mut rng).unwrap();
+        let output = history.last().unwrap();
+        let overlap: f64 = output.iter().zip(&pattern).map(|(a, b)| a * b).sum::<f64>() / 6.0;
+        assert!(overlap > 0.6, "overlap {}", overlap);
+    }
+
+    #[test]
+    fn test_parameters_round_trip() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let machine = BoltzmannMachine::new(4, 2, &mut rng).unwrap();
+        let restored = BoltzmannMachine::from_safetensors(&machine.to_safetensors()).unwrap();
+        assert_eq!((restored.num_visible, restored.num_hidden), (4, 2));
+        assert_eq!(restored.weight(0, 5), machine.weight(0, 5));
+
+        let mut file = machine.to_safetensors();
+        file.metadata.insert("num_hidden".to_string(), "3".to_string());
+        assert!(BoltzmannMachine::from_safetensors(&file).is_err());
+    }
+
+    #[test]
+    fn test_schedule_betas() {
+        let schedule = AnnealingSchedule { start_beta: 0.5, end_beta: 2.0, stages: 3, ..Default::default() };
+        let betas = schedule.betas();
+        assert_eq!(betas.len(), 3);
+        assert!((betas[1] - 1.0).abs() < 1e-12); // geometric midpoint
+        assert_eq!(AnnealingSchedule::constant(1.5, 4).betas(), vec![1.5]);
+    }
+}
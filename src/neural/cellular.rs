@@ -0,0 +1,443 @@
+use std::error::Error;
+use std::fmt;
+use rand::Rng;
+
+use super::DynamicalSystem;
+
+/// Error types for cellular automata
+#[derive(Debug)]
+pub enum CellularError {
+    DimensionMismatch(String),
+    InvalidRule(String),
+}
+
+impl fmt::Display for CellularError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellularError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+            CellularError::InvalidRule(msg) => write!(f, "Invalid rule: {}", msg),
+        }
+    }
+}
+
+impl Error for CellularError {}
+
+/// How cells beyond the edge of the lattice are treated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    /// Opposite edges are joined (ring / torus)
+    Periodic,
+    /// Cells outside the lattice are always dead
+    Dead,
+}
+
+/// A 1D elementary cellular automaton (two states, nearest neighbours).
+///
+/// The rule is given by its Wolfram number: bit `k` of `rule` is the next state of a cell
+/// whose (left, self, right) neighbourhood read as a binary number equals `k`.
+#[derive(Debug, Clone)]
+pub struct ElementaryCA {
+    /// Number of cells
+    pub width: usize,
+
+    /// Wolfram rule number (0-255)
+    pub rule: u8,
+
+    /// Current cell states
+    pub cells: Vec<bool>,
+
+    /// Boundary condition
+    pub boundary: Boundary,
+
+    /// History of cell states after each step (row 0 is the initial state)
+    pub history: Vec<Vec<bool>>,
+}
+
+impl ElementaryCA {
+    /// Creates an automaton of `width` cells with a single live cell in the middle
+    pub fn new(width: usize, rule: u8) -> Result<Self, CellularError> {
+        if width == 0 {
+            return Err(CellularError::DimensionMismatch(
+                "Automaton must have at least one cell".to_string()
+            ));
+        }
+
+        let mut cells = vec![false; width];
+        cells[width / 2] = true;
+
+        Ok(ElementaryCA {
+            width,
+            rule,
+            history: vec![cells.clone()],
+            cells,
+            boundary: Boundary::Periodic,
+        })
+    }
+
+    fn cell(&self, index: isize) -> bool {
+        let width = self.width as isize;
+        match self.boundary {
+            Boundary::Periodic => self.cells[index.rem_euclid(width) as usize],
+            Boundary::Dead => index >= 0 && index < width && self.cells[index as usize],
+        }
+    }
+
+    /// Compute the next generation and record it in history
+    pub fn step(&mut self) {
+        let next: Vec<bool> = (0..self.width as isize)
+            .map(|i| {
+                let pattern = (self.cell(i - 1) as u8) << 2 | (self.cell(i) as u8) << 1 | self.cell(i + 1) as u8;
+                (self.rule >> pattern) & 1 == 1
+            })
+            .collect();
+
+        self.cells = next;
+        self.history.push(self.cells.clone());
+    }
+
+    /// Run `steps` generations
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Set each cell alive with probability `density`
+    pub fn randomize(&mut self, density: f64, rng: &mut impl Rng) {
+        let cells = (0..self.width).map(|_| rng.gen::<f64>() < density).collect();
+        self.set_cells(cells).expect("randomized cells have the automaton's width");
+    }
+
+    /// Set the cell states directly, restarting history
+    pub fn set_cells(&mut self, cells: Vec<bool>) -> Result<(), CellularError> {
+        if cells.len() != self.width {
+            return Err(CellularError::DimensionMismatch(format!(
+                "Configuration has length {} but expected {}",
+                cells.len(), self.width
+            )));
+        }
+
+        self.cells = cells;
+        self.history = vec![self.cells.clone()];
+        Ok(())
+    }
+
+    /// Reset to the initial state
+    pub fn reset(&mut self) {
+        if let Some(initial) = self.history.first().cloned() {
+            self.cells = initial.clone();
+            self.history = vec![initial];
+        }
+    }
+
+    /// Number of live cells
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&alive| alive).count()
+    }
+}
+
+/// Birth/survival rule of a life-like 2D automaton (Moore neighbourhood)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LifeRule {
+    /// `birth[n]`: a dead cell with n live neighbours becomes alive
+    pub birth: [bool; 9],
+    /// `survival[n]`: a live cell with n live neighbours stays alive
+    pub survival: [bool; 9],
+}
+
+impl LifeRule {
+    /// Conway's Game of Life, B3/S23
+    pub fn conway() -> Self {
+        Self::parse("B3/S23").expect("valid rule")
+    }
+
+    /// Parses a rule in B/S notation, e.g. "B3/S23" or "b36/s23" (HighLife).
+    ///
+    /// The "S.../B..." order and the bare "23/3" (survival/birth) form are also accepted.
+    pub fn parse(text: &str) -> Result<Self, CellularError> {
+        let text = text.trim();
+        let parts: Vec<&str> = text.split('/').map(str::trim).collect();
+        if parts.len() != 2 {
+            return Err(CellularError::InvalidRule(format!(
+                "'{}' is not of the form B.../S...",
+                text
+            )));
+        }
+
+        let mut birth = None;
+        let mut survival = None;
+        for (position, part) in parts.iter().enumerate() {
+            let (kind, digits) = match part.chars().next() {
+                Some('B') | Some('b') => ('B', &part[1..]),
+                Some('S') | Some('s') => ('S', &part[1..]),
+                // Bare digits: survival/birth as in the classic "23/3" notation
+                _ => (if position == 0 { 'S' } else { 'B' }, *part),
+            };
+
+            let mut counts = [false; 9];
+            for c in digits.chars() {
+                match c.to_digit(10) {
+                    Some(n) if n <= 8 => counts[n as usize] = true,
+                    _ => {
+                        return Err(CellularError::InvalidRule(format!(
+                            "'{}' is not a neighbour count (0-8) in '{}'",
+                            c, text
+                        )))
+                    }
+                }
+            }
+
+            let slot = if kind == 'B' { &mut birth } else { &mut survival };
+            if slot.replace(counts).is_some() {
+                return Err(CellularError::InvalidRule(format!(
+                    "'{}' specifies {} twice",
+                    text, kind
+                )));
+            }
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(LifeRule { birth, survival }),
+            _ => Err(CellularError::InvalidRule(format!(
+                "'{}' needs both a B and an S part",
+                text
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9).filter(|&n| counts[n]).map(|n| char::from(b'0' + n as u8)).collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+/// A 2D life-like cellular automaton on a rectangular lattice
+#[derive(Debug, Clone)]
+pub struct LifeGrid {
+    pub width: usize,
+    pub height: usize,
+
+    /// Cell states in row-major order
+    pub cells: Vec<bool>,
+
+    /// Birth/survival rule
+    pub rule: LifeRule,
+
+    /// Boundary condition
+    pub boundary: Boundary,
+
+    /// History of cell states after each step
+    pub history: Vec<Vec<bool>>,
+}
+
+impl LifeGrid {
+    /// Creates an empty grid running Conway's Game of Life
+    pub fn new(width: usize, height: usize) -> Result<Self, CellularError> {
+        if width == 0 || height == 0 {
+            return Err(CellularError::DimensionMismatch(format!(
+                "Grid dimensions must be greater than 0, got {}x{}",
+                width, height
+            )));
+        }
+
+        let cells = vec![false; width * height];
+        Ok(LifeGrid {
+            width,
+            height,
+            history: vec![cells.clone()],
+            cells,
+            rule: LifeRule::conway(),
+            boundary: Boundary::Periodic,
+        })
+    }
+
+    /// Number of live cells in the Moore neighbourhood of (x, y)
+    pub fn live_neighbors(&self, x: usize, y: usize) -> usize {
+        let (w, h) = (self.width as isize, self.height as isize);
+        let mut count = 0;
+        for dy in -1..=1isize {
+            for dx in -1..=1isize {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                let alive = match self.boundary {
+                    Boundary::Periodic => {
+                        self.cells[(ny.rem_euclid(h) * w + nx.rem_euclid(w)) as usize]
+                    },
+                    Boundary::Dead => {
+                        nx >= 0 && nx < w && ny >= 0 && ny < h && self.cells[(ny * w + nx) as usize]
+                    },
+                };
+                count += alive as usize;
+            }
+        }
+        count
+    }
+
+    /// Compute the next generation and record it in history
+    pub fn step(&mut self) {
+        let mut next = vec![false; self.cells.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = y * self.width + x;
+                let n = self.live_neighbors(x, y);
+                next[index] = if self.cells[index] { self.rule.survival[n] } else { self.rule.birth[n] };
+            }
+        }
+
+        self.cells = next;
+        self.history.push(self.cells.clone());
+    }
+
+    /// Run `steps` generations
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Flip the state of the cell at (x, y), appending the edited grid to history
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        if x < self.width && y < self.height {
+            let index = y * self.width + x;
+            self.cells[index] = !self.cells[index];
+            self.history.push(self.cells.clone());
+        }
+    }
+
+    /// Set each cell alive with probability `density`
+    pub fn randomize(&mut self, density: f64, rng: &mut impl Rng) {
+        let cells = (0..self.cells.len()).map(|_| rng.gen::<f64>() < density).collect();
+        self.set_cells(cells).expect("randomized cells have the grid's size");
+    }
+
+    /// Set the cell states directly, restarting history
+    pub fn set_cells(&mut self, cells: Vec<bool>) -> Result<(), CellularError> {
+        if cells.len() != self.width * self.height {
+            return Err(CellularError::DimensionMismatch(format!(
+                "Configuration has length {} but expected {}",
+                cells.len(), self.width * self.height
+            )));
+        }
+
+        self.cells = cells;
+        self.history = vec![self.cells.clone()];
+        Ok(())
+    }
+
+    /// Reset to the initial state
+    pub fn reset(&mut self) {
+        if let Some(initial) = self.history.first().cloned() {
+            self.cells = initial.clone();
+            self.history = vec![initial];
+        }
+    }
+
+    /// Number of live cells
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&alive| alive).count()
+    }
+}
+
+// Implement the DynamicalSystem trait for both automata; a step is one generation
+impl DynamicalSystem for ElementaryCA {
+    type State = Vec<bool>;
+    type Error = CellularError;
+
+    fn size(&self) -> usize {
+        self.width
+    }
+
+    fn state(&self) -> Self::State {
+        self.cells.clone()
+    }
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.set_cells(state)
+    }
+
+    fn step(&mut self, _rng: &mut impl Rng) -> Result<(), Self::Error> {
+        ElementaryCA::step(self);
+        Ok(())
+    }
+
+    fn history(&self) -> Vec<Self::State> {
+        self.history.clone()
+    }
+}
+
+impl DynamicalSystem for LifeGrid {
+    type State = Vec<bool>;
+    type Error = CellularError;
+
+    fn size(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn state(&self) -> Self::State {
+        self.cells.clone()
+    }
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.set_cells(state)
+    }
+
+    fn step(&mut self, _rng: &mut impl Rng) -> Result<(), Self::Error> {
+        LifeGrid::step(self);
+        Ok(())
+    }
+
+    fn history(&self) -> Vec<Self::State> {
+        self.history.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_90_sierpinski() {
+        let mut ca = ElementaryCA::new(7, 90).unwrap();
+        ca.boundary = Boundary::Dead;
+        ca.run(2);
+
+        let row = |cells: &[bool]| cells.iter().map(|&c| if c { '#' } else { '.' }).collect::<String>();
+        assert_eq!(row(&ca.history[0]), "...#...");
+        assert_eq!(row(&ca.history[1]), "..#.#..");
+        assert_eq!(row(&ca.history[2]), ".#...#.");
+    }
+
+    #[test]
+    fn test_blinker_oscillates() {
+        let mut grid = LifeGrid::new(5, 5).unwrap();
+        for x in 1..4 {
+            grid.toggle(x, 2);
+        }
+        let horizontal = grid.cells.clone();
+
+        grid.step();
+        assert_eq!(grid.population(), 3);
+        assert!(grid.cells[5 + 2] && grid.cells[2 * 5 + 2] && grid.cells[3 * 5 + 2]);
+
+        grid.step();
+        assert_eq!(grid.cells, horizontal);
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let highlife = LifeRule::parse("B36/S23").unwrap();
+        assert!(highlife.birth[3] && highlife.birth[6] && !highlife.birth[2]);
+        assert_eq!(highlife.to_string(), "B36/S23");
+
+        assert_eq!(LifeRule::parse("23/3").unwrap(), LifeRule::conway());
+        assert_eq!(LifeRule::parse("s23/b3").unwrap(), LifeRule::conway());
+        assert!(LifeRule::parse("B9/S23").is_err());
+        assert!(LifeRule::parse("B3").is_err());
+    }
+}
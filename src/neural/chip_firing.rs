@@ -1,8 +1,16 @@
+use std::collections::VecDeque;
 use std::error::Error;
 use std::fmt;
+use nalgebra::DMatrix;
 use rand::Rng;
 
-use super::NeuralNetwork;
+use super::DynamicalSystem;
+use crate::core::graph::{self, Graph, GraphError, GraphStatistics};
+use crate::core::delta::{DeltaCodec, DeltaHistory};
+use crate::core::history::SimulationHistory;
+
+pub mod gpu;
+pub mod presets;
 
 /// Error types for Chip Firing Graphs
 #[derive(Debug)]
@@ -11,6 +19,7 @@ pub enum ChipFiringError {
     InvalidGraphStructure(String),
     NegativeChips(String),
     NoActiveVertices(String),
+    InvalidThreshold(String),
 }
 
 impl fmt::Display for ChipFiringError {
@@ -20,12 +29,21 @@ impl fmt::Display for ChipFiringError {
             ChipFiringError::InvalidGraphStructure(msg) => write!(f, "Invalid graph structure: {}", msg),
             ChipFiringError::NegativeChips(msg) => write!(f, "Negative chips: {}", msg),
             ChipFiringError::NoActiveVertices(msg) => write!(f, "No active vertices: {}", msg),
+            ChipFiringError::InvalidThreshold(msg) => write!(f, "Invalid threshold: {}", msg),
         }
     }
 }
 
 impl Error for ChipFiringError {}
 
+impl From<GraphError> for ChipFiringError {
+    fn from(error: GraphError) -> Self {
+        match error {
+            GraphError::InvalidGraphStructure(msg) | GraphError::Parse(msg) => ChipFiringError::InvalidGraphStructure(msg),
+        }
+    }
+}
+
 /// Update mode for Chip Firing Graph dynamics
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum UpdateMode {
@@ -44,19 +62,106 @@ pub enum VertexSelectionStrategy {
     RandomActive,
 }
 
+/// Where chips are dropped when driving the system from outside
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DriveTarget {
+    /// A uniformly random vertex other than the sink
+    Random,
+    /// Always the same vertex
+    Fixed(usize),
+}
+
+/// Optional name and color tag of a vertex, e.g. the node names of an imported graph
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VertexMetadata {
+    pub label: Option<String>,
+    /// RGB color tag
+    pub color: Option<[u8; 3]>,
+}
+
+/// Stabilization statistics over random initial configurations with the same chip density
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StabilizationStats {
+    /// Chips per vertex in the initial configurations
+    pub density: f64,
+    /// Mean number of steps until stable, over the runs that stabilized
+    pub mean_steps: f64,
+    /// Mean total number of firings, over the runs that stabilized
+    pub mean_firings: f64,
+    /// Fraction of the runs that stabilized within the step limit
+    pub stabilized_fraction: f64,
+}
+
+/// Change of a chip configuration between two recorded steps
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FiringDelta {
+    /// Vertices that fired, once per firing
+    pub fired: Vec<u32>,
+    /// Chips added (or removed) from outside, e.g. by the sandpile drive
+    pub added: Vec<(u32, i32)>,
+}
+
+/// Delta codec of chip configurations: replays fired-vertex lists on a copy of the graph
+/// structure. Firings commute, so the order of `fired` does not matter.
+#[derive(Debug, Clone)]
+pub struct FiredVertices {
+    neighbor_offsets: Vec<usize>,
+    neighbor_indices: Vec<usize>,
+    edge_multiplicities: Vec<u32>,
+    /// Chips each vertex loses when it fires
+    losses: Vec<u32>,
+}
+
+impl DeltaCodec for FiredVertices {
+    type State = Vec<i32>;
+    type Delta = FiringDelta;
+
+    fn apply(&self, configuration: &mut Vec<i32>, delta: &FiringDelta) {
+        for &vertex in &delta.fired {
+            let vertex = vertex as usize;
+            configuration[vertex] -= self.losses[vertex] as i32;
+            for k in self.neighbor_offsets[vertex]..self.neighbor_offsets[vertex + 1] {
+                configuration[self.neighbor_indices[k]] += self.edge_multiplicities[k] as i32;
+            }
+        }
+        for &(vertex, amount) in &delta.added {
+            configuration[vertex as usize] += amount;
+        }
+    }
+}
+
+/// Chip configurations recorded as fired-vertex lists
+pub type FiringLog = DeltaHistory<FiredVertices>;
+
+/// Configurations between full copies in a `FiringLog`
+const FIRING_LOG_KEYFRAME_INTERVAL: usize = 256;
+
 /// A graph where vertices have chips that can be fired based on certain rules.
 /// 
 /// Chip Firing Graphs are a type of discrete dynamical system where:
 /// - Each vertex has some number of "chips"
-/// - A vertex is "active" if it has at least as many chips as its degree
+/// - A vertex other than the sink is "active" if it has at least as many chips as its
+///   threshold, which defaults to its firing loss (degree plus dissipation)
 /// - When a vertex fires, it sends one chip along each edge to each of its neighbors
+///   and loses its dissipation
+///
+/// Parallel edges make this the weighted Laplacian of the edge multiplicities. Vertices
+/// can also dissipate extra chips when they fire, which generalizes the Laplacian to an
+/// M-matrix (see `from_m_matrix`), and need more chips than they lose to fire.
 #[derive(Debug, Clone)]
 pub struct ChipFiringGraph {
     /// Number of vertices in the graph
     pub num_vertices: usize,
     
-    /// Adjacency matrix: adjacency[i][j] is the number of edges from vertex i to vertex j
-    adjacency_matrix: Vec<Vec<u32>>,
+    /// CSR row offsets: the neighbors of vertex i are stored at
+    /// `neighbor_offsets[i]..neighbor_offsets[i + 1]` in the arrays below
+    neighbor_offsets: Vec<usize>,
+    
+    /// CSR column indices: neighbor vertex for each stored edge entry (sorted per vertex)
+    neighbor_indices: Vec<usize>,
+    
+    /// Number of parallel edges for each entry in `neighbor_indices`
+    edge_multiplicities: Vec<u32>,
     
     /// Current configuration (number of chips at each vertex)
     pub configuration: Vec<i32>,
@@ -64,19 +169,70 @@ pub struct ChipFiringGraph {
     /// Vertex degrees (number of edges connected to each vertex)
     pub degrees: Vec<u32>,
     
-    /// History of configurations after each step
-    pub history: Vec<Vec<i32>>,
+    /// Chips a firing vertex loses on top of those it sends along its edges. Nonzero
+    /// entries turn the Laplacian into a general M-matrix: every vertex leaks a little,
+    /// as in a resource-flow network, and the graph stabilizes even without a sink.
+    dissipation: Vec<u32>,
+    
+    /// Chips a vertex needs to fire, at least its firing loss (the default). A firing
+    /// still sends one chip along each edge, so a higher threshold models a vertex that
+    /// holds on to more chips before toppling.
+    thresholds: Vec<u32>,
+    
+    /// Most chips a vertex can hold: chips it receives beyond the cap dissipate, as at a
+    /// sink. Unlike firing, this does not commute, so with a cap the stable configuration
+    /// may depend on the firing order.
+    pub max_height: Option<i32>,
+    
+    /// History of configurations, tagged with the step they were recorded at
+    pub history: SimulationHistory<Vec<i32>>,
     
     /// Update mode (Sequential or Parallel)
     pub update_mode: UpdateMode,
     
     /// Vertex selection strategy for Sequential update mode
     pub selection_strategy: VertexSelectionStrategy,
+    
+    /// Probability that an active vertex chosen to fire does so in a step: 1 gives the
+    /// deterministic BTW sandpile, lower values a stochastic one in the spirit of the
+    /// Manna model. By the abelian property the stable configuration reached is the same;
+    /// only the timing of avalanches changes.
+    pub firing_probability: f64,
+    
+    /// Record a configuration in history every `history_stride` steps (1 = every step)
+    pub history_stride: usize,
+    
+    /// Steps executed since the last configuration was recorded in history
+    steps_since_record: usize,
+    
+    /// Cumulative number of times each vertex has fired
+    pub firing_counts: Vec<u64>,
+    
+    /// Recent firing rate per vertex: decays by `activity_decay` every step, +1 per firing
+    pub firing_activity: Vec<f64>,
+    
+    /// Decay factor applied to `firing_activity` at each step (0.0 to 1.0)
+    pub activity_decay: f64,
+    
+    /// Vertex that absorbs chips and never fires. With a sink every configuration
+    /// on a connected graph eventually stabilizes, as chips leave the system there.
+    sink: Option<usize>,
+    
+    /// Labels and color tags, one entry per vertex
+    metadata: Vec<VertexMetadata>,
+    
+    /// Compact record of every configuration in `history`, if enabled
+    firing_log: Option<FiringLog>,
+    
+    /// Vertices fired since the last configuration was recorded (only tracked with a log)
+    pending_firings: Vec<u32>,
 }
 
 impl ChipFiringGraph {
     /// Creates a new Chip Firing Graph with the given adjacency matrix and initial configuration.
     ///
+    /// The dense matrix is only used as input; it is converted to sparse neighbor lists.
+    ///
     /// # Arguments
     ///
     /// * `adjacency_matrix` - The adjacency matrix of the graph. A[i][j] is the number of edges from i to j.
@@ -101,6 +257,37 @@ impl ChipFiringGraph {
             }
         }
         
+        let neighbor_lists = adjacency_matrix
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .filter(|&(_, &count)| count > 0)
+                    .map(|(j, &count)| (j, count))
+                    .collect()
+            })
+            .collect();
+        
+        Self::from_neighbor_lists(neighbor_lists, initial_configuration)
+    }
+    
+    /// Creates a new Chip Firing Graph from per-vertex neighbor lists.
+    ///
+    /// # Arguments
+    ///
+    /// * `neighbor_lists` - For each vertex, a list of (neighbor, edge multiplicity) pairs.
+    ///   Repeated neighbors are merged by summing their multiplicities.
+    /// * `initial_configuration` - The initial number of chips at each vertex.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new ChipFiringGraph, or an error if the input is invalid.
+    pub fn from_neighbor_lists(
+        neighbor_lists: Vec<Vec<(usize, u32)>>,
+        initial_configuration: Vec<i32>,
+    ) -> Result<Self, ChipFiringError> {
+        let num_vertices = neighbor_lists.len();
+        
         // Validate the initial configuration
         if initial_configuration.len() != num_vertices {
             return Err(ChipFiringError::DimensionMismatch(format!(
@@ -118,22 +305,63 @@ impl ChipFiringGraph {
             }
         }
         
-        // Calculate vertex degrees
+        // Build the CSR arrays, merging duplicate entries and computing degrees
+        let mut neighbor_offsets = Vec::with_capacity(num_vertices + 1);
+        let mut neighbor_indices = Vec::new();
+        let mut edge_multiplicities = Vec::new();
         let mut degrees = vec![0; num_vertices];
-        for i in 0..num_vertices {
-            for j in 0..num_vertices {
-                degrees[i] += adjacency_matrix[i][j];
+        neighbor_offsets.push(0);
+        
+        for (i, mut list) in neighbor_lists.into_iter().enumerate() {
+            list.sort_unstable_by_key(|&(j, _)| j);
+            for (j, count) in list {
+                if j >= num_vertices {
+                    return Err(ChipFiringError::InvalidGraphStructure(format!(
+                        "Vertex {} has neighbor {} outside range 0..{}",
+                        i, j, num_vertices
+                    )));
+                }
+                if count == 0 {
+                    continue;
+                }
+                let overflow = || ChipFiringError::InvalidGraphStructure(format!(
+                    "Vertex {} has more than {} edges", i, u32::MAX
+                ));
+                degrees[i] = u32::checked_add(degrees[i], count).ok_or_else(overflow)?;
+                if neighbor_indices.len() > neighbor_offsets[i] && neighbor_indices.last() == Some(&j) {
+                    let multiplicity = edge_multiplicities.last_mut().unwrap();
+                    *multiplicity = u32::checked_add(*multiplicity, count).ok_or_else(overflow)?;
+                } else {
+                    neighbor_indices.push(j);
+                    edge_multiplicities.push(count);
+                }
             }
+            neighbor_offsets.push(neighbor_indices.len());
         }
         
         Ok(ChipFiringGraph {
             num_vertices,
-            adjacency_matrix,
+            neighbor_offsets,
+            neighbor_indices,
+            edge_multiplicities,
             configuration: initial_configuration.clone(),
+            dissipation: vec![0; num_vertices],
+            thresholds: degrees.clone(),
             degrees,
-            history: vec![initial_configuration],
+            max_height: None,
+            history: SimulationHistory::new(initial_configuration),
             update_mode: UpdateMode::Sequential,
             selection_strategy: VertexSelectionStrategy::FirstActive,
+            firing_probability: 1.0,
+            history_stride: 1,
+            steps_since_record: 0,
+            firing_counts: vec![0; num_vertices],
+            firing_activity: vec![0.0; num_vertices],
+            activity_decay: 0.9,
+            sink: None,
+            metadata: vec![VertexMetadata::default(); num_vertices],
+            firing_log: None,
+            pending_firings: Vec::new(),
         })
     }
     
@@ -152,6 +380,26 @@ impl ChipFiringGraph {
         edges: &[(usize, usize)],
         num_vertices: usize,
         initial_configuration: Vec<i32>,
+    ) -> Result<Self, ChipFiringError> {
+        let weighted: Vec<(usize, usize, u32)> = edges.iter().map(|&(from, to)| (from, to, 1)).collect();
+        Self::from_weighted_edge_list(&weighted, num_vertices, initial_configuration)
+    }
+    
+    /// Creates a new undirected Chip Firing Graph with multi-edges.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `edges` - A list of (from, to, multiplicity) triples. Repeated pairs add up.
+    /// * `num_vertices` - The total number of vertices in the graph.
+    /// * `initial_configuration` - The initial number of chips at each vertex.
+    /// 
+    /// # Returns
+    /// 
+    /// A Result containing the new ChipFiringGraph, or an error if the input is invalid.
+    pub fn from_weighted_edge_list(
+        edges: &[(usize, usize, u32)],
+        num_vertices: usize,
+        initial_configuration: Vec<i32>,
     ) -> Result<Self, ChipFiringError> {
         // Validate the initial configuration
         if initial_configuration.len() != num_vertices {
@@ -161,24 +409,82 @@ impl ChipFiringGraph {
             )));
         }
         
-        // Create empty adjacency matrix
-        let mut adjacency_matrix = vec![vec![0; num_vertices]; num_vertices];
-        
-        // Fill in the adjacency matrix based on the edge list
-        for &(from, to) in edges {
-            if from >= num_vertices || to >= num_vertices {
+        let graph = Graph::from_edges(num_vertices, edges.to_vec())?;
+        Self::from_graph(&graph, initial_configuration)
+    }
+    
+    /// Creates a Chip Firing Graph from the matrix of a weighted Laplacian, or more
+    /// generally an M-matrix: firing vertex i subtracts row i of the matrix from the
+    /// configuration, as for `laplacian`.
+    ///
+    /// # Arguments
+    ///
+    /// * `matrix` - `matrix[i][j]` for j ≠ i is minus the chips i sends to j, and must
+    ///   not be positive. `matrix[i][i]` is the chips i loses, at least what it sends;
+    ///   the excess dissipates.
+    /// * `initial_configuration` - The initial number of chips at each vertex.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new ChipFiringGraph, or an error if the input is invalid.
+    pub fn from_m_matrix(
+        matrix: &[Vec<i32>],
+        initial_configuration: Vec<i32>,
+    ) -> Result<Self, ChipFiringError> {
+        let num_vertices = matrix.len();
+        let mut neighbor_lists = Vec::with_capacity(num_vertices);
+        let mut dissipation = Vec::with_capacity(num_vertices);
+        for (i, row) in matrix.iter().enumerate() {
+            if row.len() != num_vertices {
+                return Err(ChipFiringError::DimensionMismatch(format!(
+                    "Row {} of the matrix has length {} but expected {}",
+                    i, row.len(), num_vertices
+                )));
+            }
+            let mut sent = 0i64;
+            let mut neighbors = Vec::new();
+            for (j, &entry) in row.iter().enumerate() {
+                if j == i || entry == 0 {
+                    continue;
+                }
+                if entry > 0 {
+                    return Err(ChipFiringError::InvalidGraphStructure(format!(
+                        "Entry ({}, {}) is {}, but off-diagonal entries may not be positive",
+                        i, j, entry
+                    )));
+                }
+                sent += -(entry as i64);
+                neighbors.push((j, entry.unsigned_abs()));
+            }
+            if (row[i] as i64) < sent {
                 return Err(ChipFiringError::InvalidGraphStructure(format!(
-                    "Edge ({}, {}) references vertex outside range 0..{}",
-                    from, to, num_vertices
+                    "Vertex {} sends {} chips when it fires but loses only {}",
+                    i, sent, row[i]
                 )));
             }
-            
-            // For undirected graphs, add edges in both directions
-            adjacency_matrix[from][to] += 1;
-            adjacency_matrix[to][from] += 1;
+            neighbor_lists.push(neighbors);
+            dissipation.push((row[i] as i64 - sent) as u32);
         }
         
-        Self::new(adjacency_matrix, initial_configuration)
+        let mut graph = Self::from_neighbor_lists(neighbor_lists, initial_configuration)?;
+        for (vertex, amount) in dissipation.into_iter().enumerate() {
+            graph.set_dissipation(vertex, amount)?;
+        }
+        Ok(graph)
+    }
+    
+    /// Creates a new Chip Firing Graph on the vertices and edges of `graph`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - The undirected (multi)graph to fire chips on.
+    /// * `initial_configuration` - The initial number of chips at each vertex.
+    /// 
+    /// # Returns
+    /// 
+    /// A Result containing the new ChipFiringGraph, or an error if the input is invalid.
+    pub fn from_graph(graph: &Graph, initial_configuration: Vec<i32>) -> Result<Self, ChipFiringError> {
+        Self::from_neighbor_lists(graph.neighbor_lists(), initial_configuration)
     }
     
     /// Creates a new Chip Firing Graph with a pre-defined grid structure.
@@ -207,42 +513,166 @@ impl ChipFiringGraph {
             )));
         }
         
-        // Create empty adjacency matrix
-        let mut adjacency_matrix = vec![vec![0; num_vertices]; num_vertices];
+        Self::from_graph(&Graph::grid(width, height), initial_configuration)
+    }
+    
+    /// Creates the abelian sandpile on an empty `width` x `height` grid: boundary cells
+    /// are joined to an extra sink vertex, the last one, so every cell has degree 4 and
+    /// chips toppling over the edge of the grid are lost.
+    pub fn new_sandpile(width: usize, height: usize) -> Result<Self, ChipFiringError> {
+        let cells = width * height;
+        if cells == 0 {
+            return Err(ChipFiringError::InvalidGraphStructure("A sandpile needs at least one cell".to_string()));
+        }
         
-        // Fill in the adjacency matrix with grid connections (4-connectivity)
-        for y in 0..height {
-            for x in 0..width {
-                let idx = y * width + x;
-                
-                // Connect to neighbors (up, down, left, right)
-                if y > 0 { // Up
-                    let up_idx = (y - 1) * width + x;
-                    adjacency_matrix[idx][up_idx] = 1;
-                    adjacency_matrix[up_idx][idx] = 1;
-                }
-                if x > 0 { // Left
-                    let left_idx = y * width + (x - 1);
-                    adjacency_matrix[idx][left_idx] = 1;
-                    adjacency_matrix[left_idx][idx] = 1;
-                }
-                // No need to check down and right as they will be covered by other vertices
+        let mut neighbor_lists = Graph::grid(width, height).neighbor_lists();
+        let mut sink_edges = Vec::new();
+        for (cell, neighbors) in neighbor_lists.iter_mut().enumerate() {
+            let degree: u32 = neighbors.iter().map(|&(_, count)| count).sum();
+            let missing = 4u32.saturating_sub(degree);
+            if missing > 0 {
+                neighbors.push((cells, missing));
+                sink_edges.push((cell, missing));
             }
         }
+        neighbor_lists.push(sink_edges);
         
-        Self::new(adjacency_matrix, initial_configuration)
+        let mut graph = Self::from_neighbor_lists(neighbor_lists, vec![0; cells + 1])?;
+        graph.set_sink(Some(cells))?;
+        Ok(graph)
+    }
+    
+    /// The Laplacian matrix L = D - A, with edge multiplicities as weights and the
+    /// dissipation added to the diagonal, in the convention of `from_m_matrix`: row i
+    /// holds the chips vertex i loses and sends, so firing vertex i subtracts row i of L
+    /// from the configuration.
+    pub fn laplacian(&self) -> DMatrix<f64> {
+        let neighbor_lists: Vec<Vec<(usize, u32)>> = (0..self.num_vertices)
+            .map(|i| self.neighbor_edges(i).collect())
+            .collect();
+        let mut laplacian = graph::laplacian(&neighbor_lists);
+        for (i, &amount) in self.dissipation.iter().enumerate() {
+            laplacian[(i, i)] += amount as f64;
+        }
+        laplacian
+    }
+    
+    /// Degree histogram, connected components and distances, with edges taken as
+    /// undirected
+    pub fn statistics(&self) -> GraphStatistics {
+        let neighbor_lists: Vec<Vec<(usize, u32)>> = (0..self.num_vertices)
+            .map(|i| self.neighbor_edges(i).collect())
+            .collect();
+        GraphStatistics::new(&neighbor_lists)
+    }
+    
+    /// Chips `vertex` loses when it fires: its degree plus its dissipation
+    pub fn firing_loss(&self, vertex: usize) -> u32 {
+        self.degrees.get(vertex).map_or(0, |&degree| degree + self.dissipation[vertex])
+    }
+    
+    /// Chips a firing `vertex` loses beyond those it sends to its neighbors
+    pub fn dissipation(&self, vertex: usize) -> u32 {
+        self.dissipation.get(vertex).copied().unwrap_or(0)
+    }
+    
+    /// Make `vertex` lose `amount` extra chips per firing. A threshold at the old firing
+    /// loss follows the new one; a custom threshold is raised if it would fall below it.
+    pub fn set_dissipation(&mut self, vertex: usize, amount: u32) -> Result<(), ChipFiringError> {
+        if vertex >= self.num_vertices {
+            return Err(ChipFiringError::InvalidGraphStructure(format!(
+                "Vertex {} is outside valid range 0..{}", vertex, self.num_vertices
+            )));
+        }
+        let default_threshold = self.thresholds[vertex] == self.firing_loss(vertex);
+        self.dissipation[vertex] = amount;
+        let loss = self.firing_loss(vertex);
+        if default_threshold || self.thresholds[vertex] < loss {
+            self.thresholds[vertex] = loss;
+        }
+        self.restart_firing_log();
+        Ok(())
+    }
+    
+    /// Whether any vertex dissipates chips when it fires
+    pub fn is_dissipative(&self) -> bool {
+        self.dissipation.iter().any(|&amount| amount > 0)
     }
     
     /// Returns a vector of indices of currently active vertices
-    /// A vertex is active if it has at least as many chips as its degree
+    /// A vertex is active if it has at least as many chips as its threshold
     pub fn active_vertices(&self) -> Vec<usize> {
-        let mut active = Vec::new();
-        for i in 0..self.num_vertices {
-            if self.configuration[i] >= self.degrees[i] as i32 {
-                active.push(i);
-            }
+        self.active_in(&self.configuration)
+    }
+    
+    /// Returns the vertices that would be active in `configuration`, e.g. one from history
+    pub fn active_in(&self, configuration: &[i32]) -> Vec<usize> {
+        (0..self.num_vertices.min(configuration.len()))
+            .filter(|&i| Some(i) != self.sink && configuration[i] >= self.thresholds[i] as i32)
+            .collect()
+    }
+    
+    /// Check if a vertex can fire in the current configuration
+    fn is_active(&self, vertex: usize) -> bool {
+        Some(vertex) != self.sink && self.configuration[vertex] >= self.thresholds[vertex] as i32
+    }
+    
+    /// Chips `vertex` needs to fire (its firing loss unless overridden; 0 outside the graph)
+    pub fn threshold(&self, vertex: usize) -> u32 {
+        self.thresholds.get(vertex).copied().unwrap_or(0)
+    }
+    
+    /// Override the chips `vertex` needs to fire. The threshold may not be below the
+    /// firing loss, or the vertex would fire into negative chips.
+    pub fn set_threshold(&mut self, vertex: usize, threshold: u32) -> Result<(), ChipFiringError> {
+        if vertex >= self.num_vertices {
+            return Err(ChipFiringError::InvalidGraphStructure(format!(
+                "Vertex {} is outside valid range 0..{}", vertex, self.num_vertices
+            )));
+        }
+        let loss = self.firing_loss(vertex);
+        if threshold < loss {
+            return Err(ChipFiringError::InvalidThreshold(format!(
+                "Vertex {} loses {} chips per firing, so a threshold of {} would let it fire into negative chips",
+                vertex, loss, threshold
+            )));
+        }
+        self.thresholds[vertex] = threshold;
+        Ok(())
+    }
+    
+    /// Whether any vertex needs more chips than its firing loss to fire
+    pub fn has_custom_thresholds(&self) -> bool {
+        (0..self.num_vertices).any(|v| self.thresholds[v] != self.firing_loss(v))
+    }
+    
+    /// Make every vertex fire at its firing loss again
+    pub fn reset_thresholds(&mut self) {
+        self.thresholds = (0..self.num_vertices).map(|v| self.firing_loss(v)).collect();
+    }
+    
+    /// Chips `vertex` holds after receiving `amount` more, limited by `max_height`
+    fn capped(&self, vertex: usize, amount: i32) -> i32 {
+        let chips = self.configuration[vertex] + amount;
+        match self.max_height {
+            Some(cap) if amount > 0 => chips.min(cap.max(self.configuration[vertex])),
+            _ => chips,
         }
-        active
+    }
+    
+    pub fn sink(&self) -> Option<usize> {
+        self.sink
+    }
+    
+    /// Make `sink` the vertex that absorbs chips, or remove the sink with None
+    pub fn set_sink(&mut self, sink: Option<usize>) -> Result<(), ChipFiringError> {
+        if let Some(vertex) = sink.filter(|&v| v >= self.num_vertices) {
+            return Err(ChipFiringError::InvalidGraphStructure(format!(
+                "Sink {} is outside valid range 0..{}", vertex, self.num_vertices
+            )));
+        }
+        self.sink = sink;
+        Ok(())
     }
     
     /// Check if the current configuration is stable (no active vertices)
@@ -266,21 +696,33 @@ impl ChipFiringGraph {
             )));
         }
         
+        if Some(vertex) == self.sink {
+            return Err(ChipFiringError::NoActiveVertices(format!("Vertex {} is the sink and never fires", vertex)));
+        }
+        
         // Check if the vertex is active
-        if self.configuration[vertex] < self.degrees[vertex] as i32 {
+        if self.configuration[vertex] < self.thresholds[vertex] as i32 {
             return Err(ChipFiringError::NoActiveVertices(format!(
                 "Vertex {} is not active: has {} chips but needs at least {} to fire",
-                vertex, self.configuration[vertex], self.degrees[vertex]
+                vertex, self.configuration[vertex], self.thresholds[vertex]
             )));
         }
         
         // Update the configuration
-        // The firing vertex loses one chip per outgoing edge
-        self.configuration[vertex] -= self.degrees[vertex] as i32;
+        // The firing vertex loses one chip per outgoing edge, plus its dissipation
+        self.configuration[vertex] -= self.firing_loss(vertex) as i32;
+        
+        // Each neighbor gains one chip per connecting edge, up to the height cap
+        let range = self.neighbor_offsets[vertex]..self.neighbor_offsets[vertex + 1];
+        for k in range {
+            let neighbor = self.neighbor_indices[k];
+            self.configuration[neighbor] = self.capped(neighbor, self.edge_multiplicities[k] as i32);
+        }
         
-        // Each neighbor gains one chip per connecting edge
-        for j in 0..self.num_vertices {
-            self.configuration[j] += self.adjacency_matrix[vertex][j] as i32;
+        self.firing_counts[vertex] += 1;
+        self.firing_activity[vertex] += 1.0;
+        if self.firing_log.is_some() {
+            self.pending_firings.push(vertex as u32);
         }
         
         Ok(())
@@ -290,7 +732,8 @@ impl ChipFiringGraph {
     /// 
     /// # Arguments
     /// 
-    /// * `rng` - Random number generator (used only for RandomActive strategy)
+    /// * `rng` - Random number generator (used only for the RandomActive strategy and a
+    ///   firing probability below 1)
     /// 
     /// # Returns
     /// 
@@ -304,6 +747,11 @@ impl ChipFiringGraph {
             ));
         }
         
+        // Older firings fade out of the recent activity measure
+        for activity in &mut self.firing_activity {
+            *activity *= self.activity_decay;
+        }
+        
         match self.update_mode {
             UpdateMode::Sequential => {
                 // Choose which active vertex to fire based on strategy
@@ -314,7 +762,9 @@ impl ChipFiringGraph {
                     }
                 };
                 
-                self.fire_vertex(vertex)?;
+                if self.fires(rng) {
+                    self.fire_vertex(vertex)?;
+                }
             },
             UpdateMode::Parallel => {
                 // Fire all active vertices simultaneously
@@ -322,28 +772,117 @@ impl ChipFiringGraph {
                 let mut delta = vec![0; self.num_vertices];
                 
                 for &vertex in &active {
+                    if !self.fires(rng) {
+                        continue;
+                    }
                     // Vertex loses chips
-                    delta[vertex] -= self.degrees[vertex] as i32;
+                    delta[vertex] -= self.firing_loss(vertex) as i32;
+                    self.firing_counts[vertex] += 1;
+                    self.firing_activity[vertex] += 1.0;
+                    if self.firing_log.is_some() {
+                        self.pending_firings.push(vertex as u32);
+                    }
                     
                     // Neighbors gain chips
-                    for j in 0..self.num_vertices {
-                        delta[j] += self.adjacency_matrix[vertex][j] as i32;
+                    for (j, count) in self.neighbor_edges(vertex) {
+                        delta[j] += count as i32;
                     }
                 }
                 
                 // Apply all changes
                 for i in 0..self.num_vertices {
-                    self.configuration[i] += delta[i];
+                    self.configuration[i] = self.capped(i, delta[i]);
                 }
             }
         }
         
-        // Add the new configuration to history
-        self.history.push(self.configuration.clone());
+        // Add the new configuration to history (sampled every `history_stride` steps)
+        self.steps_since_record += 1;
+        if self.steps_since_record >= self.history_stride.max(1) {
+            self.record_configuration();
+        }
         
         Ok(())
     }
     
+    /// Whether an active vertex fires this step; draws from `rng` only for a firing
+    /// probability below 1, so deterministic runs stay reproducible
+    fn fires(&self, rng: &mut impl Rng) -> bool {
+        self.firing_probability >= 1.0 || rng.gen::<f64>() < self.firing_probability
+    }
+    
+    /// Number of steps executed since the initial configuration
+    pub fn current_step(&self) -> usize {
+        self.history.last_iteration() + self.steps_since_record
+    }
+    
+    /// Push the current configuration to history
+    pub fn record_configuration(&mut self) {
+        self.history.push(self.current_step(), self.configuration.clone());
+        self.steps_since_record = 0;
+        self.log_firings();
+    }
+    
+    /// Compact record of the recorded configurations, if enabled
+    pub fn firing_log(&self) -> Option<&FiringLog> {
+        self.firing_log.as_ref()
+    }
+    
+    /// Start recording configurations as fired-vertex lists from the current one, or stop.
+    /// The log runs alongside `history`, which can then be bounded tightly.
+    pub fn set_firing_log(&mut self, enabled: bool) {
+        if !enabled {
+            self.firing_log = None;
+            self.pending_firings.clear();
+        } else if self.firing_log.is_none() {
+            self.start_firing_log();
+        }
+    }
+    
+    /// Restart an enabled log from the current configuration, after the timeline was replaced
+    pub fn restart_firing_log(&mut self) {
+        if self.firing_log.is_some() {
+            self.start_firing_log();
+        }
+    }
+    
+    fn start_firing_log(&mut self) {
+        let codec = FiredVertices {
+            neighbor_offsets: self.neighbor_offsets.clone(),
+            neighbor_indices: self.neighbor_indices.clone(),
+            edge_multiplicities: self.edge_multiplicities.clone(),
+            losses: (0..self.num_vertices).map(|v| self.firing_loss(v)).collect(),
+        };
+        self.firing_log = Some(FiringLog::new(codec, self.configuration.clone(), FIRING_LOG_KEYFRAME_INTERVAL));
+        self.pending_firings.clear();
+    }
+    
+    /// Append the firings since the last record to the log. Changes made from outside
+    /// (dropped or painted chips) are logged as the difference to the replayed firings.
+    fn log_firings(&mut self) {
+        let Some(log) = &mut self.firing_log else {
+            return;
+        };
+        let mut delta = FiringDelta { fired: std::mem::take(&mut self.pending_firings), added: Vec::new() };
+        let mut replayed = log.last().clone();
+        log.codec().apply(&mut replayed, &delta);
+        delta.added = replayed
+            .iter()
+            .zip(&self.configuration)
+            .enumerate()
+            .filter(|(_, (replayed, actual))| replayed != actual)
+            .map(|(vertex, (replayed, actual))| (vertex as u32, actual - replayed))
+            .collect();
+        log.push(self.history.last_iteration(), delta);
+    }
+    
+    /// Record the current configuration if steps were taken since the last sample
+    fn flush_history(&mut self) {
+        if self.steps_since_record > 0 {
+            self.record_configuration();
+        }
+    }
+    
     /// Run the dynamics for a specified number of steps or until stable
     /// 
     /// # Arguments
@@ -358,26 +897,162 @@ impl ChipFiringGraph {
         &mut self,
         max_steps: usize,
         rng: &mut impl Rng,
+    ) -> Result<usize, ChipFiringError> {
+        self.run_observed(max_steps, rng, |_| true)
+    }
+    
+    /// Like `run`, but calls `observer` with the number of steps executed so far
+    /// after every step. The run stops early when `observer` returns false.
+    pub fn run_observed(
+        &mut self,
+        max_steps: usize,
+        rng: &mut impl Rng,
+        mut observer: impl FnMut(usize) -> bool,
     ) -> Result<usize, ChipFiringError> {
         for i in 0..max_steps {
             if self.is_stable() {
+                self.flush_history(); // Keep the final state even if it fell between samples
                 return Ok(i); // Return if stable
             }
             
             match self.step(rng) {
                 Ok(_) => {}, // Continue to next step
                 Err(ChipFiringError::NoActiveVertices(_)) => {
+                    self.flush_history();
                     return Ok(i); // Stable configuration
                 },
                 Err(e) => return Err(e), // Other errors
             }
+            
+            if !observer(i + 1) {
+                self.flush_history();
+                return Ok(i + 1); // Stopped by the observer
+            }
         }
         
+        self.flush_history();
         Ok(max_steps) // Reached max steps
     }
     
+    /// Stabilize the configuration using a worklist of active vertices.
+    ///
+    /// By the abelian property the final stable configuration does not depend on
+    /// the order in which active vertices fire, so this skips the per-step
+    /// bookkeeping of `run` and only records the final configuration in history.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_firings` - Maximum number of vertex firings before giving up
+    ///   (graphs without a sink and too many chips never stabilize)
+    ///
+    /// # Returns
+    ///
+    /// Result with the total number of firings performed
+    pub fn stabilize(&mut self, max_firings: usize) -> Result<usize, ChipFiringError> {
+        let mut queue: VecDeque<usize> = self.active_vertices().into();
+        let mut queued = vec![false; self.num_vertices];
+        for &v in &queue {
+            queued[v] = true;
+        }
+        
+        let mut firings = 0;
+        while let Some(vertex) = queue.pop_front() {
+            queued[vertex] = false;
+            
+            // Fire the vertex as many times as it can before moving on
+            while self.is_active(vertex) {
+                if firings >= max_firings {
+                    self.steps_since_record += firings;
+                    self.record_configuration();
+                    return Ok(firings);
+                }
+                self.fire_vertex(vertex)?;
+                firings += 1;
+            }
+            
+            // Neighbors that received chips may have become active
+            for k in self.neighbor_offsets[vertex]..self.neighbor_offsets[vertex + 1] {
+                let neighbor = self.neighbor_indices[k];
+                if !queued[neighbor] && self.is_active(neighbor) {
+                    queued[neighbor] = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        
+        if firings > 0 {
+            self.steps_since_record += firings;
+            self.record_configuration();
+        }
+        Ok(firings)
+    }
+    
+    /// Relax the current configuration in waves of toppling from `seed`: each wave fires
+    /// the seed once and then relaxes every other vertex, and a new wave starts while
+    /// the seed is still active. On undirected graphs a vertex topples at most once per
+    /// wave, and each wave's footprint is a plain set of vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Vertex the avalanche started at, usually one that just received a chip
+    /// * `max_firings` - Maximum number of vertex firings before giving up
+    ///
+    /// # Returns
+    ///
+    /// Result with the vertices that toppled in each wave, in order
+    pub fn avalanche_waves(&mut self, seed: usize, max_firings: usize) -> Result<Vec<Vec<usize>>, ChipFiringError> {
+        if seed >= self.num_vertices {
+            return Err(ChipFiringError::InvalidGraphStructure(format!(
+                "Vertex {} is outside valid range 0..{}", seed, self.num_vertices
+            )));
+        }
+        
+        let mut waves = Vec::new();
+        let mut firings = 0;
+        'waves: while self.is_active(seed) && firings < max_firings {
+            let mut wave = Vec::new();
+            let mut toppled = vec![false; self.num_vertices];
+            let mut queue = VecDeque::from([seed]);
+            let mut queued = vec![false; self.num_vertices];
+            
+            // The seed fires once; everything else relaxes as far as it can
+            let mut seed_fired = false;
+            while let Some(vertex) = queue.pop_front() {
+                queued[vertex] = false;
+                while self.is_active(vertex) && !(vertex == seed && seed_fired) {
+                    if firings >= max_firings {
+                        waves.push(wave);
+                        break 'waves;
+                    }
+                    self.fire_vertex(vertex)?;
+                    firings += 1;
+                    seed_fired |= vertex == seed;
+                    if !toppled[vertex] {
+                        toppled[vertex] = true;
+                        wave.push(vertex);
+                    }
+                }
+                for k in self.neighbor_offsets[vertex]..self.neighbor_offsets[vertex + 1] {
+                    let neighbor = self.neighbor_indices[k];
+                    if neighbor != seed && !queued[neighbor] && self.is_active(neighbor) {
+                        queued[neighbor] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            waves.push(wave);
+        }
+        
+        if firings > 0 {
+            self.steps_since_record += firings;
+            self.record_configuration();
+        }
+        Ok(waves)
+    }
+    
     /// Add a chip to a specific vertex and run until stable
-    /// This is useful for studying avalanches
+    /// This is useful for studying avalanches. Like `add_chips`, the chip is not added
+    /// if the vertex is at `max_height`.
     /// 
     /// # Arguments
     /// 
@@ -394,18 +1069,140 @@ impl ChipFiringGraph {
         max_steps: usize,
         rng: &mut impl Rng,
     ) -> Result<usize, ChipFiringError> {
+        // Add a chip to the specified vertex
+        self.add_chips(vertex, 1)?;
+        
+        // Run the dynamics
+        self.run(max_steps, rng)
+    }
+    
+    /// Drop a single chip from outside, as in the driven sandpile. The change is
+    /// appended to history.
+    ///
+    /// # Returns
+    ///
+    /// Result with the vertex that received the chip
+    pub fn drop_chip(&mut self, target: DriveTarget, rng: &mut impl Rng) -> Result<usize, ChipFiringError> {
+        let vertex = match target {
+            DriveTarget::Fixed(vertex) => vertex,
+            DriveTarget::Random => {
+                let candidates = self.num_vertices - usize::from(self.sink.is_some());
+                if candidates == 0 {
+                    return Err(ChipFiringError::InvalidGraphStructure("No vertex to drop a chip on".to_string()));
+                }
+                // Skip over the sink by shifting the indices after it
+                let k = rng.gen_range(0..candidates);
+                match self.sink {
+                    Some(sink) if k >= sink => k + 1,
+                    _ => k,
+                }
+            },
+        };
+        self.add_chips(vertex, 1)?;
+        Ok(vertex)
+    }
+    
+    /// Spread `chips` chips uniformly at random over the vertices other than the sink
+    pub fn random_configuration(&self, chips: usize, rng: &mut impl Rng) -> Vec<i32> {
+        let mut configuration = vec![0; self.num_vertices];
+        let vertices: Vec<usize> = (0..self.num_vertices).filter(|&v| Some(v) != self.sink).collect();
+        if !vertices.is_empty() {
+            for _ in 0..chips {
+                configuration[vertices[rng.gen_range(0..vertices.len())]] += 1;
+            }
+        }
+        configuration
+    }
+    
+    /// Measure how long random configurations with `density` chips per vertex take to
+    /// stabilize under the current update mode. The graph itself is left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `density` - Average number of chips per vertex
+    /// * `trials` - Number of random initial configurations
+    /// * `max_steps` - Runs still unstable after this many steps count as not stabilizing
+    /// * `rng` - Random number generator for the configurations and vertex selection
+    pub fn measure_stabilization(
+        &self,
+        density: f64,
+        trials: usize,
+        max_steps: usize,
+        rng: &mut impl Rng,
+    ) -> StabilizationStats {
+        let mut graph = self.clone();
+        // Only the counts matter, so keep the history from growing
+        graph.history_stride = max_steps.max(1);
+        graph.history.set_capacity(Some(2));
+        
+        let chips = (density.max(0.0) * self.num_vertices as f64).round() as usize;
+        let (mut stabilized, mut total_steps, mut total_firings) = (0, 0, 0);
+        for _ in 0..trials {
+            let configuration = graph.random_configuration(chips, rng);
+            if graph.set_configuration(configuration).is_err() {
+                continue;
+            }
+            graph.reset_activity();
+            let Ok(steps) = graph.run(max_steps, rng) else {
+                continue;
+            };
+            if graph.is_stable() {
+                stabilized += 1;
+                total_steps += steps;
+                total_firings += graph.firing_counts.iter().sum::<u64>();
+            }
+        }
+        
+        let mean = |total: f64| if stabilized > 0 { total / stabilized as f64 } else { 0.0 };
+        StabilizationStats {
+            density,
+            mean_steps: mean(total_steps as f64),
+            mean_firings: mean(total_firings as f64),
+            stabilized_fraction: stabilized as f64 / trials.max(1) as f64,
+        }
+    }
+    
+    /// Add (or with a negative amount, remove) chips at a vertex as a manual intervention.
+    /// Unlike `set_configuration`, the change is appended to history instead of resetting it.
+    /// Added chips are limited by `max_height`.
+    pub fn add_chips(&mut self, vertex: usize, amount: i32) -> Result<(), ChipFiringError> {
         if vertex >= self.num_vertices {
             return Err(ChipFiringError::InvalidGraphStructure(format!(
                 "Vertex {} is outside valid range 0..{}", vertex, self.num_vertices
             )));
         }
         
-        // Add a chip to the specified vertex
-        self.configuration[vertex] += 1;
-        self.history.push(self.configuration.clone());
+        let chips = self.capped(vertex, amount);
+        if chips < 0 {
+            return Err(ChipFiringError::NegativeChips(format!(
+                "Vertex {} would have {} chips, but negative chips are not allowed",
+                vertex, chips
+            )));
+        }
         
-        // Run the dynamics
-        self.run(max_steps, rng)
+        self.configuration[vertex] = chips;
+        self.record_configuration();
+        Ok(())
+    }
+    
+    /// Add `amount` chips at each of `vertices` as one intervention, e.g. a brush stroke,
+    /// appending a single configuration to history. Added chips are limited by
+    /// `max_height`.
+    pub fn add_chips_to(&mut self, vertices: &[usize], amount: i32) -> Result<(), ChipFiringError> {
+        if let Some(&vertex) = vertices.iter().find(|&&v| v >= self.num_vertices) {
+            return Err(ChipFiringError::InvalidGraphStructure(format!(
+                "Vertex {} is outside valid range 0..{}", vertex, self.num_vertices
+            )));
+        }
+        if amount < 0 {
+            return Err(ChipFiringError::NegativeChips(format!("Cannot add {} chips", amount)));
+        }
+        
+        for &vertex in vertices {
+            self.configuration[vertex] = self.capped(vertex, amount);
+        }
+        self.record_configuration();
+        Ok(())
     }
     
     /// Calculate the total number of chips in the system
@@ -416,15 +1213,26 @@ impl ChipFiringGraph {
     /// Clear history to save memory
     pub fn clear_history(&mut self) {
         let current = self.configuration.clone();
-        self.history = vec![current];
+        self.history.reset(current);
+        self.steps_since_record = 0;
+        self.restart_firing_log();
     }
     
     /// Reset to initial configuration
     pub fn reset(&mut self) {
-        if !self.history.is_empty() {
-            self.configuration = self.history[0].clone();
-            self.history = vec![self.configuration.clone()];
+        if let Some(initial) = self.history.first().cloned() {
+            self.configuration = initial.clone();
+            self.history.reset(initial);
+            self.steps_since_record = 0;
+            self.restart_firing_log();
         }
+        self.reset_activity();
+    }
+    
+    /// Clear cumulative firing counts and recent activity
+    pub fn reset_activity(&mut self) {
+        self.firing_counts = vec![0; self.num_vertices];
+        self.firing_activity = vec![0.0; self.num_vertices];
     }
     
     /// Set configuration directly
@@ -446,60 +1254,100 @@ impl ChipFiringGraph {
         }
         
         self.configuration = configuration.clone();
-        self.history = vec![configuration];
+        self.history.reset(configuration);
+        self.steps_since_record = 0;
+        self.restart_firing_log();
         
         Ok(())
     }
     
+    /// Label and color tag of a vertex (empty for vertices outside the graph)
+    pub fn metadata(&self, vertex: usize) -> &VertexMetadata {
+        static EMPTY: VertexMetadata = VertexMetadata { label: None, color: None };
+        self.metadata.get(vertex).unwrap_or(&EMPTY)
+    }
+    
+    pub fn set_metadata(&mut self, vertex: usize, metadata: VertexMetadata) -> Result<(), ChipFiringError> {
+        match self.metadata.get_mut(vertex) {
+            Some(entry) => {
+                *entry = metadata;
+                Ok(())
+            },
+            None => Err(ChipFiringError::InvalidGraphStructure(format!(
+                "Vertex {} is outside valid range 0..{}", vertex, self.num_vertices
+            ))),
+        }
+    }
+    
+    /// The label of a vertex, or its index if it has none
+    pub fn vertex_name(&self, vertex: usize) -> String {
+        self.metadata(vertex).label.clone().unwrap_or_else(|| vertex.to_string())
+    }
+    
     /// Returns a vector of neighbors for a given vertex
     pub fn neighbors(&self, vertex: usize) -> Vec<usize> {
-        let mut neighbors = Vec::new();
-        if vertex < self.num_vertices {
-            for j in 0..self.num_vertices {
-                if self.adjacency_matrix[vertex][j] > 0 {
-                    // Consider edge count? For now, just list unique neighbors
-                    if !neighbors.contains(&j) { 
-                        neighbors.push(j);
-                    }
-                }
-            }
-        }
-        neighbors
+        self.neighbor_edges(vertex).map(|(j, _)| j).collect()
     }
-}
-
-// Implement the NeuralNetwork trait for ChipFiringGraph
-impl NeuralNetwork for ChipFiringGraph {
-    type Input = Vec<i32>;
-    type Output = Vec<Vec<i32>>;
-    type Error = ChipFiringError;
     
-    fn forward(&self, input: &Self::Input) -> Result<Self::Output, Self::Error> {
-        // Create a copy of self to run without modifying the original
-        let mut copy = self.clone();
-        copy.set_configuration(input.clone())?;
-        
-        let mut rng = rand::thread_rng();
-        copy.run(100, &mut rng)?;
-        
-        Ok(copy.history)
+    /// Iterates over (neighbor, edge multiplicity) pairs for a given vertex
+    pub fn neighbor_edges(&self, vertex: usize) -> impl Iterator<Item = (usize, u32)> + '_ {
+        let range = if vertex < self.num_vertices {
+            self.neighbor_offsets[vertex]..self.neighbor_offsets[vertex + 1]
+        } else {
+            0..0
+        };
+        self.neighbor_indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.edge_multiplicities[range].iter().copied())
     }
     
-    fn train(&mut self, data: &[Self::Input]) -> Result<(), Self::Error> {
-        // For ChipFiringGraph, "training" could mean:
-        // 1. Setting up the graph structure (already done in constructor)
-        // 2. Adjusting the initial configuration based on inputs
-        
-        if let Some(first) = data.first() {
-            self.set_configuration(first.clone())?;
+    /// Returns the number of edges between vertices `i` and `j`
+    pub fn edge_multiplicity(&self, i: usize, j: usize) -> u32 {
+        if i >= self.num_vertices {
+            return 0;
+        }
+        let range = self.neighbor_offsets[i]..self.neighbor_offsets[i + 1];
+        match self.neighbor_indices[range.clone()].binary_search(&j) {
+            Ok(k) => self.edge_multiplicities[range.start + k],
+            Err(_) => 0,
         }
-        
-        Ok(())
     }
-    
+}
+
+// Implement the DynamicalSystem trait for ChipFiringGraph; a step fires one vertex or,
+// in parallel mode, every active vertex
+impl DynamicalSystem for ChipFiringGraph {
+    type State = Vec<i32>;
+    type Error = ChipFiringError;
+
     fn size(&self) -> usize {
         self.num_vertices
     }
+
+    fn state(&self) -> Self::State {
+        self.configuration.clone()
+    }
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.set_configuration(state)
+    }
+
+    fn step(&mut self, rng: &mut impl Rng) -> Result<(), Self::Error> {
+        ChipFiringGraph::step(self, rng)
+    }
+
+    fn is_stable(&self) -> bool {
+        ChipFiringGraph::is_stable(self)
+    }
+
+    fn run(&mut self, max_steps: usize, rng: &mut impl Rng) -> Result<usize, Self::Error> {
+        ChipFiringGraph::run(self, max_steps, rng)
+    }
+
+    fn history(&self) -> Vec<Self::State> {
+        self.history.states().cloned().collect()
+    }
 }
 
 #[cfg(test)]
@@ -589,4 +1437,291 @@ mod tests {
         // Check degrees: each corner should have degree 2
         assert_eq!(graph.degrees, vec![2, 2, 2, 2]);
     }
+    
+    #[test]
+    fn test_multi_edges_and_dense_conversion() {
+        // Double edge 0-1 and single edge 1-2
+        let edges = vec![(0, 1), (1, 0), (1, 2)];
+        let graph = ChipFiringGraph::from_edge_list(&edges, 3, vec![0, 0, 0]).unwrap();
+        
+        assert_eq!(graph.degrees, vec![2, 3, 1]);
+        assert_eq!(graph.edge_multiplicity(0, 1), 2);
+        assert_eq!(graph.edge_multiplicity(0, 2), 0);
+        assert_eq!(graph.neighbors(1), vec![0, 2]);
+        
+        // The dense constructor produces the same structure
+        let dense = vec![vec![0, 2, 0], vec![2, 0, 1], vec![0, 1, 0]];
+        let from_dense = ChipFiringGraph::new(dense, vec![0, 0, 0]).unwrap();
+        assert_eq!(from_dense.degrees, graph.degrees);
+        assert_eq!(from_dense.neighbor_edges(1).collect::<Vec<_>>(), vec![(0, 2), (2, 1)]);
+
+        // Degrees and merged multiplicities past u32::MAX are rejected rather than wrapped
+        let heavy = vec![vec![(1, 4_000_000_000), (1, 4_000_000_000)], vec![(0, 1)]];
+        assert!(matches!(
+            ChipFiringGraph::from_neighbor_lists(heavy, vec![0, 0]),
+            Err(ChipFiringError::InvalidGraphStructure(_))
+        ));
+    }
+    
+    #[test]
+    fn test_stabilize_matches_run() {
+        // Path 0-1-2-3 with a pile at one end stabilizes to the same state either way
+        let edges = vec![(0, 1), (1, 2), (2, 3)];
+        let config = vec![1, 1, 0, 0];
+        
+        let mut stepped = ChipFiringGraph::from_edge_list(&edges, 4, config.clone()).unwrap();
+        let mut fast = ChipFiringGraph::from_edge_list(&edges, 4, config).unwrap();
+        
+        let mut rng = thread_rng();
+        stepped.run(1000, &mut rng).unwrap();
+        let firings = fast.stabilize(1000).unwrap();
+        
+        assert!(fast.is_stable());
+        assert!(firings > 0);
+        assert_eq!(fast.configuration, stepped.configuration);
+        // Only the initial and final configurations are recorded
+        assert_eq!(fast.history.len(), 2);
+        assert_eq!(fast.history.last_iteration(), firings);
+        // Both methods perform the same firings
+        assert_eq!(fast.firing_counts, stepped.firing_counts);
+    }
+    
+    #[test]
+    fn test_stochastic_firing_reaches_same_stable_state() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        
+        let run = |probability: f64| {
+            let mut graph = ChipFiringGraph::new_sandpile(5, 5).unwrap();
+            graph.add_chips(12, 12).unwrap();
+            graph.update_mode = UpdateMode::Parallel;
+            graph.firing_probability = probability;
+            let steps = graph.run(10_000, &mut StdRng::seed_from_u64(3)).unwrap();
+            (graph.configuration, steps)
+        };
+        
+        let (deterministic, btw_steps) = run(1.0);
+        let (stochastic, stochastic_steps) = run(0.3);
+        assert_eq!(stochastic, deterministic);
+        assert!(stochastic_steps > btw_steps);
+    }
+    
+    #[test]
+    fn test_thresholds_and_height_cap() {
+        // Path 0 - 1 - 2
+        let mut graph = ChipFiringGraph::from_edge_list(&[(0, 1), (1, 2)], 3, vec![0, 2, 0]).unwrap();
+        assert_eq!(graph.threshold(1), 2);
+        assert!(matches!(graph.set_threshold(1, 1), Err(ChipFiringError::InvalidThreshold(_))));
+        graph.set_threshold(1, 3).unwrap();
+        assert!(graph.has_custom_thresholds());
+        assert!(graph.is_stable());
+        
+        graph.add_chips(1, 1).unwrap();
+        assert_eq!(graph.active_vertices(), vec![1]);
+        graph.fire_vertex(1).unwrap();
+        // A firing still sends one chip per edge, leaving the rest behind
+        assert_eq!(graph.configuration, vec![1, 1, 1]);
+        
+        graph.max_height = Some(1);
+        graph.add_chips(0, 2).unwrap();
+        assert_eq!(graph.configuration[0], 1);
+        graph.reset_thresholds();
+        assert!(!graph.has_custom_thresholds());
+        
+        // Avalanches are triggered with a capped chip too
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        let mut full = ChipFiringGraph::from_edge_list(&[(0, 1)], 2, vec![1, 0]).unwrap();
+        full.set_threshold(0, 5).unwrap();
+        full.max_height = Some(1);
+        let firings = full.trigger_avalanche(0, 10, &mut StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!((firings, full.configuration[0]), (0, 1));
+    }
+    
+    #[test]
+    fn test_add_chips_to_records_one_step() {
+        let mut graph = ChipFiringGraph::new_grid(3, 1, vec![0; 3]).unwrap();
+        graph.max_height = Some(4);
+        graph.add_chips_to(&[0, 2], 3).unwrap();
+        graph.add_chips_to(&[0, 1], 3).unwrap();
+        assert_eq!(graph.configuration, vec![4, 3, 3]);
+        assert_eq!(graph.history.len(), 3);
+        assert!(graph.add_chips_to(&[3], 1).is_err());
+        assert!(graph.add_chips_to(&[0], -1).is_err());
+    }
+    
+    #[test]
+    fn test_m_matrix_dissipates_without_sink() {
+        // Path 0 - 1 - 2 where the ends leak chips when they fire
+        let matrix = vec![vec![3, -1, 0], vec![-1, 2, -1], vec![0, -1, 2]];
+        let mut graph = ChipFiringGraph::from_m_matrix(&matrix, vec![0, 6, 0]).unwrap();
+        assert_eq!((graph.firing_loss(0), graph.dissipation(2)), (3, 1));
+        assert_eq!(graph.threshold(0), 3);
+        assert_eq!(graph.laplacian()[(0, 0)], 3.0);
+        assert!(!graph.has_custom_thresholds());
+        
+        graph.stabilize(1000).unwrap();
+        assert!(graph.is_stable());
+        assert!(graph.total_chips() < 6);
+        
+        assert!(ChipFiringGraph::from_m_matrix(&[vec![1, 1], vec![0, 0]], vec![0; 2]).is_err());
+        assert!(ChipFiringGraph::from_m_matrix(&[vec![0, -1], vec![-1, 1]], vec![0; 2]).is_err());
+    }
+    
+    #[test]
+    fn test_avalanche_waves_reach_stable_configuration() {
+        let mut graph = ChipFiringGraph::new_sandpile(5, 5).unwrap();
+        graph.set_configuration([vec![3; 25], vec![0]].concat()).unwrap();
+        graph.add_chips(12, 1).unwrap();
+        let mut direct = graph.clone();
+        direct.stabilize(100_000).unwrap();
+        
+        let waves = graph.avalanche_waves(12, 100_000).unwrap();
+        assert!(waves.len() > 1);
+        assert!(graph.is_stable());
+        assert_eq!(graph.configuration, direct.configuration);
+        // Every wave starts at the seed and topples each vertex at most once
+        assert!(waves.iter().all(|wave| wave[0] == 12));
+        let topplings: usize = waves.iter().map(Vec::len).sum();
+        assert_eq!(topplings as u64, graph.firing_counts.iter().sum::<u64>());
+    }
+    
+    #[test]
+    fn test_seeded_random_selection_is_reproducible() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        
+        let edges = vec![(0, 1), (1, 2), (2, 3), (3, 0)];
+        let run = |seed: u64| {
+            let mut graph = ChipFiringGraph::from_edge_list(&edges, 4, vec![3, 2, 2, 1]).unwrap();
+            graph.selection_strategy = VertexSelectionStrategy::RandomActive;
+            graph.run(50, &mut StdRng::seed_from_u64(seed)).unwrap();
+            graph.history.into_states()
+        };
+        
+        assert_eq!(run(7), run(7));
+    }
+    
+    #[test]
+    fn test_firing_log_reproduces_history() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        
+        let mut graph = ChipFiringGraph::from_edge_list(&[(0, 1), (1, 2), (2, 3), (3, 0), (0, 2)], 4, vec![0; 4]).unwrap();
+        graph.set_sink(Some(3)).unwrap();
+        graph.update_mode = UpdateMode::Parallel;
+        graph.set_firing_log(true);
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..30 {
+            graph.drop_chip(DriveTarget::Random, &mut rng).unwrap();
+            graph.run(50, &mut rng).unwrap();
+        }
+        graph.stabilize(1000).unwrap();
+
+        let log = graph.firing_log().unwrap();
+        assert_eq!(log.to_history(), graph.history);
+        assert_eq!(log.last(), &graph.configuration);
+        assert!(log.delta(log.len() - 1).is_some());
+    }
+
+    #[test]
+    fn test_history_stride() {
+        let edges = vec![(0, 1), (1, 2), (2, 3)];
+        let mut graph = ChipFiringGraph::from_edge_list(&edges, 4, vec![1, 1, 0, 0]).unwrap();
+        graph.history_stride = 2;
+        
+        let mut rng = thread_rng();
+        let steps = graph.run(1000, &mut rng).unwrap();
+        
+        // Initial state, every second step, and the final stable state
+        assert!(graph.history.len() <= steps / 2 + 2);
+        assert_eq!(graph.history.last(), Some(&graph.configuration));
+        // Sampled entries keep the step they were recorded at
+        assert_eq!(graph.history.last_iteration(), steps);
+        assert!(graph.history.iter().all(|entry| entry.iteration % 2 == 0 || entry.iteration == steps));
+    }
+    
+    #[test]
+    fn test_sink_absorbs_chips() {
+        // Path 0 - 1 - 2 with the sink at the end: without it 4 chips circulate forever
+        let mut graph = ChipFiringGraph::from_edge_list(&[(0, 1), (1, 2)], 3, vec![2, 2, 0]).unwrap();
+        graph.set_sink(Some(2)).unwrap();
+        assert!(graph.set_sink(Some(3)).is_err());
+        
+        graph.stabilize(1000).unwrap();
+        assert!(graph.is_stable());
+        assert!(graph.configuration[2] > 0);
+        assert_eq!(graph.total_chips(), 4);
+        assert!(graph.fire_vertex(2).is_err());
+        
+        // Dropping chips never lands on the sink
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            assert_ne!(graph.drop_chip(DriveTarget::Random, &mut rng).unwrap(), 2);
+        }
+        assert_eq!(graph.drop_chip(DriveTarget::Fixed(1), &mut rng).unwrap(), 1);
+        assert!(graph.drop_chip(DriveTarget::Fixed(5), &mut rng).is_err());
+    }
+    
+    #[test]
+    fn test_sandpile_topples_into_sink() {
+        let mut graph = ChipFiringGraph::new_sandpile(3, 3).unwrap();
+        assert_eq!(graph.num_vertices, 10);
+        assert_eq!(graph.sink(), Some(9));
+        assert!(graph.degrees[..9].iter().all(|&d| d == 4));
+        
+        // A corner toppling sends two of its four chips into the sink
+        graph.add_chips(0, 4).unwrap();
+        assert_eq!(graph.stabilize(100).unwrap(), 1);
+        assert_eq!(graph.configuration[1], 1);
+        assert_eq!(graph.configuration[3], 1);
+        assert_eq!(graph.configuration[9], 2);
+    }
+    
+    #[test]
+    fn test_measure_stabilization() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        let mut rng = StdRng::seed_from_u64(5);
+        let edges: Vec<(usize, usize)> = (0..6).map(|i| (i, (i + 1) % 6)).collect();
+        let mut graph = ChipFiringGraph::from_edge_list(&edges, 6, vec![0; 6]).unwrap();
+        
+        let configuration = graph.random_configuration(9, &mut rng);
+        assert_eq!(configuration.iter().sum::<i32>(), 9);
+        
+        // A cycle with fewer chips than edges always stabilizes, with more it never does
+        let sparse = graph.measure_stabilization(0.5, 10, 1000, &mut rng);
+        assert_eq!(sparse.stabilized_fraction, 1.0);
+        let dense = graph.measure_stabilization(1.5, 10, 1000, &mut rng);
+        assert_eq!(dense.stabilized_fraction, 0.0);
+        
+        // With a sink everything stabilizes
+        graph.set_sink(Some(0)).unwrap();
+        let driven = graph.measure_stabilization(1.5, 10, 1000, &mut rng);
+        assert_eq!(driven.stabilized_fraction, 1.0);
+        assert!(driven.mean_firings > 0.0);
+        assert_eq!(graph.total_chips(), 0);
+    }
+    
+    #[test]
+    fn test_weighted_edge_list() {
+        let graph = ChipFiringGraph::from_weighted_edge_list(&[(0, 1, 3), (1, 2, 1), (1, 0, 1)], 3, vec![0; 3]).unwrap();
+        assert_eq!(graph.edge_multiplicity(0, 1), 4);
+        assert_eq!(graph.edge_multiplicity(1, 0), 4);
+        assert_eq!(graph.degrees, vec![4, 5, 1]);
+    }
+    
+    #[test]
+    fn test_firing_subtracts_laplacian_row() {
+        let mut graph = ChipFiringGraph::from_weighted_edge_list(&[(0, 1, 2), (1, 2, 1), (2, 2, 1)], 3, vec![0, 5, 0]).unwrap();
+        let laplacian = graph.laplacian();
+        let before = graph.configuration.clone();
+        graph.fire_vertex(1).unwrap();
+        for (i, (&after, &was)) in graph.configuration.iter().zip(&before).enumerate() {
+            assert_eq!(after as f64, was as f64 - laplacian[(1, i)]);
+        }
+        assert_eq!(laplacian.row_sum().iter().map(|x| x.abs()).sum::<f64>(), 0.0);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,251 @@
+use eframe::wgpu;
+
+use super::ChipFiringGraph;
+use crate::graphics::compute::{bytes_to_i32, bytes_to_u32, i32_bytes, u32_bytes, ComputeError, GpuContext};
+use crate::graphics::pipeline::{Pipeline, PipelineConfig};
+
+/// Threads per workgroup; must match `@workgroup_size` in the shader
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Most workgroups a single dispatch may launch along one dimension
+const MAX_WORKGROUPS: u32 = 65_535;
+
+/// One thread per vertex and superstep. Every active vertex topples as often as its
+/// chips allow, and each vertex gathers the chips its in-neighbours send it, so no
+/// two threads write the same cell. Vertices with a threshold of zero (the sink, and
+/// vertices without edges) never topple.
+const TOPPLE_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> offsets: array<u32>;
+@group(0) @binding(1) var<storage, read> sources: array<u32>;
+@group(0) @binding(2) var<storage, read> multiplicities: array<u32>;
+@group(0) @binding(3) var<storage, read> thresholds: array<u32>;
+@group(0) @binding(4) var<storage, read> chips_in: array<i32>;
+@group(0) @binding(5) var<storage, read_write> chips_out: array<i32>;
+@group(0) @binding(6) var<storage, read_write> firings: array<u32>;
+@group(0) @binding(7) var<storage, read_write> toppled: atomic<u32>;
+
+fn topplings(v: u32) -> i32 {
+    let threshold = i32(thresholds[v]);
+    if (threshold == 0 || chips_in[v] < threshold) {
+        return 0;
+    }
+    return chips_in[v] / threshold;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = arrayLength(&chips_in);
+    let i = id.x;
+    if (i >= n) {
+        return;
+    }
+    let own = topplings(i);
+    var chips = chips_in[i] - own * i32(thresholds[i]);
+    for (var k = offsets[i]; k < offsets[i + 1u]; k = k + 1u) {
+        chips = chips + i32(multiplicities[k]) * topplings(sources[k]);
+    }
+    chips_out[i] = chips;
+    if (own > 0) {
+        firings[i] = firings[i] + u32(own);
+        atomicAdd(&toppled, 1u);
+    }
+}
+"#;
+
+/// Incoming edges of every vertex in CSR form: (offsets, sources, multiplicities),
+/// with the edges into vertex i at `offsets[i]..offsets[i + 1]`
+fn incoming_edges(graph: &ChipFiringGraph) -> (Vec<u32>, Vec<u32>, Vec<u32>) {
+    let n = graph.num_vertices;
+    let mut incoming: Vec<Vec<(u32, u32)>> = vec![Vec::new(); n];
+    for source in 0..n {
+        for (target, count) in graph.neighbor_edges(source) {
+            incoming[target].push((source as u32, count));
+        }
+    }
+    let mut offsets = Vec::with_capacity(n + 1);
+    offsets.push(0);
+    let (mut sources, mut multiplicities) = (Vec::new(), Vec::new());
+    for edges in incoming {
+        for (source, count) in edges {
+            sources.push(source);
+            multiplicities.push(count);
+        }
+        offsets.push(sources.len() as u32);
+    }
+    (offsets, sources, multiplicities)
+}
+
+/// Result of stabilizing a configuration on the GPU
+#[derive(Debug, Clone)]
+pub struct GpuStabilization {
+    /// The configuration after the last superstep
+    pub configuration: Vec<i32>,
+    /// Topplings of each vertex
+    pub firing_counts: Vec<u32>,
+    pub supersteps: usize,
+    /// false if `max_supersteps` ran out first
+    pub stable: bool,
+}
+
+impl GpuStabilization {
+    pub fn total_firings(&self) -> u64 {
+        self.firing_counts.iter().map(|&c| c as u64).sum()
+    }
+}
+
+/// Parallel stabilization of a chip firing graph in a wgpu compute kernel. By the
+/// abelian property the stable configuration, and how often each vertex fires, do
+/// not depend on the firing order, so toppling every active vertex at once reaches
+/// the same result as `ChipFiringGraph::stabilize`.
+///
+/// The graph is uploaded once; each execution uploads a configuration and runs
+/// supersteps in batches until one ends without a toppling.
+pub struct GpuChipFiringStabilizer {
+    context: GpuContext,
+    num_vertices: usize,
+    pipeline: wgpu::ComputePipeline,
+    offsets: wgpu::Buffer,
+    sources: wgpu::Buffer,
+    multiplicities: wgpu::Buffer,
+    thresholds: wgpu::Buffer,
+
+    /// Supersteps before giving up on graphs that never stabilize
+    pub max_supersteps: usize,
+    /// Supersteps submitted between checks for stability
+    pub batch_size: usize,
+}
+
+impl GpuChipFiringStabilizer {
+    pub fn new(context: &GpuContext, graph: &ChipFiringGraph) -> Result<Self, ComputeError> {
+        let n = graph.num_vertices;
+        if n == 0 {
+            return Err(ComputeError::DimensionMismatch("The graph has no vertices".to_string()));
+        }
+        if (n as u32).div_ceil(WORKGROUP_SIZE) > MAX_WORKGROUPS {
+            return Err(ComputeError::TooLarge(format!("{} vertices need more workgroups than one dispatch allows", n)));
+        }
+
+        if graph.has_custom_thresholds() || graph.max_height.is_some() {
+            // The kernel topples at the degree and has no cap to apply
+            return Err(ComputeError::Unavailable(
+                "Custom firing thresholds and height caps are only simulated on the CPU".to_string(),
+            ));
+        }
+
+        let (offsets, mut sources, mut multiplicities) = incoming_edges(graph);
+        // Bindings may not be empty, so an edgeless graph gets one unused entry
+        if sources.is_empty() {
+            sources.push(0);
+            multiplicities.push(0);
+        }
+        context.check_buffer_size((sources.len() * 4) as u64)?;
+        let thresholds: Vec<u32> =
+            (0..n).map(|v| if graph.sink() == Some(v) { 0 } else { graph.firing_loss(v) }).collect();
+
+        Ok(GpuChipFiringStabilizer {
+            context: context.clone(),
+            num_vertices: n,
+            pipeline: context.compute_pipeline("chip_firing_topple", TOPPLE_SHADER),
+            offsets: context.storage_buffer("chip_firing_offsets", &u32_bytes(offsets)),
+            sources: context.storage_buffer("chip_firing_sources", &u32_bytes(sources)),
+            multiplicities: context.storage_buffer("chip_firing_multiplicities", &u32_bytes(multiplicities)),
+            thresholds: context.storage_buffer("chip_firing_thresholds", &u32_bytes(thresholds)),
+            max_supersteps: 1_000_000,
+            batch_size: 256,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.num_vertices
+    }
+}
+
+impl Pipeline for GpuChipFiringStabilizer {
+    type Input = Vec<i32>;
+    type Output = GpuStabilization;
+    type Error = ComputeError;
+
+    fn execute(&self, input: &Vec<i32>) -> Result<GpuStabilization, ComputeError> {
+        if input.len() != self.num_vertices {
+            return Err(ComputeError::DimensionMismatch(format!(
+                "Configuration has {} vertices but the graph has {}",
+                input.len(),
+                self.num_vertices
+            )));
+        }
+        let context = &self.context;
+        let chips = i32_bytes(input.iter().copied());
+        let buffers = [context.storage_buffer("chips_a", &chips), context.storage_buffer("chips_b", &chips)];
+        let firings = context.storage_buffer("chip_firing_counts", &vec![0; chips.len()]);
+        let toppled = context.storage_buffer("chip_firing_toppled", &[0; 4]);
+        let bind = |label, chips_in: &wgpu::Buffer, chips_out: &wgpu::Buffer| {
+            context.bind_buffers(
+                label,
+                &self.pipeline,
+                &[&self.offsets, &self.sources, &self.multiplicities, &self.thresholds, chips_in, chips_out, &firings, &toppled],
+            )
+        };
+        // Even supersteps read A and write B, odd ones the other way round
+        let bind_groups = [bind("chip_firing_ab", &buffers[0], &buffers[1]), bind("chip_firing_ba", &buffers[1], &buffers[0])];
+
+        let workgroups = (self.num_vertices as u32).div_ceil(WORKGROUP_SIZE);
+        let mut supersteps = 0;
+        let mut stable = false;
+        while supersteps < self.max_supersteps {
+            let batch = self.batch_size.max(1).min(self.max_supersteps - supersteps);
+            let mut encoder = context.encoder("chip_firing_supersteps");
+            for step in 0..batch {
+                // Only the last superstep of a batch is counted: if nothing topples
+                // there, the configuration is stable
+                if step + 1 == batch {
+                    encoder.clear_buffer(&toppled, 0, None);
+                }
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("chip_firing_topple"),
+                    timestamp_writes: None,
+                });
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_groups[(supersteps + step) % 2], &[]);
+                pass.dispatch_workgroups(workgroups, 1, 1);
+            }
+            supersteps += batch;
+            let count = bytes_to_u32(&context.submit_and_read(encoder, &toppled)?);
+            if count.first() == Some(&0) {
+                stable = true;
+                break;
+            }
+        }
+
+        let configuration = bytes_to_i32(&context.submit_and_read(context.encoder("chip_firing_result"), &buffers[supersteps % 2])?);
+        let firing_counts = bytes_to_u32(&context.submit_and_read(context.encoder("chip_firing_counts"), &firings)?);
+        Ok(GpuStabilization { configuration, firing_counts, supersteps, stable })
+    }
+
+    /// Grid sandpiles have a sink besides the `width * height` cells, so either count
+    /// is accepted
+    fn configure(&mut self, config: &PipelineConfig) -> Result<(), ComputeError> {
+        let cells = (config.width * config.height) as usize;
+        if cells != self.num_vertices && cells + 1 != self.num_vertices {
+            return Err(ComputeError::DimensionMismatch(format!(
+                "A {}x{} grid has {} cells but the graph has {} vertices",
+                config.width, config.height, cells, self.num_vertices
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incoming_edges_transpose_directed_graph() {
+        // 0 -> 1 twice, 2 -> 1, 1 -> 0
+        let graph = ChipFiringGraph::from_neighbor_lists(vec![vec![(1, 2)], vec![(0, 1)], vec![(1, 1)]], vec![0; 3]).unwrap();
+        let (offsets, sources, multiplicities) = incoming_edges(&graph);
+        assert_eq!(offsets, vec![0, 1, 3, 3]);
+        assert_eq!(sources, vec![1, 0, 2]);
+        assert_eq!(multiplicities, vec![1, 2, 1]);
+    }
+}
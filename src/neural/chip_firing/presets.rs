@@ -0,0 +1,119 @@
+use super::{ChipFiringError, ChipFiringGraph, UpdateMode};
+
+/// Chips dropped on the center of the `CenterPile` grid
+const CENTER_PILE_CHIPS: i32 = 4000;
+
+/// Classic starting configurations on grid graphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChipFiringPreset {
+    /// A tall pile at the center of a large grid, which stabilizes into the fractal
+    /// pattern of the abelian sandpile
+    CenterPile,
+    /// Every other cell exactly at its threshold: under parallel updates the two
+    /// sublattices fire in turn forever, a cycle of period 2
+    Checkerboard,
+    /// The maximal stable configuration with a sink in the corner, plus one chip in the
+    /// center: the avalanche reaches every cell
+    MaximalStablePlusOne,
+}
+
+impl ChipFiringPreset {
+    pub const ALL: [ChipFiringPreset; 3] = [
+        ChipFiringPreset::CenterPile,
+        ChipFiringPreset::Checkerboard,
+        ChipFiringPreset::MaximalStablePlusOne,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            ChipFiringPreset::CenterPile => "Center Pile",
+            ChipFiringPreset::Checkerboard => "Checkerboard",
+            ChipFiringPreset::MaximalStablePlusOne => "Maximal Stable + 1",
+        }
+    }
+
+    pub const fn description(self) -> &'static str {
+        match self {
+            ChipFiringPreset::CenterPile => "4000 chips on the center of a 61 x 61 grid; stabilize to see the sandpile fractal",
+            ChipFiringPreset::Checkerboard => "Every other cell at its degree, updated in parallel: the sublattices fire in turn forever",
+            ChipFiringPreset::MaximalStablePlusOne => "Every cell one chip short of firing and a sink in the corner, plus one chip that sets off a system-wide avalanche",
+        }
+    }
+
+    /// Firings "Stabilize" should be allowed to stabilize the preset
+    pub fn firings_to_stabilize(self) -> usize {
+        match self {
+            // About 300,000 firings
+            ChipFiringPreset::CenterPile => 1_000_000,
+            ChipFiringPreset::Checkerboard | ChipFiringPreset::MaximalStablePlusOne => 100_000,
+        }
+    }
+
+    /// Width and height of the grid the preset is laid out on
+    pub fn grid_size(self) -> (usize, usize) {
+        match self {
+            ChipFiringPreset::CenterPile => (61, 61),
+            ChipFiringPreset::Checkerboard => (12, 12),
+            ChipFiringPreset::MaximalStablePlusOne => (15, 15),
+        }
+    }
+
+    /// The grid graph with the preset's configuration, sink and update mode
+    pub fn build(self) -> Result<ChipFiringGraph, ChipFiringError> {
+        let (width, height) = self.grid_size();
+        let mut graph = ChipFiringGraph::new_grid(width, height, vec![0; width * height])?;
+        let center = (height / 2) * width + width / 2;
+        let configuration: Vec<i32> = match self {
+            ChipFiringPreset::CenterPile => {
+                let mut configuration = vec![0; width * height];
+                configuration[center] = CENTER_PILE_CHIPS;
+                configuration
+            },
+            ChipFiringPreset::Checkerboard => {
+                graph.update_mode = UpdateMode::Parallel;
+                (0..width * height)
+                    .map(|v| if (v % width + v / width) % 2 == 0 { graph.threshold(v) as i32 } else { 0 })
+                    .collect()
+            },
+            ChipFiringPreset::MaximalStablePlusOne => {
+                graph.set_sink(Some(0))?;
+                let mut configuration: Vec<i32> = (0..width * height).map(|v| graph.threshold(v) as i32 - 1).collect();
+                configuration[0] = 0;
+                configuration[center] += 1;
+                configuration
+            },
+        };
+        graph.set_configuration(configuration)?;
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_presets_behave_as_described() {
+        let mut pile = ChipFiringPreset::CenterPile.build().unwrap();
+        pile.stabilize(10_000_000).unwrap();
+        assert!(pile.is_stable());
+        // The pile spreads without reaching the boundary, so no chip is lost
+        assert_eq!(pile.total_chips(), CENTER_PILE_CHIPS);
+        assert_eq!(pile.configuration[0], 0);
+
+        let mut checkerboard = ChipFiringPreset::Checkerboard.build().unwrap();
+        let initial = checkerboard.configuration.clone();
+        let mut rng = StdRng::seed_from_u64(0);
+        checkerboard.step(&mut rng).unwrap();
+        assert_ne!(checkerboard.configuration, initial);
+        checkerboard.step(&mut rng).unwrap();
+        assert_eq!(checkerboard.configuration, initial);
+
+        let mut maximal = ChipFiringPreset::MaximalStablePlusOne.build().unwrap();
+        maximal.stabilize(1_000_000).unwrap();
+        assert!(maximal.is_stable());
+        assert!(maximal.firing_counts.iter().skip(1).all(|&count| count > 0));
+    }
+}
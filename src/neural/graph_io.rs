@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use super::chip_firing::{ChipFiringError, ChipFiringGraph, VertexMetadata};
+use crate::error::RaumError;
+
+/// Reads a graph file: DOT for `.dot` and `.gv` files, an edge list otherwise
+pub fn read_graph_file(path: &Path) -> Result<ChipFiringGraph, RaumError> {
+    let text = std::fs::read_to_string(path)?;
+    let is_dot = matches!(path.extension().and_then(|ext| ext.to_str()), Some("dot") | Some("gv"));
+    let graph = if is_dot { parse_dot(&text)? } else { parse_edge_list(&text)? };
+    Ok(graph)
+}
+
+/// Parses a plain edge-list file into a Chip Firing Graph.
+///
+/// Each non-empty line holds `from to [multiplicity]`, separated by whitespace or commas.
+/// Lines starting with `#` or `%` are comments. If every vertex token is an integer and
+/// none exceeds twice the number of edges, the tokens are used directly as indices.
+/// Otherwise vertices are numbered in order of first appearance and labelled with their
+/// names, so sparse ids such as `0 4000000000` don't allocate a vertex per skipped
+/// index. All vertices start with zero chips.
+pub fn parse_edge_list(text: &str) -> Result<ChipFiringGraph, ChipFiringError> {
+    let mut raw_edges: Vec<(String, String, u32)> = Vec::new();
+
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('%') {
+            continue;
+        }
+
+        let parts: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|p| !p.is_empty())
+            .collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(ChipFiringError::InvalidGraphStructure(format!(
+                "Line {}: expected 'from to [multiplicity]', got '{}'",
+                line_no + 1, line
+            )));
+        }
+
+        let multiplicity = match parts.get(2) {
+            Some(m) => m.parse::<u32>().map_err(|_| {
+                ChipFiringError::InvalidGraphStructure(format!(
+                    "Line {}: invalid edge multiplicity '{}'",
+                    line_no + 1, m
+                ))
+            })?,
+            None => 1,
+        };
+
+        raw_edges.push((parts[0].to_string(), parts[1].to_string(), multiplicity));
+    }
+
+    if raw_edges.is_empty() {
+        return Err(ChipFiringError::InvalidGraphStructure("Edge list contains no edges".to_string()));
+    }
+
+    // Dense integer ids index the vertices directly
+    let max_index = 2 * raw_edges.len();
+    let all_numeric = raw_edges.iter().all(|(a, b, _)| {
+        [a, b].into_iter().all(|id| id.parse::<usize>().is_ok_and(|index| index <= max_index))
+    });
+
+    let mut ids = VertexIds::default();
+    let mut edges = Vec::with_capacity(raw_edges.len());
+    for (a, b, multiplicity) in &raw_edges {
+        let (from, to) = if all_numeric {
+            (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap())
+        } else {
+            (ids.index_of(a), ids.index_of(b))
+        };
+        edges.push((from, to, *multiplicity));
+    }
+
+    let num_vertices = if all_numeric {
+        edges.iter().map(|&(a, b, _)| a.max(b)).max().unwrap_or(0) + 1
+    } else {
+        ids.len()
+    };
+
+    let mut neighbor_lists = vec![Vec::new(); num_vertices];
+    for (from, to, multiplicity) in edges {
+        neighbor_lists[from].push((to, multiplicity));
+        neighbor_lists[to].push((from, multiplicity));
+    }
+
+    let mut graph = ChipFiringGraph::from_neighbor_lists(neighbor_lists, vec![0; num_vertices])?;
+    if !all_numeric {
+        ids.label_vertices(&mut graph)?;
+    }
+    Ok(graph)
+}
+
+/// Parses a Graphviz DOT document into a Chip Firing Graph.
+///
+/// Supports `graph` and `digraph` bodies with node statements, edge chains (`a -- b -- c`
+/// or `a -> b`), attribute lists and nested subgraphs (flattened). Undirected edges are
+/// added in both directions, directed edges only from tail to head. A node's initial chip
+/// count is read from its `chips` attribute, or from its `label` if that is an integer.
+///
+/// Vertices are labelled with their `name` attribute, a non-integer `label`, or otherwise
+/// their id if it is not an integer. A `color` attribute of the form `#rrggbb` becomes
+/// the vertex color tag.
+pub fn parse_dot(text: &str) -> Result<ChipFiringGraph, ChipFiringError> {
+    let tokens = tokenize_dot(text)?;
+    let mut pos = 0;
+
+    let err = |msg: String| ChipFiringError::InvalidGraphStructure(format!("DOT parse error: {}", msg));
+
+    // Header: [strict] (graph | digraph) [id] '{'
+    if matches!(tokens.get(pos), Some(DotToken::Id(s)) if s.eq_ignore_ascii_case("strict")) {
+        pos += 1;
+    }
+    let directed = match tokens.get(pos) {
+        Some(DotToken::Id(s)) if s.eq_ignore_ascii_case("graph") => false,
+        Some(DotToken::Id(s)) if s.eq_ignore_ascii_case("digraph") => true,
+        _ => return Err(err("expected 'graph' or 'digraph'".to_string())),
+    };
+    pos += 1;
+    if let Some(DotToken::Id(_)) = tokens.get(pos) {
+        pos += 1;
+    }
+    if tokens.get(pos) != Some(&DotToken::OpenBrace) {
+        return Err(err("expected '{' after graph header".to_string()));
+    }
+    pos += 1;
+
+    let mut ids = VertexIds::default();
+    let mut chips: HashMap<usize, i32> = HashMap::new();
+    let mut metadata: HashMap<usize, VertexMetadata> = HashMap::new();
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    let mut depth = 1;
+
+    while depth > 0 {
+        match tokens.get(pos) {
+            None => return Err(err("unexpected end of input, missing '}'".to_string())),
+            Some(DotToken::CloseBrace) => {
+                depth -= 1;
+                pos += 1;
+            }
+            Some(DotToken::OpenBrace) => {
+                depth += 1;
+                pos += 1;
+            }
+            Some(DotToken::Semicolon) | Some(DotToken::Comma) => pos += 1,
+            Some(DotToken::Id(keyword)) if keyword.eq_ignore_ascii_case("subgraph") => {
+                // Subgraph contents are flattened into the parent graph
+                pos += 1;
+                if let Some(DotToken::Id(_)) = tokens.get(pos) {
+                    pos += 1;
+                }
+            }
+            Some(DotToken::Id(keyword))
+                if ["graph", "node", "edge"].iter().any(|k| keyword.eq_ignore_ascii_case(k))
+                    && tokens.get(pos + 1) == Some(&DotToken::OpenBracket) =>
+            {
+                // Default attribute statement, not relevant for the graph structure
+                pos += 1;
+                parse_dot_attributes(&tokens, &mut pos)?;
+            }
+            Some(DotToken::Id(first)) => {
+                // Graph-level attribute assignment: id = id
+                if tokens.get(pos + 1) == Some(&DotToken::Equals) {
+                    pos += 3;
+                    continue;
+                }
+
+                let mut chain = vec![ids.index_of(first)];
+                let mut chain_directed = Vec::new();
+                pos += 1;
+                while let Some(DotToken::EdgeOp(is_directed)) = tokens.get(pos) {
+                    match tokens.get(pos + 1) {
+                        Some(DotToken::Id(next)) => {
+                            chain.push(ids.index_of(next));
+                            chain_directed.push(*is_directed);
+                            pos += 2;
+                        }
+                        _ => return Err(err("expected node id after edge operator".to_string())),
+                    }
+                }
+
+                let attributes = if tokens.get(pos) == Some(&DotToken::OpenBracket) {
+                    parse_dot_attributes(&tokens, &mut pos)?
+                } else {
+                    HashMap::new()
+                };
+
+                if chain.len() == 1 {
+                    // Node statement: read the initial chip count
+                    let count = attributes
+                        .get("chips")
+                        .or_else(|| attributes.get("label"))
+                        .and_then(|v| v.trim().parse::<i32>().ok());
+                    if let Some(count) = count {
+                        chips.insert(chain[0], count);
+                    }
+                    
+                    let entry = metadata.entry(chain[0]).or_default();
+                    let label = attributes
+                        .get("name")
+                        .or_else(|| attributes.get("label").filter(|v| v.trim().parse::<i32>().is_err()));
+                    if let Some(label) = label {
+                        entry.label = Some(label.clone());
+                    }
+                    if let Some(color) = attributes.get("color").and_then(|c| parse_hex_color(c)) {
+                        entry.color = Some(color);
+                    }
+                } else {
+                    for (k, pair) in chain.windows(2).enumerate() {
+                        edges.push((pair[0], pair[1]));
+                        if !(directed && chain_directed[k]) {
+                            edges.push((pair[1], pair[0]));
+                        }
+                    }
+                }
+            }
+            Some(other) => return Err(err(format!("unexpected token {:?}", other))),
+        }
+    }
+
+    let num_vertices = ids.len();
+    if num_vertices == 0 {
+        return Err(err("graph contains no nodes".to_string()));
+    }
+
+    let mut neighbor_lists = vec![Vec::new(); num_vertices];
+    for (from, to) in edges {
+        neighbor_lists[from].push((to, 1));
+    }
+
+    let mut configuration = vec![0; num_vertices];
+    for (vertex, count) in chips {
+        configuration[vertex] = count;
+    }
+
+    let mut graph = ChipFiringGraph::from_neighbor_lists(neighbor_lists, configuration)?;
+    ids.label_vertices(&mut graph)?;
+    for (vertex, entry) in metadata {
+        let mut merged = graph.metadata(vertex).clone();
+        merged.label = entry.label.or(merged.label);
+        merged.color = entry.color;
+        graph.set_metadata(vertex, merged)?;
+    }
+    Ok(graph)
+}
+
+/// Writes a Chip Firing Graph as an undirected Graphviz DOT document.
+///
+/// Nodes are named by index and labelled with their current chip count (also stored in a
+/// `chips` attribute so the file can be imported again). Vertex labels and color tags are
+/// written as `name` and `color` attributes. Parallel edges are written once per
+/// multiplicity. If given, the random seed of the simulation is stored as a
+/// graph-level `seed` attribute.
+pub fn to_dot(graph: &ChipFiringGraph, seed: Option<u64>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "graph chip_firing {{");
+    if let Some(seed) = seed {
+        let _ = writeln!(out, "    seed={};", seed);
+    }
+    for (i, &chips) in graph.configuration.iter().enumerate() {
+        let mut attributes = format!("label=\"{}\", chips={}", chips, chips);
+        let metadata = graph.metadata(i);
+        if let Some(label) = &metadata.label {
+            let _ = write!(attributes, ", name=\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""));
+        }
+        if let Some([r, g, b]) = metadata.color {
+            let _ = write!(attributes, ", color=\"#{:02x}{:02x}{:02x}\"", r, g, b);
+        }
+        let _ = writeln!(out, "    {} [{}];", i, attributes);
+    }
+    for i in 0..graph.num_vertices {
+        for (j, count) in graph.neighbor_edges(i) {
+            // Each undirected edge is stored in both directions; write it from the lower index
+            if i <= j {
+                let count = if i == j { count / 2 } else { count };
+                for _ in 0..count {
+                    let _ = writeln!(out, "    {} -- {};", i, j);
+                }
+            }
+        }
+    }
+    let _ = writeln!(out, "}}");
+    out
+}
+
+/// Maps vertex names to indices in order of first appearance
+#[derive(Default)]
+struct VertexIds {
+    indices: HashMap<String, usize>,
+}
+
+impl VertexIds {
+    fn index_of(&mut self, name: &str) -> usize {
+        let next = self.indices.len();
+        *self.indices.entry(name.to_string()).or_insert(next)
+    }
+
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Labels each vertex with its name, unless the name is just the vertex index
+    fn label_vertices(&self, graph: &mut ChipFiringGraph) -> Result<(), ChipFiringError> {
+        for (name, &index) in &self.indices {
+            if name.parse::<usize>().ok() != Some(index) {
+                let metadata = VertexMetadata { label: Some(name.clone()), ..graph.metadata(index).clone() };
+                graph.set_metadata(index, metadata)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses a `#rrggbb` color
+fn parse_hex_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |k: usize| u8::from_str_radix(hex.get(2 * k..2 * k + 2)?, 16).ok();
+    Some([channel(0)?, channel(1)?, channel(2)?])
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DotToken {
+    Id(String),
+    /// Edge operator; `true` for `->`, `false` for `--`
+    EdgeOp(bool),
+    OpenBrace,
+    CloseBrace,
+    OpenBracket,
+    CloseBracket,
+    Equals,
+    Semicolon,
+    Comma,
+}
+
+/// Splits a DOT document into tokens, dropping comments
+fn tokenize_dot(text: &str) -> Result<Vec<DotToken>, ChipFiringError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '{' => { tokens.push(DotToken::OpenBrace); i += 1; }
+            '}' => { tokens.push(DotToken::CloseBrace); i += 1; }
+            '[' => { tokens.push(DotToken::OpenBracket); i += 1; }
+            ']' => { tokens.push(DotToken::CloseBracket); i += 1; }
+            '=' => { tokens.push(DotToken::Equals); i += 1; }
+            ';' => { tokens.push(DotToken::Semicolon); i += 1; }
+            ',' => { tokens.push(DotToken::Comma); i += 1; }
+            '-' if chars.get(i + 1) == Some(&'-') => { tokens.push(DotToken::EdgeOp(false)); i += 2; }
+            '-' if chars.get(i + 1) == Some(&'>') => { tokens.push(DotToken::EdgeOp(true)); i += 2; }
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        i += 1;
+                    }
+                    value.push(chars[i]);
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(ChipFiringError::InvalidGraphStructure(
+                        "DOT parse error: unterminated string".to_string()
+                    ));
+                }
+                i += 1;
+                tokens.push(DotToken::Id(value));
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                    i += 1;
+                }
+                tokens.push(DotToken::Id(chars[start..i].iter().collect()));
+            }
+            _ => {
+                return Err(ChipFiringError::InvalidGraphStructure(format!(
+                    "DOT parse error: unexpected character '{}'", c
+                )));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses an attribute list `[a=b, c=d; ...]` starting at `pos` (which must be '[')
+fn parse_dot_attributes(tokens: &[DotToken], pos: &mut usize) -> Result<HashMap<String, String>, ChipFiringError> {
+    let mut attributes = HashMap::new();
+    *pos += 1; // Skip '['
+
+    loop {
+        match tokens.get(*pos) {
+            Some(DotToken::CloseBracket) => {
+                *pos += 1;
+                // Consecutive attribute lists are merged
+                if tokens.get(*pos) == Some(&DotToken::OpenBracket) {
+                    *pos += 1;
+                    continue;
+                }
+                return Ok(attributes);
+            }
+            Some(DotToken::Comma) | Some(DotToken::Semicolon) => *pos += 1,
+            Some(DotToken::Id(key)) => {
+                if tokens.get(*pos + 1) == Some(&DotToken::Equals) {
+                    if let Some(DotToken::Id(value)) = tokens.get(*pos + 2) {
+                        attributes.insert(key.to_lowercase(), value.clone());
+                        *pos += 3;
+                        continue;
+                    }
+                }
+                *pos += 1;
+            }
+            _ => {
+                return Err(ChipFiringError::InvalidGraphStructure(
+                    "DOT parse error: malformed attribute list".to_string()
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_edge_list_with_names_and_weights() {
+        let text = "# comment\na b\nb,c,2\n";
+        let graph = parse_edge_list(text).unwrap();
+
+        assert_eq!(graph.num_vertices, 3);
+        assert_eq!(graph.degrees, vec![1, 3, 2]);
+        assert_eq!(graph.edge_multiplicity(1, 2), 2);
+    }
+
+    #[test]
+    fn test_sparse_numeric_ids_are_renumbered() {
+        let graph = parse_edge_list("0 4000000000\n4000000000 7\n").unwrap();
+        assert_eq!(graph.num_vertices, 3);
+        assert_eq!(graph.degrees, vec![1, 2, 1]);
+        assert_eq!(graph.metadata(1).label.as_deref(), Some("4000000000"));
+
+        // Dense ids keep their indices, including unused ones
+        assert_eq!(parse_edge_list("0 2\n").unwrap().num_vertices, 3);
+    }
+
+    #[test]
+    fn test_dot_round_trip() {
+        let edges = vec![(0, 1), (1, 2), (2, 0), (0, 1)];
+        let graph = ChipFiringGraph::from_edge_list(&edges, 3, vec![4, 0, 1]).unwrap();
+
+        let dot = to_dot(&graph, Some(42));
+        assert!(dot.contains("seed=42;"));
+        let parsed = parse_dot(&dot).unwrap();
+
+        assert_eq!(parsed.num_vertices, 3);
+        assert_eq!(parsed.configuration, vec![4, 0, 1]);
+        assert_eq!(parsed.degrees, graph.degrees);
+        assert_eq!(parsed.edge_multiplicity(0, 1), 2);
+    }
+
+    #[test]
+    fn test_vertex_metadata_round_trip() {
+        let mut graph = parse_edge_list("alpha beta\nbeta gamma\n").unwrap();
+        assert_eq!(graph.vertex_name(1), "beta");
+        let tagged = VertexMetadata { label: Some("say \"hi\"".to_string()), color: Some([255, 0, 16]) };
+        graph.set_metadata(2, tagged.clone()).unwrap();
+
+        let parsed = parse_dot(&to_dot(&graph, None)).unwrap();
+        assert_eq!(parsed.vertex_name(0), "alpha");
+        assert_eq!(parsed.metadata(2), &tagged);
+        // Numeric ids are not used as labels
+        assert_eq!(parse_edge_list("0 1\n").unwrap().metadata(0).label, None);
+    }
+
+    #[test]
+    fn test_parse_dot_chains_and_directed_edges() {
+        let text = r#"
+            digraph G {
+                node [shape=circle];
+                "x" [label="3"];
+                x -> y -> z;
+                subgraph cluster { z -> x }
+            }
+        "#;
+        let graph = parse_dot(text).unwrap();
+
+        assert_eq!(graph.num_vertices, 3);
+        assert_eq!(graph.configuration, vec![3, 0, 0]);
+        assert_eq!(graph.degrees, vec![1, 1, 1]);
+        assert_eq!(graph.edge_multiplicity(1, 0), 0);
+    }
+}
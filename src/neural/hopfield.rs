@@ -1,9 +1,23 @@
 use std::error::Error;
 use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul};
+use rand::seq::SliceRandom;
 use rand::Rng;
 use nalgebra::{DMatrix};
 
-use super::NeuralNetwork;
+use super::annealing::AnnealingSchedule;
+use super::{AssociativeMemory, TrainableParameters};
+use crate::core::delta::{DeltaCodec, DeltaHistory};
+use crate::core::graph::Graph;
+use crate::core::history::SimulationHistory;
+use crate::core::metrics::{hamming_distance, overlap};
+use crate::core::noise::standard_normal;
+use crate::core::safetensors::{SafeTensors, Tensor};
+use crate::error::RaumError;
+
+pub mod analysis;
+pub mod gpu;
 
 // Define custom error types for clarity
 #[derive(Debug)]
@@ -11,6 +25,7 @@ pub enum HopfieldError {
     DimensionMismatch(String),
     InvalidStateValue(String),
     NotPerfectSquare(String), // Added error for printing non-square grids
+    InvalidSize(String),
 }
 
 impl fmt::Display for HopfieldError {
@@ -19,6 +34,7 @@ impl fmt::Display for HopfieldError {
             HopfieldError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
             HopfieldError::InvalidStateValue(msg) => write!(f, "Invalid state value: {}", msg),
             HopfieldError::NotPerfectSquare(msg) => write!(f, "Grid dimension error: {}", msg),
+            HopfieldError::InvalidSize(msg) => write!(f, "Invalid size: {}", msg),
         }
     }
 }
@@ -28,20 +44,513 @@ impl Error for HopfieldError {}
 // Enum to select the training rule
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TrainingRule {
+    /// W_ij = c Σ_p ξ_i^p ξ_j^p, where the factor c is set by `HebbianNormalization`
+    /// (1 by default, so a local field Σ_j W_ij s_j grows with N)
     Hebbian,
+    /// W_ij = 1/N Σ_{α,β} ξ_i^α (C⁻¹)_{αβ} ξ_j^β with the overlaps C = Ξ Ξᵀ / N; the 1/N
+    /// is built in, so fields stay of order one whatever the size
     PseudoInverse,
+    /// Storkey's incremental rule: pattern ν adds
+    /// (ξ_i ξ_j - ξ_i h_ji - h_ij ξ_j) / N with h_ij = Σ_{k≠i,j} W_ik ξ_k, which
+    /// subtracts the crosstalk of the patterns stored before. Local, like the Hebbian
+    /// rule, but with a higher capacity.
+    Storkey,
+}
+
+/// Factor of the Hebbian weights. Asynchronous dynamics feed the field Σ_j W_ij s_j
+/// to the sigmoid without rescaling, so with unnormalized weights the effective
+/// temperature 1/β shrinks as the network grows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HebbianNormalization {
+    /// W_ij = Σ_p ξ_i^p ξ_j^p
+    None,
+    /// W_ij = 1/N Σ_p ξ_i^p ξ_j^p, the standard formulation: the field of a retrieved
+    /// pattern is about 1 at any N
+    Neurons,
+    /// W_ij = 1/P Σ_p ξ_i^p ξ_j^p, the mean over the patterns
+    Patterns,
+}
+
+impl HebbianNormalization {
+    pub const ALL: [HebbianNormalization; 3] =
+        [HebbianNormalization::None, HebbianNormalization::Neurons, HebbianNormalization::Patterns];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HebbianNormalization::None => "None",
+            HebbianNormalization::Neurons => "1/N",
+            HebbianNormalization::Patterns => "1/P",
+        }
+    }
+
+    /// Factor applied to Σ_p ξ_i^p ξ_j^p with `neurons` neurons and `patterns` patterns
+    pub fn factor(self, neurons: usize, patterns: usize) -> f64 {
+        match self {
+            HebbianNormalization::None => 1.0,
+            HebbianNormalization::Neurons => 1.0 / neurons.max(1) as f64,
+            HebbianNormalization::Patterns => 1.0 / patterns.max(1) as f64,
+        }
+    }
+}
+
+/// Order in which neurons are visited during one asynchronous sweep of N updates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SweepOrder {
+    /// N neurons drawn uniformly at random; some may be updated twice, others not at all
+    RandomWithReplacement,
+    /// Every neuron once, in a new random order each sweep
+    RandomPermutation,
+    /// Every neuron once, in index (row-major) order
+    Raster,
+    /// For a grid `width` neurons wide: all cells with even x + y, then all with odd x + y
+    Checkerboard { width: usize },
+}
+
+impl SweepOrder {
+    /// Neuron indices for one sweep of a network with `num_neurons` neurons
+    pub fn indices(&self, num_neurons: usize, rng: &mut impl Rng) -> Vec<usize> {
+        match *self {
+            SweepOrder::RandomWithReplacement => (0..num_neurons).map(|_| rng.gen_range(0..num_neurons)).collect(),
+            SweepOrder::RandomPermutation => {
+                let mut order: Vec<usize> = (0..num_neurons).collect();
+                order.shuffle(rng);
+                order
+            }
+            SweepOrder::Raster => (0..num_neurons).collect(),
+            SweepOrder::Checkerboard { width } => {
+                let width = width.max(1);
+                let parity = |i: &usize| (i % width + i / width) % 2;
+                let (mut even, odd): (Vec<usize>, Vec<usize>) = (0..num_neurons).partition(|i| parity(i) == 0);
+                even.extend(odd);
+                even
+            }
+        }
+    }
+}
+
+/// Preprocessing of the training patterns to remove their mutual overlaps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orthogonalization {
+    /// Train on the patterns as they are
+    None,
+    /// Gram-Schmidt: exactly orthogonal real-valued patterns, each scaled to the norm
+    /// sqrt(N) of a bipolar pattern. Pass them through [`Orthogonalization::bipolar`]
+    /// before training, since the network only accepts ±1 states.
+    GramSchmidt,
+    /// Bipolar patterns made nearly orthogonal by flipping as few bits as possible
+    NearOrthogonal,
+}
+
+impl Orthogonalization {
+    /// Returns the preprocessed patterns, in the same order.
+    ///
+    /// Later patterns are made orthogonal to earlier ones. Under Gram-Schmidt a pattern
+    /// that is a linear combination of earlier ones becomes all zeros.
+    pub fn apply(&self, patterns: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let mut result: Vec<Vec<f64>> = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            let projected = match self {
+                Orthogonalization::None => pattern.clone(),
+                Orthogonalization::GramSchmidt => {
+                    let mut residual = pattern.clone();
+                    for previous in &result {
+                        let norm_sq: f64 = previous.iter().map(|x| x * x).sum();
+                        if norm_sq < 1e-12 {
+                            continue;
+                        }
+                        let coefficient = residual.iter().zip(previous).map(|(a, b)| a * b).sum::<f64>() / norm_sq;
+                        for (r, p) in residual.iter_mut().zip(previous) {
+                            *r -= coefficient * p;
+                        }
+                    }
+                    let norm = residual.iter().map(|x| x * x).sum::<f64>().sqrt();
+                    let scale = if norm > 1e-9 { (pattern.len() as f64).sqrt() / norm } else { 0.0 };
+                    residual.iter().map(|x| x * scale).collect()
+                }
+                Orthogonalization::NearOrthogonal => Self::flip_to_orthogonal(pattern, &result),
+            };
+            result.push(projected);
+        }
+        result
+    }
+
+    /// Projects preprocessed patterns onto bipolar states by their sign, with zeros
+    /// mapped to +1. Bipolar patterns are returned unchanged.
+    pub fn bipolar(patterns: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        patterns
+            .iter()
+            .map(|pattern| pattern.iter().map(|&x| if x >= 0.0 { 1.0 } else { -1.0 }).collect())
+            .collect()
+    }
+
+    /// Greedily flips the bit of `pattern` that most reduces the sum of its squared dot
+    /// products with `previous`, until no flip helps
+    fn flip_to_orthogonal(pattern: &[f64], previous: &[Vec<f64>]) -> Vec<f64> {
+        let mut state: Vec<f64> = pattern.iter().map(|&x| if x >= 0.0 { 1.0 } else { -1.0 }).collect();
+        let mut dots: Vec<f64> = previous
+            .iter()
+            .map(|p| state.iter().zip(p).map(|(a, b)| a * b).sum())
+            .collect();
+        loop {
+            // Flipping bit i changes dot_j by -2 x_i p_ji, and the sum of squares by
+            // sum_j 4 (p_ji^2 - d_j x_i p_ji)
+            let best = (0..state.len())
+                .map(|i| {
+                    let change: f64 = previous
+                        .iter()
+                        .zip(&dots)
+                        .map(|(p, &d)| 4.0 * (p[i] * p[i] - d * state[i] * p[i]))
+                        .sum();
+                    (i, change)
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            match best {
+                Some((i, change)) if change < -1e-9 => {
+                    for (d, p) in dots.iter_mut().zip(previous) {
+                        *d -= 2.0 * state[i] * p[i];
+                    }
+                    state[i] = -state[i];
+                }
+                _ => return state,
+            }
+        }
+    }
+}
+
+/// Low-precision representation of the weights, as in hardware associative memories.
+/// Weights that are exactly 0 (the diagonal and pruned connections) stay 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantization {
+    /// Signed uniform levels spaced max |W_ij| / (2^(bits-1) - 1) apart; a single bit
+    /// keeps only the sign, with the mean magnitude
+    Uniform { bits: u32 },
+    /// Weights in {-1, 0, +1}: magnitudes below `threshold` times the mean magnitude
+    /// become 0, the others their sign
+    Ternary { threshold: f64 },
+}
+
+impl Quantization {
+    /// Quantized value of `w`, given the largest and the mean magnitude of the nonzero weights
+    fn apply(&self, w: f64, max_magnitude: f64, mean_magnitude: f64) -> f64 {
+        if w == 0.0 {
+            return 0.0;
+        }
+        match *self {
+            Quantization::Uniform { bits } if bits <= 1 => w.signum() * mean_magnitude,
+            Quantization::Uniform { bits } => {
+                let levels = ((1u64 << (bits.min(32) - 1)) - 1) as f64;
+                let step = max_magnitude / levels;
+                (w / step).round() * step
+            }
+            Quantization::Ternary { threshold } => {
+                if w.abs() < threshold * mean_magnitude {
+                    0.0
+                } else {
+                    w.signum()
+                }
+            }
+        }
+    }
+}
+
+/// Maximum number of sweeps a cue may take to settle during `evaluate`
+pub const EVALUATION_MAX_SWEEPS: usize = 100;
+
+/// Assignment of neurons to modules, for block-structured weight matrices
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modules {
+    /// Module index of each neuron
+    assignment: Vec<usize>,
+    count: usize,
+}
+
+impl Modules {
+    /// Modules given by the module index of each neuron; indices should run from 0
+    /// without gaps
+    pub fn new(assignment: Vec<usize>) -> Self {
+        let count = assignment.iter().max().map_or(0, |&m| m + 1);
+        Modules { assignment, count }
+    }
+
+    /// Tiles a `width` x `height` grid (stored row by row) into `columns` x `rows`
+    /// rectangular modules, numbered row by row
+    pub fn grid(width: usize, height: usize, columns: usize, rows: usize) -> Self {
+        let (columns, rows) = (columns.clamp(1, width.max(1)), rows.clamp(1, height.max(1)));
+        let assignment = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                (y * rows / height) * columns + x * columns / width
+            })
+            .collect();
+        Modules { assignment, count: columns * rows }
+    }
+
+    /// Number of modules
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Number of neurons covered
+    pub fn len(&self) -> usize {
+        self.assignment.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assignment.is_empty()
+    }
+
+    /// Module index of `neuron`
+    pub fn module_of(&self, neuron: usize) -> usize {
+        self.assignment[neuron]
+    }
+
+    /// Overlap of `state` with `pattern` within each module
+    pub fn overlaps(&self, state: &[f64], pattern: &[f64]) -> Vec<f64> {
+        let mut sums = vec![0.0; self.count];
+        let mut sizes = vec![0usize; self.count];
+        for ((&module, s), p) in self.assignment.iter().zip(state).zip(pattern) {
+            sums[module] += s * p;
+            sizes[module] += 1;
+        }
+        sums.iter().zip(&sizes).map(|(&sum, &size)| if size > 0 { sum / size as f64 } else { 0.0 }).collect()
+    }
+}
+
+/// Critical load α = P/N of Hebbian learning with random patterns: beyond it the stored
+/// patterns stop being attractors and retrieval fails (Amit, Gutfreund & Sompolinsky)
+pub const HEBBIAN_CRITICAL_LOAD: f64 = 0.138;
+
+/// Fraction of the critical load from which a network counts as near capacity
+const NEAR_CAPACITY_FRACTION: f64 = 0.75;
+
+/// Weight rows computed per matrix product in pseudo-inverse training, between progress
+/// reports
+const PSEUDO_INVERSE_ROW_BLOCK: usize = 64;
+
+/// How close a network is to its storage capacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadLevel {
+    Safe,
+    NearCapacity,
+    Overloaded,
+}
+
+/// Number of stored patterns relative to the number of neurons
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternLoad {
+    pub patterns: usize,
+    pub neurons: usize,
+    pub rule: TrainingRule,
+}
+
+impl PatternLoad {
+    pub fn new(patterns: usize, neurons: usize, rule: TrainingRule) -> Self {
+        PatternLoad { patterns, neurons, rule }
+    }
+
+    /// The load α = P/N
+    pub fn alpha(&self) -> f64 {
+        self.patterns as f64 / self.neurons.max(1) as f64
+    }
+
+    /// Load at which retrieval is expected to break down. The pseudo-inverse rule makes
+    /// any linearly independent patterns fixed points, so it only runs out at P = N,
+    /// although the basins of attraction shrink well before that. The Storkey rule
+    /// stores N / √(2 ln N) patterns.
+    pub fn critical(&self) -> f64 {
+        match self.rule {
+            TrainingRule::Hebbian => HEBBIAN_CRITICAL_LOAD,
+            TrainingRule::PseudoInverse => 1.0,
+            TrainingRule::Storkey => 1.0 / (2.0 * (self.neurons.max(2) as f64).ln()).sqrt(),
+        }
+    }
+
+    pub fn level(&self) -> LoadLevel {
+        let alpha = self.alpha();
+        if alpha > self.critical() {
+            LoadLevel::Overloaded
+        } else if alpha > NEAR_CAPACITY_FRACTION * self.critical() {
+            LoadLevel::NearCapacity
+        } else {
+            LoadLevel::Safe
+        }
+    }
+}
+
+/// Recall statistics for one stored pattern at one noise level, averaged over trials
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallStats {
+    /// Index of the target pattern in the evaluated pattern list
+    pub pattern_index: usize,
+    /// Probability with which each bit of the cue was flipped
+    pub noise_level: f64,
+    /// Fraction of trials that ended exactly on the target pattern
+    pub accuracy: f64,
+    /// Mean number of neurons differing from the target at the end of a trial
+    pub mean_hamming_distance: f64,
+    /// Mean number of sweeps until the state stopped changing
+    /// (`EVALUATION_MAX_SWEEPS` for trials that never settled)
+    pub mean_convergence_time: f64,
+}
+
+/// Steady-state retrieval at one inverse temperature, averaged over trials
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperaturePoint {
+    pub beta: f64,
+    /// Mean overlap with the target after the burn-in sweeps
+    pub mean_overlap: f64,
+    /// Standard deviation of the per-trial mean overlaps
+    pub overlap_std: f64,
+}
+
+/// Delta codec of Hopfield states: the indices of the neurons that flipped between two
+/// recorded states. Near a fixed point only a handful of spins change per sweep, so a
+/// `FlipHistory` is far smaller than a history of full state vectors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpinFlips;
+
+impl SpinFlips {
+    /// Indices where `to` differs from `from`
+    pub fn between(from: &[f64], to: &[f64]) -> Vec<u32> {
+        from.iter()
+            .zip(to)
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, _)| i as u32)
+            .collect()
+    }
+}
+
+impl DeltaCodec for SpinFlips {
+    type State = Vec<f64>;
+    type Delta = Vec<u32>;
+
+    fn apply(&self, state: &mut Vec<f64>, flips: &Vec<u32>) {
+        for &i in flips {
+            state[i as usize] = -state[i as usize];
+        }
+    }
+}
+
+/// Hopfield states recorded as flip lists
+pub type FlipHistory = DeltaHistory<SpinFlips>;
+
+/// Floating-point type of the weights of a `HopfieldNetwork`.
+///
+/// f64 is the default. f32 halves the memory of the N x N weight matrix and lets the
+/// field sums of big networks (64x64 grids and up) vectorize twice as wide. Only the
+/// weights take this type: states are passed and returned as f64 vectors, and the
+/// synchronous updates convert the state to `T` once per step for the field sums
+/// (exact, as the states are ±1).
+pub trait Scalar:
+    Copy + PartialEq + fmt::Debug + Send + Sync + 'static
+    + Add<Output = Self> + Mul<Output = Self> + AddAssign + Sum
+{
+    const ZERO: Self;
+
+    fn from_f64(value: f64) -> Self;
+
+    fn to_f64(self) -> f64;
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Scalar for f32 {
+    const ZERO: Self = 0.0;
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+/// Σ a_i b_i over eight independent partial sums, which the compiler can map onto SIMD
+/// lanes (a single running sum has to be evaluated in order)
+fn dot<T: Scalar>(a: &[T], b: &[T]) -> T {
+    const LANES: usize = 8;
+    let mut partial = [T::ZERO; LANES];
+    let (a_chunks, b_chunks) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+    let tail: T = a_chunks.remainder().iter().zip(b_chunks.remainder()).map(|(&x, &y)| x * y).sum();
+    for (x, y) in a_chunks.zip(b_chunks) {
+        for lane in 0..LANES {
+            partial[lane] += x[lane] * y[lane];
+        }
+    }
+    partial.into_iter().sum::<T>() + tail
+}
+
+/// Glauber rule: +1 with probability 1 / [1 + exp(-2 * β * h)], otherwise -1
+fn stochastic_spin(field: f64, beta: f64, rng: &mut impl Rng) -> f64 {
+    let prob_plus_one = 1.0 / (1.0 + (-2.0 * beta * field).exp());
+    if rng.gen::<f64>() < prob_plus_one {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+/// Local fields h_k = Σ_j W_kj S_j of a state, kept up to date as single neurons change.
+///
+/// Reading a field is O(1) and a flip of neuron i adds 2 W_ki S_i to every field, read
+/// from column i of the weights, so a sweep costs O(N × (1 + flips)) instead of O(N²).
+struct LocalFields<'a, T: Scalar> {
+    weights: &'a [Vec<T>],
+    fields: Vec<T>,
+}
+
+impl<'a, T: Scalar> LocalFields<'a, T> {
+    fn new(weights: &'a [Vec<T>], state: &[f64]) -> Self {
+        let state: Vec<T> = state.iter().map(|&s| T::from_f64(s)).collect();
+        let fields = weights.iter().map(|row| dot(row, &state)).collect();
+        LocalFields { weights, fields }
+    }
+
+    fn field(&self, neuron: usize) -> f64 {
+        self.fields[neuron].to_f64()
+    }
+
+    /// Sets `state[neuron]` to `value`, updating the fields of its neighbours on a flip
+    fn set(&mut self, state: &mut [f64], neuron: usize, value: f64) {
+        let delta = value - state[neuron];
+        if delta == 0.0 {
+            return;
+        }
+        state[neuron] = value;
+        let delta = T::from_f64(delta);
+        for (field, row) in self.fields.iter_mut().zip(self.weights) {
+            let w = row[neuron];
+            if w != T::ZERO {
+                *field += w * delta;
+            }
+        }
+    }
 }
 
 /// Represents a discrete-time Hopfield Network.
 ///
 /// Stores the network weights and provides methods for training, state updates,
-/// energy calculation, and running the network dynamics.
+/// energy calculation, and running the network dynamics. The weights are stored as
+/// `T` (f64 unless a single precision network is created with `zeros`), while states
+/// are always passed as f64.
 #[derive(Debug, Clone)]
-pub struct HopfieldNetwork {
+pub struct HopfieldNetwork<T: Scalar = f64> {
     num_neurons: usize,
     /// Weight matrix (W_ij) representing connection strengths.
     /// Size: num_neurons x num_neurons. W_ii is always 0.
-    weights: Vec<Vec<f64>>,
+    weights: Vec<Vec<T>>,
 }
 
 impl HopfieldNetwork {
@@ -51,13 +560,67 @@ impl HopfieldNetwork {
     /// # Arguments
     ///
     /// * `num_neurons` - The number of neurons in the network. Must be greater than 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_neurons` is 0; use `try_new` for sizes that come from user input.
     pub fn new(num_neurons: usize) -> Self {
+        Self::zeros(num_neurons)
+    }
+
+    /// Like `new`, but returns an error instead of panicking on 0 neurons
+    pub fn try_new(num_neurons: usize) -> Result<Self, HopfieldError> {
+        Self::try_zeros(num_neurons)
+    }
+}
+
+impl<T: Scalar> HopfieldNetwork<T> {
+    /// Like `new`, but with weights of type `T`, e.g. `HopfieldNetwork::<f32>::zeros(n)`
+    pub fn zeros(num_neurons: usize) -> Self {
+        Self::try_zeros(num_neurons).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `zeros`, but returns an error instead of panicking on 0 neurons
+    pub fn try_zeros(num_neurons: usize) -> Result<Self, HopfieldError> {
         if num_neurons == 0 {
-            panic!("Number of neurons must be greater than 0.");
+            return Err(HopfieldError::InvalidSize("Number of neurons must be greater than 0.".to_string()));
         }
-        HopfieldNetwork {
+        Ok(HopfieldNetwork {
             num_neurons,
-            weights: vec![vec![0.0; num_neurons]; num_neurons],
+            weights: vec![vec![T::ZERO; num_neurons]; num_neurons],
+        })
+    }
+
+    /// Network with the given weight matrix, e.g. one computed outside RAUM. The
+    /// diagonal is set to 0, as self-connections are not part of the model.
+    pub fn from_weights(weights: &[Vec<f64>]) -> Result<Self, HopfieldError> {
+        let mut network = Self::try_zeros(weights.len())?;
+        if let Some(row) = weights.iter().find(|row| row.len() != weights.len()) {
+            return Err(HopfieldError::DimensionMismatch(format!(
+                "Weight matrix must be square: {} rows but a row of length {}",
+                weights.len(),
+                row.len()
+            )));
+        }
+        for (i, row) in weights.iter().enumerate() {
+            for (j, &w) in row.iter().enumerate() {
+                if i != j {
+                    network.weights[i][j] = T::from_f64(w);
+                }
+            }
+        }
+        Ok(network)
+    }
+
+    /// Copy of the network with the weights converted to `U`
+    pub fn to_precision<U: Scalar>(&self) -> HopfieldNetwork<U> {
+        HopfieldNetwork {
+            num_neurons: self.num_neurons,
+            weights: self
+                .weights
+                .iter()
+                .map(|row| row.iter().map(|w| U::from_f64(w.to_f64())).collect())
+                .collect(),
         }
     }
 
@@ -66,6 +629,36 @@ impl HopfieldNetwork {
         self.num_neurons
     }
 
+    /// Returns the weight matrix W, one row per neuron.
+    pub fn weights(&self) -> &[Vec<T>] {
+        &self.weights
+    }
+
+    /// Mean over neurons of Σ_j |W_ij|, how large a local field Σ_j W_ij s_j gets when
+    /// the inputs line up with the weights. It grows with N for unnormalized Hebbian
+    /// weights and stays of order one for the pseudo-inverse rule.
+    pub fn field_scale(&self) -> f64 {
+        let total: f64 = self.weights.iter().flatten().map(|w| w.to_f64().abs()).sum();
+        total / self.num_neurons as f64
+    }
+
+    /// The β that gives the dynamics an inverse temperature of `reduced_beta` relative to
+    /// `field_scale`, so that the same value behaves alike across sizes and rules.
+    /// Synchronous updates divide the field by N before the sigmoid, asynchronous ones
+    /// do not. Without weights `reduced_beta` is returned as is.
+    pub fn calibrated_beta(&self, reduced_beta: f64, synchronous: bool) -> f64 {
+        let scale = if synchronous {
+            self.field_scale() / self.num_neurons as f64
+        } else {
+            self.field_scale()
+        };
+        if scale > 0.0 {
+            reduced_beta / scale
+        } else {
+            reduced_beta
+        }
+    }
+
     /// Validates if a given vector represents a valid bipolar state (+1.0 or -1.0).
     fn validate_state(state: &[f64], expected_len: usize) -> Result<(), HopfieldError> {
         if state.len() != expected_len {
@@ -87,83 +680,148 @@ impl HopfieldNetwork {
 
     /// Calculates the weight matrix W based on the provided patterns ξ using the
     /// selected rule:
-    /// Hebbian: W_ij = Σ_p (ξ_i^p * ξ_j^p) for i ≠ j
-    /// PseudoInverse: W_ij = Σ_{α,β} ξ_i^α (C⁻¹)_{αβ} ξ_j^β where C is overlap matrix
-    /// W_ii = 0
+    /// Hebbian: W_ij = c Σ_p (ξ_i^p * ξ_j^p), with c = 1 here (`train_observed` takes
+    /// a `HebbianNormalization` for 1/N or 1/P)
+    /// PseudoInverse: W_ij = 1/N Σ_{α,β} ξ_i^α (C⁻¹)_{αβ} ξ_j^β where C = Ξ Ξᵀ / N is the
+    /// overlap matrix
+    /// Storkey: pattern ν adds (ξ_i ξ_j - ξ_i h_ji - h_ij ξ_j) / N to the weights of the
+    /// patterns before it, with h_ij = Σ_{k≠i,j} W_ik ξ_k
+    /// W_ii = 0 for every rule
     /// This method resets existing weights before training.
     pub fn train(&mut self, patterns: &[Vec<f64>], rule: TrainingRule) -> Result<(), HopfieldError> {
+        self.train_observed(patterns, rule, HebbianNormalization::None, |_| true).map(|_| ())
+    }
+
+    /// Like `train`, but calls `observer` with the number of weight rows computed after
+    /// every row (every block of rows for the pseudo-inverse rule). Training stops early
+    /// when `observer` returns false, leaving the later rows zero. The Storkey rule
+    /// updates every row for each pattern, so it reports the share of the rows matching
+    /// the patterns learned, and stopping leaves the weights of those patterns. Hebbian
+    /// weights are scaled by `normalization`; the other rules ignore it.
+    ///
+    /// # Returns
+    ///
+    /// Result with true if every row was computed
+    pub fn train_observed(
+        &mut self,
+        patterns: &[Vec<f64>],
+        rule: TrainingRule,
+        normalization: HebbianNormalization,
+        mut observer: impl FnMut(usize) -> bool,
+    ) -> Result<bool, HopfieldError> {
         if patterns.is_empty() {
              println!("Warning: Training with an empty set of patterns.");
-             self.weights = vec![vec![0.0; self.num_neurons]; self.num_neurons];
-             return Ok(());
+             self.weights = vec![vec![T::ZERO; self.num_neurons]; self.num_neurons];
+             return Ok(true);
         }
 
         for pattern in patterns {
              Self::validate_state(pattern, self.num_neurons)?;
         }
 
-        self.weights = vec![vec![0.0; self.num_neurons]; self.num_neurons];
+        self.weights = vec![vec![T::ZERO; self.num_neurons]; self.num_neurons];
 
         match rule {
             TrainingRule::Hebbian => {
                 println!("Training using Hebbian rule...");
-                for pattern in patterns {
-                    for i in 0..self.num_neurons {
+                let factor = normalization.factor(self.num_neurons, patterns.len());
+                for i in 0..self.num_neurons {
+                    for pattern in patterns {
                         for j in 0..self.num_neurons {
                             if i != j {
-                                self.weights[i][j] += pattern[i] * pattern[j];
+                                self.weights[i][j] += T::from_f64(factor * pattern[i] * pattern[j]);
                             }
                         }
                     }
+                    if !observer(i + 1) && i + 1 < self.num_neurons {
+                        return Ok(false);
+                    }
                 }
             }
             TrainingRule::PseudoInverse => {
                 println!("Training using PseudoInverse rule...");
                 let num_patterns = patterns.len();
+                let n = self.num_neurons;
+                let n_f64 = n as f64;
 
-                // 1. Calculate the Covariance/Overlap Matrix C
-                let mut covariance_matrix = DMatrix::<f64>::zeros(num_patterns, num_patterns);
-                let n_f64 = self.num_neurons as f64;
+                // Ξ with one pattern per row
+                let xi = DMatrix::<f64>::from_fn(num_patterns, n, |alpha, i| patterns[alpha][i]);
 
-                for alpha in 0..num_patterns {
-                    for beta in alpha..num_patterns {
-                        let mut dot_product = 0.0;
-                        for i in 0..self.num_neurons {
-                            dot_product += patterns[alpha][i] * patterns[beta][i];
-                        }
-                        let overlap = dot_product / n_f64;
-                        covariance_matrix[(alpha, beta)] = overlap;
-                        if alpha != beta {
-                            covariance_matrix[(beta, alpha)] = overlap;
-                        }
-                    }
-                }
+                // 1. Calculate the Covariance/Overlap Matrix C = Ξ Ξᵀ / N
+                let covariance_matrix = &xi * xi.transpose() / n_f64;
 
                 // 2. Calculate the inverse C⁻¹
-                if let Some(inv_covariance_matrix) = covariance_matrix.try_inverse() {
-                    // 3. Calculate weights W_ij = Σ_{α,β} ξ_i^α (C⁻¹)_{αβ} ξ_j^β
-                    for i in 0..self.num_neurons {
-                        for j in 0..self.num_neurons {
-                            if i == j { continue; } // W_ii = 0
-                            let mut weight_sum = 0.0;
-                            for alpha in 0..num_patterns {
-                                for beta in 0..num_patterns {
-                                    weight_sum += patterns[alpha][i] * inv_covariance_matrix[(alpha, beta)] * patterns[beta][j];
-                                }
-                            }
-                            let norm = 1.0 / (self.num_neurons as f64);
-                            self.weights[i][j] = norm * weight_sum;
-                        }
-                    }
-                } else {
+                let Some(inv_covariance_matrix) = covariance_matrix.try_inverse() else {
                     // Handle non-invertible matrix
                     return Err(HopfieldError::DimensionMismatch(
                         "Covariance matrix is singular, cannot compute pseudo-inverse. Try Hebbian rule or different patterns.".to_string()
                     ));
+                };
+
+                // 3. Calculate weights W = Ξᵀ C⁻¹ Ξ / N as matrix products, a block of rows
+                // at a time so progress is reported between blocks
+                let projected = inv_covariance_matrix * &xi / n_f64;
+                for start in (0..n).step_by(PSEUDO_INVERSE_ROW_BLOCK) {
+                    let end = (start + PSEUDO_INVERSE_ROW_BLOCK).min(n);
+                    let block = xi.columns(start, end - start).transpose() * &projected;
+                    for (r, i) in (start..end).enumerate() {
+                        for j in 0..n {
+                            if i != j { // W_ii = 0
+                                self.weights[i][j] = T::from_f64(block[(r, j)]);
+                            }
+                        }
+                    }
+                    if !observer(end) && end < n {
+                        return Ok(false);
+                    }
+                }
+            }
+            TrainingRule::Storkey => {
+                println!("Training using Storkey rule...");
+                let n = self.num_neurons;
+                let n_f64 = n as f64;
+                for (p, pattern) in patterns.iter().enumerate() {
+                    // h_i = Σ_k W_ik ξ_k with the weights before this pattern; W_ii = 0,
+                    // so h_ij = h_i - W_ij ξ_j
+                    let fields: Vec<f64> = self
+                        .weights
+                        .iter()
+                        .map(|row| row.iter().zip(pattern).map(|(w, x)| w.to_f64() * x).sum())
+                        .collect();
+                    let previous = self.weights.clone();
+                    for i in 0..n {
+                        for j in 0..n {
+                            if i != j {
+                                let h_ij = fields[i] - previous[i][j].to_f64() * pattern[j];
+                                let h_ji = fields[j] - previous[j][i].to_f64() * pattern[i];
+                                let delta = (pattern[i] * pattern[j] - pattern[i] * h_ji - h_ij * pattern[j]) / n_f64;
+                                self.weights[i][j] += T::from_f64(delta);
+                            }
+                        }
+                    }
+                    let rows = (p + 1) * n / patterns.len();
+                    if !observer(rows) && p + 1 < patterns.len() {
+                        return Ok(false);
+                    }
                 }
             }
         }
 
+        Ok(true)
+    }
+
+    /// Adds one pattern to the weights with the Hebbian rule, W_ij += rate ξ_i ξ_j for
+    /// i ≠ j, on top of what the network learned before. Learning patterns one at a time
+    /// with rate 1 gives the same weights as `train` with the Hebbian rule.
+    pub fn learn(&mut self, pattern: &[f64], rate: f64) -> Result<(), HopfieldError> {
+        Self::validate_state(pattern, self.num_neurons)?;
+        for (i, row) in self.weights.iter_mut().enumerate() {
+            for (j, weight) in row.iter_mut().enumerate() {
+                if i != j {
+                    *weight += T::from_f64(rate * pattern[i] * pattern[j]);
+                }
+            }
+        }
         Ok(())
     }
 
@@ -175,12 +833,11 @@ impl HopfieldNetwork {
     pub fn update_step(&self, current_state: &[f64], beta: f64, rng: &mut impl Rng) -> Result<Vec<f64>, HopfieldError> {
         Self::validate_state(current_state, self.num_neurons)?;
         let mut next_state = vec![0.0; self.num_neurons];
+        // The sums run in the precision of the weights
+        let state: Vec<T> = current_state.iter().map(|&s| T::from_f64(s)).collect();
         for i in 0..self.num_neurons {
-            let mut activation_sum = 0.0;
-            for j in 0..self.num_neurons {
-                activation_sum += self.weights[i][j] * current_state[j];
-            }
-            let field = activation_sum / (self.num_neurons as f64);
+            let activation_sum = dot(&self.weights[i], &state);
+            let field = activation_sum.to_f64() / (self.num_neurons as f64);
             let scaled_activation = beta * field; 
 
             // Calculate probability P(S_i = +1)
@@ -197,99 +854,320 @@ impl HopfieldNetwork {
         Ok(next_state)
     }
 
+    /// The zero-temperature limit of `update_step`: every neuron takes the sign of its
+    /// field at once, S_i(t+1) = sgn(Σ_j W_ij S_j(t)), keeping its value when the field
+    /// is zero. This is the sweep the GPU kernel in `gpu` performs.
+    pub fn update_step_deterministic(&self, current_state: &[f64]) -> Result<Vec<f64>, HopfieldError> {
+        Self::validate_state(current_state, self.num_neurons)?;
+        let state: Vec<T> = current_state.iter().map(|&s| T::from_f64(s)).collect();
+        Ok(self
+            .weights
+            .iter()
+            .zip(current_state)
+            .map(|(row, &s)| {
+                let field = dot(row, &state).to_f64();
+                if field > 0.0 {
+                    1.0
+                } else if field < 0.0 {
+                    -1.0
+                } else {
+                    s
+                }
+            })
+            .collect())
+    }
+
     /// Performs a single asynchronous update step on a randomly chosen neuron `k`.
     /// Modifies the input state `state` directly.
     ///
-    /// Calculates the activation for neuron `k`: h_k = (1/N) * Σ_{j} W_{kj} * S_j
+    /// Calculates the field of neuron `k` without rescaling: h_k = Σ_{j} W_{kj} * S_j
     /// Updates the state of neuron `k` probabilistically with
     ///    P(S_k = +1) = 1 / [1 + exp(-2 * β * h_k)].
+    ///
+    /// The size of h_k is therefore set by the training rule: with unnormalized Hebbian
+    /// weights it grows with N, with `HebbianNormalization::Neurons`, the pseudo-inverse
+    /// and the Storkey rule (which build in 1/N) it stays of order one. Use
+    /// `calibrated_beta` for a β that behaves alike across rules and sizes.
     pub fn update_step_async(&self, state: &mut [f64], beta: f64, rng: &mut impl Rng) -> Result<(), HopfieldError> {
         Self::validate_state(state, self.num_neurons)?;
 
         let neuron_index = rng.gen_range(0..self.num_neurons);
+        self.update_neuron(state, neuron_index, beta, rng);
+        Ok(())
+    }
 
+    /// Stochastic update of neuron `neuron_index` in place, as in `update_step_async`
+    fn update_neuron(&self, state: &mut [f64], neuron_index: usize, beta: f64, rng: &mut impl Rng) {
         let mut activation_sum = 0.0;
         for j in 0..self.num_neurons {
             // Use state[j] as the network state is updated in place
-            activation_sum += self.weights[neuron_index][j] * state[j]; 
-        }
-
-        let scaled_activation = beta * activation_sum; // Apply beta scaling
-
-        // Calculate probability P(S_k = +1)
-        let prob_plus_one = 1.0 / (1.0 + (-2.0 * scaled_activation).exp());
-
-        // Decide state based on probability
-        if rng.gen::<f64>() < prob_plus_one {
-            state[neuron_index] = 1.0;
-        } else {
-            state[neuron_index] = -1.0;
+            activation_sum += self.weights[neuron_index][j].to_f64() * state[j]; 
         }
+        state[neuron_index] = stochastic_spin(activation_sum, beta, rng);
+    }
 
-        Ok(())
+    /// Like `update_neuron`, but reads the activation from the cached `fields` and keeps
+    /// them up to date, so only a flip costs more than O(1)
+    fn update_neuron_cached(
+        &self,
+        state: &mut [f64],
+        fields: &mut LocalFields<T>,
+        neuron_index: usize,
+        beta: f64,
+        rng: &mut impl Rng,
+    ) {
+        let next = stochastic_spin(fields.field(neuron_index), beta, rng);
+        fields.set(state, neuron_index, next);
     }
 
     /// Runs the network dynamics asynchronously until convergence or max iterations.
     ///
-    /// An "iteration" consists of N single-neuron updates, where N = num_neurons.
+    /// An "iteration" consists of N single-neuron updates, where N = num_neurons,
+    /// visiting the neurons in the given `order`.
     /// Convergence occurs when the state vector remains unchanged after a full
     /// sweep of N asynchronous updates.
+    /// Local fields are cached between updates, so a sweep costs O(N) plus O(degree)
+    /// per flipped neuron rather than O(N²).
     pub fn run_async(
         &self,
         initial_state: &[f64],
         max_iterations: usize,
         beta: f64, // Add beta parameter
+        order: SweepOrder,
         rng: &mut impl Rng,
-    ) -> Result<(Vec<Vec<f64>>, usize), HopfieldError> {
-        Self::validate_state(initial_state, self.num_neurons)?;
-
-        let mut states_history: Vec<Vec<f64>> = vec![initial_state.to_vec()];
-        let mut current_state = initial_state.to_vec();
+    ) -> Result<(SimulationHistory<Vec<f64>>, usize), HopfieldError> {
+        self.run_async_observed(initial_state, max_iterations, beta, order, rng, |_| true)
+    }
 
-        for _i in 0..max_iterations {
-            let _state_before_sweep = current_state.clone();
+    /// Like `run_async`, but calls `observer` with the number of completed iterations
+    /// after every sweep. The run stops early when `observer` returns false.
+    ///
+    /// # Returns
+    ///
+    /// Result with the state history and the number of iterations actually executed
+    pub fn run_async_observed(
+        &self,
+        initial_state: &[f64],
+        max_iterations: usize,
+        beta: f64,
+        order: SweepOrder,
+        rng: &mut impl Rng,
+        mut observer: impl FnMut(usize) -> bool,
+    ) -> Result<(SimulationHistory<Vec<f64>>, usize), HopfieldError> {
+        Self::validate_state(initial_state, self.num_neurons)?;
 
-            // Perform N single-neuron updates for one full sweep/iteration
-            for _ in 0..self.num_neurons {
-                 self.update_step_async(&mut current_state, beta, rng)?; // Pass beta
+        let mut states_history = SimulationHistory::new(initial_state.to_vec());
+        let iterations = self.run_async_streamed(initial_state, max_iterations, beta, order, rng, |i, state| {
+            if i == 0 {
+                return true;
             }
-
             // Store state after the full sweep
-            states_history.push(current_state.clone());
-
-            // Removed early convergence check for stochastic simulation
-        }
-
-        // Reached max iterations
-        Ok((states_history, max_iterations))
+            states_history.push(i, state.to_vec());
+            observer(i)
+        })?;
+        Ok((states_history, iterations))
     }
 
-    /// Runs the network dynamics synchronously until convergence or max iterations.
-    /// The simulation runs for max_iterations.
-    pub fn run(
+    /// Like `run_async`, but hands every state to `visit` instead of recording a history,
+    /// so memory stays constant however long the run. `visit` is called with iteration 0
+    /// and the initial state first, then after every sweep; the run stops early when it
+    /// returns false.
+    ///
+    /// # Returns
+    ///
+    /// Result with the number of iterations actually executed
+    pub fn run_async_streamed(
         &self,
         initial_state: &[f64],
         max_iterations: usize,
         beta: f64,
-        rng: &mut impl Rng, // Add Rng for stochastic updates
-    ) -> Result<(Vec<Vec<f64>>, usize), HopfieldError> {
-        // Validate the initial state first
-        Self::validate_state(initial_state, self.num_neurons)?;
+        order: SweepOrder,
+        rng: &mut impl Rng,
+        mut visit: impl FnMut(usize, &[f64]) -> bool,
+    ) -> Result<usize, HopfieldError> {
+        let n = self.num_neurons;
+        let updates = self.run_async_strided(initial_state, max_iterations, beta, order, n, rng, |update, state| {
+            visit(update / n, state)
+        })?;
+        Ok(updates / n)
+    }
 
-        let mut states_history: Vec<Vec<f64>> = vec![initial_state.to_vec()];
+    /// Like `run_async_streamed`, but calls `visit` every `stride` single-neuron updates
+    /// instead of after every sweep, with the number of updates so far, so that single
+    /// flips can be watched spreading. The run still lasts at most `max_iterations`
+    /// sweeps of N updates.
+    ///
+    /// # Returns
+    ///
+    /// Result with the number of single-neuron updates actually performed
+    #[allow(clippy::too_many_arguments)]
+    pub fn run_async_strided(
+        &self,
+        initial_state: &[f64],
+        max_iterations: usize,
+        beta: f64,
+        order: SweepOrder,
+        stride: usize,
+        rng: &mut impl Rng,
+        mut visit: impl FnMut(usize, &[f64]) -> bool,
+    ) -> Result<usize, HopfieldError> {
+        Self::validate_state(initial_state, self.num_neurons)?;
+
+        let stride = stride.max(1);
         let mut current_state = initial_state.to_vec();
+        let mut fields = LocalFields::new(&self.weights, &current_state);
+        if !visit(0, &current_state) {
+            return Ok(0);
+        }
 
-        for _i in 0..max_iterations {
-            let next_state = self.update_step(&current_state, beta, rng)?; // Pass beta and rng
-            states_history.push(next_state.clone()); // Store the new state
+        let mut updates = 0;
+        for _ in 0..max_iterations {
+            // Perform N single-neuron updates for one full sweep/iteration
+            for neuron_index in order.indices(self.num_neurons, rng) {
+                self.update_neuron_cached(&mut current_state, &mut fields, neuron_index, beta, rng);
+                updates += 1;
+                if updates % stride == 0 && !visit(updates, &current_state) {
+                    return Ok(updates);
+                }
+            }
+        }
 
-            // Removed early convergence check for stochastic simulation
+        // Reached max iterations
+        Ok(updates)
+    }
 
-            current_state = next_state;
+    /// Runs asynchronous dynamics while lowering the temperature along `schedule`.
+    ///
+    /// At each stage the network performs `schedule.sweeps_per_stage` sweeps of N
+    /// single-neuron updates at that stage's β; the state is recorded after every sweep.
+    pub fn run_annealed(
+        &self,
+        initial_state: &[f64],
+        schedule: &AnnealingSchedule,
+        rng: &mut impl Rng,
+    ) -> Result<SimulationHistory<Vec<f64>>, HopfieldError> {
+        Self::validate_state(initial_state, self.num_neurons)?;
+
+        let mut states_history = SimulationHistory::new(initial_state.to_vec());
+        let mut current_state = initial_state.to_vec();
+        let mut fields = LocalFields::new(&self.weights, &current_state);
+        let mut sweep = 0;
+
+        for beta in schedule.betas() {
+            for _ in 0..schedule.sweeps_per_stage {
+                for _ in 0..self.num_neurons {
+                    let neuron_index = rng.gen_range(0..self.num_neurons);
+                    self.update_neuron_cached(&mut current_state, &mut fields, neuron_index, beta, rng);
+                }
+                sweep += 1;
+                states_history.push(sweep, current_state.clone());
+            }
+        }
+
+        Ok(states_history)
+    }
+
+    /// Runs the network dynamics synchronously until convergence or max iterations.
+    /// The simulation runs for max_iterations.
+    pub fn run(
+        &self,
+        initial_state: &[f64],
+        max_iterations: usize,
+        beta: f64,
+        rng: &mut impl Rng, // Add Rng for stochastic updates
+    ) -> Result<(SimulationHistory<Vec<f64>>, usize), HopfieldError> {
+        self.run_observed(initial_state, max_iterations, beta, rng, |_| true)
+    }
+
+    /// Like `run`, but calls `observer` with the number of completed iterations
+    /// after every update. The run stops early when `observer` returns false.
+    ///
+    /// # Returns
+    ///
+    /// Result with the state history and the number of iterations actually executed
+    pub fn run_observed(
+        &self,
+        initial_state: &[f64],
+        max_iterations: usize,
+        beta: f64,
+        rng: &mut impl Rng,
+        mut observer: impl FnMut(usize) -> bool,
+    ) -> Result<(SimulationHistory<Vec<f64>>, usize), HopfieldError> {
+        // Validate the initial state first
+        Self::validate_state(initial_state, self.num_neurons)?;
+
+        let mut states_history = SimulationHistory::new(initial_state.to_vec());
+        let iterations = self.run_streamed(initial_state, max_iterations, beta, rng, |i, state| {
+            if i == 0 {
+                return true;
+            }
+            states_history.push(i, state.to_vec()); // Store the new state
+            observer(i)
+        })?;
+        Ok((states_history, iterations))
+    }
+
+    /// Like `run`, but hands every state to `visit` instead of recording a history.
+    /// `visit` is called with iteration 0 and the initial state first, then after every
+    /// update; the run stops early when it returns false.
+    ///
+    /// # Returns
+    ///
+    /// Result with the number of iterations actually executed
+    pub fn run_streamed(
+        &self,
+        initial_state: &[f64],
+        max_iterations: usize,
+        beta: f64,
+        rng: &mut impl Rng,
+        mut visit: impl FnMut(usize, &[f64]) -> bool,
+    ) -> Result<usize, HopfieldError> {
+        Self::validate_state(initial_state, self.num_neurons)?;
+
+        let mut current_state = initial_state.to_vec();
+        if !visit(0, &current_state) {
+            return Ok(0);
+        }
+
+        for i in 0..max_iterations {
+            current_state = self.update_step(&current_state, beta, rng)?;
+
+            if !visit(i + 1, &current_state) {
+                return Ok(i + 1);
+            }
         }
 
         // Reached max iterations without converging
-        Ok((states_history, max_iterations))
+        Ok(max_iterations)
+    }
+
+    /// Turns the trained weights into a modular network: connections between neurons in
+    /// different modules are scaled by `coupling`, connections within a module are kept.
+    ///
+    /// All modules are trained jointly on the full patterns, so `coupling` = 1 leaves the
+    /// network unchanged and `coupling` = 0 splits it into independent networks that each
+    /// store their part of every pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `modules` - Module of each neuron
+    /// * `coupling` - Scale of the inter-module weights (0.0 to 1.0)
+    pub fn apply_modules(&mut self, modules: &Modules, coupling: f64) -> Result<(), HopfieldError> {
+        if modules.len() != self.num_neurons {
+            return Err(HopfieldError::DimensionMismatch(format!(
+                "Module assignment covers {} neurons but the network has {}",
+                modules.len(), self.num_neurons
+            )));
+        }
+        let coupling = T::from_f64(coupling);
+        for (i, row) in self.weights.iter_mut().enumerate() {
+            for (j, w) in row.iter_mut().enumerate() {
+                if modules.module_of(i) != modules.module_of(j) {
+                    *w = *w * coupling;
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Applies an Erdős-Rényi graph topology to the weight matrix.
@@ -306,20 +1184,136 @@ impl HopfieldNetwork {
         }
         println!("Applying Erdős-Rényi topology with p = {}", p);
 
-        // Iterate through the upper triangle of the matrix (excluding diagonal)
+        let graph = Graph::erdos_renyi(self.num_neurons, p, rng);
+        self.apply_graph_topology(&graph)
+            .expect("Erdős-Rényi graph has one vertex per neuron");
+    }
+
+    /// Restricts the connections to the edges of `graph`: W_ij is kept when i and j are
+    /// adjacent and set to 0 otherwise. Self-loops do not restore the diagonal.
+    pub fn apply_graph_topology(&mut self, graph: &Graph) -> Result<(), HopfieldError> {
+        if graph.num_vertices() != self.num_neurons {
+            return Err(HopfieldError::DimensionMismatch(format!(
+                "Graph has {} vertices but the network has {} neurons",
+                graph.num_vertices(), self.num_neurons
+            )));
+        }
+        let adjacency = graph.adjacency();
+        for (i, (row, adjacent)) in self.weights.iter_mut().zip(&adjacency).enumerate() {
+            for (j, (w, &edge)) in row.iter_mut().zip(adjacent).enumerate() {
+                if i != j && !edge {
+                    *w = T::ZERO;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Dilutes the weights like `apply_erdos_renyi_topology`, but prunes a fraction
+    /// `asymmetry` of the connections independently in each direction, so that W_ij and
+    /// W_ji can differ. With asymmetric weights the energy is no longer guaranteed to
+    /// decrease and the dynamics may cycle.
+    ///
+    /// # Arguments
+    /// * `p` - The probability of keeping each direction of a connection (0.0 <= p <= 1.0).
+    /// * `asymmetry` - Fraction of the pairs (i, j) pruned per direction (0.0 to 1.0).
+    /// * `rng` - A random number generator.
+    pub fn apply_asymmetric_dilution(&mut self, p: f64, asymmetry: f64, rng: &mut impl Rng) {
+        if !(0.0..=1.0).contains(&p) {
+            eprintln!("Warning: dilution probability p must be between 0.0 and 1.0. Got {}. Skipping pruning.", p);
+            return;
+        }
+
         for i in 0..self.num_neurons {
             for j in (i + 1)..self.num_neurons {
-                // Decide whether to keep the connection (i, j)
-                if rng.gen::<f64>() > p { 
-                    // Prune the connection
-                    self.weights[i][j] = 0.0;
-                    self.weights[j][i] = 0.0; // Ensure symmetry
+                if rng.gen::<f64>() < asymmetry {
+                    if rng.gen::<f64>() > p {
+                        self.weights[i][j] = T::ZERO;
+                    }
+                    if rng.gen::<f64>() > p {
+                        self.weights[j][i] = T::ZERO;
+                    }
+                } else if rng.gen::<f64>() > p {
+                    self.weights[i][j] = T::ZERO;
+                    self.weights[j][i] = T::ZERO;
                 }
-                // If rng.gen::<f64>() <= p, the connection remains as calculated by train()
             }
         }
     }
 
+    /// Adds independent Gaussian noise to every off-diagonal weight (so W_ij and W_ji
+    /// receive different noise). The standard deviation is `sigma` times the RMS of the
+    /// current off-diagonal weights, which makes it comparable across training rules.
+    pub fn add_weight_noise(&mut self, sigma: f64, rng: &mut impl Rng) {
+        let n = self.num_neurons;
+        if n < 2 || sigma <= 0.0 {
+            return;
+        }
+        let sum_squares: f64 = self.weights.iter().flatten().map(|w| w.to_f64().powi(2)).sum();
+        let scale = sigma * (sum_squares / (n * (n - 1)) as f64).sqrt();
+        for (i, row) in self.weights.iter_mut().enumerate() {
+            for (j, w) in row.iter_mut().enumerate() {
+                if i != j {
+                    *w = T::from_f64(w.to_f64() + scale * standard_normal(rng));
+                }
+            }
+        }
+    }
+
+    /// Fraction of the off-diagonal weights that are zero
+    pub fn sparsity(&self) -> f64 {
+        let n = self.num_neurons;
+        if n < 2 {
+            return 0.0;
+        }
+        let zeros = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).filter(|&(i, j)| i != j && self.weights[i][j] == T::ZERO).count();
+        zeros as f64 / (n * (n - 1)) as f64
+    }
+
+    /// Sets the smallest weights to 0 until at least a fraction `sparsity` of the
+    /// off-diagonal weights is zero. Connections are pruned in both directions at once,
+    /// ordered by the mean magnitude of W_ij and W_ji, so symmetric weights stay symmetric.
+    ///
+    /// # Returns
+    ///
+    /// The number of connections (pairs i < j) that were pruned
+    pub fn prune_by_magnitude(&mut self, sparsity: f64) -> usize {
+        let n = self.num_neurons;
+        let target = (sparsity.clamp(0.0, 1.0) * (n * n.saturating_sub(1)) as f64).ceil() as usize;
+        let mut zeros = (self.sparsity() * (n * n.saturating_sub(1)) as f64).round() as usize;
+        let mut pairs: Vec<(f64, usize, usize)> = (0..n)
+            .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+            .map(|(i, j)| (self.weights[i][j].to_f64().abs() + self.weights[j][i].to_f64().abs(), i, j))
+            .filter(|&(magnitude, _, _)| magnitude > 0.0)
+            .collect();
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut pruned = 0;
+        for (_, i, j) in pairs {
+            if zeros >= target {
+                break;
+            }
+            zeros += [self.weights[i][j], self.weights[j][i]].iter().filter(|&&w| w != T::ZERO).count();
+            self.weights[i][j] = T::ZERO;
+            self.weights[j][i] = T::ZERO;
+            pruned += 1;
+        }
+        pruned
+    }
+
+    /// Replaces every weight by its `quantization`
+    pub fn quantize(&mut self, quantization: Quantization) {
+        let magnitudes: Vec<f64> = self.weights.iter().flatten().map(|w| w.to_f64().abs()).filter(|&m| m > 0.0).collect();
+        if magnitudes.is_empty() {
+            return;
+        }
+        let max_magnitude = magnitudes.iter().copied().fold(0.0, f64::max);
+        let mean_magnitude = magnitudes.iter().sum::<f64>() / magnitudes.len() as f64;
+        for w in self.weights.iter_mut().flatten() {
+            *w = T::from_f64(quantization.apply(w.to_f64(), max_magnitude, mean_magnitude));
+        }
+    }
+
     /// Calculates the Lyapunov energy function for a given state S.
     ///
     /// E = -1/N * Σ_{i≠j} W_ij * S_i * S_j
@@ -333,37 +1327,733 @@ impl HopfieldNetwork {
                 // The formula typically excludes i == j, and our w_ii is zero anyway.
                 // But explicitly checking i != j is safer if w_ii could be non-zero.
                  if i != j {
-                    energy += self.weights[i][j] * state[i] * state[j];
+                    energy += self.weights[i][j].to_f64() * state[i] * state[j];
                  }
             }
         }
         let norm = 1.0 / (self.num_neurons as f64);
         Ok(-norm * energy)
     }
+
+    /// Zero-temperature asynchronous dynamics: sweeps over the neurons in random order,
+    /// setting S_i = sgn(h_i) (ties keep the current value), until a sweep changes nothing.
+    ///
+    /// # Returns
+    ///
+    /// The number of sweeps performed, or `max_sweeps` if the state never settled
+    pub fn settle(&self, state: &mut [f64], max_sweeps: usize, rng: &mut impl Rng) -> usize {
+        let mut order: Vec<usize> = (0..self.num_neurons).collect();
+        for sweep in 0..max_sweeps {
+            order.shuffle(rng);
+            let mut changed = false;
+            for &i in &order {
+                let field: f64 = self.weights[i].iter().zip(state.iter()).map(|(w, s)| w.to_f64() * s).sum();
+                let next = if field > 0.0 {
+                    1.0
+                } else if field < 0.0 {
+                    -1.0
+                } else {
+                    state[i]
+                };
+                if next != state[i] {
+                    state[i] = next;
+                    changed = true;
+                }
+            }
+            if !changed {
+                return sweep;
+            }
+        }
+        max_sweeps
+    }
+
+    /// Finds fixed points of the zero-temperature dynamics by letting `samples` random
+    /// states settle (at most `EVALUATION_MAX_SWEEPS` sweeps each).
+    ///
+    /// # Returns
+    ///
+    /// The distinct settled states, in the order they were first reached. Stored patterns,
+    /// their inverses and spurious mixture states all appear here.
+    pub fn sample_attractors(&self, samples: usize, rng: &mut impl Rng) -> Vec<Vec<f64>> {
+        let mut attractors: Vec<Vec<f64>> = Vec::new();
+        for _ in 0..samples {
+            let mut state: Vec<f64> = (0..self.num_neurons)
+                .map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 })
+                .collect();
+            if self.settle(&mut state, EVALUATION_MAX_SWEEPS, rng) < EVALUATION_MAX_SWEEPS
+                && !attractors.contains(&state)
+            {
+                attractors.push(state);
+            }
+        }
+        attractors
+    }
+
+    /// Sweeps the inverse temperature to trace the retrieval phase transition m(T).
+    ///
+    /// At each `beta`, every trial starts from `cue`, runs `burn_in` asynchronous sweeps
+    /// to reach the steady state and then averages the overlap with `target` over
+    /// `measure` further sweeps. Above the critical temperature the overlap decays to 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `cue` - Initial state of every trial, e.g. a noisy version of `target`
+    /// * `target` - Pattern whose overlap is measured
+    /// * `betas` - Inverse temperatures to visit
+    /// * `trials` - Independent runs per inverse temperature
+    /// * `burn_in` - Sweeps discarded before measuring
+    /// * `measure` - Sweeps averaged over
+    /// * `order` - Order of the asynchronous updates within a sweep
+    /// * `rng` - Random number generator
+    /// * `observer` - Called with the number of finished inverse temperatures; the sweep
+    ///   stops early when it returns false
+    ///
+    /// # Returns
+    ///
+    /// Result with one point per visited inverse temperature
+    #[allow(clippy::too_many_arguments)]
+    pub fn temperature_sweep(
+        &self,
+        cue: &[f64],
+        target: &[f64],
+        betas: &[f64],
+        trials: usize,
+        burn_in: usize,
+        measure: usize,
+        order: SweepOrder,
+        rng: &mut impl Rng,
+        mut observer: impl FnMut(usize) -> bool,
+    ) -> Result<Vec<TemperaturePoint>, HopfieldError> {
+        Self::validate_state(cue, self.num_neurons)?;
+        if target.len() != self.num_neurons {
+            return Err(HopfieldError::DimensionMismatch(format!(
+                "Target has length {} but the network has {} neurons",
+                target.len(), self.num_neurons
+            )));
+        }
+
+        let mut points = Vec::with_capacity(betas.len());
+        for (k, &beta) in betas.iter().enumerate() {
+            let mut trial_means = Vec::with_capacity(trials);
+            for _ in 0..trials.max(1) {
+                let mut state = cue.to_vec();
+                let mut fields = LocalFields::new(&self.weights, &state);
+                let mut total = 0.0;
+                for sweep in 0..burn_in + measure.max(1) {
+                    for neuron_index in order.indices(self.num_neurons, rng) {
+                        self.update_neuron_cached(&mut state, &mut fields, neuron_index, beta, rng);
+                    }
+                    if sweep >= burn_in {
+                        total += overlap(&state, target);
+                    }
+                }
+                trial_means.push(total / measure.max(1) as f64);
+            }
+
+            let n = trial_means.len() as f64;
+            let mean_overlap = trial_means.iter().sum::<f64>() / n;
+            let variance = trial_means.iter().map(|m| (m - mean_overlap).powi(2)).sum::<f64>() / n;
+            points.push(TemperaturePoint { beta, mean_overlap, overlap_std: variance.sqrt() });
+            if !observer(k + 1) {
+                break;
+            }
+        }
+        Ok(points)
+    }
+
+    /// Measures how well each pattern is recalled from noisy cues.
+    ///
+    /// For every pattern and noise level, `trials` cues are made by flipping each bit of the
+    /// pattern with probability `noise_level`, and each cue settles under zero-temperature
+    /// asynchronous dynamics (at most `EVALUATION_MAX_SWEEPS` sweeps).
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Target patterns, usually the stored ones
+    /// * `noise_levels` - Bit flip probabilities to test (0.0 to 1.0)
+    /// * `trials` - Number of noisy cues per pattern and noise level
+    /// * `rng` - Random number generator for the cues and update order
+    ///
+    /// # Returns
+    ///
+    /// Result with one entry per (noise level, pattern) pair, ordered by noise level first
+    pub fn evaluate(
+        &self,
+        patterns: &[Vec<f64>],
+        noise_levels: &[f64],
+        trials: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<RecallStats>, HopfieldError> {
+        for pattern in patterns {
+            Self::validate_state(pattern, self.num_neurons)?;
+        }
+
+        let trials_f64 = trials.max(1) as f64;
+        let mut results = Vec::with_capacity(noise_levels.len() * patterns.len());
+        for &noise_level in noise_levels {
+            for (pattern_index, pattern) in patterns.iter().enumerate() {
+                let mut hits = 0;
+                let mut total_hamming = 0;
+                let mut total_sweeps = 0;
+                for _ in 0..trials {
+                    let mut state: Vec<f64> = pattern
+                        .iter()
+                        .map(|&v| if rng.gen::<f64>() < noise_level { -v } else { v })
+                        .collect();
+                    total_sweeps += self.settle(&mut state, EVALUATION_MAX_SWEEPS, rng);
+
+                    let hamming = hamming_distance(&state, pattern);
+                    total_hamming += hamming;
+                    if hamming == 0 {
+                        hits += 1;
+                    }
+                }
+
+                results.push(RecallStats {
+                    pattern_index,
+                    noise_level,
+                    accuracy: hits as f64 / trials_f64,
+                    mean_hamming_distance: total_hamming as f64 / trials_f64,
+                    mean_convergence_time: total_sweeps as f64 / trials_f64,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+impl TrainableParameters for HopfieldNetwork {
+    const MODEL: &'static str = "hopfield";
+
+    fn parameters(&self) -> SafeTensors {
+        SafeTensors::new()
+            .with_tensor("weights", Tensor::matrix(&self.weights))
+            .with_metadata("neurons", self.num_neurons)
+    }
+
+    fn from_parameters(parameters: &SafeTensors) -> Result<Self, RaumError> {
+        let weights = parameters
+            .tensor("weights")?
+            .to_rows()
+            .ok_or_else(|| RaumError::from("The weights are not a matrix"))?;
+        Ok(Self::from_weights(&weights)?)
+    }
 }
 
-// Implement the NeuralNetwork trait for HopfieldNetwork
-impl NeuralNetwork for HopfieldNetwork {
-    type Input = Vec<f64>;
-    type Output = Vec<Vec<f64>>;
+// Implement the AssociativeMemory trait for HopfieldNetwork
+impl<T: Scalar> AssociativeMemory for HopfieldNetwork<T> {
+    type Pattern = Vec<f64>;
     type Error = HopfieldError;
-    
-    // Note: The trait doesn't inherently support beta or rng.
-    // We'll call the run method directly in the UI where beta/rng is known.
-    // This forward implementation uses a default beta (e.g., 100.0 for near deterministic) 
-    // and creates its own rng instance.
-    fn forward(&self, input: &Self::Input) -> Result<Self::Output, Self::Error> {
-        let mut rng = rand::thread_rng();
-        let (states, _) = self.run(input, 100, 100.0, &mut rng)?; // High beta, internal rng
-        Ok(states)
-    }
-    
-    fn train(&mut self, data: &[Self::Input]) -> Result<(), Self::Error> {
-        // Default to PseudoInverse if called via trait?
-        self.train(data, TrainingRule::PseudoInverse)
-    }
-    
+
     fn size(&self) -> usize {
         self.num_neurons
     }
-}
\ No newline at end of file
+
+    // Patterns are stored with the pseudo-inverse rule, which also handles correlated patterns
+    fn store(&mut self, patterns: &[Self::Pattern]) -> Result<(), Self::Error> {
+        self.train(patterns, TrainingRule::PseudoInverse)
+    }
+
+    // Zero-temperature asynchronous dynamics from the cue until no neuron flips
+    fn recall(&self, cue: &Self::Pattern, rng: &mut impl Rng) -> Result<Self::Pattern, Self::Error> {
+        Self::validate_state(cue, self.num_neurons)?;
+        let mut state = cue.clone();
+        self.settle(&mut state, EVALUATION_MAX_SWEEPS, rng);
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_pattern_load_levels() {
+        let load = PatternLoad::new(10, 100, TrainingRule::Hebbian);
+        assert!((load.alpha() - 0.1).abs() < 1e-12);
+        assert_eq!(load.level(), LoadLevel::Safe);
+        assert_eq!(PatternLoad::new(12, 100, TrainingRule::Hebbian).level(), LoadLevel::NearCapacity);
+        assert_eq!(PatternLoad::new(14, 100, TrainingRule::Hebbian).level(), LoadLevel::Overloaded);
+        assert_eq!(PatternLoad::new(14, 100, TrainingRule::PseudoInverse).level(), LoadLevel::Safe);
+    }
+
+    #[test]
+    fn test_temperature_sweep_loses_retrieval_when_hot() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let pattern: Vec<f64> = (0..64).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect();
+        let mut net = HopfieldNetwork::new(64);
+        net.train(std::slice::from_ref(&pattern), TrainingRule::Hebbian).unwrap();
+
+        let points = net
+            .temperature_sweep(&pattern, &pattern, &[1.0, 0.001], 4, 20, 20, SweepOrder::RandomPermutation, &mut rng, |_| true)
+            .unwrap();
+        assert_eq!(points.len(), 2);
+        assert!(points[0].mean_overlap > 0.9);
+        assert!(points[1].mean_overlap.abs() < 0.3);
+    }
+
+    #[test]
+    fn test_train_observed_reports_rows_and_stops() {
+        let n = PSEUDO_INVERSE_ROW_BLOCK + 16;
+        let mut rng = StdRng::seed_from_u64(3);
+        let patterns: Vec<Vec<f64>> = (0..3)
+            .map(|_| (0..n).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        for (rule, first_report) in [(TrainingRule::Hebbian, 1), (TrainingRule::PseudoInverse, PSEUDO_INVERSE_ROW_BLOCK)] {
+            let mut full = HopfieldNetwork::new(n);
+            let mut rows = Vec::new();
+            assert!(full.train_observed(&patterns, rule, HebbianNormalization::None, |done| { rows.push(done); true }).unwrap());
+            assert_eq!((rows[0], rows.last().copied()), (first_report, Some(n)));
+
+            // Stopped after the first report, only the rows reported are set
+            let mut partial = HopfieldNetwork::new(n);
+            assert!(!partial.train_observed(&patterns, rule, HebbianNormalization::None, |_| false).unwrap());
+            assert_eq!(partial.weights()[..first_report], full.weights()[..first_report]);
+            assert!(partial.weights()[first_report].iter().all(|&w| w == 0.0));
+        }
+    }
+
+    #[test]
+    fn test_learning_one_at_a_time_matches_hebbian_training() {
+        let patterns = vec![vec![1.0, -1.0, 1.0, -1.0], vec![1.0, 1.0, -1.0, -1.0], vec![-1.0, 1.0, 1.0, 1.0]];
+        let mut batch = HopfieldNetwork::new(4);
+        batch.train(&patterns, TrainingRule::Hebbian).unwrap();
+        let mut incremental = HopfieldNetwork::new(4);
+        for pattern in &patterns {
+            incremental.learn(pattern, 1.0).unwrap();
+        }
+        assert_eq!(incremental.weights(), batch.weights());
+        assert!(incremental.learn(&[1.0, 1.0], 1.0).is_err());
+    }
+
+    #[test]
+    fn test_storkey_rule_starts_hebbian_and_subtracts_crosstalk() {
+        let patterns = vec![vec![1.0, 1.0, -1.0, -1.0], vec![1.0, -1.0, 1.0, -1.0]];
+        let mut net = HopfieldNetwork::new(4);
+        net.train(&patterns[..1], TrainingRule::Storkey).unwrap();
+        assert_eq!(net.weights()[0][1], 0.25);
+        assert_eq!(net.weights()[0][0], 0.0);
+
+        // ξ² on (0, 1): h_01 = W_02 ξ_2 + W_03 ξ_3 = 0 and h_10 = W_12 ξ_2 + W_13 ξ_3 = 0, so
+        // Δ = ξ_0 ξ_1 / 4
+        net.train(&patterns, TrainingRule::Storkey).unwrap();
+        assert_eq!(net.weights()[0][1], 0.0);
+        // (0, 3): h_03 = W_01 ξ_1 + W_02 ξ_2 = -0.5 and h_30 = W_31 ξ_1 + W_32 ξ_2 = 0.5,
+        // so Δ = (ξ_0 ξ_3 - ξ_0 h_30 - h_03 ξ_3) / 4 = -0.5
+        assert_eq!(net.weights()[0][3], -0.75);
+    }
+
+    #[test]
+    fn test_hebbian_normalization_scales_weights() {
+        // ξ_0 ξ_1 = 1 in all three patterns
+        let patterns = vec![vec![1.0, 1.0, 1.0, -1.0], vec![-1.0, -1.0, 1.0, 1.0], vec![1.0, 1.0, -1.0, 1.0]];
+        let mut net = HopfieldNetwork::new(4);
+        for (normalization, expected) in [
+            (HebbianNormalization::None, 3.0),
+            (HebbianNormalization::Neurons, 0.75),
+            (HebbianNormalization::Patterns, 1.0),
+        ] {
+            net.train_observed(&patterns, TrainingRule::Hebbian, normalization, |_| true).unwrap();
+            assert_eq!(net.weights()[0][1], expected);
+        }
+    }
+
+    #[test]
+    fn test_calibrated_beta_undoes_weight_scale() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let patterns: Vec<Vec<f64>> = (0..3)
+            .map(|_| (0..25).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let mut raw = HopfieldNetwork::new(25);
+        raw.train_observed(&patterns, TrainingRule::Hebbian, HebbianNormalization::None, |_| true).unwrap();
+        let mut normalized = HopfieldNetwork::new(25);
+        normalized.train_observed(&patterns, TrainingRule::Hebbian, HebbianNormalization::Neurons, |_| true).unwrap();
+
+        assert!((raw.field_scale() - 25.0 * normalized.field_scale()).abs() < 1e-9);
+        for synchronous in [false, true] {
+            let (beta_raw, beta_normalized) = (raw.calibrated_beta(2.0, synchronous), normalized.calibrated_beta(2.0, synchronous));
+            assert!((beta_raw * 25.0 - beta_normalized).abs() < 1e-9);
+        }
+        assert!((raw.calibrated_beta(2.0, true) * raw.field_scale() / 25.0 - 2.0).abs() < 1e-9);
+        assert_eq!(HopfieldNetwork::new(4).calibrated_beta(2.0, false), 2.0);
+    }
+
+    #[test]
+    fn test_pseudo_inverse_matches_direct_sums() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let n = 20;
+        let patterns: Vec<Vec<f64>> = (0..4)
+            .map(|_| (0..n).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let mut net = HopfieldNetwork::new(n);
+        net.train(&patterns, TrainingRule::PseudoInverse).unwrap();
+
+        // W_ij = 1/N Σ_{α,β} ξ_i^α (C⁻¹)_{αβ} ξ_j^β, term by term
+        let c = DMatrix::from_fn(4, 4, |a, b| overlap(&patterns[a], &patterns[b]));
+        let inverse = c.try_inverse().unwrap();
+        for i in 0..n {
+            for j in 0..n {
+                let mut sum = 0.0;
+                for a in 0..4 {
+                    for b in 0..4 {
+                        sum += patterns[a][i] * inverse[(a, b)] * patterns[b][j];
+                    }
+                }
+                let expected = if i == j { 0.0 } else { sum / n as f64 };
+                assert!((net.weights()[i][j] - expected).abs() < 1e-9);
+            }
+        }
+        // Stored patterns are fixed points
+        assert_eq!(net.update_step_deterministic(&patterns[0]).unwrap(), patterns[0]);
+    }
+
+    #[test]
+    fn test_strided_run_visits_single_updates() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let pattern: Vec<f64> = (0..16).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect();
+        let mut net = HopfieldNetwork::new(16);
+        net.train(std::slice::from_ref(&pattern), TrainingRule::Hebbian).unwrap();
+        let cue: Vec<f64> = pattern.iter().map(|&x| -x).collect();
+        let order = SweepOrder::RandomPermutation;
+
+        let mut steps = Vec::new();
+        let updates = net
+            .run_async_strided(&cue, 3, 0.5, order, 1, &mut StdRng::seed_from_u64(2), |update, state| {
+                steps.push((update, state.to_vec()));
+                true
+            })
+            .unwrap();
+        assert_eq!(updates, 48);
+        assert_eq!(steps.len(), 49);
+        assert!(steps.windows(2).all(|w| hamming_distance(&w[0].1, &w[1].1) <= 1));
+
+        // Every N-th update is the state after a sweep
+        let (sweeps, _) = net.run_async(&cue, 3, 0.5, order, &mut StdRng::seed_from_u64(2)).unwrap();
+        assert_eq!(sweeps.get(2), Some(&steps[32].1));
+    }
+
+    #[test]
+    fn test_flip_history_replays_run() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let pattern: Vec<f64> = (0..36).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect();
+        let mut net = HopfieldNetwork::new(36);
+        net.train(std::slice::from_ref(&pattern), TrainingRule::Hebbian).unwrap();
+        let cue: Vec<f64> = pattern.iter().enumerate().map(|(i, &x)| if i % 5 == 0 { -x } else { x }).collect();
+
+        let (full, _) = net.run_async(&cue, 10, 2.0, SweepOrder::RandomPermutation, &mut StdRng::seed_from_u64(1)).unwrap();
+        let mut flips = FlipHistory::new(SpinFlips, cue.clone(), 4);
+        let mut previous = cue.clone();
+        net.run_async_streamed(&cue, 10, 2.0, SweepOrder::RandomPermutation, &mut StdRng::seed_from_u64(1), |i, state| {
+            if i > 0 {
+                flips.push(i, SpinFlips::between(&previous, state));
+                previous.copy_from_slice(state);
+            }
+            true
+        })
+        .unwrap();
+
+        assert_eq!(flips.to_history(), full);
+        assert_eq!(flips.frame(7).as_ref(), full.get(7));
+        assert!(flips.delta(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_graph_topology_keeps_only_edges() {
+        let pattern = vec![1.0, -1.0, 1.0, 1.0];
+        let mut net = HopfieldNetwork::new(4);
+        net.train(std::slice::from_ref(&pattern), TrainingRule::Hebbian).unwrap();
+        net.apply_graph_topology(&Graph::cycle(4)).unwrap();
+
+        let w = net.weights();
+        assert_eq!(w[0][2], 0.0);
+        assert_eq!(w[1][3], 0.0);
+        assert_eq!(w[0][1], w[1][0]);
+        assert!(w[0][1] != 0.0 && w[0][3] != 0.0);
+        assert!(net.apply_graph_topology(&Graph::cycle(5)).is_err());
+    }
+
+    #[test]
+    fn test_cached_fields_match_direct_sums() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let patterns: Vec<Vec<f64>> = (0..3)
+            .map(|_| (0..30).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let mut net = HopfieldNetwork::new(30);
+        net.train(&patterns, TrainingRule::PseudoInverse).unwrap();
+        net.apply_erdos_renyi_topology(0.5, &mut rng);
+
+        let mut state = patterns[0].clone();
+        let mut fields = LocalFields::new(net.weights(), &state);
+        for step in 0..200 {
+            let neuron = step % 30;
+            net.update_neuron_cached(&mut state, &mut fields, neuron, 0.5, &mut rng);
+        }
+        for k in 0..30 {
+            let direct: f64 = net.weights()[k].iter().zip(&state).map(|(w, s)| w * s).sum();
+            assert!((fields.field(k) - direct).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_try_new_rejects_empty_networks() {
+        assert!(matches!(HopfieldNetwork::try_new(0), Err(HopfieldError::InvalidSize(_))));
+        assert!(HopfieldNetwork::<f32>::try_zeros(0).is_err());
+        assert_eq!(HopfieldNetwork::try_new(3).unwrap().size(), 3);
+    }
+
+    #[test]
+    fn test_single_precision_matches_double() {
+        let mut rng = StdRng::seed_from_u64(12);
+        let patterns: Vec<Vec<f64>> = (0..10)
+            .map(|_| (0..256).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let mut double = HopfieldNetwork::new(256);
+        double.train(&patterns, TrainingRule::PseudoInverse).unwrap();
+        let mut single = HopfieldNetwork::<f32>::zeros(256);
+        single.train(&patterns, TrainingRule::PseudoInverse).unwrap();
+
+        let scale = double.weights().iter().flatten().fold(0.0f64, |m, w| m.max(w.abs()));
+        for (row64, row32) in double.weights().iter().zip(single.weights()) {
+            for (&w64, &w32) in row64.iter().zip(row32) {
+                assert!((w64 - w32 as f64).abs() < 1e-6 * scale);
+            }
+        }
+        let (e64, e32) = (double.energy(&patterns[0]).unwrap(), single.energy(&patterns[0]).unwrap());
+        assert!((e64 - e32).abs() < 1e-5 * e64.abs());
+
+        // The same random cues are recalled alike
+        let recall64 = double.evaluate(&patterns, &[0.1, 0.3], 5, &mut StdRng::seed_from_u64(3)).unwrap();
+        let recall32 = single.evaluate(&patterns, &[0.1, 0.3], 5, &mut StdRng::seed_from_u64(3)).unwrap();
+        assert_eq!(recall64, recall32);
+
+        // Asynchronous runs with cached single precision fields follow the same path
+        let (run64, _) = double.run_async(&patterns[1], 5, 50.0, SweepOrder::RandomPermutation, &mut StdRng::seed_from_u64(5)).unwrap();
+        let (run32, _) = single.run_async(&patterns[1], 5, 50.0, SweepOrder::RandomPermutation, &mut StdRng::seed_from_u64(5)).unwrap();
+        assert_eq!(run64.into_states(), run32.into_states());
+        assert_eq!(double.to_precision::<f32>().weights(), single.weights());
+
+        // Deterministic synchronous steps keep stored patterns in either precision
+        assert_eq!(double.update_step_deterministic(&patterns[0]).unwrap(), patterns[0]);
+        assert_eq!(single.update_step_deterministic(&patterns[0]).unwrap(), patterns[0]);
+    }
+
+    #[test]
+    fn test_modules_scale_inter_module_weights() {
+        let modules = Modules::grid(4, 4, 2, 2);
+        assert_eq!(modules.count(), 4);
+        assert_eq!((modules.module_of(0), modules.module_of(3), modules.module_of(12), modules.module_of(15)), (0, 1, 2, 3));
+
+        let mut rng = StdRng::seed_from_u64(6);
+        let pattern: Vec<f64> = (0..16).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect();
+        let mut net = HopfieldNetwork::new(16);
+        net.train(std::slice::from_ref(&pattern), TrainingRule::Hebbian).unwrap();
+        let before = net.weights().to_vec();
+        net.apply_modules(&modules, 0.25).unwrap();
+        for (i, row) in before.iter().enumerate() {
+            for (j, &w) in row.iter().enumerate() {
+                let scale = if modules.module_of(i) == modules.module_of(j) { 1.0 } else { 0.25 };
+                assert_eq!(net.weights()[i][j], w * scale);
+            }
+        }
+        assert!(net.apply_modules(&Modules::grid(2, 2, 1, 1), 0.0).is_err());
+
+        // Flipping one module leaves the overlaps of the others at 1
+        let mut state = pattern.clone();
+        for (i, s) in state.iter_mut().enumerate() {
+            if modules.module_of(i) == 3 {
+                *s = -*s;
+            }
+        }
+        assert_eq!(modules.overlaps(&state, &pattern), vec![1.0, 1.0, 1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_prune_by_magnitude_removes_smallest_weights() {
+        let mut rng = StdRng::seed_from_u64(12);
+        let patterns: Vec<Vec<f64>> = (0..5)
+            .map(|_| (0..30).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let mut net = HopfieldNetwork::new(30);
+        net.train(&patterns, TrainingRule::Hebbian).unwrap();
+        let largest_pruned = |original: &HopfieldNetwork, pruned: &HopfieldNetwork| {
+            let (a, b) = (original.weights(), pruned.weights());
+            (0..30)
+                .flat_map(|i| (0..30).map(move |j| (i, j)))
+                .filter(|&(i, j)| a[i][j] != 0.0 && b[i][j] == 0.0)
+                .map(|(i, j)| a[i][j].abs())
+                .fold(0.0, f64::max)
+        };
+
+        let mut pruned = net.clone();
+        assert!(pruned.prune_by_magnitude(0.6) > 0);
+        assert!(pruned.sparsity() >= 0.6);
+        // Every surviving weight is at least as strong as every pruned one
+        let weakest_kept = pruned.weights().iter().flatten().filter(|w| **w != 0.0).fold(f64::INFINITY, |m, w| m.min(w.abs()));
+        assert!(largest_pruned(&net, &pruned) <= weakest_kept);
+        let w = pruned.weights();
+        assert!((0..30).all(|i| (0..30).all(|j| w[i][j] == w[j][i])));
+        assert_eq!(pruned.prune_by_magnitude(0.6), 0);
+    }
+
+    #[test]
+    fn test_parameters_round_trip_through_safetensors() {
+        let patterns = vec![vec![1.0, -1.0, 1.0, -1.0], vec![1.0, 1.0, -1.0, -1.0]];
+        let mut net = HopfieldNetwork::new(4);
+        net.train(&patterns, TrainingRule::PseudoInverse).unwrap();
+        let file = net.to_safetensors();
+        assert_eq!(file.metadata["model"], "hopfield");
+
+        let bytes = file.with_metadata("rule", "PseudoInverse").to_bytes();
+        let restored = HopfieldNetwork::from_safetensors(&SafeTensors::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(restored.weights(), net.weights());
+        let other = SafeTensors::new().with_metadata("model", "boltzmann");
+        assert!(HopfieldNetwork::from_safetensors(&other).is_err());
+    }
+
+    #[test]
+    fn test_quantization_levels() {
+        let mut rng = StdRng::seed_from_u64(15);
+        let patterns: Vec<Vec<f64>> = (0..6)
+            .map(|_| (0..25).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let mut net = HopfieldNetwork::new(25);
+        net.train(&patterns, TrainingRule::PseudoInverse).unwrap();
+        let distinct = |net: &HopfieldNetwork| {
+            let mut values: Vec<f64> = net.weights().iter().flatten().copied().collect();
+            values.sort_by(f64::total_cmp);
+            values.dedup();
+            values
+        };
+
+        let mut uniform = net.clone();
+        uniform.quantize(Quantization::Uniform { bits: 3 });
+        // Three magnitudes of each sign, and zero
+        assert!(distinct(&uniform).len() <= 7);
+        assert!(uniform.weights().iter().enumerate().all(|(i, row)| row[i] == 0.0));
+
+        let mut ternary = net.clone();
+        ternary.quantize(Quantization::Ternary { threshold: 0.5 });
+        assert!(distinct(&ternary).iter().all(|w| [-1.0, 0.0, 1.0].contains(w)));
+        // Clean patterns remain fixed points of a strongly ternarized network
+        assert_eq!(ternary.update_step_deterministic(&patterns[0]).unwrap(), patterns[0]);
+    }
+
+    #[test]
+    fn test_asymmetric_dilution_and_weight_noise() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let patterns: Vec<Vec<f64>> = (0..3)
+            .map(|_| (0..40).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let mut trained = HopfieldNetwork::new(40);
+        trained.train(&patterns, TrainingRule::Hebbian).unwrap();
+        let asymmetric_pairs = |net: &HopfieldNetwork| {
+            let w = net.weights();
+            (0..40).flat_map(|i| (0..i).map(move |j| (i, j))).filter(|&(i, j)| w[i][j] != w[j][i]).count()
+        };
+
+        let mut symmetric = trained.clone();
+        symmetric.apply_asymmetric_dilution(0.5, 0.0, &mut rng);
+        assert_eq!(asymmetric_pairs(&symmetric), 0);
+        let mut asymmetric = trained.clone();
+        asymmetric.apply_asymmetric_dilution(0.5, 1.0, &mut rng);
+        assert!(asymmetric_pairs(&asymmetric) > 0);
+
+        let mut noisy = trained.clone();
+        noisy.add_weight_noise(0.5, &mut rng);
+        assert_eq!(asymmetric_pairs(&noisy), 40 * 39 / 2);
+        assert!(noisy.weights().iter().enumerate().all(|(i, row)| row[i] == 0.0));
+    }
+
+    #[test]
+    fn test_orthogonalization() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let base: Vec<f64> = (0..64).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect();
+        // Two correlated patterns: the second differs from the first in 8 places
+        let mut similar = base.clone();
+        for value in similar.iter_mut().take(8) {
+            *value = -*value;
+        }
+        let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>() / a.len() as f64;
+        assert!(dot(&base, &similar) > 0.7);
+        let patterns = vec![base, similar];
+
+        let exact = Orthogonalization::GramSchmidt.apply(&patterns);
+        assert!(dot(&exact[0], &exact[1]).abs() < 1e-9);
+        assert!((dot(&exact[1], &exact[1]) - 1.0).abs() < 1e-9);
+
+        let signed = Orthogonalization::NearOrthogonal.apply(&patterns);
+        assert!(signed.iter().flatten().all(|&x| x == 1.0 || x == -1.0));
+        assert_eq!(signed[0], patterns[0]);
+        assert!(dot(&signed[0], &signed[1]).abs() < 0.05);
+        assert_eq!(Orthogonalization::None.apply(&patterns), patterns);
+    }
+
+    #[test]
+    fn test_train_on_gram_schmidt_patterns() {
+        let mut rng = StdRng::seed_from_u64(12);
+        let patterns: Vec<Vec<f64>> = (0..3)
+            .map(|_| (0..64).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let exact = Orthogonalization::GramSchmidt.apply(&patterns);
+        let mut network = HopfieldNetwork::new(64);
+        // The real-valued vectors are not valid states
+        assert!(network.train(&exact, TrainingRule::Hebbian).is_err());
+
+        let trainable = Orthogonalization::bipolar(&exact);
+        assert_eq!(Orthogonalization::bipolar(&patterns), patterns);
+        network.train(&trainable, TrainingRule::Hebbian).unwrap();
+        assert!(network.update_step_deterministic(&trainable[0]).is_ok());
+    }
+
+    #[test]
+    fn test_evaluate_recalls_clean_patterns() {
+        let patterns = vec![
+            vec![1.0, 1.0, 1.0, 1.0, -1.0, -1.0, -1.0, -1.0],
+            vec![1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0],
+        ];
+        let mut net = HopfieldNetwork::new(8);
+        net.train(&patterns, TrainingRule::PseudoInverse).unwrap();
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let results = net.evaluate(&patterns, &[0.0, 0.5], 10, &mut rng).unwrap();
+        assert_eq!(results.len(), 4);
+
+        // Noise-free cues are fixed points: recalled at once with no flips
+        for stats in results.iter().filter(|s| s.noise_level == 0.0) {
+            assert_eq!(stats.accuracy, 1.0);
+            assert_eq!(stats.mean_hamming_distance, 0.0);
+            assert_eq!(stats.mean_convergence_time, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_sweep_orders_visit_neurons() {
+        let mut rng = StdRng::seed_from_u64(5);
+        assert_eq!(SweepOrder::Raster.indices(4, &mut rng), vec![0, 1, 2, 3]);
+        assert_eq!(SweepOrder::Checkerboard { width: 2 }.indices(4, &mut rng), vec![0, 3, 1, 2]);
+
+        let mut permutation = SweepOrder::RandomPermutation.indices(9, &mut rng);
+        permutation.sort_unstable();
+        assert_eq!(permutation, (0..9).collect::<Vec<_>>());
+        let with_replacement = SweepOrder::RandomWithReplacement.indices(9, &mut rng);
+        assert_eq!(with_replacement.len(), 9);
+        assert!(with_replacement.iter().all(|&i| i < 9));
+    }
+
+    #[test]
+    fn test_sample_attractors_finds_stored_patterns() {
+        let patterns = vec![vec![1.0, 1.0, 1.0, 1.0, -1.0, -1.0, -1.0, -1.0]];
+        let mut net = HopfieldNetwork::new(8);
+        net.train(&patterns, TrainingRule::Hebbian).unwrap();
+
+        // With one pattern the only fixed points are the pattern and its inverse
+        let mut rng = StdRng::seed_from_u64(7);
+        let attractors = net.sample_attractors(20, &mut rng);
+        let inverse: Vec<f64> = patterns[0].iter().map(|x| -x).collect();
+        assert!(attractors.contains(&patterns[0]));
+        assert!(attractors.iter().all(|a| *a == patterns[0] || *a == inverse));
+    }
+}
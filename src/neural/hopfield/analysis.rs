@@ -0,0 +1,390 @@
+use nalgebra::DMatrix;
+use rand::Rng;
+
+use super::{HopfieldError, HopfieldNetwork, RecallStats, Scalar, TrainingRule};
+use crate::core::metrics::overlap;
+
+/// Eigen-decomposition of a Hopfield weight matrix.
+///
+/// The stored patterns live in the span of the eigenvectors with the largest
+/// eigenvalues: for Hebbian learning of P orthogonal patterns, W = Σ ξξᵀ - P I has
+/// eigenvalue N - P on the pattern subspace and -P on its complement. Asymmetric
+/// weights are symmetrized first, as the energy only depends on (W + Wᵀ) / 2.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightSpectrum {
+    /// Eigenvalues in descending order
+    pub eigenvalues: Vec<f64>,
+
+    /// Unit eigenvectors, aligned with `eigenvalues`
+    pub eigenvectors: Vec<Vec<f64>>,
+
+    /// Whether the weights had to be symmetrized
+    pub symmetrized: bool,
+}
+
+impl WeightSpectrum {
+    /// Diagonalizes the weight matrix of `network`
+    pub fn new<T: Scalar>(network: &HopfieldNetwork<T>) -> Self {
+        let n = network.size();
+        let weights = network.weights();
+        let symmetrized = (0..n).any(|i| (0..i).any(|j| weights[i][j] != weights[j][i]));
+        let matrix = DMatrix::from_fn(n, n, |i, j| 0.5 * (weights[i][j].to_f64() + weights[j][i].to_f64()));
+
+        let eigen = matrix.symmetric_eigen();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+
+        WeightSpectrum {
+            eigenvalues: order.iter().map(|&k| eigen.eigenvalues[k]).collect(),
+            eigenvectors: order
+                .iter()
+                .map(|&k| eigen.eigenvectors.column(k).iter().copied().collect())
+                .collect(),
+            symmetrized,
+        }
+    }
+
+    /// Fraction of |ξ|² that lies in the span of the `k` leading eigenvectors: close to 1
+    /// when the pattern is embedded in the dominant modes of the coupling
+    pub fn captured_fraction(&self, pattern: &[f64], k: usize) -> f64 {
+        let norm: f64 = pattern.iter().map(|x| x * x).sum();
+        if norm == 0.0 {
+            return 0.0;
+        }
+        let captured: f64 = self
+            .eigenvectors
+            .iter()
+            .take(k)
+            .map(|v| v.iter().zip(pattern).map(|(a, b)| a * b).sum::<f64>().powi(2))
+            .sum();
+        captured / norm
+    }
+}
+
+/// Largest network whose energy surface is computed; it has 2^20 states
+pub const MAX_SURFACE_NEURONS: usize = 20;
+
+/// Position of `bits` along a reflected Gray code, so that neighbouring positions
+/// differ in exactly one bit
+fn gray_position(bits: usize) -> usize {
+    let mut position = bits;
+    let mut shift = bits >> 1;
+    while shift != 0 {
+        position ^= shift;
+        shift >>= 1;
+    }
+    position
+}
+
+/// The energy of every state of a small network, laid out on a grid. The first
+/// `column_bits` neurons select the column and the others the row, each ordered along
+/// a Gray code, so that neighbouring cells differ by a single flipped neuron and the
+/// single-flip dynamics move between adjacent cells or wrap to the other end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnergySurface {
+    pub column_bits: usize,
+    pub row_bits: usize,
+    /// Energies row by row, `columns()` per row
+    pub energies: Vec<f64>,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl EnergySurface {
+    /// Enumerates the 2^N states of `network` along a Gray code, updating the energy
+    /// incrementally after each flip
+    pub fn new<T: Scalar>(network: &HopfieldNetwork<T>) -> Result<Self, HopfieldError> {
+        let n = network.size();
+        if n == 0 || n > MAX_SURFACE_NEURONS {
+            return Err(HopfieldError::InvalidSize(format!(
+                "The energy surface needs 1 to {} neurons, the network has {}",
+                MAX_SURFACE_NEURONS, n
+            )));
+        }
+        let weights = network.weights();
+        let coupling = |i: usize, j: usize| if i == j { 0.0 } else { weights[i][j].to_f64() + weights[j][i].to_f64() };
+
+        let column_bits = n.div_ceil(2);
+        let row_bits = n - column_bits;
+        let mut surface = EnergySurface { column_bits, row_bits, energies: vec![0.0; 1 << n], min: 0.0, max: 0.0 };
+
+        // Start from all neurons at -1; fields[i] = Σ_j (W_ij + W_ji) s_j
+        let mut state = vec![-1.0; n];
+        let mut fields: Vec<f64> = (0..n).map(|i| (0..n).map(|j| -coupling(i, j)).sum()).collect();
+        let mut energy = network.energy(&state)?;
+        let mut bits = 0usize;
+        for step in 0..(1usize << n) {
+            if step > 0 {
+                // Gray code step: flip the lowest set bit of the step counter
+                let k = step.trailing_zeros() as usize;
+                energy += 2.0 * state[k] * fields[k] / n as f64;
+                state[k] = -state[k];
+                for (i, field) in fields.iter_mut().enumerate() {
+                    *field += 2.0 * state[k] * coupling(i, k);
+                }
+                bits ^= 1 << k;
+            }
+            let index = surface.index_of_bits(bits);
+            surface.energies[index] = energy;
+        }
+        surface.min = surface.energies.iter().copied().fold(f64::INFINITY, f64::min);
+        surface.max = surface.energies.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Ok(surface)
+    }
+
+    pub fn columns(&self) -> usize {
+        1 << self.column_bits
+    }
+
+    pub fn rows(&self) -> usize {
+        1 << self.row_bits
+    }
+
+    fn index_of_bits(&self, bits: usize) -> usize {
+        let column = gray_position(bits & (self.columns() - 1));
+        let row = gray_position(bits >> self.column_bits);
+        row * self.columns() + column
+    }
+
+    /// (column, row) of a ±1 state, None if its size does not match
+    pub fn cell_of(&self, state: &[f64]) -> Option<(usize, usize)> {
+        if state.len() != self.column_bits + self.row_bits {
+            return None;
+        }
+        let bits = state.iter().enumerate().filter(|(_, &s)| s > 0.0).fold(0, |bits, (i, _)| bits | (1 << i));
+        let index = self.index_of_bits(bits);
+        Some((index % self.columns(), index / self.columns()))
+    }
+
+    pub fn energy_at(&self, column: usize, row: usize) -> f64 {
+        self.energies[row * self.columns() + column]
+    }
+}
+
+/// How far below the average robustness of all patterns a pattern's robustness must be
+/// for it to count as fragile
+pub const FRAGILE_MARGIN: f64 = 0.1;
+
+/// Recall accuracy of every stored pattern over a grid of noise levels, next to how
+/// strongly each pattern overlaps the others. Patterns with large mutual overlaps are
+/// usually the first to fail, as their cues fall into each other's basins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobustnessMap {
+    /// Bit flip probabilities, in increasing order
+    pub noise_levels: Vec<f64>,
+    /// `accuracy[p][k]`: fraction of trials cued with pattern p at noise level k that
+    /// ended on it
+    pub accuracy: Vec<Vec<f64>>,
+    /// Largest |overlap| of each pattern with another stored pattern
+    pub max_overlaps: Vec<f64>,
+}
+
+impl RobustnessMap {
+    /// `steps` evenly spaced noise levels from 0 to `max_noise`
+    pub fn noise_grid(max_noise: f64, steps: usize) -> Vec<f64> {
+        let steps = steps.max(2);
+        (0..steps).map(|k| max_noise * k as f64 / (steps - 1) as f64).collect()
+    }
+
+    /// Arranges the results of `HopfieldNetwork::evaluate` on `patterns` at `noise_levels`
+    /// by pattern and noise level. Missing combinations count as failed recall.
+    pub fn from_stats(patterns: &[Vec<f64>], noise_levels: &[f64], stats: &[RecallStats]) -> Self {
+        let mut accuracy = vec![vec![0.0; noise_levels.len()]; patterns.len()];
+        for s in stats {
+            let level = noise_levels.iter().position(|&noise| noise == s.noise_level);
+            if let (Some(row), Some(k)) = (accuracy.get_mut(s.pattern_index), level) {
+                row[k] = s.accuracy;
+            }
+        }
+        let max_overlaps = patterns
+            .iter()
+            .enumerate()
+            .map(|(p, a)| {
+                patterns
+                    .iter()
+                    .enumerate()
+                    .filter(|&(q, _)| q != p)
+                    .map(|(_, b)| overlap(a, b).abs())
+                    .fold(0.0, f64::max)
+            })
+            .collect();
+        RobustnessMap { noise_levels: noise_levels.to_vec(), accuracy, max_overlaps }
+    }
+
+    /// Mean accuracy of `pattern` over the noise levels, 1 for a pattern recalled from
+    /// every cue
+    pub fn robustness(&self, pattern: usize) -> f64 {
+        let row = &self.accuracy[pattern];
+        row.iter().sum::<f64>() / row.len().max(1) as f64
+    }
+
+    /// Noise level at which the accuracy of `pattern` first drops below one half,
+    /// interpolated between grid points; None if it never does
+    pub fn critical_noise(&self, pattern: usize) -> Option<f64> {
+        let row = &self.accuracy[pattern];
+        let k = row.iter().position(|&a| a < 0.5)?;
+        if k == 0 {
+            return Some(self.noise_levels[0]);
+        }
+        let (a0, a1) = (row[k - 1], row[k]);
+        let (n0, n1) = (self.noise_levels[k - 1], self.noise_levels[k]);
+        Some(n0 + (n1 - n0) * (a0 - 0.5) / (a0 - a1))
+    }
+
+    /// Patterns whose robustness is more than `FRAGILE_MARGIN` below the average, most
+    /// fragile first
+    pub fn fragile(&self) -> Vec<usize> {
+        let count = self.accuracy.len();
+        if count == 0 {
+            return Vec::new();
+        }
+        let average = (0..count).map(|p| self.robustness(p)).sum::<f64>() / count as f64;
+        let mut fragile: Vec<usize> = (0..count).filter(|&p| self.robustness(p) < average - FRAGILE_MARGIN).collect();
+        fragile.sort_by(|&a, &b| self.robustness(a).total_cmp(&self.robustness(b)));
+        fragile
+    }
+}
+
+/// Recall accuracy of a network trained with one rule at one load
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapacityPoint {
+    pub rule: TrainingRule,
+    /// Number of stored patterns
+    pub patterns: usize,
+    /// Load α = P/N
+    pub load: f64,
+    /// Fraction of noisy cues, over all stored patterns, that ended on their pattern
+    pub accuracy: f64,
+}
+
+/// Capacity benchmark of training rules: for every count in `pattern_counts`, draws that
+/// many random patterns of `neurons` units, trains a network on them with each of
+/// `rules` and recalls every pattern from `trials` cues with `noise` flipped bits. All
+/// rules see the same patterns. `observer` gets the number of networks evaluated after
+/// each; when it returns false the benchmark stops and returns the points so far.
+///
+/// # Returns
+///
+/// Result with one point per (count, rule) pair, ordered by count first
+pub fn compare_rules(
+    neurons: usize,
+    rules: &[TrainingRule],
+    pattern_counts: &[usize],
+    noise: f64,
+    trials: usize,
+    rng: &mut impl Rng,
+    mut observer: impl FnMut(usize) -> bool,
+) -> Result<Vec<CapacityPoint>, HopfieldError> {
+    let mut points = Vec::with_capacity(rules.len() * pattern_counts.len());
+    for &count in pattern_counts {
+        let patterns: Vec<Vec<f64>> = (0..count)
+            .map(|_| (0..neurons).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        for &rule in rules {
+            let mut net = HopfieldNetwork::try_new(neurons)?;
+            net.train(&patterns, rule)?;
+            let stats = net.evaluate(&patterns, &[noise], trials, rng)?;
+            let accuracy = stats.iter().map(|s| s.accuracy).sum::<f64>() / stats.len().max(1) as f64;
+            points.push(CapacityPoint { rule, patterns: count, load: count as f64 / neurons as f64, accuracy });
+            if !observer(points.len()) {
+                return Ok(points);
+            }
+        }
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Rows of a 16 × 16 Hadamard matrix, which are mutually orthogonal
+    fn walsh_pattern(mask: usize) -> Vec<f64> {
+        (0..16usize)
+            .map(|i| if (i & mask).count_ones().is_multiple_of(2) { 1.0 } else { -1.0 })
+            .collect()
+    }
+
+    #[test]
+    fn test_hebbian_spectrum_of_orthogonal_patterns() {
+        let patterns: Vec<Vec<f64>> = [1, 6, 11].iter().map(|&m| walsh_pattern(m)).collect();
+        let mut net = HopfieldNetwork::new(16);
+        net.train(&patterns, TrainingRule::Hebbian).unwrap();
+        let spectrum = WeightSpectrum::new(&net);
+
+        assert!(!spectrum.symmetrized);
+        assert!(spectrum.eigenvalues[..3].iter().all(|&l| (l - 13.0).abs() < 1e-9));
+        assert!(spectrum.eigenvalues[3..].iter().all(|&l| (l + 3.0).abs() < 1e-9));
+        for pattern in &patterns {
+            assert!((spectrum.captured_fraction(pattern, 3) - 1.0).abs() < 1e-9);
+        }
+        assert!(spectrum.captured_fraction(&walsh_pattern(2), 3) < 1e-9);
+
+        let norm: f64 = spectrum.eigenvectors[0].iter().map(|x| x * x).sum();
+        assert!((norm - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_energy_surface_matches_direct_energies() {
+        let patterns = vec![vec![1.0, -1.0, 1.0, 1.0, -1.0], vec![-1.0, -1.0, 1.0, -1.0, 1.0]];
+        let mut net = HopfieldNetwork::new(5);
+        net.train(&patterns, TrainingRule::Hebbian).unwrap();
+        let surface = EnergySurface::new(&net).unwrap();
+        assert_eq!((surface.columns(), surface.rows()), (8, 4));
+
+        for bits in 0..32usize {
+            let state: Vec<f64> = (0..5).map(|i| if bits & (1 << i) != 0 { 1.0 } else { -1.0 }).collect();
+            let (column, row) = surface.cell_of(&state).unwrap();
+            assert!((surface.energy_at(column, row) - net.energy(&state).unwrap()).abs() < 1e-9);
+        }
+        // Stored patterns sit at the bottom of the surface
+        let (column, row) = surface.cell_of(&patterns[0]).unwrap();
+        assert!((surface.energy_at(column, row) - surface.min).abs() < 1e-9);
+        assert!(EnergySurface::new(&HopfieldNetwork::new(21)).is_err());
+    }
+
+    #[test]
+    fn test_compare_rules_ranks_capacities() {
+        let rules = [TrainingRule::Hebbian, TrainingRule::Storkey, TrainingRule::PseudoInverse];
+        let mut rng = StdRng::seed_from_u64(11);
+        let points = compare_rules(64, &rules, &[2, 24], 0.0, 1, &mut rng, |_| true).unwrap();
+        assert_eq!(points.len(), 6);
+        assert_eq!((points[5].rule, points[5].patterns, points[5].load), (TrainingRule::PseudoInverse, 24, 0.375));
+        // Every rule stores two patterns; at α = 0.375 the Hebbian rule has lost most of
+        // them and only the pseudo-inverse rule keeps all as fixed points
+        assert!(points[..3].iter().all(|p| p.accuracy == 1.0));
+        assert!(points[3].accuracy < points[4].accuracy);
+        assert_eq!(points[5].accuracy, 1.0);
+
+        let mut evaluated = 0;
+        let partial = compare_rules(16, &rules, &[2], 0.0, 1, &mut rng, |n| { evaluated = n; n < 2 }).unwrap();
+        assert_eq!((partial.len(), evaluated), (2, 2));
+    }
+
+    #[test]
+    fn test_robustness_map_finds_fragile_pattern() {
+        let patterns = vec![vec![1.0, 1.0, 1.0, 1.0], vec![1.0, 1.0, 1.0, -1.0], vec![1.0, -1.0, 1.0, -1.0]];
+        let levels = RobustnessMap::noise_grid(0.3, 4);
+        assert_eq!(levels.len(), 4);
+        let stats: Vec<RecallStats> = (0..3)
+            .flat_map(|p| {
+                levels.iter().enumerate().map(move |(k, &noise_level)| RecallStats {
+                    pattern_index: p,
+                    noise_level,
+                    // Pattern 1 collapses after the first noise step, the others degrade slowly
+                    accuracy: if p == 1 { [1.0, 0.0, 0.0, 0.0][k] } else { [1.0, 1.0, 0.8, 0.6][k] },
+                    mean_hamming_distance: 0.0,
+                    mean_convergence_time: 0.0,
+                })
+            })
+            .collect();
+        let map = RobustnessMap::from_stats(&patterns, &levels, &stats);
+
+        assert_eq!(map.fragile(), vec![1]);
+        assert!((map.critical_noise(1).unwrap() - 0.05).abs() < 1e-12);
+        assert_eq!(map.critical_noise(0), None);
+        assert!((map.max_overlaps[0] - 0.5).abs() < 1e-12);
+    }
+}
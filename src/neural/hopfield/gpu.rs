@@ -0,0 +1,119 @@
+use eframe::wgpu;
+
+use super::{HopfieldNetwork, Scalar};
+use crate::graphics::compute::{bytes_to_f32, f32_bytes, ComputeError, GpuContext};
+use crate::graphics::pipeline::{Pipeline, PipelineConfig};
+
+/// Threads per workgroup; must match `@workgroup_size` in the shader
+const WORKGROUP_SIZE: u32 = 64;
+
+/// One thread per neuron: the sign of its field, keeping the old value on a zero field
+const SWEEP_SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> weights: array<f32>;
+@group(0) @binding(1) var<storage, read> state_in: array<f32>;
+@group(0) @binding(2) var<storage, read_write> state_out: array<f32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = arrayLength(&state_in);
+    let i = id.x;
+    if (i >= n) {
+        return;
+    }
+    var field = 0.0;
+    for (var j = 0u; j < n; j = j + 1u) {
+        field = field + weights[i * n + j] * state_in[j];
+    }
+    if (field > 0.0) {
+        state_out[i] = 1.0;
+    } else if (field < 0.0) {
+        state_out[i] = -1.0;
+    } else {
+        state_out[i] = state_in[i];
+    }
+}
+"#;
+
+/// Zero-temperature synchronous sweeps of a Hopfield network in a wgpu compute kernel,
+/// the GPU counterpart of `HopfieldNetwork::update_step_deterministic` in single
+/// precision. The weights are uploaded once; each execution uploads the state, runs
+/// `sweeps` sweeps between two state buffers and reads the result back.
+pub struct GpuHopfieldSweep {
+    context: GpuContext,
+    num_neurons: usize,
+    pipeline: wgpu::ComputePipeline,
+    weights: wgpu::Buffer,
+
+    /// Sweeps per execution
+    pub sweeps: usize,
+}
+
+impl GpuHopfieldSweep {
+    pub fn new<T: Scalar>(context: &GpuContext, network: &HopfieldNetwork<T>, sweeps: usize) -> Result<Self, ComputeError> {
+        let n = network.size();
+        context.check_buffer_size((n * n * 4) as u64)?;
+        let weights = f32_bytes(network.weights().iter().flatten().map(|w| w.to_f64() as f32));
+        Ok(GpuHopfieldSweep {
+            context: context.clone(),
+            num_neurons: n,
+            pipeline: context.compute_pipeline("hopfield_sweep", SWEEP_SHADER),
+            weights: context.storage_buffer("hopfield_weights", &weights),
+            sweeps,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.num_neurons
+    }
+}
+
+impl Pipeline for GpuHopfieldSweep {
+    type Input = Vec<f64>;
+    type Output = Vec<f64>;
+    type Error = ComputeError;
+
+    fn execute(&self, input: &Vec<f64>) -> Result<Vec<f64>, ComputeError> {
+        if input.len() != self.num_neurons {
+            return Err(ComputeError::DimensionMismatch(format!(
+                "State has {} neurons but the network has {}",
+                input.len(),
+                self.num_neurons
+            )));
+        }
+        let context = &self.context;
+        let state = f32_bytes(input.iter().map(|&s| s as f32));
+        let buffers = [context.storage_buffer("hopfield_state_a", &state), context.storage_buffer("hopfield_state_b", &state)];
+        // Even sweeps read A and write B, odd sweeps the other way round
+        let bind_groups = [
+            context.bind_buffers("hopfield_sweep_ab", &self.pipeline, &[&self.weights, &buffers[0], &buffers[1]]),
+            context.bind_buffers("hopfield_sweep_ba", &self.pipeline, &[&self.weights, &buffers[1], &buffers[0]]),
+        ];
+
+        let mut encoder = context.encoder("hopfield_sweeps");
+        let workgroups = (self.num_neurons as u32).div_ceil(WORKGROUP_SIZE);
+        for sweep in 0..self.sweeps {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("hopfield_sweep"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_groups[sweep % 2], &[]);
+            pass.dispatch_workgroups(workgroups, 1, 1);
+        }
+        let result = &buffers[self.sweeps % 2];
+        let bytes = context.submit_and_read(encoder, result)?;
+        Ok(bytes_to_f32(&bytes).into_iter().map(f64::from).collect())
+    }
+
+    /// The network size is fixed by the weights, so the grid has to match it
+    fn configure(&mut self, config: &PipelineConfig) -> Result<(), ComputeError> {
+        let cells = (config.width * config.height) as usize;
+        if cells != self.num_neurons {
+            return Err(ComputeError::DimensionMismatch(format!(
+                "A {}x{} grid has {} cells but the network has {} neurons",
+                config.width, config.height, cells, self.num_neurons
+            )));
+        }
+        Ok(())
+    }
+}
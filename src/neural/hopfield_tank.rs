@@ -0,0 +1,553 @@
+use std::error::Error;
+use std::fmt;
+use rand::Rng;
+
+use super::DynamicalSystem;
+
+/// Error types for the Hopfield-Tank optimization solver
+#[derive(Debug)]
+pub enum OptimizationError {
+    DimensionMismatch(String),
+    InvalidProblem(String),
+}
+
+impl fmt::Display for OptimizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptimizationError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+            OptimizationError::InvalidProblem(msg) => write!(f, "Invalid problem: {}", msg),
+        }
+    }
+}
+
+impl Error for OptimizationError {}
+
+/// A combinatorial problem that can be mapped onto a continuous Hopfield network
+#[derive(Debug, Clone)]
+pub enum OptimizationProblem {
+    /// Travelling salesman: unit (x, i) means "city x is visited at position i"
+    Tsp {
+        distances: Vec<Vec<f64>>,
+    },
+    /// Graph coloring: unit (v, c) means "vertex v has color c"
+    GraphColoring {
+        num_vertices: usize,
+        edges: Vec<(usize, usize)>,
+        num_colors: usize,
+    },
+}
+
+impl OptimizationProblem {
+    /// Builds a TSP instance from city coordinates using Euclidean distances
+    pub fn tsp_from_points(points: &[(f64, f64)]) -> Self {
+        let distances = points
+            .iter()
+            .map(|&(x1, y1)| {
+                points
+                    .iter()
+                    .map(|&(x2, y2)| ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt())
+                    .collect()
+            })
+            .collect();
+        OptimizationProblem::Tsp { distances }
+    }
+
+    /// Number of rows of the unit matrix (cities or vertices)
+    pub fn rows(&self) -> usize {
+        match self {
+            OptimizationProblem::Tsp { distances } => distances.len(),
+            OptimizationProblem::GraphColoring { num_vertices, .. } => *num_vertices,
+        }
+    }
+
+    /// Number of columns of the unit matrix (tour positions or colors)
+    pub fn columns(&self) -> usize {
+        match self {
+            OptimizationProblem::Tsp { distances } => distances.len(),
+            OptimizationProblem::GraphColoring { num_colors, .. } => *num_colors,
+        }
+    }
+
+    fn validate(&self) -> Result<(), OptimizationError> {
+        match self {
+            OptimizationProblem::Tsp { distances } => {
+                let n = distances.len();
+                if n < 3 {
+                    return Err(OptimizationError::InvalidProblem(format!(
+                        "TSP needs at least 3 cities, got {}",
+                        n
+                    )));
+                }
+                if let Some(row) = distances.iter().position(|row| row.len() != n) {
+                    return Err(OptimizationError::DimensionMismatch(format!(
+                        "Distance matrix row {} has length {} but expected {}",
+                        row, distances[row].len(), n
+                    )));
+                }
+            },
+            OptimizationProblem::GraphColoring { num_vertices, edges, num_colors } => {
+                if *num_vertices == 0 || *num_colors == 0 {
+                    return Err(OptimizationError::InvalidProblem(
+                        "Graph coloring needs at least one vertex and one color".to_string()
+                    ));
+                }
+                if let Some(&(u, v)) = edges.iter().find(|&&(u, v)| u >= *num_vertices || v >= *num_vertices) {
+                    return Err(OptimizationError::InvalidProblem(format!(
+                        "Edge ({}, {}) refers to a vertex outside 0..{}",
+                        u, v, num_vertices
+                    )));
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Penalty coefficients of the Hopfield-Tank energy.
+///
+/// For TSP (Hopfield & Tank, 1985):
+/// E = A/2 Σ_x Σ_{i≠j} V_xi V_xj + B/2 Σ_i Σ_{x≠y} V_xi V_yi + C/2 (Σ V - n)² + D/2 Σ_{x≠y} Σ_i d_xy V_xi (V_y,i+1 + V_y,i-1)
+///
+/// For graph coloring, A penalizes two colors on one vertex, C/2 Σ_v (Σ_c V_vc - 1)² asks for
+/// exactly one color per vertex, and D penalizes adjacent vertices sharing a color (B is unused).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PenaltyWeights {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+}
+
+impl PenaltyWeights {
+    /// Coefficients that work reasonably for small instances of `problem`
+    pub fn default_for(problem: &OptimizationProblem) -> Self {
+        match problem {
+            OptimizationProblem::Tsp { .. } => PenaltyWeights { a: 500.0, b: 500.0, c: 200.0, d: 500.0 },
+            OptimizationProblem::GraphColoring { .. } => PenaltyWeights { a: 1.0, b: 0.0, c: 1.0, d: 2.0 },
+        }
+    }
+}
+
+/// Continuous Hopfield network built from an optimization problem.
+///
+/// Units V = g(u) = (1 + tanh(u / u0)) / 2 form a rows × columns matrix stored row-major.
+/// The problem's penalty energy E = -1/2 Σ W_ab V_a V_b - Σ I_a V_a fixes the weights W and
+/// biases I, and the potentials follow du/dt = -u/τ + W V + I, which decreases E.
+#[derive(Debug, Clone)]
+pub struct HopfieldTankSolver {
+    problem: OptimizationProblem,
+    penalties: PenaltyWeights,
+
+    rows: usize,
+    columns: usize,
+
+    /// Symmetric weight matrix over all units (row-major flattened unit indices)
+    weights: Vec<Vec<f64>>,
+
+    /// Bias current of each unit
+    biases: Vec<f64>,
+
+    /// Internal potentials u
+    pub potentials: Vec<f64>,
+
+    /// Gain parameter u0 of the sigmoid (smaller = steeper)
+    pub gain: f64,
+
+    /// Time constant τ of the potential decay
+    pub time_constant: f64,
+
+    /// Euler integration step
+    pub dt: f64,
+
+    /// Activations after each step (only kept while `record_history` is set)
+    pub history: Vec<Vec<f64>>,
+
+    /// Whether `step` appends the activations to `history`
+    pub record_history: bool,
+
+    /// Network energy after each step
+    pub energy_history: Vec<f64>,
+}
+
+impl HopfieldTankSolver {
+    /// Builds the network for `problem` with the given penalty coefficients.
+    ///
+    /// Potentials start at the value where the activations sum to the expected number
+    /// of active units; call `randomize` to add the symmetry-breaking noise.
+    pub fn new(problem: OptimizationProblem, penalties: PenaltyWeights) -> Result<Self, OptimizationError> {
+        problem.validate()?;
+
+        let (rows, columns) = (problem.rows(), problem.columns());
+        let (weights, biases) = Self::build_weights(&problem, &penalties);
+        let (gain, dt) = match problem {
+            OptimizationProblem::Tsp { .. } => (0.02, 1e-5),
+            OptimizationProblem::GraphColoring { .. } => (0.1, 0.01),
+        };
+
+        let mut solver = HopfieldTankSolver {
+            problem,
+            penalties,
+            rows,
+            columns,
+            weights,
+            biases,
+            potentials: vec![0.0; rows * columns],
+            gain,
+            time_constant: 1.0,
+            dt,
+            history: Vec::new(),
+            record_history: true,
+            energy_history: Vec::new(),
+        };
+        let initial = solver.balanced_potential();
+        solver.potentials.iter_mut().for_each(|u| *u = initial);
+        solver.clear_history();
+        Ok(solver)
+    }
+
+    fn build_weights(problem: &OptimizationProblem, p: &PenaltyWeights) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let (rows, columns) = (problem.rows(), problem.columns());
+        let n = rows * columns;
+        let mut weights = vec![vec![0.0; n]; n];
+        let mut biases = vec![0.0; n];
+
+        match problem {
+            OptimizationProblem::Tsp { distances } => {
+                for (x, distances_from_x) in distances.iter().enumerate() {
+                    for i in 0..columns {
+                        let a = x * columns + i;
+                        biases[a] = p.c * rows as f64;
+                        for (y, &distance) in distances_from_x.iter().enumerate() {
+                            for j in 0..columns {
+                                let b = y * columns + j;
+                                let mut w = -p.c;
+                                if x == y && i != j {
+                                    w -= p.a;
+                                }
+                                if i == j && x != y {
+                                    w -= p.b;
+                                }
+                                if x != y && (j == (i + 1) % columns || i == (j + 1) % columns) {
+                                    w -= p.d * distance;
+                                }
+                                weights[a][b] = w;
+                            }
+                        }
+                    }
+                }
+            },
+            OptimizationProblem::GraphColoring { edges, .. } => {
+                for v in 0..rows {
+                    for c in 0..columns {
+                        let a = v * columns + c;
+                        biases[a] = p.c;
+                        for c2 in 0..columns {
+                            let b = v * columns + c2;
+                            weights[a][b] = -p.c - if c != c2 { p.a } else { 0.0 };
+                        }
+                    }
+                }
+                for &(u, v) in edges {
+                    if u == v {
+                        continue;
+                    }
+                    for c in 0..columns {
+                        weights[u * columns + c][v * columns + c] -= p.d;
+                        weights[v * columns + c][u * columns + c] -= p.d;
+                    }
+                }
+            },
+        }
+
+        (weights, biases)
+    }
+
+    /// Potential at which all activations sum to the number of rows (one active unit per row)
+    fn balanced_potential(&self) -> f64 {
+        let target = (1.0 / self.columns as f64).clamp(1e-6, 1.0 - 1e-6);
+        self.gain * (2.0 * target - 1.0).atanh()
+    }
+
+    /// The problem the network was built from
+    pub fn problem(&self) -> &OptimizationProblem {
+        &self.problem
+    }
+
+    /// Current penalty coefficients
+    pub fn penalties(&self) -> PenaltyWeights {
+        self.penalties
+    }
+
+    /// Change the penalty coefficients, rebuilding the weights (the state is kept)
+    pub fn set_penalties(&mut self, penalties: PenaltyWeights) {
+        let (weights, biases) = Self::build_weights(&self.problem, &penalties);
+        self.penalties = penalties;
+        self.weights = weights;
+        self.biases = biases;
+    }
+
+    /// Number of units (rows × columns)
+    pub fn num_units(&self) -> usize {
+        self.rows * self.columns
+    }
+
+    /// Dimensions (rows, columns) of the unit matrix
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    /// Restart from balanced potentials plus uniform noise of relative amplitude `noise`
+    pub fn randomize(&mut self, noise: f64, rng: &mut impl Rng) {
+        let initial = self.balanced_potential();
+        let amplitude = noise * self.gain;
+        for u in &mut self.potentials {
+            *u = initial + amplitude * (2.0 * rng.gen::<f64>() - 1.0);
+        }
+        self.clear_history();
+    }
+
+    /// Restart the activation and energy histories from the current state
+    pub fn clear_history(&mut self) {
+        self.history = vec![self.activations()];
+        self.energy_history = vec![self.energy()];
+    }
+
+    /// Activations V = (1 + tanh(u / u0)) / 2
+    pub fn activations(&self) -> Vec<f64> {
+        self.potentials
+            .iter()
+            .map(|&u| 0.5 * (1.0 + (u / self.gain).tanh()))
+            .collect()
+    }
+
+    /// Network energy E = -1/2 Σ W_ab V_a V_b - Σ I_a V_a
+    pub fn energy(&self) -> f64 {
+        let v = self.activations();
+        let mut energy = 0.0;
+        for a in 0..v.len() {
+            let field: f64 = self.weights[a].iter().zip(&v).map(|(w, vb)| w * vb).sum();
+            energy -= 0.5 * v[a] * field + self.biases[a] * v[a];
+        }
+        energy
+    }
+
+    /// Values of the individual penalty terms for the current activations, by name
+    pub fn energy_terms(&self) -> Vec<(&'static str, f64)> {
+        let v = self.activations();
+        let (rows, columns) = (self.rows, self.columns);
+        let p = &self.penalties;
+        let at = |r: usize, c: usize| v[r * columns + c];
+
+        // Σ over rows of pairs of different active columns, and vice versa
+        let row_overlap: f64 = (0..rows)
+            .map(|r| {
+                let sum: f64 = (0..columns).map(|c| at(r, c)).sum();
+                let squares: f64 = (0..columns).map(|c| at(r, c).powi(2)).sum();
+                sum * sum - squares
+            })
+            .sum();
+
+        match &self.problem {
+            OptimizationProblem::Tsp { distances } => {
+                let column_overlap: f64 = (0..columns)
+                    .map(|c| {
+                        let sum: f64 = (0..rows).map(|r| at(r, c)).sum();
+                        let squares: f64 = (0..rows).map(|r| at(r, c).powi(2)).sum();
+                        sum * sum - squares
+                    })
+                    .sum();
+                let total: f64 = v.iter().sum();
+                let mut distance = 0.0;
+                for (x, distances_from_x) in distances.iter().enumerate() {
+                    for (y, &d) in distances_from_x.iter().enumerate() {
+                        if x == y {
+                            continue;
+                        }
+                        for i in 0..columns {
+                            let next = at(y, (i + 1) % columns);
+                            let prev = at(y, (i + columns - 1) % columns);
+                            distance += d * at(x, i) * (next + prev);
+                        }
+                    }
+                }
+                vec![
+                    ("One position per city (A)", 0.5 * p.a * row_overlap),
+                    ("One city per position (B)", 0.5 * p.b * column_overlap),
+                    ("City count (C)", 0.5 * p.c * (total - rows as f64).powi(2)),
+                    ("Tour length (D)", 0.5 * p.d * distance),
+                ]
+            },
+            OptimizationProblem::GraphColoring { edges, .. } => {
+                let count: f64 = (0..rows)
+                    .map(|r| ((0..columns).map(|c| at(r, c)).sum::<f64>() - 1.0).powi(2))
+                    .sum();
+                let conflicts: f64 = edges
+                    .iter()
+                    .filter(|(u, v)| u != v)
+                    .map(|&(u, w)| (0..columns).map(|c| at(u, c) * at(w, c)).sum::<f64>())
+                    .sum();
+                vec![
+                    ("One color per vertex (A)", 0.5 * p.a * row_overlap),
+                    ("Vertex colored (C)", 0.5 * p.c * count),
+                    ("Color conflicts (D)", p.d * conflicts),
+                ]
+            },
+        }
+    }
+
+    /// Perform one Euler step of du/dt = -u/τ + W V + I and record the new state
+    pub fn step(&mut self) {
+        let v = self.activations();
+        for a in 0..self.potentials.len() {
+            let field: f64 = self.weights[a].iter().zip(&v).map(|(w, vb)| w * vb).sum();
+            let du = -self.potentials[a] / self.time_constant + field + self.biases[a];
+            self.potentials[a] += self.dt * du;
+        }
+
+        if self.record_history {
+            self.history.push(self.activations());
+        }
+        self.energy_history.push(self.energy());
+    }
+
+    /// Run `steps` integration steps
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Index of the most active column in each row
+    pub fn row_winners(&self) -> Vec<usize> {
+        let v = self.activations();
+        v.chunks(self.columns)
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map_or(0, |(c, _)| c)
+            })
+            .collect()
+    }
+
+    /// Decode the TSP tour (cities in visiting order), if every position holds exactly one
+    /// city with activation above 0.5 and every city is visited once
+    pub fn decode_tour(&self) -> Option<Vec<usize>> {
+        if !matches!(self.problem, OptimizationProblem::Tsp { .. }) {
+            return None;
+        }
+        let v = self.activations();
+        let n = self.rows;
+        let mut tour = Vec::with_capacity(n);
+        for i in 0..n {
+            let active: Vec<usize> = (0..n).filter(|&x| v[x * n + i] > 0.5).collect();
+            if active.len() != 1 {
+                return None;
+            }
+            tour.push(active[0]);
+        }
+
+        let mut seen = vec![false; n];
+        for &city in &tour {
+            if std::mem::replace(&mut seen[city], true) {
+                return None;
+            }
+        }
+        Some(tour)
+    }
+
+    /// Total length of a closed tour
+    pub fn tour_length(&self, tour: &[usize]) -> f64 {
+        match &self.problem {
+            OptimizationProblem::Tsp { distances } if !tour.is_empty() => (0..tour.len())
+                .map(|i| distances[tour[i]][tour[(i + 1) % tour.len()]])
+                .sum(),
+            _ => 0.0,
+        }
+    }
+
+    /// Color of each vertex (most active color unit) and the number of conflicting edges
+    pub fn decode_coloring(&self) -> Option<(Vec<usize>, usize)> {
+        let OptimizationProblem::GraphColoring { edges, .. } = &self.problem else {
+            return None;
+        };
+        let colors = self.row_winners();
+        let conflicts = edges
+            .iter()
+            .filter(|&&(u, v)| u != v && colors[u] == colors[v])
+            .count();
+        Some((colors, conflicts))
+    }
+
+    /// Set the internal potentials directly
+    pub fn set_potentials(&mut self, potentials: Vec<f64>) -> Result<(), OptimizationError> {
+        if potentials.len() != self.num_units() {
+            return Err(OptimizationError::DimensionMismatch(format!(
+                "Potentials have length {} but expected {}",
+                potentials.len(), self.num_units()
+            )));
+        }
+        self.potentials = potentials;
+        self.clear_history();
+        Ok(())
+    }
+}
+
+// Implement the DynamicalSystem trait for HopfieldTankSolver. The state is the vector of
+// internal potentials; a step is one Euler step.
+impl DynamicalSystem for HopfieldTankSolver {
+    type State = Vec<f64>;
+    type Error = OptimizationError;
+
+    fn size(&self) -> usize {
+        self.num_units()
+    }
+
+    fn state(&self) -> Self::State {
+        self.potentials.clone()
+    }
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.set_potentials(state)
+    }
+
+    fn step(&mut self, _rng: &mut impl Rng) -> Result<(), Self::Error> {
+        HopfieldTankSolver::step(self);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_energy_matches_penalty_terms() {
+        let problem = OptimizationProblem::tsp_from_points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        let mut solver = HopfieldTankSolver::new(problem, PenaltyWeights { a: 2.0, b: 3.0, c: 5.0, d: 7.0 }).unwrap();
+        solver.randomize(1.0, &mut thread_rng());
+
+        // The quadratic form equals the sum of the penalty terms up to the constant C n² / 2
+        let terms: f64 = solver.energy_terms().iter().map(|(_, e)| e).sum();
+        assert!((solver.energy() + 0.5 * 5.0 * 16.0 - terms).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_colors_triangle() {
+        let problem = OptimizationProblem::GraphColoring {
+            num_vertices: 3,
+            edges: vec![(0, 1), (1, 2), (2, 0)],
+            num_colors: 3,
+        };
+        let penalties = PenaltyWeights::default_for(&problem);
+        let mut solver = HopfieldTankSolver::new(problem, penalties).unwrap();
+        solver.randomize(0.5, &mut thread_rng());
+        solver.run(2000);
+
+        let (colors, conflicts) = solver.decode_coloring().unwrap();
+        assert_eq!(conflicts, 0);
+        assert!(colors[0] != colors[1] && colors[1] != colors[2] && colors[2] != colors[0]);
+        assert!(solver.energy_history.last().unwrap() < solver.energy_history.first().unwrap());
+    }
+}
@@ -0,0 +1,417 @@
+use std::error::Error;
+use std::fmt;
+use rand::Rng;
+
+use super::DynamicalSystem;
+
+/// Critical temperature of the 2D square-lattice Ising model (J = 1, h = 0, k_B = 1)
+pub const CRITICAL_TEMPERATURE: f64 = 2.269_185_314_213_022;
+
+/// Error types for the Ising model
+#[derive(Debug)]
+pub enum IsingError {
+    DimensionMismatch(String),
+    InvalidSpinValue(String),
+    InvalidParameter(String),
+}
+
+impl fmt::Display for IsingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IsingError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+            IsingError::InvalidSpinValue(msg) => write!(f, "Invalid spin value: {}", msg),
+            IsingError::InvalidParameter(msg) => write!(f, "Invalid parameter: {}", msg),
+        }
+    }
+}
+
+impl Error for IsingError {}
+
+/// Single-spin-flip dynamics used to sample the Boltzmann distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IsingDynamics {
+    /// Heat-bath dynamics: flip with probability 1 / (1 + exp(β ΔE))
+    Glauber,
+    /// Flip with probability min(1, exp(-β ΔE))
+    Metropolis,
+}
+
+/// Thermal averages measured at one temperature of a temperature sweep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepPoint {
+    pub temperature: f64,
+    /// Mean absolute magnetization per spin, <|m|>
+    pub magnetization: f64,
+    /// Mean energy per spin, <e>
+    pub energy: f64,
+    /// Magnetic susceptibility per spin, N (<m²> - <|m|>²) / T
+    pub susceptibility: f64,
+    /// Specific heat per spin, N (<e²> - <e>²) / T²
+    pub specific_heat: f64,
+}
+
+/// Ising model on a 2D square lattice with periodic boundaries.
+///
+/// The energy of a spin configuration s ∈ {-1, +1}^N is
+/// E = -J Σ_<ij> s_i s_j - h Σ_i s_i, where the first sum runs over nearest-neighbour pairs.
+/// Spins are stored as `f64` (±1.0) like Hopfield states, so the same grid widgets can draw them.
+#[derive(Debug, Clone)]
+pub struct IsingModel {
+    pub width: usize,
+    pub height: usize,
+
+    /// Spin at each site in row-major order (+1.0 or -1.0)
+    pub spins: Vec<f64>,
+
+    /// Nearest-neighbour coupling J (positive = ferromagnetic)
+    pub coupling: f64,
+
+    /// External magnetic field h
+    pub field: f64,
+
+    /// Temperature T (k_B = 1); 0 gives zero-temperature dynamics
+    pub temperature: f64,
+
+    /// Spin-flip rule
+    pub dynamics: IsingDynamics,
+
+    /// Magnetization per spin after each sweep
+    pub magnetization_history: Vec<f64>,
+
+    /// Energy per spin after each sweep
+    pub energy_history: Vec<f64>,
+}
+
+impl IsingModel {
+    /// Creates a fully magnetized (all spins up) lattice with J = 1, h = 0 at the critical temperature.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Number of columns of the lattice
+    /// * `height` - Number of rows of the lattice
+    pub fn new(width: usize, height: usize) -> Result<Self, IsingError> {
+        if width < 2 || height < 2 {
+            return Err(IsingError::DimensionMismatch(format!(
+                "Lattice must be at least 2x2, got {}x{}",
+                width, height
+            )));
+        }
+
+        let mut model = IsingModel {
+            width,
+            height,
+            spins: vec![1.0; width * height],
+            coupling: 1.0,
+            field: 0.0,
+            temperature: CRITICAL_TEMPERATURE,
+            dynamics: IsingDynamics::Glauber,
+            magnetization_history: Vec::new(),
+            energy_history: Vec::new(),
+        };
+        model.clear_history();
+        Ok(model)
+    }
+
+    /// Number of spins in the lattice
+    pub fn num_spins(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Set all spins independently to ±1 with equal probability
+    pub fn randomize(&mut self, rng: &mut impl Rng) {
+        for spin in &mut self.spins {
+            *spin = if rng.gen::<bool>() { 1.0 } else { -1.0 };
+        }
+        self.clear_history();
+    }
+
+    /// Set all spins to the same value (+1 if `up`, -1 otherwise)
+    pub fn fill(&mut self, up: bool) {
+        let value = if up { 1.0 } else { -1.0 };
+        self.spins.iter_mut().for_each(|spin| *spin = value);
+        self.clear_history();
+    }
+
+    /// Set the spin configuration directly
+    pub fn set_spins(&mut self, spins: Vec<f64>) -> Result<(), IsingError> {
+        if spins.len() != self.num_spins() {
+            return Err(IsingError::DimensionMismatch(format!(
+                "Configuration has length {} but expected {}",
+                spins.len(), self.num_spins()
+            )));
+        }
+        if let Some((i, &s)) = spins.iter().enumerate().find(|(_, &s)| s != 1.0 && s != -1.0) {
+            return Err(IsingError::InvalidSpinValue(format!(
+                "Spin {} has value {}, expected +1.0 or -1.0",
+                i, s
+            )));
+        }
+
+        self.spins = spins;
+        self.clear_history();
+        Ok(())
+    }
+
+    /// Restart the magnetization and energy time series from the current configuration
+    pub fn clear_history(&mut self) {
+        self.magnetization_history = vec![self.magnetization()];
+        self.energy_history = vec![self.energy_per_spin()];
+    }
+
+    /// Indices of the four nearest neighbours of site `index` (periodic boundaries)
+    fn neighbors(&self, index: usize) -> [usize; 4] {
+        let (x, y) = (index % self.width, index / self.width);
+        let left = (x + self.width - 1) % self.width;
+        let right = (x + 1) % self.width;
+        let up = (y + self.height - 1) % self.height;
+        let down = (y + 1) % self.height;
+        [
+            y * self.width + left,
+            y * self.width + right,
+            up * self.width + x,
+            down * self.width + x,
+        ]
+    }
+
+    /// Local field J Σ_j s_j + h acting on site `index`
+    pub fn local_field(&self, index: usize) -> f64 {
+        let neighbor_sum: f64 = self.neighbors(index).iter().map(|&j| self.spins[j]).sum();
+        self.coupling * neighbor_sum + self.field
+    }
+
+    /// Energy change caused by flipping the spin at `index`
+    pub fn flip_energy(&self, index: usize) -> f64 {
+        2.0 * self.spins[index] * self.local_field(index)
+    }
+
+    /// Total energy E = -J Σ_<ij> s_i s_j - h Σ_i s_i
+    pub fn energy(&self) -> f64 {
+        let mut bond_sum = 0.0;
+        for index in 0..self.num_spins() {
+            let [_, right, _, down] = self.neighbors(index);
+            bond_sum += self.spins[index] * (self.spins[right] + self.spins[down]);
+        }
+        let spin_sum: f64 = self.spins.iter().sum();
+        -self.coupling * bond_sum - self.field * spin_sum
+    }
+
+    /// Energy per spin E / N
+    pub fn energy_per_spin(&self) -> f64 {
+        self.energy() / self.num_spins() as f64
+    }
+
+    /// Magnetization per spin m = (1/N) Σ_i s_i
+    pub fn magnetization(&self) -> f64 {
+        self.spins.iter().sum::<f64>() / self.num_spins() as f64
+    }
+
+    /// Probability of accepting a flip that changes the energy by `delta_energy`
+    fn flip_probability(&self, delta_energy: f64) -> f64 {
+        if self.temperature <= 0.0 {
+            // Zero temperature: only downhill moves (ties broken at random for Glauber)
+            return if delta_energy < 0.0 {
+                1.0
+            } else if delta_energy > 0.0 {
+                0.0
+            } else {
+                match self.dynamics {
+                    IsingDynamics::Glauber => 0.5,
+                    IsingDynamics::Metropolis => 1.0,
+                }
+            };
+        }
+
+        let beta = 1.0 / self.temperature;
+        match self.dynamics {
+            IsingDynamics::Glauber => 1.0 / (1.0 + (beta * delta_energy).exp()),
+            IsingDynamics::Metropolis => (-beta * delta_energy).exp().min(1.0),
+        }
+    }
+
+    /// Attempt to flip the spin at `index` according to the current dynamics.
+    ///
+    /// # Returns
+    ///
+    /// Whether the spin was flipped
+    pub fn update_spin(&mut self, index: usize, rng: &mut impl Rng) -> bool {
+        let delta_energy = self.flip_energy(index);
+        if rng.gen::<f64>() < self.flip_probability(delta_energy) {
+            self.spins[index] = -self.spins[index];
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Perform one Monte Carlo sweep (N randomly chosen single-spin updates)
+    /// and append the resulting magnetization and energy to the history.
+    ///
+    /// # Returns
+    ///
+    /// Number of spins flipped during the sweep
+    pub fn sweep(&mut self, rng: &mut impl Rng) -> usize {
+        let n = self.num_spins();
+        let mut flips = 0;
+        for _ in 0..n {
+            let index = rng.gen_range(0..n);
+            if self.update_spin(index, rng) {
+                flips += 1;
+            }
+        }
+
+        self.magnetization_history.push(self.magnetization());
+        self.energy_history.push(self.energy_per_spin());
+        flips
+    }
+
+    /// Run `num_sweeps` Monte Carlo sweeps
+    pub fn run(&mut self, num_sweeps: usize, rng: &mut impl Rng) {
+        for _ in 0..num_sweeps {
+            self.sweep(rng);
+        }
+    }
+
+    /// Measure thermal averages over a range of temperatures.
+    ///
+    /// At each temperature the lattice (continuing from the previous temperature) is
+    /// equilibrated for `equilibration_sweeps`, then measured after each of
+    /// `measurement_sweeps` sweeps. The model's temperature and history are restored afterwards,
+    /// but the spin configuration is left in its final state.
+    ///
+    /// # Arguments
+    ///
+    /// * `temperatures` - Temperatures to visit, in order
+    /// * `equilibration_sweeps` - Sweeps discarded before measuring at each temperature
+    /// * `measurement_sweeps` - Sweeps averaged over at each temperature (must be > 0)
+    /// * `rng` - Random number generator
+    pub fn temperature_sweep(
+        &mut self,
+        temperatures: &[f64],
+        equilibration_sweeps: usize,
+        measurement_sweeps: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<SweepPoint>, IsingError> {
+        if measurement_sweeps == 0 {
+            return Err(IsingError::InvalidParameter(
+                "At least one measurement sweep is required".to_string()
+            ));
+        }
+        if let Some(&t) = temperatures.iter().find(|&&t| t <= 0.0 || t.is_nan()) {
+            return Err(IsingError::InvalidParameter(format!(
+                "Temperatures must be positive, got {}",
+                t
+            )));
+        }
+
+        let original_temperature = self.temperature;
+        let magnetization_history = std::mem::take(&mut self.magnetization_history);
+        let energy_history = std::mem::take(&mut self.energy_history);
+        let n = self.num_spins() as f64;
+
+        let mut results = Vec::with_capacity(temperatures.len());
+        for &temperature in temperatures {
+            self.temperature = temperature;
+            for _ in 0..equilibration_sweeps {
+                self.sweep(rng);
+            }
+
+            let (mut m_sum, mut m2_sum, mut e_sum, mut e2_sum) = (0.0, 0.0, 0.0, 0.0);
+            for _ in 0..measurement_sweeps {
+                self.sweep(rng);
+                let m = self.magnetization().abs();
+                let e = self.energy_per_spin();
+                m_sum += m;
+                m2_sum += m * m;
+                e_sum += e;
+                e2_sum += e * e;
+            }
+
+            let samples = measurement_sweeps as f64;
+            let (m_mean, e_mean) = (m_sum / samples, e_sum / samples);
+            results.push(SweepPoint {
+                temperature,
+                magnetization: m_mean,
+                energy: e_mean,
+                susceptibility: n * (m2_sum / samples - m_mean * m_mean) / temperature,
+                specific_heat: n * (e2_sum / samples - e_mean * e_mean) / (temperature * temperature),
+            });
+
+            // The sweeps above only feed the averages
+            self.magnetization_history.clear();
+            self.energy_history.clear();
+        }
+
+        self.temperature = original_temperature;
+        self.magnetization_history = magnetization_history;
+        self.energy_history = energy_history;
+        Ok(results)
+    }
+}
+
+// Implement the DynamicalSystem trait for IsingModel; a step is one Monte Carlo sweep
+impl DynamicalSystem for IsingModel {
+    type State = Vec<f64>;
+    type Error = IsingError;
+
+    fn size(&self) -> usize {
+        self.num_spins()
+    }
+
+    fn state(&self) -> Self::State {
+        self.spins.clone()
+    }
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.set_spins(state)
+    }
+
+    fn step(&mut self, rng: &mut impl Rng) -> Result<(), Self::Error> {
+        self.sweep(rng);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_ground_state_energy() {
+        let model = IsingModel::new(4, 4).unwrap();
+
+        // All spins up: every one of the 2N bonds contributes -J
+        assert_eq!(model.energy(), -32.0);
+        assert_eq!(model.magnetization(), 1.0);
+
+        // Flipping one spin breaks its 4 bonds
+        assert_eq!(model.flip_energy(5), 8.0);
+    }
+
+    #[test]
+    fn test_low_temperature_stays_ordered() {
+        let mut rng = thread_rng();
+        for dynamics in [IsingDynamics::Glauber, IsingDynamics::Metropolis] {
+            let mut model = IsingModel::new(8, 8).unwrap();
+            model.dynamics = dynamics;
+            model.temperature = 0.5;
+            model.run(50, &mut rng);
+
+            assert!(model.magnetization() > 0.9);
+            assert_eq!(model.magnetization_history.len(), 51);
+        }
+    }
+
+    #[test]
+    fn test_temperature_sweep_disorders() {
+        let mut rng = thread_rng();
+        let mut model = IsingModel::new(10, 10).unwrap();
+        let results = model.temperature_sweep(&[1.0, 10.0], 50, 50, &mut rng).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].magnetization > 0.9);
+        assert!(results[1].magnetization < 0.5);
+        assert_eq!(model.temperature, CRITICAL_TEMPERATURE);
+        assert!(model.temperature_sweep(&[0.0], 1, 1, &mut rng).is_err());
+    }
+}
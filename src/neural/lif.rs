@@ -0,0 +1,314 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use rand::Rng;
+
+use super::hopfield::HopfieldNetwork;
+use crate::core::noise::standard_normal;
+
+/// Error types for the integrate-and-fire lattice
+#[derive(Debug)]
+pub enum LifError {
+    DimensionMismatch(String),
+    InvalidParameter(String),
+}
+
+impl fmt::Display for LifError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {}", msg),
+            LifError::InvalidParameter(msg) => write!(f, "Invalid parameter: {}", msg),
+        }
+    }
+}
+
+impl Error for LifError {}
+
+/// Membrane and coupling parameters. Times are in ms, potentials in mV.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LifParameters {
+    /// Membrane time constant τ_m
+    pub tau_membrane: f64,
+    pub v_rest: f64,
+    /// Potential right after a spike
+    pub v_reset: f64,
+    pub v_threshold: f64,
+    /// Time after a spike during which the potential stays at `v_reset`
+    pub refractory: f64,
+    /// Euler time step
+    pub dt: f64,
+    /// Jump of V_i when neuron j spikes, for the strongest Hopfield weight: J_ij = gain W_ij / max |W|
+    pub coupling_gain: f64,
+    /// Constant input R I to every neuron
+    pub drive: f64,
+    /// Standard deviation of the white input noise, in mV / sqrt(ms)
+    pub noise: f64,
+    /// Time constant of the exponential spike trace used as firing rate
+    pub tau_rate: f64,
+}
+
+impl Default for LifParameters {
+    fn default() -> Self {
+        LifParameters {
+            tau_membrane: 20.0,
+            v_rest: -65.0,
+            v_reset: -70.0,
+            v_threshold: -50.0,
+            refractory: 2.0,
+            dt: 0.5,
+            coupling_gain: 2.0,
+            drive: 14.0,
+            noise: 1.0,
+            tau_rate: 50.0,
+        }
+    }
+}
+
+/// A spike of `neuron` at `time` (ms)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spike {
+    pub time: f64,
+    pub neuron: usize,
+}
+
+/// A grid of leaky integrate-and-fire neurons coupled through a Hopfield weight matrix.
+///
+/// Between spikes each membrane potential follows
+/// τ_m dV_i/dt = V_rest - V_i + drive + cue_i + noise, and when neuron j fires every
+/// V_i jumps by J_ij. Neurons that are +1 in a stored pattern excite each other and
+/// inhibit the -1 neurons, so a cued pattern persists as a set of active neurons: the
+/// spiking counterpart of a Hopfield attractor.
+#[derive(Debug, Clone)]
+pub struct LifLattice {
+    pub width: usize,
+    pub height: usize,
+    pub params: LifParameters,
+
+    /// Membrane potential of each neuron in row-major order
+    pub potentials: Vec<f64>,
+
+    /// Hopfield weights scaled to a largest magnitude of 1
+    weights: Vec<Vec<f64>>,
+
+    /// Extra input of each neuron, e.g. a cue pattern
+    cue: Vec<f64>,
+
+    /// Remaining refractory time of each neuron
+    refractory_left: Vec<f64>,
+
+    /// Exponentially filtered spike train of each neuron, in Hz
+    pub rates: Vec<f64>,
+
+    /// Neurons that fired in the last step
+    last_spikes: Vec<usize>,
+
+    /// Spikes of the last `history_ms`, oldest first
+    pub spikes: VecDeque<Spike>,
+    pub history_ms: f64,
+
+    /// Simulated time in ms
+    pub time: f64,
+}
+
+impl LifLattice {
+    /// Creates a lattice whose coupling is the weight matrix of `network`
+    ///
+    /// # Arguments
+    ///
+    /// * `width`, `height` - Grid size; `network` must have `width * height` neurons
+    /// * `network` - Trained Hopfield network
+    /// * `params` - Membrane and coupling parameters
+    pub fn from_hopfield(
+        width: usize,
+        height: usize,
+        network: &HopfieldNetwork,
+        params: LifParameters,
+    ) -> Result<Self, LifError> {
+        let n = width * height;
+        if network.size() != n {
+            return Err(LifError::DimensionMismatch(format!(
+                "Network has {} neurons but the lattice has {}",
+                network.size(), n
+            )));
+        }
+        if params.dt <= 0.0 || params.tau_membrane <= 0.0 || params.tau_rate <= 0.0 {
+            return Err(LifError::InvalidParameter(
+                "Time step and time constants must be positive".to_string()
+            ));
+        }
+
+        let max_weight = network.weights().iter().flatten().fold(0.0f64, |m, w| m.max(w.abs()));
+        let scale = if max_weight > 0.0 { 1.0 / max_weight } else { 0.0 };
+        let weights = network
+            .weights()
+            .iter()
+            .map(|row| row.iter().map(|w| w * scale).collect())
+            .collect();
+
+        Ok(LifLattice {
+            width,
+            height,
+            params,
+            potentials: vec![params.v_rest; n],
+            weights,
+            cue: vec![0.0; n],
+            refractory_left: vec![0.0; n],
+            rates: vec![0.0; n],
+            last_spikes: Vec::new(),
+            spikes: VecDeque::new(),
+            history_ms: 1000.0,
+            time: 0.0,
+        })
+    }
+
+    pub fn num_neurons(&self) -> usize {
+        self.potentials.len()
+    }
+
+    /// Drives each neuron with `strength` times its value in `pattern` (±1)
+    pub fn set_cue(&mut self, pattern: &[f64], strength: f64) -> Result<(), LifError> {
+        if pattern.len() != self.num_neurons() {
+            return Err(LifError::DimensionMismatch(format!(
+                "Cue has length {} but the lattice has {} neurons",
+                pattern.len(), self.num_neurons()
+            )));
+        }
+        self.cue = pattern.iter().map(|p| strength * p).collect();
+        Ok(())
+    }
+
+    pub fn clear_cue(&mut self) {
+        self.cue.iter_mut().for_each(|c| *c = 0.0);
+    }
+
+    /// Whether a cue is currently applied
+    pub fn is_cued(&self) -> bool {
+        self.cue.iter().any(|&c| c != 0.0)
+    }
+
+    /// Random potentials between reset and threshold; clears rates and spikes
+    pub fn reset(&mut self, rng: &mut impl Rng) {
+        let (low, high) = (self.params.v_reset, self.params.v_threshold);
+        for v in &mut self.potentials {
+            *v = low + (high - low) * rng.gen::<f64>();
+        }
+        self.refractory_left.iter_mut().for_each(|r| *r = 0.0);
+        self.rates.iter_mut().for_each(|r| *r = 0.0);
+        self.last_spikes.clear();
+        self.spikes.clear();
+        self.time = 0.0;
+    }
+
+    /// Advances the lattice by one time step `dt`
+    ///
+    /// # Returns
+    ///
+    /// The neurons that fired during the step
+    pub fn step(&mut self, rng: &mut impl Rng) -> &[usize] {
+        let p = self.params;
+        let n = self.num_neurons();
+
+        // Spikes of the previous step arrive now
+        let mut synaptic = vec![0.0; n];
+        for &j in &self.last_spikes {
+            for (input, row) in synaptic.iter_mut().zip(&self.weights) {
+                *input += p.coupling_gain * row[j];
+            }
+        }
+
+        let leak = p.dt / p.tau_membrane;
+        let rate_decay = (-p.dt / p.tau_rate).exp();
+        let noise_scale = p.noise * p.dt.sqrt();
+        self.time += p.dt;
+        self.last_spikes.clear();
+        for (i, v) in self.potentials.iter_mut().enumerate() {
+            self.rates[i] *= rate_decay;
+            if self.refractory_left[i] > 0.0 {
+                self.refractory_left[i] -= p.dt;
+                continue;
+            }
+
+            *v += leak * (p.v_rest - *v + p.drive + self.cue[i]) + synaptic[i];
+            if noise_scale > 0.0 {
+                *v += noise_scale * standard_normal(rng);
+            }
+            if *v >= p.v_threshold {
+                *v = p.v_reset;
+                self.refractory_left[i] = p.refractory;
+                self.rates[i] += 1000.0 / p.tau_rate;
+                self.last_spikes.push(i);
+                self.spikes.push_back(Spike { time: self.time, neuron: i });
+            }
+        }
+
+        while self.spikes.front().is_some_and(|s| s.time < self.time - self.history_ms) {
+            self.spikes.pop_front();
+        }
+        &self.last_spikes
+    }
+
+    /// Runs `duration` ms of dynamics
+    ///
+    /// # Returns
+    ///
+    /// The number of spikes fired
+    pub fn run(&mut self, duration: f64, rng: &mut impl Rng) -> usize {
+        let steps = (duration / self.params.dt).round() as usize;
+        (0..steps).map(|_| self.step(rng).len()).sum()
+    }
+
+    /// Mean firing rate over the lattice, in Hz
+    pub fn mean_rate(&self) -> f64 {
+        self.rates.iter().sum::<f64>() / self.num_neurons() as f64
+    }
+
+    /// Bipolar readout of the activity: +1 for neurons firing faster than the mean rate,
+    /// -1 for the rest (all -1 while the lattice is silent), comparable with Hopfield states
+    pub fn activity_state(&self) -> Vec<f64> {
+        let mean = self.mean_rate();
+        self.rates
+            .iter()
+            .map(|&r| if mean > 1.0 && r > mean { 1.0 } else { -1.0 })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::metrics::overlap;
+    use crate::neural::hopfield::TrainingRule;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_single_neuron_fires_at_analytic_rate() {
+        let params = LifParameters { dt: 0.01, noise: 0.0, drive: 20.0, ..LifParameters::default() };
+        let mut lattice = LifLattice::from_hopfield(1, 1, &HopfieldNetwork::new(1), params).unwrap();
+        let spikes = lattice.run(1000.0, &mut StdRng::seed_from_u64(0));
+
+        // Period t_ref + τ ln((drive + V_rest - V_reset) / (drive + V_rest - V_threshold)) = 2 + 20 ln 5
+        let period = 2.0 + 20.0 * 5.0f64.ln();
+        assert!((spikes as f64 - 1000.0 / period).abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_cued_pattern_persists_as_activity() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let patterns: Vec<Vec<f64>> = (0..3)
+            .map(|_| (0..64).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        let mut net = HopfieldNetwork::new(64);
+        net.train(&patterns, TrainingRule::Hebbian).unwrap();
+
+        let mut lattice = LifLattice::from_hopfield(8, 8, &net, LifParameters::default()).unwrap();
+        lattice.reset(&mut rng);
+        lattice.set_cue(&patterns[1], 5.0).unwrap();
+        lattice.run(200.0, &mut rng);
+        lattice.clear_cue();
+        lattice.run(300.0, &mut rng);
+
+        assert!(overlap(&lattice.activity_state(), &patterns[1]) > 0.8);
+        assert!(LifLattice::from_hopfield(4, 4, &net, LifParameters::default()).is_err());
+    }
+}
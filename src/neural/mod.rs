@@ -1,20 +1,145 @@
 pub mod hopfield;
 pub mod chip_firing;
+pub mod graph_io;
+pub mod rotor_router;
+pub mod ising;
+pub mod cellular;
+pub mod hopfield_tank;
+pub mod annealing;
+pub mod bam;
+pub mod boltzmann;
+pub mod lif;
+pub mod pattern_formation;
+pub mod potts;
 
+use rand::Rng;
 use std::error::Error;
+use std::time::SystemTime;
 
-/// Common trait for all neural network implementations
-pub trait NeuralNetwork {
-    type Input;
-    type Output;
+use crate::core::safetensors::{utc_timestamp, SafeTensors};
+use crate::error::RaumError;
+
+/// A model whose state evolves step by step, e.g. chip firing or an Ising lattice
+pub trait DynamicalSystem {
+    type State: Clone;
+    type Error: Error + 'static;
+
+    /// Number of units (vertices, spins, cells or neurons)
+    fn size(&self) -> usize;
+
+    /// The current state
+    fn state(&self) -> Self::State;
+
+    /// Replaces the current state, e.g. to start a run from a given configuration
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error>;
+
+    /// Advances the dynamics by a single step
+    fn step(&mut self, rng: &mut impl Rng) -> Result<(), Self::Error>;
+
+    /// Whether the dynamics have come to rest; systems that never settle keep the default
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    /// Steps until the system is stable or `max_steps` steps were taken
+    ///
+    /// # Returns
+    ///
+    /// Result with the number of steps actually executed
+    fn run(&mut self, max_steps: usize, rng: &mut impl Rng) -> Result<usize, Self::Error> {
+        for i in 0..max_steps {
+            if self.is_stable() {
+                return Ok(i);
+            }
+            self.step(rng)?;
+        }
+        Ok(max_steps)
+    }
+
+    /// Recorded states, oldest first. Models that do not record their states only
+    /// return the current one.
+    fn history(&self) -> Vec<Self::State> {
+        vec![self.state()]
+    }
+}
+
+/// A model that stores patterns and retrieves them from noisy or partial cues
+pub trait AssociativeMemory {
+    type Pattern;
     type Error: Error + 'static;
-    
-    /// Runs the network forward pass
-    fn forward(&self, input: &Self::Input) -> Result<Self::Output, Self::Error>;
-    
-    /// Trains the network on provided data
-    fn train(&mut self, data: &[Self::Input]) -> Result<(), Self::Error>;
-    
-    /// Returns the size or dimension of the network
+
+    /// Number of units a pattern has
     fn size(&self) -> usize;
-}
\ No newline at end of file
+
+    /// Learns `patterns` with the model's default learning rule
+    fn store(&mut self, patterns: &[Self::Pattern]) -> Result<(), Self::Error>;
+
+    /// The state the memory settles into from `cue`
+    fn recall(&self, cue: &Self::Pattern, rng: &mut impl Rng) -> Result<Self::Pattern, Self::Error>;
+}
+
+/// A model whose trainable parameters can be saved to and restored from a safetensors
+/// file, the interchange format between RAUM builds
+pub trait TrainableParameters: Sized {
+    /// Model name stored in the `model` metadata entry and checked when loading
+    const MODEL: &'static str;
+
+    /// The parameter tensors, with metadata describing the model's shape
+    fn parameters(&self) -> SafeTensors;
+
+    /// Rebuilds a model from the tensors and metadata written by `parameters`
+    fn from_parameters(parameters: &SafeTensors) -> Result<Self, RaumError>;
+
+    /// `parameters` with the model name, the RAUM version and the time of export
+    fn to_safetensors(&self) -> SafeTensors {
+        self.parameters()
+            .with_metadata("model", Self::MODEL)
+            .with_metadata("raum_version", env!("CARGO_PKG_VERSION"))
+            .with_metadata("created", utc_timestamp(SystemTime::now()))
+    }
+
+    /// Like `from_parameters`, but refuses files exported from a different model
+    fn from_safetensors(file: &SafeTensors) -> Result<Self, RaumError> {
+        match file.metadata.get("model") {
+            Some(model) if model != Self::MODEL => {
+                Err(format!("The file holds a {} model, not a {} one", model, Self::MODEL).into())
+            },
+            _ => Self::from_parameters(file),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::graph::Graph;
+    use crate::neural::chip_firing::ChipFiringGraph;
+    use crate::neural::hopfield::HopfieldNetwork;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Runs any system to rest and returns its final state
+    fn relax<S: DynamicalSystem>(system: &mut S, rng: &mut impl Rng) -> S::State {
+        system.run(1000, rng).unwrap();
+        system.state()
+    }
+
+    #[test]
+    fn test_models_through_generic_traits() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut graph = ChipFiringGraph::from_graph(&Graph::cycle(3), vec![0; 3]).unwrap();
+        DynamicalSystem::set_state(&mut graph, vec![3, 0, 0]).unwrap();
+        let stable = relax(&mut graph, &mut rng);
+        assert!(DynamicalSystem::is_stable(&graph));
+        assert_eq!(stable.iter().sum::<i32>(), 3);
+        assert_eq!(DynamicalSystem::history(&graph).last(), Some(&stable));
+
+        let patterns = vec![vec![1.0, -1.0, 1.0, -1.0, 1.0, -1.0], vec![1.0, 1.0, 1.0, -1.0, -1.0, -1.0]];
+        let mut net = HopfieldNetwork::new(6);
+        net.store(&patterns).unwrap();
+        let mut cue = patterns[1].clone();
+        cue[0] = -1.0;
+        assert_eq!(net.recall(&cue, &mut rng).unwrap(), patterns[1]);
+        assert!(net.recall(&vec![1.0; 5], &mut rng).is_err());
+    }
+}
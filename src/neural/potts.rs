@@ -0,0 +1,262 @@
+use std::error::Error;
+use std::fmt;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::AssociativeMemory;
+
+/// Error types for Potts networks
+#[derive(Debug)]
+pub enum PottsError {
+    DimensionMismatch { expected: usize, actual: usize },
+    InvalidState(String),
+    InvalidSize(String),
+}
+
+impl fmt::Display for PottsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PottsError::DimensionMismatch { expected, actual } => {
+                write!(f, "Dimension mismatch: expected {} sites, got {}", expected, actual)
+            }
+            PottsError::InvalidState(msg) => write!(f, "Invalid state: {}", msg),
+            PottsError::InvalidSize(msg) => write!(f, "Invalid size: {}", msg),
+        }
+    }
+}
+
+impl Error for PottsError {}
+
+/// A Hopfield network whose sites take one of q states instead of ±1 (Kanter 1988),
+/// e.g. the gray levels of a quantized image.
+///
+/// Patterns are stored with the Potts Hebb rule
+/// J_ij(k, l) = 1/N Σ_μ u(ξ_i^μ, k) u(ξ_j^μ, l), with u(ξ, k) = δ(ξ, k) - 1/q. The
+/// couplings are never built: the field of a site follows from the overlaps with the
+/// stored patterns, which keeps memory at O(P N) instead of O(N² q²).
+#[derive(Debug, Clone)]
+pub struct PottsNetwork {
+    num_sites: usize,
+    num_states: usize,
+    patterns: Vec<Vec<usize>>,
+}
+
+impl PottsNetwork {
+    /// Creates an empty network
+    ///
+    /// # Arguments
+    ///
+    /// * `num_sites` - Number of sites. Must be greater than 0.
+    /// * `num_states` - States per site, q. Must be at least 2.
+    pub fn try_new(num_sites: usize, num_states: usize) -> Result<Self, PottsError> {
+        if num_sites == 0 {
+            return Err(PottsError::InvalidSize("a Potts network needs at least one site".to_string()));
+        }
+        if num_states < 2 {
+            return Err(PottsError::InvalidSize(format!("sites need at least 2 states, got {}", num_states)));
+        }
+        Ok(PottsNetwork { num_sites, num_states, patterns: Vec::new() })
+    }
+
+    pub fn size(&self) -> usize {
+        self.num_sites
+    }
+
+    /// States per site, q
+    pub fn num_states(&self) -> usize {
+        self.num_states
+    }
+
+    pub fn patterns(&self) -> &[Vec<usize>] {
+        &self.patterns
+    }
+
+    /// Replaces the stored patterns
+    pub fn train(&mut self, patterns: &[Vec<usize>]) -> Result<(), PottsError> {
+        for pattern in patterns {
+            self.validate_state(pattern)?;
+        }
+        self.patterns = patterns.to_vec();
+        Ok(())
+    }
+
+    fn validate_state(&self, state: &[usize]) -> Result<(), PottsError> {
+        if state.len() != self.num_sites {
+            return Err(PottsError::DimensionMismatch { expected: self.num_sites, actual: state.len() });
+        }
+        if let Some(&s) = state.iter().find(|&&s| s >= self.num_states) {
+            return Err(PottsError::InvalidState(format!("state {} is out of range for q = {}", s, self.num_states)));
+        }
+        Ok(())
+    }
+
+    /// u(ξ, k) = δ(ξ, k) - 1/q
+    fn u(&self, xi: usize, k: usize) -> f64 {
+        (xi == k) as u8 as f64 - 1.0 / self.num_states as f64
+    }
+
+    /// Σ_j u(ξ_j^μ, s_j) for every stored pattern μ
+    fn raw_overlaps(&self, state: &[usize]) -> Vec<f64> {
+        self.patterns
+            .iter()
+            .map(|pattern| pattern.iter().zip(state).map(|(&xi, &s)| self.u(xi, s)).sum())
+            .collect()
+    }
+
+    /// Overlap of `state` with each stored pattern, 1 for the pattern itself and about 0
+    /// for an unrelated state
+    pub fn overlaps(&self, state: &[usize]) -> Vec<f64> {
+        let q = self.num_states as f64;
+        let scale = q / ((q - 1.0) * self.num_sites as f64);
+        self.raw_overlaps(state).into_iter().map(|m| m * scale).collect()
+    }
+
+    /// Energy E = -1/2 Σ_{i≠j} J_ij(s_i, s_j)
+    pub fn energy(&self, state: &[usize]) -> Result<f64, PottsError> {
+        self.validate_state(state)?;
+        let n = self.num_sites as f64;
+        let energy = self
+            .patterns
+            .iter()
+            .zip(self.raw_overlaps(state))
+            .map(|(pattern, m)| {
+                let self_terms: f64 = pattern.iter().zip(state).map(|(&xi, &s)| self.u(xi, s).powi(2)).sum();
+                m * m - self_terms
+            })
+            .sum::<f64>();
+        Ok(-energy / (2.0 * n))
+    }
+
+    /// One asynchronous sweep in random order: each site takes the state with the
+    /// largest field, keeping its current state on ties, so the energy never increases
+    ///
+    /// # Returns
+    ///
+    /// Number of sites that changed state
+    pub fn sweep(&self, state: &mut [usize], rng: &mut impl Rng) -> Result<usize, PottsError> {
+        self.validate_state(state)?;
+        let mut overlaps = self.raw_overlaps(state);
+        let mut order: Vec<usize> = (0..self.num_sites).collect();
+        order.shuffle(rng);
+
+        let mut changes = 0;
+        for i in order {
+            let current = state[i];
+            // Field of state k at site i from all other sites
+            let field = |k: usize| -> f64 {
+                self.patterns
+                    .iter()
+                    .zip(&overlaps)
+                    .map(|(pattern, &m)| self.u(pattern[i], k) * (m - self.u(pattern[i], current)))
+                    .sum()
+            };
+            let mut best = (current, field(current));
+            for k in (0..self.num_states).filter(|&k| k != current) {
+                let h = field(k);
+                if h > best.1 + 1e-12 {
+                    best = (k, h);
+                }
+            }
+            if best.0 != current {
+                for (pattern, m) in self.patterns.iter().zip(&mut overlaps) {
+                    *m += self.u(pattern[i], best.0) - self.u(pattern[i], current);
+                }
+                state[i] = best.0;
+                changes += 1;
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Sweeps until no site changes or `max_sweeps` sweeps were made
+    ///
+    /// # Returns
+    ///
+    /// The visited states, starting with `state` itself
+    pub fn run(&self, state: &[usize], max_sweeps: usize, rng: &mut impl Rng) -> Result<Vec<Vec<usize>>, PottsError> {
+        let mut current = state.to_vec();
+        let mut history = vec![current.clone()];
+        for _ in 0..max_sweeps {
+            if self.sweep(&mut current, rng)? == 0 {
+                break;
+            }
+            history.push(current.clone());
+        }
+        Ok(history)
+    }
+}
+
+impl AssociativeMemory for PottsNetwork {
+    type Pattern = Vec<usize>;
+    type Error = PottsError;
+
+    fn size(&self) -> usize {
+        self.num_sites
+    }
+
+    // Adds the patterns to those already stored
+    fn store(&mut self, patterns: &[Self::Pattern]) -> Result<(), Self::Error> {
+        let mut all = self.patterns.clone();
+        all.extend_from_slice(patterns);
+        self.train(&all)
+    }
+
+    fn recall(&self, cue: &Self::Pattern, rng: &mut impl Rng) -> Result<Self::Pattern, Self::Error> {
+        let mut history = self.run(cue, super::hopfield::EVALUATION_MAX_SWEEPS, rng)?;
+        Ok(history.pop().unwrap_or_else(|| cue.clone()))
+    }
+}
+
+/// Quantizes gray values in [0, 1] into `levels` evenly spaced levels, 0 for black
+pub fn quantize(values: &[f64], levels: usize) -> Vec<usize> {
+    let top = levels.saturating_sub(1);
+    values
+        .iter()
+        .map(|&v| ((v.clamp(0.0, 1.0) * top as f64).round() as usize).min(top))
+        .collect()
+}
+
+/// Gray values in [0, 1] of quantized levels, the inverse of `quantize`
+pub fn dequantize(states: &[usize], levels: usize) -> Vec<f64> {
+    let top = levels.saturating_sub(1).max(1) as f64;
+    states.iter().map(|&s| (s as f64 / top).min(1.0)).collect()
+}
+
+/// Randomizes each site with `probability`, drawing any of the `levels` states
+pub fn randomize_sites(state: &[usize], levels: usize, probability: f64, rng: &mut impl Rng) -> Vec<usize> {
+    state
+        .iter()
+        .map(|&s| if rng.gen::<f64>() < probability { rng.gen_range(0..levels.max(1)) } else { s })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_recalls_gray_level_patterns() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let (n, q) = (100, 4);
+        let patterns: Vec<Vec<usize>> = (0..3).map(|_| (0..n).map(|_| rng.gen_range(0..q)).collect()).collect();
+        let mut net = PottsNetwork::try_new(n, q).unwrap();
+        net.train(&patterns).unwrap();
+
+        let overlaps = net.overlaps(&patterns[1]);
+        assert!((overlaps[1] - 1.0).abs() < 1e-12);
+        assert!(overlaps[0].abs() < 0.3);
+
+        let cue = randomize_sites(&patterns[1], q, 0.3, &mut rng);
+        let history = net.run(&cue, 20, &mut rng).unwrap();
+        assert_eq!(history.last().unwrap(), &patterns[1]);
+        let energies: Vec<f64> = history.iter().map(|s| net.energy(s).unwrap()).collect();
+        assert!(energies.windows(2).all(|w| w[1] <= w[0] + 1e-9));
+
+        assert!(net.energy(&[q; 100]).is_err());
+        assert!(PottsNetwork::try_new(10, 1).is_err());
+        assert_eq!(quantize(&[0.0, 0.4, 1.0], 4), vec![0, 1, 3]);
+        assert_eq!(dequantize(&[0, 3], 4), vec![0.0, 1.0]);
+    }
+}
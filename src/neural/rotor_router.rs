@@ -0,0 +1,362 @@
+use rand::Rng;
+
+use super::chip_firing::{ChipFiringError, ChipFiringGraph, VertexSelectionStrategy};
+use super::DynamicalSystem;
+
+/// Snapshot of a rotor-router system: chips at each vertex and the rotor of each vertex
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotorRouterState {
+    pub chips: Vec<i32>,
+    pub rotors: Vec<usize>,
+}
+
+/// Rotor-routing (Eulerian walkers) on the same graphs used for chip firing.
+///
+/// Rotor-routing is the deterministic sibling of the sandpile:
+/// - Each vertex has a rotor pointing at one of its outgoing edges, taken from a fixed cyclic order
+/// - A vertex holding a chip routes it by advancing its rotor to the next edge
+///   and sending the chip along that edge
+/// - Chips reaching the sink (if any) are absorbed
+///
+/// Parallel edges appear several times in the rotor order, so a vertex sends chips
+/// to each neighbor in proportion to the edge multiplicity, like a firing does.
+#[derive(Debug, Clone)]
+pub struct RotorRouter {
+    /// Number of vertices in the graph
+    pub num_vertices: usize,
+
+    /// Cyclic order of outgoing edges at each vertex (neighbors repeated per multiplicity)
+    rotor_orders: Vec<Vec<usize>>,
+
+    /// Index into `rotor_orders[v]` of the edge the rotor at v currently points along
+    pub rotors: Vec<usize>,
+
+    /// Number of chips at each vertex
+    pub chips: Vec<i32>,
+
+    /// Vertex that absorbs chips instead of routing them
+    pub sink: Option<usize>,
+
+    /// Number of chips absorbed by the sink
+    pub absorbed: u64,
+
+    /// History of states after each step
+    pub history: Vec<RotorRouterState>,
+
+    /// How the next vertex to route from is chosen
+    pub selection_strategy: VertexSelectionStrategy,
+}
+
+impl RotorRouter {
+    /// Creates a rotor-router on the graph of `graph`, starting from its current chip configuration.
+    ///
+    /// All rotors start at the first edge of their vertex.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The chip-firing graph providing vertices, edges and initial chips
+    /// * `sink` - Optional vertex that absorbs chips
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new RotorRouter, or an error if the sink is out of range.
+    pub fn from_graph(graph: &ChipFiringGraph, sink: Option<usize>) -> Result<Self, ChipFiringError> {
+        let n = graph.num_vertices;
+        if let Some(s) = sink {
+            if s >= n {
+                return Err(ChipFiringError::InvalidGraphStructure(format!(
+                    "Sink {} is out of range for a graph with {} vertices",
+                    s, n
+                )));
+            }
+        }
+
+        let rotor_orders: Vec<Vec<usize>> = (0..n)
+            .map(|v| {
+                graph
+                    .neighbor_edges(v)
+                    .flat_map(|(j, count)| std::iter::repeat_n(j, count as usize))
+                    .collect()
+            })
+            .collect();
+
+        let mut chips = graph.configuration.clone();
+        let mut absorbed = 0;
+        if let Some(s) = sink {
+            absorbed = chips[s].max(0) as u64;
+            chips[s] = 0;
+        }
+
+        let rotors = vec![0; n];
+        let initial = RotorRouterState { chips: chips.clone(), rotors: rotors.clone() };
+
+        Ok(RotorRouter {
+            num_vertices: n,
+            rotor_orders,
+            rotors,
+            chips,
+            sink,
+            absorbed,
+            history: vec![initial],
+            selection_strategy: VertexSelectionStrategy::FirstActive,
+        })
+    }
+
+    /// Vertices that hold a chip and can route it (not the sink, at least one edge)
+    pub fn active_vertices(&self) -> Vec<usize> {
+        (0..self.num_vertices)
+            .filter(|&v| self.can_route(v))
+            .collect()
+    }
+
+    /// Check if no chip can move any more
+    pub fn is_stable(&self) -> bool {
+        !(0..self.num_vertices).any(|v| self.can_route(v))
+    }
+
+    fn can_route(&self, vertex: usize) -> bool {
+        self.chips[vertex] > 0
+            && Some(vertex) != self.sink
+            && !self.rotor_orders[vertex].is_empty()
+    }
+
+    /// The vertex the rotor at `vertex` currently points to
+    pub fn rotor_target(&self, vertex: usize) -> Option<usize> {
+        self.rotor_order(vertex).get(*self.rotors.get(vertex)?).copied()
+    }
+
+    /// Cyclic order of the edges the rotor at `vertex` turns through
+    pub fn rotor_order(&self, vertex: usize) -> &[usize] {
+        self.rotor_orders.get(vertex).map_or(&[], |order| order.as_slice())
+    }
+
+    /// Advance the rotor at `vertex` and send one of its chips along the new rotor edge.
+    ///
+    /// # Returns
+    ///
+    /// Result with the vertex that received the chip
+    pub fn route_chip(&mut self, vertex: usize) -> Result<usize, ChipFiringError> {
+        if vertex >= self.num_vertices {
+            return Err(ChipFiringError::DimensionMismatch(format!(
+                "Vertex {} is out of range for a graph with {} vertices",
+                vertex, self.num_vertices
+            )));
+        }
+        if !self.can_route(vertex) {
+            return Err(ChipFiringError::NoActiveVertices(format!(
+                "Vertex {} has no chip it can route",
+                vertex
+            )));
+        }
+
+        let degree = self.rotor_orders[vertex].len();
+        self.rotors[vertex] = (self.rotors[vertex] + 1) % degree;
+        let target = self.rotor_orders[vertex][self.rotors[vertex]];
+
+        self.chips[vertex] -= 1;
+        if Some(target) == self.sink {
+            self.absorbed += 1;
+        } else {
+            self.chips[target] += 1;
+        }
+
+        Ok(target)
+    }
+
+    /// Route a single chip from one vertex chosen by the selection strategy
+    pub fn step(&mut self, rng: &mut impl Rng) -> Result<(), ChipFiringError> {
+        let active = self.active_vertices();
+
+        if active.is_empty() {
+            return Err(ChipFiringError::NoActiveVertices(
+                "No chips left to route".to_string()
+            ));
+        }
+
+        let vertex = match self.selection_strategy {
+            VertexSelectionStrategy::FirstActive => active[0],
+            VertexSelectionStrategy::RandomActive => active[rng.gen_range(0..active.len())],
+        };
+
+        self.route_chip(vertex)?;
+        self.record_state();
+
+        Ok(())
+    }
+
+    /// Run the dynamics for a specified number of steps or until no chip can move
+    ///
+    /// # Returns
+    ///
+    /// Result with the number of steps actually executed
+    pub fn run(&mut self, max_steps: usize, rng: &mut impl Rng) -> Result<usize, ChipFiringError> {
+        for i in 0..max_steps {
+            if self.is_stable() {
+                return Ok(i);
+            }
+            self.step(rng)?;
+        }
+
+        Ok(max_steps)
+    }
+
+    /// Drop a single walker at `vertex` and route it until it reaches the sink
+    /// (or `max_steps` moves have been made).
+    ///
+    /// Only the walker moves; other chips stay where they are.
+    ///
+    /// # Returns
+    ///
+    /// Result with the path of vertices visited, starting at `vertex`
+    pub fn add_walker(&mut self, vertex: usize, max_steps: usize) -> Result<Vec<usize>, ChipFiringError> {
+        if vertex >= self.num_vertices {
+            return Err(ChipFiringError::DimensionMismatch(format!(
+                "Vertex {} is out of range for a graph with {} vertices",
+                vertex, self.num_vertices
+            )));
+        }
+
+        let mut path = vec![vertex];
+        if Some(vertex) == self.sink {
+            self.absorbed += 1;
+            return Ok(path);
+        }
+
+        self.chips[vertex] += 1;
+        let mut current = vertex;
+        for _ in 0..max_steps {
+            if !self.can_route(current) {
+                break;
+            }
+            current = self.route_chip(current)?;
+            path.push(current);
+            if Some(current) == self.sink {
+                break;
+            }
+        }
+
+        self.record_state();
+        Ok(path)
+    }
+
+    /// Push the current state to history
+    pub fn record_state(&mut self) {
+        self.history.push(RotorRouterState {
+            chips: self.chips.clone(),
+            rotors: self.rotors.clone(),
+        });
+    }
+
+    /// Calculate the total number of chips on the graph (excluding absorbed chips)
+    pub fn total_chips(&self) -> i32 {
+        self.chips.iter().sum()
+    }
+
+    /// Reset chips and rotors to the initial state
+    pub fn reset(&mut self) {
+        if let Some(initial) = self.history.first().cloned() {
+            self.chips = initial.chips.clone();
+            self.rotors = initial.rotors.clone();
+            self.history = vec![initial];
+        }
+        self.absorbed = 0;
+    }
+
+    /// Set chips directly, keeping the rotors where they are
+    pub fn set_chips(&mut self, chips: Vec<i32>) -> Result<(), ChipFiringError> {
+        if chips.len() != self.num_vertices {
+            return Err(ChipFiringError::DimensionMismatch(format!(
+                "Configuration has length {} but expected {}",
+                chips.len(), self.num_vertices
+            )));
+        }
+        if let Some((i, &c)) = chips.iter().enumerate().find(|(_, &c)| c < 0) {
+            return Err(ChipFiringError::NegativeChips(format!(
+                "Vertex {} has {} chips, but negative chips are not allowed",
+                i, c
+            )));
+        }
+
+        self.chips = chips;
+        if let Some(s) = self.sink {
+            self.chips[s] = 0;
+        }
+        self.absorbed = 0;
+        self.history = vec![RotorRouterState { chips: self.chips.clone(), rotors: self.rotors.clone() }];
+
+        Ok(())
+    }
+}
+
+// Implement the DynamicalSystem trait for RotorRouter; a step routes a single chip
+impl DynamicalSystem for RotorRouter {
+    type State = Vec<i32>;
+    type Error = ChipFiringError;
+
+    fn size(&self) -> usize {
+        self.num_vertices
+    }
+
+    fn state(&self) -> Self::State {
+        self.chips.clone()
+    }
+
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        self.set_chips(state)
+    }
+
+    fn step(&mut self, rng: &mut impl Rng) -> Result<(), Self::Error> {
+        RotorRouter::step(self, rng)
+    }
+
+    fn is_stable(&self) -> bool {
+        RotorRouter::is_stable(self)
+    }
+
+    fn history(&self) -> Vec<Self::State> {
+        self.history.iter().map(|state| state.chips.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_rotor_cycles_through_neighbors() {
+        // Star with center 0 and leaves 1, 2, 3
+        let edges = vec![(0, 1), (0, 2), (0, 3)];
+        let graph = ChipFiringGraph::from_edge_list(&edges, 4, vec![3, 0, 0, 0]).unwrap();
+        let mut router = RotorRouter::from_graph(&graph, None).unwrap();
+
+        // Rotor starts at neighbor 1, so chips go to 2, 3, then 1
+        assert_eq!(router.route_chip(0).unwrap(), 2);
+        assert_eq!(router.route_chip(0).unwrap(), 3);
+        assert_eq!(router.route_chip(0).unwrap(), 1);
+        assert_eq!(router.chips, vec![0, 1, 1, 1]);
+        assert_eq!(router.total_chips(), 3);
+    }
+
+    #[test]
+    fn test_walkers_reach_sink() {
+        // Path 0-1-2-3 with the sink at 3
+        let edges = vec![(0, 1), (1, 2), (2, 3)];
+        let graph = ChipFiringGraph::from_edge_list(&edges, 4, vec![0; 4]).unwrap();
+        let mut router = RotorRouter::from_graph(&graph, Some(3)).unwrap();
+
+        let path = router.add_walker(0, 1000).unwrap();
+        assert_eq!(path.first(), Some(&0));
+        assert_eq!(path.last(), Some(&3));
+        assert_eq!(router.total_chips(), 0);
+        assert_eq!(router.absorbed, 1);
+
+        // Every chip eventually drains into the sink
+        router.set_chips(vec![2, 1, 1, 0]).unwrap();
+        let mut rng = thread_rng();
+        router.run(1000, &mut rng).unwrap();
+        assert!(router.is_stable());
+        assert_eq!(router.total_chips(), 0);
+        assert_eq!(router.absorbed, 4);
+    }
+}
@@ -0,0 +1,265 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tungstenite::{Message, WebSocket};
+
+mod session;
+
+pub use session::{route, Sessions};
+
+/// How long a WebSocket connection waits for a client message before forwarding states
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Error types for the remote control server
+#[derive(Debug)]
+pub enum RemoteError {
+    Io(io::Error),
+    NotFound(String),
+    BadRequest(String),
+    /// The request was well-formed but the model rejected it
+    Model(String),
+}
+
+impl RemoteError {
+    fn status(&self) -> &'static str {
+        match self {
+            RemoteError::Io(_) => "500 Internal Server Error",
+            RemoteError::NotFound(_) => "404 Not Found",
+            RemoteError::BadRequest(_) => "400 Bad Request",
+            RemoteError::Model(_) => "422 Unprocessable Entity",
+        }
+    }
+}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteError::Io(e) => write!(f, "I/O error: {}", e),
+            RemoteError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            RemoteError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            RemoteError::Model(msg) => write!(f, "Model error: {}", msg),
+        }
+    }
+}
+
+impl Error for RemoteError {}
+
+impl From<io::Error> for RemoteError {
+    fn from(e: io::Error) -> Self {
+        RemoteError::Io(e)
+    }
+}
+
+/// HTTP and WebSocket server that lets external tools create, train and run Hopfield
+/// networks. REST requests are described at [`route`]; a WebSocket opened on
+/// `/networks/{id}/stream` receives every sweep of the network's runs as JSON and accepts
+/// `{"action": "patterns" | "run", ...}` messages with the same bodies as the REST calls.
+///
+/// The server runs on background threads for as long as the process.
+pub struct RemoteServer {
+    address: SocketAddr,
+    sessions: Arc<Mutex<Sessions>>,
+}
+
+impl RemoteServer {
+    /// Binds to `address` and starts accepting connections
+    pub fn start(address: impl ToSocketAddrs) -> Result<Self, RemoteError> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let sessions = Arc::new(Mutex::new(Sessions::default()));
+
+        let shared = Arc::clone(&sessions);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sessions = Arc::clone(&shared);
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, &sessions) {
+                        eprintln!("Remote connection error: {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(RemoteServer { address, sessions })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn sessions(&self) -> Arc<Mutex<Sessions>> {
+        Arc::clone(&self.sessions)
+    }
+}
+
+/// A parsed HTTP request; `raw` holds the bytes read so far for the WebSocket handshake
+struct Request {
+    method: String,
+    path: String,
+    upgrade: bool,
+    body: Vec<u8>,
+    raw: Vec<u8>,
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request, RemoteError> {
+    let mut reader = BufReader::new(stream);
+    let mut raw = Vec::new();
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    raw.extend_from_slice(line.as_bytes());
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(RemoteError::BadRequest("Malformed request line".to_string())),
+    };
+
+    let mut content_length = 0;
+    let mut upgrade = false;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        raw.extend_from_slice(line.as_bytes());
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "upgrade" => upgrade = value.trim().eq_ignore_ascii_case("websocket"),
+                _ => {},
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Request { method, path, upgrade, body, raw })
+}
+
+fn write_response(mut stream: &TcpStream, status: &str, body: &Value) -> Result<(), RemoteError> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, sessions: &Mutex<Sessions>) -> Result<(), RemoteError> {
+    let request = read_request(&stream)?;
+    if request.upgrade {
+        return stream_states(stream, request, sessions);
+    }
+
+    let body = if request.body.is_empty() {
+        Value::Null
+    } else {
+        match serde_json::from_slice(&request.body) {
+            Ok(body) => body,
+            Err(e) => return write_response(&stream, "400 Bad Request", &json!({ "error": e.to_string() })),
+        }
+    };
+    match route(sessions, &request.method, &request.path, &body) {
+        Ok(value) => write_response(&stream, "200 OK", &value),
+        Err(e) => write_response(&stream, e.status(), &json!({ "error": e.to_string() })),
+    }
+}
+
+/// A stream that first replays the bytes of an already parsed request
+struct Replay {
+    prefix: Cursor<Vec<u8>>,
+    stream: TcpStream,
+}
+
+impl Read for Replay {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.prefix.read(buf)? {
+            0 => self.stream.read(buf),
+            n => Ok(n),
+        }
+    }
+}
+
+impl Write for Replay {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+/// Serves a WebSocket on `/networks/{id}/stream` until the client disconnects
+fn stream_states(stream: TcpStream, request: Request, sessions: &Mutex<Sessions>) -> Result<(), RemoteError> {
+    let id = match request.path.trim_matches('/').split('/').collect::<Vec<_>>().as_slice() {
+        ["networks", id, "stream"] => id.parse::<usize>().ok(),
+        _ => None,
+    };
+    let Some(id) = id else {
+        return write_response(&stream, "404 Not Found", &json!({ "error": "WebSockets are served on /networks/{id}/stream" }));
+    };
+    let (sender, receiver) = mpsc::channel();
+    if let Err(e) = sessions.lock().unwrap().subscribe(id, sender) {
+        return write_response(&stream, e.status(), &json!({ "error": e.to_string() }));
+    }
+
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    let replay = Replay { prefix: Cursor::new(request.raw), stream };
+    let mut socket = tungstenite::accept(replay).map_err(|e| RemoteError::BadRequest(e.to_string()))?;
+    serve_socket(&mut socket, id, &receiver, sessions)
+}
+
+fn serve_socket(
+    socket: &mut WebSocket<Replay>,
+    id: usize,
+    states: &Receiver<String>,
+    sessions: &Mutex<Sessions>,
+) -> Result<(), RemoteError> {
+    let closed = |e: tungstenite::Error| match e {
+        tungstenite::Error::ConnectionClosed
+        | tungstenite::Error::AlreadyClosed
+        | tungstenite::Error::Protocol(tungstenite::error::ProtocolError::ResetWithoutClosingHandshake) => None,
+        e => Some(RemoteError::BadRequest(e.to_string())),
+    };
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                let reply = match serde_json::from_str::<Value>(&text) {
+                    Ok(body) => {
+                        let action = body.get("action").and_then(Value::as_str).unwrap_or_default();
+                        route(sessions, "POST", &format!("/networks/{}/{}", id, action), &body)
+                            .unwrap_or_else(|e| json!({ "error": e.to_string() }))
+                    },
+                    Err(e) => json!({ "error": e.to_string() }),
+                };
+                if let Err(e) = socket.send(Message::Text(reply.to_string())) {
+                    return closed(e).map_or(Ok(()), Err);
+                }
+            },
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {},
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {},
+            Err(e) => return closed(e).map_or(Ok(()), Err),
+        }
+
+        for state in states.try_iter() {
+            if let Err(e) = socket.send(Message::Text(state)) {
+                return closed(e).map_or(Ok(()), Err);
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::{json, Value};
+
+use super::RemoteError;
+use crate::neural::hopfield::{HopfieldNetwork, SweepOrder, TrainingRule};
+
+/// Sweeps a run performs when the request does not say
+const DEFAULT_MAX_ITERATIONS: usize = 100;
+
+/// A network created by a remote client
+struct Session {
+    network: HopfieldNetwork,
+    patterns: usize,
+    /// Final state of the last run
+    state: Option<Vec<f64>>,
+    /// WebSocket connections receiving the states of runs as JSON
+    subscribers: Vec<Sender<String>>,
+}
+
+/// The networks of all remote clients, addressed by id
+#[derive(Default)]
+pub struct Sessions {
+    next_id: usize,
+    sessions: BTreeMap<usize, Session>,
+}
+
+impl Sessions {
+    fn get(&mut self, id: usize) -> Result<&mut Session, RemoteError> {
+        self.sessions
+            .get_mut(&id)
+            .ok_or_else(|| RemoteError::NotFound(format!("No network with id {}", id)))
+    }
+
+    /// Streams the states of future runs on network `id` to `subscriber`
+    pub fn subscribe(&mut self, id: usize, subscriber: Sender<String>) -> Result<(), RemoteError> {
+        self.get(id)?.subscribers.push(subscriber);
+        Ok(())
+    }
+
+    fn summary(id: usize, session: &Session) -> Value {
+        json!({
+            "id": id,
+            "size": session.network.size(),
+            "patterns": session.patterns,
+            "state": session.state,
+        })
+    }
+}
+
+fn field<'a>(body: &'a Value, name: &str) -> Result<&'a Value, RemoteError> {
+    body.get(name).ok_or_else(|| RemoteError::BadRequest(format!("Missing field '{}'", name)))
+}
+
+fn to_state(value: &Value) -> Result<Vec<f64>, RemoteError> {
+    value
+        .as_array()
+        .and_then(|values| values.iter().map(Value::as_f64).collect())
+        .ok_or_else(|| RemoteError::BadRequest("Expected an array of numbers".to_string()))
+}
+
+/// Handles one request against the shared sessions.
+///
+/// * `POST /networks` with `{"size": n}` creates a network
+/// * `GET /networks` and `GET /networks/{id}` describe networks
+/// * `POST /networks/{id}/patterns` with `{"patterns": [[...]], "rule": "hebbian" | "pseudo-inverse" | "storkey"}` trains
+/// * `POST /networks/{id}/run` with `{"state": [...], "max_iterations": n, "beta": b, "seed": s}` runs
+///   asynchronous sweeps from `state`; without `beta` the dynamics are deterministic and stop
+///   once a sweep changes nothing. Every sweep is sent to the subscribers of the network.
+pub fn route(sessions: &Mutex<Sessions>, method: &str, path: &str, body: &Value) -> Result<Value, RemoteError> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let id = || -> Result<usize, RemoteError> {
+        segments[1].parse().map_err(|_| RemoteError::NotFound(format!("No network with id {}", segments[1])))
+    };
+    match (method, segments.as_slice()) {
+        ("GET", ["networks"]) => {
+            let sessions = sessions.lock().unwrap();
+            Ok(Value::Array(sessions.sessions.iter().map(|(&id, s)| Sessions::summary(id, s)).collect()))
+        },
+        ("POST", ["networks"]) => {
+            let size = field(body, "size")?
+                .as_u64()
+                .ok_or_else(|| RemoteError::BadRequest("'size' must be a positive integer".to_string()))?;
+            let network = HopfieldNetwork::try_new(size as usize).map_err(|e| RemoteError::BadRequest(e.to_string()))?;
+            let mut sessions = sessions.lock().unwrap();
+            let id = sessions.next_id;
+            sessions.next_id += 1;
+            let session = Session {
+                network,
+                patterns: 0,
+                state: None,
+                subscribers: Vec::new(),
+            };
+            let summary = Sessions::summary(id, &session);
+            sessions.sessions.insert(id, session);
+            Ok(summary)
+        },
+        ("GET", ["networks", _]) => {
+            let id = id()?;
+            let mut sessions = sessions.lock().unwrap();
+            Ok(Sessions::summary(id, sessions.get(id)?))
+        },
+        ("POST", ["networks", _, "patterns"]) => {
+            let patterns = field(body, "patterns")?
+                .as_array()
+                .ok_or_else(|| RemoteError::BadRequest("'patterns' must be an array".to_string()))?
+                .iter()
+                .map(to_state)
+                .collect::<Result<Vec<_>, _>>()?;
+            let rule = match body.get("rule").and_then(Value::as_str).unwrap_or("hebbian") {
+                "hebbian" => TrainingRule::Hebbian,
+                "pseudo-inverse" => TrainingRule::PseudoInverse,
+                "storkey" => TrainingRule::Storkey,
+                other => return Err(RemoteError::BadRequest(format!("Unknown training rule '{}'", other))),
+            };
+            let mut sessions = sessions.lock().unwrap();
+            let session = sessions.get(id()?)?;
+            session.network.train(&patterns, rule).map_err(|e| RemoteError::Model(e.to_string()))?;
+            session.patterns = patterns.len();
+            Ok(json!({ "patterns": patterns.len() }))
+        },
+        ("POST", ["networks", _, "run"]) => run(sessions, id()?, body),
+        _ => Err(RemoteError::NotFound(format!("No route for {} {}", method, path))),
+    }
+}
+
+/// Runs without holding the lock, so other requests are served while the network settles
+fn run(sessions: &Mutex<Sessions>, id: usize, body: &Value) -> Result<Value, RemoteError> {
+    let mut state = to_state(field(body, "state")?)?;
+    let max_iterations = body.get("max_iterations").and_then(Value::as_u64).map_or(DEFAULT_MAX_ITERATIONS, |n| n as usize);
+    let beta = body.get("beta").and_then(Value::as_f64).unwrap_or(f64::INFINITY);
+    let mut rng = match body.get("seed").and_then(Value::as_u64) {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let (network, mut subscribers) = {
+        let mut sessions = sessions.lock().unwrap();
+        let session = sessions.get(id)?;
+        (session.network.clone(), session.subscribers.clone())
+    };
+    let model_error = |e: crate::neural::hopfield::HopfieldError| RemoteError::Model(e.to_string());
+
+    let mut energy = network.energy(&state).map_err(model_error)?;
+    let mut iterations = 0;
+    for iteration in 1..=max_iterations {
+        let (history, _) = network
+            .run_async(&state, 1, beta, SweepOrder::RandomPermutation, &mut rng)
+            .map_err(model_error)?;
+        let next = history.last().cloned().unwrap_or_default();
+        let unchanged = next == state;
+        state = next;
+        energy = network.energy(&state).map_err(model_error)?;
+        iterations = iteration;
+
+        let message = json!({ "event": "state", "id": id, "iteration": iteration, "energy": energy, "state": state });
+        let text = message.to_string();
+        // Subscribers whose connection closed are dropped
+        subscribers.retain(|subscriber| subscriber.send(text.clone()).is_ok());
+
+        if unchanged && beta.is_infinite() {
+            break;
+        }
+    }
+
+    let mut sessions = sessions.lock().unwrap();
+    let session = sessions.get(id)?;
+    session.state = Some(state.clone());
+    session.subscribers.retain(|subscriber| subscriber.send(json!({ "event": "finished", "id": id }).to_string()).is_ok());
+    Ok(json!({ "iterations": iterations, "energy": energy, "state": state }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_create_train_and_run_with_subscriber() {
+        let sessions = Mutex::new(Sessions::default());
+        let created = route(&sessions, "POST", "/networks", &json!({ "size": 4 })).unwrap();
+        assert_eq!(created["id"], 0);
+
+        let pattern = json!([1.0, -1.0, 1.0, -1.0]);
+        let trained = route(&sessions, "POST", "/networks/0/patterns", &json!({ "patterns": [pattern] })).unwrap();
+        assert_eq!(trained["patterns"], 1);
+
+        let (sender, receiver) = mpsc::channel();
+        sessions.lock().unwrap().subscribe(0, sender).unwrap();
+        let result = route(&sessions, "POST", "/networks/0/run", &json!({ "state": [1.0, -1.0, 1.0, 1.0], "seed": 1 })).unwrap();
+        assert_eq!(result["state"], pattern);
+
+        let events: Vec<Value> = receiver.try_iter().map(|text| serde_json::from_str(&text).unwrap()).collect();
+        assert_eq!(events.last().unwrap()["event"], "finished");
+        assert_eq!(events.len() as u64, result["iterations"].as_u64().unwrap() + 1);
+
+        assert!(matches!(route(&sessions, "GET", "/networks/7", &Value::Null), Err(RemoteError::NotFound(_))));
+        assert!(matches!(route(&sessions, "POST", "/networks", &json!({})), Err(RemoteError::BadRequest(_))));
+        assert!(matches!(
+            route(&sessions, "POST", "/networks/0/run", &json!({ "state": [1.0] })),
+            Err(RemoteError::Model(_))
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ImmutableString};
+
+use crate::core::graph::Graph;
+use crate::core::metrics::overlap;
+use crate::core::noise::flip_bits;
+use crate::graphics::export::write_csv;
+use crate::neural::chip_firing::{ChipFiringGraph, DriveTarget};
+use crate::neural::hopfield::{HopfieldNetwork, SweepOrder, TrainingRule, EVALUATION_MAX_SWEEPS};
+
+/// Firings after which `stabilize` gives up, for graphs without a sink
+const MAX_FIRINGS: usize = 1_000_000;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn script_error(e: impl ToString) -> Box<EvalAltResult> {
+    e.to_string().into()
+}
+
+/// A script array of numbers as a state vector
+fn to_state(array: &Array) -> ScriptResult<Vec<f64>> {
+    array
+        .iter()
+        .map(|value| {
+            value
+                .as_float()
+                .or_else(|_| value.as_int().map(|x| x as f64))
+                .map_err(|t| script_error(format!("Expected a number, found {}", t)))
+        })
+        .collect()
+}
+
+fn to_patterns(array: &Array) -> ScriptResult<Vec<Vec<f64>>> {
+    array
+        .iter()
+        .map(|value| match value.read_lock::<Array>() {
+            Some(pattern) => to_state(&pattern),
+            None => Err(script_error("Expected an array of patterns")),
+        })
+        .collect()
+}
+
+fn from_state(state: &[f64]) -> Array {
+    state.iter().map(|&x| Dynamic::from_float(x)).collect()
+}
+
+fn to_index(value: i64) -> ScriptResult<usize> {
+    usize::try_from(value).map_err(|_| script_error(format!("Expected a non-negative number, found {}", value)))
+}
+
+/// Registers the scripting API:
+///
+/// * `seed(n)`, `random_pattern(n)`, `corrupt(state, p)`, `overlap(a, b)`
+/// * `hopfield(n)` with `train(patterns[, rule])`, `recall(state[, max_sweeps])`,
+///   `run(state, sweeps, beta)`, `energy(state)` and the `size` property
+/// * `grid_graph(w, h)`, `cycle_graph(n)`, `complete_graph(n)`, `edge_list_graph(text)`
+///   with `add_chips(v, n)`, `set_sink(v)`, `stabilize()`, `drop_chip()`,
+///   `configuration()` and the `num_vertices` and `total_chips` properties
+/// * `export_csv(path, header, rows)`
+pub(super) fn register(engine: &mut Engine, rng: Arc<Mutex<StdRng>>) {
+    register_common(engine, &rng);
+    register_hopfield(engine, &rng);
+    register_chip_firing(engine, &rng);
+}
+
+fn register_common(engine: &mut Engine, rng: &Arc<Mutex<StdRng>>) {
+    let seed_rng = Arc::clone(rng);
+    engine.register_fn("seed", move |seed: i64| {
+        *seed_rng.lock().unwrap() = StdRng::seed_from_u64(seed as u64);
+    });
+
+    let pattern_rng = Arc::clone(rng);
+    engine.register_fn("random_pattern", move |n: i64| -> ScriptResult<Array> {
+        let mut rng = pattern_rng.lock().unwrap();
+        let pattern: Vec<f64> = (0..to_index(n)?).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect();
+        Ok(from_state(&pattern))
+    });
+
+    let noise_rng = Arc::clone(rng);
+    engine.register_fn("corrupt", move |state: Array, probability: f64| -> ScriptResult<Array> {
+        let state = to_state(&state)?;
+        Ok(from_state(&flip_bits(&state, probability, &mut *noise_rng.lock().unwrap())))
+    });
+
+    engine.register_fn("overlap", |a: Array, b: Array| -> ScriptResult<f64> {
+        Ok(overlap(&to_state(&a)?, &to_state(&b)?))
+    });
+
+    engine.register_fn(
+        "export_csv",
+        |path: ImmutableString, header: Array, rows: Array| -> ScriptResult<()> {
+            let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+            let header: Vec<&str> = header.iter().map(String::as_str).collect();
+            write_csv(path.as_str(), &header, &to_patterns(&rows)?).map_err(script_error)
+        },
+    );
+}
+
+fn register_hopfield(engine: &mut Engine, rng: &Arc<Mutex<StdRng>>) {
+    engine.register_type_with_name::<HopfieldNetwork>("HopfieldNetwork");
+    engine.register_fn("hopfield", |n: i64| -> ScriptResult<HopfieldNetwork> {
+        HopfieldNetwork::try_new(to_index(n)?).map_err(script_error)
+    });
+    engine.register_get("size", |net: &mut HopfieldNetwork| net.size() as i64);
+
+    let train = |net: &mut HopfieldNetwork, patterns: Array, rule: &str| -> ScriptResult<()> {
+        let rule = match rule {
+            "hebbian" => TrainingRule::Hebbian,
+            "pseudo-inverse" => TrainingRule::PseudoInverse,
+            "storkey" => TrainingRule::Storkey,
+            other => return Err(script_error(format!("Unknown training rule '{}'", other))),
+        };
+        net.train(&to_patterns(&patterns)?, rule).map_err(script_error)
+    };
+    engine.register_fn("train", move |net: &mut HopfieldNetwork, patterns: Array| train(net, patterns, "hebbian"));
+    engine.register_fn("train", move |net: &mut HopfieldNetwork, patterns: Array, rule: ImmutableString| {
+        train(net, patterns, rule.as_str())
+    });
+
+    engine.register_fn("energy", |net: &mut HopfieldNetwork, state: Array| -> ScriptResult<f64> {
+        net.energy(&to_state(&state)?).map_err(script_error)
+    });
+
+    // Zero-temperature recall: asynchronous sweeps until no neuron flips
+    let recall = {
+        let rng = Arc::clone(rng);
+        move |net: &mut HopfieldNetwork, state: Array, max_sweeps: usize| -> ScriptResult<Array> {
+            let mut state = to_state(&state)?;
+            if state.len() != net.size() {
+                return Err(script_error(format!("State has {} neurons, network has {}", state.len(), net.size())));
+            }
+            net.settle(&mut state, max_sweeps, &mut *rng.lock().unwrap());
+            Ok(from_state(&state))
+        }
+    };
+    let recall_default = recall.clone();
+    engine.register_fn("recall", move |net: &mut HopfieldNetwork, state: Array| {
+        recall_default(net, state, EVALUATION_MAX_SWEEPS)
+    });
+    engine.register_fn("recall", move |net: &mut HopfieldNetwork, state: Array, max_sweeps: i64| {
+        recall(net, state, to_index(max_sweeps)?)
+    });
+
+    let run_rng = Arc::clone(rng);
+    engine.register_fn(
+        "run",
+        move |net: &mut HopfieldNetwork, state: Array, sweeps: i64, beta: f64| -> ScriptResult<Array> {
+            let mut rng = run_rng.lock().unwrap();
+            let (history, _) = net
+                .run_async(&to_state(&state)?, to_index(sweeps)?, beta, SweepOrder::RandomPermutation, &mut *rng)
+                .map_err(script_error)?;
+            Ok(history.last().map(|s| from_state(s)).unwrap_or_default())
+        },
+    );
+}
+
+fn register_chip_firing(engine: &mut Engine, rng: &Arc<Mutex<StdRng>>) {
+    engine.register_type_with_name::<ChipFiringGraph>("ChipFiringGraph");
+    let from_graph = |graph: Graph| -> ScriptResult<ChipFiringGraph> {
+        let chips = vec![0; graph.num_vertices()];
+        ChipFiringGraph::from_graph(&graph, chips).map_err(script_error)
+    };
+    engine.register_fn("grid_graph", move |w: i64, h: i64| from_graph(Graph::grid(to_index(w)?, to_index(h)?)));
+    engine.register_fn("cycle_graph", move |n: i64| from_graph(Graph::cycle(to_index(n)?)));
+    engine.register_fn("complete_graph", move |n: i64| from_graph(Graph::complete(to_index(n)?)));
+    engine.register_fn("edge_list_graph", move |text: ImmutableString| {
+        from_graph(Graph::parse_edges(&text).map_err(script_error)?)
+    });
+
+    engine.register_get("num_vertices", |g: &mut ChipFiringGraph| g.num_vertices as i64);
+    engine.register_get("total_chips", |g: &mut ChipFiringGraph| g.total_chips() as i64);
+    engine.register_fn("configuration", |g: &mut ChipFiringGraph| -> Array {
+        g.configuration.iter().map(|&c| Dynamic::from_int(c as i64)).collect()
+    });
+    engine.register_fn("add_chips", |g: &mut ChipFiringGraph, vertex: i64, amount: i64| -> ScriptResult<()> {
+        let amount = i32::try_from(amount).map_err(script_error)?;
+        g.add_chips(to_index(vertex)?, amount).map_err(script_error)
+    });
+    engine.register_fn("set_sink", |g: &mut ChipFiringGraph, vertex: i64| -> ScriptResult<()> {
+        g.set_sink(Some(to_index(vertex)?)).map_err(script_error)
+    });
+    engine.register_fn("stabilize", |g: &mut ChipFiringGraph| -> ScriptResult<i64> {
+        g.stabilize(MAX_FIRINGS).map(|firings| firings as i64).map_err(script_error)
+    });
+
+    // Drops a chip on a random vertex and stabilizes; returns the avalanche size
+    let drive_rng = Arc::clone(rng);
+    engine.register_fn("drop_chip", move |g: &mut ChipFiringGraph| -> ScriptResult<i64> {
+        g.drop_chip(DriveTarget::Random, &mut *drive_rng.lock().unwrap()).map_err(script_error)?;
+        g.stabilize(MAX_FIRINGS).map(|firings| firings as i64).map_err(script_error)
+    });
+}
@@ -0,0 +1,133 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rhai::{Dynamic, Engine, EvalAltResult};
+
+mod api;
+
+/// Error types for scripts
+#[derive(Debug)]
+pub enum ScriptError {
+    Parse(String),
+    Runtime(String),
+    /// Stopped through the interrupt flag
+    Interrupted,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::Parse(msg) => write!(f, "Parse error: {}", msg),
+            ScriptError::Runtime(msg) => write!(f, "Runtime error: {}", msg),
+            ScriptError::Interrupted => write!(f, "Script interrupted"),
+        }
+    }
+}
+
+impl Error for ScriptError {}
+
+/// A Rhai engine with the Hopfield, chip-firing and export functions registered.
+///
+/// Scripts share one random number generator, so a script that calls `seed(n)` first
+/// is reproducible. Everything a script prints is appended to `output`, which can be
+/// read while the script is still running on another thread.
+pub struct ScriptEngine {
+    engine: Engine,
+    output: Arc<Mutex<Vec<String>>>,
+    interrupt: Arc<AtomicBool>,
+}
+
+impl ScriptEngine {
+    pub fn new(seed: u64) -> Self {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let rng = Arc::new(Mutex::new(StdRng::seed_from_u64(seed)));
+
+        let mut engine = Engine::new();
+        api::register(&mut engine, rng);
+
+        let printed = Arc::clone(&output);
+        engine.on_print(move |text| printed.lock().unwrap().push(text.to_string()));
+        let debugged = Arc::clone(&output);
+        engine.on_debug(move |text, _, _| debugged.lock().unwrap().push(format!("[debug] {}", text)));
+        let stop = Arc::clone(&interrupt);
+        engine.on_progress(move |_| stop.load(Ordering::Relaxed).then_some(Dynamic::UNIT));
+
+        ScriptEngine { engine, output, interrupt }
+    }
+
+    /// Lines printed by scripts so far
+    pub fn output(&self) -> Arc<Mutex<Vec<String>>> {
+        Arc::clone(&self.output)
+    }
+
+    /// Setting this flag stops the running script at its next operation
+    pub fn interrupt_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.interrupt)
+    }
+
+    /// Runs `script` and returns its final value as text (empty for unit)
+    pub fn run(&self, script: &str) -> Result<String, ScriptError> {
+        self.interrupt.store(false, Ordering::Relaxed);
+        let ast = self.engine.compile(script).map_err(|e| ScriptError::Parse(e.to_string()))?;
+        match self.engine.eval_ast::<Dynamic>(&ast) {
+            Ok(value) if value.is_unit() => Ok(String::new()),
+            Ok(value) => Ok(value.to_string()),
+            Err(e) => match *e {
+                EvalAltResult::ErrorTerminated(..) => Err(ScriptError::Interrupted),
+                e => Err(ScriptError::Runtime(e.to_string())),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_trains_and_recalls() {
+        let engine = ScriptEngine::new(0);
+        let script = r#"
+            seed(7);
+            let a = random_pattern(64);
+            let b = random_pattern(64);
+            let net = hopfield(64);
+            net.train([a, b], "hebbian");
+            let recalled = net.recall(corrupt(a, 0.1));
+            print(`overlap ${overlap(recalled, a)}`);
+            net.size
+        "#;
+        assert_eq!(engine.run(script).unwrap(), "64");
+        assert_eq!(engine.output().lock().unwrap().as_slice(), ["overlap 1.0"]);
+
+        assert!(matches!(engine.run("let x = ;"), Err(ScriptError::Parse(_))));
+        assert!(matches!(engine.run("hopfield(4).train([[1.0]])"), Err(ScriptError::Runtime(_))));
+    }
+
+    #[test]
+    fn test_script_stabilizes_sandpile() {
+        let engine = ScriptEngine::new(0);
+        let script = r#"
+            let g = grid_graph(3, 3);
+            g.add_chips(4, 8);
+            let firings = g.stabilize();
+            [firings, g.total_chips, g.configuration()[4]]
+        "#;
+        assert_eq!(engine.run(script).unwrap(), "[2, 8, 0]");
+    }
+
+    #[test]
+    fn test_interrupt_stops_script() {
+        let engine = ScriptEngine::new(0);
+        let flag = engine.interrupt_flag();
+        let handle = std::thread::spawn(move || engine.run("loop {}"));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        flag.store(true, Ordering::Relaxed);
+        assert!(matches!(handle.join().unwrap(), Err(ScriptError::Interrupted)));
+    }
+}
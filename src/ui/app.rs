@@ -1,71 +1,629 @@
 use eframe::egui;
 use eframe::egui::TextureHandle;
 use std::collections::HashMap;
-use std::path::Path;
 
+use crate::core::assets::{self, Asset};
+use crate::graphics::compute::GpuContext;
+use crate::ui::events;
+use crate::ui::instances::WindowTypes;
+use crate::ui::notifications::{self, report_error, report_info, NotificationCenter};
+use crate::ui::plugins::{PluginContext, PluginRegistry};
+use crate::ui::session::{self, Journal, SessionAction, SessionPlayer};
+use crate::ui::shortcuts::ShortcutRegistry;
+use crate::ui::undo::UndoStack;
+use crate::ui::widgets::grid::draw_grid;
+use crate::ui::widgets::height_field;
+use crate::ui::widgets::state_texture;
 use crate::ui::windows::{self, Window};
 
 // Helper function to load image for egui
-fn load_image_for_ui(path: &Path) -> Result<egui::ColorImage, image::ImageError> {
-    let image = image::open(path)?.to_rgba8();
+fn load_image_for_ui(asset: &Asset) -> Result<egui::ColorImage, image::ImageError> {
+    let (data, _) = asset.load();
+    let image = image::load_from_memory(&data)?.to_rgba8();
     let size = [image.width() as _, image.height() as _];
     let image_buffer = image.into_raw();
     Ok(egui::ColorImage::from_rgba_unmultiplied(size, &image_buffer))
 }
 
+/// Color theme of the application
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+/// Colors used to paint grids and graphs.
+///
+/// The current colors are stored in the egui context, so widgets and window painters
+/// can read them with `GridColors::get` without threading them through every call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridColors {
+    /// Cells in the +1 state
+    pub on: egui::Color32,
+    /// Cells in the -1 state, and idle vertices
+    pub off: egui::Color32,
+    /// Vertices that can fire
+    pub active: egui::Color32,
+    /// The vertex selected by the user
+    pub selected: egui::Color32,
+}
+
+impl Default for GridColors {
+    fn default() -> Self {
+        GridColors {
+            on: egui::Color32::BLACK,
+            off: egui::Color32::WHITE,
+            active: egui::Color32::GREEN,
+            selected: egui::Color32::YELLOW,
+        }
+    }
+}
+
+impl GridColors {
+    fn id() -> egui::Id {
+        egui::Id::new("raum_grid_colors")
+    }
+
+    /// The colors currently set for the application
+    pub fn get(ctx: &egui::Context) -> Self {
+        ctx.data(|data| data.get_temp(Self::id())).unwrap_or_default()
+    }
+
+    fn store(self, ctx: &egui::Context) {
+        ctx.data_mut(|data| data.insert_temp(Self::id(), self));
+    }
+
+    /// Black or white, whichever is more legible on `fill`
+    pub fn text_on(fill: egui::Color32) -> egui::Color32 {
+        let luminance = 0.299 * fill.r() as f32 + 0.587 * fill.g() as f32 + 0.114 * fill.b() as f32;
+        if luminance > 128.0 {
+            egui::Color32::BLACK
+        } else {
+            egui::Color32::WHITE
+        }
+    }
+}
+
+/// Application-wide settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppSettings {
+    pub theme: Theme,
+    pub grid_colors: GridColors,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            theme: Theme::Dark,
+            grid_colors: GridColors::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Applies the theme and publishes the grid colors to `ctx`
+    pub fn apply(&self, ctx: &egui::Context) {
+        ctx.set_visuals(match self.theme {
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light => egui::Visuals::light(),
+        });
+        self.grid_colors.store(ctx);
+    }
+
+    /// Draws the settings controls with a preview of the grid colors.
+    ///
+    /// # Returns
+    ///
+    /// true if a setting was changed
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        ui.horizontal(|ui| {
+            ui.label("Theme:");
+            ui.selectable_value(&mut self.theme, Theme::Dark, "Dark");
+            ui.selectable_value(&mut self.theme, Theme::Light, "Light");
+        });
+
+        ui.label("Grid Colors:");
+        egui::Grid::new("grid_color_settings").num_columns(2).show(ui, |ui| {
+            let colors = &mut self.grid_colors;
+            for (label, color) in [
+                ("On Cells", &mut colors.on),
+                ("Off Cells", &mut colors.off),
+                ("Active Vertex", &mut colors.active),
+                ("Selected Vertex", &mut colors.selected),
+            ] {
+                ui.label(label);
+                ui.color_edit_button_srgba(color);
+                ui.end_row();
+            }
+        });
+        if ui.button("Reset Colors").clicked() {
+            self.grid_colors = GridColors::default();
+        }
+
+        let changed = *self != before;
+        if changed {
+            self.apply(ui.ctx());
+        }
+
+        // Preview of a bipolar grid in the current colors
+        ui.label("Preview:");
+        let preview = [1.0, -1.0, 1.0, -1.0, -1.0, 1.0, -1.0, 1.0];
+        draw_grid(ui, &preview, 4, 2, 12.0);
+        ui.horizontal(|ui| {
+            for (label, color) in [("Active", self.grid_colors.active), ("Selected", self.grid_colors.selected)] {
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 0.0, color);
+                ui.label(label);
+            }
+        });
+
+        changed
+    }
+}
+
+/// Change to the window instances requested from the sidebar, applied after it is drawn
+enum InstanceAction {
+    /// Open a new instance of a window type
+    Spawn(String),
+    /// Open a new instance with the settings of an existing one
+    Duplicate(String),
+    Rename(String),
+    Remove(String),
+}
+
 /// Main application structure
 pub struct RaumApp {
     /// Collection of windows that can be opened
     windows: HashMap<String, Box<dyn Window>>,
     /// Track which windows are currently open
     window_open_states: HashMap<String, bool>,
+    /// Constructors of the built-in window types, for opening more instances
+    window_types: WindowTypes,
+    /// Window being renamed, and its new title as typed so far
+    renaming: Option<(String, String)>,
+    /// Open states as of the last lifecycle update, to detect windows opening and closing
+    lifecycle_states: HashMap<String, bool>,
     /// Texture handle for the application icon
     icon_texture: Option<TextureHandle>,
+    /// Theme and grid colors
+    settings: AppSettings,
+    /// Keyboard shortcuts dispatched to the focused window
+    shortcuts: ShortcutRegistry,
+    /// Whether the About dialog is shown
+    show_about: bool,
+    /// Plugins that contributed windows. Declared after `windows`, so their windows are
+    /// dropped first.
+    plugins: PluginRegistry,
+    /// Whether the plugins page is shown
+    show_plugins: bool,
+    /// Error and information toasts reported by the windows, and their log
+    notifications: NotificationCenter,
+    /// Snapshots taken by the windows before destructive operations, for Edit → Undo
+    undo: UndoStack,
+    /// File a session journal is recorded to and replayed from
+    session_path: String,
+    /// Journal being replayed, if any
+    session_player: Option<SessionPlayer>,
 }
 
 impl RaumApp {
-    /// Creates a new application instance
+    /// Creates a new application instance with the built-in windows
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        Self::with_plugins(cc, PluginRegistry::default())
+    }
+
+    /// Creates a new application instance with the built-in windows and those of `plugins`
+    pub fn with_plugins(cc: &eframe::CreationContext<'_>, mut plugins: PluginRegistry) -> Self {
         // Set up custom fonts if needed
         let egui_ctx = &cc.egui_ctx;
         
         // Load the icon texture
-        let icon_texture = match load_image_for_ui(Path::new("assets/icon.png")) {
+        let icon_texture = match load_image_for_ui(&assets::ICON) {
             Ok(image) => Some(egui_ctx.load_texture(
                 "app_icon", 
                 image, 
                 Default::default()
             )),
             Err(e) => {
-                eprintln!("Failed to load icon for UI from assets/icon.png: {}", e);
+                eprintln!("Failed to load icon for UI: {}", e);
                 None
             }
         };
 
-        // Initialize with default windows
-        let mut windows: HashMap<String, Box<dyn Window>> = HashMap::new();
-        let mut window_open_states: HashMap<String, bool> = HashMap::new();
+        // Let windows draw large animated states and 3D views through their own GPU textures
+        if let Some(render_state) = &cc.wgpu_render_state {
+            state_texture::install(egui_ctx, render_state);
+            height_field::install(egui_ctx, render_state);
+        }
+
+        // Initialize with default windows. Each type is registered with its constructor,
+        // so more instances can be opened later.
+        let mut window_types = WindowTypes::default();
         
         // Add Hopfield Network window
-        let hopfield_window = windows::hopfield::HopfieldWindow::new();
-        let window_name_hopfield = hopfield_window.name().to_string();
-        windows.insert(window_name_hopfield.clone(), Box::new(hopfield_window));
-        window_open_states.insert(window_name_hopfield, false); // Closed by default
+        window_types.register(windows::hopfield::HopfieldWindow::new);
         
         // Add Chip Firing Graph window
-        let chip_firing_window = windows::chip_firing::ChipFiringWindow::new();
-        let window_name_chip = chip_firing_window.name().to_string();
-        windows.insert(window_name_chip.clone(), Box::new(chip_firing_window));
-        window_open_states.insert(window_name_chip, false); // Closed by default
+        window_types.register(windows::chip_firing::ChipFiringWindow::new);
+        
+        // Add Rotor Router window
+        window_types.register(windows::rotor_router::RotorRouterWindow::new);
+        
+        // Add Ising Model window
+        window_types.register(windows::ising::IsingWindow::new);
+        
+        // Add Cellular Automata window
+        window_types.register(windows::cellular::CellularWindow::new);
+        
+        // Add Hopfield-Tank Optimizer window
+        window_types.register(windows::hopfield_tank::HopfieldTankWindow::new);
+        
+        // Add Boltzmann Machine window
+        window_types.register(windows::boltzmann::BoltzmannWindow::new);
+        
+        // Add Potts Network window
+        window_types.register(windows::potts::PottsWindow::new);
+        
+        // Add Bidirectional Memory window
+        window_types.register(windows::bam::BamWindow::new);
+        
+        // GPU compute kernels share the device of the renderer, when it uses wgpu
+        let gpu = cc.wgpu_render_state.as_ref().map(GpuContext::from_render_state);
+        
+        // Add Sandpile window
+        let sandpile_gpu = gpu.clone();
+        window_types.register(move || windows::sandpile::SandpileWindow::new(sandpile_gpu.clone()));
+        
+        // Add Sandpile Memory window, which feeds sandpile avalanches to a Hopfield network
+        window_types.register(windows::sandpile_memory::SandpileMemoryWindow::new);
+        
+        // Add Spiking Network window
+        window_types.register(windows::lif::LifWindow::new);
+        
+        // Add Reaction-Diffusion window
+        window_types.register(windows::gray_scott::GrayScottWindow::new);
+        
+        // Add Graph Analysis window
+        window_types.register(windows::graph_analysis::GraphAnalysisWindow::new);
+        
+        // Add Performance window, which benchmarks on the renderer's GPU if it uses wgpu
+        let performance_gpu = gpu.clone();
+        window_types.register(move || windows::performance::PerformanceWindow::new(performance_gpu.clone()));
+        
+        // Add Script Console window
+        window_types.register(windows::script_console::ScriptConsoleWindow::new);
+        
+        // Add Sweeps window, which runs parameter grid searches on worker threads
+        window_types.register(windows::sweeps::SweepsWindow::new);
+        
+        // Add Metrics window, which plots values published by running simulations
+        window_types.register(windows::dashboard::MetricsWindow::new);
         
         // Future windows go here
         
+        // Add the windows of plugins, which cannot replace the built-in ones
+        let mut created = window_types.take_registered();
+        let reserved: Vec<String> = created.iter().map(|window| window.name().to_string()).collect();
+        created.extend(plugins.create_windows(&PluginContext { egui_ctx, gpu }, &reserved));
+        
+        let mut windows: HashMap<String, Box<dyn Window>> = HashMap::new();
+        let mut window_open_states: HashMap<String, bool> = HashMap::new();
+        for window in created {
+            let name = window.name().to_string();
+            windows.insert(name.clone(), window);
+            window_open_states.insert(name, false); // Closed by default
+        }
+        
+        let settings = AppSettings::default();
+        settings.apply(egui_ctx);
+        
         Self {
             windows,
+            lifecycle_states: window_open_states.clone(),
             window_open_states,
+            window_types,
+            renaming: None,
             icon_texture,
+            settings,
+            shortcuts: ShortcutRegistry::default(),
+            show_about: false,
+            plugins,
+            show_plugins: false,
+            notifications: NotificationCenter::default(),
+            undo: UndoStack::default(),
+            session_path: "session.journal".to_string(),
+            session_player: None,
+        }
+    }
+    
+    /// Calls `on_open` and `on_close` for the windows opened or closed since the last update
+    /// Delivers the events emitted since the last frame to the open windows, except the
+    /// one that emitted each
+    fn dispatch_events(&mut self) {
+        for event in events::take_pending() {
+            for (title, window) in self.windows.iter_mut() {
+                let open = self.window_open_states.get(title).copied().unwrap_or(false);
+                if open && event.source.as_deref() != Some(title.as_str()) {
+                    notifications::with_source(title, || window.on_event(&event));
+                }
+            }
+        }
+    }
+
+    fn update_lifecycle(&mut self) {
+        for (name, &is_open) in &self.window_open_states {
+            let was_open = self.lifecycle_states.insert(name.clone(), is_open).unwrap_or(false);
+            if is_open != was_open {
+                if let Some(window) = self.windows.get_mut(name) {
+                    notifications::with_source(name, || {
+                        if is_open {
+                            window.on_open();
+                        } else {
+                            window.on_close();
+                        }
+                    });
+                }
+            }
         }
     }
+    
+    /// Starts recording a session journal. Every window's settings are recorded first,
+    /// then its seed is applied again so its random generator restarts from it.
+    fn start_recording(&mut self) {
+        session::start_recording();
+        for (name, window) in self.windows.iter_mut() {
+            let mut settings = window.session_settings();
+            // The seed goes last, so replaying the other settings cannot draw from the generator
+            settings.sort_by_key(|(key, _)| *key == "seed");
+            if let Some((_, seed)) = settings.iter().find(|(key, _)| *key == "seed") {
+                let action = SessionAction::set("seed", seed);
+                notifications::with_source(name, || window.replay(&action));
+            }
+            session::record_settings(name, settings);
+        }
+        report_info(format!("Recording session to {}", self.session_path));
+    }
+    
+    fn stop_recording(&mut self) {
+        let Some(journal) = session::stop_recording() else {
+            return;
+        };
+        match journal.save(&self.session_path) {
+            Ok(()) => report_info(format!("Saved {} actions to {}", journal.entries.len(), self.session_path)),
+            Err(e) => report_error(format!("Failed to save session to '{}': {}", self.session_path, e)),
+        }
+    }
+    
+    fn start_replay(&mut self, ctx: &egui::Context) {
+        match Journal::load(&self.session_path) {
+            Ok(journal) => self.session_player = Some(SessionPlayer::new(journal, ctx.input(|i| i.time))),
+            Err(e) => report_error(format!("Failed to load session from '{}': {}", self.session_path, e)),
+        }
+    }
+    
+    /// Sends the journal entries that are due to their windows, opening them. An entry
+    /// waits while its window is busy with a background job.
+    fn replay_session(&mut self, ctx: &egui::Context) {
+        let Some(player) = &mut self.session_player else {
+            return;
+        };
+        let now = ctx.input(|i| i.time);
+        while let Some(entry) = player.due(now).cloned() {
+            let performed = match self.windows.get_mut(&entry.window) {
+                Some(window) if window.is_busy() => break,
+                Some(window) => {
+                    self.window_open_states.insert(entry.window.clone(), true);
+                    notifications::with_source(&entry.window, || window.replay(&entry.action))
+                },
+                None => false,
+            };
+            player.advance(performed);
+        }
+        if player.is_finished() {
+            match player.skipped() {
+                0 => report_info("Session replay finished"),
+                skipped => report_info(format!("Session replay finished, {} actions were not supported", skipped)),
+            }
+            self.session_player = None;
+        } else {
+            ctx.request_repaint();
+        }
+    }
+    
+    /// The open window drawn on top of the others, which receives keyboard shortcuts
+    fn focused_window(&self, ctx: &egui::Context) -> Option<String> {
+        // egui::Window uses its title as the id of its layer
+        let top = ctx.memory(|memory| memory.areas().top_layer_id(egui::Order::Middle))?;
+        self.window_open_states
+            .iter()
+            .find(|(name, &is_open)| is_open && egui::Id::new(name.as_str()) == top.id)
+            .map(|(name, _)| name.clone())
+    }
+    
+    fn apply_instance_action(&mut self, action: InstanceAction) {
+        let windows = &self.windows;
+        let taken = |title: &str| windows.contains_key(title);
+        let created = match action {
+            InstanceAction::Spawn(type_name) => self.window_types.spawn(&type_name, taken),
+            InstanceAction::Duplicate(source) => match windows.get(&source) {
+                Some(window) => self.window_types.duplicate(&source, window.as_ref(), taken),
+                None => None,
+            },
+            InstanceAction::Rename(title) => {
+                self.renaming = Some((title.clone(), title));
+                None
+            },
+            InstanceAction::Remove(title) => {
+                self.remove_instance(&title);
+                None
+            },
+        };
+        if let Some((title, window)) = created {
+            self.windows.insert(title.clone(), window);
+            self.window_open_states.insert(title, true);
+        }
+    }
+    
+    /// Closes and drops the window instance titled `title`
+    fn remove_instance(&mut self, title: &str) {
+        if self.lifecycle_states.remove(title).unwrap_or(false) {
+            if let Some(window) = self.windows.get_mut(title) {
+                notifications::with_source(title, || window.on_close());
+            }
+        }
+        self.windows.remove(title);
+        self.window_open_states.remove(title);
+        self.window_types.remove(title);
+        self.undo.remove_window(title);
+    }
+    
+    /// Restores the snapshot taken before the most recent destructive operation
+    fn undo_last(&mut self) {
+        let Some((title, label)) = self.undo.peek() else {
+            return;
+        };
+        if self.windows.get(title).is_some_and(|window| window.is_busy()) {
+            // Kept for when the job is done
+            report_error(format!("Cannot undo {} while {} is busy", label, title));
+            return;
+        }
+        let Some(entry) = self.undo.pop() else {
+            return;
+        };
+        let Some(window) = self.windows.get_mut(&entry.window) else {
+            return;
+        };
+        let label = entry.label;
+        let restored = notifications::with_source(&entry.window, || window.restore(entry.snapshot));
+        if restored {
+            report_info(format!("Undid {} in {}", label, entry.window));
+        } else {
+            report_error(format!("{} could not undo {}", entry.window, label));
+        }
+    }
+
+    /// Moves the window titled `old` to the title `new`
+    fn rename_instance(&mut self, old: &str, new: &str) {
+        if let Some(window) = self.windows.remove(old) {
+            self.windows.insert(new.to_string(), window);
+        }
+        for states in [&mut self.window_open_states, &mut self.lifecycle_states] {
+            if let Some(state) = states.remove(old) {
+                states.insert(new.to_string(), state);
+            }
+        }
+        self.window_types.rename(old, new);
+        self.undo.rename_window(old, new);
+    }
+    
+    /// Draws the dialog for the title of the window being renamed
+    fn show_rename_dialog(&mut self, ctx: &egui::Context) {
+        let Some((old, new)) = &mut self.renaming else {
+            return;
+        };
+        let title = new.trim().to_string();
+        let taken = title != *old && self.windows.contains_key(&title);
+        let valid = !title.is_empty() && !taken;
+        let mut finished = false;
+        let mut accepted = false;
+        egui::Window::new("Rename Window")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("New title for {}:", old));
+                let response = ui.text_edit_singleline(new);
+                let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if taken {
+                    ui.colored_label(egui::Color32::LIGHT_RED, "Another window has this title");
+                }
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(valid, egui::Button::new("Rename")).clicked() || (entered && valid) {
+                        accepted = true;
+                        finished = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        finished = true;
+                    }
+                });
+            });
+        if finished {
+            let (old, _) = self.renaming.take().expect("a window is being renamed");
+            if accepted && title != old {
+                self.rename_instance(&old, &title);
+            }
+        }
+    }
+    
+    /// Draws the plugins page, closing the windows of plugins that were disabled
+    fn show_plugins_dialog(&mut self, ctx: &egui::Context) {
+        let plugins = &mut self.plugins;
+        let mut changed = false;
+        egui::Window::new("Plugins")
+            .open(&mut self.show_plugins)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                changed = plugins.show(ui);
+            });
+        if changed {
+            for (name, is_open) in self.window_open_states.iter_mut() {
+                if self.plugins.is_hidden(name) {
+                    *is_open = false;
+                }
+            }
+        }
+    }
+    
+    /// Draws the About dialog: version, build and asset information, and the shortcuts
+    fn show_about_dialog(&mut self, ctx: &egui::Context) {
+        let shortcuts = &self.shortcuts;
+        let icon = &self.icon_texture;
+        egui::Window::new("About Raum")
+            .open(&mut self.show_about)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if let Some(icon) = icon {
+                        ui.add(egui::Image::new(icon).max_height(32.0));
+                    }
+                    ui.heading(format!("Raum {}", env!("CARGO_PKG_VERSION")));
+                });
+                ui.label("Interactive simulations of neural networks and discrete dynamical systems.");
+                ui.separator();
+                
+                egui::Grid::new("about_build_info").num_columns(2).show(ui, |ui| {
+                    ui.label("Build:");
+                    ui.label(if cfg!(debug_assertions) { "debug" } else { "release" });
+                    ui.end_row();
+                    ui.label("Target:");
+                    ui.label(format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS));
+                    ui.end_row();
+                    for asset in [&assets::FONT, &assets::ICON] {
+                        ui.label(format!("{}:", asset.name));
+                        match asset.resolve() {
+                            Some(path) => ui.label(path.display().to_string()),
+                            None => ui.label("embedded"),
+                        };
+                        ui.end_row();
+                    }
+                });
+                ui.separator();
+                
+                ui.label("Keyboard Shortcuts (sent to the front window):");
+                egui::Grid::new("about_shortcuts").num_columns(2).show(ui, |ui| {
+                    for shortcut in shortcuts.shortcuts() {
+                        ui.monospace(ctx.format_shortcut(&shortcut.keys));
+                        ui.label(shortcut.description);
+                        ui.end_row();
+                    }
+                    ui.monospace("F1");
+                    ui.label("About");
+                    ui.end_row();
+                });
+            });
+    }
 }
 
 impl eframe::App for RaumApp {
@@ -79,16 +637,64 @@ impl eframe::App for RaumApp {
                 }
 
                 ui.menu_button("File", |ui| {
+                    ui.label("Session Journal:");
+                    ui.text_edit_singleline(&mut self.session_path);
+                    let replaying = self.session_player.is_some();
+                    if session::is_recording() {
+                        if ui.button("Stop Recording").clicked() {
+                            self.stop_recording();
+                            ui.close_menu();
+                        }
+                    } else if ui.add_enabled(!replaying, egui::Button::new("Record Session"))
+                        .on_hover_text("Log training, runs, parameter changes and edits so the session can be replayed")
+                        .clicked()
+                    {
+                        self.start_recording();
+                        ui.close_menu();
+                    }
+                    if replaying {
+                        if ui.button("Stop Replay").clicked() {
+                            self.session_player = None;
+                            ui.close_menu();
+                        }
+                    } else if ui.add_enabled(!session::is_recording(), egui::Button::new("Replay Session")).clicked() {
+                        self.start_replay(ctx);
+                        ui.close_menu();
+                    }
+                    ui.separator();
                     if ui.button("Exit").clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
                     // Add other file options here if needed
                 });
+                ui.menu_button("Edit", |ui| {
+                    let text = match self.undo.peek() {
+                        Some((window, label)) => format!("Undo {} ({})", label, window),
+                        None => "Undo".to_string(),
+                    };
+                    if ui.add_enabled(!self.undo.is_empty(), egui::Button::new(text))
+                        .on_hover_text("Restore the model as it was before the last retraining, topology change or randomization")
+                        .clicked()
+                    {
+                        self.undo_last();
+                        ui.close_menu();
+                    }
+                });
                 ui.menu_button("Help", |ui| {
                     if ui.button("About").clicked() {
-                        // TODO: Implement About dialog
+                        self.show_about = true;
+                        ui.close_menu();
                     }
                 });
+                if ui.selectable_label(self.notifications.show_log, self.notifications.menu_label()).clicked() {
+                    self.notifications.show_log = !self.notifications.show_log;
+                }
+                if session::is_recording() {
+                    ui.colored_label(egui::Color32::RED, "● Recording");
+                } else if let Some(player) = &self.session_player {
+                    let (played, total) = player.progress();
+                    ui.label(format!("▶ Replaying {}/{}", played, total));
+                }
                 // Add icon space to the right if desired later
                 // ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 //     ui.label("ICON"); // Placeholder
@@ -96,48 +702,115 @@ impl eframe::App for RaumApp {
             });
         });
 
-        // --- Right Sidebar for Controls & Window Toggles ---
+        // --- Right Sidebar for Window Toggles & App Settings ---
         egui::SidePanel::right("config_sidebar")
             .resizable(true)
-            .default_width(250.0)
+            .default_width(200.0)
             .show(ctx, |ui| {
-                ui.heading("Configuration");
+                ui.heading("Windows");
                 ui.separator();
 
-                // Window Toggles
-                ui.label("Windows:");
-                let mut open_window_names = Vec::new(); // Collect names of windows that should be open
-                for (name, is_open) in &mut self.window_open_states {
-                    if ui.checkbox(is_open, name).clicked() {
-                        // Toggle state handled by checkbox binding
+                // Window Toggles (each window shows its own configuration)
+                let mut names: Vec<String> = self.window_open_states.keys().cloned().collect();
+                names.sort();
+                let mut action = None;
+                for name in names {
+                    if self.plugins.is_hidden(&name) {
+                        continue;
                     }
-                    if *is_open {
-                        open_window_names.push(name.clone());
+                    if let Some(is_open) = self.window_open_states.get_mut(&name) {
+                        let response = ui.checkbox(is_open, name.as_str());
+                        let Some(type_name) = self.window_types.type_of(&name) else {
+                            response.on_hover_text(format!("Show the {} window", name));
+                            continue;
+                        };
+                        let removable = self.window_types.is_removable(&name);
+                        response
+                            .on_hover_text(format!("Show the {} window. Right-click for more instances.", name))
+                            .context_menu(|ui| {
+                                if ui.button(format!("New {}", type_name)).clicked() {
+                                    action = Some(InstanceAction::Spawn(type_name.to_string()));
+                                    ui.close_menu();
+                                }
+                                if ui.button("Duplicate").on_hover_text("A new instance with the same settings").clicked() {
+                                    action = Some(InstanceAction::Duplicate(name.clone()));
+                                    ui.close_menu();
+                                }
+                                if ui.button("Rename...").clicked() {
+                                    action = Some(InstanceAction::Rename(name.clone()));
+                                    ui.close_menu();
+                                }
+                                if ui.add_enabled(removable, egui::Button::new("Remove"))
+                                    .on_disabled_hover_text("The last instance of a window is kept")
+                                    .clicked()
+                                {
+                                    action = Some(InstanceAction::Remove(name.clone()));
+                                    ui.close_menu();
+                                }
+                            });
                     }
                 }
+                if let Some(action) = action {
+                    self.apply_instance_action(action);
+                }
                 ui.separator();
                 
-                // Show config for open windows
-                if !open_window_names.is_empty() {
-                    ui.label("Settings:");
-                    // Use ScrollArea for potentially long configs
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        for name in open_window_names {
-                            if let Some(window) = self.windows.get_mut(&name) {
-                                ui.push_id(name.clone(), |ui| { // Ensure unique IDs for widgets (use cloned name for ID)
-                                    let window_name = window.name().to_string(); // Get name before borrow conflict
-                                    ui.collapsing(window_name.clone(), |ui| { // Use cloned name for header
-                                        window.show_config(ui);    
-                                    }).header_response.on_hover_text(format!("Configure {}", window_name)); // Use cloned name for hover
-                                });
-                                ui.separator(); // Add separator between window configs
+                // App Settings
+                ui.heading("Settings");
+                ui.separator();
+                let mut zoom = ctx.zoom_factor();
+                ui.horizontal(|ui| {
+                    ui.label("UI Scale:");
+                    if ui.add(egui::Slider::new(&mut zoom, 0.5..=2.0).step_by(0.05)).changed() {
+                        ctx.set_zoom_factor(zoom);
+                    }
+                });
+                ui.separator();
+                self.settings.show(ui);
+                ui.separator();
+                if ui.button("Plugins...").on_hover_text("Windows added by other crates and plugin libraries").clicked() {
+                    self.show_plugins = true;
+                }
+            });
+
+        // --- Keyboard Shortcuts ---
+        if ctx.input_mut(|input| input.consume_key(egui::Modifiers::NONE, egui::Key::F1)) {
+            self.show_about = !self.show_about;
+        }
+        let commands = self.shortcuts.pressed(ctx);
+        if !commands.is_empty() {
+            if let Some(name) = self.focused_window(ctx) {
+                if let Some(window) = self.windows.get_mut(&name) {
+                    notifications::with_source(&name, || {
+                        for command in commands {
+                            if window.handle_command(command) {
+                                session::record(&name, SessionAction::Command(command));
                             }
                         }
                     });
-                } else {
-                    ui.label("Select a window to configure.");
                 }
-            });
+            }
+        }
+        self.show_about_dialog(ctx);
+        self.show_plugins_dialog(ctx);
+        self.show_rename_dialog(ctx);
+        
+        // --- Session Replay ---
+        self.replay_session(ctx);
+        
+        // --- Window Lifecycle ---
+        self.update_lifecycle();
+        self.dispatch_events();
+        let dt = ctx.input(|i| i.stable_dt) as f64;
+        let mut animating = false;
+        for (name, window) in self.windows.iter_mut() {
+            if self.window_open_states.get(name).copied().unwrap_or(false) {
+                animating |= notifications::with_source(name, || window.tick(dt));
+            }
+        }
+        if animating {
+            ctx.request_repaint();
+        }
 
         // --- Individual Windows --- 
         // Iterate through windows and show the content for the open ones in separate egui windows.
@@ -146,19 +819,37 @@ impl eframe::App for RaumApp {
             if *is_open {
                 if let Some(window) = self.windows.get_mut(name) {
                     // Create an actual egui::Window for the content
-                    let window_name = window.name().to_string(); // Get name for title
+                    let window_name = name.clone(); // Instances of a window type differ by title
                     egui::Window::new(&window_name)
                         .open(is_open) // Bind the window's open state to our map
                         .resizable(true)
-                        .default_width(400.0) // Give a default size
+                        .default_size([900.0, 600.0]) // Room for the config panel and the content
                         .show(ctx, |ui| {
-                            window.show_content(ctx, ui); // Call the content drawing function
+                            // Config panel on the left, content beside it
+                            notifications::with_source(&window_name, || window.show(ctx, ui));
                         });
                 }
             }
         }
         // Update the original map with potentially changed states (from window closing)
         self.window_open_states = open_window_states;
+        
+        // --- Settings changed this frame, for the session journal ---
+        if session::is_recording() {
+            for (name, window) in &self.windows {
+                session::record_settings(name, window.session_settings());
+            }
+        }
+        
+        // --- Notifications and undo snapshots recorded this frame ---
+        self.undo.collect();
+        self.notifications.collect(ctx);
+        self.notifications.show(ctx);
+
+        // --- Events emitted this frame are delivered at the start of the next ---
+        if events::has_pending() {
+            ctx.request_repaint();
+        }
 
         // Optional: Add a central panel back if you want something when *no* windows are open
         // egui::CentralPanel::default().show(ctx, |ui| {
@@ -0,0 +1,80 @@
+use std::sync::Mutex;
+
+use crate::ui::notifications;
+
+/// Something that happened in a window that other windows may want to react to
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventKind {
+    /// A model finished training on `patterns` patterns of `neurons` units
+    TrainingFinished { patterns: usize, neurons: usize },
+    /// A simulation run ended after `iterations` iterations; `converged` is false if it
+    /// was cancelled or gave up
+    RunCompleted { iterations: usize, converged: bool },
+    /// The training patterns were edited (selected, augmented or orthogonalized); `patterns`
+    /// are left
+    PatternsEdited { patterns: usize },
+}
+
+impl EventKind {
+    /// Short description for logs and plot markers
+    pub fn label(&self) -> String {
+        match self {
+            EventKind::TrainingFinished { patterns, .. } => format!("Trained on {} patterns", patterns),
+            EventKind::RunCompleted { iterations, converged: true } => format!("Converged in {} iterations", iterations),
+            EventKind::RunCompleted { iterations, converged: false } => format!("Stopped after {} iterations", iterations),
+            EventKind::PatternsEdited { patterns } => format!("Patterns edited, {} left", patterns),
+        }
+    }
+}
+
+/// An event with the window it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppEvent {
+    /// Title of the emitting window, or None outside of window code
+    pub source: Option<String>,
+    pub kind: EventKind,
+}
+
+/// Events emitted since the application last dispatched them. Like notifications, a
+/// plain queue lets windows emit without knowing about each other or the app.
+static PENDING: Mutex<Vec<AppEvent>> = Mutex::new(Vec::new());
+
+fn pending() -> std::sync::MutexGuard<'static, Vec<AppEvent>> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Queues `kind` for the open windows, attributed to the window whose code is running
+/// (see `notifications::with_source`). The application delivers it through
+/// `Window::on_event` at the start of the next frame.
+pub fn emit(kind: EventKind) {
+    let source = notifications::current_source();
+    pending().push(AppEvent { source, kind });
+}
+
+/// Whether events are waiting to be dispatched
+pub fn has_pending() -> bool {
+    !pending().is_empty()
+}
+
+/// Removes and returns the queued events, oldest first
+pub fn take_pending() -> Vec<AppEvent> {
+    std::mem::take(&mut *pending())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emitted_events_are_attributed_and_taken_once() {
+        notifications::with_source("Events Test", || emit(EventKind::PatternsEdited { patterns: 2 }));
+        let events = take_pending();
+        let event = events
+            .iter()
+            .find(|event| event.source.as_deref() == Some("Events Test"))
+            .expect("the event was queued");
+        assert_eq!(event.kind, EventKind::PatternsEdited { patterns: 2 });
+        assert_eq!(event.kind.label(), "Patterns edited, 2 left");
+        assert!(!take_pending().iter().any(|event| event.source.as_deref() == Some("Events Test")));
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use crate::ui::notifications;
+use crate::ui::session::SessionAction;
+use crate::ui::windows::Window;
+
+/// Creates a window of one type
+type WindowFactory = Box<dyn Fn() -> Box<dyn Window>>;
+
+/// Title for another instance of the `base` window: "`base` #2", or the next number
+/// not yet `taken`
+pub fn instance_title(base: &str, taken: impl Fn(&str) -> bool) -> String {
+    (2..)
+        .map(|number| format!("{} #{}", base, number))
+        .find(|title| !taken(title))
+        .expect("instance numbers are unbounded")
+}
+
+/// Applies the session settings of `source` to `target`, the seed last so the other
+/// settings cannot draw from the generator it restarts.
+///
+/// # Returns
+///
+/// The number of settings `target` applied
+pub fn copy_settings(source: &dyn Window, target: &mut dyn Window) -> usize {
+    let mut settings = source.session_settings();
+    settings.sort_by_key(|(key, _)| *key == "seed");
+    settings
+        .into_iter()
+        .filter(|(key, value)| target.replay(&SessionAction::set(key, value)))
+        .count()
+}
+
+/// The window types the application can open more instances of, and the type of every
+/// open instance by its title
+#[derive(Default)]
+pub struct WindowTypes {
+    factories: Vec<(String, WindowFactory)>,
+    instances: HashMap<String, String>,
+    /// First instances of the types registered since they were last taken
+    registered: Vec<Box<dyn Window>>,
+}
+
+impl WindowTypes {
+    /// Adds a window type, named after the window `factory` creates. Its first instance,
+    /// titled with the type name, is returned by `take_registered`.
+    pub fn register<W: Window + 'static>(&mut self, factory: impl Fn() -> W + 'static) {
+        let window = factory();
+        let name = window.name().to_string();
+        self.instances.insert(name.clone(), name.clone());
+        self.factories.push((name, Box::new(move || Box::new(factory()))));
+        self.registered.push(Box::new(window));
+    }
+
+    /// The first instances of the types registered since this was last called
+    pub fn take_registered(&mut self) -> Vec<Box<dyn Window>> {
+        std::mem::take(&mut self.registered)
+    }
+
+    /// Names of the window types, in the order they were registered
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Type of the instance titled `title`, if it was created from a registered type
+    pub fn type_of(&self, title: &str) -> Option<&str> {
+        self.instances.get(title).map(String::as_str)
+    }
+
+    /// Creates a new instance of `type_name`, titled so it is not `taken`
+    pub fn spawn(&mut self, type_name: &str, taken: impl Fn(&str) -> bool) -> Option<(String, Box<dyn Window>)> {
+        let (name, factory) = self.factories.iter().find(|(name, _)| name == type_name)?;
+        let title = instance_title(name, taken);
+        let window = factory();
+        self.instances.insert(title.clone(), name.clone());
+        Some((title, window))
+    }
+
+    /// Creates a new instance of the type of `source`, titled `source_title`, with its
+    /// settings. The model itself is not copied; it is built again from the settings.
+    pub fn duplicate(
+        &mut self,
+        source_title: &str,
+        source: &dyn Window,
+        taken: impl Fn(&str) -> bool,
+    ) -> Option<(String, Box<dyn Window>)> {
+        let type_name = self.type_of(source_title)?.to_string();
+        let (title, mut window) = self.spawn(&type_name, taken)?;
+        notifications::with_source(&title, || copy_settings(source, window.as_mut()));
+        Some((title, window))
+    }
+
+    pub fn rename(&mut self, old: &str, new: &str) {
+        if let Some(type_name) = self.instances.remove(old) {
+            self.instances.insert(new.to_string(), type_name);
+        }
+    }
+
+    /// Whether the instance titled `title` can be removed: the last instance of a type
+    /// is kept, so it can always be opened again
+    pub fn is_removable(&self, title: &str) -> bool {
+        self.type_of(title).is_some_and(|type_name| {
+            self.instances.values().filter(|other| *other == type_name).count() > 1
+        })
+    }
+
+    pub fn remove(&mut self, title: &str) {
+        self.instances.remove(title);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::egui;
+
+    #[derive(Default)]
+    struct CounterWindow {
+        steps: usize,
+    }
+
+    impl Window for CounterWindow {
+        fn show_content(&mut self, _ctx: &egui::Context, _ui: &mut egui::Ui) {}
+        fn show_config(&mut self, _ui: &mut egui::Ui) {}
+        fn name(&self) -> &str {
+            "Counter"
+        }
+
+        fn session_settings(&self) -> Vec<(&'static str, String)> {
+            vec![("steps", self.steps.to_string())]
+        }
+
+        fn replay(&mut self, action: &SessionAction) -> bool {
+            match action {
+                SessionAction::Set { key, value } if key == "steps" => value.parse().map(|steps| self.steps = steps).is_ok(),
+                _ => false,
+            }
+        }
+    }
+
+    #[test]
+    fn test_instance_title_skips_taken_numbers() {
+        let taken = ["Counter #2", "Counter #3"];
+        assert_eq!(instance_title("Counter", |title| taken.contains(&title)), "Counter #4");
+        assert_eq!(instance_title("Counter", |_| false), "Counter #2");
+    }
+
+    #[test]
+    fn test_duplicate_copies_settings() {
+        let mut types = WindowTypes::default();
+        types.register(CounterWindow::default);
+        let first = types.take_registered().pop().unwrap();
+        assert_eq!(types.names().collect::<Vec<_>>(), vec!["Counter"]);
+        assert!(!types.is_removable("Counter"));
+
+        let source = CounterWindow { steps: 7 };
+        let (title, copy) = types.duplicate("Counter", &source, |title| title == first.name()).unwrap();
+        assert_eq!(title, "Counter #2");
+        assert_eq!(copy.session_settings(), vec![("steps", "7".to_string())]);
+
+        types.rename("Counter #2", "Copy");
+        assert_eq!(types.type_of("Copy"), Some("Counter"));
+        assert!(types.is_removable("Copy"));
+        types.remove("Copy");
+        assert!(!types.is_removable("Counter"));
+    }
+}
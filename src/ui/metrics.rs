@@ -0,0 +1,102 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::ui::notifications;
+
+/// Quantities that model windows publish as their simulations run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Metric {
+    Energy,
+    Magnetization,
+    ActiveVertices,
+    TotalChips,
+    FramesPerSecond,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 5] = [
+        Metric::Energy,
+        Metric::Magnetization,
+        Metric::ActiveVertices,
+        Metric::TotalChips,
+        Metric::FramesPerSecond,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Energy => "Energy",
+            Metric::Magnetization => "Magnetization",
+            Metric::ActiveVertices => "Active Vertices",
+            Metric::TotalChips => "Total Chips",
+            Metric::FramesPerSecond => "Frames per Second",
+        }
+    }
+}
+
+/// One published value, with the window it came from
+#[derive(Debug, Clone)]
+pub struct MetricSample {
+    pub source: String,
+    pub metric: Metric,
+    pub value: f64,
+    pub time: Instant,
+}
+
+/// Channels of the subscribers; a subscriber that dropped its receiver is removed at
+/// the next publication
+static SUBSCRIBERS: Mutex<Vec<Sender<MetricSample>>> = Mutex::new(Vec::new());
+
+fn subscribers() -> std::sync::MutexGuard<'static, Vec<Sender<MetricSample>>> {
+    SUBSCRIBERS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Receives every sample published from now on, until the receiver is dropped
+pub fn subscribe() -> Receiver<MetricSample> {
+    let (sender, receiver) = mpsc::channel();
+    subscribers().push(sender);
+    receiver
+}
+
+/// Whether anyone listens, so that windows can skip metrics that are costly to compute
+pub fn has_subscribers() -> bool {
+    !subscribers().is_empty()
+}
+
+/// Sends `value` to every subscriber, attributed to the window whose code is running
+/// (see `notifications::with_source`). Without subscribers this does nothing.
+pub fn publish(metric: Metric, value: f64) {
+    let mut subscribers = subscribers();
+    if subscribers.is_empty() {
+        return;
+    }
+    let sample = MetricSample {
+        source: notifications::current_source().unwrap_or_else(|| "Application".to_string()),
+        metric,
+        value,
+        time: Instant::now(),
+    };
+    subscribers.retain(|sender| sender.send(sample.clone()).is_ok());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribers_receive_attributed_samples() {
+        let receiver = subscribe();
+        notifications::with_source("Metrics Test", || publish(Metric::TotalChips, 12.0));
+        let sample = receiver
+            .try_iter()
+            .find(|sample| sample.source == "Metrics Test")
+            .expect("the sample was delivered");
+        assert_eq!((sample.metric, sample.value), (Metric::TotalChips, 12.0));
+
+        // A dropped receiver no longer counts as a subscriber
+        let count = subscribers().len();
+        drop(receiver);
+        publish(Metric::Energy, 0.0);
+        assert!(subscribers().len() < count);
+    }
+}
@@ -1,4 +1,12 @@
 pub mod app;
+pub mod events;
+pub mod instances;
+pub mod metrics;
+pub mod notifications;
+pub mod plugins;
+pub mod session;
+pub mod shortcuts;
+pub mod undo;
 pub mod windows;
 pub mod widgets;
 
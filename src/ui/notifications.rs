@@ -0,0 +1,215 @@
+use eframe::egui;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::error::RaumError;
+
+/// Seconds an information toast stays on screen
+const INFO_SECONDS: f64 = 4.0;
+
+/// Seconds an error toast stays on screen; errors also stay in the log
+const ERROR_SECONDS: f64 = 10.0;
+
+/// Most toasts shown at once; older ones are only in the log
+const MAX_TOASTS: usize = 5;
+
+/// Notifications kept in the log
+const LOG_CAPACITY: usize = 200;
+
+/// Notifications reported since the application last collected them. A plain queue
+/// lets windows, their background jobs and the scripting engine report without
+/// access to the app or the egui context.
+static PENDING: Mutex<Vec<Notification>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Window whose code is currently running on this thread, see `with_source`
+    static SOURCE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Error,
+}
+
+/// A message for the user, with the window it came from
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub severity: Severity,
+    pub source: Option<String>,
+    pub message: String,
+}
+
+/// Window whose code is currently running on this thread, if any
+pub fn current_source() -> Option<String> {
+    SOURCE.with(|source| source.borrow().clone())
+}
+
+fn report(severity: Severity, message: String) {
+    let source = current_source();
+    let notification = Notification { severity, source, message };
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).push(notification);
+}
+
+/// Show `error` as a toast and add it to the notification log
+pub fn report_error(error: impl Into<RaumError>) {
+    report(Severity::Error, error.into().to_string());
+}
+
+/// Show `message` as a short-lived toast
+pub fn report_info(message: impl Into<String>) {
+    report(Severity::Info, message.into());
+}
+
+/// Runs `f` with notifications reported on this thread attributed to `source`
+pub fn with_source<R>(source: &str, f: impl FnOnce() -> R) -> R {
+    let previous = SOURCE.with(|current| current.replace(Some(source.to_string())));
+    let result = f();
+    SOURCE.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// A notification on screen until `expires` (in egui input time)
+struct Toast {
+    notification: Notification,
+    expires: f64,
+}
+
+/// Toasts and the log of past notifications, owned by the application
+#[derive(Default)]
+pub struct NotificationCenter {
+    toasts: Vec<Toast>,
+    log: VecDeque<Notification>,
+
+    /// Errors reported since the log was last opened
+    unread_errors: usize,
+
+    /// Whether the log window is shown
+    pub show_log: bool,
+}
+
+impl NotificationCenter {
+    /// Takes the notifications reported since the last frame
+    pub fn collect(&mut self, ctx: &egui::Context) {
+        let pending = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+        let now = ctx.input(|input| input.time);
+        for notification in pending {
+            let seconds = match notification.severity {
+                Severity::Info => INFO_SECONDS,
+                Severity::Error => {
+                    self.unread_errors += 1;
+                    eprintln!("{}", notification.message);
+                    ERROR_SECONDS
+                },
+            };
+            self.log.push_back(notification.clone());
+            if self.log.len() > LOG_CAPACITY {
+                self.log.pop_front();
+            }
+            self.toasts.push(Toast { notification, expires: now + seconds });
+        }
+        self.toasts.retain(|toast| toast.expires > now);
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.drain(..self.toasts.len() - MAX_TOASTS);
+        }
+    }
+
+    /// Notifications in the log, oldest first
+    pub fn log(&self) -> impl Iterator<Item = &Notification> {
+        self.log.iter()
+    }
+
+    /// Label of the menu bar button that opens the log
+    pub fn menu_label(&self) -> String {
+        match self.unread_errors {
+            0 => "Notifications".to_string(),
+            n => format!("Notifications ({})", n),
+        }
+    }
+
+    /// Draws the toasts in the bottom right corner and the log window if it is open
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if let Some(expires) = self.toasts.iter().map(|toast| toast.expires).reduce(f64::min) {
+            let remaining = expires - ctx.input(|input| input.time);
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(remaining.max(0.0)));
+        }
+
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("raum_toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, toast) in self.toasts.iter().enumerate() {
+                    let color = severity_color(toast.notification.severity);
+                    egui::Frame::popup(ui.style()).stroke(egui::Stroke::new(1.0, color)).show(ui, |ui| {
+                        ui.set_max_width(320.0);
+                        ui.horizontal(|ui| {
+                            if let Some(source) = &toast.notification.source {
+                                ui.strong(source);
+                            }
+                            if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                                dismissed = Some(i);
+                            }
+                        });
+                        ui.colored_label(color, &toast.notification.message);
+                    });
+                }
+            });
+        if let Some(i) = dismissed {
+            self.toasts.remove(i);
+        }
+
+        if self.show_log {
+            self.unread_errors = 0;
+            let log = &mut self.log;
+            egui::Window::new("Notifications")
+                .open(&mut self.show_log)
+                .default_size([420.0, 300.0])
+                .show(ctx, |ui| {
+                    if ui.button("Clear").clicked() {
+                        log.clear();
+                    }
+                    ui.separator();
+                    egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                        if log.is_empty() {
+                            ui.label("No notifications.");
+                        }
+                        for notification in log.iter() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.strong(notification.source.as_deref().unwrap_or("Raum"));
+                                ui.colored_label(severity_color(notification.severity), &notification.message);
+                            });
+                        }
+                    });
+                });
+        }
+    }
+}
+
+fn severity_color(severity: Severity) -> egui::Color32 {
+    match severity {
+        Severity::Info => egui::Color32::LIGHT_BLUE,
+        Severity::Error => egui::Color32::from_rgb(255, 90, 90),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_are_collected_with_their_source() {
+        with_source("Hopfield Network", || report_error("Select a target pattern first"));
+        report_info("Saved");
+
+        let mut center = NotificationCenter::default();
+        center.collect(&egui::Context::default());
+        let log: Vec<&Notification> = center.log().collect();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].source.as_deref(), Some("Hopfield Network"));
+        assert_eq!(log[0].severity, Severity::Error);
+        assert_eq!(log[1].source, None);
+        assert_eq!(center.menu_label(), "Notifications (1)");
+    }
+}
@@ -0,0 +1,299 @@
+use eframe::egui;
+use std::path::PathBuf;
+
+use crate::graphics::compute::GpuContext;
+use crate::ui::notifications::report_error;
+use crate::ui::windows::Window;
+
+/// Version of Raum that plugins are built against. Libraries built for another version
+/// are refused, since the `Window` trait may have changed.
+pub const RAUM_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// What the application shares with plugins when their windows are created
+pub struct PluginContext<'a> {
+    pub egui_ctx: &'a egui::Context,
+    /// The renderer's device, when it uses wgpu
+    pub gpu: Option<GpuContext>,
+}
+
+/// A set of windows contributed to the application by another crate.
+///
+/// Binaries that link the plugin register it before starting the app:
+/// `RaumApp::with_plugins(cc, registry)`. With the "dylib-plugins" feature, a cdylib can
+/// export one with `raum::declare_plugin!` and is loaded from the plugin directory; it
+/// has to be built with the same compiler and Raum version as the application.
+pub trait WindowPlugin {
+    /// Name shown on the plugins page
+    fn name(&self) -> &str;
+
+    fn version(&self) -> &str {
+        ""
+    }
+
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// Creates the plugin's windows. Called once, while the application starts.
+    fn create_windows(&self, ctx: &PluginContext) -> Vec<Box<dyn Window>>;
+}
+
+/// Exports a plugin from a dynamic library, so that the application can load it from
+/// its plugin directory. `$constructor` returns the `WindowPlugin`.
+#[macro_export]
+macro_rules! declare_plugin {
+    ($constructor:expr) => {
+        #[no_mangle]
+        pub static _RAUM_PLUGIN_VERSION: &str = $crate::ui::plugins::RAUM_VERSION;
+
+        #[no_mangle]
+        pub fn _raum_plugin_create() -> Box<dyn $crate::ui::plugins::WindowPlugin> {
+            Box::new($constructor())
+        }
+    };
+}
+
+/// Where a plugin came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginSource {
+    /// Registered by the binary
+    Linked,
+    /// Loaded from a dynamic library
+    Library(PathBuf),
+}
+
+/// A registered plugin and the windows it contributed
+pub struct PluginEntry {
+    pub plugin: Box<dyn WindowPlugin>,
+    pub source: PluginSource,
+    /// Disabled plugins keep their windows, but they are closed and hidden
+    pub enabled: bool,
+    /// Names of the windows created by the plugin
+    pub windows: Vec<String>,
+}
+
+/// Failure to load a plugin library
+#[cfg(feature = "dylib-plugins")]
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    #[error(transparent)]
+    Library(#[from] libloading::Error),
+    #[error("built for Raum {found}, expected {expected}")]
+    Version { expected: String, found: String },
+}
+
+/// Plugins contributing windows to the application, and the libraries that failed to load
+#[derive(Default)]
+pub struct PluginRegistry {
+    entries: Vec<PluginEntry>,
+    /// Directory scanned for plugin libraries, if any
+    directory: Option<PathBuf>,
+    failures: Vec<(PathBuf, String)>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a plugin linked into the binary
+    pub fn register(&mut self, plugin: impl WindowPlugin + 'static) {
+        self.add(Box::new(plugin), PluginSource::Linked);
+    }
+
+    fn add(&mut self, plugin: Box<dyn WindowPlugin>, source: PluginSource) {
+        self.entries.push(PluginEntry { plugin, source, enabled: true, windows: Vec::new() });
+    }
+
+    pub fn entries(&self) -> &[PluginEntry] {
+        &self.entries
+    }
+
+    /// Loads every library with the platform's extension in `dir`. Libraries that fail
+    /// to load are listed on the plugins page.
+    ///
+    /// # Returns
+    ///
+    /// The number of plugins loaded
+    #[cfg(feature = "dylib-plugins")]
+    pub fn load_directory(&mut self, dir: impl Into<PathBuf>) -> usize {
+        let dir = dir.into();
+        let mut paths: Vec<PathBuf> = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION))
+                .collect(),
+            // A missing plugin directory just means there are no plugins
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+        self.directory = Some(dir);
+
+        let mut loaded = 0;
+        for path in paths {
+            // SAFETY: the library runs its initializers and exports the plugin symbols
+            // declared by `declare_plugin!`; both sides are checked to be the same version
+            match unsafe { Self::load_library(&path) } {
+                Ok(plugin) => {
+                    self.add(plugin, PluginSource::Library(path));
+                    loaded += 1;
+                },
+                Err(e) => self.failures.push((path, e.to_string())),
+            }
+        }
+        loaded
+    }
+
+    /// # Safety
+    ///
+    /// `path` has to be a library exporting a plugin with `declare_plugin!`, built with
+    /// the same compiler as the application
+    #[cfg(feature = "dylib-plugins")]
+    unsafe fn load_library(path: &std::path::Path) -> Result<Box<dyn WindowPlugin>, PluginError> {
+        let library = libloading::Library::new(path)?;
+        let found: &str = **library.get::<*const &str>(b"_RAUM_PLUGIN_VERSION")?;
+        if found != RAUM_VERSION {
+            return Err(PluginError::Version { expected: RAUM_VERSION.to_string(), found: found.to_string() });
+        }
+        let create = library.get::<fn() -> Box<dyn WindowPlugin>>(b"_raum_plugin_create")?;
+        let plugin = create();
+        // The windows and their vtables live in the library, so it stays loaded for the
+        // life of the process
+        std::mem::forget(library);
+        Ok(plugin)
+    }
+
+    /// Creates the windows of every plugin. A window whose name is in `reserved` or was
+    /// taken by an earlier plugin is dropped and reported.
+    pub fn create_windows(&mut self, ctx: &PluginContext, reserved: &[String]) -> Vec<Box<dyn Window>> {
+        let mut taken = reserved.to_vec();
+        let mut windows = Vec::new();
+        for entry in &mut self.entries {
+            for window in entry.plugin.create_windows(ctx) {
+                let name = window.name().to_string();
+                if taken.contains(&name) {
+                    report_error(format!("Plugin '{}' adds a window named '{}', which already exists", entry.plugin.name(), name));
+                    continue;
+                }
+                taken.push(name.clone());
+                entry.windows.push(name);
+                windows.push(window);
+            }
+        }
+        windows
+    }
+
+    /// Whether `window` belongs to a disabled plugin
+    pub fn is_hidden(&self, window: &str) -> bool {
+        self.entries.iter().any(|entry| !entry.enabled && entry.windows.iter().any(|name| name == window))
+    }
+
+    /// Draws the plugins page: every plugin with its source and windows, and a switch to
+    /// enable it.
+    ///
+    /// # Returns
+    ///
+    /// true if a plugin was enabled or disabled
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        match &self.directory {
+            Some(dir) => ui.label(format!("Plugin directory: {}", dir.display())),
+            None if cfg!(feature = "dylib-plugins") => ui.label("No plugin directory was scanned."),
+            None => ui.label("Loading plugin libraries requires the \"dylib-plugins\" feature."),
+        };
+        ui.separator();
+
+        if self.entries.is_empty() {
+            ui.label("No plugins are registered.");
+        } else {
+            egui::Grid::new("plugin_list").num_columns(4).striped(true).show(ui, |ui| {
+                ui.strong("Enabled");
+                ui.strong("Plugin");
+                ui.strong("Source");
+                ui.strong("Windows");
+                ui.end_row();
+                for entry in &mut self.entries {
+                    changed |= ui.checkbox(&mut entry.enabled, "").changed();
+                    let name = match entry.plugin.version() {
+                        "" => entry.plugin.name().to_string(),
+                        version => format!("{} {}", entry.plugin.name(), version),
+                    };
+                    let label = ui.label(name);
+                    if !entry.plugin.description().is_empty() {
+                        label.on_hover_text(entry.plugin.description());
+                    }
+                    match &entry.source {
+                        PluginSource::Linked => ui.label("linked"),
+                        PluginSource::Library(path) => ui.label(path.file_name().unwrap_or_default().to_string_lossy()),
+                    };
+                    ui.label(entry.windows.join(", "));
+                    ui.end_row();
+                }
+            });
+        }
+
+        if !self.failures.is_empty() {
+            ui.separator();
+            ui.label("Failed to load:");
+            for (path, error) in &self.failures {
+                ui.colored_label(egui::Color32::LIGHT_RED, format!("{}: {}", path.display(), error));
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NamedWindow(&'static str);
+
+    impl Window for NamedWindow {
+        fn show_content(&mut self, _ctx: &egui::Context, _ui: &mut egui::Ui) {}
+        fn show_config(&mut self, _ui: &mut egui::Ui) {}
+        fn name(&self) -> &str {
+            self.0
+        }
+    }
+
+    struct TestPlugin(Vec<&'static str>);
+
+    impl WindowPlugin for TestPlugin {
+        fn name(&self) -> &str {
+            "Test"
+        }
+
+        fn create_windows(&self, _ctx: &PluginContext) -> Vec<Box<dyn Window>> {
+            self.0.iter().map(|&name| Box::new(NamedWindow(name)) as Box<dyn Window>).collect()
+        }
+    }
+
+    #[test]
+    fn test_create_windows_skips_taken_names() {
+        let mut registry = PluginRegistry::new();
+        registry.register(TestPlugin(vec!["Extra", "Hopfield Network"]));
+        registry.register(TestPlugin(vec!["Extra", "Other"]));
+        let egui_ctx = egui::Context::default();
+        let ctx = PluginContext { egui_ctx: &egui_ctx, gpu: None };
+
+        let windows = registry.create_windows(&ctx, &["Hopfield Network".to_string()]);
+        let names: Vec<&str> = windows.iter().map(|window| window.name()).collect();
+        assert_eq!(names, vec!["Extra", "Other"]);
+        assert_eq!(registry.entries()[0].windows, vec!["Extra"]);
+        assert_eq!(registry.entries()[1].windows, vec!["Other"]);
+    }
+
+    #[test]
+    fn test_disabled_plugin_hides_its_windows() {
+        let mut registry = PluginRegistry::new();
+        registry.register(TestPlugin(vec!["Extra"]));
+        let egui_ctx = egui::Context::default();
+        registry.create_windows(&PluginContext { egui_ctx: &egui_ctx, gpu: None }, &[]);
+
+        assert!(!registry.is_hidden("Extra"));
+        registry.entries[0].enabled = false;
+        assert!(registry.is_hidden("Extra"));
+        assert!(!registry.is_hidden("Hopfield Network"));
+    }
+}
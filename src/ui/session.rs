@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::ui::notifications;
+use crate::ui::windows::Command;
+
+/// First line of a journal file
+const HEADER: &str = "# Raum session journal";
+
+/// Error types for session journals
+#[derive(Debug)]
+pub enum JournalError {
+    Io(io::Error),
+    /// A line that is not a journal entry, numbered from 1
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::Io(e) => write!(f, "I/O error: {}", e),
+            JournalError::Parse { line, message } => write!(f, "Line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for JournalError {}
+
+impl From<io::Error> for JournalError {
+    fn from(e: io::Error) -> Self {
+        JournalError::Io(e)
+    }
+}
+
+/// Something a window did to its model
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionAction {
+    /// A command, from a shortcut or the matching button
+    Command(Command),
+    /// A setting took a new value
+    Set { key: String, value: String },
+    /// A window-specific action, e.g. an edit of the model, with an optional argument
+    Perform { name: String, argument: String },
+}
+
+impl SessionAction {
+    pub fn set(key: &str, value: impl ToString) -> Self {
+        SessionAction::Set { key: key.to_string(), value: value.to_string() }
+    }
+
+    pub fn perform(name: &str, argument: impl ToString) -> Self {
+        SessionAction::Perform { name: name.to_string(), argument: argument.to_string() }
+    }
+}
+
+/// An action with the window it was sent to
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    /// Seconds since the recording started
+    pub time: f64,
+    pub window: String,
+    pub action: SessionAction,
+}
+
+/// The model-affecting actions of a recorded session, in order, stored as text with
+/// one tab-separated action per line (tabs shown as spaces):
+///
+/// ```text
+/// # Raum session journal
+/// 0.000    Hopfield Network    set      seed    42
+/// 1.250    Hopfield Network    command  train
+/// 3.500    Chip Firing Graph   do       add_chip    12
+/// ```
+///
+/// Replaying a journal sends the same actions to the same windows at the same times. A
+/// recording starts by listing every setting and restarting the random generators from
+/// their seeds, so the replay draws the same random numbers as the original session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Journal {
+    pub entries: Vec<JournalEntry>,
+}
+
+fn command_name(command: Command) -> &'static str {
+    match command {
+        Command::Step => "step",
+        Command::Run => "run",
+        Command::Reset => "reset",
+        Command::Train => "train",
+    }
+}
+
+fn parse_command(name: &str) -> Option<Command> {
+    match name {
+        "step" => Some(Command::Step),
+        "run" => Some(Command::Run),
+        "reset" => Some(Command::Reset),
+        "train" => Some(Command::Train),
+        _ => None,
+    }
+}
+
+/// Escapes the field separators so any text fits in one field
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+impl Journal {
+    /// The journal as text, one tab-separated entry per line
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", HEADER);
+        for entry in &self.entries {
+            let action = match &entry.action {
+                SessionAction::Command(command) => format!("command\t{}", command_name(*command)),
+                SessionAction::Set { key, value } => format!("set\t{}\t{}", escape(key), escape(value)),
+                SessionAction::Perform { name, argument } => format!("do\t{}\t{}", escape(name), escape(argument)),
+            };
+            text.push_str(&format!("{:.3}\t{}\t{}\n", entry.time, escape(&entry.window), action));
+        }
+        text
+    }
+
+    /// Parses the text of a journal; blank lines and lines starting with `#` are skipped
+    pub fn parse(text: &str) -> Result<Self, JournalError> {
+        let mut entries = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| JournalError::Parse { line: index + 1, message };
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 4 {
+                return Err(error(format!("expected at least 4 tab-separated fields, got {}", fields.len())));
+            }
+            let time: f64 = fields[0].parse().map_err(|_| error(format!("invalid time '{}'", fields[0])))?;
+            let field = |i: usize| fields.get(i).map(|f| unescape(f)).unwrap_or_default();
+            let action = match fields[2] {
+                "command" => SessionAction::Command(
+                    parse_command(fields[3]).ok_or_else(|| error(format!("unknown command '{}'", fields[3])))?,
+                ),
+                "set" => SessionAction::Set { key: field(3), value: field(4) },
+                "do" => SessionAction::Perform { name: field(3), argument: field(4) },
+                other => return Err(error(format!("unknown action '{}'", other))),
+            };
+            entries.push(JournalEntry { time, window: unescape(fields[1]), action });
+        }
+        Ok(Journal { entries })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), JournalError> {
+        std::fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, JournalError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// The recording in progress
+struct Recording {
+    start: Instant,
+    entries: Vec<JournalEntry>,
+    /// Settings of each window as last recorded
+    settings: HashMap<String, Vec<(&'static str, String)>>,
+}
+
+/// The running recording, if any. Like notifications, windows record through this
+/// global so they need no access to the application.
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+fn with_recording<R>(f: impl FnOnce(&mut Option<Recording>) -> R) -> R {
+    f(&mut RECORDING.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Starts a new recording, discarding any recording in progress
+pub fn start_recording() {
+    with_recording(|recording| {
+        *recording = Some(Recording { start: Instant::now(), entries: Vec::new(), settings: HashMap::new() })
+    });
+}
+
+/// Stops the recording and returns its journal
+pub fn stop_recording() -> Option<Journal> {
+    with_recording(|recording| recording.take()).map(|recording| Journal { entries: recording.entries })
+}
+
+pub fn is_recording() -> bool {
+    with_recording(|recording| recording.is_some())
+}
+
+/// Records `action` of `window` if a recording is running. The action is attributed to
+/// the window instance whose code is running, if that is known, since instances of one
+/// window type share its name.
+pub fn record(window: &str, action: SessionAction) {
+    with_recording(|recording| {
+        if let Some(recording) = recording {
+            let time = recording.start.elapsed().as_secs_f64();
+            let window = notifications::current_source().unwrap_or_else(|| window.to_string());
+            recording.entries.push(JournalEntry { time, window, action });
+        }
+    });
+}
+
+/// Records the settings of `window` that changed since they were last recorded, or all
+/// of them the first time
+pub fn record_settings(window: &str, settings: Vec<(&'static str, String)>) {
+    with_recording(|recording| {
+        let Some(recording) = recording else {
+            return;
+        };
+        let time = recording.start.elapsed().as_secs_f64();
+        let previous = recording.settings.get(window);
+        for (key, value) in &settings {
+            let old = previous.and_then(|previous| previous.iter().find(|(k, _)| k == key));
+            if old.is_none_or(|(_, old)| old != value) {
+                let action = SessionAction::set(key, value);
+                recording.entries.push(JournalEntry { time, window: window.to_string(), action });
+            }
+        }
+        recording.settings.insert(window.to_string(), settings);
+    });
+}
+
+/// Plays back a journal in real time
+pub struct SessionPlayer {
+    journal: Journal,
+    next: usize,
+    /// egui input time at which playback started
+    start: f64,
+    /// Entries that their window did not perform
+    skipped: usize,
+}
+
+impl SessionPlayer {
+    pub fn new(journal: Journal, now: f64) -> Self {
+        SessionPlayer { journal, next: 0, start: now, skipped: 0 }
+    }
+
+    /// The next entry if its time has come, without consuming it
+    pub fn due(&self, now: f64) -> Option<&JournalEntry> {
+        self.journal.entries.get(self.next).filter(|entry| entry.time <= now - self.start)
+    }
+
+    /// Moves on to the next entry after the current one was played, or skipped if its
+    /// window did not perform it
+    pub fn advance(&mut self, performed: bool) {
+        self.next += 1;
+        self.skipped += !performed as usize;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.journal.entries.len()
+    }
+
+    /// Entries played so far and in total
+    pub fn progress(&self) -> (usize, usize) {
+        (self.next, self.journal.entries.len())
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_round_trip() {
+        let journal = Journal {
+            entries: vec![
+                JournalEntry { time: 0.0, window: "Hopfield Network".into(), action: SessionAction::set("seed", 42) },
+                JournalEntry { time: 1.25, window: "Hopfield Network".into(), action: SessionAction::Command(Command::Train) },
+                JournalEntry {
+                    time: 2.5,
+                    window: "Chip Firing Graph".into(),
+                    action: SessionAction::set("edges", "0,1\t1,2\n2,0 \\"),
+                },
+                JournalEntry { time: 3.0, window: "Chip Firing Graph".into(), action: SessionAction::perform("add_chip", 7) },
+            ],
+        };
+        assert_eq!(Journal::parse(&journal.to_text()).unwrap(), journal);
+
+        assert!(matches!(
+            Journal::parse("# comment\n\n1.0\tX\tcommand\tjump\n"),
+            Err(JournalError::Parse { line: 3, .. })
+        ));
+
+        let mut player = SessionPlayer::new(journal, 10.0);
+        assert!(player.due(10.0).is_some());
+        player.advance(false);
+        assert_eq!(player.skipped(), 1);
+        assert!(player.due(11.0).is_none());
+        assert_eq!(player.due(11.25).map(|e| &e.action), Some(&SessionAction::Command(Command::Train)));
+    }
+}
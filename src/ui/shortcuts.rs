@@ -0,0 +1,101 @@
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+
+use crate::ui::windows::Command;
+
+/// A key combination bound to a window command
+#[derive(Debug, Clone, Copy)]
+pub struct Shortcut {
+    pub keys: KeyboardShortcut,
+    pub command: Command,
+    pub description: &'static str,
+}
+
+/// Key bindings for the commands dispatched to the focused window
+pub struct ShortcutRegistry {
+    shortcuts: Vec<Shortcut>,
+}
+
+impl Default for ShortcutRegistry {
+    fn default() -> Self {
+        let mut registry = ShortcutRegistry::new();
+        registry.register(Modifiers::NONE, Key::Space, Command::Step, "Step");
+        registry.register(Modifiers::NONE, Key::Enter, Command::Run, "Run");
+        registry.register(Modifiers::NONE, Key::R, Command::Reset, "Reset");
+        registry.register(Modifiers::COMMAND, Key::T, Command::Train, "Train / build the model");
+        registry
+    }
+}
+
+impl ShortcutRegistry {
+    /// Creates a registry without any bindings
+    pub fn new() -> Self {
+        ShortcutRegistry { shortcuts: Vec::new() }
+    }
+
+    /// Binds `modifiers` + `key` to `command`, replacing any earlier binding of the same keys
+    pub fn register(&mut self, modifiers: Modifiers, key: Key, command: Command, description: &'static str) {
+        let keys = KeyboardShortcut::new(modifiers, key);
+        self.shortcuts.retain(|shortcut| shortcut.keys != keys);
+        self.shortcuts.push(Shortcut { keys, command, description });
+    }
+
+    pub fn shortcuts(&self) -> &[Shortcut] {
+        &self.shortcuts
+    }
+
+    /// Returns the commands whose shortcuts were pressed this frame, consuming the key
+    /// events. Nothing is returned while a text field has keyboard focus, so typing is
+    /// never taken for a command.
+    pub fn pressed(&self, ctx: &egui::Context) -> Vec<Command> {
+        if ctx.wants_keyboard_input() {
+            return Vec::new();
+        }
+        ctx.input_mut(|input| {
+            self.shortcuts
+                .iter()
+                .filter(|shortcut| input.consume_shortcut(&shortcut.keys))
+                .map(|shortcut| shortcut.command)
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_event(key: Key, modifiers: Modifiers) -> egui::Event {
+        egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers,
+        }
+    }
+
+    #[test]
+    fn test_pressed_consumes_bound_keys() {
+        let registry = ShortcutRegistry::default();
+        let ctx = egui::Context::default();
+        ctx.begin_frame(egui::RawInput {
+            events: vec![key_event(Key::R, Modifiers::NONE), key_event(Key::T, Modifiers::COMMAND)],
+            modifiers: Modifiers::COMMAND,
+            ..Default::default()
+        });
+
+        assert_eq!(registry.pressed(&ctx), vec![Command::Reset, Command::Train]);
+        // The events were consumed
+        assert!(registry.pressed(&ctx).is_empty());
+        let _ = ctx.end_frame();
+    }
+
+    #[test]
+    fn test_register_replaces_binding() {
+        let mut registry = ShortcutRegistry::default();
+        let count = registry.shortcuts().len();
+        registry.register(Modifiers::NONE, Key::R, Command::Run, "Run");
+        assert_eq!(registry.shortcuts().len(), count);
+        assert_eq!(registry.shortcuts().last().unwrap().command, Command::Run);
+    }
+}
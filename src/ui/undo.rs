@@ -0,0 +1,103 @@
+use std::any::Any;
+use std::sync::Mutex;
+
+use crate::ui::notifications;
+
+/// Most operations that can be undone; older snapshots are dropped
+const MAX_ENTRIES: usize = 20;
+
+/// Model state a window captured before a destructive operation. Only the window that
+/// took it knows its type, see `Window::restore`.
+pub type Snapshot = Box<dyn Any + Send>;
+
+/// A snapshot with the window it belongs to
+pub struct UndoEntry {
+    /// Title of the window that took the snapshot
+    pub window: String,
+    /// Name of the operation, shown in the Edit menu
+    pub label: String,
+    pub snapshot: Snapshot,
+}
+
+/// Snapshots taken since the application last collected them
+static PENDING: Mutex<Vec<UndoEntry>> = Mutex::new(Vec::new());
+
+/// Records `snapshot` as the state before the operation `label` of the window whose
+/// code is running (see `notifications::with_source`). Outside of window code there is
+/// nothing to restore it to, and the snapshot is dropped.
+pub fn checkpoint(label: impl Into<String>, snapshot: Snapshot) {
+    let Some(window) = notifications::current_source() else {
+        return;
+    };
+    let entry = UndoEntry { window, label: label.into(), snapshot };
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).push(entry);
+}
+
+/// The application's undo history across all windows, newest last
+#[derive(Default)]
+pub struct UndoStack {
+    entries: Vec<UndoEntry>,
+}
+
+impl UndoStack {
+    /// Takes over the snapshots recorded since the last call
+    pub fn collect(&mut self) {
+        let pending = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+        self.entries.extend(pending);
+        let excess = self.entries.len().saturating_sub(MAX_ENTRIES);
+        self.entries.drain(..excess);
+    }
+
+    /// Window title and label of the operation `pop` would undo
+    pub fn peek(&self) -> Option<(&str, &str)> {
+        self.entries.last().map(|entry| (entry.window.as_str(), entry.label.as_str()))
+    }
+
+    pub fn pop(&mut self) -> Option<UndoEntry> {
+        self.entries.pop()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drops the snapshots of a removed window
+    pub fn remove_window(&mut self, title: &str) {
+        self.entries.retain(|entry| entry.window != title);
+    }
+
+    /// Keeps the snapshots of a renamed window restorable under its new title
+    pub fn rename_window(&mut self, old: &str, new: &str) {
+        for entry in self.entries.iter_mut().filter(|entry| entry.window == old) {
+            entry.window = new.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoints_are_collected_per_window_and_bounded() {
+        let mut stack = UndoStack::default();
+        notifications::with_source("Undo Test", || {
+            for i in 0..MAX_ENTRIES + 2 {
+                checkpoint(format!("Step {}", i), Box::new(i));
+            }
+        });
+        checkpoint("Outside of a window", Box::new(0usize));
+        stack.collect();
+        stack.entries.retain(|entry| entry.window.starts_with("Undo Test"));
+        assert_eq!(stack.entries.len(), MAX_ENTRIES);
+        assert_eq!(stack.peek(), Some(("Undo Test", format!("Step {}", MAX_ENTRIES + 1).as_str())));
+
+        stack.rename_window("Undo Test", "Undo Test #2");
+        let entry = stack.pop().expect("an entry is left");
+        assert_eq!(entry.window, "Undo Test #2");
+        assert_eq!(entry.snapshot.downcast::<usize>().ok().map(|i| *i), Some(MAX_ENTRIES + 1));
+
+        stack.remove_window("Undo Test #2");
+        assert!(stack.is_empty());
+    }
+}
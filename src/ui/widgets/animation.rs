@@ -0,0 +1,52 @@
+use eframe::egui;
+
+/// Scale at which network views are exported at their on-screen size
+pub const DEFAULT_SCALE: u32 = 8;
+
+/// Settings for exporting a simulation history as an animated GIF
+pub struct AnimationExport {
+    /// Output file
+    pub path: String,
+    /// Playback rate in frames per second
+    pub fps: f32,
+    /// Pixels per grid cell; network views are drawn at `scale / DEFAULT_SCALE` times their on-screen size
+    pub scale: u32,
+    /// Longer histories are sampled evenly down to this many frames
+    pub max_frames: usize,
+}
+
+impl AnimationExport {
+    pub fn new(path: &str) -> Self {
+        AnimationExport {
+            path: path.to_string(),
+            fps: 10.0,
+            scale: DEFAULT_SCALE,
+            max_frames: 300,
+        }
+    }
+
+    /// Draws the export settings and the "Record Animation" button.
+    ///
+    /// # Returns
+    ///
+    /// true if the button was clicked
+    pub fn show(&mut self, ui: &mut egui::Ui, enabled: bool) -> bool {
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.path);
+        });
+        ui.horizontal(|ui| {
+            ui.label("FPS:");
+            ui.add(egui::DragValue::new(&mut self.fps).speed(0.5).range(1.0..=60.0));
+            ui.label("Scale:");
+            ui.add(egui::DragValue::new(&mut self.scale).speed(0.2).range(1..=32));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Max Frames:");
+            ui.add(egui::DragValue::new(&mut self.max_frames).speed(10.0).range(2..=5000));
+        });
+        ui.add_enabled(enabled, egui::Button::new("Record Animation"))
+            .on_hover_text("Write the recorded history as an animated GIF")
+            .clicked()
+    }
+}
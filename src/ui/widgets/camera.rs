@@ -0,0 +1,183 @@
+use eframe::egui;
+
+/// Smallest and largest zoom factors
+const MIN_ZOOM: f32 = 0.02;
+const MAX_ZOOM: f32 = 50.0;
+
+/// Zoom change per point of mouse wheel scrolling
+const SCROLL_ZOOM_SPEED: f32 = 0.002;
+
+/// Margin left around the content by `Camera::fit`, in points
+const FIT_MARGIN: f32 = 4.0;
+
+/// Zoom and pan of a painter area. Content coordinates are mapped to offsets from the
+/// top left corner of the area as `content * zoom + pan`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub zoom: f32,
+    pub pan: egui::Vec2,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera { zoom: 1.0, pan: egui::Vec2::ZERO }
+    }
+}
+
+impl Camera {
+    /// The camera that shows all of `content` centered in an area of size `view`
+    pub fn fit(content: egui::Rect, view: egui::Vec2) -> Self {
+        let available = (view - egui::vec2(2.0 * FIT_MARGIN, 2.0 * FIT_MARGIN)).max(egui::vec2(1.0, 1.0));
+        let size = content.size().max(egui::vec2(1e-3, 1e-3));
+        let zoom = (available.x / size.x).min(available.y / size.y).clamp(MIN_ZOOM, MAX_ZOOM);
+        Camera { zoom, pan: view / 2.0 - content.center().to_vec2() * zoom }
+    }
+
+    /// The identity camera if `content` already fits into `view`, otherwise `fit`
+    pub fn initial(content: egui::Rect, view: egui::Vec2) -> Self {
+        if egui::Rect::from_min_size(egui::Pos2::ZERO, view).contains_rect(content) {
+            Camera::default()
+        } else {
+            Camera::fit(content, view)
+        }
+    }
+
+    /// Offset from the top left corner of the area at which content point `p` is drawn
+    pub fn transform(&self, p: egui::Vec2) -> egui::Vec2 {
+        p * self.zoom + self.pan
+    }
+
+    /// Content point drawn at `offset` from the top left corner of the area
+    pub fn inverse(&self, offset: egui::Vec2) -> egui::Vec2 {
+        (offset - self.pan) / self.zoom
+    }
+
+    /// Scales by `factor` while keeping the content under `anchor` (an offset in the
+    /// area) in place
+    pub fn zoom_about(&mut self, anchor: egui::Vec2, factor: f32) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan = anchor - (anchor - self.pan) * (zoom / self.zoom);
+        self.zoom = zoom;
+    }
+
+    /// Zooms with a pinch gesture or Ctrl + mouse wheel around the pointer while the area
+    /// of `response` is hovered, and pans while it is dragged. With `wheel_zoom` the plain
+    /// mouse wheel zooms too, and scrolling over the area no longer moves an enclosing
+    /// scroll area. Without `primary_pan` only the middle button pans, leaving primary
+    /// drags to tools such as a brush.
+    pub fn interact(&mut self, ui: &egui::Ui, response: &egui::Response, wheel_zoom: bool, primary_pan: bool) {
+        let primary = primary_pan && response.dragged_by(egui::PointerButton::Primary);
+        if primary || response.dragged_by(egui::PointerButton::Middle) {
+            self.pan += response.drag_delta();
+        }
+        if !response.hovered() {
+            return;
+        }
+        let (pinch, scroll) = ui.input_mut(|i| {
+            if !wheel_zoom {
+                return (i.zoom_delta(), 0.0);
+            }
+            let scroll = i.smooth_scroll_delta.y;
+            i.smooth_scroll_delta = egui::Vec2::ZERO;
+            (i.zoom_delta(), scroll)
+        });
+        let factor = pinch * (scroll * SCROLL_ZOOM_SPEED).exp();
+        if factor != 1.0 {
+            if let Some(pointer) = response.hover_pos() {
+                self.zoom_about(pointer - response.rect.min, factor);
+            }
+        }
+    }
+}
+
+/// Zoom and pan for the painter area of `response`, showing `content` (in content
+/// coordinates). The camera is kept in egui memory under `id`; a double click or the
+/// "Fit" button that appears once the view has moved fits the content into the area.
+/// See `Camera::interact` for `wheel_zoom` and `primary_pan`.
+///
+/// # Returns
+///
+/// The camera to draw this frame with
+pub fn viewport(
+    ui: &mut egui::Ui,
+    response: &egui::Response,
+    id: egui::Id,
+    content: egui::Rect,
+    wheel_zoom: bool,
+    primary_pan: bool,
+) -> Camera {
+    let view = response.rect.size();
+    let mut camera = ui
+        .ctx()
+        .data(|data| data.get_temp::<Camera>(id))
+        .unwrap_or_else(|| Camera::initial(content, view));
+    camera.interact(ui, response, wheel_zoom, primary_pan);
+
+    let fitted = Camera::fit(content, view);
+    let mut fit = response.double_clicked();
+    if camera != fitted && camera != Camera::initial(content, view) {
+        let button_rect = egui::Rect::from_min_size(response.rect.right_top() + egui::vec2(-38.0, 4.0), egui::vec2(34.0, 18.0));
+        let button = ui.interact(button_rect, id.with("fit"), egui::Sense::click());
+        let visuals = ui.style().interact(&button);
+        let painter = ui.painter().with_clip_rect(response.rect);
+        painter.rect(button_rect, 3.0, visuals.bg_fill, visuals.bg_stroke);
+        painter.text(button_rect.center(), egui::Align2::CENTER_CENTER, "Fit", egui::FontId::proportional(12.0), visuals.text_color());
+        fit |= button.on_hover_text("Fit to view (or double click)").clicked();
+    }
+    if fit {
+        camera = fitted;
+    }
+
+    ui.ctx().data_mut(|data| data.insert_temp(id, camera));
+    camera
+}
+
+/// Side of the minimap's longer edge, in points
+const MINIMAP_SIZE: f32 = 96.0;
+
+/// Draws a minimap in the bottom right corner of `view` (the painter area, in screen
+/// coordinates) showing where the visible part lies in `content`. Nothing is drawn while
+/// all of the content is visible.
+pub fn draw_minimap(painter: &egui::Painter, view: egui::Rect, content: egui::Rect, camera: Camera) {
+    let visible = egui::Rect::from_min_max(
+        camera.inverse(egui::Vec2::ZERO).to_pos2(),
+        camera.inverse(view.size()).to_pos2(),
+    );
+    if visible.contains_rect(content) || !content.is_positive() {
+        return;
+    }
+
+    let scale = MINIMAP_SIZE / content.width().max(content.height());
+    let size = content.size() * scale;
+    let map = egui::Rect::from_min_size(view.right_bottom() - size - egui::vec2(6.0, 6.0), size);
+    let to_map = |p: egui::Pos2| map.min + (p - content.min) * scale;
+    let frame = egui::Rect::from_min_max(to_map(visible.min), to_map(visible.max)).intersect(map);
+
+    let visuals = painter.ctx().style().visuals.clone();
+    painter.rect(map, 2.0, visuals.extreme_bg_color.gamma_multiply(0.85), visuals.widgets.noninteractive.bg_stroke);
+    if frame.is_positive() {
+        painter.rect_stroke(frame, 0.0, egui::Stroke::new(1.5, visuals.selection.stroke.color));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_and_zoom_keep_points_in_place() {
+        let content = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1000.0, 500.0));
+        let camera = Camera::fit(content, egui::vec2(208.0, 208.0));
+        assert!((camera.zoom - 0.2).abs() < 1e-6);
+        let corner = camera.transform(egui::vec2(1000.0, 500.0));
+        assert!((corner.x - 204.0).abs() < 1e-3 && (corner.y - 154.0).abs() < 1e-3);
+        assert_eq!(Camera::initial(content, egui::vec2(1000.0, 600.0)), Camera::default());
+
+        let mut camera = Camera::default();
+        let anchor = egui::vec2(30.0, 40.0);
+        let before = camera.inverse(anchor);
+        camera.zoom_about(anchor, 3.0);
+        assert!((camera.inverse(anchor) - before).length() < 1e-4);
+        assert_eq!(camera.zoom, 3.0);
+    }
+}
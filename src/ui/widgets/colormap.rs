@@ -0,0 +1,169 @@
+use eframe::egui;
+
+use crate::ui::app::GridColors;
+
+/// Width of the gradient bar drawn by `legend`, in points
+const LEGEND_WIDTH: f32 = 160.0;
+
+/// Number of colored strips the legend gradient is drawn with
+const LEGEND_STEPS: usize = 48;
+
+/// Evenly spaced stops of the viridis palette, dark blue to yellow
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84],
+    [71, 44, 122],
+    [59, 81, 139],
+    [44, 113, 142],
+    [33, 144, 141],
+    [39, 173, 129],
+    [92, 200, 99],
+    [170, 220, 50],
+    [253, 231, 37],
+];
+
+/// Evenly spaced stops of the plasma palette, dark blue through magenta to yellow
+const PLASMA: [[u8; 3]; 9] = [
+    [13, 8, 135],
+    [75, 3, 161],
+    [125, 3, 168],
+    [168, 34, 150],
+    [203, 70, 121],
+    [229, 107, 93],
+    [248, 148, 65],
+    [253, 195, 40],
+    [240, 249, 33],
+];
+
+/// Blue for negative values through white at zero to red for positive ones
+const DIVERGING: [[u8; 3]; 5] = [
+    [33, 102, 172],
+    [103, 169, 207],
+    [247, 247, 247],
+    [239, 138, 98],
+    [178, 24, 43],
+];
+
+/// Palettes for continuous values: weights, firing rates, overlaps and the like
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Colormap {
+    #[default]
+    Viridis,
+    Plasma,
+    Diverging,
+}
+
+impl Colormap {
+    pub const ALL: [Colormap; 3] = [Colormap::Viridis, Colormap::Plasma, Colormap::Diverging];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Colormap::Viridis => "Viridis",
+            Colormap::Plasma => "Plasma",
+            Colormap::Diverging => "Diverging",
+        }
+    }
+
+    fn stops(self) -> &'static [[u8; 3]] {
+        match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Plasma => &PLASMA,
+            Colormap::Diverging => &DIVERGING,
+        }
+    }
+
+    /// Color at position `t` along the palette, clamped to [0, 1]
+    pub fn sample(self, t: f32) -> egui::Color32 {
+        let stops = self.stops();
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let position = t * (stops.len() - 1) as f32;
+        let i = (position.floor() as usize).min(stops.len() - 2);
+        let u = position - i as f32;
+        let channel = |c: usize| (stops[i][c] as f32 + (stops[i + 1][c] as f32 - stops[i][c] as f32) * u).round() as u8;
+        egui::Color32::from_rgb(channel(0), channel(1), channel(2))
+    }
+
+    /// Color of `value` on a scale from `min` to `max`; a zero-width range maps to the
+    /// middle of the palette
+    pub fn map(self, value: f64, min: f64, max: f64) -> egui::Color32 {
+        let t = if max > min { (value - min) / (max - min) } else { 0.5 };
+        self.sample(t as f32)
+    }
+
+    /// Color of `value` on a scale from `-max_abs` to `max_abs`, so that zero falls on
+    /// the middle of the palette (white for `Diverging`)
+    pub fn map_signed(self, value: f64, max_abs: f64) -> egui::Color32 {
+        let max_abs = max_abs.abs();
+        self.map(value, -max_abs, max_abs)
+    }
+}
+
+/// Combo box choosing a colormap
+pub fn colormap_selector(ui: &mut egui::Ui, id: impl std::hash::Hash, colormap: &mut Colormap) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(colormap.name())
+        .show_ui(ui, |ui| {
+            for option in Colormap::ALL {
+                ui.selectable_value(colormap, option, option.name());
+            }
+        });
+}
+
+/// A horizontal gradient bar of `colormap` labeled with the values at its ends
+pub fn legend(ui: &mut egui::Ui, colormap: Colormap, min: f64, max: f64, label: &str) {
+    ui.horizontal(|ui| {
+        if !label.is_empty() {
+            ui.label(label);
+        }
+        ui.label(format_value(min));
+        let height = ui.text_style_height(&egui::TextStyle::Body);
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(LEGEND_WIDTH, height), egui::Sense::hover());
+        let painter = ui.painter();
+        let step = rect.width() / LEGEND_STEPS as f32;
+        for i in 0..LEGEND_STEPS {
+            let t = (i as f32 + 0.5) / LEGEND_STEPS as f32;
+            let strip = egui::Rect::from_min_size(rect.min + egui::vec2(i as f32 * step, 0.0), egui::vec2(step + 0.5, rect.height()));
+            painter.rect_filled(strip, 0.0, colormap.sample(t));
+        }
+        painter.rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+        ui.label(format_value(max));
+    });
+}
+
+/// A grid cell filled with `fill` showing `text` in a contrasting color
+pub fn color_cell(ui: &mut egui::Ui, fill: egui::Color32, text: &str) -> egui::Response {
+    let galley = ui.painter().layout_no_wrap(text.to_string(), egui::TextStyle::Body.resolve(ui.style()), GridColors::text_on(fill));
+    let size = galley.size() + egui::vec2(8.0, 2.0);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    ui.painter().rect_filled(rect, 2.0, fill);
+    ui.painter().galley(rect.center() - galley.size() / 2.0, galley, GridColors::text_on(fill));
+    response
+}
+
+/// Legend end labels: integers as such, other values with up to three decimals
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e9 {
+        format!("{}", value as i64)
+    } else if value.abs() >= 1000.0 || (value != 0.0 && value.abs() < 0.01) {
+        format!("{:.2e}", value)
+    } else {
+        format!("{:.3}", value).trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palettes_hit_their_stops_and_clamp() {
+        assert_eq!(Colormap::Viridis.sample(0.0), egui::Color32::from_rgb(68, 1, 84));
+        assert_eq!(Colormap::Viridis.sample(1.0), egui::Color32::from_rgb(253, 231, 37));
+        assert_eq!(Colormap::Plasma.sample(2.0), Colormap::Plasma.sample(1.0));
+        assert_eq!(Colormap::Plasma.sample(f32::NAN), Colormap::Plasma.sample(0.0));
+        assert_eq!(Colormap::Diverging.map_signed(0.0, 3.0), egui::Color32::from_rgb(247, 247, 247));
+        assert_eq!(Colormap::Diverging.map_signed(-3.0, 3.0), egui::Color32::from_rgb(33, 102, 172));
+        assert_eq!(Colormap::Viridis.map(5.0, 5.0, 5.0), Colormap::Viridis.sample(0.5));
+        assert_eq!(format_value(12.0), "12");
+        assert_eq!(format_value(0.25), "0.25");
+    }
+}
@@ -0,0 +1,20 @@
+use rand::Rng;
+
+use crate::neural::DynamicalSystem;
+use crate::ui::notifications::report_error;
+
+/// Advances `system` by up to `steps` steps, reporting a failure to the user.
+///
+/// # Returns
+///
+/// The number of steps taken; fewer than `steps` once the system has come to rest or
+/// when a step failed
+pub fn advance<S: DynamicalSystem>(system: &mut S, steps: usize, rng: &mut impl Rng) -> usize {
+    match system.run(steps, rng) {
+        Ok(taken) => taken,
+        Err(e) => {
+            report_error(format!("Simulation error: {}", e));
+            0
+        },
+    }
+}
@@ -1,57 +1,130 @@
 use eframe::egui;
 
-/// Draws a grid of cells representing a state vector
+use crate::core::noise::flip_bits;
+use crate::ui::app::GridColors;
+use crate::ui::widgets::camera::{draw_minimap, viewport, Camera};
+use crate::ui::widgets::visual::{GrayGrid, SpinGrid, Visualizable};
+
+/// Draws a grid of cells representing a state vector, in the application's grid colors
 pub fn draw_grid(ui: &mut egui::Ui, state: &[f64], width: usize, height: usize, cell_size: f32) {
     // Prevent drawing if state is empty or incorrect size
     if state.len() != width * height {
         ui.label("Invalid state for grid display");
         return;
     }
-    
-    // Calculate the total size needed for the grid
+    draw_model_grid(ui, &SpinGrid { state, width }, cell_size);
+}
+
+/// Draws gray values in [0, 1] (0 black, 1 white) as a grid of cells, like `draw_grid`
+/// does for ±1 states
+pub fn draw_gray_grid(ui: &mut egui::Ui, values: &[f64], width: usize, height: usize, cell_size: f32) {
+    if values.len() != width * height {
+        ui.label("Invalid state for grid display");
+        return;
+    }
+    draw_model_grid(ui, &GrayGrid { values, width }, cell_size);
+}
+
+/// Allocates space for `model` and draws it as a grid of bordered cells. The grid can
+/// be zoomed with Ctrl + mouse wheel or a pinch and panned by dragging.
+pub fn draw_model_grid(ui: &mut egui::Ui, model: &impl Visualizable, cell_size: f32) {
+    let Some((width, height)) = model.grid_shape() else {
+        ui.label("This state has no grid layout");
+        return;
+    };
     let grid_size = egui::vec2(width as f32 * cell_size, height as f32 * cell_size);
-    
-    // Allocate space for the grid
-    let (response, painter) = ui.allocate_painter(grid_size, egui::Sense::hover());
-    let rect = response.rect;
-
-    for y in 0..height {
-        for x in 0..width {
-            let index = y * width + x;
-            let cell_state = state.get(index).copied().unwrap_or(0.0);
-
-            let cell_color = if cell_state == 1.0 {
-                egui::Color32::BLACK
-            } else if cell_state == -1.0 {
-                egui::Color32::WHITE
-            } else {
-                egui::Color32::GRAY // Should not happen with valid states
-            };
-
-            let cell_top_left = rect.min + egui::vec2(x as f32 * cell_size, y as f32 * cell_size);
-            let cell_rect = egui::Rect::from_min_size(cell_top_left, egui::vec2(cell_size, cell_size));
-
-            painter.rect_filled(cell_rect, 0.0, cell_color);
-            // Add a border for clarity
-            painter.rect_stroke(cell_rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+    let (response, painter) = ui.allocate_painter(grid_size.min(egui::vec2(ui.available_width(), grid_size.y)), egui::Sense::click_and_drag());
+    let content = egui::Rect::from_min_size(egui::Pos2::ZERO, grid_size);
+    let camera = viewport(ui, &response, response.id.with("camera"), content, false, true);
+    paint_grid(
+        &painter,
+        response.rect.min + camera.pan,
+        model,
+        cell_size * camera.zoom,
+        Some(egui::Stroke::new(1.0, egui::Color32::DARK_GRAY)),
+    );
+    draw_minimap(&painter, response.rect, content, camera);
+
+    let hovered = response.hover_pos().and_then(|pointer| cell_at(model, cell_size, camera, pointer - response.rect.min));
+    if let Some(index) = hovered {
+        response.on_hover_text_at_pointer(model.describe(index));
+    }
+}
+
+/// Index of the cell of `model` (drawn with `cell_size` through `camera`) at `offset` from
+/// the top left corner of the painter area
+pub fn cell_at(model: &impl Visualizable, cell_size: f32, camera: Camera, offset: egui::Vec2) -> Option<usize> {
+    let (width, height) = model.grid_shape()?;
+    let p = camera.inverse(offset) / cell_size;
+    if p.x < 0.0 || p.y < 0.0 {
+        return None;
+    }
+    let (x, y) = (p.x as usize, p.y as usize);
+    let index = y * width + x;
+    (x < width && y < height && index < model.len()).then_some(index)
+}
+
+/// Paints `model` as square cells of `cell_size` starting at `origin`, with the unit
+/// labels on top and an optional `border` around every cell
+pub fn paint_grid(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    model: &impl Visualizable,
+    cell_size: f32,
+    border: Option<egui::Stroke>,
+) {
+    let Some((width, _)) = model.grid_shape() else {
+        return;
+    };
+    let colors = GridColors::get(painter.ctx());
+
+    for index in 0..model.len() {
+        let cell_top_left = origin + egui::vec2((index % width) as f32 * cell_size, (index / width) as f32 * cell_size);
+        let cell_rect = egui::Rect::from_min_size(cell_top_left, egui::vec2(cell_size, cell_size));
+        let fill = model.color(index, &colors);
+
+        painter.rect_filled(cell_rect, 0.0, fill);
+        if let Some(stroke) = border {
+            painter.rect_stroke(cell_rect, 0.0, stroke);
         }
+        if let Some(label) = model.label(index) {
+            painter.text(
+                cell_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                label,
+                egui::FontId::proportional(14.0),
+                GridColors::text_on(fill),
+            );
+        }
+    }
+}
+
+/// Draws a grid of real values on a diverging scale: the application's "on" color for
+/// positive values and "off" color for negative ones, fading to gray at zero. Values
+/// are scaled by the largest magnitude, so eigenvectors and fields of any size show up.
+pub fn draw_signed_grid(ui: &mut egui::Ui, values: &[f64], width: usize, height: usize, cell_size: f32) {
+    if values.len() != width * height {
+        ui.label("Invalid state for grid display");
+        return;
+    }
+
+    let grid_size = egui::vec2(width as f32 * cell_size, height as f32 * cell_size);
+    let (response, painter) = ui.allocate_painter(grid_size, egui::Sense::hover());
+    let colors = GridColors::get(ui.ctx());
+    let max = values.iter().fold(0.0f64, |m, v| m.max(v.abs())).max(f64::MIN_POSITIVE);
+
+    for (index, &value) in values.iter().enumerate() {
+        let t = (value / max).clamp(-1.0, 1.0) as f32;
+        let end = if t >= 0.0 { colors.on } else { colors.off };
+        let cell_color = egui::Color32::GRAY.lerp_to_gamma(end, t.abs());
+        let cell_top_left = response.rect.min + egui::vec2((index % width) as f32 * cell_size, (index / width) as f32 * cell_size);
+        painter.rect_filled(egui::Rect::from_min_size(cell_top_left, egui::vec2(cell_size, cell_size)), 0.0, cell_color);
     }
+    painter.rect_stroke(response.rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
 }
 
 /// Applies noise to a state vector by flipping bits.
 /// `noise_level` is the probability (0.0 to 1.0) that any given bit is flipped.
-pub fn apply_noise(state: &[f64], noise_level: f32) -> Vec<f64> {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    
-    state
-        .iter()
-        .map(|&val| {
-            if rng.gen::<f32>() < noise_level {
-                -val // Flip the bit
-            } else {
-                val // Keep the bit
-            }
-        })
-        .collect()
-}
\ No newline at end of file
+pub fn apply_noise(state: &[f64], noise_level: f32, rng: &mut impl rand::Rng) -> Vec<f64> {
+    flip_bits(state, noise_level as f64, rng)
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use eframe::egui;
+use eframe::egui_wgpu;
+use eframe::wgpu;
+
+use crate::graphics::compute::f32_bytes;
+use crate::graphics::height_field::{HeightFieldRenderer, OrbitCamera};
+use crate::graphics::renderer::Renderer;
+use crate::ui::notifications::report_error;
+
+/// Radians turned per point of dragging
+const ORBIT_SPEED: f32 = 0.01;
+
+/// Zoom change per point of mouse wheel scrolling
+const SCROLL_ZOOM_SPEED: f32 = 0.002;
+
+/// The height field renderers of all widgets, kept in the egui renderer's callback
+/// resources so they outlive a frame
+struct HeightFields {
+    target_format: wgpu::TextureFormat,
+    renderers: HashMap<egui::Id, HeightFieldRenderer>,
+}
+
+fn available_id() -> egui::Id {
+    egui::Id::new("raum_height_fields")
+}
+
+/// Registers the renderers with the wgpu backend of egui. Until this is called,
+/// `is_available` is false.
+pub fn install(ctx: &egui::Context, render_state: &egui_wgpu::RenderState) {
+    render_state.renderer.write().callback_resources.insert(HeightFields {
+        target_format: render_state.target_format,
+        renderers: HashMap::new(),
+    });
+    ctx.data_mut(|data| data.insert_temp(available_id(), true));
+}
+
+/// Whether `height_field` can be used
+pub fn is_available(ctx: &egui::Context) -> bool {
+    ctx.data(|data| data.get_temp(available_id())).unwrap_or(false)
+}
+
+/// Paint callback drawing one frame of a height field
+struct HeightFieldCallback {
+    id: egui::Id,
+    columns: u32,
+    rows: u32,
+    heights: Vec<f32>,
+    camera: OrbitCamera,
+    /// Size of the callback rect in pixels
+    pixels: [u32; 2],
+}
+
+impl egui_wgpu::CallbackTrait for HeightFieldCallback {
+    fn prepare(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _screen_descriptor: &egui_wgpu::ScreenDescriptor,
+        _egui_encoder: &mut wgpu::CommandEncoder,
+        callback_resources: &mut egui_wgpu::CallbackResources,
+    ) -> Vec<wgpu::CommandBuffer> {
+        let Some(fields) = callback_resources.get_mut::<HeightFields>() else {
+            return Vec::new();
+        };
+        let target_format = fields.target_format;
+        let renderer = fields
+            .renderers
+            .entry(self.id)
+            .or_insert_with(|| HeightFieldRenderer::new(target_format, self.columns, self.rows));
+        if !renderer.is_initialized() {
+            if let Err(e) = renderer.initialize(device, queue) {
+                report_error(format!("Failed to create 3D view: {}", e));
+                return Vec::new();
+            }
+        }
+        renderer.set_grid(device, self.columns, self.rows);
+        renderer.resize(device, self.pixels[0], self.pixels[1]);
+        renderer.camera = self.camera;
+        if let Err(e) = renderer.update(queue, &f32_bytes(self.heights.iter().copied())) {
+            report_error(format!("Failed to upload heights: {}", e));
+            return Vec::new();
+        }
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("height_field") });
+        renderer.render_offscreen(device, queue, &mut encoder);
+        vec![encoder.finish()]
+    }
+
+    fn paint<'a>(
+        &'a self,
+        _info: egui::PaintCallbackInfo,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        callback_resources: &'a egui_wgpu::CallbackResources,
+    ) {
+        if let Some(renderer) = callback_resources.get::<HeightFields>().and_then(|f| f.renderers.get(&self.id)) {
+            renderer.paint(render_pass);
+        }
+    }
+}
+
+/// Turns `camera` while the area of `response` is dragged and zooms with the mouse
+/// wheel or a pinch while it is hovered
+pub fn orbit(ui: &egui::Ui, response: &egui::Response, camera: &mut OrbitCamera) {
+    if response.dragged() {
+        let delta = response.drag_delta();
+        camera.orbit(delta.x * ORBIT_SPEED, delta.y * ORBIT_SPEED);
+    }
+    if response.hovered() {
+        let (pinch, scroll) = ui.input_mut(|i| {
+            let scroll = i.smooth_scroll_delta.y;
+            i.smooth_scroll_delta = egui::Vec2::ZERO;
+            (i.zoom_delta(), scroll)
+        });
+        camera.zoom(pinch * (scroll * SCROLL_ZOOM_SPEED).exp());
+    }
+    if response.double_clicked() {
+        *camera = OrbitCamera::default();
+    }
+}
+
+/// Draws `heights`, `columns` values per row, as a lit 3D surface filling `rect`.
+/// Only call this when `is_available` is true.
+///
+/// # Arguments
+///
+/// * `id` - Identifies the renderer, which is reused across frames
+pub fn height_field(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    id: egui::Id,
+    heights: Vec<f32>,
+    columns: usize,
+    camera: OrbitCamera,
+) {
+    let rows = heights.len() / columns.max(1);
+    let pixels = rect.size() * painter.ctx().pixels_per_point();
+    let callback = HeightFieldCallback {
+        id,
+        columns: columns as u32,
+        rows: rows as u32,
+        heights,
+        camera,
+        pixels: [pixels.x.round() as u32, pixels.y.round() as u32],
+    };
+    painter.add(egui_wgpu::Callback::new_paint_callback(rect, callback));
+}
@@ -0,0 +1,185 @@
+use eframe::egui;
+use egui_plot::{Line, PlotPoints};
+
+use crate::core::delta::{DeltaCodec, DeltaHistory};
+use crate::core::history::SimulationHistory;
+
+/// Maximum number of points drawn for a history plot
+pub const MAX_PLOT_POINTS: usize = 2000;
+
+/// What `history_slider` needs to know about a recorded history
+pub trait BrowsableHistory {
+    /// Number of recorded entries
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iteration the entry at `index` was recorded at
+    fn iteration(&self, index: usize) -> Option<usize>;
+
+    /// Iteration of the most recent entry
+    fn last_iteration(&self) -> usize;
+
+    /// Entries dropped to respect a capacity
+    fn dropped(&self) -> usize {
+        0
+    }
+}
+
+impl<T> BrowsableHistory for SimulationHistory<T> {
+    fn len(&self) -> usize {
+        SimulationHistory::len(self)
+    }
+
+    fn iteration(&self, index: usize) -> Option<usize> {
+        self.entry(index).map(|entry| entry.iteration)
+    }
+
+    fn last_iteration(&self) -> usize {
+        SimulationHistory::last_iteration(self)
+    }
+
+    fn dropped(&self) -> usize {
+        self.evicted()
+    }
+}
+
+impl<C: DeltaCodec> BrowsableHistory for DeltaHistory<C> {
+    fn len(&self) -> usize {
+        DeltaHistory::len(self)
+    }
+
+    fn iteration(&self, index: usize) -> Option<usize> {
+        DeltaHistory::iteration(self, index)
+    }
+
+    fn last_iteration(&self) -> usize {
+        DeltaHistory::last_iteration(self)
+    }
+}
+
+/// Draws a slider for browsing a simulation history.
+///
+/// `index` selects an entry of `history` and is clamped to its length. The label shows
+/// the recorded iteration of the selected entry, so sampled histories still read in
+/// simulation steps. When a bounded history has dropped entries, a note next to the
+/// slider says how many, since neighbouring positions can then be far apart in time.
+///
+/// # Returns
+///
+/// true if the slider was moved
+pub fn history_slider(ui: &mut egui::Ui, history: &impl BrowsableHistory, index: &mut usize, label: &str) -> bool {
+    if history.len() <= 1 {
+        *index = 0;
+        ui.label(format!("{}: 0 / 0", label));
+        return false;
+    }
+
+    let max_index = history.len() - 1;
+    *index = (*index).min(max_index);
+
+    ui.horizontal(|ui| {
+        let iteration = history.iteration(*index).unwrap_or(0);
+        ui.label(format!("{}: {} / {}", label, iteration, history.last_iteration()));
+        let changed = ui.add(egui::Slider::new(index, 0..=max_index).show_value(false)).changed();
+        if history.dropped() > 0 {
+            ui.weak(format!("({} of {} kept)", history.len(), history.len() + history.dropped()))
+                .on_hover_text("Entries were dropped to bound memory and cannot be browsed");
+        }
+        changed
+    })
+    .inner
+}
+
+/// A plot line of a scalar history against iteration, downsampled to `MAX_PLOT_POINTS`
+pub fn history_line(history: &SimulationHistory<f64>) -> Line {
+    let points: PlotPoints = history
+        .downsample(MAX_PLOT_POINTS)
+        .into_iter()
+        .map(|entry| [entry.iteration as f64, entry.state])
+        .collect();
+    Line::new(points)
+}
+
+/// Indices of every `stride`-th entry of a history of `len` entries, always ending with
+/// the last one so a sampled sheet still shows where the run ended
+pub fn mosaic_frames(len: usize, stride: usize) -> Vec<usize> {
+    let mut frames: Vec<usize> = (0..len).step_by(stride.max(1)).collect();
+    if len > 0 && frames.last() != Some(&(len - 1)) {
+        frames.push(len - 1);
+    }
+    frames
+}
+
+/// Draws a scrollable contact sheet of the entries of `history` listed by
+/// `mosaic_frames`, as tiles of `tile_size` labelled with their iteration.
+/// `paint_frame` paints the entry at an index into a tile. The entry at `selected`
+/// is outlined, and clicking a tile selects it. Only visible rows are painted, so
+/// long histories stay cheap.
+///
+/// # Returns
+///
+/// true if a tile was clicked
+pub fn history_mosaic(
+    ui: &mut egui::Ui,
+    history: &impl BrowsableHistory,
+    stride: usize,
+    tile_size: egui::Vec2,
+    height: f32,
+    selected: &mut usize,
+    mut paint_frame: impl FnMut(&egui::Painter, egui::Rect, usize),
+) -> bool {
+    let frames = mosaic_frames(history.len(), stride);
+    let spacing = ui.spacing().item_spacing;
+    let label_height = ui.text_style_height(&egui::TextStyle::Small);
+    let cell = tile_size + egui::vec2(spacing.x, spacing.y + label_height);
+    let per_row = ((ui.available_width() + spacing.x) / cell.x).floor().max(1.0) as usize;
+    let rows = frames.len().div_ceil(per_row);
+
+    let mut clicked = false;
+    egui::ScrollArea::vertical().max_height(height).auto_shrink([false, true]).show_rows(ui, cell.y, rows, |ui, visible| {
+        for row in visible {
+            ui.horizontal(|ui| {
+                for &index in frames.iter().skip(row * per_row).take(per_row) {
+                    let (rect, response) = ui.allocate_exact_size(egui::vec2(tile_size.x, cell.y - spacing.y), egui::Sense::click());
+                    let tile = egui::Rect::from_min_size(rect.min, tile_size);
+                    let painter = ui.painter_at(rect);
+                    paint_frame(&painter, tile, index);
+                    let stroke = if index == *selected {
+                        egui::Stroke::new(2.0, ui.visuals().selection.stroke.color)
+                    } else {
+                        egui::Stroke::new(1.0, egui::Color32::DARK_GRAY)
+                    };
+                    painter.rect_stroke(tile, 0.0, stroke);
+                    painter.text(
+                        egui::pos2(tile.center().x, tile.max.y),
+                        egui::Align2::CENTER_TOP,
+                        history.iteration(index).unwrap_or(index).to_string(),
+                        egui::TextStyle::Small.resolve(ui.style()),
+                        ui.visuals().text_color(),
+                    );
+                    if response.clicked() {
+                        *selected = index;
+                        clicked = true;
+                    }
+                }
+            });
+        }
+    });
+    clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mosaic_frames_end_with_last_entry() {
+        assert_eq!(mosaic_frames(7, 3), vec![0, 3, 6]);
+        assert_eq!(mosaic_frames(8, 3), vec![0, 3, 6, 7]);
+        assert_eq!(mosaic_frames(2, 0), vec![0, 1]);
+        assert!(mosaic_frames(0, 4).is_empty());
+    }
+}
@@ -0,0 +1,21 @@
+use eframe::egui;
+
+use crate::core::job::BackgroundJob;
+
+/// Draws a progress bar for a running background job with a Cancel button.
+///
+/// Also requests a repaint so the bar keeps moving while the worker runs.
+pub fn job_progress<T>(ui: &mut egui::Ui, job: &BackgroundJob<T>) {
+    ui.horizontal(|ui| {
+        let text = if job.is_cancelled() {
+            format!("{}: cancelling...", job.label())
+        } else {
+            format!("{}: {} / {}", job.label(), job.done(), job.total())
+        };
+        ui.add(egui::ProgressBar::new(job.fraction()).text(text).desired_width(250.0));
+        if ui.add_enabled(!job.is_cancelled(), egui::Button::new("Cancel")).clicked() {
+            job.cancel();
+        }
+    });
+    ui.ctx().request_repaint();
+}
@@ -0,0 +1,98 @@
+use eframe::egui;
+
+use crate::core::noise::RegionMask;
+use crate::ui::widgets::grid::paint_grid;
+use crate::ui::widgets::visual::Visualizable;
+
+/// Fill drawn over masked cells
+const MASK_FILL: egui::Color32 = egui::Color32::from_rgba_premultiplied(120, 60, 0, 120);
+
+/// Outline of masks and of the region being drawn
+const MASK_STROKE: egui::Color32 = egui::Color32::from_rgb(255, 160, 40);
+
+/// How a region mask is drawn with the pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskShape {
+    /// Drag from one corner to the opposite one
+    Rectangle,
+    /// Drag around the region
+    Lasso,
+}
+
+impl MaskShape {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaskShape::Rectangle => "Rectangle",
+            MaskShape::Lasso => "Lasso",
+        }
+    }
+}
+
+/// Draws `model` with `mask` shaded on top and lets the user drag out a new mask of
+/// `shape` over it. The outline in progress is kept in egui memory.
+///
+/// # Returns
+///
+/// The new mask once the drag is released
+pub fn mask_editor(
+    ui: &mut egui::Ui,
+    model: &impl Visualizable,
+    cell_size: f32,
+    shape: MaskShape,
+    mask: Option<&RegionMask>,
+) -> Option<RegionMask> {
+    let (width, height) = model.grid_shape()?;
+    let size = egui::vec2(width as f32 * cell_size, height as f32 * cell_size);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::drag());
+    let origin = response.rect.min;
+    paint_grid(&painter, origin, model, cell_size, None);
+
+    if let Some(mask) = mask.filter(|mask| mask.fits(width, height)) {
+        for (index, _) in mask.cells().iter().enumerate().filter(|&(_, &masked)| masked) {
+            let top_left = origin + egui::vec2((index % width) as f32 * cell_size, (index / width) as f32 * cell_size);
+            painter.rect_filled(egui::Rect::from_min_size(top_left, egui::vec2(cell_size, cell_size)), 0.0, MASK_FILL);
+        }
+    }
+    painter.rect_stroke(response.rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+
+    // Outline points in cell coordinates
+    let id = response.id.with("mask_outline");
+    let mut outline: Vec<egui::Pos2> = ui.ctx().data(|data| data.get_temp(id)).unwrap_or_default();
+    if let Some(pointer) = response.interact_pointer_pos() {
+        let point = ((pointer - origin) / cell_size).to_pos2();
+        if response.drag_started() {
+            outline.clear();
+        }
+        match shape {
+            MaskShape::Rectangle if outline.len() >= 2 => outline[1] = point,
+            MaskShape::Lasso if outline.last().is_some_and(|last| last.distance(point) < 0.25) => {},
+            _ => outline.push(point),
+        }
+    }
+
+    let to_screen = |p: egui::Pos2| origin + p.to_vec2() * cell_size;
+    let stroke = egui::Stroke::new(1.5, MASK_STROKE);
+    match shape {
+        MaskShape::Rectangle if outline.len() >= 2 => {
+            painter.rect_stroke(egui::Rect::from_two_pos(to_screen(outline[0]), to_screen(outline[1])), 0.0, stroke);
+        },
+        MaskShape::Lasso if outline.len() >= 2 => {
+            let mut points: Vec<egui::Pos2> = outline.iter().map(|&p| to_screen(p)).collect();
+            points.push(points[0]);
+            painter.add(egui::Shape::line(points, stroke));
+        },
+        _ => {},
+    }
+
+    let mut result = None;
+    if response.drag_stopped() && outline.len() >= 2 {
+        let points: Vec<(f64, f64)> = outline.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+        result = Some(match shape {
+            MaskShape::Rectangle => RegionMask::rectangle(width, height, points[0], points[1]),
+            MaskShape::Lasso => RegionMask::lasso(width, height, &points),
+        });
+        outline.clear();
+    }
+    ui.ctx().data_mut(|data| data.insert_temp(id, outline));
+    result
+}
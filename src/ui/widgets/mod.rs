@@ -1 +1,16 @@
-pub mod grid;
\ No newline at end of file
+pub mod animation;
+pub mod camera;
+pub mod colormap;
+pub mod dynamics;
+pub mod grid;
+pub mod height_field;
+pub mod history;
+pub mod job;
+pub mod mask;
+pub mod network;
+pub mod seed;
+pub mod snapshot;
+pub mod spectrum;
+pub mod state_texture;
+pub mod timeline;
+pub mod visual;
@@ -0,0 +1,123 @@
+use eframe::egui;
+
+use crate::ui::app::GridColors;
+use crate::ui::widgets::visual::Visualizable;
+
+/// Sizes used when painting a network
+#[derive(Debug, Clone, Copy)]
+pub struct NetworkStyle {
+    pub vertex_radius: f32,
+    pub edge_thickness: f32,
+}
+
+/// Draws a graph as circles connected by line segments.
+///
+/// `positions` are offsets from `origin`; `edges` lists each undirected edge once;
+/// `labels` and `fills` give the text and fill color for every node.
+pub fn draw_network(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    positions: &[egui::Vec2],
+    edges: &[(usize, usize)],
+    labels: &[String],
+    fills: &[egui::Color32],
+    style: NetworkStyle,
+) {
+    let weighted: Vec<(usize, usize, u32)> = edges.iter().map(|&(i, j)| (i, j, 1)).collect();
+    draw_weighted_network(painter, origin, positions, &weighted, labels, fills, style);
+}
+
+/// Like `draw_network`, for edges given as (i, j, multiplicity). Multi-edges are drawn
+/// with proportionally thicker lines (up to the vertex diameter) and labeled with
+/// their multiplicity.
+pub fn draw_weighted_network(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    positions: &[egui::Vec2],
+    edges: &[(usize, usize, u32)],
+    labels: &[String],
+    fills: &[egui::Color32],
+    style: NetworkStyle,
+) {
+    let NetworkStyle { vertex_radius, edge_thickness } = style;
+
+    // Draw edges first so nodes are painted on top
+    for &(i, j, multiplicity) in edges {
+        if let (Some(&start), Some(&end)) = (positions.get(i), positions.get(j)) {
+            let thickness = (edge_thickness * multiplicity.max(1) as f32).min(2.0 * vertex_radius);
+            painter.line_segment(
+                [origin + start, origin + end],
+                egui::Stroke::new(thickness, egui::Color32::GRAY),
+            );
+            if multiplicity > 1 {
+                painter.text(
+                    origin + (start + end) / 2.0,
+                    egui::Align2::CENTER_CENTER,
+                    multiplicity.to_string(),
+                    egui::FontId::proportional(12.0),
+                    egui::Color32::DARK_BLUE,
+                );
+            }
+        }
+    }
+
+    for (i, &offset) in positions.iter().enumerate() {
+        let pos = origin + offset;
+        let fill = fills.get(i).copied().unwrap_or(egui::Color32::WHITE);
+
+        painter.circle_filled(pos, vertex_radius, fill);
+        painter.circle_stroke(pos, vertex_radius, egui::Stroke::new(2.0, egui::Color32::BLACK));
+
+        if let Some(label) = labels.get(i) {
+            painter.text(
+                pos,
+                egui::Align2::CENTER_CENTER,
+                label,
+                egui::FontId::proportional(14.0),
+                egui::Color32::BLACK,
+            );
+        }
+    }
+}
+
+/// Draws `model` on its graph at `positions` (offsets from `origin`), using the model's
+/// edges, fill colors and labels
+pub fn draw_model(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    positions: &[egui::Vec2],
+    model: &impl Visualizable,
+    style: NetworkStyle,
+) {
+    let colors = GridColors::get(painter.ctx());
+    let fills: Vec<egui::Color32> = (0..model.len()).map(|i| model.color(i, &colors)).collect();
+    let labels: Vec<String> = (0..model.len()).map(|i| model.label(i).unwrap_or_default()).collect();
+    draw_weighted_network(painter, origin, positions, &model.edges(), &labels, &fills, style);
+}
+
+/// Draws an arrow from the edge of node `from` towards node `to`, e.g. to show a rotor direction
+pub fn draw_node_arrow(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    from: egui::Vec2,
+    to: egui::Vec2,
+    vertex_radius: f32,
+    color: egui::Color32,
+) {
+    let direction = to - from;
+    let length = direction.length();
+    if length <= f32::EPSILON {
+        return;
+    }
+    let unit = direction / length;
+    let start = origin + from + unit * vertex_radius;
+    let arrow_length = (length * 0.5 - vertex_radius).max(vertex_radius * 0.5);
+    painter.arrow(start, unit * arrow_length, egui::Stroke::new(2.0, color));
+}
+
+/// Returns the index of the node whose circle contains `pointer`, if any
+pub fn node_at(positions: &[egui::Vec2], origin: egui::Pos2, pointer: egui::Pos2, vertex_radius: f32) -> Option<usize> {
+    positions
+        .iter()
+        .position(|&node_pos| ((origin + node_pos) - pointer).length() <= vertex_radius)
+}
@@ -0,0 +1,22 @@
+use eframe::egui;
+
+/// Draws a seed field with buttons to apply it or pick a new random one.
+///
+/// # Returns
+///
+/// true if the window's random number generator should be re-seeded from `seed`
+pub fn seed_control(ui: &mut egui::Ui, seed: &mut u64) -> bool {
+    ui.horizontal(|ui| {
+        ui.label("Seed:");
+        ui.add(egui::DragValue::new(seed).speed(1.0));
+        let mut reseed = ui.button("Apply")
+            .on_hover_text("Restart the random number generator from this seed")
+            .clicked();
+        if ui.button("New").on_hover_text("Pick a new random seed").clicked() {
+            *seed = rand::random::<u32>() as u64;
+            reseed = true;
+        }
+        reseed
+    })
+    .inner
+}
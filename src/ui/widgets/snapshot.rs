@@ -0,0 +1,65 @@
+use eframe::egui;
+
+/// Settings for saving individual views as PNG images
+pub struct SnapshotExport {
+    /// File name prefix; each view is written to `<prefix>_<view>.png`
+    pub prefix: String,
+    /// Pixels per grid cell or matrix entry
+    pub scale: u32,
+    /// Size of exported plots in pixels
+    pub plot_size: [u32; 2],
+}
+
+impl SnapshotExport {
+    pub fn new(prefix: &str) -> Self {
+        SnapshotExport {
+            prefix: prefix.to_string(),
+            scale: 8,
+            plot_size: [800, 400],
+        }
+    }
+
+    /// Output file for the view named `view`
+    pub fn path_for(&self, view: &str) -> String {
+        format!("{}_{}.png", self.prefix, view.to_lowercase().replace(' ', "_"))
+    }
+
+    /// Draws the export settings and one "Save as PNG" button per view.
+    ///
+    /// # Arguments
+    ///
+    /// * `views` - View names with whether they can currently be saved
+    ///
+    /// # Returns
+    ///
+    /// The index of the view whose button was clicked, if any
+    pub fn show(&mut self, ui: &mut egui::Ui, views: &[(&str, bool)]) -> Option<usize> {
+        ui.horizontal(|ui| {
+            ui.label("File Prefix:");
+            ui.text_edit_singleline(&mut self.prefix);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Cell Size:");
+            ui.add(egui::DragValue::new(&mut self.scale).speed(0.2).range(1..=64).suffix(" px"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Plot Size:");
+            ui.add(egui::DragValue::new(&mut self.plot_size[0]).speed(10.0).range(100..=8000));
+            ui.label("x");
+            ui.add(egui::DragValue::new(&mut self.plot_size[1]).speed(10.0).range(100..=8000));
+        });
+
+        let mut clicked = None;
+        ui.horizontal_wrapped(|ui| {
+            for (i, &(view, enabled)) in views.iter().enumerate() {
+                if ui.add_enabled(enabled, egui::Button::new(view))
+                    .on_hover_text(format!("Save as {}", self.path_for(view)))
+                    .clicked()
+                {
+                    clicked = Some(i);
+                }
+            }
+        });
+        clicked
+    }
+}
@@ -0,0 +1,43 @@
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
+
+use crate::core::graph::LaplacianSpectrum;
+
+/// Largest graph whose dense Laplacian is diagonalized from the UI
+pub const MAX_SPECTRUM_VERTICES: usize = 1500;
+
+/// Labels with the spectral gap, largest eigenvalue and number of components, plus the
+/// smallest eigenvalue and spanning-tree count of the reduced Laplacian if given
+pub fn spectrum_summary(ui: &mut egui::Ui, spectrum: &LaplacianSpectrum, reduced: Option<&LaplacianSpectrum>) {
+    ui.label(format!(
+        "λ₂ (spectral gap) = {:.4}   λ_max = {:.4}   components = {}",
+        spectrum.spectral_gap(), spectrum.largest(), spectrum.zero_count()
+    ))
+    .on_hover_text("A larger gap means faster mixing of random walks and faster relaxation");
+    if let Some(reduced) = reduced {
+        ui.label(format!(
+            "Reduced (sink removed): λ_min = {:.4}   log₁₀ spanning trees = {:.2}",
+            reduced.smallest(), reduced.log10_determinant()
+        ))
+        .on_hover_text("The number of spanning trees is the number of recurrent sandpile configurations");
+    }
+}
+
+/// Eigenvalues against their index, full spectrum as points and reduced spectrum as a line
+pub fn spectrum_plot(ui: &mut egui::Ui, id: &str, spectrum: &LaplacianSpectrum, reduced: Option<&LaplacianSpectrum>, height: f32) {
+    let indexed = |values: &[f64]| -> PlotPoints {
+        values.iter().enumerate().map(|(k, &l)| [k as f64, l]).collect()
+    };
+    Plot::new(id)
+        .height(height)
+        .legend(Legend::default())
+        .include_y(0.0)
+        .x_axis_label("k")
+        .y_axis_label("λ_k")
+        .show(ui, |plot_ui| {
+            plot_ui.points(Points::new(indexed(&spectrum.eigenvalues)).radius(2.0).name("Laplacian"));
+            if let Some(reduced) = reduced {
+                plot_ui.line(Line::new(indexed(&reduced.eigenvalues)).name("Reduced"));
+            }
+        });
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use eframe::egui;
+use eframe::egui_wgpu;
+use eframe::wgpu;
+
+use crate::graphics::renderer::{Renderer, StateTextureRenderer};
+use crate::ui::notifications::report_error;
+
+/// The state texture renderers of all widgets, kept in the egui renderer's callback
+/// resources so they outlive a frame
+struct StateTextures {
+    target_format: wgpu::TextureFormat,
+    renderers: HashMap<egui::Id, StateTextureRenderer>,
+}
+
+fn available_id() -> egui::Id {
+    egui::Id::new("raum_state_textures")
+}
+
+/// Registers the renderers with the wgpu backend of egui. Until this is called,
+/// `is_available` is false and windows fall back to egui textures.
+pub fn install(ctx: &egui::Context, render_state: &egui_wgpu::RenderState) {
+    render_state.renderer.write().callback_resources.insert(StateTextures {
+        target_format: render_state.target_format,
+        renderers: HashMap::new(),
+    });
+    ctx.data_mut(|data| data.insert_temp(available_id(), true));
+}
+
+/// Whether `state_texture` can be used
+pub fn is_available(ctx: &egui::Context) -> bool {
+    ctx.data(|data| data.get_temp(available_id())).unwrap_or(false)
+}
+
+/// RGBA bytes of `pixels` in row order, the layout the state textures are uploaded in
+pub fn rgba_bytes(pixels: &[egui::Color32]) -> Vec<u8> {
+    pixels.iter().flat_map(|c| c.to_array()).collect()
+}
+
+/// Paint callback uploading one frame and drawing it
+struct StateTextureCallback {
+    id: egui::Id,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl egui_wgpu::CallbackTrait for StateTextureCallback {
+    fn prepare(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _screen_descriptor: &egui_wgpu::ScreenDescriptor,
+        _egui_encoder: &mut wgpu::CommandEncoder,
+        callback_resources: &mut egui_wgpu::CallbackResources,
+    ) -> Vec<wgpu::CommandBuffer> {
+        let Some(textures) = callback_resources.get_mut::<StateTextures>() else {
+            return Vec::new();
+        };
+        let target_format = textures.target_format;
+        let renderer = textures
+            .renderers
+            .entry(self.id)
+            .or_insert_with(|| StateTextureRenderer::new(target_format, self.width, self.height));
+        if !renderer.is_initialized() {
+            if let Err(e) = renderer.initialize(device, queue) {
+                report_error(format!("Failed to create state texture: {}", e));
+                return Vec::new();
+            }
+        }
+        renderer.resize(device, self.width, self.height);
+        if let Err(e) = renderer.update(queue, &self.rgba) {
+            report_error(format!("Failed to upload state texture: {}", e));
+        }
+        Vec::new()
+    }
+
+    fn paint<'a>(
+        &'a self,
+        _info: egui::PaintCallbackInfo,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        callback_resources: &'a egui_wgpu::CallbackResources,
+    ) {
+        if let Some(renderer) = callback_resources.get::<StateTextures>().and_then(|t| t.renderers.get(&self.id)) {
+            renderer.paint(render_pass);
+        }
+    }
+}
+
+/// Draws `image` scaled to `size` through a double-buffered GPU texture, bypassing
+/// egui's texture manager. Only call this when `is_available` is true.
+///
+/// # Arguments
+///
+/// * `id_source` - Identifies the texture pair, which is reused across frames
+pub fn state_texture(
+    ui: &mut egui::Ui,
+    id_source: impl std::hash::Hash,
+    image: &egui::ColorImage,
+    size: egui::Vec2,
+    sense: egui::Sense,
+) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(size, sense);
+    let callback = StateTextureCallback {
+        id: ui.id().with(id_source),
+        width: image.size[0] as u32,
+        height: image.size[1] as u32,
+        rgba: rgba_bytes(&image.pixels),
+    };
+    ui.painter().add(egui_wgpu::Callback::new_paint_callback(rect, callback));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgba_bytes_in_row_order() {
+        let pixels = [egui::Color32::from_rgb(1, 2, 3), egui::Color32::from_rgb(4, 5, 6)];
+        assert_eq!(rgba_bytes(&pixels), vec![1, 2, 3, 255, 4, 5, 6, 255]);
+    }
+}
@@ -0,0 +1,138 @@
+use eframe::egui;
+
+/// Playback of a recorded history: play/pause and single-step transport controls that
+/// move an index through the history at a configurable frame rate
+pub struct Timeline {
+    pub playing: bool,
+    /// Frames shown per second while playing
+    pub fps: f64,
+    /// Start again from the first frame after the last one instead of pausing
+    pub looping: bool,
+    last_frame_time: f64,
+}
+
+impl Timeline {
+    pub fn new(fps: f64) -> Self {
+        Timeline {
+            playing: false,
+            fps,
+            looping: false,
+            last_frame_time: 0.0,
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Moves `index` on by the frames due at time `now` (in seconds) while playing.
+    ///
+    /// # Returns
+    ///
+    /// true if `index` changed
+    fn advance(&mut self, now: f64, index: &mut usize, len: usize) -> bool {
+        if !self.playing || len == 0 {
+            return false;
+        }
+        let interval = 1.0 / self.fps.max(0.1);
+        let frames = ((now - self.last_frame_time) / interval).floor();
+        if frames < 1.0 {
+            return false;
+        }
+        self.last_frame_time += frames * interval;
+
+        let last = len - 1;
+        let target = *index + frames as usize;
+        let next = if target <= last {
+            target
+        } else if self.looping {
+            target % len
+        } else {
+            self.playing = false;
+            last
+        };
+        let changed = next != *index;
+        *index = next;
+        changed
+    }
+
+    /// Draws the transport controls for a history of `len` entries and advances `index`
+    /// while playing. Playing from the last entry starts again from the first.
+    ///
+    /// # Returns
+    ///
+    /// true if `index` changed
+    pub fn show(&mut self, ui: &mut egui::Ui, index: &mut usize, len: usize) -> bool {
+        let now = ui.input(|i| i.time);
+        let last = len.saturating_sub(1);
+        let before = *index;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(*index > 0, egui::Button::new("|<")).on_hover_text("First").clicked() {
+                self.playing = false;
+                *index = 0;
+            }
+            if ui.add_enabled(*index > 0, egui::Button::new("<")).on_hover_text("Step back").clicked() {
+                self.playing = false;
+                *index -= 1;
+            }
+            let label = if self.playing { "Pause" } else { "Play" };
+            if ui.add_enabled(len > 1, egui::Button::new(label)).clicked() {
+                self.playing = !self.playing;
+                if self.playing {
+                    if *index >= last {
+                        *index = 0;
+                    }
+                    self.last_frame_time = now;
+                }
+            }
+            if ui.add_enabled(*index < last, egui::Button::new(">")).on_hover_text("Step forward").clicked() {
+                self.playing = false;
+                *index += 1;
+            }
+            if ui.add_enabled(*index < last, egui::Button::new(">|")).on_hover_text("Last").clicked() {
+                self.playing = false;
+                *index = last;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("FPS:");
+            ui.add(egui::DragValue::new(&mut self.fps).speed(0.5).range(0.5..=60.0));
+            ui.checkbox(&mut self.looping, "Loop");
+        });
+
+        self.advance(now, index, len);
+        if self.playing {
+            ui.ctx().request_repaint();
+        }
+        *index != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance_plays_at_fps_and_stops_at_end() {
+        let mut timeline = Timeline::new(10.0);
+        timeline.playing = true;
+        let mut index = 0;
+
+        assert!(!timeline.advance(0.05, &mut index, 4));
+        assert!(timeline.advance(0.1, &mut index, 4));
+        assert_eq!(index, 1);
+        // Frames missed between calls are skipped over
+        assert!(timeline.advance(0.35, &mut index, 4));
+        assert_eq!(index, 3);
+        assert!(timeline.playing);
+        assert!(!timeline.advance(0.45, &mut index, 4));
+        assert_eq!(index, 3);
+        assert!(!timeline.playing);
+
+        timeline.looping = true;
+        timeline.playing = true;
+        assert!(timeline.advance(0.55, &mut index, 4));
+        assert_eq!(index, 0);
+    }
+}
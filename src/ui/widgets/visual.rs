@@ -0,0 +1,260 @@
+use eframe::egui;
+
+use crate::neural::chip_firing::ChipFiringGraph;
+use crate::ui::app::GridColors;
+
+/// Fill color of the sink vertex
+pub const SINK_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 110, 170);
+
+/// Fill colors of successive avalanche waves, repeating after the last
+pub const WAVE_COLORS: [egui::Color32; 6] = [
+    egui::Color32::from_rgb(240, 200, 60),
+    egui::Color32::from_rgb(240, 140, 60),
+    egui::Color32::from_rgb(220, 70, 70),
+    egui::Color32::from_rgb(170, 80, 190),
+    egui::Color32::from_rgb(80, 120, 220),
+    egui::Color32::from_rgb(60, 180, 170),
+];
+
+/// Fill color of wave `index` (counting from 0)
+pub fn wave_color(index: usize) -> egui::Color32 {
+    WAVE_COLORS[index % WAVE_COLORS.len()]
+}
+
+/// How a model state is laid out and colored on screen, so that the grid and network
+/// painters can draw any model without knowing what its values mean
+pub trait Visualizable {
+    /// Number of cells or vertices
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Columns and rows when the units live on a grid, stored row by row
+    fn grid_shape(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Edges as (i, j, multiplicity), each undirected edge listed once
+    fn edges(&self) -> Vec<(usize, usize, u32)> {
+        Vec::new()
+    }
+
+    /// Fill color of unit `index`
+    fn color(&self, index: usize, colors: &GridColors) -> egui::Color32;
+
+    /// Text drawn on unit `index`
+    fn label(&self, _index: usize) -> Option<String> {
+        None
+    }
+
+    /// Hover text for unit `index`
+    fn describe(&self, index: usize) -> String {
+        format!("Unit {}", index)
+    }
+}
+
+/// A ±1 state on a grid `width` cells wide, e.g. a Hopfield pattern or Ising spins
+#[derive(Debug, Clone, Copy)]
+pub struct SpinGrid<'a> {
+    pub state: &'a [f64],
+    pub width: usize,
+}
+
+impl Visualizable for SpinGrid<'_> {
+    fn len(&self) -> usize {
+        self.state.len()
+    }
+
+    fn grid_shape(&self) -> Option<(usize, usize)> {
+        (self.width > 0).then(|| (self.width, self.state.len().div_ceil(self.width)))
+    }
+
+    fn color(&self, index: usize, colors: &GridColors) -> egui::Color32 {
+        let state = self.state.get(index).copied().unwrap_or(0.0);
+        if state == 1.0 {
+            colors.on
+        } else if state == -1.0 {
+            colors.off
+        } else {
+            egui::Color32::GRAY // Should not happen with valid states
+        }
+    }
+
+    fn describe(&self, index: usize) -> String {
+        let state = self.state.get(index).copied().unwrap_or(0.0);
+        let (x, y) = (index % self.width.max(1), index / self.width.max(1));
+        format!("Neuron {} ({}, {}): {:+}", index, x, y, state)
+    }
+}
+
+/// Gray values in [0, 1] on a grid `width` cells wide, 0 drawn black and 1 white, e.g.
+/// the quantized images of a Potts network
+#[derive(Debug, Clone, Copy)]
+pub struct GrayGrid<'a> {
+    pub values: &'a [f64],
+    pub width: usize,
+}
+
+impl Visualizable for GrayGrid<'_> {
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn grid_shape(&self) -> Option<(usize, usize)> {
+        (self.width > 0).then(|| (self.width, self.values.len().div_ceil(self.width)))
+    }
+
+    fn color(&self, index: usize, _colors: &GridColors) -> egui::Color32 {
+        let value = self.values.get(index).copied().unwrap_or(0.0);
+        egui::Color32::from_gray((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    fn describe(&self, index: usize) -> String {
+        let value = self.values.get(index).copied().unwrap_or(0.0);
+        let (x, y) = (index % self.width.max(1), index / self.width.max(1));
+        format!("Cell {} ({}, {}): gray {:.2}", index, x, y, value)
+    }
+}
+
+/// A chip configuration on its graph, labeled with chip counts and colored by vertex
+/// role: selected, sink, toppled in an avalanche wave, active or idle
+#[derive(Debug, Clone)]
+pub struct ChipView<'a> {
+    graph: &'a ChipFiringGraph,
+    configuration: &'a [i32],
+    active: Vec<bool>,
+    selected: Option<usize>,
+    grid_width: Option<usize>,
+    /// Last avalanche wave each vertex toppled in, if any
+    last_wave: Vec<Option<usize>>,
+}
+
+impl<'a> ChipView<'a> {
+    /// `configuration` (e.g. one from history) on `graph`; active vertices are only
+    /// highlighted when `show_active` is set
+    pub fn new(graph: &'a ChipFiringGraph, configuration: &'a [i32], show_active: bool, selected: Option<usize>) -> Self {
+        let mut active = vec![false; graph.num_vertices];
+        if show_active {
+            for vertex in graph.active_in(configuration) {
+                active[vertex] = true;
+            }
+        }
+        ChipView { graph, configuration, active, selected, grid_width: None, last_wave: Vec::new() }
+    }
+
+    /// Color the vertices by the waves of an avalanche (see
+    /// `ChipFiringGraph::avalanche_waves`). Later waves are stacked on top of earlier ones,
+    /// so each vertex shows the last wave it toppled in.
+    pub fn with_waves(mut self, waves: &[Vec<usize>]) -> Self {
+        let mut last_wave = vec![None; self.graph.num_vertices];
+        for (index, wave) in waves.iter().enumerate() {
+            for &vertex in wave {
+                if let Some(slot) = last_wave.get_mut(vertex) {
+                    *slot = Some(index);
+                }
+            }
+        }
+        self.last_wave = last_wave;
+        self
+    }
+
+    /// Lay the vertices out on a grid `width` columns wide, for grid graphs
+    pub fn with_grid_width(mut self, width: usize) -> Self {
+        self.grid_width = Some(width);
+        self
+    }
+}
+
+impl Visualizable for ChipView<'_> {
+    fn len(&self) -> usize {
+        self.graph.num_vertices
+    }
+
+    fn grid_shape(&self) -> Option<(usize, usize)> {
+        self.grid_width
+            .filter(|&width| width > 0)
+            .map(|width| (width, self.graph.num_vertices.div_ceil(width)))
+    }
+
+    fn edges(&self) -> Vec<(usize, usize, u32)> {
+        (0..self.graph.num_vertices)
+            .flat_map(|i| self.graph.neighbor_edges(i).filter(move |&(j, _)| i < j).map(move |(j, count)| (i, j, count)))
+            .collect()
+    }
+
+    fn color(&self, index: usize, colors: &GridColors) -> egui::Color32 {
+        if Some(index) == self.selected {
+            colors.selected
+        } else if Some(index) == self.graph.sink() {
+            SINK_COLOR
+        } else if let Some(wave) = self.last_wave.get(index).copied().flatten() {
+            wave_color(wave)
+        } else if self.active.get(index).copied().unwrap_or(false) {
+            colors.active
+        } else {
+            colors.off
+        }
+    }
+
+    fn label(&self, index: usize) -> Option<String> {
+        self.configuration.get(index).map(|chips| chips.to_string())
+    }
+
+    fn describe(&self, index: usize) -> String {
+        let chips = self.configuration.get(index).copied().unwrap_or(0);
+        let degree = self.graph.degrees.get(index).copied().unwrap_or(0);
+        let mut text = match &self.graph.metadata(index).label {
+            Some(label) => format!("Vertex {} ({}): {} chips, degree {}", index, label, chips, degree),
+            None => format!("Vertex {}: {} chips, degree {}", index, chips, degree),
+        };
+        let threshold = self.graph.threshold(index);
+        if threshold != degree {
+            text.push_str(&format!(", threshold {}", threshold));
+        }
+        if let Some(wave) = self.last_wave.get(index).copied().flatten() {
+            text.push_str(&format!(", last toppled in wave {}", wave + 1));
+        }
+        if Some(index) == self.graph.sink() {
+            text.push_str(" (sink)");
+        } else if self.active.get(index).copied().unwrap_or(false) {
+            text.push_str(" (active)");
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::graph::Graph;
+
+    #[test]
+    fn test_chip_view_colors_vertex_roles() {
+        let mut graph = ChipFiringGraph::from_graph(&Graph::grid(2, 2), vec![2, 0, 0, 1]).unwrap();
+        graph.set_sink(Some(3)).unwrap();
+        let configuration = graph.configuration.clone();
+        let view = ChipView::new(&graph, &configuration, true, Some(1)).with_grid_width(2);
+        let colors = GridColors::default();
+
+        assert_eq!(view.grid_shape(), Some((2, 2)));
+        assert_eq!(view.edges().len(), 4);
+        assert_eq!(view.color(0, &colors), colors.active);
+        assert_eq!(view.color(1, &colors), colors.selected);
+        assert_eq!(view.color(2, &colors), colors.off);
+        assert_eq!(view.color(3, &colors), SINK_COLOR);
+        assert_eq!(view.label(0).as_deref(), Some("2"));
+        assert!(view.describe(0).ends_with("2 chips, degree 2 (active)"));
+
+        let spins = SpinGrid { state: &[1.0, -1.0, 1.0], width: 2 };
+        assert_eq!(spins.grid_shape(), Some((2, 2)));
+        assert_eq!(spins.color(1, &colors), colors.off);
+        assert_eq!(spins.describe(2), "Neuron 2 (0, 1): +1");
+
+        let gray = GrayGrid { values: &[0.0, 0.5, 1.0, 2.0], width: 2 };
+        assert_eq!(gray.color(0, &colors), egui::Color32::BLACK);
+        assert_eq!(gray.color(1, &colors), egui::Color32::from_gray(128));
+        assert_eq!(gray.color(3, &colors), egui::Color32::WHITE);
+    }
+}
@@ -0,0 +1,301 @@
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::core::metrics::overlap;
+use crate::neural::bam::{BamRecall, BidirectionalMemory, RecallDirection};
+use crate::ui::notifications::report_error;
+use crate::ui::session::{self, SessionAction};
+use crate::ui::widgets::grid::{apply_noise, draw_grid};
+use crate::ui::widgets::seed::seed_control;
+use crate::ui::windows::hopfield::HopfieldWindow;
+use crate::ui::windows::{Command, Window};
+
+/// Side of the drawn grids in points
+const GRID_VIEW_SIZE: f32 = 120.0;
+
+/// Window for the bidirectional associative memory, pairing each letter with its
+/// lowercase form on grids of different sizes
+pub struct BamWindow {
+    /// Pair settings: uppercase letters on the X grid, lowercase on the Y grid
+    letters: String,
+    x_grid_size: usize,
+    y_grid_size: usize,
+
+    /// Trained memory and the pairs it stores
+    memory: Option<BidirectionalMemory>,
+    pairs: Vec<(char, Vec<f64>, Vec<f64>)>,
+
+    /// Recall settings and the last recall with its cue
+    selected_pair: usize,
+    direction: RecallDirection,
+    noise_level: f32,
+    max_iterations: usize,
+    cue: Option<Vec<f64>>,
+    recall: Option<BamRecall>,
+
+    seed: u64,
+    rng: StdRng,
+}
+
+impl BamWindow {
+    pub fn new() -> Self {
+        let seed = rand::random::<u32>() as u64;
+        Self {
+            letters: "ABCD".to_string(),
+            x_grid_size: 12,
+            y_grid_size: 8,
+            memory: None,
+            pairs: Vec::new(),
+            selected_pair: 0,
+            direction: RecallDirection::Forward,
+            noise_level: 0.1,
+            max_iterations: 20,
+            cue: None,
+            recall: None,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Render the letter pairs and store them
+    fn train(&mut self) {
+        let upper: Vec<char> = self.letters.chars().filter(|c| c.is_alphabetic()).map(|c| c.to_ascii_uppercase()).collect();
+        if upper.is_empty() {
+            report_error("Enter at least one letter to store");
+            return;
+        }
+        let lower: Vec<char> = upper.iter().map(|c| c.to_ascii_lowercase()).collect();
+        let xs = HopfieldWindow::get_patterns(self.x_grid_size, &upper);
+        let ys = HopfieldWindow::get_patterns(self.y_grid_size, &lower);
+        let pairs: Vec<(char, Vec<f64>, Vec<f64>)> =
+            xs.into_iter().zip(ys).map(|((c, x), (_, y))| (c, x, y)).collect();
+
+        let x_size = self.x_grid_size * self.x_grid_size;
+        let y_size = self.y_grid_size * self.y_grid_size;
+        let mut memory = match BidirectionalMemory::try_new(x_size, y_size) {
+            Ok(memory) => memory,
+            Err(e) => {
+                report_error(e.to_string());
+                return;
+            },
+        };
+        let training: Vec<(Vec<f64>, Vec<f64>)> = pairs.iter().map(|(_, x, y)| (x.clone(), y.clone())).collect();
+        if let Err(e) = memory.train(&training) {
+            report_error(format!("Training failed: {}", e));
+            return;
+        }
+        self.memory = Some(memory);
+        self.pairs = pairs;
+        self.selected_pair = self.selected_pair.min(self.pairs.len() - 1);
+        self.cue = None;
+        self.recall = None;
+    }
+
+    /// Recall the selected pair from a noisy copy of one of its patterns
+    fn recall_selected(&mut self) {
+        let (Some(memory), Some((_, x, y))) = (&self.memory, self.pairs.get(self.selected_pair)) else {
+            return;
+        };
+        let clean = match self.direction {
+            RecallDirection::Forward => x,
+            RecallDirection::Backward => y,
+        };
+        let cue = apply_noise(clean, self.noise_level, &mut self.rng);
+        match memory.recall(&cue, self.direction, self.max_iterations) {
+            Ok(recall) => {
+                self.cue = Some(cue);
+                self.recall = Some(recall);
+            },
+            Err(e) => report_error(format!("Recall failed: {}", e)),
+        }
+    }
+
+    /// Apply a setting from a session journal
+    fn apply_session_setting(&mut self, key: &str, value: &str) -> bool {
+        match key {
+            "letters" => self.letters = value.to_string(),
+            "direction" => {
+                self.direction = match value {
+                    "Forward" => RecallDirection::Forward,
+                    "Backward" => RecallDirection::Backward,
+                    _ => return false,
+                };
+            },
+            "noise_level" => match value.parse() {
+                Ok(noise_level) => self.noise_level = noise_level,
+                Err(_) => return false,
+            },
+            _ => {
+                let Ok(number) = value.parse::<u64>() else { return false };
+                match key {
+                    "x_grid_size" => self.x_grid_size = number as usize,
+                    "y_grid_size" => self.y_grid_size = number as usize,
+                    "pair" => self.selected_pair = number as usize,
+                    "max_iterations" => self.max_iterations = number as usize,
+                    "seed" => {
+                        self.seed = number;
+                        self.rng = StdRng::seed_from_u64(number);
+                    },
+                    _ => return false,
+                }
+            },
+        }
+        true
+    }
+
+    /// A state on a square grid under `title`, with its overlap with `target`
+    fn draw_state(ui: &mut egui::Ui, title: &str, state: Option<&Vec<f64>>, size: usize, target: Option<&Vec<f64>>) {
+        ui.vertical_centered(|ui| {
+            ui.label(title);
+            ui.separator();
+            match state {
+                Some(state) if state.len() == size * size => {
+                    draw_grid(ui, state, size, size, GRID_VIEW_SIZE / size as f32);
+                    if let Some(target) = target {
+                        ui.label(format!("m = {:.2}", overlap(state, target)));
+                    }
+                },
+                _ => {
+                    ui.label("—");
+                },
+            }
+        });
+    }
+}
+
+impl Window for BamWindow {
+    fn name(&self) -> &str {
+        "Bidirectional Memory"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Train => self.train(),
+            Command::Run | Command::Step => self.recall_selected(),
+            Command::Reset => {
+                self.cue = None;
+                self.recall = None;
+            },
+        }
+        true
+    }
+
+    fn session_settings(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("letters", self.letters.clone()),
+            ("x_grid_size", self.x_grid_size.to_string()),
+            ("y_grid_size", self.y_grid_size.to_string()),
+            ("pair", self.selected_pair.to_string()),
+            ("direction", format!("{:?}", self.direction)),
+            ("noise_level", self.noise_level.to_string()),
+            ("max_iterations", self.max_iterations.to_string()),
+            ("seed", self.seed.to_string()),
+        ]
+    }
+
+    fn replay(&mut self, action: &SessionAction) -> bool {
+        match action {
+            SessionAction::Command(command) => self.handle_command(*command),
+            SessionAction::Set { key, value } => self.apply_session_setting(key, value),
+            SessionAction::Perform { .. } => false,
+        }
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Pairs");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Letters:");
+            ui.text_edit_singleline(&mut self.letters)
+                .on_hover_text("Each letter is paired with its lowercase form");
+        });
+        ui.horizontal(|ui| {
+            ui.label("X Grid:");
+            ui.add(egui::DragValue::new(&mut self.x_grid_size).speed(1.0).range(4..=32));
+            ui.label("Y Grid:");
+            ui.add(egui::DragValue::new(&mut self.y_grid_size).speed(1.0).range(4..=32));
+        });
+        if ui.button("Train").on_hover_text("Store the pairs with the correlation rule W = Σ y xᵀ").clicked() {
+            session::record(self.name(), SessionAction::Command(Command::Train));
+            self.train();
+        }
+
+        ui.separator();
+        ui.heading("Recall");
+        ui.separator();
+
+        ui.add_enabled_ui(self.memory.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Pair:");
+                egui::ComboBox::from_id_source("bam_pair")
+                    .selected_text(self.pairs.get(self.selected_pair).map(|p| p.0.to_string()).unwrap_or_default())
+                    .show_ui(ui, |ui| {
+                        for (i, (c, _, _)) in self.pairs.iter().enumerate() {
+                            ui.selectable_value(&mut self.selected_pair, i, format!("{} ↔ {}", c, c.to_ascii_lowercase()));
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.direction, RecallDirection::Forward, "Forward (X → Y)");
+                ui.radio_value(&mut self.direction, RecallDirection::Backward, "Backward (Y → X)");
+            });
+            ui.add(egui::Slider::new(&mut self.noise_level, 0.0..=0.5).text("Cue Noise"));
+            ui.horizontal(|ui| {
+                ui.label("Max Iterations:");
+                ui.add(egui::DragValue::new(&mut self.max_iterations).speed(1.0).range(1..=200));
+            });
+            if ui.button("Recall").clicked() {
+                session::record(self.name(), SessionAction::Command(Command::Run));
+                self.recall_selected();
+            }
+        });
+        if seed_control(ui, &mut self.seed) {
+            self.rng = StdRng::seed_from_u64(self.seed);
+        }
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        ui.heading("Bidirectional Associative Memory");
+        ui.separator();
+
+        let Some(memory) = &self.memory else {
+            ui.vertical_centered(|ui| {
+                ui.label("No memory trained yet. Enter letters and press Train.");
+            });
+            return;
+        };
+        ui.label(format!(
+            "{} pairs, X layer {} units, Y layer {} units",
+            self.pairs.len(),
+            memory.x_size(),
+            memory.y_size()
+        ));
+
+        let (xs, ys) = (self.x_grid_size, self.y_grid_size);
+        let pair = self.pairs.get(self.selected_pair);
+        let (target_x, target_y) = (pair.map(|p| &p.1), pair.map(|p| &p.2));
+        let cue_size = match self.direction {
+            RecallDirection::Forward => xs,
+            RecallDirection::Backward => ys,
+        };
+        ui.columns(3, |columns| {
+            Self::draw_state(&mut columns[0], "Cue", self.cue.as_ref(), cue_size, None);
+            Self::draw_state(&mut columns[1], "X Layer", self.recall.as_ref().map(|r| &r.x), xs, target_x);
+            Self::draw_state(&mut columns[2], "Y Layer", self.recall.as_ref().map(|r| &r.y), ys, target_y);
+        });
+
+        if let Some(recall) = &self.recall {
+            ui.separator();
+            ui.label(format!("Settled after {} passes", recall.iterations));
+            let points: PlotPoints = recall.energies.iter().enumerate().map(|(i, &e)| [i as f64, e]).collect();
+            Plot::new("bam_energy_plot")
+                .height(150.0)
+                .x_axis_label("Pass")
+                .y_axis_label("E = -yᵀWx")
+                .show(ui, |plot_ui| plot_ui.line(Line::new(points)));
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::core::metrics::overlap;
+use crate::neural::annealing::{AnnealingSchedule, ScheduleKind};
+use crate::neural::boltzmann::BoltzmannMachine;
+use crate::neural::hopfield::{HopfieldNetwork, TrainingRule};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::grid::{apply_noise, draw_grid};
+use crate::ui::windows::Window;
+
+/// Recall quality of both models over a batch of noisy cues
+#[derive(Debug, Clone, Copy)]
+struct TrialSummary {
+    trials: usize,
+    hopfield_overlap: f64,
+    boltzmann_overlap: f64,
+    hopfield_success: f64,
+    boltzmann_success: f64,
+}
+
+/// Overlaps at or above this count as successful recall
+const SUCCESS_OVERLAP: f64 = 0.9;
+
+pub struct BoltzmannWindow {
+    /// Models trained on the same patterns
+    machine: Option<BoltzmannMachine>,
+    hopfield: Option<HopfieldNetwork>,
+
+    /// Patterns (±1, grid_size × grid_size)
+    grid_size: usize,
+    num_patterns: usize,
+    patterns: Vec<Vec<f64>>,
+
+    /// Training settings
+    num_hidden: usize,
+    learning_rate: f64,
+    epochs: usize,
+    training_schedule: AnnealingSchedule,
+    weight_changes: Vec<f64>,
+
+    /// Recall settings (the same schedule drives both models)
+    recall_schedule: AnnealingSchedule,
+    selected_pattern: usize,
+    noise_level: f32,
+    num_trials: usize,
+
+    /// Last recall
+    cue: Option<Vec<f64>>,
+    hopfield_output: Option<Vec<f64>>,
+    boltzmann_output: Option<Vec<f64>>,
+    trial_summary: Option<TrialSummary>,
+
+    /// Random number generator for patterns, noise and sampling
+    rng: ThreadRng,
+}
+
+impl BoltzmannWindow {
+    pub fn new() -> Self {
+        let mut window = Self {
+            machine: None,
+            hopfield: None,
+            grid_size: 5,
+            num_patterns: 3,
+            patterns: Vec::new(),
+            num_hidden: 8,
+            learning_rate: 0.1,
+            epochs: 50,
+            training_schedule: AnnealingSchedule::default(),
+            weight_changes: Vec::new(),
+            recall_schedule: AnnealingSchedule {
+                start_beta: 0.5,
+                end_beta: 3.0,
+                stages: 8,
+                sweeps_per_stage: 2,
+                kind: ScheduleKind::Geometric,
+            },
+            selected_pattern: 0,
+            noise_level: 0.15,
+            num_trials: 20,
+            cue: None,
+            hopfield_output: None,
+            boltzmann_output: None,
+            trial_summary: None,
+            rng: rand::thread_rng(),
+        };
+        window.generate_patterns();
+        window
+    }
+
+    /// Draw fresh random patterns; invalidates the trained models
+    fn generate_patterns(&mut self) {
+        let n = self.grid_size * self.grid_size;
+        self.patterns = (0..self.num_patterns)
+            .map(|_| (0..n).map(|_| if self.rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        self.machine = None;
+        self.hopfield = None;
+        self.weight_changes.clear();
+        self.clear_recall();
+    }
+
+    fn clear_recall(&mut self) {
+        self.cue = None;
+        self.hopfield_output = None;
+        self.boltzmann_output = None;
+        self.trial_summary = None;
+    }
+
+    /// Train a Hebbian Hopfield network and a Boltzmann machine on the patterns
+    fn train_models(&mut self) {
+        let n = self.grid_size * self.grid_size;
+
+        let mut hopfield = match HopfieldNetwork::try_new(n) {
+            Ok(hopfield) => hopfield,
+            Err(e) => {
+                report_error(e);
+                return;
+            },
+        };
+        if let Err(e) = hopfield.train(&self.patterns, TrainingRule::Hebbian) {
+            report_error(format!("Hopfield training failed: {}", e));
+            return;
+        }
+
+        let mut machine = match BoltzmannMachine::new(n, self.num_hidden, &mut self.rng) {
+            Ok(machine) => machine,
+            Err(e) => {
+                report_error(format!("Failed to create Boltzmann machine: {}", e));
+                return;
+            }
+        };
+        machine.learning_rate = self.learning_rate;
+        machine.schedule = self.training_schedule;
+
+        match machine.train(&self.patterns, self.epochs, &mut self.rng) {
+            Ok(changes) => {
+                self.weight_changes = changes;
+                self.machine = Some(machine);
+                self.hopfield = Some(hopfield);
+                self.clear_recall();
+            },
+            Err(e) => report_error(format!("Boltzmann training failed: {}", e)),
+        }
+    }
+
+    /// Recall `target` from a noisy cue with both models; returns (cue, hopfield, boltzmann)
+    fn recall_both(&mut self, target: &[f64]) -> Option<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+        let (Some(hopfield), Some(machine)) = (&self.hopfield, &self.machine) else {
+            return None;
+        };
+        let cue = apply_noise(target, self.noise_level, &mut self.rng);
+
+        let hopfield_output = hopfield
+            .run_annealed(&cue, &self.recall_schedule, &mut self.rng)
+            .ok()?
+            .into_states()
+            .pop()?;
+        let boltzmann_output = machine
+            .recall(&cue, &self.recall_schedule, &mut self.rng)
+            .ok()?
+            .pop()?;
+
+        Some((cue, hopfield_output, boltzmann_output))
+    }
+
+    fn recall_selected(&mut self) {
+        let Some(target) = self.patterns.get(self.selected_pattern).cloned() else {
+            return;
+        };
+        match self.recall_both(&target) {
+            Some((cue, hopfield_output, boltzmann_output)) => {
+                self.cue = Some(cue);
+                self.hopfield_output = Some(hopfield_output);
+                self.boltzmann_output = Some(boltzmann_output);
+            },
+            None => report_error("Recall failed; train the models first".to_string()),
+        }
+    }
+
+    /// Recall every pattern `num_trials` times from fresh noisy cues and average the results
+    fn run_trials(&mut self) {
+        let mut summary = TrialSummary {
+            trials: 0,
+            hopfield_overlap: 0.0,
+            boltzmann_overlap: 0.0,
+            hopfield_success: 0.0,
+            boltzmann_success: 0.0,
+        };
+
+        for _ in 0..self.num_trials {
+            for target in self.patterns.clone() {
+                let Some((_, hopfield_output, boltzmann_output)) = self.recall_both(&target) else {
+                    report_error("Recall failed; train the models first".to_string());
+                    return;
+                };
+                let h = overlap(&hopfield_output, &target);
+                let b = overlap(&boltzmann_output, &target);
+                summary.trials += 1;
+                summary.hopfield_overlap += h;
+                summary.boltzmann_overlap += b;
+                summary.hopfield_success += (h >= SUCCESS_OVERLAP) as u8 as f64;
+                summary.boltzmann_success += (b >= SUCCESS_OVERLAP) as u8 as f64;
+            }
+        }
+
+        if summary.trials > 0 {
+            let n = summary.trials as f64;
+            summary.hopfield_overlap /= n;
+            summary.boltzmann_overlap /= n;
+            summary.hopfield_success /= n;
+            summary.boltzmann_success /= n;
+            self.trial_summary = Some(summary);
+        }
+    }
+}
+
+/// Controls for editing an annealing schedule
+fn schedule_editor(ui: &mut egui::Ui, id: &str, schedule: &mut AnnealingSchedule) {
+    egui::Grid::new(id).show(ui, |ui| {
+        ui.label("β start:");
+        ui.add(egui::DragValue::new(&mut schedule.start_beta).speed(0.01).range(0.01..=10.0));
+        ui.label("β end:");
+        ui.add(egui::DragValue::new(&mut schedule.end_beta).speed(0.01).range(0.01..=10.0));
+        ui.end_row();
+        ui.label("Stages:");
+        ui.add(egui::DragValue::new(&mut schedule.stages).speed(1.0).range(1..=100));
+        ui.label("Sweeps/Stage:");
+        ui.add(egui::DragValue::new(&mut schedule.sweeps_per_stage).speed(1.0).range(1..=100));
+        ui.end_row();
+    });
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut schedule.kind, ScheduleKind::Geometric, "Geometric");
+        ui.radio_value(&mut schedule.kind, ScheduleKind::Linear, "Linear");
+    });
+}
+
+impl Window for BoltzmannWindow {
+    fn name(&self) -> &str {
+        "Boltzmann Machine"
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Patterns");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Grid Size:");
+            ui.add(egui::DragValue::new(&mut self.grid_size).speed(1.0).range(3..=8));
+            ui.label("Patterns:");
+            ui.add(egui::DragValue::new(&mut self.num_patterns).speed(1.0).range(1..=10));
+        });
+        if ui.button("New Random Patterns").clicked() {
+            self.generate_patterns();
+        }
+
+        ui.separator();
+        ui.heading("Training");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Hidden Units:");
+            ui.add(egui::DragValue::new(&mut self.num_hidden).speed(1.0).range(0..=64));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Learning Rate:");
+            ui.add(egui::DragValue::new(&mut self.learning_rate).speed(0.001).range(0.001..=1.0));
+            ui.label("Epochs:");
+            ui.add(egui::DragValue::new(&mut self.epochs).speed(1.0).range(1..=1000));
+        });
+        ui.label("Training Annealing Schedule:");
+        schedule_editor(ui, "boltzmann_training_schedule", &mut self.training_schedule);
+
+        if ui.button("Train Both Models")
+            .on_hover_text("Hebbian Hopfield network and Boltzmann machine on the same patterns")
+            .clicked()
+        {
+            let n = self.grid_size * self.grid_size;
+            if self.patterns.first().is_none_or(|p| p.len() != n) || self.patterns.len() != self.num_patterns {
+                self.generate_patterns();
+            }
+            self.train_models();
+        }
+
+        ui.separator();
+        ui.heading("Recall");
+        ui.separator();
+
+        ui.label("Recall Annealing Schedule (shared by both models):");
+        schedule_editor(ui, "boltzmann_recall_schedule", &mut self.recall_schedule);
+
+        ui.horizontal(|ui| {
+            ui.label("Pattern:");
+            let max_index = self.patterns.len().saturating_sub(1);
+            ui.add(egui::DragValue::new(&mut self.selected_pattern).speed(1.0).range(0..=max_index));
+        });
+        ui.add(egui::Slider::new(&mut self.noise_level, 0.0..=0.5).text("Noise"));
+
+        let trained = self.machine.is_some() && self.hopfield.is_some();
+        if ui.add_enabled(trained, egui::Button::new("Recall")).clicked() {
+            self.recall_selected();
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Trials per Pattern:");
+            ui.add(egui::DragValue::new(&mut self.num_trials).speed(1.0).range(1..=500));
+            if ui.add_enabled(trained, egui::Button::new("Compare Recall")).clicked() {
+                self.run_trials();
+            }
+        });
+
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        ui.heading("Recall: Hopfield vs Boltzmann");
+        ui.separator();
+
+        let size = self.grid_size;
+        let cell = 120.0 / size as f32;
+        let target = self.patterns.get(self.selected_pattern);
+
+        ui.columns(4, |columns| {
+            let panels: [(&str, Option<&Vec<f64>>); 4] = [
+                ("Target", target),
+                ("Cue", self.cue.as_ref()),
+                ("Hopfield", self.hopfield_output.as_ref()),
+                ("Boltzmann", self.boltzmann_output.as_ref()),
+            ];
+            for (ui, (title, state)) in columns.iter_mut().zip(panels) {
+                ui.vertical_centered(|ui| {
+                    ui.label(title);
+                    ui.separator();
+                    match state {
+                        Some(state) if state.len() == size * size => {
+                            draw_grid(ui, state, size, size, cell);
+                            if let Some(target) = target {
+                                ui.label(format!("m = {:.2}", overlap(state, target)));
+                            }
+                        },
+                        _ => {
+                            ui.label("—");
+                        },
+                    }
+                });
+            }
+        });
+
+        if let Some(summary) = &self.trial_summary {
+            ui.separator();
+            ui.label(format!("Recall over {} noisy cues (success: m ≥ {}):", summary.trials, SUCCESS_OVERLAP));
+            egui::Grid::new("boltzmann_trial_summary").striped(true).show(ui, |ui| {
+                ui.label("");
+                ui.label("Mean overlap");
+                ui.label("Success rate");
+                ui.end_row();
+                ui.label("Hopfield");
+                ui.label(format!("{:.3}", summary.hopfield_overlap));
+                ui.label(format!("{:.1}%", 100.0 * summary.hopfield_success));
+                ui.end_row();
+                ui.label("Boltzmann");
+                ui.label(format!("{:.3}", summary.boltzmann_overlap));
+                ui.label(format!("{:.1}%", 100.0 * summary.boltzmann_success));
+                ui.end_row();
+            });
+        }
+
+        ui.separator();
+        ui.label("Boltzmann Training (mean |Δw| per epoch):");
+        if self.weight_changes.is_empty() {
+            ui.label("(Train the models to see the learning curve)");
+        } else {
+            let points: PlotPoints = self.weight_changes
+                .iter()
+                .enumerate()
+                .map(|(i, &dw)| [i as f64, dw])
+                .collect();
+            Plot::new("boltzmann_training_plot")
+                .height(150.0)
+                .show(ui, |plot_ui| {
+                    plot_ui.line(Line::new(points));
+                });
+        }
+    }
+}
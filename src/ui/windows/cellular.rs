@@ -0,0 +1,443 @@
+use eframe::egui;
+use rand::rngs::ThreadRng;
+
+use crate::neural::cellular::{Boundary, ElementaryCA, LifeGrid, LifeRule};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::dynamics;
+use crate::ui::windows::{Command, Window};
+
+/// Which kind of automaton the window is simulating
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AutomatonKind {
+    /// 1D elementary CA, drawn as a space-time diagram
+    Elementary,
+    /// 2D life-like CA
+    Life,
+}
+
+/// Named life-like rules offered in the UI
+const LIFE_PRESETS: [(&str, &str); 6] = [
+    ("Conway's Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Seeds", "B2/S"),
+    ("Day & Night", "B3678/S34678"),
+    ("Maze", "B3/S12345"),
+    ("Replicator", "B1357/S1357"),
+];
+
+pub struct CellularWindow {
+    /// The automata (only the one matching `kind` is shown)
+    elementary: Option<ElementaryCA>,
+    life: Option<LifeGrid>,
+
+    /// Configuration for automaton creation
+    kind: AutomatonKind,
+    elementary_width: usize,
+    elementary_rule: u8,
+    life_width: usize,
+    life_height: usize,
+    life_rule_text: String,
+    boundary: Boundary,
+    random_density: f64,
+
+    /// Simulation parameters
+    run_steps: usize,
+    step_interval: f64,       // In seconds
+    time_since_step: f64,
+    auto_step: bool,
+
+    /// Display settings
+    display_step: usize,
+    cell_size: f32,
+
+    /// Random number generator for random initial states
+    rng: ThreadRng,
+}
+
+impl CellularWindow {
+    pub fn new() -> Self {
+        Self {
+            elementary: None,
+            life: None,
+            kind: AutomatonKind::Life,
+            elementary_width: 101,
+            elementary_rule: 110,
+            life_width: 40,
+            life_height: 40,
+            life_rule_text: "B3/S23".to_string(),
+            boundary: Boundary::Periodic,
+            random_density: 0.3,
+            run_steps: 50,
+            step_interval: 0.1,
+            time_since_step: 0.0,
+            auto_step: false,
+            display_step: 0,
+            cell_size: 10.0,
+            rng: rand::thread_rng(),
+        }
+    }
+
+    /// Create a new automaton of the selected kind based on current settings
+    fn create_automaton(&mut self) {
+        self.display_step = 0;
+        self.auto_step = false;
+        match self.kind {
+            AutomatonKind::Elementary => match ElementaryCA::new(self.elementary_width, self.elementary_rule) {
+                Ok(mut ca) => {
+                    ca.boundary = self.boundary;
+                    self.elementary = Some(ca);
+                },
+                Err(e) => report_error(format!("Failed to create automaton: {}", e)),
+            },
+            AutomatonKind::Life => {
+                let rule = match LifeRule::parse(&self.life_rule_text) {
+                    Ok(rule) => rule,
+                    Err(e) => {
+                        report_error(e.to_string());
+                        return;
+                    }
+                };
+                match LifeGrid::new(self.life_width, self.life_height) {
+                    Ok(mut grid) => {
+                        grid.rule = rule;
+                        grid.boundary = self.boundary;
+                        grid.randomize(self.random_density, &mut self.rng);
+                        self.life = Some(grid);
+                    },
+                    Err(e) => report_error(format!("Failed to create grid: {}", e)),
+                }
+            },
+        }
+    }
+
+    fn has_automaton(&self) -> bool {
+        match self.kind {
+            AutomatonKind::Elementary => self.elementary.is_some(),
+            AutomatonKind::Life => self.life.is_some(),
+        }
+    }
+
+    fn history_len(&self) -> usize {
+        match self.kind {
+            AutomatonKind::Elementary => self.elementary.as_ref().map_or(0, |ca| ca.history.len()),
+            AutomatonKind::Life => self.life.as_ref().map_or(0, |grid| grid.history.len()),
+        }
+    }
+
+    /// Advance the current automaton by `steps` generations
+    fn advance(&mut self, steps: usize) {
+        match self.kind {
+            AutomatonKind::Elementary => {
+                if let Some(ca) = &mut self.elementary {
+                    dynamics::advance(ca, steps, &mut self.rng);
+                }
+            },
+            AutomatonKind::Life => {
+                if let Some(grid) = &mut self.life {
+                    dynamics::advance(grid, steps, &mut self.rng);
+                }
+            },
+        }
+        self.display_step = self.history_len().saturating_sub(1);
+    }
+
+    fn randomize(&mut self) {
+        match self.kind {
+            AutomatonKind::Elementary => {
+                if let Some(ca) = &mut self.elementary {
+                    ca.randomize(self.random_density, &mut self.rng);
+                }
+            },
+            AutomatonKind::Life => {
+                if let Some(grid) = &mut self.life {
+                    grid.randomize(self.random_density, &mut self.rng);
+                }
+            },
+        }
+        self.display_step = 0;
+    }
+
+    fn reset(&mut self) {
+        match self.kind {
+            AutomatonKind::Elementary => {
+                if let Some(ca) = &mut self.elementary {
+                    ca.reset();
+                }
+            },
+            AutomatonKind::Life => {
+                if let Some(grid) = &mut self.life {
+                    grid.reset();
+                }
+            },
+        }
+        self.display_step = 0;
+    }
+
+    /// Draw the space-time diagram of an elementary CA up to the display step (time runs downwards)
+    fn draw_elementary(&self, ui: &mut egui::Ui, ca: &ElementaryCA) {
+        let rows = &ca.history[..=self.display_step.min(ca.history.len() - 1)];
+        let cell = (ui.available_width() / ca.width as f32).clamp(1.0, self.cell_size);
+        let size = egui::vec2(ca.width as f32 * cell, rows.len() as f32 * cell);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let rect = response.rect;
+
+        painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+        for (t, row) in rows.iter().enumerate() {
+            for (x, &alive) in row.iter().enumerate() {
+                if alive {
+                    let min = rect.min + egui::vec2(x as f32 * cell, t as f32 * cell);
+                    painter.rect_filled(egui::Rect::from_min_size(min, egui::vec2(cell, cell)), 0.0, egui::Color32::BLACK);
+                }
+            }
+        }
+    }
+
+    /// Draw a life grid at the display step; returns the clicked cell, if any
+    fn draw_life(&self, ui: &mut egui::Ui, grid: &LifeGrid) -> Option<(usize, usize)> {
+        let cells = &grid.history[self.display_step.min(grid.history.len() - 1)];
+        let cell = self.cell_size;
+        let size = egui::vec2(grid.width as f32 * cell, grid.height as f32 * cell);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::click());
+        let rect = response.rect;
+
+        painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                if cells[y * grid.width + x] {
+                    let min = rect.min + egui::vec2(x as f32 * cell, y as f32 * cell);
+                    painter.rect_filled(egui::Rect::from_min_size(min, egui::vec2(cell, cell)), 0.0, egui::Color32::BLACK);
+                }
+            }
+        }
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+
+        if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let relative_pos = pos - rect.min;
+                let x = (relative_pos.x / cell).floor() as usize;
+                let y = (relative_pos.y / cell).floor() as usize;
+                if x < grid.width && y < grid.height {
+                    return Some((x, y));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Window for CellularWindow {
+    fn name(&self) -> &str {
+        "Cellular Automata"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Step => self.advance(1),
+            Command::Run => self.advance(self.run_steps),
+            Command::Reset => self.reset(),
+            Command::Train => return false,
+        }
+        true
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Automaton Settings");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Type:");
+            if ui.radio_value(&mut self.kind, AutomatonKind::Elementary, "Elementary (1D)").changed()
+                | ui.radio_value(&mut self.kind, AutomatonKind::Life, "Life-like (2D)").changed()
+            {
+                self.display_step = self.history_len().saturating_sub(1);
+                self.auto_step = false;
+            }
+        });
+
+        match self.kind {
+            AutomatonKind::Elementary => {
+                ui.horizontal(|ui| {
+                    ui.label("Width:");
+                    ui.add(egui::DragValue::new(&mut self.elementary_width).speed(1.0).range(3..=1000));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Rule:");
+                    ui.add(egui::DragValue::new(&mut self.elementary_rule).speed(1.0));
+                    for preset in [30u8, 90, 110, 184] {
+                        if ui.small_button(preset.to_string()).clicked() {
+                            self.elementary_rule = preset;
+                        }
+                    }
+                });
+            },
+            AutomatonKind::Life => {
+                ui.horizontal(|ui| {
+                    ui.label("Width:");
+                    ui.add(egui::DragValue::new(&mut self.life_width).speed(1.0).range(3..=300));
+                    ui.label("Height:");
+                    ui.add(egui::DragValue::new(&mut self.life_height).speed(1.0).range(3..=300));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Rule (B/S):");
+                    ui.text_edit_singleline(&mut self.life_rule_text);
+                });
+                egui::ComboBox::from_label("Presets")
+                    .selected_text(
+                        LIFE_PRESETS
+                            .iter()
+                            .find(|(_, rule)| *rule == self.life_rule_text)
+                            .map_or("Custom", |(name, _)| *name),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (name, rule) in LIFE_PRESETS {
+                            ui.selectable_value(&mut self.life_rule_text, rule.to_string(), name);
+                        }
+                    });
+            },
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Boundary:");
+            ui.radio_value(&mut self.boundary, Boundary::Periodic, "Periodic");
+            ui.radio_value(&mut self.boundary, Boundary::Dead, "Dead");
+        });
+
+        ui.add(egui::Slider::new(&mut self.random_density, 0.0..=1.0).text("Random Density"));
+
+        if ui.button("Create Automaton").clicked() {
+            self.create_automaton();
+        }
+
+        ui.separator();
+
+        if self.has_automaton() {
+            ui.heading("Simulation Settings");
+            ui.separator();
+
+            // Rule changes apply to the running automaton
+            match self.kind {
+                AutomatonKind::Elementary => {
+                    if let Some(ca) = &mut self.elementary {
+                        ca.rule = self.elementary_rule;
+                        ca.boundary = self.boundary;
+                    }
+                },
+                AutomatonKind::Life => {
+                    if let Some(grid) = &mut self.life {
+                        if let Ok(rule) = LifeRule::parse(&self.life_rule_text) {
+                            grid.rule = rule;
+                        }
+                        grid.boundary = self.boundary;
+                    }
+                },
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Steps per Run:");
+                ui.add(egui::DragValue::new(&mut self.run_steps).speed(1.0).range(1..=1000));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Step Interval (s):");
+                ui.add(egui::Slider::new(&mut self.step_interval, 0.01..=2.0).logarithmic(true));
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Step").clicked() {
+                    self.advance(1);
+                }
+                ui.checkbox(&mut self.auto_step, "Auto Step");
+                if ui.button(format!("Run {} Steps", self.run_steps)).clicked() {
+                    self.advance(self.run_steps);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Randomize").clicked() {
+                    self.randomize();
+                }
+                if ui.button("Reset").clicked() {
+                    self.reset();
+                }
+            });
+
+            ui.separator();
+            ui.heading("Display Settings");
+            ui.horizontal(|ui| {
+                ui.label("Cell Size:");
+                ui.add(egui::Slider::new(&mut self.cell_size, 2.0..=30.0));
+            });
+
+            if self.kind == AutomatonKind::Life {
+                ui.label("Click cells to toggle them.");
+            }
+        } else {
+            ui.label("Create an automaton first.");
+        }
+
+    }
+
+    fn on_close(&mut self) {
+        self.auto_step = false;
+    }
+
+    fn tick(&mut self, dt: f64) -> bool {
+        if !self.auto_step {
+            return false;
+        }
+        self.time_since_step += dt;
+        if self.time_since_step >= self.step_interval {
+            self.advance(1);
+            self.time_since_step = 0.0;
+        }
+        true
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        let history_len = self.history_len();
+        if history_len == 0 {
+            self.display_step = 0;
+            ui.vertical_centered(|ui| {
+                ui.label("No automaton created yet. Use the configuration panel to create one.");
+            });
+            return;
+        }
+
+        // History slider
+        self.display_step = self.display_step.min(history_len - 1);
+        ui.horizontal(|ui| {
+            ui.label(format!("Step: {} / {}", self.display_step, history_len - 1));
+            if history_len > 1 {
+                ui.add(egui::Slider::new(&mut self.display_step, 0..=(history_len - 1)).text("View Step"));
+            }
+        });
+        ui.separator();
+
+        match self.kind {
+            AutomatonKind::Elementary => {
+                if let Some(ca) = &self.elementary {
+                    egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                        self.draw_elementary(ui, ca);
+                    });
+                    ui.separator();
+                    ui.label(format!("Rule {}   Population: {}", ca.rule, ca.population()));
+                }
+            },
+            AutomatonKind::Life => {
+                let mut clicked = None;
+                if let Some(grid) = &self.life {
+                    clicked = self.draw_life(ui, grid);
+                    ui.separator();
+                    ui.label(format!("Rule {}   Population: {}", grid.rule, grid.population()));
+                }
+                if let (Some((x, y)), Some(grid)) = (clicked, &mut self.life) {
+                    // Editing from an earlier step discards the later generations
+                    grid.history.truncate(self.display_step + 1);
+                    grid.cells = grid.history[self.display_step].clone();
+                    grid.toggle(x, y);
+                    self.display_step = grid.history.len() - 1;
+                }
+            },
+        }
+    }
+}
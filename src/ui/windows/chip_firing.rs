@@ -1,19 +1,45 @@
 use eframe::egui;
-use egui_plot::{Plot, PlotPoints, Points};
-use rand::rngs::ThreadRng;
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints, Points};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::neural::chip_firing::{ChipFiringGraph, UpdateMode, VertexSelectionStrategy};
-use crate::ui::windows::Window;
+use crate::core::graph::{GraphStatistics, GraphType, LaplacianSpectrum};
+use crate::core::history::{HistoryEntry, Retention, SimulationHistory};
+use crate::core::job::{BackgroundJob, JobStatus};
+use crate::graphics::export::{write_gif, write_png};
+use crate::graphics::height_field::OrbitCamera;
+use crate::graphics::raster::{self, render_value_grid, Canvas};
+use crate::neural::chip_firing::presets::ChipFiringPreset;
+use crate::neural::chip_firing::{
+    ChipFiringError, ChipFiringGraph, DriveTarget, StabilizationStats, UpdateMode, VertexMetadata,
+    VertexSelectionStrategy,
+};
+use crate::neural::graph_io;
+use crate::ui::app::GridColors;
+use crate::ui::events::{self, EventKind};
+use crate::ui::metrics::{self, Metric};
+use crate::ui::notifications::{report_error, report_info};
+use crate::ui::session::{self, SessionAction};
+use crate::ui::undo::{self, Snapshot};
+use crate::ui::widgets::animation::{AnimationExport, DEFAULT_SCALE};
+use crate::ui::widgets::camera::{draw_minimap, viewport, Camera};
+use crate::ui::widgets::colormap::{colormap_selector, legend, Colormap};
+use crate::ui::widgets::grid::{cell_at, paint_grid};
+use crate::ui::widgets::height_field;
+use crate::ui::widgets::history::history_slider;
+use crate::ui::widgets::job::job_progress;
+use crate::ui::widgets::network::{self, NetworkStyle};
+use crate::ui::widgets::seed::seed_control;
+use crate::ui::widgets::snapshot::SnapshotExport;
+use crate::ui::widgets::spectrum::{spectrum_plot, spectrum_summary, MAX_SPECTRUM_VERTICES};
+use crate::ui::widgets::visual::{wave_color, ChipView, Visualizable};
+use crate::ui::windows::{Command, Window};
 
-/// Predefined graph types for the UI
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum GraphType {
-    Grid,
-    Cycle,
-    Complete,
-    Star,
-    Custom,
-}
+/// Graph types compared by the stabilization experiment
+const EXPERIMENT_TYPES: [GraphType; 4] = [GraphType::Grid, GraphType::Cycle, GraphType::Complete, GraphType::Star];
+
+/// Stabilization statistics over a range of chip densities for each graph type
+type ExperimentResults = Vec<(GraphType, Vec<StabilizationStats>)>;
 
 /// Visualization mode for the chip firing graph
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -24,6 +50,124 @@ enum VisualizationMode {
     Grid,
     /// Display the chip distribution as a bar chart
     BarChart,
+    /// Display firing activity per cell as a heatmap (only for grid graphs)
+    Heatmap,
+    /// Display chip counts as a 3D height field (only for grid graphs on the wgpu renderer)
+    Heights3D,
+}
+
+/// Quantity shown by the activity heatmap
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HeatmapMetric {
+    /// Total number of firings since the last reset
+    Cumulative,
+    /// Exponentially decaying recent firing rate
+    Recent,
+}
+
+/// Largest graph whose statistics are computed as soon as the panel is shown
+const MAX_STATISTICS_VERTICES: usize = 2000;
+
+/// Firing probabilities the slider offers; journals are held to the same range
+const FIRING_PROBABILITY_RANGE: std::ops::RangeInclusive<f64> = 0.05..=1.0;
+
+/// Entries of the Presets menu, one per `ChipFiringPreset`
+const PRESETS: [(&str, &str); 3] = [
+    (ChipFiringPreset::CenterPile.name(), ChipFiringPreset::CenterPile.description()),
+    (ChipFiringPreset::Checkerboard.name(), ChipFiringPreset::Checkerboard.description()),
+    (ChipFiringPreset::MaximalStablePlusOne.name(), ChipFiringPreset::MaximalStablePlusOne.description()),
+];
+
+/// Maximum number of entries kept on the undo stack
+const MAX_UNDO_ENTRIES: usize = 200;
+
+/// Color of the chips animated along the edges of a firing vertex
+const CHIP_TRAIL_COLOR: egui::Color32 = egui::Color32::from_rgb(240, 170, 40);
+
+/// Shortest and longest time in seconds a chip takes to travel along an edge; in
+/// between, the animation lasts one auto-step interval
+const CHIP_TRAIL_SECONDS: (f64, f64) = (0.1, 0.6);
+
+/// How to restore the history vector when applying an undo/redo entry
+#[derive(Debug, Clone)]
+enum HistoryChange {
+    /// Drop everything after the first `n` history entries (undo of appended steps)
+    Truncate(usize),
+    /// Append previously dropped entries (redo of appended steps)
+    Extend(Vec<HistoryEntry<Vec<i32>>>),
+    /// Swap in a complete history (for operations that replace the timeline)
+    Replace(SimulationHistory<Vec<i32>>),
+}
+
+/// A restorable snapshot of the graph state taken before an action
+#[derive(Debug, Clone)]
+struct UndoEntry {
+    /// Name of the action, shown in the Undo/Redo button tooltips
+    label: &'static str,
+    configuration: Vec<i32>,
+    history: HistoryChange,
+    firing_counts: Vec<u64>,
+    firing_activity: Vec<f64>,
+}
+
+impl UndoEntry {
+    /// Capture the graph state before an action that only appends to history
+    fn before_append(label: &'static str, graph: &ChipFiringGraph) -> Self {
+        Self {
+            label,
+            configuration: graph.configuration.clone(),
+            history: HistoryChange::Truncate(graph.history.len()),
+            firing_counts: graph.firing_counts.clone(),
+            firing_activity: graph.firing_activity.clone(),
+        }
+    }
+    
+    /// Capture the graph state before an action that replaces the history
+    fn before_replace(label: &'static str, graph: &ChipFiringGraph) -> Self {
+        Self {
+            history: HistoryChange::Replace(graph.history.clone()),
+            ..Self::before_append(label, graph)
+        }
+    }
+    
+    /// Restore this entry into the graph, returning the entry that reverses it
+    fn apply(self, graph: &mut ChipFiringGraph) -> Self {
+        let history = match self.history {
+            HistoryChange::Truncate(len) => {
+                let tail = graph.history.split_off(len);
+                HistoryChange::Extend(tail)
+            },
+            HistoryChange::Extend(tail) => {
+                let len = graph.history.len();
+                graph.history.extend(tail);
+                HistoryChange::Truncate(len)
+            },
+            HistoryChange::Replace(history) => {
+                HistoryChange::Replace(std::mem::replace(&mut graph.history, history))
+            },
+        };
+        
+        let configuration = std::mem::replace(&mut graph.configuration, self.configuration);
+        // The firing log only runs forward, so it starts over from the restored configuration
+        graph.restart_firing_log();
+        
+        Self {
+            label: self.label,
+            configuration,
+            history,
+            firing_counts: std::mem::replace(&mut graph.firing_counts, self.firing_counts),
+            firing_activity: std::mem::replace(&mut graph.firing_activity, self.firing_activity),
+        }
+    }
+}
+
+/// The whole graph with its own undo history, captured for Edit → Undo before the graph
+/// is replaced or randomized
+struct GraphState {
+    graph: Option<ChipFiringGraph>,
+    graph_type: GraphType,
+    undo_stack: Vec<UndoEntry>,
+    redo_stack: Vec<UndoEntry>,
 }
 
 /// Window for chip firing graph simulation and visualization
@@ -37,37 +181,98 @@ pub struct ChipFiringWindow {
     grid_width: usize,        // For grid graphs
     grid_height: usize,       // For grid graphs
     custom_edges: String,     // For custom graphs, format: "0,1 1,2 ..."
+    graph_file_path: String,  // For importing/exporting DOT or edge-list files
+    animation_export: AnimationExport,
+    snapshot_export: SnapshotExport,
     
     /// Simulation parameters
     max_steps: usize,
+    max_firings: usize,       // Limit for fast stabilization
     step_interval: f64,       // In seconds
-    last_step_time: f64,
+    time_since_step: f64,
     auto_step: bool,
     
+    /// Driven sandpile: drop a chip every `drive_interval` steps while the system relaxes
+    sink: Option<usize>,
+    driven: bool,
+    drive_interval: usize,
+    drive_target: DriveTarget,
+    steps_since_drop: usize,
+    
     /// Display settings
     display_step: usize,
     visualization_mode: VisualizationMode,
+    heatmap_metric: HeatmapMetric,
+    heatmap_colormap: Colormap,
+    /// View of the 3D height field
+    orbit_camera: OrbitCamera,
     show_active_vertices: bool,
     vertex_radius: f32,
     edge_thickness: f32,
     grid_cell_size: f32,
     
+    /// Vertices that toppled in each wave of the last avalanche decomposed into waves
+    waves: Vec<Vec<usize>>,
+    show_waves: bool,
+    
+    /// Firing animation in the network view: the vertices that fired in the last step,
+    /// and how far (0 to 1) their chips have traveled along the edges
+    trail_sources: Vec<usize>,
+    trail_progress: f64,
+    animate_firings: bool,
+    
     /// Vertex interaction
     selected_vertex: Option<usize>,
     add_chip_to_selected: bool,
     
+    /// Chip painter for the grid view: holding the primary button adds `paint_rate`
+    /// chips per second to every cell within `brush_radius` cells of the pointer
+    paint_chips: bool,
+    brush_radius: usize,
+    paint_rate: f64,
+    /// Whether a brush stroke is in progress (it is undone as a whole), and the fraction
+    /// of a chip owed to the cells under the brush, carried between frames
+    paint_stroke: bool,
+    paint_carry: f64,
+    
     /// Node positions for network visualization
     node_positions: Vec<egui::Vec2>,
     
-    /// Random number generator for vertex selection
-    rng: ThreadRng,
+    /// Undo/redo stacks of manual interventions and simulation steps
+    undo_stack: Vec<UndoEntry>,
+    redo_stack: Vec<UndoEntry>,
     
-    /// UI state
-    error_message: Option<String>,
+    /// Random number generator for vertex selection and random configurations
+    rng: StdRng,
+    seed: u64, // Seed of `rng`, recorded in exports
+    
+    /// "Run Until Stable" running on a worker thread; returns the evolved copy of the graph
+    job: Option<BackgroundJob<(ChipFiringGraph, Result<usize, ChipFiringError>)>>,
+    
+    /// Stabilization experiment: random configurations on several graph types, with the
+    /// sizes from the graph settings
+    experiment_types: Vec<GraphType>,
+    experiment_densities: (f64, f64),
+    experiment_points: usize,
+    experiment_trials: usize,
+    experiment_with_sink: bool,
+    experiment_show_firings: bool,
+    experiment_job: Option<BackgroundJob<ExperimentResults>>,
+    experiment_results: ExperimentResults,
+    
+    /// Laplacian spectrum of the current graph, and of the reduced Laplacian if it has a
+    /// sink; computed on request and cleared when the graph or sink changes
+    spectrum: Option<LaplacianSpectrum>,
+    reduced_spectrum: Option<LaplacianSpectrum>,
+    
+    /// Degree distribution, components and distances of the current graph, computed
+    /// when the statistics panel is shown and cleared with the spectrum
+    statistics: Option<GraphStatistics>,
 }
 
 impl ChipFiringWindow {
     pub fn new() -> Self {
+        let seed = rand::random::<u32>() as u64;
         Self {
             graph: None,
             graph_type: GraphType::Grid,
@@ -75,269 +280,658 @@ impl ChipFiringWindow {
             grid_width: 5,
             grid_height: 5,
             custom_edges: String::new(),
+            graph_file_path: "graph.dot".to_string(),
+            animation_export: AnimationExport::new("chip_firing.gif"),
+            snapshot_export: SnapshotExport::new("chip_firing"),
             max_steps: 100,
+            max_firings: 100_000,
             step_interval: 0.2,
-            last_step_time: 0.0,
+            time_since_step: 0.0,
             auto_step: false,
+            sink: None,
+            driven: false,
+            drive_interval: 10,
+            drive_target: DriveTarget::Random,
+            steps_since_drop: 0,
             display_step: 0,
             visualization_mode: VisualizationMode::Network,
+            heatmap_metric: HeatmapMetric::Cumulative,
+            heatmap_colormap: Colormap::default(),
+            orbit_camera: OrbitCamera::default(),
             show_active_vertices: true,
             vertex_radius: 15.0,
             edge_thickness: 2.0,
             grid_cell_size: 50.0,
+            waves: Vec::new(),
+            show_waves: true,
+            trail_sources: Vec::new(),
+            trail_progress: 0.0,
+            animate_firings: true,
             selected_vertex: None,
             add_chip_to_selected: false,
+            paint_chips: false,
+            brush_radius: 1,
+            paint_rate: 10.0,
+            paint_stroke: false,
+            paint_carry: 0.0,
             node_positions: Vec::new(),
-            rng: rand::thread_rng(),
-            error_message: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+            job: None,
+            experiment_types: EXPERIMENT_TYPES.to_vec(),
+            experiment_densities: (0.0, 2.0),
+            experiment_points: 11,
+            experiment_trials: 20,
+            experiment_with_sink: true,
+            experiment_show_firings: false,
+            experiment_job: None,
+            experiment_results: Vec::new(),
+            spectrum: None,
+            reduced_spectrum: None,
+            statistics: None,
         }
     }
     
     /// Create a new chip firing graph based on current settings
     fn create_graph(&mut self) -> Result<ChipFiringGraph, String> {
-        match self.graph_type {
-            GraphType::Grid => {
-                // Create a grid graph
-                if self.grid_width == 0 || self.grid_height == 0 {
-                    return Err("Grid dimensions must be greater than 0".to_string());
-                }
-                
-                let num_vertices = self.grid_width * self.grid_height;
-                let initial_config = vec![0; num_vertices];
-                
-                ChipFiringGraph::new_grid(self.grid_width, self.grid_height, initial_config)
-                    .map_err(|e| format!("Failed to create grid graph: {}", e))
+        build_graph(self.graph_type, self.graph_size, self.grid_width, self.grid_height, &self.custom_edges)
+    }
+    
+    /// Calculate node positions for network visualization
+    fn calculate_node_positions(&mut self) {
+        if let Some(graph) = &self.graph {
+            self.node_positions = layout_positions(self.graph_type, graph.num_vertices, self.grid_width, self.grid_height);
+        }
+    }
+    
+    /// Get the configuration at the current display step
+    fn current_configuration(&self) -> Option<&Vec<i32>> {
+        if let Some(graph) = &self.graph {
+            if !graph.history.is_empty() {
+                let step = self.display_step.min(graph.history.len() - 1);
+                return Some(&graph.history[step]);
+            }
+        }
+        None
+    }
+    
+    /// Get active vertices at the current display step
+    fn current_active_vertices(&self) -> Vec<usize> {
+        match (&self.graph, self.current_configuration()) {
+            (Some(graph), Some(config)) => graph.active_in(config),
+            _ => Vec::new(),
+        }
+    }
+    
+    /// Take a new graph into use, with the configured sink if it is a valid vertex
+    fn set_graph(&mut self, mut graph: ChipFiringGraph) {
+        self.sink = self.sink.filter(|&v| v < graph.num_vertices);
+        if let DriveTarget::Fixed(vertex) = self.drive_target {
+            if vertex >= graph.num_vertices {
+                self.drive_target = DriveTarget::Random;
+            }
+        }
+        let _ = graph.set_sink(self.sink);
+        self.graph = Some(graph);
+        self.waves.clear();
+        self.trail_sources.clear();
+        self.clear_analysis();
+        self.steps_since_drop = 0;
+    }
+    
+    fn clear_analysis(&mut self) {
+        self.spectrum = None;
+        self.reduced_spectrum = None;
+        self.statistics = None;
+    }
+    
+    /// Diagonalize the Laplacian of the current graph (and the reduced one with a sink)
+    fn compute_spectrum(&mut self) {
+        let Some(graph) = &self.graph else {
+            return;
+        };
+        let laplacian = graph.laplacian();
+        self.reduced_spectrum = graph.sink().map(|sink| LaplacianSpectrum::reduced(laplacian.clone(), sink));
+        self.spectrum = Some(LaplacianSpectrum::new(laplacian));
+    }
+    
+    /// Spectrum panel: compute button, summary and plot
+    fn draw_spectrum(&mut self, ui: &mut egui::Ui) {
+        let num_vertices = self.graph.as_ref().map_or(0, |g| g.num_vertices);
+        let too_large = num_vertices > MAX_SPECTRUM_VERTICES;
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!too_large, egui::Button::new("Compute Spectrum")).clicked() {
+                self.compute_spectrum();
+            }
+            if too_large {
+                ui.label(format!("Only available up to {} vertices", MAX_SPECTRUM_VERTICES));
+            }
+        });
+        if let Some(spectrum) = &self.spectrum {
+            spectrum_summary(ui, spectrum, self.reduced_spectrum.as_ref());
+            spectrum_plot(ui, "chip_firing_spectrum", spectrum, self.reduced_spectrum.as_ref(), 200.0);
+        }
+    }
+    
+    /// Statistics panel: degree histogram, components and path lengths. Small graphs are
+    /// analyzed as soon as the panel opens, larger ones on request.
+    fn draw_statistics(&mut self, ui: &mut egui::Ui) {
+        let Some(graph) = &self.graph else {
+            return;
+        };
+        if self.statistics.is_none() {
+            let too_large = graph.num_vertices > MAX_STATISTICS_VERTICES;
+            if !too_large || ui.button("Compute Statistics").on_hover_text("Path lengths take a search from every vertex").clicked() {
+                self.statistics = Some(graph.statistics());
+            }
+        }
+        let Some(stats) = &self.statistics else {
+            return;
+        };
+        
+        if stats.is_connected() {
+            ui.label("Connected");
+        } else {
+            let sizes: Vec<String> = stats.component_sizes.iter().map(usize::to_string).collect();
+            ui.colored_label(
+                egui::Color32::from_rgb(230, 160, 40),
+                format!("Disconnected: {} components of {} vertices", stats.component_sizes.len(), sizes.join(", ")),
+            );
+            ui.label("Chips never cross between components, so each one stabilizes on its own; a component without a sink or dissipation may fire forever.");
+        }
+        ui.label(format!("Diameter: {}", stats.diameter));
+        ui.label(format!("Average Path Length: {:.3}", stats.average_path_length));
+        
+        let bars: Vec<Bar> = stats.degree_histogram
+            .iter()
+            .map(|(&degree, &count)| Bar::new(degree as f64, count as f64).width(0.8))
+            .collect();
+        Plot::new("chip_firing_degree_histogram")
+            .height(160.0)
+            .x_axis_label("Degree")
+            .y_axis_label("Vertices")
+            .allow_drag(false)
+            .allow_zoom(false)
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new(bars).name("Degree"));
+            });
+    }
+    
+    /// Record the current state on the undo stack before an action that appends to history
+    fn push_undo(&mut self, label: &'static str) {
+        if let Some(graph) = &self.graph {
+            let entry = UndoEntry::before_append(label, graph);
+            self.push_undo_entry(entry);
+        }
+    }
+    
+    /// Record the current state on the undo stack before an action that replaces history
+    fn push_undo_replace(&mut self, label: &'static str) {
+        if let Some(graph) = &self.graph {
+            let entry = UndoEntry::before_replace(label, graph);
+            self.push_undo_entry(entry);
+        }
+    }
+    
+    fn push_undo_entry(&mut self, entry: UndoEntry) {
+        self.undo_stack.push(entry);
+        if self.undo_stack.len() > MAX_UNDO_ENTRIES {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+    
+    /// Record the whole graph on the application undo stack before it is replaced
+    fn checkpoint(&self, label: &str) {
+        if self.graph.is_none() {
+            return;
+        }
+        let state = GraphState {
+            graph: self.graph.clone(),
+            graph_type: self.graph_type,
+            undo_stack: self.undo_stack.clone(),
+            redo_stack: self.redo_stack.clone(),
+        };
+        undo::checkpoint(label, Box::new(state));
+    }
+    
+    /// Put back a graph recorded by `checkpoint`
+    fn restore_graph(&mut self, state: GraphState) {
+        self.graph = state.graph;
+        self.graph_type = state.graph_type;
+        self.undo_stack = state.undo_stack;
+        self.redo_stack = state.redo_stack;
+        self.waves.clear();
+        self.trail_sources.clear();
+        self.clear_analysis();
+        self.calculate_node_positions();
+        self.selected_vertex = None;
+        self.steps_since_drop = 0;
+        self.display_step = self.graph.as_ref().map_or(0, |graph| graph.history.len().saturating_sub(1));
+    }
+    
+    /// Drop all undo/redo entries (e.g., when a new graph is created)
+    fn clear_undo(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+    
+    /// Revert the most recent action
+    fn undo(&mut self) {
+        if let (Some(graph), Some(entry)) = (&mut self.graph, self.undo_stack.pop()) {
+            let inverse = entry.apply(graph);
+            self.redo_stack.push(inverse);
+            self.display_step = graph.history.len().saturating_sub(1);
+        }
+    }
+    
+    /// Re-apply the most recently undone action
+    fn redo(&mut self) {
+        if let (Some(graph), Some(entry)) = (&mut self.graph, self.redo_stack.pop()) {
+            let inverse = entry.apply(graph);
+            self.undo_stack.push(inverse);
+            self.display_step = graph.history.len().saturating_sub(1);
+        }
+    }
+    
+    /// Build a graph from the graph settings and show it
+    fn create_and_show_graph(&mut self) {
+        match self.create_graph() {
+            Ok(graph) => {
+                self.checkpoint("New Graph");
+                self.set_graph(graph);
+                self.clear_undo();
+                self.calculate_node_positions();
+                self.display_step = 0;
             },
-            GraphType::Cycle => {
-                // Create a cycle graph
-                if self.graph_size < 3 {
-                    return Err("Cycle graph needs at least 3 vertices".to_string());
-                }
-                
-                let mut edges = Vec::new();
-                for i in 0..self.graph_size {
-                    edges.push((i, (i + 1) % self.graph_size));
-                }
-                
-                let initial_config = vec![0; self.graph_size];
-                
-                ChipFiringGraph::from_edge_list(&edges, self.graph_size, initial_config)
-                    .map_err(|e| format!("Failed to create cycle graph: {}", e))
+            Err(e) => {
+                report_error(e);
             },
-            GraphType::Complete => {
-                // Create a complete graph (all vertices connected to all others)
-                if self.graph_size < 2 {
-                    return Err("Complete graph needs at least 2 vertices".to_string());
-                }
-                
-                let mut edges = Vec::new();
-                for i in 0..self.graph_size {
-                    for j in (i+1)..self.graph_size {
-                        edges.push((i, j));
-                    }
-                }
-                
-                let initial_config = vec![0; self.graph_size];
-                
-                ChipFiringGraph::from_edge_list(&edges, self.graph_size, initial_config)
-                    .map_err(|e| format!("Failed to create complete graph: {}", e))
+        }
+    }
+    
+    /// Replace the graph with a preset grid and show it as a grid
+    fn load_grid_preset(&mut self, preset: ChipFiringPreset) {
+        match preset.build() {
+            Ok(graph) => {
+                self.checkpoint("Load Preset");
+                (self.grid_width, self.grid_height) = preset.grid_size();
+                self.graph_type = GraphType::Grid;
+                self.sink = graph.sink();
+                self.max_firings = self.max_firings.max(preset.firings_to_stabilize());
+                self.set_graph(graph);
+                self.clear_undo();
+                self.calculate_node_positions();
+                self.selected_vertex = None;
+                self.display_step = 0;
+                self.visualization_mode = VisualizationMode::Grid;
             },
-            GraphType::Star => {
-                // Create a star graph (center connected to all others)
-                if self.graph_size < 3 {
-                    return Err("Star graph needs at least 3 vertices".to_string());
-                }
-                
-                let mut edges = Vec::new();
-                for i in 1..self.graph_size {
-                    edges.push((0, i)); // Connect center (0) to all others
-                }
-                
-                let initial_config = vec![0; self.graph_size];
-                
-                ChipFiringGraph::from_edge_list(&edges, self.graph_size, initial_config)
-                    .map_err(|e| format!("Failed to create star graph: {}", e))
+            Err(e) => report_error(e),
+        }
+    }
+    
+    /// Apply a setting from a session journal
+    fn apply_session_setting(&mut self, key: &str, value: &str) -> bool {
+        let number = value.parse::<usize>();
+        match (key, number) {
+            ("graph_type", _) => {
+                let types = [GraphType::Grid, GraphType::Cycle, GraphType::Complete, GraphType::Star, GraphType::Custom];
+                let Some(graph_type) = types.into_iter().find(|graph_type| graph_type.name() == value) else {
+                    return false;
+                };
+                self.graph_type = graph_type;
             },
-            GraphType::Custom => {
-                // Parse custom edges from string
-                let edges_result = self.parse_custom_edges();
-                match edges_result {
-                    Ok((edges, num_vertices)) => {
-                        let initial_config = vec![0; num_vertices];
-                        
-                        ChipFiringGraph::from_edge_list(&edges, num_vertices, initial_config)
-                            .map_err(|e| format!("Failed to create custom graph: {}", e))
-                    },
-                    Err(e) => Err(e),
+            ("graph_size", Ok(n)) => self.graph_size = n,
+            ("grid_width", Ok(n)) => self.grid_width = n,
+            ("grid_height", Ok(n)) => self.grid_height = n,
+            ("edges", _) => self.custom_edges = value.to_string(),
+            ("max_steps", Ok(n)) => self.max_steps = n,
+            ("max_firings", Ok(n)) => self.max_firings = n,
+            ("update_mode", _) => {
+                let Some(graph) = &mut self.graph else { return false };
+                graph.update_mode = match value {
+                    "Sequential" => UpdateMode::Sequential,
+                    "Parallel" => UpdateMode::Parallel,
+                    _ => return false,
+                };
+            },
+            ("firing_probability", _) => {
+                let (Some(graph), Ok(probability)) = (&mut self.graph, value.parse::<f64>()) else { return false };
+                if !probability.is_finite() {
+                    return false;
                 }
+                graph.firing_probability = probability.clamp(*FIRING_PROBABILITY_RANGE.start(), *FIRING_PROBABILITY_RANGE.end());
+            },
+            ("max_height", _) => {
+                let Some(graph) = &mut self.graph else { return false };
+                graph.max_height = value.parse().ok();
+            },
+            ("selection_strategy", _) => {
+                let Some(graph) = &mut self.graph else { return false };
+                graph.selection_strategy = match value {
+                    "FirstActive" => VertexSelectionStrategy::FirstActive,
+                    "RandomActive" => VertexSelectionStrategy::RandomActive,
+                    _ => return false,
+                };
+            },
+            ("seed", _) => {
+                let Ok(seed) = value.parse() else { return false };
+                self.seed = seed;
+                self.rng = StdRng::seed_from_u64(seed);
             },
+            _ => return false,
         }
+        true
     }
     
-    /// Parse custom edges string into edge list and vertex count
-    fn parse_custom_edges(&self) -> Result<(Vec<(usize, usize)>, usize), String> {
-        let mut edges = Vec::new();
-        let mut max_vertex = 0;
-        
-        for edge_str in self.custom_edges.split_whitespace() {
-            let parts: Vec<&str> = edge_str.split(',').collect();
-            if parts.len() != 2 {
-                return Err(format!("Invalid edge format: '{}'. Use 'from,to' format.", edge_str));
-            }
-            
-            let from = parts[0].parse::<usize>().map_err(|_| {
-                format!("Invalid vertex index: '{}' in edge '{}'", parts[0], edge_str)
-            })?;
-            
-            let to = parts[1].parse::<usize>().map_err(|_| {
-                format!("Invalid vertex index: '{}' in edge '{}'", parts[1], edge_str)
-            })?;
-            
-            edges.push((from, to));
-            max_vertex = max_vertex.max(from).max(to);
+    /// Reset the graph to its initial configuration
+    fn reset_graph(&mut self) {
+        self.push_undo_replace("Reset");
+        if let Some(graph) = &mut self.graph {
+            graph.reset();
+            self.display_step = 0;
         }
+    }
+    
+    /// Start "Run Until Stable" on a copy of the graph in a worker thread
+    fn start_run_until_stable(&mut self) {
+        let Some(graph) = &self.graph else {
+            return;
+        };
+        let mut graph = graph.clone();
+        self.push_undo("Run Until Stable");
         
-        // Number of vertices is max index + 1
-        let num_vertices = max_vertex + 1;
-        
-        if edges.is_empty() {
-            return Err("No valid edges provided".to_string());
+        let max_steps = self.max_steps;
+        // The worker gets its own generator, seeded from ours so runs stay reproducible
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        self.job = Some(BackgroundJob::spawn("Run Until Stable", max_steps, move |ctx| {
+            let result = graph.run_observed(max_steps, &mut rng, |steps| {
+                ctx.report(steps);
+                !ctx.is_cancelled()
+            });
+            (graph, result)
+        }));
+    }
+    
+    /// Take over the graph from a finished background run
+    fn poll_job(&mut self) {
+        let Some(job) = &mut self.job else {
+            return;
+        };
+        match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished((graph, result)) => {
+                match result {
+                    Ok(steps) => println!("Simulation finished in {} steps", steps),
+                    Err(e) => report_error(format!("Run error: {}", e)),
+                }
+                self.display_step = graph.history.len().saturating_sub(1);
+                self.graph = Some(graph);
+            },
+            JobStatus::Failed => {
+                self.undo_stack.pop(); // The graph was never modified
+                report_error("Simulation thread stopped unexpectedly".to_string());
+            },
         }
-        
-        Ok((edges, num_vertices))
+        self.job = None;
     }
     
-    /// Calculate node positions for network visualization
-    fn calculate_node_positions(&mut self) {
-        if let Some(graph) = &self.graph {
-            let n = graph.num_vertices;
-            self.node_positions.clear();
-            
-            match self.graph_type {
-                GraphType::Grid => {
-                    // Position nodes in a grid layout
-                    for y in 0..self.grid_height {
-                        for x in 0..self.grid_width {
-                            let pos = egui::Vec2::new(
-                                x as f32 * 100.0,
-                                y as f32 * 100.0,
-                            );
-                            self.node_positions.push(pos);
-                        }
+    /// Start the stabilization experiment for the selected graph types on a worker thread.
+    ///
+    /// Runs use the parallel update, so the stabilization time counts rounds in which all
+    /// active vertices fire at once.
+    fn start_experiment(&mut self) {
+        let mut graphs = Vec::new();
+        for &graph_type in &self.experiment_types {
+            match build_graph(graph_type, self.graph_size, self.grid_width, self.grid_height, "") {
+                Ok(mut graph) => {
+                    graph.update_mode = UpdateMode::Parallel;
+                    if self.experiment_with_sink {
+                        let _ = graph.set_sink(Some(0));
                     }
+                    graphs.push((graph_type, graph));
                 },
-                GraphType::Cycle => {
-                    // Position nodes in a circle
-                    let radius = 200.0;
-                    let center = egui::Vec2::new(250.0, 250.0);
-                    
-                    for i in 0..n {
-                        let angle = (i as f32 / n as f32) * 2.0 * std::f32::consts::PI;
-                        let pos = egui::Vec2::new(
-                            center.x + radius * angle.cos(),
-                            center.y + radius * angle.sin(),
-                        );
-                        self.node_positions.push(pos);
-                    }
+                Err(e) => {
+                    report_error(e);
+                    return;
                 },
-                GraphType::Complete | GraphType::Star | GraphType::Custom => {
-                    // Position nodes in a circle for these graph types too
-                    let radius = 200.0;
-                    let center = egui::Vec2::new(250.0, 250.0);
-                    
-                    // For Star, place center at the middle
-                    if self.graph_type == GraphType::Star {
-                        self.node_positions.push(center);
-                        for i in 1..n {
-                            let angle = ((i-1) as f32 / (n-1) as f32) * 2.0 * std::f32::consts::PI;
-                            let pos = egui::Vec2::new(
-                                center.x + radius * angle.cos(),
-                                center.y + radius * angle.sin(),
-                            );
-                            self.node_positions.push(pos);
-                        }
-                    } else {
-                        // Circle layout for Complete and Custom
-                        for i in 0..n {
-                            let angle = (i as f32 / n as f32) * 2.0 * std::f32::consts::PI;
-                            let pos = egui::Vec2::new(
-                                center.x + radius * angle.cos(),
-                                center.y + radius * angle.sin(),
-                            );
-                            self.node_positions.push(pos);
-                        }
+            }
+        }
+        
+        let (low, high) = self.experiment_densities;
+        let points = self.experiment_points.max(2);
+        let densities: Vec<f64> = (0..points)
+            .map(|i| low + (high - low) * i as f64 / (points - 1) as f64)
+            .collect();
+        let (trials, max_steps) = (self.experiment_trials, self.max_steps);
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let total = graphs.len() * densities.len();
+        self.experiment_job = Some(BackgroundJob::spawn("Stabilization Experiment", total, move |ctx| {
+            let mut results = Vec::new();
+            for (graph_type, graph) in graphs {
+                let mut stats = Vec::new();
+                for &density in &densities {
+                    if ctx.is_cancelled() {
+                        break;
                     }
-                },
+                    stats.push(graph.measure_stabilization(density, trials, max_steps, &mut rng));
+                    ctx.report(results.len() * densities.len() + stats.len());
+                }
+                results.push((graph_type, stats));
             }
+            results
+        }));
+    }
+    
+    fn poll_experiment(&mut self) {
+        let Some(job) = &mut self.experiment_job else {
+            return;
+        };
+        match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(results) => self.experiment_results = results,
+            JobStatus::Failed => report_error("Experiment thread stopped unexpectedly".to_string()),
         }
+        self.experiment_job = None;
     }
     
-    /// Get the configuration at the current display step
-    fn current_configuration(&self) -> Option<&Vec<i32>> {
-        if let Some(graph) = &self.graph {
-            if !graph.history.is_empty() {
-                let step = self.display_step.min(graph.history.len() - 1);
-                return Some(&graph.history[step]);
+    /// Plot the mean stabilization time (or firings) against the chip density per graph type
+    fn draw_experiment(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.experiment_show_firings, false, "Stabilization Time");
+            ui.radio_value(&mut self.experiment_show_firings, true, "Total Firings");
+        });
+        let firings = self.experiment_show_firings;
+        Plot::new("stabilization_experiment")
+            .height(250.0)
+            .legend(Legend::default())
+            .x_axis_label("Chips per vertex")
+            .y_axis_label(if firings { "Mean firings" } else { "Mean steps" })
+            .show(ui, |plot_ui| {
+                for (graph_type, stats) in &self.experiment_results {
+                    // Densities at which no run stabilized have no mean
+                    let points: Vec<[f64; 2]> = stats
+                        .iter()
+                        .filter(|s| s.stabilized_fraction > 0.0)
+                        .map(|s| [s.density, if firings { s.mean_firings } else { s.mean_steps }])
+                        .collect();
+                    plot_ui.line(Line::new(points.clone()).name(graph_type.name()));
+                    plot_ui.points(Points::new(points).radius(3.0).name(graph_type.name()));
+                }
+            });
+        for (graph_type, stats) in &self.experiment_results {
+            if let Some(first) = stats.iter().find(|s| s.stabilized_fraction < 1.0) {
+                ui.label(format!(
+                    "{}: only {:.0}% of runs stabilized from density {:.2}",
+                    graph_type.name(), first.stabilized_fraction * 100.0, first.density
+                ));
             }
         }
-        None
     }
     
-    /// Get active vertices at the current display step
-    fn current_active_vertices(&self) -> Vec<usize> {
-        if let Some(config) = self.current_configuration() {
-            if let Some(graph) = &self.graph {
-                // Identify which vertices would be active with this configuration
-                let mut active = Vec::new();
-                for i in 0..graph.num_vertices {
-                    if config[i] >= graph.degrees[i] as i32 {
-                        active.push(i);
-                    }
-                }
-                return active;
+    /// Edit the label and color tag of a vertex
+    fn metadata_editor(&mut self, ui: &mut egui::Ui, vertex: usize) {
+        let Some(graph) = &mut self.graph else {
+            return;
+        };
+        if vertex >= graph.num_vertices {
+            return;
+        }
+        let metadata = graph.metadata(vertex).clone();
+        let mut label = metadata.label.clone().unwrap_or_default();
+        let mut tagged = metadata.color.is_some();
+        let mut color = metadata.color.unwrap_or([200, 80, 80]);
+        ui.horizontal(|ui| {
+            ui.label("Label:");
+            ui.text_edit_singleline(&mut label);
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut tagged, "Color Tag");
+            ui.add_enabled_ui(tagged, |ui| ui.color_edit_button_srgb(&mut color));
+        });
+        
+        let edited = VertexMetadata {
+            label: (!label.trim().is_empty()).then_some(label),
+            color: tagged.then_some(color),
+        };
+        if edited != metadata {
+            let _ = graph.set_metadata(vertex, edited);
+        }
+    }
+    
+    /// Edit the firing threshold and dissipation of a vertex, recording changes in the
+    /// session journal
+    fn threshold_editor(&mut self, ui: &mut egui::Ui, vertex: usize) {
+        let Some(graph) = &self.graph else {
+            return;
+        };
+        if vertex >= graph.num_vertices {
+            return;
+        }
+        let loss = graph.firing_loss(vertex);
+        let mut threshold = graph.threshold(vertex);
+        let mut dissipation = graph.dissipation(vertex);
+        ui.horizontal(|ui| {
+            ui.label("Firing Threshold:");
+            ui.add(egui::DragValue::new(&mut threshold).range(loss..=loss + 1000))
+                .on_hover_text("Chips the vertex needs to fire. It still loses only its firing loss, so the chips above that stay behind.");
+            if threshold != loss && ui.button("Reset").clicked() {
+                threshold = loss;
             }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Dissipation:");
+            ui.add(egui::DragValue::new(&mut dissipation).range(0..=1000))
+                .on_hover_text("Chips lost on every firing besides the one sent along each edge, as in a leaky resource-flow network");
+        });
+        if dissipation != graph.dissipation(vertex) {
+            session::record(self.name(), SessionAction::perform("set_dissipation", format!("{} {}", vertex, dissipation)));
+            self.edit_vertex(vertex, |graph| graph.set_dissipation(vertex, dissipation));
+        } else if threshold != graph.threshold(vertex) {
+            session::record(self.name(), SessionAction::perform("set_threshold", format!("{} {}", vertex, threshold)));
+            self.edit_vertex(vertex, |graph| graph.set_threshold(vertex, threshold));
         }
-        Vec::new()
     }
     
-    /// Reset the graph to its initial configuration
-    fn reset_graph(&mut self) {
+    /// Select `vertex` and change how it fires
+    fn edit_vertex(
+        &mut self,
+        vertex: usize,
+        edit: impl FnOnce(&mut ChipFiringGraph) -> Result<(), ChipFiringError>,
+    ) {
+        self.selected_vertex = Some(vertex);
         if let Some(graph) = &mut self.graph {
-            graph.reset();
-            self.display_step = 0;
+            if let Err(e) = edit(graph) {
+                report_error(format!("Failed to edit vertex: {}", e));
+            }
         }
+        // Dissipation is part of the Laplacian
+        self.clear_analysis();
     }
     
-    /// Execute a single step of the simulation
+    /// Execute a single step of the simulation. In driven mode a chip is dropped every
+    /// `drive_interval` steps, and stable configurations wait for the next chip.
     fn step_simulation(&mut self) {
-        if let Some(graph) = &mut self.graph {
+        let drop = self.driven && self.steps_since_drop + 1 >= self.drive_interval;
+        if self.driven {
+            self.steps_since_drop = if drop { 0 } else { self.steps_since_drop + 1 };
+        }
+        let Some(stable) = self.graph.as_ref().map(|graph| graph.is_stable()) else {
+            return;
+        };
+        if drop || !stable {
+            self.push_undo("Step");
+        }
+        let Some(graph) = &mut self.graph else {
+            return;
+        };
+        
+        if drop {
+            if let Err(e) = graph.drop_chip(self.drive_target, &mut self.rng) {
+                report_error(format!("Failed to drop chip: {}", e));
+                self.driven = false;
+            }
+        }
+        let animate = self.animate_firings && self.visualization_mode == VisualizationMode::Network;
+        let counts_before = if animate { graph.firing_counts.clone() } else { Vec::new() };
+        if !(self.driven && graph.is_stable()) {
             if let Err(e) = graph.step(&mut self.rng) {
-                self.error_message = Some(format!("Simulation error: {}", e));
-            } else {
-                self.display_step = graph.history.len() - 1;
+                report_error(format!("Simulation error: {}", e));
             }
         }
+        self.trail_sources = (0..counts_before.len())
+            .filter(|&v| graph.firing_counts[v] > counts_before[v])
+            .collect();
+        self.trail_progress = 0.0;
+        self.display_step = graph.history.len() - 1;
+        if metrics::has_subscribers() {
+            metrics::publish(Metric::ActiveVertices, graph.active_vertices().len() as f64);
+            metrics::publish(Metric::TotalChips, graph.total_chips() as f64);
+        }
+    }
+    
+    /// Stabilize from a worklist without recording intermediate steps
+    fn stabilize_fast(&mut self) {
+        self.push_undo("Stabilize");
+        if let Some(graph) = &mut self.graph {
+            match graph.stabilize(self.max_firings) {
+                Ok(firings) => {
+                    let stable = graph.is_stable();
+                    if stable {
+                        report_info(format!("Stabilized after {} firings", firings));
+                    } else {
+                        report_error(format!("Not stable after {} firings", firings));
+                    }
+                    events::emit(EventKind::RunCompleted { iterations: firings, converged: stable });
+                },
+                Err(e) => report_error(format!("Stabilize error: {}", e)),
+            }
+            self.display_step = graph.history.len() - 1;
+        }
     }
     
     /// Initialize a random configuration
     fn randomize_configuration(&mut self) {
+        self.checkpoint("Randomize");
+        self.push_undo_replace("Randomize");
         if let Some(graph) = &mut self.graph {
-            let _rng = rand::thread_rng();
             let mut new_config = Vec::with_capacity(graph.num_vertices);
             
             for i in 0..graph.num_vertices {
-                // Random number of chips from 0 to degree
-                let degree = graph.degrees[i] as i32;
-                let chips = if rand::random::<bool>() {
-                    rand::random::<i32>() % (degree + 1) 
+                // Random number of chips from 0 to the firing threshold
+                let threshold = graph.threshold(i) as i32;
+                let chips = if self.rng.gen::<bool>() {
+                    self.rng.gen::<i32>() % (threshold + 1)
                 } else {
-                    degree // Exactly the degree (active)
+                    threshold // Exactly the threshold (active)
                 };
                 new_config.push(chips.max(0)); // Ensure non-negative
             }
             
             if let Err(e) = graph.set_configuration(new_config) {
-                self.error_message = Some(format!("Failed to set random configuration: {}", e));
+                report_error(format!("Failed to set random configuration: {}", e));
             } else {
                 self.display_step = 0;
             }
@@ -346,31 +940,51 @@ impl ChipFiringWindow {
     
     /// Add a chip to the selected vertex
     fn add_chip(&mut self) {
-        if let (Some(graph), Some(vertex)) = (&mut self.graph, self.selected_vertex) {
-            if vertex < graph.num_vertices {
-                let mut new_config = graph.configuration.clone();
-                new_config[vertex] += 1;
-                
-                if let Err(e) = graph.set_configuration(new_config) {
-                    self.error_message = Some(format!("Failed to add chip: {}", e));
-                } else {
-                    self.display_step = 0;
-                }
-            }
-        }
+        self.change_selected_chips(1, "Add Chip");
     }
     
     /// Remove a chip from the selected vertex
     fn remove_chip(&mut self) {
-        if let (Some(graph), Some(vertex)) = (&mut self.graph, self.selected_vertex) {
-            if vertex < graph.num_vertices && graph.configuration[vertex] > 0 {
-                let mut new_config = graph.configuration.clone();
-                new_config[vertex] -= 1;
-                
-                if let Err(e) = graph.set_configuration(new_config) {
-                    self.error_message = Some(format!("Failed to remove chip: {}", e));
-                } else {
-                    self.display_step = 0;
+        self.change_selected_chips(-1, "Remove Chip");
+    }
+    
+    /// Add or remove chips at the selected vertex, appending the change to history
+    fn change_selected_chips(&mut self, amount: i32, label: &'static str) {
+        let Some(vertex) = self.selected_vertex else { return };
+        let valid = self.graph.as_ref().is_some_and(|graph| {
+            vertex < graph.num_vertices && graph.configuration[vertex] + amount >= 0
+        });
+        if !valid {
+            return;
+        }
+        
+        self.push_undo(label);
+        if let Some(graph) = &mut self.graph {
+            if let Err(e) = graph.add_chips(vertex, amount) {
+                report_error(format!("Failed to change chips: {}", e));
+            } else {
+                self.display_step = graph.history.len() - 1;
+            }
+        }
+    }
+    
+    /// Fire the selected vertex once (if it is active)
+    fn fire_selected(&mut self) {
+        let Some(vertex) = self.selected_vertex else { return };
+        if self.graph.is_none() {
+            return;
+        }
+        
+        self.push_undo("Fire");
+        if let Some(graph) = &mut self.graph {
+            match graph.fire_vertex(vertex) {
+                Ok(_) => {
+                    graph.record_configuration();
+                    self.display_step = graph.history.len() - 1;
+                },
+                Err(e) => {
+                    self.undo_stack.pop(); // Nothing changed
+                    report_error(format!("Failed to fire vertex: {}", e));
                 }
             }
         }
@@ -378,6 +992,9 @@ impl ChipFiringWindow {
     
     /// Trigger an avalanche at the selected vertex
     fn trigger_avalanche(&mut self) {
+        if self.selected_vertex.is_some() {
+            self.push_undo("Avalanche");
+        }
         if let (Some(graph), Some(vertex)) = (&mut self.graph, self.selected_vertex) {
             if vertex < graph.num_vertices {
                 match graph.trigger_avalanche(vertex, self.max_steps, &mut self.rng) {
@@ -386,15 +1003,258 @@ impl ChipFiringWindow {
                         println!("Avalanche completed in {} steps", steps);
                     },
                     Err(e) => {
-                        self.error_message = Some(format!("Avalanche error: {}", e));
+                        report_error(format!("Avalanche error: {}", e));
                     }
                 }
             }
         }
     }
     
+    /// Drop a chip on the selected vertex and relax the avalanche wave by wave, keeping
+    /// the footprint of every wave for the grid view
+    fn avalanche_waves(&mut self) {
+        let Some(vertex) = self.selected_vertex else { return };
+        let valid = self.graph.as_ref().is_some_and(|graph| vertex < graph.num_vertices);
+        if !valid {
+            return;
+        }
+        self.push_undo("Avalanche Waves");
+        if let Some(graph) = &mut self.graph {
+            let result = graph.add_chips(vertex, 1).and_then(|_| graph.avalanche_waves(vertex, self.max_firings));
+            match result {
+                Ok(waves) => {
+                    self.display_step = graph.history.len() - 1;
+                    self.waves = waves;
+                    self.show_waves = true;
+                },
+                Err(e) => report_error(format!("Avalanche error: {}", e)),
+            }
+        }
+    }
+    
+    /// Whether primary drags in the current view paint chips instead of panning
+    fn painting_enabled(&self) -> bool {
+        self.paint_chips
+            && self.graph_type == GraphType::Grid
+            && matches!(self.visualization_mode, VisualizationMode::Grid | VisualizationMode::Heatmap)
+    }
+    
+    /// Cells within the brush around `center`, except the sink
+    fn brush_cells(&self, center: usize) -> Vec<usize> {
+        let num_vertices = self.graph.as_ref().map_or(0, |graph| graph.num_vertices);
+        let width = self.grid_width.max(1) as i64;
+        let (cx, cy) = (center as i64 % width, center as i64 / width);
+        let r = self.brush_radius as i64;
+        let limit = (self.brush_radius as f64 + 0.5).powi(2);
+        let mut cells = Vec::new();
+        for y in (cy - r).max(0)..=cy + r {
+            for x in (cx - r).max(0)..(cx + r + 1).min(width) {
+                let cell = (y * width + x) as usize;
+                let inside = ((x - cx).pow(2) + (y - cy).pow(2)) as f64 <= limit;
+                if inside && cell < num_vertices && Some(cell) != self.sink {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells
+    }
+    
+    /// Add `amount` chips to each of `cells` as one brush application
+    fn paint(&mut self, cells: &[usize], amount: i32) {
+        if let Some(graph) = &mut self.graph {
+            match graph.add_chips_to(cells, amount) {
+                Ok(()) => self.display_step = graph.history.len() - 1,
+                Err(e) => report_error(format!("Failed to paint chips: {}", e)),
+            }
+        }
+    }
+    
+    /// Import a graph from a DOT (.dot/.gv) or plain edge-list file
+    fn import_graph(&mut self) {
+        match graph_io::read_graph_file(std::path::Path::new(&self.graph_file_path)) {
+            Ok(graph) => {
+                report_info(format!("Imported graph with {} vertices from {}", graph.num_vertices, self.graph_file_path));
+                self.checkpoint("Import Graph");
+                self.set_graph(graph);
+                self.clear_undo();
+                self.graph_type = GraphType::Custom; // Imported graphs use the circle layout
+                self.calculate_node_positions();
+                self.display_step = 0;
+                self.selected_vertex = None;
+            },
+            Err(e) => report_error(format!("Failed to import '{}': {}", self.graph_file_path, e)),
+        }
+    }
+    
+    /// Export the current graph (with chip counts as node labels) to a DOT file
+    fn export_graph(&mut self) {
+        if let Some(graph) = &self.graph {
+            match std::fs::write(&self.graph_file_path, graph_io::to_dot(graph, Some(self.seed))) {
+                Ok(_) => {
+                    report_info(format!("Exported graph to {}", self.graph_file_path));
+                },
+                Err(e) => report_error(format!("Failed to write '{}': {}", self.graph_file_path, e)),
+            }
+        }
+    }
+    
+    /// Render a configuration for file export: the grid view for grid graphs shown as a
+    /// grid or heatmap, the network view otherwise. Chip counts are shown as shades of
+    /// gray relative to the firing threshold, active vertices in green.
+    fn render_configuration(&self, graph: &ChipFiringGraph, config: &[i32], scale: u32) -> image::RgbaImage {
+        let fill = |v: usize| {
+            let threshold = graph.threshold(v);
+            if config[v] >= threshold as i32 {
+                image::Rgba([0, 200, 0, 255])
+            } else {
+                let shade = 255 - (200.0 * (config[v].max(0) as f32 / threshold.max(1) as f32)) as u8;
+                image::Rgba([shade, shade, shade, 255])
+            }
+        };
+        
+        let grid_view = self.graph_type == GraphType::Grid
+            && matches!(self.visualization_mode, VisualizationMode::Grid | VisualizationMode::Heatmap | VisualizationMode::Heights3D);
+        if grid_view {
+            let cell = scale.max(1) as f32;
+            let mut canvas = Canvas::new(
+                self.grid_width as u32 * scale.max(1),
+                self.grid_height as u32 * scale.max(1),
+                raster::WHITE,
+            );
+            for v in 0..graph.num_vertices.min(self.grid_width * self.grid_height) {
+                let (x, y) = ((v % self.grid_width) as f32 * cell, (v / self.grid_width) as f32 * cell);
+                canvas.fill_rect(x, y, cell, cell, fill(v));
+                canvas.stroke_rect(x, y, cell, cell, 1.0, raster::BLACK);
+            }
+            return canvas.into_image();
+        }
+        
+        let zoom = scale as f32 / DEFAULT_SCALE as f32;
+        let radius = self.vertex_radius * zoom;
+        let (max_x, max_y) = self.node_positions.iter().fold((0.0f32, 0.0f32), |(mx, my), p| (mx.max(p.x), my.max(p.y)));
+        let mut canvas = Canvas::new(
+            ((max_x * zoom + 2.0 * radius).ceil() as u32).max(1),
+            ((max_y * zoom + 2.0 * radius).ceil() as u32).max(1),
+            raster::WHITE,
+        );
+        let point = |v: usize| (self.node_positions[v].x * zoom, self.node_positions[v].y * zoom);
+        for i in 0..graph.num_vertices {
+            for j in graph.neighbors(i) {
+                if i < j {
+                    canvas.draw_line(point(i), point(j), self.edge_thickness * zoom, raster::GRAY);
+                }
+            }
+        }
+        for v in 0..graph.num_vertices {
+            let (x, y) = point(v);
+            canvas.fill_circle(x, y, radius, fill(v));
+            canvas.stroke_circle(x, y, radius, zoom.max(1.0), raster::BLACK);
+        }
+        canvas.into_image()
+    }
+    
+    /// Write the recorded history as an animated GIF
+    fn record_animation(&mut self) {
+        let Some(graph) = &self.graph else {
+            return;
+        };
+        if self.node_positions.len() != graph.num_vertices {
+            report_error("Cannot record animation: node positions mismatch".to_string());
+            return;
+        }
+        
+        let export = &self.animation_export;
+        let frames = graph.history
+            .downsample(export.max_frames)
+            .into_iter()
+            .map(|entry| self.render_configuration(graph, &entry.state, export.scale))
+            .collect();
+        
+        match write_gif(&export.path, frames, export.fps) {
+            Ok(()) => {
+                report_info(format!("Recorded animation to {}", export.path));
+            },
+            Err(e) => report_error(format!("Failed to record animation: {}", e)),
+        }
+    }
+    
+    /// Save the configuration at the current display step, or the firing activity of a
+    /// grid graph, as a PNG
+    fn save_snapshot(&mut self, activity: bool) {
+        let Some(graph) = &self.graph else {
+            return;
+        };
+        let export = &self.snapshot_export;
+        let (image, view) = if activity {
+            let values = self.heatmap_values(graph);
+            let max_value = values.iter().cloned().fold(0.0, f64::max);
+            let rows: Vec<Vec<f64>> = values.chunks(self.grid_width.max(1)).map(|row| row.to_vec()).collect();
+            let colormap = self.heatmap_colormap;
+            let image = render_value_grid(&rows, export.scale, |value| {
+                image::Rgba(colormap.map(value, 0.0, max_value).to_array())
+            });
+            (image, "Activity")
+        } else {
+            let Some(config) = self.current_configuration() else {
+                return;
+            };
+            if self.node_positions.len() != graph.num_vertices {
+                report_error("Cannot save image: node positions mismatch".to_string());
+                return;
+            }
+            (self.render_configuration(graph, config, export.scale), "Configuration")
+        };
+        
+        let path = export.path_for(view);
+        match write_png(&path, &image) {
+            Ok(()) => {
+                report_info(format!("Saved {}", path));
+            },
+            Err(e) => report_error(format!("Failed to save image: {}", e)),
+        }
+    }
+    
+    /// Extent of the current view in content coordinates, for zooming and panning
+    fn view_content(&self) -> egui::Rect {
+        match self.visualization_mode {
+            VisualizationMode::Network if !self.node_positions.is_empty() => {
+                let (min, max) = self.node_positions.iter().fold(
+                    (egui::vec2(f32::MAX, f32::MAX), egui::vec2(f32::MIN, f32::MIN)),
+                    |(min, max), &p| (min.min(p), max.max(p)),
+                );
+                // Leave room for the metadata rings and the labels below the vertices
+                let margin = self.vertex_radius + 6.0;
+                egui::Rect::from_min_max(
+                    (min - egui::vec2(margin, margin)).to_pos2(),
+                    (max + egui::vec2(margin, margin + 16.0)).to_pos2(),
+                )
+            },
+            VisualizationMode::Network => egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(500.0, 500.0)),
+            _ => egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(self.grid_width as f32, self.grid_height as f32) * self.grid_cell_size,
+            ),
+        }
+    }
+    
+    /// Vertex drawn at `offset` from the top left corner of the view, if any
+    fn vertex_at(&self, offset: egui::Vec2, camera: Camera) -> Option<usize> {
+        let graph = self.graph.as_ref()?;
+        match self.visualization_mode {
+            VisualizationMode::Network if self.node_positions.len() == graph.num_vertices => {
+                let positions: Vec<egui::Vec2> = self.node_positions.iter().map(|&p| camera.transform(p)).collect();
+                network::node_at(&positions, egui::Pos2::ZERO, offset.to_pos2(), self.vertex_radius * camera.zoom)
+            },
+            VisualizationMode::Grid | VisualizationMode::Heatmap if self.graph_type == GraphType::Grid => {
+                let view = ChipView::new(graph, &graph.configuration, false, None).with_grid_width(self.grid_width);
+                cell_at(&view, self.grid_cell_size, camera, offset)
+            },
+            _ => None, // No interaction for the bar chart
+        }
+    }
+    
     /// Draw the graph as a network (immutable self, takes painter)
-    fn draw_network(&self, painter: &egui::Painter, response: &egui::Response) {
+    fn draw_network(&self, painter: &egui::Painter, response: &egui::Response, camera: Camera) {
         if let Some(graph) = &self.graph {
             if self.node_positions.len() != graph.num_vertices {
                 // Cannot draw if positions mismatch
@@ -408,120 +1268,177 @@ impl ChipFiringWindow {
                 return;
             }
             
-            // Get configuration and active vertices for current display step
+            // Configuration at the current display step; each undirected edge is drawn
+            // once, as thick as its multiplicity
             let config = self.current_configuration().unwrap_or(&graph.configuration);
-            let active_vertices = if self.show_active_vertices {
-                self.current_active_vertices()
-            } else {
-                Vec::new()
-            };
+            let view = ChipView::new(graph, config, self.show_active_vertices, self.selected_vertex);
+            let positions: Vec<egui::Vec2> = self.node_positions.iter().map(|&p| camera.transform(p)).collect();
+            let radius = self.vertex_radius * camera.zoom;
+            network::draw_model(
+                painter,
+                response.rect.min,
+                &positions,
+                &view,
+                NetworkStyle { vertex_radius: radius, edge_thickness: self.edge_thickness * camera.zoom },
+            );
             
-            // Draw edges first
-            for i in 0..graph.num_vertices {
-                for &j in &graph.neighbors(i) {
-                    if i < j { 
-                        let start = self.node_positions[i];
-                        let end = self.node_positions[j];
-                        
-                        painter.line_segment(
-                            [response.rect.min + start, response.rect.min + end],
-                            egui::Stroke::new(self.edge_thickness, egui::Color32::GRAY),
-                        );
+            // Chips on their way from the vertices that fired in the last step, only while
+            // the latest configuration is shown
+            if !self.trail_sources.is_empty() && self.display_step + 1 == graph.history.len() {
+                // Ease out, so chips slow down as they arrive
+                let t = 1.0 - (1.0 - self.trail_progress.clamp(0.0, 1.0) as f32).powi(2);
+                for &source in &self.trail_sources {
+                    for (target, _) in graph.neighbor_edges(source) {
+                        let offset = positions[source] + (positions[target] - positions[source]) * t;
+                        painter.circle_filled(response.rect.min + offset, (radius * 0.3).max(2.0), CHIP_TRAIL_COLOR);
                     }
                 }
             }
             
-            // Draw nodes
-            for i in 0..graph.num_vertices {
-                let pos = response.rect.min + self.node_positions[i];
-                let is_active = active_vertices.contains(&i);
-                let is_selected = Some(i) == self.selected_vertex;
-                
-                let fill_color = if is_selected {
-                    egui::Color32::YELLOW
-                } else if is_active {
-                    egui::Color32::GREEN
-                } else {
-                    egui::Color32::WHITE
-                };
-                
-                painter.circle_filled(pos, self.vertex_radius, fill_color);
-                painter.circle_stroke(pos, self.vertex_radius, egui::Stroke::new(2.0, egui::Color32::BLACK));
-                
-                let chip_count = config[i].to_string();
-                painter.text(
-                    pos,
-                    egui::Align2::CENTER_CENTER,
-                    chip_count,
-                    egui::FontId::proportional(14.0),
-                    egui::Color32::BLACK,
-                );
+            // Color tags as rings around the vertices, labels below them
+            let text_color = painter.ctx().style().visuals.text_color();
+            for (i, &offset) in positions.iter().enumerate() {
+                let pos = response.rect.min + offset;
+                let metadata = graph.metadata(i);
+                if let Some([r, g, b]) = metadata.color {
+                    painter.circle_stroke(pos, radius + 3.0, egui::Stroke::new(3.0, egui::Color32::from_rgb(r, g, b)));
+                }
+                if let Some(label) = &metadata.label {
+                    painter.text(
+                        pos + egui::vec2(0.0, radius + 4.0),
+                        egui::Align2::CENTER_TOP,
+                        label,
+                        egui::FontId::proportional(12.0),
+                        text_color,
+                    );
+                }
+            }
+            
+            // Interaction is handled by the caller (show_content)
+        }
+    }
+    
+    /// Draw the graph as a grid (immutable self, takes painter)
+    /// Draw the chip counts of a grid graph as a 3D surface
+    fn draw_heights(&self, painter: &egui::Painter, response: &egui::Response) {
+        let Some(graph) = &self.graph else {
+            return;
+        };
+        let cells = self.grid_width * self.grid_height;
+        if self.graph_type != GraphType::Grid || !height_field::is_available(painter.ctx()) || graph.num_vertices < cells {
+            painter.text(
+                response.rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "3D view only for Grid graphs on the wgpu renderer",
+                egui::FontId::default(),
+                egui::Color32::RED,
+            );
+            return;
+        }
+        
+        // The sink's pile would dwarf the grid, so it is drawn flat
+        let config = self.current_configuration().unwrap_or(&graph.configuration);
+        let heights = (0..cells)
+            .map(|v| if graph.sink() == Some(v) { 0.0 } else { config[v].max(0) as f32 })
+            .collect();
+        let id = response.id.with("chip_firing_heights");
+        height_field::height_field(painter, response.rect, id, heights, self.grid_width, self.orbit_camera);
+    }
+    
+    fn draw_grid(&self, painter: &egui::Painter, response: &egui::Response, camera: Camera) {
+        if let Some(graph) = &self.graph {
+            if self.graph_type != GraphType::Grid {
+                 painter.text(
+                    response.rect.center(), 
+                    egui::Align2::CENTER_CENTER, 
+                    "Grid view only for Grid graphs", 
+                    egui::FontId::default(), 
+                    egui::Color32::RED
+                );
+                return;
+            }
+            
+            let config = self.current_configuration().unwrap_or(&graph.configuration);
+            let mut view = ChipView::new(graph, config, self.show_active_vertices, self.selected_vertex)
+                .with_grid_width(self.grid_width);
+            if self.show_waves {
+                view = view.with_waves(&self.waves);
             }
+            paint_grid(
+                painter,
+                response.rect.min + camera.pan,
+                &view,
+                self.grid_cell_size * camera.zoom,
+                Some(egui::Stroke::new(1.0, egui::Color32::BLACK)),
+            );
             
             // Interaction is handled by the caller (show_content)
         }
     }
     
-    /// Draw the graph as a grid (immutable self, takes painter)
-    fn draw_grid(&self, painter: &egui::Painter, response: &egui::Response) {
+    /// Per-vertex values of the selected heatmap metric
+    fn heatmap_values(&self, graph: &ChipFiringGraph) -> Vec<f64> {
+        match self.heatmap_metric {
+            HeatmapMetric::Cumulative => graph.firing_counts.iter().map(|&c| c as f64).collect(),
+            HeatmapMetric::Recent => graph.firing_activity.clone(),
+        }
+    }
+
+    /// Draw firing activity as a heatmap over the grid (immutable self, takes painter)
+    fn draw_heatmap(&self, painter: &egui::Painter, response: &egui::Response, camera: Camera) {
         if let Some(graph) = &self.graph {
             if self.graph_type != GraphType::Grid {
                  painter.text(
                     response.rect.center(), 
                     egui::Align2::CENTER_CENTER, 
-                    "Grid view only for Grid graphs", 
+                    "Heatmap view only for Grid graphs", 
                     egui::FontId::default(), 
                     egui::Color32::RED
                 );
                 return;
             }
             
-            let config = self.current_configuration().unwrap_or(&graph.configuration);
-            let active_vertices = if self.show_active_vertices {
-                self.current_active_vertices()
-            } else {
-                Vec::new()
-            };
-            
-            let grid_width = self.grid_width;
-            let grid_height = self.grid_height;
+            let values = self.heatmap_values(graph);
+            let max_value = values.iter().cloned().fold(0.0, f64::max);
+            let selected_color = GridColors::get(painter.ctx()).selected;
+            let cell_size = self.grid_cell_size * camera.zoom;
             
-            // Draw grid cells
-            for y in 0..grid_height {
-                for x in 0..grid_width {
-                    let idx = y * grid_width + x;
-                    let is_active = active_vertices.contains(&idx);
-                    let is_selected = Some(idx) == self.selected_vertex;
-                    
-                    let fill_color = if is_selected {
-                        egui::Color32::YELLOW
-                    } else if is_active {
-                        egui::Color32::GREEN
-                    } else {
-                        egui::Color32::WHITE
-                    };
+            for y in 0..self.grid_height {
+                for x in 0..self.grid_width {
+                    let idx = y * self.grid_width + x;
+                    let value = values.get(idx).copied().unwrap_or(0.0);
+                    let fill = self.heatmap_colormap.map(value, 0.0, max_value);
                     
-                    let cell_pos = egui::Vec2::new(x as f32 * self.grid_cell_size, y as f32 * self.grid_cell_size);
+                    let cell_pos = egui::Vec2::new(x as f32 * cell_size, y as f32 * cell_size);
                     let cell_rect = egui::Rect::from_min_size(
-                        response.rect.min + cell_pos,
-                        egui::vec2(self.grid_cell_size, self.grid_cell_size),
+                        response.rect.min + camera.pan + cell_pos,
+                        egui::vec2(cell_size, cell_size),
                     );
                     
-                    painter.rect_filled(cell_rect, 0.0, fill_color);
-                    painter.rect_stroke(cell_rect, 0.0, egui::Stroke::new(1.0, egui::Color32::BLACK));
+                    painter.rect_filled(cell_rect, 0.0, fill);
+                    let stroke_color = if Some(idx) == self.selected_vertex {
+                        selected_color
+                    } else {
+                        egui::Color32::DARK_GRAY
+                    };
+                    painter.rect_stroke(cell_rect, 0.0, egui::Stroke::new(1.0, stroke_color));
                     
-                    let chip_count = config[idx].to_string();
-                    painter.text(
-                        cell_rect.center(),
-                        egui::Align2::CENTER_CENTER,
-                        chip_count,
-                        egui::FontId::proportional(14.0),
-                        egui::Color32::BLACK,
-                    );
+                    // Only label cells when there is room for the text
+                    if cell_size >= 30.0 {
+                        let label = match self.heatmap_metric {
+                            HeatmapMetric::Cumulative => format!("{}", value as u64),
+                            HeatmapMetric::Recent => format!("{:.1}", value),
+                        };
+                        painter.text(
+                            cell_rect.center(),
+                            egui::Align2::CENTER_CENTER,
+                            label,
+                            egui::FontId::proportional(12.0),
+                            GridColors::text_on(fill),
+                        );
+                    }
                 }
             }
-            
-            // Interaction is handled by the caller (show_content)
         }
     }
     
@@ -530,8 +1447,19 @@ impl ChipFiringWindow {
         if let Some(graph) = &self.graph {
             if let Some(config) = self.current_configuration() {
                 let max_chips = config.iter().cloned().max().unwrap_or(0);
+                let colors = GridColors::get(ui.ctx());
+                // Vertices are named by their labels on the x axis
+                let names: Vec<String> = (0..graph.num_vertices).map(|i| graph.vertex_name(i)).collect();
                 
                 let chart = Plot::new("chip_distribution")
+                    .x_axis_formatter(move |mark, _, _| {
+                        let index = mark.value.round();
+                        if (mark.value - index).abs() < 1e-6 && index >= 0.0 {
+                            names.get(index as usize).cloned().unwrap_or_default()
+                        } else {
+                            String::new()
+                        }
+                    })
                     .height(300.0)
                     .y_axis_label("Chips")
                     .x_axis_label("Vertex")
@@ -563,9 +1491,11 @@ impl ChipFiringWindow {
                         let is_selected = Some(i) == self.selected_vertex;
                         
                         let color = if is_selected {
-                            egui::Color32::YELLOW
+                            colors.selected
                         } else if is_active {
-                            egui::Color32::GREEN
+                            colors.active
+                        } else if let Some([r, g, b]) = graph.metadata(i).color {
+                            egui::Color32::from_rgb(r, g, b)
                         } else {
                             egui::Color32::BLUE
                         };
@@ -595,12 +1525,217 @@ impl ChipFiringWindow {
     }
 }
 
+/// Build a chip firing graph of the given predefined type (shared with other graph-based windows)
+pub(crate) fn build_graph(
+    graph_type: GraphType,
+    graph_size: usize,
+    grid_width: usize,
+    grid_height: usize,
+    custom_edges: &str,
+) -> Result<ChipFiringGraph, String> {
+    let graph = graph_type
+        .build(graph_size, grid_width, grid_height, custom_edges)
+        .map_err(|e| e.to_string())?;
+    ChipFiringGraph::from_graph(&graph, vec![0; graph.num_vertices()])
+        .map_err(|e| format!("Failed to create {} graph: {}", graph_type.name().to_lowercase(), e))
+}
+
+/// Calculate node positions for network visualization of a predefined graph type
+pub(crate) fn layout_positions(
+    graph_type: GraphType,
+    n: usize,
+    grid_width: usize,
+    grid_height: usize,
+) -> Vec<egui::Vec2> {
+    let mut node_positions = Vec::with_capacity(n);
+    
+    match graph_type {
+        GraphType::Grid => {
+            // Position nodes in a grid layout
+            for y in 0..grid_height {
+                for x in 0..grid_width {
+                    let pos = egui::Vec2::new(
+                        x as f32 * 100.0,
+                        y as f32 * 100.0,
+                    );
+                    node_positions.push(pos);
+                }
+            }
+        },
+        GraphType::Cycle => {
+            // Position nodes in a circle
+            let radius = 200.0;
+            let center = egui::Vec2::new(250.0, 250.0);
+            
+            for i in 0..n {
+                let angle = (i as f32 / n as f32) * 2.0 * std::f32::consts::PI;
+                let pos = egui::Vec2::new(
+                    center.x + radius * angle.cos(),
+                    center.y + radius * angle.sin(),
+                );
+                node_positions.push(pos);
+            }
+        },
+        GraphType::Complete | GraphType::Star | GraphType::Custom => {
+            // Position nodes in a circle for these graph types too
+            let radius = 200.0;
+            let center = egui::Vec2::new(250.0, 250.0);
+            
+            // For Star, place center at the middle
+            if graph_type == GraphType::Star {
+                node_positions.push(center);
+                for i in 1..n {
+                    let angle = ((i-1) as f32 / (n-1) as f32) * 2.0 * std::f32::consts::PI;
+                    let pos = egui::Vec2::new(
+                        center.x + radius * angle.cos(),
+                        center.y + radius * angle.sin(),
+                    );
+                    node_positions.push(pos);
+                }
+            } else {
+                // Circle layout for Complete and Custom
+                for i in 0..n {
+                    let angle = (i as f32 / n as f32) * 2.0 * std::f32::consts::PI;
+                    let pos = egui::Vec2::new(
+                        center.x + radius * angle.cos(),
+                        center.y + radius * angle.sin(),
+                    );
+                    node_positions.push(pos);
+                }
+            }
+        },
+    }
+    
+    node_positions
+}
+
 impl Window for ChipFiringWindow {
     fn name(&self) -> &str {
         "Chip Firing Graph"
     }
     
+    fn handle_command(&mut self, command: Command) -> bool {
+        // Like the buttons, commands wait for a background run to finish
+        if self.graph.is_none() || self.job.is_some() {
+            return false;
+        }
+        match command {
+            Command::Step => self.step_simulation(),
+            Command::Run => self.start_run_until_stable(),
+            Command::Reset => self.reset_graph(),
+            Command::Train => return false,
+        }
+        true
+    }
+    
+    fn session_settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings = vec![
+            ("graph_type", self.graph_type.name().to_string()),
+            ("graph_size", self.graph_size.to_string()),
+            ("grid_width", self.grid_width.to_string()),
+            ("grid_height", self.grid_height.to_string()),
+            ("edges", self.custom_edges.clone()),
+            ("max_steps", self.max_steps.to_string()),
+            ("max_firings", self.max_firings.to_string()),
+        ];
+        if let Some(graph) = &self.graph {
+            settings.push(("update_mode", format!("{:?}", graph.update_mode)));
+            settings.push(("selection_strategy", format!("{:?}", graph.selection_strategy)));
+            settings.push(("firing_probability", graph.firing_probability.to_string()));
+            settings.push(("max_height", graph.max_height.map_or("none".to_string(), |cap| cap.to_string())));
+        }
+        settings.push(("seed", self.seed.to_string()));
+        settings
+    }
+    
+    fn replay(&mut self, action: &SessionAction) -> bool {
+        match action {
+            SessionAction::Command(command) => self.handle_command(*command),
+            SessionAction::Set { key, value } => self.apply_session_setting(key, value),
+            SessionAction::Perform { name, argument } => {
+                // Vertex edits act on the selected vertex, so they select theirs first
+                if let Ok(vertex) = argument.parse::<usize>() {
+                    self.selected_vertex = Some(vertex);
+                }
+                match name.as_str() {
+                    "create_graph" => self.create_and_show_graph(),
+                    "stabilize" => self.stabilize_fast(),
+                    "randomize" => self.randomize_configuration(),
+                    "undo" => self.undo(),
+                    "redo" => self.redo(),
+                    "add_chip" => self.add_chip(),
+                    "remove_chip" => self.remove_chip(),
+                    "fire" => self.fire_selected(),
+                    "avalanche" => self.trigger_avalanche(),
+                    "avalanche_waves" => self.avalanche_waves(),
+                    "preset" => return self.load_preset(argument),
+                    "paint_chips" => {
+                        // The argument is "<amount> <cell>,<cell>,..."
+                        let Some((amount, cells)) = argument.split_once(' ') else { return false };
+                        let (Ok(amount), Ok(cells)) = (
+                            amount.parse(),
+                            cells.split(',').map(str::parse).collect::<Result<Vec<usize>, _>>(),
+                        ) else {
+                            return false;
+                        };
+                        self.paint(&cells, amount);
+                    },
+                    "set_threshold" | "set_dissipation" => {
+                        // The argument is "<vertex> <value>"
+                        let parsed = argument.split_once(' ').and_then(|(vertex, value)| {
+                            Some((vertex.parse().ok()?, value.parse().ok()?))
+                        });
+                        let Some((vertex, value)) = parsed else { return false };
+                        if name == "set_threshold" {
+                            self.edit_vertex(vertex, |graph| graph.set_threshold(vertex, value));
+                        } else {
+                            self.edit_vertex(vertex, |graph| graph.set_dissipation(vertex, value));
+                        }
+                    },
+                    _ => return false,
+                }
+                true
+            },
+        }
+    }
+    
+    fn is_busy(&self) -> bool {
+        self.job.is_some()
+    }
+    
+    fn presets(&self) -> &'static [(&'static str, &'static str)] {
+        &PRESETS
+    }
+    
+    fn load_preset(&mut self, name: &str) -> bool {
+        let Some(preset) = ChipFiringPreset::ALL.into_iter().find(|preset| preset.name() == name) else {
+            return false;
+        };
+        // Loading replaces the graph, which a background run still owns
+        if self.job.is_some() {
+            return false;
+        }
+        self.load_grid_preset(preset);
+        true
+    }
+    
+    fn restore(&mut self, snapshot: Snapshot) -> bool {
+        match snapshot.downcast::<GraphState>() {
+            Ok(state) => {
+                self.restore_graph(*state);
+                true
+            },
+            Err(_) => false,
+        }
+    }
+    
     fn show_config(&mut self, ui: &mut egui::Ui) {
+        // The graph belongs to the worker until the background run finishes
+        if self.job.is_some() {
+            ui.label("Simulation running...");
+            ui.disable();
+        }
+        
         // Graph creation settings
         ui.heading("Graph Settings");
         ui.separator();
@@ -619,9 +1754,9 @@ impl Window for ChipFiringWindow {
             GraphType::Grid => {
                 ui.horizontal(|ui| {
                     ui.label("Width:");
-                    ui.add(egui::DragValue::new(&mut self.grid_width).speed(1.0).range(2..=20));
+                    ui.add(egui::DragValue::new(&mut self.grid_width).speed(1.0).range(2..=100));
                     ui.label("Height:");
-                    ui.add(egui::DragValue::new(&mut self.grid_height).speed(1.0).range(2..=20));
+                    ui.add(egui::DragValue::new(&mut self.grid_height).speed(1.0).range(2..=100));
                 });
             },
             GraphType::Cycle | GraphType::Complete | GraphType::Star => {
@@ -631,25 +1766,48 @@ impl Window for ChipFiringWindow {
                 });
             },
             GraphType::Custom => {
-                ui.label("Enter edges as space-separated pairs (e.g., \"0,1 1,2 2,0\"):");
+                ui.label("Enter edges as space-separated pairs (e.g., \"0,1 1,2 2,0\"), with an optional weight for multi-edges (e.g., \"0,1,3\"):");
                 ui.text_edit_multiline(&mut self.custom_edges);
             },
         }
         
         if ui.button("Create Graph").clicked() {
-            match self.create_graph() {
-                Ok(graph) => {
-                    self.graph = Some(graph);
-                    self.calculate_node_positions();
-                    self.display_step = 0;
-                    self.error_message = None;
-                },
-                Err(e) => {
-                    self.error_message = Some(e);
-                },
-            }
+            session::record(self.name(), SessionAction::perform("create_graph", ""));
+            self.create_and_show_graph();
         }
         
+        ui.collapsing("Import / Export", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut self.graph_file_path);
+            });
+            ui.label("DOT (.dot, .gv) or edge list (\"from to [count]\" per line)");
+            ui.horizontal(|ui| {
+                if ui.button("Import Graph…").clicked() {
+                    self.import_graph();
+                }
+                if ui.add_enabled(self.graph.is_some(), egui::Button::new("Export Graph")).clicked() {
+                    self.export_graph();
+                }
+            });
+            
+            ui.separator();
+            ui.label("Animation of the recorded history (grid or network view):");
+            if self.animation_export.show(ui, self.graph.is_some()) {
+                self.record_animation();
+            }
+            
+            ui.separator();
+            ui.label("Save as PNG (activity heatmap only for grid graphs):");
+            let views = [
+                ("Configuration", self.graph.is_some()),
+                ("Activity", self.graph.is_some() && self.graph_type == GraphType::Grid),
+            ];
+            if let Some(view) = self.snapshot_export.show(ui, &views) {
+                self.save_snapshot(view == 1);
+            }
+        });
+        
         ui.separator();
         
         // Simulation settings (only show if graph exists)
@@ -674,13 +1832,89 @@ impl Window for ChipFiringWindow {
                         ui.radio_value(&mut graph.selection_strategy, VertexSelectionStrategy::RandomActive, "Random Active");
                     });
                 }
+                ui.add(egui::Slider::new(&mut graph.firing_probability, FIRING_PROBABILITY_RANGE).text("Firing Probability"))
+                    .on_hover_text("Chance that an active vertex fires in a step. 1 is the deterministic BTW sandpile; lower values make a stochastic sandpile with the same stable states but longer, irregular avalanches.");
+                
+                let mut capped = graph.max_height.is_some();
+                let mut cap = graph.max_height.unwrap_or_else(|| 2 * graph.degrees.iter().copied().max().unwrap_or(1) as i32);
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut capped, "Max Height:")
+                        .on_hover_text("Chips a vertex receives beyond this height are lost, as at a sink");
+                    ui.add_enabled(capped, egui::DragValue::new(&mut cap).range(0..=100_000));
+                });
+                graph.max_height = capped.then_some(cap);
+                
+                if graph.has_custom_thresholds() {
+                    ui.horizontal(|ui| {
+                        let custom = (0..graph.num_vertices).filter(|&v| graph.threshold(v) != graph.firing_loss(v)).count();
+                        ui.label(format!("{} vertices with raised thresholds", custom));
+                        if ui.button("Reset Thresholds").clicked() {
+                            graph.reset_thresholds();
+                        }
+                    });
+                }
             }
             
             ui.horizontal(|ui| {
                 ui.label("Max Steps:");
-                ui.add(egui::DragValue::new(&mut self.max_steps).speed(1.0).range(1..=1000));
+                ui.add(egui::DragValue::new(&mut self.max_steps).speed(10.0).range(1..=1_000_000));
+            });
+            
+            ui.horizontal(|ui| {
+                ui.label("Max Firings (Stabilize):");
+                ui.add(egui::DragValue::new(&mut self.max_firings).speed(100.0).range(1..=10_000_000));
             });
             
+            if let Some(graph) = &mut self.graph {
+                ui.horizontal(|ui| {
+                    ui.label("Record Every:");
+                    ui.add(egui::DragValue::new(&mut graph.history_stride).speed(1.0).range(1..=1000));
+                    ui.label("steps");
+                });
+                
+                // Bounded history keeps the initial state and either only the most recent
+                // entries or those plus a thinned record of the older ones
+                let mut bounded = graph.history.capacity().is_some();
+                let mut capacity = graph.history.capacity().unwrap_or(1000);
+                let mut retention = graph.history.retention();
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut bounded, "Limit History:");
+                    ui.add_enabled(bounded, egui::DragValue::new(&mut capacity).speed(10.0).range(2..=1_000_000));
+                    ui.label("entries");
+                    ui.add_enabled_ui(bounded, |ui| {
+                        egui::ComboBox::from_id_source("chip_firing_retention")
+                            .selected_text(retention.name())
+                            .show_ui(ui, |ui| {
+                                for option in [Retention::Recent, Retention::Thinned] {
+                                    ui.selectable_value(&mut retention, option, option.name());
+                                }
+                            })
+                            .response
+                            .on_hover_text("Thinned keeps the newest half at full resolution and sparser older entries");
+                    });
+                });
+                if retention != graph.history.retention() {
+                    graph.history.set_retention(retention);
+                }
+                let capacity = bounded.then_some(capacity);
+                if capacity != graph.history.capacity() {
+                    graph.history.set_capacity(capacity);
+                }
+                
+                // The firing log stores every recorded configuration as the vertices that fired
+                let mut log = graph.firing_log().is_some();
+                ui.checkbox(&mut log, "Record Firing Log")
+                    .on_hover_text("Keep the whole run as fired-vertex lists, which stays small even with a bounded history");
+                graph.set_firing_log(log);
+                if let Some(log) = graph.firing_log() {
+                    ui.label(format!("{} configurations logged up to step {}", log.len(), log.last_iteration()));
+                }
+            }
+            
+            if seed_control(ui, &mut self.seed) {
+                self.rng = StdRng::seed_from_u64(self.seed);
+            }
+            
             ui.horizontal(|ui| {
                 ui.label("Auto Step Interval:"); 
                 ui.add(egui::DragValue::new(&mut self.step_interval).speed(0.1).range(0.1..=5.0));
@@ -688,6 +1922,65 @@ impl Window for ChipFiringWindow {
             });
             
             ui.checkbox(&mut self.add_chip_to_selected, "Add Chip on Click");
+            ui.checkbox(&mut self.paint_chips, "Paint Chips")
+                .on_hover_text("Hold and drag across the grid view to add chips under the brush. The middle mouse button still pans.");
+            if self.paint_chips {
+                ui.horizontal(|ui| {
+                    ui.label("Brush Radius:");
+                    ui.add(egui::DragValue::new(&mut self.brush_radius).range(0..=20));
+                    ui.label("Chips per Cell per Second:");
+                    ui.add(egui::DragValue::new(&mut self.paint_rate).speed(0.5).range(1.0..=200.0));
+                });
+            }
+            
+            ui.separator();
+            ui.label("Sink and Drive:");
+            let num_vertices = self.graph.as_ref().map_or(0, |graph| graph.num_vertices);
+            let mut has_sink = self.sink.is_some();
+            let mut sink = self.sink.unwrap_or(0);
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut has_sink, "Sink Vertex:")
+                    .on_hover_text("Absorbs chips and never fires, so every configuration stabilizes");
+                ui.add_enabled(has_sink, egui::DragValue::new(&mut sink).range(0..=num_vertices.saturating_sub(1)));
+                if ui.add_enabled(self.selected_vertex.is_some(), egui::Button::new("Use Selected")).clicked() {
+                    has_sink = true;
+                    sink = self.selected_vertex.unwrap_or(0);
+                }
+            });
+            let sink = has_sink.then_some(sink);
+            if sink != self.sink {
+                if let Some(graph) = &mut self.graph {
+                    match graph.set_sink(sink) {
+                        Ok(()) => {
+                            self.sink = sink;
+                            self.clear_analysis();
+                        },
+                        Err(e) => report_error(e.to_string()),
+                    }
+                }
+            }
+            
+            ui.checkbox(&mut self.driven, "Driven (drop chips while stepping)")
+                .on_hover_text("Without a sink the chips pile up until the system never stabilizes");
+            ui.add_enabled_ui(self.driven, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Drop a Chip Every:");
+                    ui.add(egui::DragValue::new(&mut self.drive_interval).speed(1.0).range(1..=1000));
+                    ui.label("steps");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Drop On:");
+                    let mut fixed = matches!(self.drive_target, DriveTarget::Fixed(_));
+                    let mut vertex = match self.drive_target {
+                        DriveTarget::Fixed(vertex) => vertex,
+                        DriveTarget::Random => self.selected_vertex.unwrap_or(0),
+                    };
+                    ui.radio_value(&mut fixed, false, "Random Vertex");
+                    ui.radio_value(&mut fixed, true, "Vertex");
+                    ui.add_enabled(fixed, egui::DragValue::new(&mut vertex).range(0..=num_vertices.saturating_sub(1)));
+                    self.drive_target = if fixed { DriveTarget::Fixed(vertex) } else { DriveTarget::Random };
+                });
+            });
             
             ui.separator();
             
@@ -699,12 +1992,19 @@ impl Window for ChipFiringWindow {
                 ui.label("View Mode:");
                 ui.radio_value(&mut self.visualization_mode, VisualizationMode::Network, "Network");
                 
-                // Only allow Grid mode for grid graphs
+                // Only allow Grid and Heatmap modes for grid graphs
                 if self.graph_type == GraphType::Grid {
                     ui.radio_value(&mut self.visualization_mode, VisualizationMode::Grid, "Grid");
                 }
                 
                 ui.radio_value(&mut self.visualization_mode, VisualizationMode::BarChart, "Bar Chart");
+                
+                if self.graph_type == GraphType::Grid {
+                    ui.radio_value(&mut self.visualization_mode, VisualizationMode::Heatmap, "Heatmap");
+                    if height_field::is_available(ui.ctx()) {
+                        ui.radio_value(&mut self.visualization_mode, VisualizationMode::Heights3D, "3D Heights");
+                    }
+                }
             });
             
             ui.checkbox(&mut self.show_active_vertices, "Highlight Active Vertices");
@@ -717,14 +2017,65 @@ impl Window for ChipFiringWindow {
                         ui.label("Edge Thickness:");
                         ui.add(egui::Slider::new(&mut self.edge_thickness, 1.0..=10.0));
                     });
+                    if ui.checkbox(&mut self.animate_firings, "Animate Firings")
+                        .on_hover_text("Show chips traveling along the edges of the vertices that fired in each step")
+                        .changed()
+                    {
+                        self.trail_sources.clear();
+                    }
                 },
                 VisualizationMode::Grid => {
                     ui.horizontal(|ui| {
                         ui.label("Cell Size:");
                         ui.add(egui::Slider::new(&mut self.grid_cell_size, 20.0..=100.0));
                     });
+                    if !self.waves.is_empty() {
+                        ui.checkbox(&mut self.show_waves, format!("Show Avalanche Waves ({})", self.waves.len()));
+                        if self.show_waves {
+                            ui.horizontal_wrapped(|ui| {
+                                for (index, wave) in self.waves.iter().enumerate() {
+                                    ui.colored_label(wave_color(index), format!("Wave {}: {}", index + 1, wave.len()))
+                                        .on_hover_text("Vertices that toppled in this wave");
+                                }
+                            });
+                        }
+                    }
                 },
                 VisualizationMode::BarChart => { /* No specific config needed here */ }
+                VisualizationMode::Heights3D => {
+                    ui.horizontal(|ui| {
+                        ui.label("Drag to orbit, scroll to zoom.");
+                        if ui.button("Reset View").clicked() {
+                            self.orbit_camera = OrbitCamera::default();
+                        }
+                    });
+                }
+                VisualizationMode::Heatmap => {
+                    ui.horizontal(|ui| {
+                        ui.label("Metric:");
+                        ui.radio_value(&mut self.heatmap_metric, HeatmapMetric::Cumulative, "Cumulative Firings");
+                        ui.radio_value(&mut self.heatmap_metric, HeatmapMetric::Recent, "Recent Rate");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Colormap:");
+                        colormap_selector(ui, "chip_firing_heatmap_colormap", &mut self.heatmap_colormap);
+                    });
+                    if let Some(graph) = &mut self.graph {
+                        if self.heatmap_metric == HeatmapMetric::Recent {
+                            ui.horizontal(|ui| {
+                                ui.label("Decay per Step:");
+                                ui.add(egui::Slider::new(&mut graph.activity_decay, 0.0..=1.0));
+                            });
+                        }
+                        if ui.button("Reset Activity").clicked() {
+                            graph.reset_activity();
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Cell Size:");
+                        ui.add(egui::Slider::new(&mut self.grid_cell_size, 20.0..=100.0));
+                    });
+                }
             }
             
             ui.separator();
@@ -733,48 +2084,85 @@ impl Window for ChipFiringWindow {
 
             ui.horizontal(|ui| {
                 if ui.button("Step").clicked() {
+                    session::record(self.name(), SessionAction::Command(Command::Step));
                     self.step_simulation();
                 }
                 if ui.checkbox(&mut self.auto_step, "Auto-Step").changed() {
                     // Reset timer when toggling auto-step
-                    self.last_step_time = ui.input(|i| i.time);
+                    self.time_since_step = 0.0;
                 }
             });
 
             if ui.button("Run Until Stable").clicked() {
-                if let Some(graph) = &mut self.graph {
-                    match graph.run(self.max_steps, &mut self.rng) {
-                        Ok(steps) => println!("Simulation finished in {} steps", steps),
-                        Err(e) => self.error_message = Some(format!("Run error: {}", e)),
-                    }
-                    self.display_step = graph.history.len() - 1;
-                }
+                session::record(self.name(), SessionAction::Command(Command::Run));
+                self.start_run_until_stable();
+            }
+            
+            if ui.button("Stabilize (Fast)").on_hover_text("Fire active vertices from a worklist without recording intermediate steps").clicked() {
+                session::record(self.name(), SessionAction::perform("stabilize", ""));
+                self.stabilize_fast();
             }
             
             if ui.button("Reset Configuration").clicked() {
+                session::record(self.name(), SessionAction::Command(Command::Reset));
                 self.reset_graph();
             }
             
             if ui.button("Randomize Configuration").clicked() {
+                session::record(self.name(), SessionAction::perform("randomize", ""));
                 self.randomize_configuration();
             }
             
+            ui.horizontal(|ui| {
+                let undo_label = self.undo_stack.last().map(|e| e.label).unwrap_or("nothing");
+                if ui.add_enabled(!self.undo_stack.is_empty(), egui::Button::new("Undo"))
+                    .on_hover_text(format!("Undo {} (Ctrl+Z)", undo_label))
+                    .clicked()
+                {
+                    session::record(self.name(), SessionAction::perform("undo", ""));
+                    self.undo();
+                }
+                let redo_label = self.redo_stack.last().map(|e| e.label).unwrap_or("nothing");
+                if ui.add_enabled(!self.redo_stack.is_empty(), egui::Button::new("Redo"))
+                    .on_hover_text(format!("Redo {} (Ctrl+Y)", redo_label))
+                    .clicked()
+                {
+                    session::record(self.name(), SessionAction::perform("redo", ""));
+                    self.redo();
+                }
+            });
+            
             ui.separator();
             
             // Actions on selected vertex
             if let Some(vertex_idx) = self.selected_vertex {
                  ui.label(format!("Selected Vertex: {}", vertex_idx));
+                 self.metadata_editor(ui, vertex_idx);
+                 self.threshold_editor(ui, vertex_idx);
                  ui.horizontal(|ui| {
                     if ui.button("Add Chip").clicked() {
+                        session::record(self.name(), SessionAction::perform("add_chip", vertex_idx));
                         self.add_chip();
                     }
                     if ui.button("Remove Chip").clicked() {
+                        session::record(self.name(), SessionAction::perform("remove_chip", vertex_idx));
                         self.remove_chip();
                     }
+                    if ui.button("Fire").clicked() {
+                        session::record(self.name(), SessionAction::perform("fire", vertex_idx));
+                        self.fire_selected();
+                    }
+                 });
+                 ui.horizontal(|ui| {
+                    if ui.button("Trigger Avalanche").clicked() {
+                        session::record(self.name(), SessionAction::perform("avalanche", vertex_idx));
+                        self.trigger_avalanche();
+                    }
+                    if ui.button("Avalanche in Waves").on_hover_text("Add a chip and relax it wave by wave: each wave fires this vertex once and then everything else. The grid view colors the waves.").clicked() {
+                        session::record(self.name(), SessionAction::perform("avalanche_waves", vertex_idx));
+                        self.avalanche_waves();
+                    }
                  });
-                 if ui.button("Trigger Avalanche").clicked() {
-                    self.trigger_avalanche();
-                 }
             } else {
                 ui.label("Select a vertex in the visualization to interact.");
             }
@@ -783,32 +2171,109 @@ impl Window for ChipFiringWindow {
             ui.label("Create a graph first.");
         }
         
-        // Display Error Messages
-        if let Some(err) = &self.error_message {
-            ui.separator();
-            ui.colored_label(egui::Color32::RED, err);
-        }
+        ui.separator();
+        ui.collapsing("Stabilization Experiment", |ui| {
+            ui.label("Random configurations on each graph type, sized as in the graph settings:");
+            ui.horizontal_wrapped(|ui| {
+                for graph_type in EXPERIMENT_TYPES {
+                    let mut selected = self.experiment_types.contains(&graph_type);
+                    if ui.checkbox(&mut selected, graph_type.name()).changed() {
+                        self.experiment_types.retain(|&t| t != graph_type);
+                        if selected {
+                            self.experiment_types.push(graph_type);
+                        }
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Chips per Vertex:");
+                let (low, high) = &mut self.experiment_densities;
+                ui.add(egui::DragValue::new(low).speed(0.05).range(0.0..=*high));
+                ui.label("to");
+                ui.add(egui::DragValue::new(high).speed(0.05).range(*low..=20.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Points:");
+                ui.add(egui::DragValue::new(&mut self.experiment_points).range(2..=100));
+                ui.label("Trials per Point:");
+                ui.add(egui::DragValue::new(&mut self.experiment_trials).range(1..=1000));
+            });
+            ui.checkbox(&mut self.experiment_with_sink, "Vertex 0 is a Sink")
+                .on_hover_text("Without a sink, dense configurations never stabilize");
+            ui.label(format!("Runs stop after the Max Steps limit ({})", self.max_steps));
+            let can_run = self.experiment_job.is_none() && !self.experiment_types.is_empty();
+            if ui.add_enabled(can_run, egui::Button::new("Run Experiment")).clicked() {
+                self.start_experiment();
+            }
+        });
+        
     }
 
-    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
-        // Handle auto-stepping (Does this need &mut self? Yes, for step_simulation)
-        if self.auto_step {
-            let current_time = ui.input(|i| i.time);
-            if current_time - self.last_step_time >= self.step_interval {
-                self.step_simulation(); // Needs &mut self
-                self.last_step_time = current_time;
+    fn on_close(&mut self) {
+        self.auto_step = false;
+    }
+
+    /// Auto-stepping (and dropping chips in driven mode) while no background run is active
+    fn tick(&mut self, dt: f64) -> bool {
+        let animating = !self.trail_sources.is_empty();
+        if animating {
+            let (shortest, longest) = CHIP_TRAIL_SECONDS;
+            self.trail_progress += dt / self.step_interval.clamp(shortest, longest);
+            if self.trail_progress >= 1.0 {
+                self.trail_sources.clear();
             }
-            ui.ctx().request_repaint(); 
         }
+        if !self.auto_step || self.job.is_some() {
+            return animating;
+        }
+        self.time_since_step += dt;
+        if self.time_since_step >= self.step_interval {
+            session::record(self.name(), SessionAction::Command(Command::Step));
+            self.step_simulation();
+            self.time_since_step = 0.0;
+        }
+        true
+    }
 
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        // Collect a finished background run; edits are blocked while one is in progress
+        self.poll_job();
+        if let Some(job) = &self.job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        let running = self.job.is_some();
+        self.poll_experiment();
+        if let Some(job) = &self.experiment_job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        
+        // Undo/redo shortcuts (Ctrl+Z, Ctrl+Y or Ctrl+Shift+Z)
+        let (undo_pressed, redo_pressed) = ui.input_mut(|i| {
+            let redo = i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z)
+                || i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y);
+            let undo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+            (undo, redo)
+        });
+        if undo_pressed && !running {
+            session::record(self.name(), SessionAction::perform("undo", ""));
+            self.undo();
+        }
+        if redo_pressed && !running {
+            session::record(self.name(), SessionAction::perform("redo", ""));
+            self.redo();
+        }
+        
         // --- Visualization Area Setup ---
         let desired_size = match self.visualization_mode {
              VisualizationMode::Network => egui::vec2(500.0, 500.0),
-             VisualizationMode::Grid => egui::vec2(
+             VisualizationMode::Grid | VisualizationMode::Heatmap => egui::vec2(
                  self.grid_width as f32 * self.grid_cell_size,
                  self.grid_height as f32 * self.grid_cell_size,
-             ),
+             ).min(egui::vec2(ui.available_width(), 600.0)),
              VisualizationMode::BarChart => egui::vec2(ui.available_width(), 300.0),
+             VisualizationMode::Heights3D => egui::vec2(ui.available_width().min(600.0), 450.0),
         };
         // Allocate painter space. Bar chart doesn't strictly need this, but we need response for others.
         let (response, painter) = ui.allocate_painter(
@@ -817,36 +2282,56 @@ impl Window for ChipFiringWindow {
             if self.visualization_mode != VisualizationMode::BarChart { egui::Sense::click_and_drag() } else { egui::Sense::hover() } 
         );
 
+        // Zoom and pan, kept separately for every view; the 3D view orbits instead
+        if self.visualization_mode == VisualizationMode::Heights3D {
+            height_field::orbit(ui, &response, &mut self.orbit_camera);
+        }
+        let camera = if !matches!(self.visualization_mode, VisualizationMode::BarChart | VisualizationMode::Heights3D) {
+            let id = ui.id().with(("chip_firing_camera", self.visualization_mode as u8));
+            viewport(ui, &response, id, self.view_content(), true, !self.painting_enabled())
+        } else {
+            Camera::default()
+        };
+
         // --- Interaction Handling (Needs &self, BEFORE borrowing graph) ---
+        let painting = self.painting_enabled() && !running;
+        let primary_down = ui.input(|i| i.pointer.primary_down());
         let mut clicked_idx = None;
-        if response.clicked() && (self.visualization_mode == VisualizationMode::Network || self.visualization_mode == VisualizationMode::Grid) {
-            if let Some(pos) = response.interact_pointer_pos() {
-                 clicked_idx = match self.visualization_mode {
-                     VisualizationMode::Network => {
-                         // Check if graph exists before accessing node_positions
-                         if self.graph.is_some() && !self.node_positions.is_empty() {
-                             self.node_positions.iter().position(|&node_pos| {
-                                 ( (response.rect.min + node_pos) - pos).length() <= self.vertex_radius
-                             })
-                         } else { None }
-                     }
-                     VisualizationMode::Grid => {
-                         let relative_pos = pos - response.rect.min;
-                         let grid_x = (relative_pos.x / self.grid_cell_size).floor() as usize;
-                         let grid_y = (relative_pos.y / self.grid_cell_size).floor() as usize;
-                         if grid_x < self.grid_width && grid_y < self.grid_height {
-                              Some(grid_y * self.grid_width + grid_x)
-                         } else { None }
-                     }
-                     _ => None, // No interaction for BarChart
-                 };
+        let mut brushed_idx = None;
+        if let Some(pos) = response.interact_pointer_pos() {
+            if painting && primary_down && response.is_pointer_button_down_on() {
+                brushed_idx = self.vertex_at(pos - response.rect.min, camera);
+            } else if response.clicked() && !painting {
+                clicked_idx = self.vertex_at(pos - response.rect.min, camera);
             }
         }
+        
+        // Brush strokes add chips while the button is held, even without moving
+        if let Some(center) = brushed_idx {
+            if !self.paint_stroke {
+                self.push_undo("Paint Chips");
+                self.paint_stroke = true;
+                self.paint_carry = 1.0; // The first frame of a stroke paints at once
+            }
+            self.paint_carry += self.paint_rate * ui.input(|i| i.stable_dt) as f64;
+            let amount = self.paint_carry.floor();
+            if amount >= 1.0 {
+                self.paint_carry -= amount;
+                let cells = self.brush_cells(center);
+                let list: Vec<String> = cells.iter().map(usize::to_string).collect();
+                session::record(self.name(), SessionAction::perform("paint_chips", format!("{} {}", amount, list.join(","))));
+                self.paint(&cells, amount as i32);
+            }
+            ui.ctx().request_repaint();
+        } else if !primary_down {
+            self.paint_stroke = false;
+        }
 
         // --- Apply Interaction Results (Needs &mut self) ---
-        if let Some(idx) = clicked_idx {
+        if let Some(idx) = clicked_idx.filter(|_| !running) {
             self.selected_vertex = Some(idx);
             if self.add_chip_to_selected {
+                session::record(self.name(), SessionAction::perform("add_chip", idx));
                 self.add_chip(); // Mutable call OK here
             }
         }
@@ -854,30 +2339,50 @@ impl Window for ChipFiringWindow {
         // --- Drawing and Status (Needs &self.graph immutable borrow) ---
         if let Some(graph) = &self.graph {
             // History slider
-            if graph.history.len() > 1 {
-                ui.horizontal(|ui| {
-                    ui.label(format!("Step: {} / {}", self.display_step, graph.history.len() - 1));
-                    // Check if display_step needs update after interaction/step
-                    self.display_step = self.display_step.min(graph.history.len() - 1);
-                    ui.add(egui::Slider::new(&mut self.display_step, 0..=(graph.history.len() - 1)).text("View Step"));
-                });
-                ui.separator();
-            } else {
-                self.display_step = 0; // Reset display step if history is cleared
-                ui.label("Step: 0 / 0");
-                ui.separator();
-            }
+            history_slider(ui, &graph.history, &mut self.display_step, "Step");
+            ui.separator();
             
             // Draw visualization (using immutable self)
             match self.visualization_mode {
-                VisualizationMode::Network => self.draw_network(&painter, &response),
-                VisualizationMode::Grid => self.draw_grid(&painter, &response),
+                VisualizationMode::Network => self.draw_network(&painter, &response, camera),
+                VisualizationMode::Grid => self.draw_grid(&painter, &response, camera),
                 VisualizationMode::BarChart => self.draw_bar_chart(ui),
+                VisualizationMode::Heatmap => self.draw_heatmap(&painter, &response, camera),
+                VisualizationMode::Heights3D => self.draw_heights(&painter, &response),
+            }
+            if painting {
+                if let Some(pos) = response.hover_pos() {
+                    let radius = (self.brush_radius as f32 + 0.5) * self.grid_cell_size * camera.zoom;
+                    painter.circle_stroke(pos, radius, egui::Stroke::new(1.5, egui::Color32::WHITE));
+                }
+            }
+            if !matches!(self.visualization_mode, VisualizationMode::BarChart | VisualizationMode::Heights3D) {
+                draw_minimap(&painter, response.rect, self.view_content(), camera);
+                let hovered = response.hover_pos().and_then(|pos| self.vertex_at(pos - response.rect.min, camera));
+                if let Some(vertex) = hovered {
+                    let config = self.current_configuration().unwrap_or(&graph.configuration);
+                    let mut text = ChipView::new(graph, config, true, None).describe(vertex);
+                    if self.visualization_mode == VisualizationMode::Heatmap {
+                        text.push_str(&format!("\n{} firings", graph.firing_counts[vertex]));
+                    }
+                    response.clone().on_hover_text_at_pointer(text);
+                }
+            }
+            if self.visualization_mode == VisualizationMode::Heatmap && self.graph_type == GraphType::Grid {
+                let max_value = self.heatmap_values(graph).into_iter().fold(0.0, f64::max);
+                let label = match self.heatmap_metric {
+                    HeatmapMetric::Cumulative => "Firings:",
+                    HeatmapMetric::Recent => "Rate:",
+                };
+                legend(ui, self.heatmap_colormap, 0.0, max_value, label);
             }
 
             // Display status information (using immutable graph)
             ui.separator();
             ui.label(format!("Total Chips: {}", graph.total_chips()));
+            if let Some(sink) = graph.sink() {
+                ui.label(format!("Chips Absorbed by Sink: {}", graph.configuration[sink]));
+            }
             if graph.is_stable() {
                  ui.colored_label(egui::Color32::GREEN, "Stable");
             } else {
@@ -892,5 +2397,16 @@ impl Window for ChipFiringWindow {
                 ui.label("No graph created yet. Use the configuration panel to create one.");
             });
         }
+        
+        if self.graph.is_some() {
+            ui.separator();
+            ui.collapsing("Laplacian Spectrum", |ui| self.draw_spectrum(ui));
+            ui.collapsing("Graph Statistics", |ui| self.draw_statistics(ui));
+        }
+        
+        if !self.experiment_results.is_empty() {
+            ui.separator();
+            ui.collapsing("Stabilization Experiment", |ui| self.draw_experiment(ui));
+        }
     }
 }
\ No newline at end of file
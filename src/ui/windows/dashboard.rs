@@ -0,0 +1,203 @@
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints, VLine};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::mpsc::Receiver;
+use std::time::Instant;
+
+use crate::ui::events::AppEvent;
+use crate::ui::metrics::{self, Metric, MetricSample};
+use crate::ui::windows::Window;
+
+/// Weight of the newest frame in the smoothed frame rate
+const FPS_SMOOTHING: f64 = 0.1;
+
+/// Frame rate samples per second of wall-clock time
+const FPS_SAMPLE_INTERVAL: f64 = 0.25;
+
+/// Height of each metric plot in points
+const PLOT_HEIGHT: f32 = 140.0;
+
+/// Window plotting the metrics published by running simulations over the last seconds,
+/// one plot per metric with a line per window
+pub struct MetricsWindow {
+    /// Subscription to the metric bus, held while the window is open
+    receiver: Option<Receiver<MetricSample>>,
+    /// (seconds since `start`, value) per window and metric, oldest first
+    series: BTreeMap<(Metric, String), VecDeque<[f64; 2]>>,
+    /// (seconds since `start`, description) of the events of other windows, marked on
+    /// every plot
+    markers: VecDeque<(f64, String)>,
+    start: Instant,
+
+    /// Seconds of history shown
+    window_seconds: f64,
+    shown: BTreeMap<Metric, bool>,
+    paused: bool,
+
+    smoothed_fps: f64,
+    time_to_fps_sample: f64,
+}
+
+impl MetricsWindow {
+    pub fn new() -> Self {
+        Self {
+            receiver: None,
+            series: BTreeMap::new(),
+            markers: VecDeque::new(),
+            start: Instant::now(),
+            window_seconds: 30.0,
+            shown: Metric::ALL.iter().map(|&metric| (metric, true)).collect(),
+            paused: false,
+            smoothed_fps: 0.0,
+            time_to_fps_sample: 0.0,
+        }
+    }
+
+    fn record(&mut self, sample: MetricSample) {
+        let time = sample.time.saturating_duration_since(self.start).as_secs_f64();
+        self.series.entry((sample.metric, sample.source)).or_default().push_back([time, sample.value]);
+    }
+
+    /// Drops the samples older than the shown window and the series left empty
+    fn prune(&mut self) {
+        let cutoff = self.start.elapsed().as_secs_f64() - self.window_seconds;
+        for points in self.series.values_mut() {
+            while points.front().is_some_and(|point| point[0] < cutoff) {
+                points.pop_front();
+            }
+        }
+        self.series.retain(|_, points| !points.is_empty());
+        while self.markers.front().is_some_and(|(time, _)| *time < cutoff) {
+            self.markers.pop_front();
+        }
+    }
+}
+
+impl Window for MetricsWindow {
+    fn name(&self) -> &str {
+        "Metrics"
+    }
+
+    fn on_open(&mut self) {
+        self.receiver = Some(metrics::subscribe());
+    }
+
+    fn on_close(&mut self) {
+        // Publishers skip their work while nobody listens
+        self.receiver = None;
+        self.series.clear();
+        self.markers.clear();
+    }
+
+    fn on_event(&mut self, event: &AppEvent) {
+        if self.paused {
+            return;
+        }
+        let source = event.source.as_deref().unwrap_or("Application");
+        let time = self.start.elapsed().as_secs_f64();
+        self.markers.push_back((time, format!("{}: {}", source, event.kind.label())));
+    }
+
+    fn tick(&mut self, dt: f64) -> bool {
+        if dt > 0.0 {
+            self.smoothed_fps = if self.smoothed_fps == 0.0 {
+                1.0 / dt
+            } else {
+                (1.0 - FPS_SMOOTHING) * self.smoothed_fps + FPS_SMOOTHING / dt
+            };
+        }
+        let mut samples: Vec<MetricSample> = match &self.receiver {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => return false,
+        };
+        self.time_to_fps_sample -= dt;
+        if self.time_to_fps_sample <= 0.0 {
+            self.time_to_fps_sample = FPS_SAMPLE_INTERVAL;
+            samples.push(MetricSample {
+                source: "Application".to_string(),
+                metric: Metric::FramesPerSecond,
+                value: self.smoothed_fps,
+                time: Instant::now(),
+            });
+        }
+        if !self.paused {
+            for sample in samples {
+                self.record(sample);
+            }
+            self.prune();
+        }
+        // Keep sampling the frame rate and the running simulations
+        true
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Metrics");
+        ui.separator();
+
+        for metric in Metric::ALL {
+            if let Some(shown) = self.shown.get_mut(&metric) {
+                ui.checkbox(shown, metric.name());
+            }
+        }
+        ui.separator();
+        ui.add(egui::Slider::new(&mut self.window_seconds, 5.0..=300.0).logarithmic(true).text("Seconds Shown"));
+        ui.checkbox(&mut self.paused, "Pause")
+            .on_hover_text("Freeze the plots; samples published meanwhile are dropped");
+        if ui.button("Clear").clicked() {
+            self.series.clear();
+            self.markers.clear();
+        }
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        ui.heading("Live Metrics");
+        ui.separator();
+        ui.label(format!("{:.0} frames per second", self.smoothed_fps));
+
+        let now = self.start.elapsed().as_secs_f64();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let mut any = false;
+            for metric in Metric::ALL {
+                if !self.shown.get(&metric).copied().unwrap_or(false) {
+                    continue;
+                }
+                let lines: Vec<(&str, &VecDeque<[f64; 2]>)> = self
+                    .series
+                    .range((metric, String::new())..)
+                    .take_while(|((m, _), _)| *m == metric)
+                    .map(|((_, source), points)| (source.as_str(), points))
+                    .collect();
+                if lines.is_empty() {
+                    continue;
+                }
+                any = true;
+                ui.label(metric.name());
+                Plot::new(("metrics_plot", metric))
+                    .height(PLOT_HEIGHT)
+                    .legend(Legend::default())
+                    .include_x(now - self.window_seconds)
+                    .include_x(now)
+                    .x_axis_label("Seconds")
+                    .show(ui, |plot_ui| {
+                        for (source, points) in lines {
+                            let points: PlotPoints = points.iter().copied().collect();
+                            plot_ui.line(Line::new(points).name(source));
+                        }
+                        for (time, _) in &self.markers {
+                            plot_ui.vline(VLine::new(*time).color(egui::Color32::GRAY));
+                        }
+                    });
+            }
+            if !any {
+                ui.label("No metrics published yet. Run a simulation in another window, e.g. the Ising Model or Chip Firing Graph.");
+            }
+            if !self.markers.is_empty() {
+                ui.separator();
+                ui.label("Events (gray lines):");
+                for (time, label) in self.markers.iter().rev() {
+                    ui.label(format!("{:.1} s ago   {}", now - time, label));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,219 @@
+use eframe::egui;
+use egui_plot::{Plot, PlotPoints, Points};
+use rand::rngs::ThreadRng;
+
+use crate::core::graph::{Graph, GraphType, LaplacianSpectrum};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::colormap::{legend, Colormap};
+use crate::ui::widgets::network::{self, NetworkStyle};
+use crate::ui::widgets::spectrum::{spectrum_plot, spectrum_summary, MAX_SPECTRUM_VERTICES};
+use crate::ui::windows::chip_firing::layout_positions;
+use crate::ui::windows::Window;
+
+/// Side length of the network view in points
+const NETWORK_VIEW_SIZE: f32 = 360.0;
+
+/// Graphs with more edges are not drawn as a network
+const MAX_DRAWN_EDGES: usize = 20_000;
+
+/// Graph families the window can analyze
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GraphSource {
+    Family(GraphType),
+    ErdosRenyi,
+}
+
+/// Window for the Laplacian spectrum of the graph families used by the chip-firing,
+/// rotor-router and Hopfield models
+pub struct GraphAnalysisWindow {
+    /// Graph settings
+    source: GraphSource,
+    graph_size: usize,
+    grid_width: usize,
+    grid_height: usize,
+    custom_edges: String,
+    er_probability: f64,
+    use_sink: bool,
+    sink_vertex: usize,
+
+    /// The analyzed graph, its layout and spectra
+    graph: Option<Graph>,
+    node_positions: Vec<egui::Vec2>,
+    spectrum: Option<LaplacianSpectrum>,
+    reduced_spectrum: Option<LaplacianSpectrum>,
+
+    /// Random number generator for Erdős-Rényi graphs
+    rng: ThreadRng,
+}
+
+impl GraphAnalysisWindow {
+    pub fn new() -> Self {
+        Self {
+            source: GraphSource::Family(GraphType::Grid),
+            graph_size: 20,
+            grid_width: 8,
+            grid_height: 8,
+            custom_edges: "0,1 1,2 2,0 2,3".to_string(),
+            er_probability: 0.2,
+            use_sink: false,
+            sink_vertex: 0,
+            graph: None,
+            node_positions: Vec::new(),
+            spectrum: None,
+            reduced_spectrum: None,
+            rng: rand::thread_rng(),
+        }
+    }
+
+    /// Build the selected graph and diagonalize its Laplacian
+    fn analyze(&mut self) {
+        let graph = match self.source {
+            GraphSource::Family(graph_type) => {
+                match graph_type.build(self.graph_size, self.grid_width, self.grid_height, &self.custom_edges) {
+                    Ok(graph) => graph,
+                    Err(e) => {
+                        report_error(e.to_string());
+                        return;
+                    },
+                }
+            },
+            GraphSource::ErdosRenyi => Graph::erdos_renyi(self.graph_size, self.er_probability, &mut self.rng),
+        };
+
+        let n = graph.num_vertices();
+        if n > MAX_SPECTRUM_VERTICES {
+            report_error(format!("Spectra are only computed up to {} vertices", MAX_SPECTRUM_VERTICES));
+            return;
+        }
+
+        // Erdős-Rényi graphs are drawn on a circle like complete graphs
+        let layout_type = match self.source {
+            GraphSource::Family(graph_type) => graph_type,
+            GraphSource::ErdosRenyi => GraphType::Complete,
+        };
+        self.node_positions = layout_positions(layout_type, n, self.grid_width, self.grid_height);
+
+        let laplacian = graph.laplacian();
+        self.reduced_spectrum = (self.use_sink && self.sink_vertex < n)
+            .then(|| LaplacianSpectrum::reduced(laplacian.clone(), self.sink_vertex));
+        self.spectrum = Some(LaplacianSpectrum::new(laplacian));
+        self.graph = Some(graph);
+    }
+
+    /// The graph with vertices colored by the sign and size of their Fiedler vector
+    /// component, which splits the graph along its sparsest cut
+    fn draw_fiedler_network(&self, ui: &mut egui::Ui, graph: &Graph, fiedler: &[f64]) {
+        let size = egui::vec2(NETWORK_VIEW_SIZE, NETWORK_VIEW_SIZE);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let max = fiedler.iter().fold(0.0f64, |m, x| m.max(x.abs())).max(1e-12);
+        let fills: Vec<egui::Color32> = fiedler.iter().map(|&x| Colormap::Diverging.map_signed(x, max)).collect();
+        let labels = vec![String::new(); graph.num_vertices()];
+        let style = NetworkStyle {
+            vertex_radius: (NETWORK_VIEW_SIZE / (4.0 * (graph.num_vertices() as f32).sqrt().max(1.0))).clamp(2.0, 12.0),
+            edge_thickness: 1.0,
+        };
+
+        // Fit the bounding box of the layout into the view
+        let (min, max) = self.node_positions.iter().fold(
+            (egui::vec2(f32::MAX, f32::MAX), egui::vec2(f32::MIN, f32::MIN)),
+            |(min, max), &p| (min.min(p), max.max(p)),
+        );
+        let margin = style.vertex_radius + 2.0;
+        let extent = (max - min).max_elem().max(1.0);
+        let scale = (NETWORK_VIEW_SIZE - 2.0 * margin) / extent;
+        let positions: Vec<egui::Vec2> = self.node_positions.iter().map(|&p| (p - min) * scale).collect();
+        let origin = response.rect.min + egui::vec2(margin, margin);
+        network::draw_weighted_network(&painter, origin, &positions, graph.edges(), &labels, &fills, style);
+    }
+}
+
+impl Window for GraphAnalysisWindow {
+    fn name(&self) -> &str {
+        "Graph Analysis"
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Graph Settings");
+        ui.separator();
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Graph Type:");
+            for graph_type in [GraphType::Grid, GraphType::Cycle, GraphType::Complete, GraphType::Star, GraphType::Custom] {
+                ui.radio_value(&mut self.source, GraphSource::Family(graph_type), graph_type.name());
+            }
+            ui.radio_value(&mut self.source, GraphSource::ErdosRenyi, "Erdős-Rényi");
+        });
+
+        match self.source {
+            GraphSource::Family(GraphType::Grid) => {
+                ui.horizontal(|ui| {
+                    ui.label("Width:");
+                    ui.add(egui::DragValue::new(&mut self.grid_width).speed(1.0).range(1..=38));
+                    ui.label("Height:");
+                    ui.add(egui::DragValue::new(&mut self.grid_height).speed(1.0).range(1..=38));
+                });
+            },
+            GraphSource::Family(GraphType::Custom) => {
+                ui.label("Enter edges as space-separated pairs (e.g., \"0,1 1,2 2,0\"):");
+                ui.text_edit_multiline(&mut self.custom_edges);
+            },
+            GraphSource::Family(_) | GraphSource::ErdosRenyi => {
+                ui.horizontal(|ui| {
+                    ui.label("Number of Vertices:");
+                    ui.add(egui::DragValue::new(&mut self.graph_size).speed(1.0).range(2..=MAX_SPECTRUM_VERTICES));
+                });
+                if self.source == GraphSource::ErdosRenyi {
+                    ui.add(egui::Slider::new(&mut self.er_probability, 0.0..=1.0).text("Edge Probability"));
+                }
+            },
+        }
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.use_sink, "Sink at vertex")
+                .on_hover_text("Also diagonalize the reduced Laplacian used by chip-firing with a sink");
+            ui.add_enabled(self.use_sink, egui::DragValue::new(&mut self.sink_vertex).speed(1.0));
+        });
+
+        if ui.button("Analyze").clicked() {
+            self.analyze();
+        }
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        let (Some(graph), Some(spectrum)) = (&self.graph, &self.spectrum) else {
+            ui.vertical_centered(|ui| {
+                ui.label("No graph analyzed yet. Use the configuration panel to pick one.");
+            });
+            return;
+        };
+
+        ui.label(format!("{} vertices, {} edges", graph.num_vertices(), graph.edges().len()));
+        spectrum_summary(ui, spectrum, self.reduced_spectrum.as_ref());
+        ui.separator();
+
+        ui.label("Laplacian Eigenvalues");
+        spectrum_plot(ui, "graph_analysis_spectrum", spectrum, self.reduced_spectrum.as_ref(), 220.0);
+
+        if let Some(fiedler) = &spectrum.fiedler_vector {
+            ui.separator();
+            ui.horizontal(|ui| {
+                if graph.edges().len() <= MAX_DRAWN_EDGES {
+                    ui.vertical(|ui| {
+                        ui.label("Fiedler Vector on the Graph (red +, blue -)");
+                        self.draw_fiedler_network(ui, graph, fiedler);
+                        let max = fiedler.iter().fold(0.0f64, |m, x| m.max(x.abs()));
+                        legend(ui, Colormap::Diverging, -max, max, "");
+                    });
+                }
+                ui.vertical(|ui| {
+                    ui.label("Fiedler Vector Components");
+                    let points: PlotPoints = fiedler.iter().enumerate().map(|(i, &x)| [i as f64, x]).collect();
+                    Plot::new("graph_analysis_fiedler")
+                        .height(NETWORK_VIEW_SIZE)
+                        .x_axis_label("Vertex")
+                        .show(ui, |plot_ui| plot_ui.points(Points::new(points).radius(2.0)));
+                });
+            });
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use eframe::egui;
+use rand::rngs::ThreadRng;
+
+use crate::neural::pattern_formation::{GrayScott, GrayScottParameters, GrayScottPreset};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::state_texture;
+use crate::ui::windows::Window;
+
+/// Side length of the drawn field in points
+const DISPLAY_SIZE: f32 = 512.0;
+
+pub struct GrayScottWindow {
+    /// The simulator, rebuilt when the grid size changes
+    sim: Option<GrayScott>,
+
+    /// Grid settings
+    grid_size: usize,
+    preset: GrayScottPreset,
+    params: GrayScottParameters,
+
+    /// Seeding: number of random discs on reset and radius of seeds and the brush
+    seed_count: usize,
+    seed_radius: usize,
+
+    /// Simulation
+    running: bool,
+    steps_per_frame: usize,
+
+    /// Texture the v field is uploaded to each frame
+    texture: Option<egui::TextureHandle>,
+    /// Upload the field to double-buffered wgpu textures instead of an egui texture
+    gpu_upload: bool,
+
+    /// Random number generator for seed positions
+    rng: ThreadRng,
+}
+
+impl GrayScottWindow {
+    pub fn new() -> Self {
+        let mut window = Self {
+            sim: None,
+            grid_size: 128,
+            preset: GrayScottPreset::Mitosis,
+            params: GrayScottPreset::Mitosis.parameters(),
+            seed_count: 8,
+            seed_radius: 4,
+            running: false,
+            steps_per_frame: 10,
+            texture: None,
+            gpu_upload: true,
+            rng: rand::thread_rng(),
+        };
+        window.rebuild();
+        window
+    }
+
+    /// Create a fresh grid and scatter random seeds over it
+    fn rebuild(&mut self) {
+        match GrayScott::new(self.grid_size, self.grid_size, self.params) {
+            Ok(mut sim) => {
+                sim.seed_random(self.seed_count, self.seed_radius, &mut self.rng);
+                self.sim = Some(sim);
+            },
+            Err(e) => {
+                self.sim = None;
+                self.running = false;
+                report_error(format!("Failed to create grid: {}", e));
+            },
+        }
+    }
+
+    /// v from 0 (dark) to its maximum (bright) as an egui image
+    fn field_image(sim: &GrayScott) -> egui::ColorImage {
+        let v = &sim.fields().v;
+        let max = v.iter().fold(0.0f32, |m, &x| m.max(x)).max(1e-6);
+        let pixels = v
+            .iter()
+            .map(|&x| {
+                let t = (x / max).clamp(0.0, 1.0);
+                egui::Color32::from_rgb((20.0 + 235.0 * t) as u8, (20.0 + 180.0 * t * t) as u8, (60.0 + 60.0 * t) as u8)
+            })
+            .collect();
+        egui::ColorImage { size: [sim.width, sim.height], pixels }
+    }
+}
+
+impl Window for GrayScottWindow {
+    fn name(&self) -> &str {
+        "Reaction-Diffusion"
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Gray–Scott Model");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Preset:");
+            egui::ComboBox::from_id_source("gray_scott_preset")
+                .selected_text(self.preset.name())
+                .show_ui(ui, |ui| {
+                    for preset in GrayScottPreset::ALL {
+                        if ui.selectable_value(&mut self.preset, preset, preset.name()).clicked() {
+                            self.params = preset.parameters();
+                        }
+                    }
+                });
+        });
+
+        let p = &mut self.params;
+        ui.add(egui::Slider::new(&mut p.feed, 0.0..=0.1).text("Feed F").fixed_decimals(4));
+        ui.add(egui::Slider::new(&mut p.kill, 0.0..=0.08).text("Kill k").fixed_decimals(4));
+        ui.add(egui::Slider::new(&mut p.diffusion_u, 0.0..=1.0).text("D_u"));
+        ui.add(egui::Slider::new(&mut p.diffusion_v, 0.0..=1.0).text("D_v"));
+        let max_dt = 1.0 / p.diffusion_u.max(p.diffusion_v).max(1e-3);
+        ui.add(egui::Slider::new(&mut p.dt, 0.05..=max_dt.min(2.0)).text("Time Step"))
+            .on_hover_text("Explicit steps are stable while D dt ≤ 1");
+        p.dt = p.dt.min(max_dt);
+
+        ui.separator();
+        ui.heading("Grid");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Grid Size:");
+            ui.add(egui::DragValue::new(&mut self.grid_size).speed(1.0).range(16..=256));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Seeds:");
+            ui.add(egui::DragValue::new(&mut self.seed_count).speed(0.2).range(1..=64));
+            ui.label("Radius:");
+            ui.add(egui::DragValue::new(&mut self.seed_radius).speed(0.1).range(1..=16));
+        });
+        if ui.button("Reset and Seed").clicked() {
+            self.rebuild();
+        }
+
+        ui.separator();
+        ui.heading("Simulation");
+        ui.separator();
+
+        ui.add_enabled_ui(self.sim.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Steps per Frame:");
+                ui.add(egui::DragValue::new(&mut self.steps_per_frame).speed(1.0).range(1..=200));
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.running, "Run");
+                if ui.button("Step 100").clicked() {
+                    if let Some(sim) = &mut self.sim {
+                        sim.params = self.params;
+                        sim.run(100);
+                    }
+                }
+            });
+        });
+
+        if state_texture::is_available(ui.ctx()) {
+            ui.checkbox(&mut self.gpu_upload, "GPU Texture Upload")
+                .on_hover_text("Draw the field through double-buffered GPU textures instead of egui's texture manager");
+        }
+
+    }
+
+    fn on_close(&mut self) {
+        self.running = false;
+    }
+
+    fn tick(&mut self, _dt: f64) -> bool {
+        let Some(sim) = &mut self.sim else {
+            return false;
+        };
+        if !self.running {
+            return false;
+        }
+        sim.params = self.params;
+        sim.run(self.steps_per_frame);
+        true
+    }
+
+    fn show_content(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let Some(sim) = &mut self.sim else {
+            ui.vertical_centered(|ui| {
+                ui.label("No grid yet. Use the configuration panel to create one.");
+            });
+            return;
+        };
+
+        let image = Self::field_image(sim);
+        ui.label("Concentration of v (drag to seed)");
+        let size = egui::vec2(DISPLAY_SIZE, DISPLAY_SIZE);
+        let response = if self.gpu_upload && state_texture::is_available(ctx) {
+            state_texture::state_texture(ui, "gray_scott_field", &image, size, egui::Sense::click_and_drag())
+        } else {
+            let texture = match &mut self.texture {
+                Some(texture) => {
+                    texture.set(image, egui::TextureOptions::NEAREST);
+                    texture
+                },
+                None => self.texture.insert(ctx.load_texture("gray_scott_field", image, egui::TextureOptions::NEAREST)),
+            };
+            ui.add(egui::Image::new((texture.id(), size)).sense(egui::Sense::click_and_drag()))
+        };
+        if response.clicked() || response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let rel = (pos - response.rect.min) / size;
+                let x = ((rel.x * sim.width as f32) as usize).min(sim.width - 1);
+                let y = ((rel.y * sim.height as f32) as usize).min(sim.height - 1);
+                sim.seed_disc(x, y, self.seed_radius);
+            }
+        }
+
+        ui.label(format!(
+            "step {}   mean v = {:.4}   F = {:.4}   k = {:.4}",
+            sim.steps, sim.mean_v(), sim.params.feed, sim.params.kill
+        ));
+    }
+}
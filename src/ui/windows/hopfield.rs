@@ -1,17 +1,57 @@
 use eframe::egui;
-use egui_plot::{Line, Plot, PlotPoints};
-use rand::rngs::ThreadRng;
+use egui_plot::{Bar, BarChart, Legend, Line, MarkerShape, Plot, PlotPoint, Points, Text, VLine};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::borrow::Cow;
 use std::collections::HashSet;
+use std::path::Path;
 use rusttype::{point, Font, Scale};
 
-use crate::neural::hopfield::{HopfieldNetwork, TrainingRule};
-use crate::ui::widgets::grid::{draw_grid, apply_noise};
-use crate::ui::windows::Window;
+use crate::core::assets;
+use crate::core::augment::Augmentation;
+use crate::core::graph::Graph;
+use crate::core::history::{HistoryEntry, Retention, SimulationHistory};
+use crate::audio::{AudioError, AudioOutput, SonificationSettings, SonificationSource, Tone};
+use crate::core::job::{BackgroundJob, JobStatus};
+use crate::core::matrix_io::{read_matrices, write_matrices, MatrixFormat};
+use crate::core::safetensors::SafeTensors;
+use crate::error::RaumError;
+use crate::core::recorder::{RecordFormat, Recorder, RecorderConfig};
+use crate::core::metrics::{classify, histogram, masked_overlap, mean_std, mean_std_curves, overlap};
+use crate::core::noise::{flip_bits, NoiseModel, RegionMask};
+use crate::core::projection::{overlap_coordinates, Projection};
+use crate::graphics::export::{write_csv, write_gif, write_png};
+use crate::graphics::isometric::{downsample_min, IsometricProjection};
+use crate::graphics::raster::{render_bipolar_grid, render_line_plot, render_value_grid};
+use crate::neural::hopfield::analysis::{compare_rules, CapacityPoint, EnergySurface, RobustnessMap, WeightSpectrum, MAX_SURFACE_NEURONS};
+use crate::neural::hopfield::{
+    FlipHistory, HebbianNormalization, HopfieldError, HopfieldNetwork, LoadLevel, Modules, Orthogonalization, PatternLoad, Quantization, RecallStats,
+    SpinFlips, SweepOrder, TemperaturePoint, TrainingRule, EVALUATION_MAX_SWEEPS, HEBBIAN_CRITICAL_LOAD,
+};
+use crate::neural::TrainableParameters;
+use crate::ui::events::{self, EventKind};
+use crate::ui::metrics::{self, Metric};
+use crate::ui::notifications::{report_error, report_info};
+use crate::ui::session::{self, SessionAction};
+use crate::ui::undo::{self, Snapshot};
+use crate::ui::widgets::animation::AnimationExport;
+use crate::ui::widgets::colormap::{color_cell, legend, Colormap};
+use crate::ui::widgets::grid::{draw_grid, draw_signed_grid, paint_grid};
+use crate::ui::widgets::history::{history_line, history_mosaic, history_slider, mosaic_frames, BrowsableHistory};
+use crate::ui::widgets::job::job_progress;
+use crate::ui::widgets::mask::{mask_editor, MaskShape};
+use crate::ui::widgets::seed::seed_control;
+use crate::ui::widgets::snapshot::SnapshotExport;
+use crate::ui::widgets::timeline::Timeline;
+use crate::ui::widgets::visual::SpinGrid;
+use crate::ui::windows::{Command, Window};
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum GraphType {
     FullyConnected,
     ErdosRenyi,
+    /// Nearest neighbours on the pattern grid
+    Lattice,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -20,6 +60,552 @@ enum UpdateMode {
     Asynchronous,
 }
 
+/// Views that can be saved as PNG, in the order of their buttons
+const SNAPSHOT_VIEWS: [&str; 5] = ["Target", "Input", "Output", "Weights", "Energy"];
+
+/// Entries of the Presets menu
+const PRESETS: [(&str, &str); 2] = [
+    (
+        "Three Letters, 30% Noise",
+        "The classic demo: Hebbian training on A, B and C, with 30% of the bits of A flipped as the cue",
+    ),
+    (
+        "Catastrophic Forgetting",
+        "Learn the letters one after another and watch recall of the first letter collapse",
+    ),
+];
+
+/// How the states of a run are kept
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HistoryStorage {
+    /// Every state as a full vector
+    Full,
+    /// At most this many full states: the first, the latest and a thinned record in between
+    Thinned(usize),
+    /// Every state as the neurons flipped since the previous one, replayed on demand
+    FlipLists,
+}
+
+/// Keyframe spacing of flip-list histories: frames are rebuilt from at most this many sweeps
+const FLIP_KEYFRAME_INTERVAL: usize = 64;
+
+/// States recorded during a run
+#[derive(Debug, Clone)]
+enum RecordedStates {
+    Full(SimulationHistory<Vec<f64>>),
+    Flips(FlipHistory),
+}
+
+impl RecordedStates {
+    fn len(&self) -> usize {
+        match self {
+            RecordedStates::Full(states) => states.len(),
+            RecordedStates::Flips(flips) => flips.len(),
+        }
+    }
+
+    fn iteration(&self, index: usize) -> Option<usize> {
+        match self {
+            RecordedStates::Full(states) => states.entry(index).map(|entry| entry.iteration),
+            RecordedStates::Flips(flips) => flips.iteration(index),
+        }
+    }
+
+    /// State at `index`, borrowed from a full history or rebuilt from flip lists
+    fn get(&self, index: usize) -> Option<Cow<'_, [f64]>> {
+        match self {
+            RecordedStates::Full(states) => states.get(index).map(|state| Cow::Borrowed(state.as_slice())),
+            RecordedStates::Flips(flips) => flips.frame(index).map(Cow::Owned),
+        }
+    }
+
+    fn last(&self) -> Option<&Vec<f64>> {
+        match self {
+            RecordedStates::Full(states) => states.last(),
+            RecordedStates::Flips(flips) => Some(flips.last()),
+        }
+    }
+
+    /// At most `max_points` states spread evenly over the run
+    fn downsample(&self, max_points: usize) -> Vec<HistoryEntry<Vec<f64>>> {
+        match self {
+            RecordedStates::Full(states) => states.downsample(max_points).into_iter().cloned().collect(),
+            RecordedStates::Flips(flips) => flips.downsample(max_points),
+        }
+    }
+
+    fn try_map<U, E>(&self, f: impl FnMut(&Vec<f64>) -> Result<U, E>) -> Result<SimulationHistory<U>, E> {
+        match self {
+            RecordedStates::Full(states) => states.try_map(f),
+            RecordedStates::Flips(flips) => flips.try_map(f),
+        }
+    }
+}
+
+impl BrowsableHistory for RecordedStates {
+    fn len(&self) -> usize {
+        RecordedStates::len(self)
+    }
+
+    fn iteration(&self, index: usize) -> Option<usize> {
+        RecordedStates::iteration(self, index)
+    }
+
+    fn last_iteration(&self) -> usize {
+        self.iteration(self.len().saturating_sub(1)).unwrap_or(0)
+    }
+
+    fn dropped(&self) -> usize {
+        match self {
+            RecordedStates::Full(states) => states.evicted(),
+            RecordedStates::Flips(_) => 0,
+        }
+    }
+}
+
+/// Result of a network run performed on a worker thread
+struct RunOutput {
+    states: RecordedStates,
+    energies: SimulationHistory<f64>,
+    /// Sweeps, or single-neuron updates if `micro_steps`
+    iterations: usize,
+    /// States were recorded every few single-neuron updates instead of every sweep
+    micro_steps: bool,
+}
+
+/// Plots shown below the state grids
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum PlotTab {
+    Energy,
+    Landscape,
+    Surface,
+    Mosaic,
+    Trajectory,
+    Temperature,
+    Eigenmodes,
+    Pruning,
+    Demo,
+    Forgetting,
+    Rules,
+}
+
+/// Axes of the energy landscape view
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum LandscapeCoordinates {
+    /// First two principal components of the sampled states
+    Pca,
+    /// Overlaps with two stored patterns (indices into the trained patterns)
+    Overlap(usize, usize),
+}
+
+/// The map from states to landscape coordinates
+enum LandscapeAxes {
+    Pca(Projection),
+    Overlap(Vec<f64>, Vec<f64>),
+}
+
+impl LandscapeAxes {
+    fn project(&self, state: &[f64]) -> [f64; 2] {
+        match self {
+            LandscapeAxes::Pca(projection) => projection.project(state),
+            LandscapeAxes::Overlap(first, second) => overlap_coordinates(state, first, second),
+        }
+    }
+}
+
+/// Sampled states projected onto two dimensions, each with its energy
+struct Landscape {
+    axis_labels: [String; 2],
+    patterns: Vec<([f64; 2], f64, char)>,
+    attractors: Vec<([f64; 2], f64)>,
+    trajectory: Vec<([f64; 2], f64)>,
+}
+
+/// Maximum number of trajectory states included in the energy landscape
+const LANDSCAPE_TRAJECTORY_POINTS: usize = 200;
+
+/// Maximum number of states drawn in the overlap trajectory plot
+const TRAJECTORY_POINTS: usize = 500;
+
+/// Isometric view of a point in the (m1, m2, m3) overlap cube
+fn isometric(m: [f64; 3]) -> [f64; 2] {
+    let (cos, sin) = (30f64.to_radians().cos(), 30f64.to_radians().sin());
+    [(m[0] - m[1]) * cos, m[2] + (m[0] + m[1]) * sin]
+}
+
+/// Blue for the lowest energies through white to red for the highest, `t` from 0.0 to 1.0
+fn energy_color(t: f64) -> egui::Color32 {
+    let t = t.clamp(0.0, 1.0) as f32;
+    if t < 0.5 {
+        let u = t * 2.0;
+        egui::Color32::from_rgb((60.0 + 195.0 * u) as u8, (90.0 + 165.0 * u) as u8, 255)
+    } else {
+        let u = (t - 0.5) * 2.0;
+        egui::Color32::from_rgb(255, (255.0 - 195.0 * u) as u8, (255.0 - 195.0 * u) as u8)
+    }
+}
+
+/// Largest number of blocks along each side of the energy surface view
+const SURFACE_MAX_SIDE: usize = 64;
+
+/// Height of the highest block of the energy surface, relative to its longer side
+const SURFACE_RELIEF: f32 = 0.4;
+
+/// Load α = P/N of the trained network on a gauge running to twice the critical load,
+/// with a marker at the critical load and a warning color once it is approached
+fn load_gauge(ui: &mut egui::Ui, load: PatternLoad) {
+    let critical = load.critical();
+    let (color, status) = match load.level() {
+        LoadLevel::Safe => (egui::Color32::from_rgb(90, 170, 90), "within capacity"),
+        LoadLevel::NearCapacity => (egui::Color32::from_rgb(220, 170, 50), "near capacity"),
+        LoadLevel::Overloaded => (egui::Color32::from_rgb(210, 70, 70), "overloaded"),
+    };
+    let tooltip = format!(
+        "{} patterns on {} neurons. With the {:?} rule, retrieval is expected to fail beyond α ≈ {}: \
+         the crosstalk between stored patterns then outweighs each pattern's own field, \
+         so cues settle into spurious mixtures instead of the stored patterns.",
+        load.patterns, load.neurons, load.rule, critical,
+    );
+
+    ui.horizontal(|ui| {
+        ui.label(format!("Load α = P/N = {:.3}", load.alpha()));
+        ui.colored_label(color, status);
+    })
+    .response
+    .on_hover_text(&tooltip);
+    let gauge = egui::ProgressBar::new((load.alpha() / (2.0 * critical)).min(1.0) as f32)
+        .fill(color)
+        .text(format!("α_c = {}", critical));
+    let response = ui.add(gauge).on_hover_text(tooltip);
+    let x = response.rect.center().x;
+    ui.painter().vline(x, response.rect.y_range(), egui::Stroke::new(2.0, ui.visuals().strong_text_color()));
+}
+
+/// Outputs of the main network and, in a comparative run, of the second one, and the
+/// overlap with a replica run alongside the main one
+type RunResult = Result<(RunOutput, Option<RunOutput>, Option<SimulationHistory<f64>>), HopfieldError>;
+
+/// The trained network and the rule used, with the comparison network if one was
+/// requested, or None if training was cancelled
+type TrainResult = Result<Option<(HopfieldNetwork, TrainingRule, Option<(HopfieldNetwork, TrainingRule)>)>, HopfieldError>;
+
+/// Settings of the second network in a comparative run
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RunConfig {
+    training_rule: TrainingRule,
+    beta: f64,
+    update_mode: UpdateMode,
+    sweep_order: SweepOrder,
+    /// Single-neuron updates between recorded states of asynchronous runs; None records
+    /// every sweep
+    record_stride: Option<usize>,
+}
+
+impl RunConfig {
+    fn label(&self) -> String {
+        match self.update_mode {
+            UpdateMode::Synchronous => format!("{:?}, β = {:.2}, Synchronous", self.training_rule, self.beta),
+            UpdateMode::Asynchronous => format!(
+                "{:?}, β = {:.2}, Asynchronous ({})",
+                self.training_rule,
+                self.beta,
+                sweep_order_name(self.sweep_order)
+            ),
+        }
+    }
+}
+
+/// Bins of the final-overlap histogram of repeated runs, over [-1, 1]
+const OVERLAP_BINS: usize = 20;
+
+/// Aggregate of many runs from the same cue with independent random streams
+struct MonteCarloResult {
+    /// Overlap of each run's final state with the target pattern
+    final_overlaps: Vec<f64>,
+    /// Iterations of each run until it converged or hit the limit
+    convergence_times: Vec<usize>,
+    /// Number of runs that converged before the iteration limit
+    converged: usize,
+    /// Mean and standard deviation of the energy at each iteration
+    energy_band: Vec<(f64, f64)>,
+}
+
+type MonteCarloJob = BackgroundJob<Result<MonteCarloResult, HopfieldError>>;
+
+type RobustnessJob = BackgroundJob<Result<RobustnessMap, HopfieldError>>;
+
+/// Sparsity levels of a pruning sweep, from 0 to `PRUNING_MAX_SPARSITY`
+const PRUNING_LEVELS: usize = 20;
+
+const PRUNING_MAX_SPARSITY: f64 = 0.95;
+
+/// Recall accuracy of the trained network after magnitude pruning to `sparsity`
+#[derive(Debug, Clone, Copy)]
+struct PruningPoint {
+    sparsity: f64,
+    accuracy: f64,
+}
+
+type PruningJob = BackgroundJob<Result<Vec<PruningPoint>, HopfieldError>>;
+
+/// Rules compared by the capacity benchmark, in plotting order
+const COMPARED_RULES: [TrainingRule; 3] = [TrainingRule::Hebbian, TrainingRule::Storkey, TrainingRule::PseudoInverse];
+
+/// Size of the networks of the rule comparison; the patterns are random, so the grid
+/// size plays no part
+const COMPARISON_NEURONS: usize = 100;
+
+/// Loads of the rule comparison, in steps of 1 / `COMPARISON_NEURONS` up to this one
+const COMPARISON_MAX_LOAD: f64 = 0.5;
+
+/// Cue noise and cues per pattern of the rule comparison
+const COMPARISON_NOISE: f64 = 0.1;
+const COMPARISON_TRIALS: usize = 5;
+
+/// Pattern counts the rule comparison evaluates, every second one up to the maximum load
+fn comparison_counts() -> Vec<usize> {
+    let max = (COMPARISON_MAX_LOAD * COMPARISON_NEURONS as f64) as usize;
+    (1..=max / 2).map(|k| 2 * k).collect()
+}
+
+type RuleComparisonJob = BackgroundJob<Result<Vec<CapacityPoint>, HopfieldError>>;
+
+/// Mean recall accuracy of `net` over all `patterns` from cues with `noise` flipped bits
+fn recall_accuracy(
+    net: &HopfieldNetwork,
+    patterns: &[Vec<f64>],
+    noise: f64,
+    trials: usize,
+    rng: &mut StdRng,
+) -> Result<f64, HopfieldError> {
+    let stats = net.evaluate(patterns, &[noise], trials, rng)?;
+    Ok(stats.iter().map(|s| s.accuracy).sum::<f64>() / stats.len().max(1) as f64)
+}
+
+/// Noise levels of the degradation demo, evenly spaced from 0 to 1
+const DEMO_LEVELS: usize = 21;
+
+/// Recall trials per noise level of the degradation demo
+const DEMO_TRIALS: usize = 10;
+
+/// Seconds each demo trial stays on screen
+const DEMO_TRIAL_SECONDS: f64 = 0.15;
+
+/// Scripted demo: recall of one pattern from cues with more and more flipped bits,
+/// one trial at a time
+struct DegradationDemo {
+    pattern_index: usize,
+    /// (noise level, successful recalls, completed trials) for every level started
+    levels: Vec<(f64, usize, usize)>,
+    /// Outcome of the trial on screen
+    last_success: Option<bool>,
+    running: bool,
+    time_to_next: f64,
+}
+
+impl DegradationDemo {
+    fn new(pattern_index: usize) -> Self {
+        Self { pattern_index, levels: Vec::new(), last_success: None, running: true, time_to_next: 0.0 }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.levels.len() == DEMO_LEVELS && self.levels.last().is_some_and(|l| l.2 == DEMO_TRIALS)
+    }
+
+    /// Noise level of the next trial, opening a new level when the current one is done
+    fn next_level(&mut self) -> Option<f64> {
+        if self.is_finished() {
+            return None;
+        }
+        if self.levels.last().is_none_or(|l| l.2 == DEMO_TRIALS) {
+            let noise = self.levels.len() as f64 / (DEMO_LEVELS - 1) as f64;
+            self.levels.push((noise, 0, 0));
+        }
+        self.levels.last().map(|l| l.0)
+    }
+
+    fn record(&mut self, success: bool) {
+        if let Some(level) = self.levels.last_mut() {
+            level.1 += success as usize;
+            level.2 += 1;
+        }
+        self.last_success = Some(success);
+    }
+}
+
+/// Recall trials of the first letter after each letter of the forgetting demo
+const FORGETTING_TRIALS: usize = 10;
+
+/// Fraction of flipped bits in the cues of the forgetting demo
+const FORGETTING_CUE_NOISE: f64 = 0.1;
+
+/// Seconds between the letters of the forgetting demo
+const FORGETTING_STEP_SECONDS: f64 = 0.5;
+
+/// Scripted demo of catastrophic interference: a network of its own learns one letter
+/// after another with the Hebbian rule, and recall of the first letter is tested after
+/// each. Past the capacity the crosstalk of the new letters wipes out the old ones at once.
+struct ForgettingDemo {
+    network: HopfieldNetwork,
+    /// Letters learned so far, in order
+    learned: Vec<char>,
+    /// (letters learned, mean overlap with the first letter, fraction of perfect recalls)
+    curve: Vec<(usize, f64, f64)>,
+    running: bool,
+    time_to_next: f64,
+}
+
+/// Sweep orders offered for asynchronous updates; the checkerboard width is set to the
+/// grid size when a run starts
+const SWEEP_ORDERS: [SweepOrder; 4] = [
+    SweepOrder::RandomWithReplacement,
+    SweepOrder::RandomPermutation,
+    SweepOrder::Raster,
+    SweepOrder::Checkerboard { width: 0 },
+];
+
+fn sweep_order_name(order: SweepOrder) -> &'static str {
+    match order {
+        SweepOrder::RandomWithReplacement => "Random",
+        SweepOrder::RandomPermutation => "Random Permutation",
+        SweepOrder::Raster => "Raster",
+        SweepOrder::Checkerboard { .. } => "Checkerboard",
+    }
+}
+
+/// Draws the update mode radio buttons and, for asynchronous updates, the sweep order
+fn update_mode_control(ui: &mut egui::Ui, id: &str, mode: &mut UpdateMode, order: &mut SweepOrder) {
+    ui.horizontal(|ui| {
+        ui.radio_value(mode, UpdateMode::Synchronous, "Synchronous");
+        ui.radio_value(mode, UpdateMode::Asynchronous, "Asynchronous");
+    });
+    if *mode == UpdateMode::Asynchronous {
+        egui::ComboBox::from_id_source(id)
+            .selected_text(sweep_order_name(*order))
+            .show_ui(ui, |ui| {
+                for option in SWEEP_ORDERS {
+                    let selected = sweep_order_name(option) == sweep_order_name(*order);
+                    if ui.selectable_label(selected, sweep_order_name(option)).clicked() {
+                        *order = option;
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Order in which neurons are visited during a sweep");
+    }
+}
+
+/// Runs `net` from `input` with the given settings. `observer` receives the number of
+/// completed iterations after every update and stops the run by returning false.
+/// States are recorded as they are produced in the form `storage` asks for, so thinned
+/// or flip-list histories never hold every state of a long run at once.
+fn run_with(
+    net: &HopfieldNetwork,
+    input: &[f64],
+    max_iterations: usize,
+    config: RunConfig,
+    storage: HistoryStorage,
+    rng: &mut StdRng,
+    mut observer: impl FnMut(usize) -> bool,
+) -> Result<RunOutput, HopfieldError> {
+    let mut full = SimulationHistory::new(input.to_vec());
+    full.set_retention(Retention::Thinned);
+    if let HistoryStorage::Thinned(capacity) = storage {
+        full.set_capacity(Some(capacity));
+    }
+    let mut flips = FlipHistory::new(SpinFlips, input.to_vec(), FLIP_KEYFRAME_INTERVAL);
+    let stride = config.record_stride.filter(|_| config.update_mode == UpdateMode::Asynchronous);
+    // Progress is reported in sweeps either way
+    let updates_per_iteration = if stride.is_some() { net.size() } else { 1 };
+    let mut record = |i: usize, state: &[f64]| {
+        if i == 0 {
+            return true;
+        }
+        if storage == HistoryStorage::FlipLists {
+            let delta = SpinFlips::between(flips.last(), state);
+            flips.push(i, delta);
+        } else {
+            full.push(i, state.to_vec());
+        }
+        observer(i / updates_per_iteration)
+    };
+
+    // Call appropriate run method based on mode
+    let iterations = match (config.update_mode, stride) {
+        (UpdateMode::Synchronous, _) => {
+            net.run_streamed(input, max_iterations, config.beta, rng, &mut record)?
+        }
+        (UpdateMode::Asynchronous, None) => {
+            net.run_async_streamed(input, max_iterations, config.beta, config.sweep_order, rng, &mut record)?
+        }
+        (UpdateMode::Asynchronous, Some(stride)) => {
+            net.run_async_strided(input, max_iterations, config.beta, config.sweep_order, stride, rng, &mut record)?
+        }
+    };
+    let states = match storage {
+        HistoryStorage::FlipLists => RecordedStates::Flips(flips),
+        _ => RecordedStates::Full(full),
+    };
+    
+    // Calculate energy for each state
+    let energies = states.try_map(|state| net.energy(state))?;
+    Ok(RunOutput { states, energies, iterations, micro_steps: stride.is_some() })
+}
+
+fn is_safetensors(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("safetensors"))
+}
+
+/// Weights from a safetensors file exported by RAUM, or the matrix 'W' (or the first
+/// square matrix) of a .npy or .mat file
+fn read_weights(path: &Path) -> Result<HopfieldNetwork, RaumError> {
+    if is_safetensors(path) {
+        return HopfieldNetwork::from_safetensors(&SafeTensors::load(path)?);
+    }
+    let variables = read_matrices(path)?;
+    let square = |matrix: &Vec<Vec<f64>>| !matrix.is_empty() && matrix.iter().all(|row| row.len() == matrix.len());
+    let (_, weights) = variables
+        .iter()
+        .find(|(name, _)| name == "W")
+        .or_else(|| variables.iter().find(|(_, matrix)| square(matrix)))
+        .ok_or_else(|| format!("{} holds no square weight matrix", path.display()))?;
+    Ok(HopfieldNetwork::from_weights(weights)?)
+}
+
+/// Overlap q(t) = 1/N Σᵢ sᵢᵃ(t) sᵢᵇ(t) of two replicas at each iteration recorded for
+/// `a`. A replica that settled earlier stays on its last state.
+fn replica_overlap(a: &RecordedStates, b: &RecordedStates) -> SimulationHistory<f64> {
+    let mut q: Option<SimulationHistory<f64>> = None;
+    let mut j = 0;
+    for i in 0..a.len() {
+        let iteration = a.iteration(i).unwrap_or(0);
+        while j + 1 < b.len() && b.iteration(j + 1).is_some_and(|next| next <= iteration) {
+            j += 1;
+        }
+        let (Some(state_a), Some(state_b)) = (a.get(i), b.get(j)) else {
+            continue;
+        };
+        let value = overlap(&state_a, &state_b);
+        match &mut q {
+            Some(q) => q.push(iteration, value),
+            None => q = Some(SimulationHistory::new(value)),
+        }
+    }
+    q.unwrap_or_else(|| SimulationHistory::new(1.0))
+}
+
+/// The trained weights and how they were obtained, captured before retraining so that
+/// Edit → Undo can bring them back
+struct TrainedState {
+    network: Option<HopfieldNetwork>,
+    unpruned: Option<HopfieldNetwork>,
+    full_precision: Option<HopfieldNetwork>,
+    comparison_network: Option<(HopfieldNetwork, TrainingRule)>,
+    pattern_load: Option<PatternLoad>,
+    training_rule: TrainingRule,
+    hebbian_normalization: HebbianNormalization,
+}
+
 pub struct HopfieldWindow {
     // Neural network
     network: Option<HopfieldNetwork>,
@@ -34,26 +620,139 @@ pub struct HopfieldWindow {
     available_chars: Vec<char>,
     selected_indices_for_training: HashSet<usize>,
     selected_pattern_index_for_input: Option<usize>,
-    noise_level: f32,
+    noise_model: NoiseModel, // Corruption of the target pattern that gives the input
+    mask_shape: Option<MaskShape>, // Region mask tool on the input grid, if enabled
+    region_mask: Option<RegionMask>, // Cells of the input replaced by random values
     input_state: Vec<f64>,
     
     // Output state
-    output_states: Option<Vec<Vec<f64>>>,
-    energy_history: Option<Vec<f64>>,
+    output_states: Option<RecordedStates>,
+    energy_history: Option<SimulationHistory<f64>>,
     display_iteration: Option<usize>,
+    timeline: Timeline, // Playback of the recorded output states
+    sonify: bool, // Play the activity of each step while the timeline plays
+    sonification: SonificationSettings,
+    audio: Option<AudioOutput>, // Opened when sonification is first switched on
     iterations: Option<usize>,
+    micro_steps: bool, // The recorded states are single-neuron updates apart, not sweeps
+    record_micro_steps: bool, // Record asynchronous runs every `micro_step_stride` updates
+    micro_step_stride: usize,
+    history_storage: HistoryStorage,
+    run_cancelled: bool, // The last run was stopped before max_iterations
+    run_job: Option<BackgroundJob<RunResult>>,
+    train_job: Option<BackgroundJob<TrainResult>>, // Weight rows computed on a worker thread
+    
+    // Comparative run: the same input on a second, differently configured network
+    compare: bool,
+    comparison_config: RunConfig,
+    comparison_network: Option<(HopfieldNetwork, TrainingRule)>, // Trained with the main network
+    comparison: Option<RunOutput>,
+    
+    // Replica: the main network run again from the same input with independent noise
+    replica: bool,
+    replica_overlap: Option<SimulationHistory<f64>>, // q(t) between the two runs
+    
+    // Repeated runs from the current cue, each with its own random stream
+    repeat_runs: usize,
+    redraw_noise: bool, // Draw fresh input noise for every run
+    monte_carlo_job: Option<MonteCarloJob>,
+    monte_carlo: Option<MonteCarloResult>,
+    
+    // Batch recall evaluation
+    eval_noise_levels: String, // Comma-separated bit flip probabilities
+    eval_trials: usize,
+    evaluation: Option<Vec<RecallStats>>,
+    robustness_max_noise: f64,
+    robustness_steps: usize, // Noise levels of the robustness map
+    robustness_job: Option<RobustnessJob>,
+    robustness: Option<RobustnessMap>,
+    
+    // Magnitude pruning of the trained weights
+    prune_sparsity: f64,
+    compression_noise: f64, // Cue noise pruned and quantized networks are evaluated at
+    unpruned: Option<HopfieldNetwork>, // Trained weights while a pruned copy is in use
+    pruning_points: Vec<PruningPoint>, // One per manual pruning
+    pruning_job: Option<PruningJob>,
+    pruning_curve: Option<Vec<PruningPoint>>,
+    
+    // Capacity benchmark of the training rules on random patterns
+    rule_comparison_job: Option<RuleComparisonJob>,
+    rule_comparison: Option<Vec<CapacityPoint>>, // Only loads evaluated with every rule
+    rule_comparison_cancelled: bool, // The benchmark stopped before the maximum load
+    rule_comparison_csv_path: String,
+    
+    // Low-precision weights, applied to the network in use
+    quantize: bool,
+    quantization: Quantization,
+    full_precision: Option<HopfieldNetwork>, // The weights before quantization
+    quantization_accuracy: Option<(f64, f64)>, // Recall of the full-precision and quantized networks
+    
+    // Temperature sweep: steady-state overlap m(T) over a log-spaced range of beta
+    sweep_beta_range: (f64, f64),
+    sweep_points: usize,
+    sweep_trials: usize,
+    sweep_burn_in: usize, // Sweeps discarded before measuring
+    sweep_measure: usize, // Sweeps averaged over
+    sweep_job: Option<BackgroundJob<Result<Vec<TemperaturePoint>, HopfieldError>>>,
+    temperature_curve: Option<Vec<TemperaturePoint>>,
+    temperature_csv_path: String,
+    
+    // Long runs streamed to a file instead of kept in memory
+    record_path: String,
+    record_config: RecorderConfig,
+    record_iterations: usize,
+    record_energy: bool,
+    record_overlap: bool, // Overlap with the target pattern
+    record_job: Option<BackgroundJob<Result<usize, String>>>,
+    record_status: Option<String>,
+    animation_export: AnimationExport,
+    snapshot_export: SnapshotExport,
+    weights_path: String, // .npy or .mat
+    patterns_path: String,
+    
+    // Energy landscape view
+    plot_tab: PlotTab,
+    landscape_coordinates: LandscapeCoordinates,
+    landscape_samples: usize, // Random starts used to find attractors
+    landscape: Option<Landscape>,
+    energy_surface: Option<EnergySurface>, // Every state of a small network
+    surface_rotation: u8, // Quarter turns of the isometric view
+    mosaic_stride: usize, // Every k-th recorded state in the mosaic
+    mosaic_tile: f32, // Side of a mosaic tile in points
+    
+    // Degradation demo
+    demo: Option<DegradationDemo>,
+    forgetting: Option<ForgettingDemo>,
+    
+    // Weight eigen-decomposition view
+    weight_spectrum: Option<WeightSpectrum>,
+    eigen_modes: usize, // Leading eigenvectors shown
+
+    // Load of the trained network, P/N
+    pattern_load: Option<PatternLoad>,
     
     // Configuration
-    error_message: Option<String>,
     max_iterations: usize,
     beta: f64, 
+    calibrate_beta: bool, // `beta` is relative to the field scale of the network
     pattern_overlap: Option<Vec<Vec<f64>>>, 
     training_rule: TrainingRule,
+    hebbian_normalization: HebbianNormalization,
+    orthogonalization: Orthogonalization,
+    augmentation: Augmentation, // Shifted, mirrored and rotated copies added to the training set
     overlap_histogram: Option<Vec<egui_plot::Bar>>,
+    raw_overlap_histogram: Option<Vec<egui_plot::Bar>>, // Before orthogonalization
     graph_type: GraphType,
     er_connectivity: f64,
-    rng: ThreadRng,
+    er_asymmetry: f64, // Fraction of connections pruned independently in each direction
+    weight_noise: f64, // Gaussian weight noise, relative to the RMS weight
+    modular: bool, // Split the grid into modules with weaker coupling between them
+    module_grid: (usize, usize), // Columns and rows of modules
+    module_coupling: f64,
+    seed: u64, // Seed of `rng`, shown so runs can be reproduced
+    rng: StdRng,
     update_mode: UpdateMode,
+    sweep_order: SweepOrder, // Neuron order for asynchronous updates
 }
 
 impl HopfieldWindow {
@@ -71,6 +770,7 @@ impl HopfieldWindow {
         // Select the first pattern of the training subset as the initial input
         let initial_input = patterns.get(0).cloned().unwrap_or_else(|| vec![0.0; initial_grid_size * initial_grid_size]);
         let initial_selected_pattern_index = if patterns.is_empty() { None } else { Some(0) };
+        let seed = rand::random::<u32>() as u64;
 
         Self {
             network: None,
@@ -81,22 +781,120 @@ impl HopfieldWindow {
             available_chars,
             selected_indices_for_training: initial_selected_indices,
             selected_pattern_index_for_input: initial_selected_pattern_index,
-            noise_level: 0.0,
+            noise_model: NoiseModel::BitFlip { probability: 0.0 },
+            mask_shape: None,
+            region_mask: None,
             input_state: initial_input,
             output_states: None,
             energy_history: None,
             display_iteration: None,
+            timeline: Timeline::new(5.0),
+            sonify: false,
+            sonification: SonificationSettings::default(),
+            audio: None,
             iterations: None,
-            error_message: None,
+            micro_steps: false,
+            record_micro_steps: false,
+            micro_step_stride: 1,
+            history_storage: HistoryStorage::Full,
+            run_cancelled: false,
+            run_job: None,
+            train_job: None,
+            compare: false,
+            comparison_network: None,
+            replica: false,
+            replica_overlap: None,
+            comparison_config: RunConfig {
+                training_rule: TrainingRule::Hebbian,
+                beta: 1.0,
+                update_mode: UpdateMode::Synchronous,
+                sweep_order: SweepOrder::RandomWithReplacement,
+                record_stride: None,
+            },
+            comparison: None,
+            repeat_runs: 100,
+            redraw_noise: true,
+            monte_carlo_job: None,
+            monte_carlo: None,
+            eval_noise_levels: "0.0, 0.1, 0.2, 0.3".to_string(),
+            eval_trials: 20,
+            evaluation: None,
+            robustness_max_noise: 0.5,
+            robustness_steps: 11,
+            robustness_job: None,
+            robustness: None,
+            prune_sparsity: 0.5,
+            compression_noise: 0.1,
+            unpruned: None,
+            pruning_points: Vec::new(),
+            pruning_job: None,
+            pruning_curve: None,
+            rule_comparison_job: None,
+            rule_comparison: None,
+            rule_comparison_cancelled: false,
+            rule_comparison_csv_path: "hopfield_rules.csv".to_string(),
+            quantize: false,
+            quantization: Quantization::Uniform { bits: 4 },
+            full_precision: None,
+            quantization_accuracy: None,
+            sweep_beta_range: (0.01, 10.0),
+            sweep_points: 20,
+            sweep_trials: 5,
+            sweep_burn_in: 20,
+            sweep_measure: 20,
+            sweep_job: None,
+            temperature_curve: None,
+            temperature_csv_path: "hopfield_temperature.csv".to_string(),
+            record_path: "hopfield_run.csv".to_string(),
+            record_config: RecorderConfig {
+                format: RecordFormat::Csv,
+                stride: 1,
+                record_state: true,
+                metrics: Vec::new(),
+            },
+            record_iterations: 10_000,
+            record_energy: true,
+            record_overlap: true,
+            record_job: None,
+            record_status: None,
+            animation_export: AnimationExport::new("hopfield.gif"),
+            snapshot_export: SnapshotExport::new("hopfield"),
+            weights_path: "hopfield_weights.npy".to_string(),
+            patterns_path: "hopfield_patterns.npy".to_string(),
+            plot_tab: PlotTab::Energy,
+            landscape_coordinates: LandscapeCoordinates::Pca,
+            landscape_samples: 50,
+            landscape: None,
+            energy_surface: None,
+            surface_rotation: 0,
+            mosaic_stride: 1,
+            mosaic_tile: 48.0,
+            demo: None,
+            forgetting: None,
+            weight_spectrum: None,
+            eigen_modes: 4,
+            pattern_load: None,
             max_iterations: 100,
             beta: 1.0,
+            calibrate_beta: false,
             pattern_overlap: Self::calculate_overlap_matrix(&patterns),
             training_rule: TrainingRule::PseudoInverse,
+            hebbian_normalization: HebbianNormalization::None,
+            orthogonalization: Orthogonalization::None,
+            augmentation: Augmentation::default(),
             overlap_histogram: Self::calculate_overlap_histogram(&Self::calculate_overlap_matrix(&patterns)),
+            raw_overlap_histogram: None,
             graph_type: GraphType::FullyConnected,
             er_connectivity: 1.0,
-            rng: rand::thread_rng(),
+            er_asymmetry: 0.0,
+            weight_noise: 0.0,
+            modular: false,
+            module_grid: (2, 2),
+            module_coupling: 0.0,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
             update_mode: UpdateMode::Synchronous,
+            sweep_order: SweepOrder::RandomWithReplacement,
         }
     }
     
@@ -114,9 +912,8 @@ impl HopfieldWindow {
     }
     
     // Generate patterns of specified size for given characters using rusttype
-    fn get_patterns(grid_size: usize, characters: &[char]) -> Vec<(char, Vec<f64>)> {
+    pub(crate) fn get_patterns(grid_size: usize, characters: &[char]) -> Vec<(char, Vec<f64>)> {
         // --- Configuration ---
-        let font_path = "assets/font.otf"; // Ensure this font file exists at the project root
         let reference_pixel_height = 100.0; // Render large initially for bounds
         let threshold = 0.5; // Coverage threshold for 'on'
         let target_width = grid_size as f32;
@@ -124,28 +921,19 @@ impl HopfieldWindow {
         let num_neurons_dynamic = grid_size * grid_size;
         // ---------------------
 
+        // A font file in an asset directory overrides the embedded one
+        let (font_data, source) = assets::FONT.load();
         println!(
-            "Attempting to load font: {}, Grid size: {}, Chars: {:?}",
-            font_path, grid_size, characters
+            "Loading font from {:?}, Grid size: {}, Chars: {:?}",
+            source, grid_size, characters
         );
-        let font_data = match std::fs::read(font_path) {
-            Ok(data) => data,
-            Err(e) => {
-                eprintln!(
-                    "Error loading font file '{}': {}. Using fallback patterns.",
-                    font_path, e
-                );
-                // Fallback to basic patterns
-                return Self::get_fallback_patterns(grid_size, characters);
-            }
-        };
 
-        let font = match Font::try_from_vec(font_data) {
+        let font = match Font::try_from_vec(font_data.into_owned()) {
             Some(f) => f,
             None => {
                 eprintln!(
-                    "Error parsing font file '{}'. Using fallback patterns.",
-                    font_path
+                    "Error parsing font from {:?}. Using fallback patterns.",
+                    source
                 );
                 return Self::get_fallback_patterns(grid_size, characters);
             }
@@ -239,9 +1027,14 @@ impl HopfieldWindow {
         if let Some(idx) = self.selected_pattern_index_for_input {
             if let Some(pattern) = self.patterns.get(idx) {
                 if pattern.len() == self.current_grid_size * self.current_grid_size {
-                    self.input_state = apply_noise(pattern, self.noise_level);
+                    let size = self.current_grid_size;
+                    self.input_state = self.noise_model.apply(pattern, size, size, &mut self.rng);
+                    if let Some(mask) = self.region_mask.as_ref().filter(|mask| mask.fits(size, size)) {
+                        self.input_state = mask.apply(&self.input_state, &mut self.rng);
+                    }
                     // Reset output
                     self.output_states = None;
+                    self.comparison = None;
                     self.energy_history = None;
                     self.display_iteration = None;
                     self.iterations = None;
@@ -250,6 +1043,58 @@ impl HopfieldWindow {
         }
     }
     
+    // Select the training patterns, orthogonalize them if enabled and update the overlaps
+    fn refresh_training_patterns(&mut self) {
+        let (raw_patterns, trained_chars) = Self::filter_patterns(
+            &self.all_generated_patterns, 
+            &self.selected_indices_for_training
+        );
+        let size = self.current_grid_size;
+        let (raw_patterns, trained_chars) = self.augmentation.apply(&raw_patterns, &trained_chars, size, size);
+        // Gram-Schmidt vectors are real-valued, so the network is trained and cued with
+        // their sign projection; the overlap display still shows the exact vectors
+        let orthogonalized = self.orthogonalization.apply(&raw_patterns);
+        self.patterns = Orthogonalization::bipolar(&orthogonalized);
+        self.trained_chars = trained_chars;
+        
+        self.pattern_overlap = Self::calculate_overlap_matrix(&orthogonalized);
+        self.overlap_histogram = Self::calculate_overlap_histogram(&self.pattern_overlap);
+        events::emit(EventKind::PatternsEdited { patterns: self.patterns.len() });
+        self.raw_overlap_histogram = if self.orthogonalization == Orthogonalization::None {
+            None
+        } else {
+            Self::calculate_overlap_histogram(&Self::calculate_overlap_matrix(&raw_patterns))
+        };
+    }
+    
+    // Update the training subset after the selected characters changed
+    fn training_selection_changed(&mut self) {
+        // Update the training subset
+        self.refresh_training_patterns();
+        
+        // Reset network and output; a network still training would not match the patterns
+        self.train_job = None;
+        self.network = None;
+        self.landscape = None;
+        self.weight_spectrum = None;
+        self.demo = None;
+        self.output_states = None;
+        self.comparison = None;
+        self.energy_history = None;
+        self.iterations = None;
+        self.display_iteration = None;
+        
+        // Reset selected input pattern
+        self.selected_pattern_index_for_input = if self.patterns.is_empty() { 
+            None 
+        } else { 
+            Some(0) 
+        };
+        
+        // Update input state
+        self.update_input_state();
+    }
+    
     // Handle grid size change
     fn handle_grid_size_change(&mut self, new_size: usize) {
         println!("Grid size changed to: {}", new_size);
@@ -259,19 +1104,18 @@ impl HopfieldWindow {
         self.all_generated_patterns = Self::get_patterns(self.current_grid_size, &self.available_chars);
         
         // Update the training subset
-        let (new_patterns, new_trained_chars) = Self::filter_patterns(
-            &self.all_generated_patterns, 
-            &self.selected_indices_for_training
-        );
-        
-        self.patterns = new_patterns;
-        self.trained_chars = new_trained_chars;
+        self.refresh_training_patterns();
         
         // Reset network and output
+        self.train_job = None;
         self.network = None;
-        self.pattern_overlap = Self::calculate_overlap_matrix(&self.patterns);
-        self.overlap_histogram = Self::calculate_overlap_histogram(&self.pattern_overlap);
+        self.landscape = None;
+        self.weight_spectrum = None;
+        self.demo = None;
+        self.forgetting = None;
+        self.monte_carlo = None;
         self.output_states = None;
+        self.comparison = None;
         self.energy_history = None;
         self.iterations = None;
         self.display_iteration = None;
@@ -286,93 +1130,1952 @@ impl HopfieldWindow {
         // Update input state
         self.update_input_state();
     }
-    
-    // Run the network
-    fn run_network(&mut self) {
-        if self.input_state.len() != self.current_grid_size * self.current_grid_size {
-            self.error_message = Some("Cannot run: Input state size mismatch.".to_string());
-            return;
-        }
-        
-        self.error_message = None;
-        
-        if let Some(net) = &self.network {
-            // Check network size matches current grid size before running
-            if net.size() != self.current_grid_size * self.current_grid_size {
-                self.error_message = Some(
-                    "Cannot run: Network size does not match current grid size. Retrain network.".to_string()
-                );
-                return;
-            }
-            
-            // Call appropriate run method based on mode
-            let run_result = match self.update_mode {
-                UpdateMode::Synchronous => {
-                    net.run(&self.input_state, self.max_iterations, self.beta, &mut self.rng)
+
+    // Apply a setting from a session journal with the side effects of the matching
+    // control. Values equal to the current ones change nothing, so settings that changed
+    // together with another one replay without drawing extra random numbers.
+    fn apply_session_setting(&mut self, key: &str, value: &str) -> bool {
+        match key {
+            "grid_size" => {
+                let Ok(size) = value.parse::<usize>() else { return false };
+                if size != self.current_grid_size {
+                    self.handle_grid_size_change(size);
                 }
-                UpdateMode::Asynchronous => {
-                    net.run_async(&self.input_state, self.max_iterations, self.beta, &mut self.rng)
+            },
+            "training" => {
+                let selected: HashSet<usize> = value
+                    .chars()
+                    .filter_map(|c| self.available_chars.iter().position(|&a| a == c))
+                    .collect();
+                if selected != self.selected_indices_for_training {
+                    self.selected_indices_for_training = selected;
+                    self.training_selection_changed();
                 }
-            };
-
-            match run_result {
-                Ok((states_history, iters)) => {
-                    println!(
-                        "Network run completed. Iterations: {}. States: {}",
-                        iters,
-                        states_history.len()
-                    );
-                    
-                    // Calculate energy for each state
-                    let energies: Result<Vec<f64>, _> = states_history
-                        .iter()
-                        .map(|state| net.energy(state))
-                        .collect();
-
-                    match energies {
-                        Ok(energy_values) => {
-                            // Assign the whole history
-                            self.output_states = Some(states_history.clone()); 
-                            self.energy_history = Some(energy_values);
-                            self.iterations = Some(iters);
-                            // Default view to the last iteration
-                            self.display_iteration = Some(states_history.len().saturating_sub(1)); 
-                        }
-                        Err(e) => {
-                            // Handle energy calculation error
-                            self.output_states = None;
-                            self.energy_history = None;
-                            self.iterations = None;
-                            self.error_message = Some(format!("Energy Calc Error: {}", e));
-                        }
-                    }
+            },
+            "rule" => {
+                let rule = match value {
+                    "Hebbian" => TrainingRule::Hebbian,
+                    "PseudoInverse" => TrainingRule::PseudoInverse,
+                    "Storkey" => TrainingRule::Storkey,
+                    _ => return false,
+                };
+                if rule != self.training_rule {
+                    self.training_rule = rule;
+                    self.network = None;
                 }
-                Err(e) => {
-                    self.output_states = None;
-                    self.energy_history = None;
-                    self.iterations = None;
-                    self.error_message = Some(format!("Runtime Error: {}", e));
+            },
+            "hebbian_normalization" => {
+                let Some(normalization) = HebbianNormalization::ALL.into_iter().find(|n| n.name() == value) else {
+                    return false;
+                };
+                if normalization != self.hebbian_normalization {
+                    self.hebbian_normalization = normalization;
+                    self.network = None;
                 }
-            }
+            },
+            "input" => {
+                let index = value.parse::<usize>().ok();
+                if index != self.selected_pattern_index_for_input {
+                    self.selected_pattern_index_for_input = index;
+                    self.update_input_state();
+                }
+            },
+            "flip_probability" => {
+                let Ok(probability) = value.parse::<f64>() else { return false };
+                if self.noise_model != (NoiseModel::BitFlip { probability }) {
+                    self.noise_model = NoiseModel::BitFlip { probability };
+                    self.update_input_state();
+                }
+            },
+            "update_mode" => {
+                self.update_mode = match value {
+                    "Synchronous" => UpdateMode::Synchronous,
+                    "Asynchronous" => UpdateMode::Asynchronous,
+                    _ => return false,
+                };
+            },
+            "beta" => match value.parse() {
+                Ok(beta) => self.beta = beta,
+                Err(_) => return false,
+            },
+            "calibrate_beta" => match value.parse() {
+                Ok(calibrate) => self.calibrate_beta = calibrate,
+                Err(_) => return false,
+            },
+            "max_iterations" => match value.parse() {
+                Ok(max_iterations) => self.max_iterations = max_iterations,
+                Err(_) => return false,
+            },
+            // Like the seed control, re-seeding redraws the input noise
+            "seed" => {
+                let Ok(seed) = value.parse() else { return false };
+                self.seed = seed;
+                self.rng = StdRng::seed_from_u64(seed);
+                self.update_input_state();
+            },
+            _ => return false,
         }
+        true
     }
 
-    // Helper function to calculate histogram data for off-diagonal overlaps
-    fn calculate_overlap_histogram(overlap_matrix: &Option<Vec<Vec<f64>>>) -> Option<Vec<egui_plot::Bar>> {
-        let matrix = overlap_matrix.as_ref()?; // Return None if overlap_matrix is None
-
-        if matrix.is_empty() {
-            return None;
+    // Train a new network on the selected patterns
+    fn train_network(&mut self) {
+        if self.patterns.is_empty() {
+            report_error("Cannot train: No patterns selected.".to_string());
+            return;
         }
-
-        let num_patterns = matrix.len();
-        let mut off_diagonal_overlaps = Vec::new();
-
-        for i in 0..num_patterns {
-            for j in (i + 1)..num_patterns { // Only upper triangle (excluding diagonal)
-                // We care about the magnitude of correlation
-                off_diagonal_overlaps.push(matrix[i][j].abs()); 
-            }
+        
+        if self.train_job.is_some() {
+            return;
+        }
+        self.output_states = None;
+        self.comparison = None;
+        self.energy_history = None;
+        self.iterations = None;
+        self.display_iteration = None;
+
+        // Create network first
+        let mut net = match HopfieldNetwork::try_new(self.current_grid_size * self.current_grid_size) {
+            Ok(net) => net,
+            Err(e) => {
+                report_error(e);
+                return;
+            }
+        };
+
+        // Train using the selected rule on a worker thread; the pseudo-inverse rule takes
+        // O(N²P²) on large grids. The comparison network is trained on the same patterns
+        // in the same job, so runs only have to reuse it.
+        let patterns = self.patterns.clone();
+        let rule = self.training_rule;
+        let normalization = self.hebbian_normalization;
+        let comparison_rule = self.compare.then_some(self.comparison_config.training_rule);
+        let size = net.size();
+        let total = size * (1 + comparison_rule.is_some() as usize);
+        self.train_job = Some(BackgroundJob::spawn("Training (weight rows)", total, move |ctx| {
+            let completed = net.train_observed(&patterns, rule, normalization, |rows| {
+                ctx.report(rows);
+                !ctx.is_cancelled()
+            })?;
+            if !completed {
+                return Ok(None);
+            }
+            let comparison = match comparison_rule {
+                Some(comparison_rule) => {
+                    let mut other = HopfieldNetwork::new(size);
+                    let completed = other.train_observed(&patterns, comparison_rule, HebbianNormalization::None, |rows| {
+                        ctx.report(size + rows);
+                        !ctx.is_cancelled()
+                    })?;
+                    if !completed {
+                        return Ok(None);
+                    }
+                    Some((other, comparison_rule))
+                }
+                None => None,
+            };
+            Ok(Some((net, rule, comparison)))
+        }));
+    }
+    
+    // Take over the network of a finished training job
+    fn poll_training(&mut self) {
+        let Some(job) = &mut self.train_job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result,
+            JobStatus::Failed => {
+                self.train_job = None;
+                report_error("Training thread stopped unexpectedly".to_string());
+                return;
+            }
+        };
+        self.train_job = None;
+        
+        match result {
+            Ok(None) => report_info("Training cancelled."),
+            Ok(Some((mut net, rule, comparison))) => {
+                if self.network.is_some() {
+                    undo::checkpoint("Train", Box::new(self.trained_state()));
+                }
+                // Apply topology modification if necessary
+                self.apply_topology(&mut net);
+                self.comparison_network = comparison.map(|(mut other, comparison_rule)| {
+                    self.apply_topology(&mut other);
+                    (other, comparison_rule)
+                });
+                let net_size = net.size();
+                self.pattern_load = Some(PatternLoad::new(self.patterns.len(), net_size, rule));
+                self.unpruned = None;
+                self.pruning_points.clear();
+                self.pruning_curve = None;
+                self.set_weights(net);
+                self.demo = None;
+                self.monte_carlo = None;
+                report_info(format!("Network trained successfully on {} patterns.", self.patterns.len()));
+                events::emit(EventKind::TrainingFinished { patterns: self.patterns.len(), neurons: net_size });
+            }
+            Err(e) => {
+                self.network = None;
+                report_error(format!("Training Error: {}", e));
+            }
+        }
+    }
+    
+    fn trained_state(&self) -> TrainedState {
+        TrainedState {
+            network: self.network.clone(),
+            unpruned: self.unpruned.clone(),
+            full_precision: self.full_precision.clone(),
+            comparison_network: self.comparison_network.clone(),
+            pattern_load: self.pattern_load,
+            training_rule: self.training_rule,
+            hebbian_normalization: self.hebbian_normalization,
+        }
+    }
+
+    // Put back weights trained before, unless the grid size changed since
+    fn restore_trained_state(&mut self, state: TrainedState) -> bool {
+        let neurons = self.current_grid_size * self.current_grid_size;
+        if state.network.as_ref().is_some_and(|net| net.size() != neurons) {
+            report_error("Cannot undo training: The grid size changed since".to_string());
+            return false;
+        }
+        self.network = state.network;
+        self.unpruned = state.unpruned;
+        self.full_precision = state.full_precision;
+        self.comparison_network = state.comparison_network;
+        self.pattern_load = state.pattern_load;
+        self.training_rule = state.training_rule;
+        self.hebbian_normalization = state.hebbian_normalization;
+        self.quantization_accuracy = None;
+        self.pruning_points.clear();
+        self.pruning_curve = None;
+        self.demo = None;
+        self.monte_carlo = None;
+        self.output_states = None;
+        self.comparison = None;
+        self.energy_history = None;
+        self.iterations = None;
+        self.display_iteration = None;
+        self.weights_changed();
+        true
+    }
+    
+    // Dilution, weight noise and modules of the topology settings, applied after training
+    fn apply_topology(&mut self, net: &mut HopfieldNetwork) {
+        match self.graph_type {
+            GraphType::FullyConnected => {},
+            GraphType::ErdosRenyi if self.er_asymmetry > 0.0 => {
+                net.apply_asymmetric_dilution(self.er_connectivity, self.er_asymmetry, &mut self.rng);
+            },
+            GraphType::ErdosRenyi => net.apply_erdos_renyi_topology(self.er_connectivity, &mut self.rng),
+            GraphType::Lattice => {
+                let size = self.current_grid_size;
+                let _ = net.apply_graph_topology(&Graph::grid(size, size));
+            },
+        }
+        net.add_weight_noise(self.weight_noise, &mut self.rng);
+        if let Some(modules) = self.modules() {
+            let _ = net.apply_modules(&modules, self.module_coupling);
+        }
+    }
+    
+    // Module layout of the current grid, if the network is modular
+    fn modules(&self) -> Option<Modules> {
+        let size = self.current_grid_size;
+        self.modular.then(|| Modules::grid(size, size, self.module_grid.0, self.module_grid.1))
+    }
+    
+    // Run the network on a worker thread; the result is collected by `poll_run`
+    fn run_network(&mut self) {
+        if self.input_state.len() != self.current_grid_size * self.current_grid_size {
+            report_error("Cannot run: Input state size mismatch.".to_string());
+            return;
+        }
+        
+        if let Some(net) = &self.network {
+            // Check network size matches current grid size before running
+            if net.size() != self.current_grid_size * self.current_grid_size {
+                report_error(
+                    "Cannot run: Network size does not match current grid size. Retrain network.".to_string()
+                );
+                return;
+            }
+            
+            let net = net.clone();
+            let input = self.input_state.clone();
+            let max_iterations = self.max_iterations;
+            let history_storage = self.history_storage;
+            let record_stride = self.record_micro_steps.then_some(self.micro_step_stride);
+            let config = RunConfig { record_stride, ..self.with_grid_width(self.current_config()) };
+            
+            // The second network was trained with the first, on the same patterns
+            let comparison = if self.compare {
+                match &self.comparison_network {
+                    Some((other, rule)) if *rule == self.comparison_config.training_rule && other.size() == net.size() => {
+                        Some((other.clone(), RunConfig { record_stride, ..self.with_grid_width(self.comparison_config) }))
+                    }
+                    _ => {
+                        report_error(
+                            "Cannot compare: Retrain the network to train the second configuration's rule.".to_string()
+                        );
+                        return;
+                    }
+                }
+            } else {
+                None
+            };
+            
+            // The worker gets its own generator, seeded from ours so runs stay reproducible
+            let mut rng = StdRng::seed_from_u64(self.rng.gen());
+            let replica_seed = self.replica.then(|| self.rng.gen::<u64>());
+            let total = max_iterations * (1 + comparison.is_some() as usize + replica_seed.is_some() as usize);
+            self.run_job = Some(BackgroundJob::spawn("Running network", total, move |ctx| {
+                let progress = |offset: usize| move |iteration| {
+                    ctx.report(offset + iteration);
+                    !ctx.is_cancelled()
+                };
+                let output = run_with(&net, &input, max_iterations, config, history_storage, &mut rng, progress(0))?;
+                let comparison_output = match &comparison {
+                    Some((other, other_config)) if !ctx.is_cancelled() => {
+                        Some(run_with(
+                            other, &input, max_iterations, *other_config, history_storage, &mut rng, progress(max_iterations)
+                        )?)
+                    }
+                    _ => None,
+                };
+                // Same network, input and settings; only the random stream differs
+                let replica = match replica_seed {
+                    Some(seed) if !ctx.is_cancelled() => {
+                        let mut replica_rng = StdRng::seed_from_u64(seed);
+                        let offset = max_iterations * (1 + comparison.is_some() as usize);
+                        let replica = run_with(&net, &input, max_iterations, config, history_storage, &mut replica_rng, progress(offset))?;
+                        Some(replica_overlap(&output.states, &replica.states))
+                    }
+                    _ => None,
+                };
+                Ok((output, comparison_output, replica))
+            }));
+        }
+    }
+    
+    // `config` with a checkerboard sweep order fitted to the current grid
+    fn with_grid_width(&self, config: RunConfig) -> RunConfig {
+        match config.sweep_order {
+            SweepOrder::Checkerboard { .. } => RunConfig {
+                sweep_order: SweepOrder::Checkerboard { width: self.current_grid_size },
+                ..config
+            },
+            _ => config,
+        }
+    }
+    
+    // The β the dynamics use: `beta` itself, or scaled by the weights if calibrated
+    fn effective_beta(&self) -> f64 {
+        match &self.network {
+            Some(net) if self.calibrate_beta => net.calibrated_beta(self.beta, self.update_mode == UpdateMode::Synchronous),
+            _ => self.beta,
+        }
+    }
+    
+    // Settings of the main network as a `RunConfig`
+    fn current_config(&self) -> RunConfig {
+        RunConfig {
+            training_rule: self.training_rule,
+            beta: self.effective_beta(),
+            update_mode: self.update_mode,
+            sweep_order: self.sweep_order,
+            record_stride: None,
+        }
+    }
+    
+    // Collect the result of a finished background run
+    fn poll_run(&mut self) {
+        let Some(job) = &mut self.run_job else {
+            return;
+        };
+        let cancelled = job.is_cancelled();
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result.map_err(|e| e.to_string()),
+            JobStatus::Failed => Err("Simulation thread stopped unexpectedly".to_string()),
+        };
+        self.run_job = None;
+        
+        match result {
+            Ok((output, comparison, replica)) => {
+                println!(
+                    "Network run completed. Iterations: {}. States: {}",
+                    output.iterations,
+                    output.states.len()
+                );
+                if let Some(&energy) = output.energies.last() {
+                    metrics::publish(Metric::Energy, energy);
+                }
+                events::emit(EventKind::RunCompleted { iterations: output.iterations, converged: !cancelled });
+                // Default view to the last iteration
+                self.display_iteration = Some(output.states.len().saturating_sub(1));
+                self.timeline.pause();
+                // Assign the whole history
+                self.output_states = Some(output.states);
+                self.energy_history = Some(output.energies);
+                self.iterations = Some(output.iterations);
+                self.micro_steps = output.micro_steps;
+                self.run_cancelled = cancelled;
+                self.comparison = comparison;
+                self.replica_overlap = replica;
+            }
+            Err(e) => {
+                self.output_states = None;
+                self.comparison = None;
+                self.replica_overlap = None;
+                self.energy_history = None;
+                self.iterations = None;
+                report_error(format!("Runtime Error: {}", e));
+            }
+        }
+    }
+
+    // Write the recorded output history as an animated GIF
+    fn record_animation(&mut self) {
+        let Some(states) = &self.output_states else {
+            return;
+        };
+        let export = &self.animation_export;
+        let size = self.current_grid_size;
+        let frames = states
+            .downsample(export.max_frames)
+            .into_iter()
+            .map(|entry| render_bipolar_grid(&entry.state, size, size, export.scale))
+            .collect();
+        
+        match write_gif(&export.path, frames, export.fps) {
+            Ok(()) => {
+                report_info(format!("Recorded animation to {}", export.path));
+            }
+            Err(e) => report_error(format!("Failed to record animation: {}", e)),
+        }
+    }
+    
+    // Grid state currently shown in the output column
+    fn displayed_output(&self) -> Option<Cow<'_, [f64]>> {
+        let states = self.output_states.as_ref()?;
+        states.get(self.display_iteration.unwrap_or(0).min(states.len().saturating_sub(1)))
+    }
+    
+    // Save one of the views listed in `SNAPSHOT_VIEWS` as a PNG
+    fn save_snapshot(&mut self, view: usize) {
+        let export = &self.snapshot_export;
+        let size = self.current_grid_size;
+        let image = match SNAPSHOT_VIEWS[view] {
+            "Target" => self.selected_pattern_index_for_input
+                .and_then(|idx| self.patterns.get(idx))
+                .map(|pattern| render_bipolar_grid(pattern, size, size, export.scale)),
+            "Input" => Some(render_bipolar_grid(&self.input_state, size, size, export.scale)),
+            "Output" => self.displayed_output()
+                .map(|output| render_bipolar_grid(&output, size, size, export.scale)),
+            "Weights" => self.network.as_ref()
+                .map(|network| {
+                    let weights = network.weights();
+                    let max_abs = weights.iter().flatten().fold(0.0f64, |m, w| m.max(w.abs()));
+                    render_value_grid(weights, export.scale, |w| image::Rgba(Colormap::Diverging.map_signed(w, max_abs).to_array()))
+                }),
+            _ => self.energy_history.as_ref().map(|energies| {
+                let points: Vec<[f64; 2]> = energies.iter().map(|e| [e.iteration as f64, e.state]).collect();
+                render_line_plot(&points, export.plot_size[0], export.plot_size[1])
+            }),
+        };
+        let Some(image) = image else {
+            return;
+        };
+        
+        let path = export.path_for(SNAPSHOT_VIEWS[view]);
+        match write_png(&path, &image) {
+            Ok(()) => {
+                report_info(format!("Saved {}", path));
+            }
+            Err(e) => report_error(format!("Failed to save image: {}", e)),
+        }
+    }
+    
+    // Write the weights in use (and, to a .mat file, the patterns) for analysis elsewhere
+    fn export_weights(&mut self) {
+        let Some(net) = &self.network else {
+            return;
+        };
+        let path = Path::new(&self.weights_path);
+        if is_safetensors(path) {
+            let file = net
+                .to_safetensors()
+                .with_metadata("rule", format!("{:?}", self.training_rule))
+                .with_metadata("patterns", self.patterns.len());
+            match file.save(path) {
+                Ok(()) => report_info(format!("Saved {}", self.weights_path)),
+                Err(e) => report_error(format!("Failed to save weights: {}", e)),
+            }
+            return;
+        }
+        let mut variables: Vec<(&str, &[Vec<f64>])> = vec![("W", net.weights())];
+        if MatrixFormat::from_path(path).is_ok_and(|format| format == MatrixFormat::Mat) && !self.patterns.is_empty() {
+            variables.push(("patterns", &self.patterns));
+        }
+        match write_matrices(path, &variables) {
+            Ok(()) => report_info(format!("Saved {}", self.weights_path)),
+            Err(e) => report_error(format!("Failed to save weights: {}", e)),
+        }
+    }
+    
+    fn export_patterns(&mut self) {
+        match write_matrices(Path::new(&self.patterns_path), &[("patterns", &self.patterns)]) {
+            Ok(()) => report_info(format!("Saved {}", self.patterns_path)),
+            Err(e) => report_error(format!("Failed to save patterns: {}", e)),
+        }
+    }
+    
+    // Replace the network by externally computed weights, resizing the grid to fit
+    fn import_weights(&mut self) {
+        let net = match read_weights(Path::new(&self.weights_path)) {
+            Ok(net) => net,
+            Err(e) => {
+                report_error(format!("Failed to load weights: {}", e));
+                return;
+            }
+        };
+        let side = (net.size() as f64).sqrt().round() as usize;
+        if side * side != net.size() || !(8..=32).contains(&side) {
+            report_error(format!(
+                "A {0} × {0} weight matrix does not fit a square grid of 8 × 8 to 32 × 32 neurons",
+                net.size()
+            ));
+            return;
+        }
+        if side != self.current_grid_size {
+            self.handle_grid_size_change(side);
+        }
+        report_info(format!("Loaded {} × {} weights from {}", net.size(), net.size(), self.weights_path));
+        self.unpruned = None;
+        self.pruning_points.clear();
+        self.pruning_curve = None;
+        self.set_weights(net);
+    }
+    
+    // Output grids of both networks of a comparative run at the displayed iteration
+    fn show_comparison(&self, ui: &mut egui::Ui) {
+        let (Some(states), Some(comparison)) = (&self.output_states, &self.comparison) else {
+            return;
+        };
+        let size = self.current_grid_size;
+        let index = self.display_iteration.unwrap_or(0);
+        let runs = [
+            ("A", self.current_config(), states, self.iterations.unwrap_or(0)),
+            ("B", self.comparison_config, &comparison.states, comparison.iterations),
+        ];
+        
+        ui.columns(2, |columns| {
+            for (ui, (name, config, states, iterations)) in columns.iter_mut().zip(runs) {
+                ui.vertical_centered(|ui| {
+                    ui.label(format!("{}: {}", name, config.label()));
+                    // Runs may converge at different times; the shorter one stays on its last state
+                    let index = index.min(states.len().saturating_sub(1));
+                    let (Some(iteration), Some(state)) = (states.iteration(index), states.get(index)) else {
+                        return;
+                    };
+                    if state.len() == size * size {
+                        draw_grid(ui, &state, size, size, 4.0);
+                        let unit = if self.micro_steps { "Update" } else { "Iteration" };
+                        ui.label(format!("{} {} of {}", unit, iteration, iterations));
+                        if let Some(retrieval) = classify(&state, &self.patterns, true) {
+                            let recalled = self.trained_chars.get(retrieval.pattern_index).copied().unwrap_or('?');
+                            ui.label(format!(
+                                "Recalled: '{}' ({:.0}% match{})",
+                                recalled,
+                                retrieval.match_fraction * 100.0,
+                                if retrieval.inverted { ", inverted" } else { "" }
+                            ));
+                        }
+                    }
+                });
+            }
+        });
+    }
+    
+    // Sample attractors and project them, the stored patterns and the current trajectory to 2D
+    fn compute_landscape(&mut self) {
+        let Some(net) = &self.network else {
+            return;
+        };
+        if net.size() != self.current_grid_size * self.current_grid_size {
+            report_error("Cannot compute landscape: Retrain network.".to_string());
+            return;
+        }
+        
+        // Attractors other than the stored patterns themselves (inverses and spurious states)
+        let attractors: Vec<Vec<f64>> = net
+            .sample_attractors(self.landscape_samples, &mut self.rng)
+            .into_iter()
+            .filter(|a| !self.patterns.contains(a))
+            .collect();
+        let trajectory: Vec<Vec<f64>> = self.output_states.as_ref().map_or(Vec::new(), |states| {
+            states.downsample(LANDSCAPE_TRAJECTORY_POINTS).into_iter().map(|e| e.state.clone()).collect()
+        });
+        
+        let (axes, axis_labels) = match self.landscape_coordinates {
+            LandscapeCoordinates::Pca => {
+                let samples: Vec<Vec<f64>> = self.patterns.iter().chain(&attractors).chain(&trajectory).cloned().collect();
+                let Some(projection) = Projection::pca(&samples) else {
+                    report_error("Cannot compute landscape: No states to project.".to_string());
+                    return;
+                };
+                let [v1, v2] = projection.explained_variance;
+                let labels = [format!("PC1 ({:.0}%)", v1 * 100.0), format!("PC2 ({:.0}%)", v2 * 100.0)];
+                (LandscapeAxes::Pca(projection), labels)
+            }
+            LandscapeCoordinates::Overlap(i, j) => {
+                let (Some(first), Some(second)) = (self.patterns.get(i).cloned(), self.patterns.get(j).cloned()) else {
+                    report_error("Cannot compute landscape: Select two trained patterns.".to_string());
+                    return;
+                };
+                let char_at = |k: usize| self.trained_chars.get(k).copied().unwrap_or('?');
+                let labels = [format!("Overlap with '{}'", char_at(i)), format!("Overlap with '{}'", char_at(j))];
+                (LandscapeAxes::Overlap(first, second), labels)
+            }
+        };
+        let point = |state: &Vec<f64>| (axes.project(state), net.energy(state).unwrap_or(0.0));
+        
+        self.landscape = Some(Landscape {
+            axis_labels,
+            patterns: self.patterns.iter().zip(&self.trained_chars)
+                .map(|(pattern, &c)| {
+                    let (position, energy) = point(pattern);
+                    (position, energy, c)
+                })
+                .collect(),
+            attractors: attractors.iter().map(point).collect(),
+            trajectory: trajectory.iter().map(point).collect(),
+        });
+    }
+    
+    // Scatter plot of the energy landscape, colored by energy
+    fn show_landscape(&self, ui: &mut egui::Ui, height: f32) {
+        let Some(landscape) = &self.landscape else {
+            ui.label("(Compute the landscape in the controls)");
+            return;
+        };
+        let energies = landscape.patterns.iter().map(|p| p.1)
+            .chain(landscape.attractors.iter().map(|a| a.1))
+            .chain(landscape.trajectory.iter().map(|t| t.1));
+        let (min, max) = energies.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), e| (lo.min(e), hi.max(e)));
+        let color = |energy: f64| energy_color(if max > min { (energy - min) / (max - min) } else { 0.5 });
+        
+        ui.label(format!(
+            "◆ stored patterns   ● other attractors   ▪ trajectory   (energy {:.3} blue to {:.3} red)",
+            min, max
+        ));
+        Plot::new("landscape_plot")
+            .height(height)
+            .data_aspect(1.0)
+            .x_axis_label(landscape.axis_labels[0].clone())
+            .y_axis_label(landscape.axis_labels[1].clone())
+            .show(ui, |plot_ui| {
+                if landscape.trajectory.len() > 1 {
+                    let path: Vec<[f64; 2]> = landscape.trajectory.iter().map(|t| t.0).collect();
+                    plot_ui.line(Line::new(path).color(egui::Color32::GRAY));
+                }
+                for &(position, energy) in &landscape.trajectory {
+                    plot_ui.points(Points::new(position).shape(MarkerShape::Square).radius(2.5).color(color(energy)));
+                }
+                for &(position, energy) in &landscape.attractors {
+                    plot_ui.points(Points::new(position).shape(MarkerShape::Circle).radius(4.0).color(color(energy)));
+                }
+                for &(position, energy, c) in &landscape.patterns {
+                    plot_ui.points(Points::new(position).shape(MarkerShape::Diamond).radius(7.0).color(color(energy)));
+                    plot_ui.text(Text::new(PlotPoint::new(position[0], position[1]), format!("  {}", c))
+                        .anchor(egui::Align2::LEFT_BOTTOM));
+                }
+            });
+    }
+    
+    /// Isometric view of the energy of every state, with the stored patterns and the
+    /// last run's trajectory on top
+    fn show_energy_surface(&mut self, ui: &mut egui::Ui, height: f32) {
+        let size = self.network.as_ref().map(|net| net.size());
+        ui.horizontal(|ui| {
+            let computable = size.is_some_and(|n| n <= MAX_SURFACE_NEURONS);
+            if ui.add_enabled(computable, egui::Button::new("Compute Surface"))
+                .on_disabled_hover_text(format!("Train a network of at most {} neurons (a 4 × 4 grid)", MAX_SURFACE_NEURONS))
+                .clicked()
+            {
+                if let Some(net) = &self.network {
+                    match EnergySurface::new(net) {
+                        Ok(surface) => self.energy_surface = Some(surface),
+                        Err(e) => report_error(format!("Cannot compute surface: {}", e)),
+                    }
+                }
+            }
+            if ui.button("⟲").on_hover_text("Turn the view").clicked() {
+                self.surface_rotation = (self.surface_rotation + 3) % 4;
+            }
+            if ui.button("⟳").on_hover_text("Turn the view").clicked() {
+                self.surface_rotation = (self.surface_rotation + 1) % 4;
+            }
+        });
+        let Some(surface) = &self.energy_surface else {
+            ui.label("(Compute the surface of a trained network)");
+            return;
+        };
+        if size != Some(surface.column_bits + surface.row_bits) {
+            ui.label("(The network changed size; compute the surface again)");
+            return;
+        }
+
+        // Large surfaces are shown by their lowest energy per block of states
+        let (energies, columns, rows, factor) = downsample_min(&surface.energies, surface.columns(), surface.rows(), SURFACE_MAX_SIDE);
+        let range = (surface.max - surface.min).max(1e-12);
+        let relief = columns.max(rows) as f32 * SURFACE_RELIEF;
+        let heights: Vec<f32> = energies.iter().map(|&e| ((e - surface.min) / range) as f32 * relief).collect();
+        let projection = IsometricProjection::new(columns, rows, self.surface_rotation);
+        let blocks = projection.blocks(&heights);
+
+        ui.label(format!(
+            "{} states, energy {:.3} (blue) to {:.3} (red){}   ◆ stored patterns   ▪ trajectory",
+            surface.energies.len(),
+            surface.min,
+            surface.max,
+            if factor > 1 { format!(", lowest of each {} × {} block of states", factor, factor) } else { String::new() }
+        ));
+        let (response, painter) = ui.allocate_painter(egui::vec2(ui.available_width(), height), egui::Sense::hover());
+        let (min, max) = blocks.iter().flat_map(|b| b.top.iter().chain(&b.left).chain(&b.right)).fold(
+            (egui::vec2(f32::MAX, f32::MAX), egui::vec2(f32::MIN, f32::MIN)),
+            |(lo, hi), p| (lo.min(egui::vec2(p[0], p[1])), hi.max(egui::vec2(p[0], p[1]))),
+        );
+        let extent = (max - min).max(egui::vec2(1e-3, 1e-3));
+        let scale = 0.95 * (response.rect.width() / extent.x).min(response.rect.height() / extent.y);
+        let to_screen = |p: [f32; 2]| response.rect.center() + (egui::vec2(p[0], p[1]) - (min + max) / 2.0) * scale;
+
+        let mut mesh = egui::Mesh::default();
+        let mut quad = |corners: &[[f32; 2]; 4], color: egui::Color32| {
+            let first = mesh.vertices.len() as u32;
+            for &corner in corners {
+                mesh.colored_vertex(to_screen(corner), color);
+            }
+            mesh.add_triangle(first, first + 1, first + 2);
+            mesh.add_triangle(first, first + 2, first + 3);
+        };
+        let shade = |color: egui::Color32, f: f32| {
+            egui::Color32::from_rgb((color.r() as f32 * f) as u8, (color.g() as f32 * f) as u8, (color.b() as f32 * f) as u8)
+        };
+        for block in &blocks {
+            let (column, row) = block.cell;
+            let color = energy_color((energies[row * columns + column] - surface.min) / range);
+            quad(&block.left, shade(color, 0.75));
+            quad(&block.right, shade(color, 0.55));
+            quad(&block.top, color);
+        }
+        painter.add(egui::Shape::mesh(mesh));
+
+        // Markers sit on top of the block holding their state
+        let marker = |state: &[f64]| {
+            let (column, row) = surface.cell_of(state)?;
+            let (column, row) = (column / factor, row / factor);
+            let lift = heights[row * columns + column];
+            Some(to_screen(projection.project(column as f32 + 0.5, row as f32 + 0.5, lift)))
+        };
+        if let Some(states) = &self.output_states {
+            let path: Vec<egui::Pos2> = states.downsample(200).iter().filter_map(|entry| marker(&entry.state)).collect();
+            painter.add(egui::Shape::line(path.clone(), egui::Stroke::new(2.0, egui::Color32::WHITE)));
+            for &point in &path {
+                painter.rect_filled(egui::Rect::from_center_size(point, egui::vec2(4.0, 4.0)), 0.0, egui::Color32::WHITE);
+            }
+            let shown = self.display_iteration.and_then(|i| states.get(i)).and_then(|state| marker(&state));
+            if let Some(point) = shown {
+                painter.circle_stroke(point, 6.0, egui::Stroke::new(2.0, egui::Color32::YELLOW));
+            }
+        }
+        for (pattern, &c) in self.patterns.iter().zip(&self.trained_chars) {
+            if let Some(point) = marker(pattern) {
+                let diamond = [point + egui::vec2(0.0, -6.0), point + egui::vec2(6.0, 0.0), point + egui::vec2(0.0, 6.0), point + egui::vec2(-6.0, 0.0)];
+                painter.add(egui::Shape::convex_polygon(diamond.to_vec(), egui::Color32::BLACK, egui::Stroke::new(1.5, egui::Color32::WHITE)));
+                painter.text(point + egui::vec2(8.0, -8.0), egui::Align2::LEFT_BOTTOM, c, egui::FontId::proportional(14.0), egui::Color32::WHITE);
+            }
+        }
+    }
+    
+    // Contact sheet of the recorded states of the last run; clicking a tile shows it above
+    fn show_mosaic(&mut self, ui: &mut egui::Ui, height: f32) {
+        let Some(states) = &self.output_states else {
+            ui.label("(Run network to see its states)");
+            return;
+        };
+        let size = self.current_grid_size;
+        ui.horizontal(|ui| {
+            ui.label("Stride:");
+            ui.add(egui::DragValue::new(&mut self.mosaic_stride).speed(0.2).range(1..=states.len().max(1)))
+                .on_hover_text("Show every k-th recorded state; the final state is always shown");
+            ui.separator();
+            ui.label("Tile Size:");
+            ui.add(egui::Slider::new(&mut self.mosaic_tile, 16.0..=128.0).suffix(" pt"));
+            ui.separator();
+            ui.label(format!("{} of {} states", mosaic_frames(states.len(), self.mosaic_stride).len(), states.len()));
+        });
+
+        let cell_size = self.mosaic_tile / size.max(1) as f32;
+        let mut selected = self.display_iteration.unwrap_or(0);
+        let clicked = history_mosaic(ui, states, self.mosaic_stride, egui::Vec2::splat(self.mosaic_tile), height, &mut selected, |painter, tile, index| {
+            if let Some(state) = states.get(index).filter(|state| state.len() == size * size) {
+                paint_grid(painter, tile.min, &SpinGrid { state: &state, width: size }, cell_size, None);
+            }
+        });
+        if clicked {
+            self.timeline.pause();
+            self.display_iteration = Some(selected);
+        }
+    }
+    
+    // Run the network `repeat_runs` times on a worker thread. Run k uses a generator
+    // seeded with base + k, so the whole batch is reproducible from the window seed.
+    fn start_monte_carlo(&mut self) {
+        let size = self.current_grid_size;
+        let Some(net) = self.network.clone().filter(|net| net.size() == size * size) else {
+            report_error("Cannot run: Network size does not match current grid size. Retrain network.".to_string());
+            return;
+        };
+        let Some(target) = self.selected_pattern_index_for_input.and_then(|i| self.patterns.get(i)).cloned() else {
+            report_error("Select a target pattern first".to_string());
+            return;
+        };
+        
+        let input = self.input_state.clone();
+        let noise_model = self.redraw_noise.then_some(self.noise_model);
+        let max_iterations = self.max_iterations;
+        let config = self.with_grid_width(self.current_config());
+        let runs = self.repeat_runs;
+        let base_seed: u64 = self.rng.gen();
+        self.monte_carlo_job = Some(BackgroundJob::spawn("Repeated runs", runs, move |ctx| {
+            let mut final_overlaps = Vec::with_capacity(runs);
+            let mut convergence_times = Vec::with_capacity(runs);
+            let mut energy_curves = Vec::with_capacity(runs);
+            for k in 0..runs {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                let mut rng = StdRng::seed_from_u64(base_seed.wrapping_add(k as u64));
+                let cue = match noise_model {
+                    Some(model) => model.apply(&target, size, size, &mut rng),
+                    None => input.clone(),
+                };
+                // Energy curves are averaged sweep by sweep, so every run keeps its full history
+                let output = run_with(&net, &cue, max_iterations, config, HistoryStorage::Full, &mut rng, |_| !ctx.is_cancelled())?;
+                if let Some(state) = output.states.last() {
+                    final_overlaps.push(overlap(state, &target));
+                }
+                convergence_times.push(output.iterations);
+                energy_curves.push(output.energies.iter().map(|entry| entry.state).collect::<Vec<f64>>());
+                ctx.report(k + 1);
+            }
+            Ok(MonteCarloResult {
+                converged: convergence_times.iter().filter(|&&t| t < max_iterations).count(),
+                final_overlaps,
+                convergence_times,
+                energy_band: mean_std_curves(&energy_curves),
+            })
+        }));
+    }
+    
+    // Collect the result of finished repeated runs
+    fn poll_monte_carlo(&mut self) {
+        let Some(job) = &mut self.monte_carlo_job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result.map_err(|e| e.to_string()),
+            JobStatus::Failed => Err("Simulation thread stopped unexpectedly".to_string()),
+        };
+        self.monte_carlo_job = None;
+        match result {
+            Ok(result) => self.monte_carlo = Some(result),
+            Err(e) => report_error(format!("Repeated Runs Error: {}", e)),
+        }
+    }
+    
+    // Summary, final-overlap and convergence-time histograms and the energy band of
+    // the repeated runs
+    fn show_monte_carlo(&self, ui: &mut egui::Ui) {
+        let Some(result) = &self.monte_carlo else {
+            return;
+        };
+        let runs = result.convergence_times.len();
+        let (overlap_mean, overlap_std) = mean_std(&result.final_overlaps);
+        let times: Vec<f64> = result.convergence_times.iter().map(|&t| t as f64).collect();
+        let (time_mean, time_std) = mean_std(&times);
+        ui.label(format!(
+            "{} runs, {} converged ({:.0}%)   final overlap {:.3} ± {:.3}   iterations {:.1} ± {:.1}",
+            runs,
+            result.converged,
+            100.0 * result.converged as f64 / runs.max(1) as f64,
+            overlap_mean, overlap_std, time_mean, time_std
+        ));
+        
+        let bin_width = 2.0 / OVERLAP_BINS as f64;
+        let overlap_bars: Vec<Bar> = histogram(&result.final_overlaps, OVERLAP_BINS, -1.0, 1.0)
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| Bar::new(-1.0 + (i as f64 + 0.5) * bin_width, count as f64).width(bin_width))
+            .collect();
+        let max_time = result.convergence_times.iter().copied().max().unwrap_or(0);
+        let time_bins = (max_time + 1).min(30);
+        let time_width = (max_time + 1) as f64 / time_bins as f64;
+        let time_bars: Vec<Bar> = histogram(&times, time_bins, 0.0, (max_time + 1) as f64)
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| Bar::new((i as f64 + 0.5) * time_width, count as f64).width(time_width))
+            .collect();
+        
+        let band = |sign: f64| -> Vec<[f64; 2]> {
+            result.energy_band.iter().enumerate().map(|(t, &(m, s))| [t as f64, m + sign * s]).collect()
+        };
+        let plot_width = (ui.available_width() / 3.0 - 8.0).max(120.0);
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Final Overlap");
+                Plot::new("monte_carlo_overlap").width(plot_width).height(160.0).show(ui, |plot_ui| {
+                    plot_ui.bar_chart(BarChart::new(overlap_bars));
+                });
+            });
+            ui.vertical(|ui| {
+                ui.label("Iterations to Converge");
+                Plot::new("monte_carlo_time").width(plot_width).height(160.0).show(ui, |plot_ui| {
+                    plot_ui.bar_chart(BarChart::new(time_bars).color(egui::Color32::from_rgb(200, 140, 60)));
+                });
+            });
+            ui.vertical(|ui| {
+                ui.label("Energy (mean ± σ)");
+                Plot::new("monte_carlo_energy").width(plot_width).height(160.0).show(ui, |plot_ui| {
+                    let grey = egui::Color32::from_gray(140);
+                    plot_ui.line(Line::new(band(1.0)).color(grey).style(egui_plot::LineStyle::dashed_dense()));
+                    plot_ui.line(Line::new(band(-1.0)).color(grey).style(egui_plot::LineStyle::dashed_dense()));
+                    plot_ui.line(Line::new(band(0.0)));
+                });
+            });
+        });
+    }
+    
+    // Sonification settings. The output device is opened when sound is first switched on.
+    fn show_audio_panel(&mut self, ui: &mut egui::Ui) {
+        let toggle = ui.add_enabled(AudioOutput::AVAILABLE, egui::Checkbox::new(&mut self.sonify, "Sonify Playback"))
+            .on_hover_text("Map each step of the played-back run to a tone that falls silent as the network converges")
+            .on_disabled_hover_text(AudioError::Unavailable.to_string());
+        if toggle.changed() && self.sonify && self.audio.is_none() {
+            match AudioOutput::new() {
+                Ok(audio) => self.audio = Some(audio),
+                Err(e) => {
+                    self.sonify = false;
+                    report_error(e.to_string());
+                },
+            }
+        }
+        
+        let settings = &mut self.sonification;
+        ui.horizontal(|ui| {
+            ui.label("Source:");
+            for source in [SonificationSource::EnergyChange, SonificationSource::FlippedNeurons] {
+                ui.radio_value(&mut settings.source, source, source.name());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Pitch (Hz):");
+            ui.add(egui::DragValue::new(&mut settings.low_frequency).speed(5.0).range(40.0..=2000.0));
+            ui.label("to");
+            ui.add(egui::DragValue::new(&mut settings.high_frequency).speed(5.0).range(40.0..=4000.0));
+        });
+        ui.add(egui::Slider::new(&mut settings.volume, 0.0..=1.0).text("Volume"));
+    }
+    
+    // Send the tone for the displayed step to the output, or silence unless the timeline plays
+    fn update_sonification(&self) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        let tone = match (&self.output_states, &self.energy_history) {
+            (Some(states), Some(energies)) if self.sonify && self.timeline.playing => {
+                let activity = match states {
+                    RecordedStates::Full(states) => self.sonification.activity(states, energies),
+                    RecordedStates::Flips(flips) => {
+                        let flipped: Vec<usize> = (0..flips.len()).map(|i| flips.delta(i).map_or(0, Vec::len)).collect();
+                        self.sonification.activity_with_flips(&flipped, energies)
+                    },
+                };
+                let index = self.display_iteration.unwrap_or(0);
+                self.sonification.tone(activity.get(index).copied().unwrap_or(0.0))
+            },
+            _ => Tone::SILENCE,
+        };
+        audio.set_tone(tone);
+    }
+    
+    // Start the degradation demo on the selected pattern, training the network first if needed
+    fn start_demo(&mut self) {
+        if self.network.is_none() && self.train_job.is_none() {
+            self.train_network();
+        }
+        if self.train_job.is_some() {
+            report_info("Training the network first. Start the demo again when it is done.");
+            return;
+        }
+        if self.network.is_none() || self.patterns.is_empty() {
+            report_error("Demo needs a trained network with at least one pattern".to_string());
+            return;
+        }
+        let pattern_index = self.selected_pattern_index_for_input.unwrap_or(0).min(self.patterns.len() - 1);
+        self.selected_pattern_index_for_input = Some(pattern_index);
+        self.demo = Some(DegradationDemo::new(pattern_index));
+        self.forgetting = None;
+        self.plot_tab = PlotTab::Demo;
+        self.timeline.pause();
+    }
+    
+    // Run one demo trial: flip bits of the pattern at the current noise level, let the
+    // network settle as in batch evaluation and show cue and result in the grids
+    fn advance_demo(&mut self) {
+        let (Some(demo), Some(net)) = (&mut self.demo, &self.network) else {
+            return;
+        };
+        let Some(pattern) = self.patterns.get(demo.pattern_index) else {
+            self.demo = None;
+            return;
+        };
+        let Some(noise) = demo.next_level() else {
+            demo.running = false;
+            return;
+        };
+        
+        let cue = flip_bits(pattern, noise, &mut self.rng);
+        let mut state = cue.clone();
+        let sweeps = net.settle(&mut state, EVALUATION_MAX_SWEEPS, &mut self.rng);
+        demo.record(state == *pattern);
+        
+        self.input_state = cue;
+        self.output_states = Some(RecordedStates::Full(SimulationHistory::new(state)));
+        self.iterations = Some(sweeps);
+        self.micro_steps = false;
+        self.display_iteration = Some(0);
+        self.energy_history = None;
+        self.comparison = None;
+        self.run_cancelled = false;
+    }
+    
+    // Outcome of the current demo trial and recall probability against noise so far
+    fn show_demo(&mut self, ui: &mut egui::Ui, height: f32) {
+        let Some(demo) = &mut self.demo else {
+            ui.label("(Press Demo to recall a pattern from increasingly noisy cues)");
+            return;
+        };
+        ui.horizontal(|ui| {
+            if let Some(&(noise, _, trials)) = demo.levels.last() {
+                ui.label(format!("Noise {:.2}, trial {} / {}", noise, trials, DEMO_TRIALS));
+            }
+            match demo.last_success {
+                Some(true) => ui.colored_label(egui::Color32::GREEN, "✔ Recalled"),
+                Some(false) => ui.colored_label(egui::Color32::RED, "✘ Failed"),
+                None => ui.label(""),
+            };
+            if demo.is_finished() {
+                ui.label("Done.");
+            } else if ui.button(if demo.running { "Pause" } else { "Resume" }).clicked() {
+                demo.running = !demo.running;
+            }
+        });
+        
+        let curve: Vec<[f64; 2]> = demo
+            .levels
+            .iter()
+            .filter(|l| l.2 > 0)
+            .map(|&(noise, hits, trials)| [noise, hits as f64 / trials as f64])
+            .collect();
+        Plot::new("demo_plot")
+            .height(height - 30.0)
+            .include_x(0.0)
+            .include_x(1.0)
+            .include_y(0.0)
+            .include_y(1.0)
+            .x_axis_label("Fraction of flipped bits")
+            .y_axis_label("Recall probability")
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(curve.clone()));
+                plot_ui.points(Points::new(curve).radius(3.0));
+            });
+    }
+    
+    // Start the forgetting demo on a fresh network, independent of the trained one
+    fn start_forgetting_demo(&mut self) {
+        if self.all_generated_patterns.is_empty() {
+            report_error("Forgetting demo needs the letter patterns".to_string());
+            return;
+        }
+        let network = match HopfieldNetwork::try_new(self.current_grid_size * self.current_grid_size) {
+            Ok(network) => network,
+            Err(e) => {
+                report_error(e);
+                return;
+            }
+        };
+        self.forgetting = Some(ForgettingDemo {
+            network,
+            learned: Vec::new(),
+            curve: Vec::new(),
+            running: true,
+            time_to_next: 0.0,
+        });
+        self.demo = None;
+        self.plot_tab = PlotTab::Forgetting;
+        self.timeline.pause();
+    }
+    
+    // Learn the next letter, then recall the first letter from noisy cues and show the
+    // last trial in the grids
+    fn advance_forgetting_demo(&mut self) {
+        let Some(demo) = &mut self.forgetting else {
+            return;
+        };
+        let Some((letter, pattern)) = self.all_generated_patterns.get(demo.learned.len()) else {
+            demo.running = false;
+            return;
+        };
+        // 1/N keeps the fields of order one however many letters are learned
+        if let Err(e) = demo.network.learn(pattern, 1.0 / pattern.len() as f64) {
+            report_error(e);
+            self.forgetting = None;
+            return;
+        }
+        demo.learned.push(*letter);
+        
+        let first = &self.all_generated_patterns[0].1;
+        let mut overlap_sum = 0.0;
+        let mut recalls = 0;
+        let mut last_trial = None;
+        for _ in 0..FORGETTING_TRIALS {
+            let cue = flip_bits(first, FORGETTING_CUE_NOISE, &mut self.rng);
+            let mut state = cue.clone();
+            let sweeps = demo.network.settle(&mut state, EVALUATION_MAX_SWEEPS, &mut self.rng);
+            overlap_sum += overlap(&state, first);
+            recalls += (state == *first) as usize;
+            last_trial = Some((cue, state, sweeps));
+        }
+        let trials = FORGETTING_TRIALS as f64;
+        demo.curve.push((demo.learned.len(), overlap_sum / trials, recalls as f64 / trials));
+        
+        if let Some((cue, state, sweeps)) = last_trial {
+            self.input_state = cue;
+            self.output_states = Some(RecordedStates::Full(SimulationHistory::new(state)));
+            self.iterations = Some(sweeps);
+            self.micro_steps = false;
+            self.display_iteration = Some(0);
+            self.energy_history = None;
+            self.comparison = None;
+            self.run_cancelled = false;
+        }
+    }
+    
+    // Recall of the first letter against the number of letters learned
+    fn show_forgetting(&mut self, ui: &mut egui::Ui, height: f32) {
+        let Some(demo) = &mut self.forgetting else {
+            ui.label("(Press Forgetting Demo to learn the letters one by one and test recall of the first)");
+            return;
+        };
+        let first = self.all_generated_patterns.first().map_or('?', |(c, _)| *c);
+        let finished = demo.learned.len() == self.all_generated_patterns.len();
+        ui.horizontal(|ui| {
+            ui.label(format!("Learned: {}", demo.learned.iter().collect::<String>()));
+            if let Some(&(_, mean_overlap, recall)) = demo.curve.last() {
+                ui.label(format!("Recall of {}: overlap {:.2}, {:.0}% perfect", first, mean_overlap, recall * 100.0));
+            }
+            if finished {
+                ui.label("Done.");
+            } else if ui.button(if demo.running { "Pause" } else { "Resume" }).clicked() {
+                demo.running = !demo.running;
+            }
+        });
+        
+        let overlaps: Vec<[f64; 2]> = demo.curve.iter().map(|&(letters, m, _)| [letters as f64, m]).collect();
+        let recalls: Vec<[f64; 2]> = demo.curve.iter().map(|&(letters, _, r)| [letters as f64, r]).collect();
+        let capacity = HEBBIAN_CRITICAL_LOAD * demo.network.size() as f64;
+        Plot::new("forgetting_plot")
+            .height(height - 30.0)
+            .legend(Legend::default())
+            .include_x(0.0)
+            .include_x(self.all_generated_patterns.len() as f64)
+            .include_y(-1.0)
+            .include_y(1.0)
+            .x_axis_label("Letters learned")
+            .y_axis_label(format!("Recall of {}", first))
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(overlaps.clone()).name("Mean overlap"));
+                plot_ui.points(Points::new(overlaps).radius(3.0));
+                plot_ui.line(Line::new(recalls).name("Perfect recall probability"));
+                plot_ui.vline(VLine::new(capacity).color(egui::Color32::GRAY).name("Capacity 0.138 N"));
+            });
+    }
+    
+    // Leading eigenvalues and eigenvectors of the weight matrix, and how much of each
+    // stored pattern the shown eigenvectors capture
+    fn show_eigenmodes(&mut self, ui: &mut egui::Ui, height: f32) {
+        let Some(net) = &self.network else {
+            ui.label("(Train the network to decompose its weights)");
+            return;
+        };
+        ui.horizontal(|ui| {
+            if ui.button("Decompose Weights").clicked() {
+                self.weight_spectrum = Some(WeightSpectrum::new(net));
+            }
+            ui.label("Modes Shown:");
+            ui.add(egui::DragValue::new(&mut self.eigen_modes).speed(0.1).range(1..=8));
+        });
+        let Some(spectrum) = &self.weight_spectrum else {
+            return;
+        };
+        if spectrum.symmetrized {
+            ui.small("Asymmetric weights: showing the eigenmodes of (W + Wᵀ) / 2");
+        }
+        
+        let size = self.current_grid_size;
+        let k = self.eigen_modes.min(spectrum.eigenvalues.len());
+        ui.horizontal_wrapped(|ui| {
+            for (m, (value, vector)) in spectrum.eigenvalues.iter().zip(&spectrum.eigenvectors).take(k).enumerate() {
+                ui.vertical(|ui| {
+                    ui.label(format!("v{}  λ = {:.2}", m + 1, value));
+                    draw_signed_grid(ui, vector, size, size, 3.0);
+                });
+            }
+        });
+        let captured: Vec<String> = self
+            .patterns
+            .iter()
+            .zip(&self.trained_chars)
+            .map(|(pattern, c)| format!("'{}' {:.0}%", c, 100.0 * spectrum.captured_fraction(pattern, k)))
+            .collect();
+        ui.label(format!("Captured by the first {} modes: {}", k, captured.join(", ")))
+            .on_hover_text("Fraction of each stored pattern lying in the span of the shown eigenvectors");
+        
+        let values: Vec<[f64; 2]> = spectrum.eigenvalues.iter().enumerate().map(|(i, &l)| [(i + 1) as f64, l]).collect();
+        let (leading, rest) = values.split_at(k);
+        Plot::new("eigenvalue_plot")
+            .height((height - 3.0 * size as f32 - 60.0).max(120.0))
+            .legend(Legend::default())
+            .x_axis_label("Index")
+            .y_axis_label("Eigenvalue")
+            .show(ui, |plot_ui| {
+                plot_ui.points(Points::new(rest.to_vec()).radius(1.5).color(egui::Color32::GRAY).name("Eigenvalues"));
+                plot_ui.points(Points::new(leading.to_vec()).radius(3.5).name("Shown modes"));
+            });
+    }
+    
+    // Trajectory of the output in the space of overlaps with the two or three stored patterns
+    fn show_trajectory(&self, ui: &mut egui::Ui, height: f32) {
+        let dims = self.patterns.len();
+        if dims != 2 && dims != 3 {
+            ui.label("(Overlap trajectories need exactly two or three trained patterns)");
+            return;
+        }
+        let overlaps = |state: &[f64]| -> Vec<f64> { self.patterns.iter().map(|p| overlap(state, p)).collect() };
+        // Two patterns are plotted directly, three in an isometric view of the cube
+        let to_plot = |m: &[f64]| -> [f64; 2] {
+            if dims == 2 { [m[0], m[1]] } else { isometric([m[0], m[1], m[2]]) }
+        };
+        let char_at = |k: usize| self.trained_chars.get(k).copied().unwrap_or('?');
+        
+        let trajectory: Vec<[f64; 2]> = self.output_states.as_ref().map_or(Vec::new(), |states| {
+            states.downsample(TRAJECTORY_POINTS).into_iter().map(|e| to_plot(&overlaps(&e.state))).collect()
+        });
+        if trajectory.is_empty() {
+            ui.label("(Run network to see its trajectory)");
+        }
+        
+        let mut plot = Plot::new("trajectory_plot").height(height).data_aspect(1.0);
+        if dims == 2 {
+            plot = plot
+                .x_axis_label(format!("m('{}')", char_at(0)))
+                .y_axis_label(format!("m('{}')", char_at(1)));
+        } else {
+            plot = plot.show_axes(false).show_grid(false);
+        }
+        plot.show(ui, |plot_ui| {
+            // Boundary of the overlap space
+            let corners: Vec<Vec<f64>> = (0..1 << dims)
+                .map(|bits: usize| (0..dims).map(|d| if bits >> d & 1 == 1 { 1.0 } else { -1.0 }).collect())
+                .collect();
+            for (a, corner) in corners.iter().enumerate() {
+                for d in 0..dims {
+                    let b = a | 1 << d;
+                    if b != a {
+                        let edge = vec![to_plot(corner), to_plot(&corners[b])];
+                        plot_ui.line(Line::new(edge).color(egui::Color32::DARK_GRAY));
+                    }
+                }
+            }
+            if dims == 3 {
+                for k in 0..3 {
+                    let mut end = [0.0; 3];
+                    end[k] = 1.15;
+                    let [x, y] = isometric(end);
+                    plot_ui.text(Text::new(PlotPoint::new(x, y), format!("m('{}')", char_at(k))));
+                }
+            }
+            
+            // Each stored pattern and its inverse are attractors
+            for (k, pattern) in self.patterns.iter().enumerate() {
+                let m = overlaps(pattern);
+                let inverse: Vec<f64> = m.iter().map(|x| -x).collect();
+                for (position, label) in [(to_plot(&m), format!("{}", char_at(k))), (to_plot(&inverse), format!("-{}", char_at(k)))] {
+                    plot_ui.points(Points::new(position).shape(MarkerShape::Diamond).radius(6.0).color(egui::Color32::GOLD));
+                    plot_ui.text(Text::new(PlotPoint::new(position[0], position[1]), format!("  {}", label))
+                        .anchor(egui::Align2::LEFT_BOTTOM));
+                }
+            }
+            
+            if let (Some(&start), Some(&end)) = (trajectory.first(), trajectory.last()) {
+                plot_ui.line(Line::new(trajectory.clone()).color(egui::Color32::LIGHT_BLUE).name("Trajectory"));
+                plot_ui.points(Points::new(start).shape(MarkerShape::Circle).radius(4.0).color(egui::Color32::GREEN).name("Start"));
+                plot_ui.points(Points::new(end).shape(MarkerShape::Circle).radius(4.0).color(egui::Color32::RED).name("End"));
+            }
+        });
+    }
+    
+    // Evaluate recall of all trained patterns over the configured noise levels
+    // Measure m(T) in the background, cued with the current input and scored against the
+    // selected pattern
+    fn start_temperature_sweep(&mut self) {
+        let (Some(net), Some(target)) = (
+            &self.network,
+            self.selected_pattern_index_for_input.and_then(|i| self.patterns.get(i)),
+        ) else {
+            report_error("Train the network and select a target pattern first".to_string());
+            return;
+        };
+        let (low, high) = self.sweep_beta_range;
+        if !(low > 0.0 && high > low) {
+            report_error("The beta range must satisfy 0 < min < max".to_string());
+            return;
+        }
+        
+        // Log spacing resolves the transition, whose position scales with the weights
+        let points = self.sweep_points.max(2);
+        let betas: Vec<f64> = (0..points)
+            .map(|k| low * (high / low).powf(k as f64 / (points - 1) as f64))
+            .collect();
+        let net = net.clone();
+        let (cue, target) = (self.input_state.clone(), target.clone());
+        let (trials, burn_in, measure) = (self.sweep_trials, self.sweep_burn_in, self.sweep_measure);
+        let order = self.with_grid_width(self.current_config()).sweep_order;
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        self.sweep_job = Some(BackgroundJob::spawn("Sweeping temperature", points, move |ctx| {
+            net.temperature_sweep(&cue, &target, &betas, trials, burn_in, measure, order, &mut rng, |done| {
+                ctx.report(done);
+                !ctx.is_cancelled()
+            })
+        }));
+        self.plot_tab = PlotTab::Temperature;
+    }
+    
+    // Run from the input state for `record_iterations` and stream every `stride`-th state
+    // with the chosen metrics to `record_path`, without keeping a history
+    fn start_recording(&mut self) {
+        let size = self.current_grid_size;
+        let Some(net) = self.network.clone().filter(|net| net.size() == size * size) else {
+            report_error("Cannot record: Network size does not match current grid size. Retrain network.".to_string());
+            return;
+        };
+        let target = self.selected_pattern_index_for_input.and_then(|i| self.patterns.get(i)).cloned();
+        if self.record_overlap && target.is_none() {
+            report_error("Select a target pattern to record the overlap".to_string());
+            return;
+        }
+        
+        let mut config = self.record_config.clone();
+        config.metrics = [("energy", self.record_energy), ("overlap", self.record_overlap)]
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let mut recorder = match Recorder::create(&self.record_path, config, size * size) {
+            Ok(recorder) => recorder,
+            Err(e) => {
+                report_error(format!("Recording Error: {}", e));
+                return;
+            }
+        };
+        
+        let input = self.input_state.clone();
+        let iterations = self.record_iterations;
+        let run_config = self.with_grid_width(self.current_config());
+        let (record_energy, record_overlap) = (self.record_energy, self.record_overlap);
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        self.record_status = None;
+        self.record_job = Some(BackgroundJob::spawn("Recording run", iterations, move |ctx| {
+            let mut failure = None;
+            let mut visit = |iteration: usize, state: &[f64]| {
+                ctx.report(iteration);
+                if recorder.is_due(iteration) {
+                    let mut metrics = Vec::with_capacity(2);
+                    if record_energy {
+                        metrics.push(net.energy(state).unwrap_or(f64::NAN));
+                    }
+                    if let Some(target) = target.as_ref().filter(|_| record_overlap) {
+                        metrics.push(overlap(state, target));
+                    }
+                    if let Err(e) = recorder.record(iteration, state, &metrics) {
+                        failure = Some(e.to_string());
+                    }
+                }
+                failure.is_none() && !ctx.is_cancelled()
+            };
+            let run = match run_config.update_mode {
+                UpdateMode::Synchronous => net.run_streamed(&input, iterations, run_config.beta, &mut rng, &mut visit),
+                UpdateMode::Asynchronous => {
+                    net.run_async_streamed(&input, iterations, run_config.beta, run_config.sweep_order, &mut rng, &mut visit)
+                }
+            };
+            run.map_err(|e| e.to_string())?;
+            if let Some(e) = failure {
+                return Err(e);
+            }
+            recorder.finish().map_err(|e| e.to_string())
+        }));
+    }
+    
+    // Report the outcome of a finished recording
+    fn poll_recording(&mut self) {
+        let Some(job) = &mut self.record_job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result,
+            JobStatus::Failed => Err("Recording thread stopped unexpectedly".to_string()),
+        };
+        self.record_job = None;
+        match result {
+            Ok(rows) => self.record_status = Some(format!("Wrote {} records to {}", rows, self.record_path)),
+            Err(e) => report_error(format!("Recording Error: {}", e)),
+        }
+    }
+    
+    // Collect the curve of a finished temperature sweep
+    fn poll_temperature_sweep(&mut self) {
+        let Some(job) = &mut self.sweep_job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result.map_err(|e| e.to_string()),
+            JobStatus::Failed => Err("Temperature sweep thread stopped unexpectedly".to_string()),
+        };
+        self.sweep_job = None;
+        match result {
+            Ok(curve) => {
+                self.temperature_curve = Some(curve);
+            }
+            Err(e) => report_error(format!("Temperature Sweep Error: {}", e)),
+        }
+    }
+    
+    // Steady-state overlap against temperature T = 1/beta, with a band of one standard deviation
+    fn show_temperature_curve(&self, ui: &mut egui::Ui, height: f32) {
+        let Some(curve) = self.temperature_curve.as_ref().filter(|c| !c.is_empty()) else {
+            ui.label("(Run a temperature sweep to plot the retrieval transition)");
+            return;
+        };
+        let point_at = |p: &TemperaturePoint, offset: f64| [1.0 / p.beta, p.mean_overlap + offset];
+        let mean: Vec<[f64; 2]> = curve.iter().map(|p| point_at(p, 0.0)).collect();
+        let upper: Vec<[f64; 2]> = curve.iter().map(|p| point_at(p, p.overlap_std)).collect();
+        let lower: Vec<[f64; 2]> = curve.iter().map(|p| point_at(p, -p.overlap_std)).collect();
+        
+        Plot::new("temperature_plot")
+            .height(height)
+            .legend(Legend::default())
+            .x_axis_label("Temperature T = 1/β")
+            .y_axis_label("Overlap m")
+            .include_y(0.0)
+            .include_y(1.0)
+            .show(ui, |plot_ui| {
+                let band = egui::Color32::from_gray(140);
+                plot_ui.line(Line::new(upper).color(band).style(egui_plot::LineStyle::dashed_dense()).name("m ± σ"));
+                plot_ui.line(Line::new(lower).color(band).style(egui_plot::LineStyle::dashed_dense()).name("m ± σ"));
+                plot_ui.line(Line::new(mean.clone()).name("m(T)"));
+                plot_ui.points(Points::new(mean).radius(3.0).name("m(T)"));
+            });
+    }
+    
+    // Write the last temperature sweep as CSV
+    fn export_temperature_curve(&mut self) {
+        let Some(curve) = &self.temperature_curve else {
+            return;
+        };
+        let rows: Vec<Vec<f64>> = curve
+            .iter()
+            .map(|p| vec![p.beta, 1.0 / p.beta, p.mean_overlap, p.overlap_std])
+            .collect();
+        match write_csv(&self.temperature_csv_path, &["beta", "temperature", "mean_overlap", "overlap_std"], &rows) {
+            Ok(()) => {
+                report_info(format!("Saved temperature sweep to {}", self.temperature_csv_path));
+            }
+            Err(e) => report_error(format!("Failed to save temperature sweep: {}", e)),
+        }
+    }
+    
+    fn evaluate_recall(&mut self) {
+        let noise_levels: Result<Vec<f64>, _> = self.eval_noise_levels
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse::<f64>)
+            .collect();
+        let noise_levels = match noise_levels {
+            Ok(levels) if !levels.is_empty() && levels.iter().all(|p| (0.0..=1.0).contains(p)) => levels,
+            _ => {
+                report_error(
+                    "Noise levels must be a comma-separated list of values between 0.0 and 1.0".to_string()
+                );
+                return;
+            }
+        };
+        
+        if let Some(net) = &self.network {
+            match net.evaluate(&self.patterns, &noise_levels, self.eval_trials, &mut self.rng) {
+                Ok(results) => {
+                    self.evaluation = Some(results);
+                }
+                Err(e) => report_error(format!("Evaluation Error: {}", e)),
+            }
+        }
+    }
+    
+    // Evaluate recall on a grid of noise levels on a worker thread, one level at a time
+    fn start_robustness_map(&mut self) {
+        let Some(net) = self.network.clone() else {
+            return;
+        };
+        let patterns = self.patterns.clone();
+        let levels = RobustnessMap::noise_grid(self.robustness_max_noise, self.robustness_steps);
+        let trials = self.eval_trials;
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        self.robustness_job = Some(BackgroundJob::spawn("Robustness map", levels.len(), move |ctx| {
+            let mut stats = Vec::with_capacity(levels.len() * patterns.len());
+            for (k, &level) in levels.iter().enumerate() {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                stats.extend(net.evaluate(&patterns, &[level], trials, &mut rng)?);
+                ctx.report(k + 1);
+            }
+            Ok(RobustnessMap::from_stats(&patterns, &levels, &stats))
+        }));
+    }
+    
+    fn poll_robustness_map(&mut self) {
+        let Some(job) = &mut self.robustness_job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result.map_err(|e| e.to_string()),
+            JobStatus::Failed => Err("Evaluation thread stopped unexpectedly".to_string()),
+        };
+        self.robustness_job = None;
+        match result {
+            Ok(map) => self.robustness = Some(map),
+            Err(e) => report_error(format!("Robustness Map Error: {}", e)),
+        }
+    }
+    
+    // Pattern × noise level heatmap of recall accuracy, with each pattern's largest overlap
+    fn show_robustness_map(&self, ui: &mut egui::Ui) {
+        let Some(map) = &self.robustness else {
+            ui.label("(Map robustness to compare patterns)");
+            return;
+        };
+        let label = |p: usize| self.trained_chars.get(p).map_or_else(|| p.to_string(), |c| c.to_string());
+        egui::Grid::new("robustness_grid")
+            .min_col_width(30.0)
+            .spacing([5.0, 5.0])
+            .show(ui, |ui| {
+                ui.label("");
+                for noise in &map.noise_levels {
+                    ui.label(format!("{:.2}", noise));
+                }
+                ui.label("max |m|").on_hover_text("Largest overlap with another stored pattern");
+                ui.label("p½").on_hover_text("Noise level at which recall accuracy drops below 50%");
+                ui.end_row();
+                for (p, row) in map.accuracy.iter().enumerate() {
+                    ui.label(label(p));
+                    for &accuracy in row {
+                        color_cell(ui, Colormap::Viridis.map(accuracy, 0.0, 1.0), &format!("{:.0}", accuracy * 100.0));
+                    }
+                    ui.label(format!("{:.2}", map.max_overlaps[p]));
+                    ui.label(map.critical_noise(p).map_or_else(|| "-".to_string(), |noise| format!("{:.2}", noise)));
+                    ui.end_row();
+                }
+            });
+        legend(ui, Colormap::Viridis, 0.0, 100.0, "Accuracy (%):");
+        let fragile = map.fragile();
+        if fragile.is_empty() {
+            ui.label("No pattern is markedly less robust than the others.");
+        } else {
+            let names: Vec<String> = fragile.iter().map(|&p| label(p)).collect();
+            ui.colored_label(egui::Color32::LIGHT_RED, format!("Fragile: {}", names.join(", ")))
+                .on_hover_text("Mean accuracy well below the average of all patterns; compare their overlaps above");
+        }
+    }
+    
+    // Replace the network by a copy of its trained weights pruned to `prune_sparsity`
+    // and evaluate recall right away
+    fn prune_network(&mut self) {
+        let Some(trained) = self.trained_weights() else {
+            return;
+        };
+        let mut net = trained.clone();
+        net.prune_by_magnitude(self.prune_sparsity);
+        match recall_accuracy(&net, &self.patterns, self.compression_noise, self.eval_trials, &mut self.rng) {
+            Ok(accuracy) => self.pruning_points.push(PruningPoint { sparsity: net.sparsity(), accuracy }),
+            Err(e) => {
+                report_error(format!("Evaluation Error: {}", e));
+                return;
+            }
+        }
+        self.unpruned = Some(trained);
+        self.set_weights(net);
+        self.plot_tab = PlotTab::Pruning;
+    }
+    
+    fn restore_weights(&mut self) {
+        if let Some(trained) = self.unpruned.take() {
+            self.set_weights(trained);
+        }
+    }
+    
+    // Full-precision weights before any pruning
+    fn trained_weights(&self) -> Option<HopfieldNetwork> {
+        self.unpruned.clone().or_else(|| self.full_precision.clone()).or_else(|| self.network.clone())
+    }
+    
+    // Swap in new full-precision weights, quantized if quantization is on
+    fn set_weights(&mut self, net: HopfieldNetwork) {
+        self.full_precision = None;
+        self.quantization_accuracy = None;
+        self.network = Some(net);
+        if self.quantize {
+            self.apply_quantization();
+        }
+        self.weights_changed();
+    }
+    
+    // Results computed from the old weights no longer apply
+    fn weights_changed(&mut self) {
+        self.evaluation = None;
+        self.robustness = None;
+        self.landscape = None;
+        self.weight_spectrum = None;
+        self.energy_surface = None;
+    }
+    
+    // Quantize the full-precision weights and compare recall of both networks on the
+    // same cues
+    fn apply_quantization(&mut self) {
+        let Some(full) = self.full_precision.take().or_else(|| self.network.take()) else {
+            return;
+        };
+        let mut quantized = full.clone();
+        quantized.quantize(self.quantization);
+        let seed: u64 = self.rng.gen();
+        let (noise, trials) = (self.compression_noise, self.eval_trials);
+        let full_accuracy = recall_accuracy(&full, &self.patterns, noise, trials, &mut StdRng::seed_from_u64(seed));
+        let quantized_accuracy = recall_accuracy(&quantized, &self.patterns, noise, trials, &mut StdRng::seed_from_u64(seed));
+        self.quantization_accuracy = match (full_accuracy, quantized_accuracy) {
+            (Ok(full), Ok(quantized)) => Some((full, quantized)),
+            (Err(e), _) | (_, Err(e)) => {
+                report_error(format!("Evaluation Error: {}", e));
+                None
+            }
+        };
+        self.full_precision = Some(full);
+        self.network = Some(quantized);
+        self.weights_changed();
+    }
+    
+    fn remove_quantization(&mut self) {
+        if let Some(full) = self.full_precision.take() {
+            self.network = Some(full);
+            self.quantization_accuracy = None;
+            self.weights_changed();
+        }
+    }
+    
+    // Prune copies of the trained weights to a range of sparsities on a worker thread
+    fn start_pruning_sweep(&mut self) {
+        let Some(trained) = self.trained_weights() else {
+            return;
+        };
+        let patterns = self.patterns.clone();
+        let (noise, trials) = (self.compression_noise, self.eval_trials);
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        self.pruning_job = Some(BackgroundJob::spawn("Pruning sweep", PRUNING_LEVELS, move |ctx| {
+            let mut curve = Vec::with_capacity(PRUNING_LEVELS);
+            for k in 0..PRUNING_LEVELS {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                let mut net = trained.clone();
+                net.prune_by_magnitude(PRUNING_MAX_SPARSITY * k as f64 / (PRUNING_LEVELS - 1) as f64);
+                let accuracy = recall_accuracy(&net, &patterns, noise, trials, &mut rng)?;
+                curve.push(PruningPoint { sparsity: net.sparsity(), accuracy });
+                ctx.report(k + 1);
+            }
+            Ok(curve)
+        }));
+        self.plot_tab = PlotTab::Pruning;
+    }
+    
+    fn poll_pruning_sweep(&mut self) {
+        let Some(job) = &mut self.pruning_job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result.map_err(|e| e.to_string()),
+            JobStatus::Failed => Err("Evaluation thread stopped unexpectedly".to_string()),
+        };
+        self.pruning_job = None;
+        match result {
+            Ok(curve) => self.pruning_curve = Some(curve),
+            Err(e) => report_error(format!("Pruning Sweep Error: {}", e)),
+        }
+    }
+    
+    // Recall accuracy against sparsity for the sweep and every manual pruning
+    fn show_pruning(&self, ui: &mut egui::Ui, height: f32) {
+        match (&self.network, &self.unpruned) {
+            (Some(net), Some(_)) => ui.label(format!("Pruned network in use: {:.1}% of the weights are zero", net.sparsity() * 100.0)),
+            (Some(net), None) => ui.label(format!("Trained weights in use: {:.1}% are zero", net.sparsity() * 100.0)),
+            (None, _) => ui.label("(Train network first)"),
+        };
+        if self.pruning_curve.is_none() && self.pruning_points.is_empty() {
+            ui.label("(Prune the weights or sweep the sparsity to plot recall accuracy)");
+            return;
+        }
+        let to_plot = |points: &[PruningPoint]| -> Vec<[f64; 2]> {
+            points.iter().map(|p| [p.sparsity * 100.0, p.accuracy * 100.0]).collect()
+        };
+        Plot::new("pruning_plot")
+            .height(height)
+            .legend(Legend::default())
+            .include_x(0.0)
+            .include_x(100.0)
+            .include_y(0.0)
+            .include_y(100.0)
+            .x_axis_label("Sparsity (%)")
+            .y_axis_label(format!("Accuracy at noise {:.2} (%)", self.compression_noise))
+            .show(ui, |plot_ui| {
+                if let Some(curve) = &self.pruning_curve {
+                    plot_ui.line(Line::new(to_plot(curve)).name("Sweep"));
+                }
+                if !self.pruning_points.is_empty() {
+                    plot_ui.points(
+                        Points::new(to_plot(&self.pruning_points))
+                            .radius(4.0)
+                            .shape(MarkerShape::Diamond)
+                            .name("Pruned"),
+                    );
+                }
+            });
+    }
+    
+    // Benchmark every training rule at increasing load on a worker thread
+    fn start_rule_comparison(&mut self) {
+        let counts = comparison_counts();
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let total = counts.len() * COMPARED_RULES.len();
+        self.rule_comparison_job = Some(BackgroundJob::spawn("Rule comparison (networks)", total, move |ctx| {
+            compare_rules(COMPARISON_NEURONS, &COMPARED_RULES, &counts, COMPARISON_NOISE, COMPARISON_TRIALS, &mut rng, |done| {
+                ctx.report(done);
+                !ctx.is_cancelled()
+            })
+        }));
+        self.plot_tab = PlotTab::Rules;
+    }
+    
+    fn poll_rule_comparison(&mut self) {
+        let Some(job) = &mut self.rule_comparison_job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result.map_err(|e| e.to_string()),
+            JobStatus::Failed => Err("Benchmark thread stopped unexpectedly".to_string()),
+        };
+        self.rule_comparison_job = None;
+        match result {
+            Ok(mut points) => {
+                // A cancelled run can stop halfway through a load; keep whole loads only
+                let loads = points.len() / COMPARED_RULES.len();
+                points.truncate(loads * COMPARED_RULES.len());
+                self.rule_comparison_cancelled = loads < comparison_counts().len();
+                if self.rule_comparison_cancelled {
+                    report_info(format!("Rule comparison cancelled after {} of {} loads", loads, comparison_counts().len()));
+                }
+                self.rule_comparison = Some(points);
+            },
+            Err(e) => report_error(format!("Rule Comparison Error: {}", e)),
+        }
+    }
+    
+    // Recall accuracy against load, one line per training rule
+    fn show_rule_comparison(&self, ui: &mut egui::Ui, height: f32) {
+        let Some(points) = &self.rule_comparison else {
+            ui.label("(Press Compare Rules to benchmark the capacity of each training rule)");
+            return;
+        };
+        ui.label(format!(
+            "{} neurons, random patterns, cues with {:.0}% flipped bits{}",
+            COMPARISON_NEURONS,
+            COMPARISON_NOISE * 100.0,
+            if self.rule_comparison_cancelled { " (cancelled, partial result)" } else { "" }
+        ));
+        Plot::new("rule_comparison_plot")
+            .height(height - 20.0)
+            .legend(Legend::default())
+            .include_x(0.0)
+            .include_x(COMPARISON_MAX_LOAD)
+            .include_y(0.0)
+            .include_y(100.0)
+            .x_axis_label("Load α = P/N")
+            .y_axis_label("Recall accuracy (%)")
+            .show(ui, |plot_ui| {
+                for rule in COMPARED_RULES {
+                    let curve: Vec<[f64; 2]> = points
+                        .iter()
+                        .filter(|p| p.rule == rule)
+                        .map(|p| [p.load, p.accuracy * 100.0])
+                        .collect();
+                    let name = format!("{:?}", rule);
+                    plot_ui.line(Line::new(curve.clone()).name(&name));
+                    plot_ui.points(Points::new(curve).radius(3.0).name(&name));
+                }
+            });
+    }
+    
+    // Write the last rule comparison as CSV, one row per load with a column per rule
+    fn export_rule_comparison(&mut self) {
+        let Some(points) = &self.rule_comparison else {
+            return;
+        };
+        let rows: Vec<Vec<f64>> = points
+            .chunks(COMPARED_RULES.len())
+            .map(|chunk| {
+                let mut row = vec![chunk[0].patterns as f64, chunk[0].load];
+                row.extend(chunk.iter().map(|p| p.accuracy));
+                row
+            })
+            .collect();
+        let rule_columns: Vec<String> = COMPARED_RULES.iter().map(|rule| format!("{:?}", rule).to_lowercase()).collect();
+        let mut header = vec!["patterns", "load"];
+        header.extend(rule_columns.iter().map(String::as_str));
+        match write_csv(&self.rule_comparison_csv_path, &header, &rows) {
+            Ok(()) => {
+                report_info(format!("Saved rule comparison to {}", self.rule_comparison_csv_path));
+            }
+            Err(e) => report_error(format!("Failed to save rule comparison: {}", e)),
+        }
+    }
+    
+    // Results table and grouped accuracy chart of the last batch evaluation
+    fn show_evaluation(&self, ui: &mut egui::Ui) {
+        let Some(results) = &self.evaluation else {
+            return;
+        };
+        let mut noise_levels: Vec<f64> = Vec::new();
+        for stats in results {
+            if !noise_levels.contains(&stats.noise_level) {
+                noise_levels.push(stats.noise_level);
+            }
+        }
+        let pattern_label = |index: usize| {
+            self.trained_chars.get(index).map_or_else(|| index.to_string(), |c| c.to_string())
+        };
+        
+        ui.label("Accuracy / mean Hamming distance / mean sweeps to settle");
+        egui::Grid::new("evaluation_table").striped(true).show(ui, |ui| {
+            ui.label("Pattern");
+            for noise in &noise_levels {
+                ui.label(format!("noise {:.2}", noise));
+            }
+            ui.end_row();
+            
+            let num_patterns = results.iter().map(|s| s.pattern_index + 1).max().unwrap_or(0);
+            for pattern_index in 0..num_patterns {
+                ui.label(pattern_label(pattern_index));
+                for noise in &noise_levels {
+                    match results.iter().find(|s| s.pattern_index == pattern_index && s.noise_level == *noise) {
+                        Some(stats) => ui.label(format!(
+                            "{:.0}% / {:.1} / {:.1}",
+                            stats.accuracy * 100.0, stats.mean_hamming_distance, stats.mean_convergence_time
+                        )),
+                        None => ui.label("-"),
+                    };
+                }
+                ui.end_row();
+            }
+        });
+        
+        // One group of bars per pattern, one bar per noise level
+        let bar_width = 0.8 / noise_levels.len().max(1) as f64;
+        let charts: Vec<BarChart> = noise_levels
+            .iter()
+            .enumerate()
+            .map(|(k, noise)| {
+                let bars = results
+                    .iter()
+                    .filter(|s| s.noise_level == *noise)
+                    .map(|s| {
+                        let x = s.pattern_index as f64 - 0.4 + bar_width * (k as f64 + 0.5);
+                        Bar::new(x, s.accuracy * 100.0)
+                            .width(bar_width)
+                            .name(pattern_label(s.pattern_index))
+                    })
+                    .collect();
+                BarChart::new(bars).name(format!("noise {:.2}", noise))
+            })
+            .collect();
+        Plot::new("evaluation_chart")
+            .legend(Legend::default())
+            .height(180.0)
+            .include_y(0.0)
+            .include_y(100.0)
+            .y_axis_label("Accuracy (%)")
+            .show(ui, |plot_ui| {
+                for chart in charts {
+                    plot_ui.bar_chart(chart);
+                }
+            });
+    }
+    
+    // Helper function to calculate histogram data for off-diagonal overlaps
+    fn calculate_overlap_histogram(overlap_matrix: &Option<Vec<Vec<f64>>>) -> Option<Vec<egui_plot::Bar>> {
+        let matrix = overlap_matrix.as_ref()?; // Return None if overlap_matrix is None
+
+        if matrix.is_empty() {
+            return None;
+        }
+
+        let num_patterns = matrix.len();
+        let mut off_diagonal_overlaps = Vec::new();
+
+        for i in 0..num_patterns {
+            for j in (i + 1)..num_patterns { // Only upper triangle (excluding diagonal)
+                // We care about the magnitude of correlation
+                off_diagonal_overlaps.push(matrix[i][j].abs()); 
+            }
         }
 
         if off_diagonal_overlaps.is_empty() {
@@ -423,20 +3126,160 @@ impl HopfieldWindow {
                 }
             }
         }
-        Some(overlap_matrix)
+        Some(overlap_matrix)
+    }
+}
+
+impl Window for HopfieldWindow {
+    fn handle_command(&mut self, command: Command) -> bool {
+        // The network has no single-step mode, so a step runs it as well
+        match command {
+            Command::Train if self.run_job.is_none() => self.train_network(),
+            Command::Step | Command::Run if self.run_job.is_none() && self.network.is_some() => self.run_network(),
+            Command::Reset => self.update_input_state(),
+            _ => return false,
+        }
+        true
+    }
+    
+    fn session_settings(&self) -> Vec<(&'static str, String)> {
+        let mut training: Vec<usize> = self.selected_indices_for_training.iter().copied().collect();
+        training.sort_unstable();
+        let training: String = training.iter().filter_map(|&i| self.available_chars.get(i)).collect();
+        let mut settings = vec![
+            ("grid_size", self.current_grid_size.to_string()),
+            ("training", training),
+            ("rule", format!("{:?}", self.training_rule)),
+            ("hebbian_normalization", self.hebbian_normalization.name().to_string()),
+            ("input", self.selected_pattern_index_for_input.map(|i| i.to_string()).unwrap_or_default()),
+            ("update_mode", format!("{:?}", self.update_mode)),
+            ("beta", self.beta.to_string()),
+            ("calibrate_beta", self.calibrate_beta.to_string()),
+            ("max_iterations", self.max_iterations.to_string()),
+        ];
+        // Other noise models are not journaled
+        if let NoiseModel::BitFlip { probability } = self.noise_model {
+            settings.push(("flip_probability", probability.to_string()));
+        }
+        settings.push(("seed", self.seed.to_string()));
+        settings
+    }
+    
+    fn replay(&mut self, action: &SessionAction) -> bool {
+        match action {
+            SessionAction::Command(command) => self.handle_command(*command),
+            SessionAction::Set { key, value } => self.apply_session_setting(key, value),
+            SessionAction::Perform { name, .. } if name == "resample_noise" => {
+                self.update_input_state();
+                true
+            },
+            SessionAction::Perform { name, argument } if name == "preset" => self.load_preset(argument),
+            _ => false,
+        }
+    }
+    
+    fn is_busy(&self) -> bool {
+        self.run_job.is_some() || self.train_job.is_some()
+    }
+    
+    fn presets(&self) -> &'static [(&'static str, &'static str)] {
+        &PRESETS
+    }
+    
+    fn load_preset(&mut self, name: &str) -> bool {
+        if self.is_busy() {
+            return false;
+        }
+        if name == PRESETS[1].0 {
+            self.start_forgetting_demo();
+            return true;
+        }
+        if name != PRESETS[0].0 {
+            return false;
+        }
+        // The same settings a journal would record, applied in dependency order
+        let settings = [("training", "ABC"), ("rule", "Hebbian"), ("input", "0"), ("flip_probability", "0.3")];
+        for (key, value) in settings {
+            self.apply_session_setting(key, value);
+        }
+        self.train_network();
+        true
     }
-}
-
-impl Window for HopfieldWindow {
+    
+    fn restore(&mut self, snapshot: Snapshot) -> bool {
+        match snapshot.downcast::<TrainedState>() {
+            Ok(state) => self.restore_trained_state(*state),
+            Err(_) => false,
+        }
+    }
+    
+    fn on_close(&mut self) {
+        // The landscape samples can be recomputed on demand
+        self.timeline.pause();
+        self.audio = None;
+        self.sonify = false;
+        if let Some(demo) = &mut self.demo {
+            demo.running = false;
+        }
+        if let Some(demo) = &mut self.forgetting {
+            demo.running = false;
+        }
+        self.landscape = None;
+        self.weight_spectrum = None;
+    }
+    
     fn name(&self) -> &str {
         "Hopfield Network"
     }
+    
+    fn tick(&mut self, dt: f64) -> bool {
+        if self.run_job.is_some() {
+            return false;
+        }
+        if let Some(demo) = self.demo.as_mut().filter(|demo| demo.running) {
+            demo.time_to_next -= dt;
+            if demo.time_to_next <= 0.0 {
+                demo.time_to_next = DEMO_TRIAL_SECONDS;
+                self.advance_demo();
+            }
+            return true;
+        }
+        if let Some(demo) = self.forgetting.as_mut().filter(|demo| demo.running) {
+            demo.time_to_next -= dt;
+            if demo.time_to_next <= 0.0 {
+                demo.time_to_next = FORGETTING_STEP_SECONDS;
+                self.advance_forgetting_demo();
+            }
+            return true;
+        }
+        false
+    }
 
     fn show_config(&mut self, ui: &mut egui::Ui) {
         // --- Controls Panel Content (Moved from SidePanel::left) ---
         ui.heading("Controls");
         ui.separator();
         
+        if ui.add_enabled(self.run_job.is_none(), egui::Button::new("▶ Demo"))
+            .on_hover_text("Recall the selected pattern from cues with 0% to 100% flipped bits and plot the recall probability")
+            .clicked()
+        {
+            self.start_demo();
+        }
+        if ui.add_enabled(self.run_job.is_none(), egui::Button::new("▶ Forgetting Demo"))
+            .on_hover_text("Learn the letters one after another and plot how recall of the first letter degrades")
+            .clicked()
+        {
+            self.start_forgetting_demo();
+        }
+        ui.separator();
+        
+        // Settings stay fixed while a run is in progress
+        if self.run_job.is_some() {
+            ui.label("Network running...");
+            ui.disable();
+        }
+        
         // Grid Size
         ui.label("Grid Size (N x N):");
         let mut grid_size_mut = self.current_grid_size;
@@ -484,35 +3327,7 @@ impl Window for HopfieldWindow {
             });
 
         if selection_changed {
-            println!("Training selection changed: {:?}", self.selected_indices_for_training);
-            
-            // Update the training subset
-            let (new_patterns, new_trained_chars) = Self::filter_patterns(
-                &self.all_generated_patterns, 
-                &self.selected_indices_for_training
-            );
-            
-            self.patterns = new_patterns;
-            self.trained_chars = new_trained_chars;
-            
-            // Reset network and output
-            self.network = None;
-            self.pattern_overlap = Self::calculate_overlap_matrix(&self.patterns);
-            self.overlap_histogram = Self::calculate_overlap_histogram(&self.pattern_overlap);
-            self.output_states = None;
-            self.energy_history = None;
-            self.iterations = None;
-            self.display_iteration = None;
-            
-            // Reset selected input pattern
-            self.selected_pattern_index_for_input = if self.patterns.is_empty() { 
-                None 
-            } else { 
-                Some(0) 
-            };
-            
-            // Update input state
-            self.update_input_state();
+            self.training_selection_changed();
         }
 
         ui.separator();
@@ -522,11 +3337,66 @@ impl Window for HopfieldWindow {
         ui.horizontal(|ui| {
             let changed = ui.radio_value(&mut self.training_rule, TrainingRule::Hebbian, "Hebbian").changed();
             let changed = changed || ui.radio_value(&mut self.training_rule, TrainingRule::PseudoInverse, "Pseudo-Inverse").changed();
+            let changed = changed || ui.radio_value(&mut self.training_rule, TrainingRule::Storkey, "Storkey").changed();
             if changed {
                 self.network = None; // Require retraining if rule changes
                 println!("Training rule changed to {:?}. Retrain network.", self.training_rule);
             }
         });
+        if self.training_rule == TrainingRule::Hebbian {
+            ui.horizontal(|ui| {
+                ui.label("Normalization:");
+                let mut changed = false;
+                for normalization in HebbianNormalization::ALL {
+                    changed |= ui.radio_value(&mut self.hebbian_normalization, normalization, normalization.name()).changed();
+                }
+                if changed {
+                    self.network = None;
+                }
+            }).response.on_hover_text(
+                "Factor of the Hebbian weights. Without it, asynchronous fields grow with N, so the same β means a colder network on a larger grid.",
+            );
+        }
+
+        // --- Pattern Preprocessing ---
+        let mut preprocessing_changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Orthogonalize Patterns:");
+            let before = self.orthogonalization;
+            egui::ComboBox::from_id_source("orthogonalization")
+                .selected_text(match self.orthogonalization {
+                    Orthogonalization::None => "Off",
+                    Orthogonalization::GramSchmidt => "Gram-Schmidt",
+                    Orthogonalization::NearOrthogonal => "Near-Orthogonal (bipolar)",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.orthogonalization, Orthogonalization::None, "Off");
+                    ui.selectable_value(&mut self.orthogonalization, Orthogonalization::GramSchmidt, "Gram-Schmidt")
+                        .on_hover_text("Exactly orthogonal, real-valued patterns; the network is trained on their signs");
+                    ui.selectable_value(&mut self.orthogonalization, Orthogonalization::NearOrthogonal, "Near-Orthogonal (bipolar)")
+                        .on_hover_text("Flips as few bits as possible to remove the overlaps");
+                });
+            preprocessing_changed |= self.orthogonalization != before;
+        });
+        ui.horizontal(|ui| {
+            ui.label("Augment:");
+            let hint = "Also train on transformed copies of each pattern; the network treats every copy as a new pattern";
+            preprocessing_changed |= ui.checkbox(&mut self.augmentation.shifts, "±1 Shifts").on_hover_text(hint).changed();
+            preprocessing_changed |= ui.checkbox(&mut self.augmentation.mirror, "Mirror").on_hover_text(hint).changed();
+            preprocessing_changed |= ui.checkbox(&mut self.augmentation.rotations, "Rotations").on_hover_text(hint).changed();
+        });
+        if self.augmentation.is_enabled() {
+            let selected = self.selected_indices_for_training.len();
+            ui.label(format!("{} patterns from {} characters", self.patterns.len(), selected));
+        }
+        if preprocessing_changed {
+            self.refresh_training_patterns();
+            self.network = None; // Require retraining on the new patterns
+            self.landscape = None;
+            self.weight_spectrum = None;
+            self.demo = None;
+            self.update_input_state();
+        }
 
         ui.separator();
 
@@ -536,6 +3406,9 @@ impl Window for HopfieldWindow {
         ui.horizontal(|ui| {
             topology_changed |= ui.radio_value(&mut self.graph_type, GraphType::FullyConnected, "Fully Connected").changed();
             topology_changed |= ui.radio_value(&mut self.graph_type, GraphType::ErdosRenyi, "Erdős-Rényi").changed();
+            topology_changed |= ui.radio_value(&mut self.graph_type, GraphType::Lattice, "Lattice")
+                .on_hover_text("Only nearest neighbours on the grid are connected")
+                .changed();
         });
 
         // Only show connectivity slider if Erdős-Rényi is selected
@@ -546,9 +3419,77 @@ impl Window for HopfieldWindow {
             if er_slider.changed() {
                 topology_changed = true;
             }
+            topology_changed |= ui.add(egui::Slider::new(&mut self.er_asymmetry, 0.0..=1.0).text("Asymmetry"))
+                .on_hover_text("Fraction of connections pruned independently in each direction, so W_ij != W_ji")
+                .changed();
+        }
+        topology_changed |= ui.add(egui::Slider::new(&mut self.weight_noise, 0.0..=2.0).text("Weight Noise σ"))
+            .on_hover_text("Independent Gaussian noise on every weight, relative to the RMS weight")
+            .changed();
+        if self.weight_noise > 0.0 || (self.graph_type == GraphType::ErdosRenyi && self.er_asymmetry > 0.0) {
+            ui.small("Asymmetric weights: the energy may rise and the dynamics may cycle");
+        }
+        
+        topology_changed |= ui.checkbox(&mut self.modular, "Modular")
+            .on_hover_text("Train all modules jointly, then scale the weights between modules by the coupling")
+            .changed();
+        if self.modular {
+            ui.horizontal(|ui| {
+                ui.label("Modules:");
+                topology_changed |= ui.add(egui::DragValue::new(&mut self.module_grid.0).speed(0.1).range(1..=4)).changed();
+                ui.label("x");
+                topology_changed |= ui.add(egui::DragValue::new(&mut self.module_grid.1).speed(0.1).range(1..=4)).changed();
+            });
+            topology_changed |= ui.add(egui::Slider::new(&mut self.module_coupling, 0.0..=1.0).text("Coupling")).changed();
+        }
+
+        // Low-precision weights apply to the trained network directly
+        let mut quantization_changed = ui.checkbox(&mut self.quantize, "Quantize Weights")
+            .on_hover_text("Replace the trained weights by low-precision ones and compare recall with full precision")
+            .changed();
+        if self.quantize {
+            ui.horizontal(|ui| {
+                let uniform = matches!(self.quantization, Quantization::Uniform { .. });
+                if ui.radio(uniform, "Uniform").clicked() && !uniform {
+                    self.quantization = Quantization::Uniform { bits: 4 };
+                    quantization_changed = true;
+                }
+                if ui.radio(!uniform, "Ternary").clicked() && uniform {
+                    self.quantization = Quantization::Ternary { threshold: 0.5 };
+                    quantization_changed = true;
+                }
+            });
+            match &mut self.quantization {
+                Quantization::Uniform { bits } => {
+                    quantization_changed |= ui.add(egui::Slider::new(bits, 1..=8).text("Bits")).changed();
+                }
+                Quantization::Ternary { threshold } => {
+                    quantization_changed |= ui.add(egui::Slider::new(threshold, 0.0..=2.0).text("Zero Threshold"))
+                        .on_hover_text("Weights below this multiple of the mean magnitude become 0, the others ±1")
+                        .changed();
+                }
+            }
+            if let Some((full, quantized)) = self.quantization_accuracy {
+                ui.label(format!(
+                    "Recall at noise {:.2}: {:.0}% full precision, {:.0}% quantized",
+                    self.compression_noise,
+                    full * 100.0,
+                    quantized * 100.0
+                ));
+            }
+        }
+        if quantization_changed && self.network.is_some() {
+            if self.quantize {
+                self.apply_quantization();
+            } else {
+                self.remove_quantization();
+            }
         }
 
         if topology_changed {
+            if self.network.is_some() {
+                undo::checkpoint("Topology Change", Box::new(self.trained_state()));
+            }
             self.network = None; // Require retraining if topology settings change
             println!("Graph topology settings changed. Retrain network.");
         }
@@ -556,36 +3497,12 @@ impl Window for HopfieldWindow {
         ui.separator();
 
         // Train Button
-        if ui.button("Train Network").clicked() {
-            // Logic previously in train_network method
-            if self.patterns.is_empty() {
-                self.error_message = Some("Cannot train: No patterns selected.".to_string());
-            } else {
-                self.error_message = None;
-                self.output_states = None;
-                self.energy_history = None;
-                self.iterations = None;
-                self.display_iteration = None;
-
-                // Create network first
-                let mut net = HopfieldNetwork::new(self.current_grid_size * self.current_grid_size); 
-
-                // Train using the selected rule
-                match net.train(&self.patterns, self.training_rule) { 
-                    Ok(_) => {
-                        // Apply topology modification if necessary
-                        if self.graph_type == GraphType::ErdosRenyi {
-                            net.apply_erdos_renyi_topology(self.er_connectivity, &mut self.rng);
-                        }
-                        self.network = Some(net);
-                        println!("Network trained successfully on {} patterns.", self.patterns.len());
-                    }
-                    Err(e) => {
-                        self.network = None;
-                        self.error_message = Some(format!("Training Error: {}", e));
-                    }
-                }
-            }
+        self.poll_training();
+        if let Some(job) = &self.train_job {
+            job_progress(ui, job);
+        } else if ui.button("Train Network").on_hover_text("Ctrl+T").clicked() {
+            session::record(self.name(), SessionAction::Command(Command::Train));
+            self.train_network();
         }
 
         ui.separator();
@@ -644,10 +3561,59 @@ impl Window for HopfieldWindow {
         // --- Bottom Controls ---
         ui.separator();
         
-        // Noise Control
-        ui.label("Noise Level:");
-        let noise_slider = ui.add(egui::Slider::new(&mut self.noise_level, 0.0..=1.0).text("Noise"));
-        if noise_slider.changed() {
+        // Noise Control: how the target pattern is corrupted to give the input
+        ui.label("Input Noise:");
+        let mut noise_changed = false;
+        egui::ComboBox::from_id_source("noise_model")
+            .selected_text(self.noise_model.name())
+            .show_ui(ui, |ui| {
+                for model in NoiseModel::DEFAULTS {
+                    let selected = std::mem::discriminant(&model) == std::mem::discriminant(&self.noise_model);
+                    if ui.selectable_label(selected, model.name()).clicked() && !selected {
+                        self.noise_model = model;
+                        noise_changed = true;
+                    }
+                }
+            });
+        noise_changed |= match &mut self.noise_model {
+            NoiseModel::BitFlip { probability } => {
+                ui.add(egui::Slider::new(probability, 0.0..=1.0).text("Flip Probability")).changed()
+            }
+            NoiseModel::Mask { x, y, width, height } => {
+                let mut changed = ui.add(egui::Slider::new(x, 0.0..=1.0).text("Left")).changed();
+                changed |= ui.add(egui::Slider::new(y, 0.0..=1.0).text("Top")).changed();
+                changed |= ui.add(egui::Slider::new(width, 0.0..=1.0).text("Width")).changed();
+                changed |= ui.add(egui::Slider::new(height, 0.0..=1.0).text("Height")).changed();
+                changed
+            }
+            NoiseModel::Occlusion { fraction } => {
+                ui.add(egui::Slider::new(fraction, 0.0..=1.0).text("Hidden Rows")).changed()
+            }
+            NoiseModel::SaltAndPepper { density, radius } => {
+                let changed = ui.add(egui::Slider::new(density, 0.0..=1.0).text("Density")).changed();
+                changed | ui.add(egui::Slider::new(radius, 1..=8).text("Blob Radius")).changed()
+            }
+            NoiseModel::Blur { sigma } => {
+                ui.add(egui::Slider::new(sigma, 0.1..=4.0).text("Sigma")).changed()
+            }
+        };
+        if ui.button("Resample Noise").clicked() {
+            session::record(self.name(), SessionAction::perform("resample_noise", ""));
+            noise_changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Region Mask:");
+            ui.radio_value(&mut self.mask_shape, None, "Off");
+            for shape in [MaskShape::Rectangle, MaskShape::Lasso] {
+                ui.radio_value(&mut self.mask_shape, Some(shape), shape.name())
+                    .on_hover_text("Drag on the input grid to randomize a region and recall it from the rest");
+            }
+        });
+        if ui.add_enabled(self.region_mask.is_some(), egui::Button::new("Clear Mask")).clicked() {
+            self.region_mask = None;
+            noise_changed = true;
+        }
+        if noise_changed {
             self.update_input_state();
         }
         
@@ -655,31 +3621,369 @@ impl Window for HopfieldWindow {
         
         // Update Mode Selection
         ui.label("Update Mode:");
+        update_mode_control(ui, "sweep_order", &mut self.update_mode, &mut self.sweep_order);
+        if self.update_mode == UpdateMode::Asynchronous {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.record_micro_steps, "Record Every")
+                    .on_hover_text("Record states between sweeps to watch single flips spread. Use flip-list storage for large grids.");
+                ui.add_enabled(
+                    self.record_micro_steps,
+                    egui::DragValue::new(&mut self.micro_step_stride).speed(1.0).range(1..=100_000),
+                );
+                ui.label("updates");
+            });
+        }
+        
+        ui.separator();
+        
+        // Beta Control
+        if self.calibrate_beta {
+            ui.label("Update Rule Beta (relative to the field scale):");
+        } else {
+            ui.label("Update Rule Beta (Temperature Inverse):");
+        }
         ui.horizontal(|ui| {
-            ui.radio_value(&mut self.update_mode, UpdateMode::Synchronous, "Synchronous");
-            ui.radio_value(&mut self.update_mode, UpdateMode::Asynchronous, "Asynchronous");
+            ui.add(egui::DragValue::new(&mut self.beta).speed(0.01).range(0.01..=10.0));
+            ui.checkbox(&mut self.calibrate_beta, "Calibrate β")
+                .on_hover_text("Divide β by the typical local field of the trained weights, so the same value gives similar noise at any grid size and with either rule");
         });
         
         ui.separator();
         
-        // Beta Control
-        ui.label("Update Rule Beta (Temperature Inverse):");
-        ui.add(egui::DragValue::new(&mut self.beta).speed(0.01).range(0.01..=10.0));
+        // Random seed: re-seeding also redraws the input noise from the new generator
+        if seed_control(ui, &mut self.seed) {
+            self.rng = StdRng::seed_from_u64(self.seed);
+            self.update_input_state();
+        }
         
         ui.separator();
         
         // Run Controls
         ui.label("Max Iterations:");
-        ui.add(egui::DragValue::new(&mut self.max_iterations).speed(1.0).range(1..=1000));
+        ui.add(egui::DragValue::new(&mut self.max_iterations).speed(1.0).range(1..=100_000));
+        
+        // Long runs can keep only some states in full, or all of them as flip lists
+        let mut capacity = match self.history_storage {
+            HistoryStorage::Thinned(capacity) => capacity,
+            _ => 500,
+        };
+        ui.horizontal(|ui| {
+            ui.label("History:");
+            ui.radio_value(&mut self.history_storage, HistoryStorage::Full, "All States");
+            if ui.radio(matches!(self.history_storage, HistoryStorage::Thinned(_)), "Thinned")
+                .on_hover_text("Keep the first state, the latest ones and fewer older ones the further back they are")
+                .clicked()
+            {
+                self.history_storage = HistoryStorage::Thinned(capacity);
+            }
+            ui.radio_value(&mut self.history_storage, HistoryStorage::FlipLists, "Flip Lists")
+                .on_hover_text("Keep every state as the neurons flipped since the previous one and rebuild frames on demand");
+        });
+        if let HistoryStorage::Thinned(current) = &mut self.history_storage {
+            ui.horizontal(|ui| {
+                ui.label("Keep at most");
+                ui.add(egui::DragValue::new(&mut capacity).speed(10.0).range(4..=100_000));
+                ui.label("states");
+            });
+            *current = capacity;
+        }
         
         ui.separator();
         
         if ui.add_enabled(self.network.is_some(), egui::Button::new("Run Network")).clicked() {
+            session::record(self.name(), SessionAction::Command(Command::Run));
             self.run_network();
         }
         
+        // Repeated runs with independent random streams
+        ui.horizontal(|ui| {
+            ui.label("Repeat Runs:");
+            ui.add(egui::DragValue::new(&mut self.repeat_runs).speed(1.0).range(2..=1000));
+            let idle = self.network.is_some() && self.monte_carlo_job.is_none();
+            if ui.add_enabled(idle, egui::Button::new("Run Repeated")).clicked() {
+                self.start_monte_carlo();
+            }
+        });
+        ui.checkbox(&mut self.redraw_noise, "Fresh Input Noise per Run")
+            .on_hover_text("Otherwise every run starts from the input state shown");
+        
+        ui.checkbox(&mut self.replica, "Run Replica")
+            .on_hover_text("Run the network twice from the input with independent noise and plot the overlap q(t) of the two runs; the runs only part at finite β or in random sweep orders");
+        
+        // Comparative run: a second network trained on the same patterns, run from the same input
+        ui.checkbox(&mut self.compare, "Compare With Second Configuration")
+            .on_hover_text("The second network is trained along with the first, so retrain after turning this on or changing its rule");
+        if self.compare {
+            ui.indent("comparison_config", |ui| {
+                let config = &mut self.comparison_config;
+                ui.horizontal(|ui| {
+                    ui.label("Rule:");
+                    ui.radio_value(&mut config.training_rule, TrainingRule::Hebbian, "Hebbian");
+                    ui.radio_value(&mut config.training_rule, TrainingRule::PseudoInverse, "Pseudo-Inverse");
+                    ui.radio_value(&mut config.training_rule, TrainingRule::Storkey, "Storkey");
+                });
+                ui.label("Mode:");
+                update_mode_control(ui, "comparison_sweep_order", &mut config.update_mode, &mut config.sweep_order);
+                ui.horizontal(|ui| {
+                    ui.label("Beta:");
+                    ui.add(egui::DragValue::new(&mut config.beta).speed(0.01).range(0.01..=10.0));
+                });
+            });
+        }
+        
+        ui.collapsing("Record Animation", |ui| {
+            if self.animation_export.show(ui, self.output_states.is_some()) {
+                self.record_animation();
+            }
+        });
+        
+        ui.collapsing("Audio", |ui| self.show_audio_panel(ui));
+        
+        ui.collapsing("Weight Files", |ui| {
+            ui.label("NumPy (.npy), MATLAB (.mat) or .safetensors");
+            ui.horizontal(|ui| {
+                ui.label("Weights:");
+                ui.text_edit_singleline(&mut self.weights_path);
+            });
+            ui.horizontal(|ui| {
+                if ui.add_enabled(self.network.is_some(), egui::Button::new("Save"))
+                    .on_hover_text("A .mat file also gets the training patterns, as 'patterns'; a .safetensors file gets the training rule")
+                    .clicked()
+                {
+                    self.export_weights();
+                }
+                if ui.button("Load")
+                    .on_hover_text("Use an N² × N² weight matrix ('W' in a .mat file) instead of training")
+                    .clicked()
+                {
+                    self.import_weights();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Patterns:");
+                ui.text_edit_singleline(&mut self.patterns_path);
+                if ui.add_enabled(!self.patterns.is_empty(), egui::Button::new("Save"))
+                    .on_hover_text("One training pattern per row")
+                    .clicked()
+                {
+                    self.export_patterns();
+                }
+            });
+        });
+        
+        ui.collapsing("Save as PNG", |ui| {
+            let available = [
+                self.selected_pattern_index_for_input.is_some(),
+                true,
+                self.output_states.is_some(),
+                self.network.is_some(),
+                self.energy_history.is_some(),
+            ];
+            let views: Vec<(&str, bool)> = SNAPSHOT_VIEWS.iter().copied().zip(available).collect();
+            if let Some(view) = self.snapshot_export.show(ui, &views) {
+                self.save_snapshot(view);
+            }
+        });
+        
+        ui.separator();
+        
+        // Batch Evaluation Controls
+        ui.label("Batch Evaluation:");
+        ui.horizontal(|ui| {
+            ui.label("Noise Levels:");
+            ui.text_edit_singleline(&mut self.eval_noise_levels);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Trials:");
+            ui.add(egui::DragValue::new(&mut self.eval_trials).speed(1.0).range(1..=500));
+        });
+        if ui.add_enabled(self.network.is_some(), egui::Button::new("Evaluate Recall"))
+            .on_hover_text("Recall every trained pattern from noisy cues at each noise level")
+            .clicked()
+        {
+            self.evaluate_recall();
+        }
+        ui.horizontal(|ui| {
+            ui.label("Max Noise:");
+            ui.add(egui::DragValue::new(&mut self.robustness_max_noise).speed(0.01).range(0.05..=1.0));
+            ui.label("Steps:");
+            ui.add(egui::DragValue::new(&mut self.robustness_steps).speed(1.0).range(2..=50));
+        });
+        if ui.add_enabled(self.network.is_some() && self.robustness_job.is_none(), egui::Button::new("Map Robustness"))
+            .on_hover_text("Recall every trained pattern at evenly spaced noise levels; the map is shown with the overlap matrix")
+            .clicked()
+        {
+            self.start_robustness_map();
+        }
+        
+        // Pruning Controls
+        ui.label("Weight Pruning:");
+        ui.horizontal(|ui| {
+            ui.label("Sparsity:");
+            ui.add(egui::Slider::new(&mut self.prune_sparsity, 0.0..=0.99))
+                .on_hover_text("Fraction of the connections set to zero, smallest weights first");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Cue Noise:");
+            ui.add(egui::DragValue::new(&mut self.compression_noise).speed(0.01).range(0.0..=1.0));
+        });
+        ui.horizontal(|ui| {
+            let trained = self.network.is_some();
+            if ui.add_enabled(trained, egui::Button::new("Prune Weights"))
+                .on_hover_text("Prune the trained weights to the chosen sparsity and evaluate recall")
+                .clicked()
+            {
+                self.prune_network();
+            }
+            if ui.add_enabled(trained && self.unpruned.is_some(), egui::Button::new("Restore"))
+                .on_hover_text("Go back to the trained weights")
+                .clicked()
+            {
+                self.restore_weights();
+            }
+            if ui.add_enabled(trained && self.pruning_job.is_none(), egui::Button::new("Sweep Sparsity"))
+                .on_hover_text(format!("Evaluate recall at {} sparsities from 0 to {:.0}%", PRUNING_LEVELS, PRUNING_MAX_SPARSITY * 100.0))
+                .clicked()
+            {
+                self.start_pruning_sweep();
+            }
+        });
+        
+        // Temperature Sweep Controls
+        ui.label("Temperature Sweep:");
+        ui.horizontal(|ui| {
+            let (low, high) = &mut self.sweep_beta_range;
+            ui.label("β from");
+            ui.add(egui::DragValue::new(low).speed(0.001).range(0.0001..=100.0));
+            ui.label("to");
+            ui.add(egui::DragValue::new(high).speed(0.01).range(0.0001..=100.0));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Points:");
+            ui.add(egui::DragValue::new(&mut self.sweep_points).speed(1.0).range(2..=100));
+            ui.label("Trials:");
+            ui.add(egui::DragValue::new(&mut self.sweep_trials).speed(1.0).range(1..=100));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Burn-in:");
+            ui.add(egui::DragValue::new(&mut self.sweep_burn_in).speed(1.0).range(0..=1000));
+            ui.label("Measure:");
+            ui.add(egui::DragValue::new(&mut self.sweep_measure).speed(1.0).range(1..=1000));
+        });
+        if ui.add_enabled(self.network.is_some() && self.sweep_job.is_none(), egui::Button::new("Sweep Temperature"))
+            .on_hover_text("Measure the steady-state overlap with the target, starting from the input, at each β")
+            .clicked()
+        {
+            self.start_temperature_sweep();
+        }
+        ui.horizontal(|ui| {
+            ui.label("CSV:");
+            ui.text_edit_singleline(&mut self.temperature_csv_path);
+            if ui.add_enabled(self.temperature_curve.is_some(), egui::Button::new("Export")).clicked() {
+                self.export_temperature_curve();
+            }
+        });
+        
+        // Training Rule Benchmark
+        ui.label("Rule Comparison:");
+        if ui.add_enabled(self.rule_comparison_job.is_none(), egui::Button::new("Compare Rules"))
+            .on_hover_text(format!(
+                "Train {}-neuron networks with the Hebbian, Storkey and pseudo-inverse rules on random patterns up to α = {} and plot recall accuracy",
+                COMPARISON_NEURONS, COMPARISON_MAX_LOAD
+            ))
+            .clicked()
+        {
+            self.start_rule_comparison();
+        }
+        ui.horizontal(|ui| {
+            ui.label("CSV:");
+            ui.text_edit_singleline(&mut self.rule_comparison_csv_path);
+            if ui.add_enabled(self.rule_comparison.is_some(), egui::Button::new("Export")).clicked() {
+                self.export_rule_comparison();
+            }
+        });
+        
+        // Recording Controls
+        ui.label("Record Long Run:");
+        ui.horizontal(|ui| {
+            for format in [RecordFormat::Csv, RecordFormat::Parquet] {
+                let button = ui.add_enabled(format.is_available(), egui::RadioButton::new(self.record_config.format == format, format.name()))
+                    .on_disabled_hover_text("Requires building with `--features parquet`");
+                if button.clicked() {
+                    self.record_config.format = format;
+                    let stem = Path::new(&self.record_path).with_extension(format.extension());
+                    self.record_path = stem.to_string_lossy().into_owned();
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.record_path);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Iterations:");
+            ui.add(egui::DragValue::new(&mut self.record_iterations).speed(100.0).range(1..=10_000_000));
+            ui.label("Stride:");
+            ui.add(egui::DragValue::new(&mut self.record_config.stride).speed(1.0).range(1..=100_000));
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.record_config.record_state, "States");
+            ui.checkbox(&mut self.record_energy, "Energy");
+            ui.checkbox(&mut self.record_overlap, "Overlap");
+        });
+        if ui.add_enabled(self.network.is_some() && self.record_job.is_none(), egui::Button::new("Record Run"))
+            .on_hover_text("Run from the input state and stream the recorded quantities to the file as they are produced")
+            .clicked()
+        {
+            self.start_recording();
+        }
+        if let Some(status) = &self.record_status {
+            ui.label(status);
+        }
+        
+        // Energy Landscape Controls
+        ui.label("Energy Landscape:");
+        ui.horizontal(|ui| {
+            let is_pca = self.landscape_coordinates == LandscapeCoordinates::Pca;
+            if ui.radio(is_pca, "PCA").clicked() {
+                self.landscape_coordinates = LandscapeCoordinates::Pca;
+            }
+            if ui.radio(!is_pca, "Overlaps").clicked() && is_pca {
+                self.landscape_coordinates = LandscapeCoordinates::Overlap(0, 1);
+            }
+        });
+        if let LandscapeCoordinates::Overlap(first, second) = &mut self.landscape_coordinates {
+            let chars = &self.trained_chars;
+            ui.horizontal(|ui| {
+                for (id, index) in [("landscape_x", first), ("landscape_y", second)] {
+                    egui::ComboBox::from_id_source(id)
+                        .width(50.0)
+                        .selected_text(chars.get(*index).map_or("?".to_string(), |c| c.to_string()))
+                        .show_ui(ui, |ui| {
+                            for (k, c) in chars.iter().enumerate() {
+                                ui.selectable_value(index, k, c.to_string());
+                            }
+                        });
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label("Random Starts:");
+            ui.add(egui::DragValue::new(&mut self.landscape_samples).speed(1.0).range(1..=1000));
+        });
+        if ui.add_enabled(self.network.is_some(), egui::Button::new("Compute Landscape"))
+            .on_hover_text("Find attractors from random states and project them with the trajectory")
+            .clicked()
+        {
+            self.compute_landscape();
+            self.plot_tab = PlotTab::Landscape;
+        }
+        
         // --- Info Section ---
         ui.separator();
+        if let (Some(load), Some(_)) = (self.pattern_load, &self.network) {
+            load_gauge(ui, load);
+        }
         
         egui::CollapsingHeader::new("Info & Formulae")
             .id_source("info_collapse")
@@ -687,6 +3991,11 @@ impl Window for HopfieldWindow {
                 ui.label(format!("Grid Size: {}x{}", self.current_grid_size, self.current_grid_size));
                 ui.label(format!("Neurons: {}", self.current_grid_size * self.current_grid_size));
                 ui.label(format!("Stored Patterns: {}", self.patterns.len()));
+                if let Some(net) = &self.network {
+                    let beta = self.effective_beta();
+                    ui.label(format!("Field Scale: {:.3} (mean Σⱼ |Wᵢⱼ|)", net.field_scale()));
+                    ui.label(format!("Effective β = {:.4}, temperature T = {:.4}", beta, 1.0 / beta));
+                }
                 ui.separator();
                 ui.label("Update Rule: Sᵢ(t+1) = sgn( Σⱼ Wᵢⱼ Sⱼ(t) )");
                 ui.label("Learning Rule: Wᵢⱼ = Σₚ ξᵢᵖ ξⱼᵖ  (i ≠ j, Wᵢᵢ = 0)");
@@ -709,27 +4018,27 @@ impl Window for HopfieldWindow {
                                 }
                                 ui.end_row();
 
-                                // Matrix Rows
+                                // Matrix Rows, fragile patterns of the robustness map in red
+                                let fragile = self.robustness.as_ref().map(RobustnessMap::fragile).unwrap_or_default();
                                 for (p, &char_code) in self.trained_chars.iter().enumerate() {
-                                    ui.label(char_code.to_string()); // Row Header
+                                    if fragile.contains(&p) {
+                                        ui.colored_label(egui::Color32::LIGHT_RED, char_code.to_string())
+                                            .on_hover_text("Fragile under noise");
+                                    } else {
+                                        ui.label(char_code.to_string()); // Row Header
+                                    }
                                     for q in 0..self.trained_chars.len() {
+                                        // Off-diagonal overlaps far from 0 (white) are the ones that interfere
                                         let overlap = matrix[p][q];
-                                        // Color based on overlap value (closer to +/-1 is less ideal)
-                                        let abs_overlap = overlap.abs();
-                                        let color = if p == q { // Diagonal
-                                            egui::Color32::WHITE
-                                        } else if abs_overlap > 0.7 {
-                                            egui::Color32::from_rgb(255, 100, 100) // Reddish for high overlap
-                                        } else if abs_overlap > 0.3 {
-                                            egui::Color32::from_rgb(255, 200, 100) // Orange/Yellow for medium
-                                        } else {
-                                            egui::Color32::from_rgb(100, 255, 100) // Greenish for low
-                                        };
-                                        ui.colored_label(color, format!("{:.2}", overlap));
+                                        color_cell(ui, Colormap::Diverging.map_signed(overlap, 1.0), &format!("{:.2}", overlap));
                                     }
                                     ui.end_row();
                                 }
                             });
+                        legend(ui, Colormap::Diverging, -1.0, 1.0, "Overlap:");
+                        ui.separator();
+                        ui.label("Noise Robustness (recall accuracy by pattern and noise level):");
+                        self.show_robustness_map(ui);
                     } else {
                         ui.label("(No patterns selected for overlap calculation)");
                     }
@@ -740,18 +4049,26 @@ impl Window for HopfieldWindow {
                 ui.separator();
                 ui.label("Histogram of Off-Diagonal Overlap Magnitudes (|m_pq|, p != q):");
 
-                // Display Overlap Histogram
+                // Display Overlap Histogram, next to the one before orthogonalization
                 if let Some(histogram_bars) = &self.overlap_histogram {
                     if !histogram_bars.is_empty() {
                         let chart = egui_plot::BarChart::new(histogram_bars.clone()) 
                             .color(egui::Color32::LIGHT_BLUE)
-                            .name("Overlap Distribution");
+                            .name(if self.raw_overlap_histogram.is_some() { "Orthogonalized" } else { "Overlap Distribution" });
+                        let raw_chart = self.raw_overlap_histogram.as_ref().map(|bars| {
+                            egui_plot::BarChart::new(bars.clone())
+                                .color(egui::Color32::from_rgba_unmultiplied(255, 160, 60, 140))
+                                .name("Before Orthogonalization")
+                        });
 
                         egui_plot::Plot::new("overlap_histogram_plot")
                             .legend(egui_plot::Legend::default())
                             .height(100.0) // Adjust height as needed
                             .show_axes([true, true])
                             .show(ui, |plot_ui| {
+                                if let Some(raw_chart) = raw_chart {
+                                    plot_ui.bar_chart(raw_chart);
+                                }
                                 plot_ui.bar_chart(chart);
                             });
                     } else {
@@ -762,11 +4079,6 @@ impl Window for HopfieldWindow {
                 }
             });
             
-        // Display Error Messages
-        if let Some(err) = &self.error_message {
-            ui.separator();
-            ui.colored_label(egui::Color32::RED, err);
-        }
     }
     
     fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
@@ -774,7 +4086,39 @@ impl Window for HopfieldWindow {
         ui.heading("Network State & Energy");
         ui.separator();
         
+        self.poll_run();
+        if let Some(job) = &self.run_job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        self.poll_temperature_sweep();
+        if let Some(job) = &self.sweep_job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        self.poll_recording();
+        if let Some(job) = &self.record_job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        self.poll_robustness_map();
+        if let Some(job) = &self.robustness_job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        self.poll_pruning_sweep();
+        if let Some(job) = &self.pruning_job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        self.poll_rule_comparison();
+        if let Some(job) = &self.rule_comparison_job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        
         // Top part: Target | Input | Output Grids
+        let mut new_mask = None;
         ui.columns(3, |columns| {
             // Column 1: Target Pattern
             columns[0].vertical_centered(|ui| {
@@ -804,12 +4148,18 @@ impl Window for HopfieldWindow {
             columns[1].vertical_centered(|ui| {
                 ui.label("Input State");
                 ui.separator();
-                if self.input_state.len() == self.current_grid_size * self.current_grid_size {
-                    draw_grid(ui, &self.input_state, self.current_grid_size, self.current_grid_size, 4.0); 
-                } else {
+                if self.input_state.len() != self.current_grid_size * self.current_grid_size {
                     ui.label("(Invalid input state size)");
+                } else if let Some(shape) = self.mask_shape {
+                    let grid = SpinGrid { state: &self.input_state, width: self.current_grid_size };
+                    new_mask = mask_editor(ui, &grid, 4.0, shape, self.region_mask.as_ref());
+                } else {
+                    draw_grid(ui, &self.input_state, self.current_grid_size, self.current_grid_size, 4.0); 
+                }
+                ui.label(self.noise_model.describe());
+                if let Some(mask) = &self.region_mask {
+                    ui.label(format!("{} cells masked", mask.count()));
                 }
-                ui.label(format!("Noise: {:.2}", self.noise_level));
             });
 
             // Column 3: Output State (Iteration Viewer)
@@ -823,8 +4173,48 @@ impl Window for HopfieldWindow {
                         .min(states.len().saturating_sub(1));
                         
                     if let Some(output) = states.get(iteration_to_display) {
+                        let output: &[f64] = &output;
                         if output.len() == self.current_grid_size * self.current_grid_size {
                             draw_grid(ui, output, self.current_grid_size, self.current_grid_size, 4.0);
+                            // Closest stored pattern (Hopfield networks also store each inverse)
+                            if let Some(retrieval) = classify(output, &self.patterns, true) {
+                                let recalled = self.trained_chars.get(retrieval.pattern_index).copied().unwrap_or('?');
+                                ui.label(format!(
+                                    "Recalled: '{}' ({:.0}% match{})",
+                                    recalled,
+                                    retrieval.match_fraction * 100.0,
+                                    if retrieval.inverted { ", inverted" } else { "" }
+                                ));
+                            }
+                            // Interference between modules shows up as modules recalling different patterns
+                            let target = self.selected_pattern_index_for_input.and_then(|i| self.patterns.get(i));
+                            if let (Some(modules), Some(target)) = (self.modules(), target) {
+                                let overlaps: Vec<String> = modules
+                                    .overlaps(output, target)
+                                    .iter()
+                                    .map(|m| format!("{:.2}", m))
+                                    .collect();
+                                ui.label(format!("Module overlaps: {}", overlaps.join(", ")))
+                                    .on_hover_text("Overlap with the target pattern within each module, row by row");
+                            }
+                            // Completion of the masked region, which the cue gave no information about
+                            let size = self.current_grid_size;
+                            let mask = self.region_mask.as_ref().filter(|mask| mask.fits(size, size) && mask.count() > 0);
+                            if let (Some(mask), Some(target)) = (mask, target) {
+                                let correct = output
+                                    .iter()
+                                    .zip(target)
+                                    .zip(mask.cells())
+                                    .filter(|&((a, b), &masked)| masked && a == b)
+                                    .count();
+                                ui.label(format!(
+                                    "Masked region: overlap {:.2}, {} of {} cells correct",
+                                    masked_overlap(output, target, mask.cells()),
+                                    correct,
+                                    mask.count()
+                                ))
+                                .on_hover_text("Recall quality measured only over the masked cells");
+                            }
                         } else {
                             ui.label("(Invalid output state size)");
                         }
@@ -834,21 +4224,31 @@ impl Window for HopfieldWindow {
 
                     if let Some(total_iters) = self.iterations {
                         // Add slider to view iterations
-                        let num_states = states.len();
-                        let max_slider_idx = num_states.saturating_sub(1);
-                        let mut current_slider_val = self.display_iteration
-                            .unwrap_or(0)
-                            .min(max_slider_idx);
+                        let mut current_slider_val = self.display_iteration.unwrap_or(0);
 
                         ui.add_space(10.0);
-                        if ui.add(egui::Slider::new(&mut current_slider_val, 0..=max_slider_idx)
-                            .text("Iteration"))
-                            .changed() 
-                        {
+                        let unit = if self.micro_steps { "Update" } else { "Iteration" };
+                        if history_slider(ui, states, &mut current_slider_val, unit) {
+                            self.timeline.pause();
+                            self.display_iteration = Some(current_slider_val);
+                        }
+                        if self.micro_steps {
+                            let neurons = (self.current_grid_size * self.current_grid_size).max(1);
+                            let update = states.iteration(current_slider_val).unwrap_or(0);
+                            ui.weak(format!(
+                                "Single-neuron updates; sweep {} + {} of {}",
+                                update / neurons,
+                                update % neurons,
+                                neurons
+                            ));
+                        }
+                        if self.timeline.show(ui, &mut current_slider_val, states.len()) {
                             self.display_iteration = Some(current_slider_val);
                         }
 
-                        let label_text = if total_iters < self.max_iterations {
+                        let label_text = if self.run_cancelled {
+                            format!("Cancelled after {} iterations.", total_iters)
+                        } else if total_iters < self.max_iterations {
                             format!("Converged in {} iterations.", total_iters)
                         } else {
                             format!("Stopped after {} iterations.", total_iters)
@@ -863,26 +4263,123 @@ impl Window for HopfieldWindow {
             });
         });
 
+        if let Some(mask) = new_mask {
+            self.region_mask = Some(mask);
+            self.update_input_state();
+        }
+
+        self.update_sonification();
         ui.separator();
 
-        // Bottom part: Energy Plot
-        ui.label("Energy Profile:");
+        // Comparative run results
+        if self.comparison.is_some() {
+            egui::CollapsingHeader::new("Comparison")
+                .id_source("comparison_collapse")
+                .default_open(true)
+                .show(ui, |ui| self.show_comparison(ui));
+            ui.separator();
+        }
+
+        // Repeated run statistics
+        self.poll_monte_carlo();
+        if let Some(job) = &self.monte_carlo_job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        if self.monte_carlo.is_some() {
+            egui::CollapsingHeader::new("Repeated Runs")
+                .id_source("monte_carlo_collapse")
+                .default_open(true)
+                .show(ui, |ui| self.show_monte_carlo(ui));
+            ui.separator();
+        }
+        
+        // Batch evaluation results
+        if self.evaluation.is_some() {
+            egui::CollapsingHeader::new("Batch Evaluation")
+                .id_source("evaluation_collapse")
+                .default_open(true)
+                .show(ui, |ui| self.show_evaluation(ui));
+            ui.separator();
+        }
+
+        // Bottom part: Energy Plot or Landscape
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Energy, "Energy Profile");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Landscape, "Energy Landscape");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Surface, "Energy Surface")
+                .on_hover_text(format!("Every state of networks with up to {} neurons", MAX_SURFACE_NEURONS));
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Mosaic, "State Mosaic")
+                .on_hover_text("Every recorded state of the run side by side");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Trajectory, "Overlap Trajectory")
+                .on_hover_text("Available with two or three trained patterns");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Temperature, "Temperature Sweep");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Eigenmodes, "Weight Eigenmodes");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Pruning, "Weight Pruning");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Demo, "Degradation Demo");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Forgetting, "Forgetting Demo");
+            ui.selectable_value(&mut self.plot_tab, PlotTab::Rules, "Rule Comparison");
+        });
         let plot_height = ui.available_height() * 0.8;
-        if let Some(energies) = &self.energy_history {
+        if self.plot_tab == PlotTab::Landscape {
+            self.show_landscape(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Surface {
+            self.show_energy_surface(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Mosaic {
+            self.show_mosaic(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Trajectory {
+            self.show_trajectory(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Temperature {
+            self.show_temperature_curve(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Eigenmodes {
+            self.show_eigenmodes(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Pruning {
+            self.show_pruning(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Demo {
+            self.show_demo(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Forgetting {
+            self.show_forgetting(ui, plot_height);
+        } else if self.plot_tab == PlotTab::Rules {
+            self.show_rule_comparison(ui, plot_height);
+        } else if let Some(energies) = &self.energy_history {
             if !energies.is_empty() {
-                let points: PlotPoints = energies
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &e)| [i as f64, e])
-                    .collect();
-                
-                let line = Line::new(points);
-                Plot::new("energy_plot")
+                // Both curves of a comparative run share the plot
+                let (line, comparison_line) = match &self.comparison {
+                    Some(comparison) => (
+                        history_line(energies).name(format!("A: {}", self.current_config().label())),
+                        Some(history_line(&comparison.energies).name(format!("B: {}", self.comparison_config.label()))),
+                    ),
+                    None => (history_line(energies), None),
+                };
+                // The replica overlap gets a plot of its own below, as it lives on [-1, 1]
+                let energy_height = if self.replica_overlap.is_some() { plot_height * 0.6 } else { plot_height };
+                let unit = if self.micro_steps { "Single-neuron updates" } else { "Iteration" };
+                let mut plot = Plot::new("energy_plot")
                     .view_aspect(2.0)
-                    .height(plot_height)
-                    .show(ui, |plot_ui| {
-                        plot_ui.line(line);
-                    });
+                    .height(energy_height)
+                    .x_axis_label(unit);
+                if comparison_line.is_some() {
+                    plot = plot.legend(Legend::default());
+                }
+                plot.show(ui, |plot_ui| {
+                    plot_ui.line(line);
+                    if let Some(comparison_line) = comparison_line {
+                        plot_ui.line(comparison_line);
+                    }
+                });
+                if let Some(q) = &self.replica_overlap {
+                    Plot::new("replica_overlap_plot")
+                        .height(plot_height * 0.4)
+                        .include_y(-1.0)
+                        .include_y(1.0)
+                        .x_axis_label(unit)
+                        .y_axis_label("q(t)")
+                        .show(ui, |plot_ui| {
+                            plot_ui.line(history_line(q).name("Replica overlap q(t)"));
+                        });
+                    ui.label(format!("Final replica overlap q = {:.3}", q.last().copied().unwrap_or(1.0)))
+                        .on_hover_text("q stays near 1 when both runs fall into the same attractor and drops when noise sends them to different ones");
+                }
             } else {
                 ui.label("(No energy data)");
             }
@@ -0,0 +1,478 @@
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::core::graph::GraphType;
+use crate::neural::hopfield_tank::{HopfieldTankSolver, OptimizationProblem, PenaltyWeights};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::dynamics;
+use crate::ui::widgets::network::{self, NetworkStyle};
+use crate::ui::windows::chip_firing::{build_graph, layout_positions};
+use crate::ui::windows::{Command, Window};
+
+/// Kind of problem posed to the solver
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ProblemKind {
+    Tsp,
+    GraphColoring,
+}
+
+/// Colors used for vertex colorings
+const PALETTE: [egui::Color32; 8] = [
+    egui::Color32::from_rgb(230, 80, 80),
+    egui::Color32::from_rgb(80, 160, 230),
+    egui::Color32::from_rgb(100, 200, 100),
+    egui::Color32::from_rgb(240, 200, 60),
+    egui::Color32::from_rgb(170, 110, 220),
+    egui::Color32::from_rgb(240, 140, 60),
+    egui::Color32::from_rgb(90, 210, 200),
+    egui::Color32::from_rgb(200, 200, 200),
+];
+
+pub struct HopfieldTankWindow {
+    /// The solver for the current problem
+    solver: Option<HopfieldTankSolver>,
+
+    /// Problem settings
+    problem_kind: ProblemKind,
+    num_cities: usize,
+    cities: Vec<(f64, f64)>,       // City coordinates in the unit square
+    graph_type: GraphType,
+    graph_size: usize,
+    grid_width: usize,
+    grid_height: usize,
+    num_colors: usize,
+    graph_edges: Vec<(usize, usize)>,
+    node_positions: Vec<egui::Vec2>,
+
+    /// Network parameters (applied when the solver is created)
+    penalties: PenaltyWeights,
+    gain: f64,
+    dt: f64,
+    noise: f64,
+
+    /// Simulation parameters
+    steps_per_frame: usize,
+    running: bool,
+
+    /// Penalty term values after each step, one series per term
+    term_names: Vec<&'static str>,
+    term_history: Vec<Vec<f64>>,
+
+    /// Random number generator for instances and initial noise
+    rng: ThreadRng,
+}
+
+impl HopfieldTankWindow {
+    pub fn new() -> Self {
+        Self {
+            solver: None,
+            problem_kind: ProblemKind::Tsp,
+            num_cities: 10,
+            cities: Vec::new(),
+            graph_type: GraphType::Cycle,
+            graph_size: 7,
+            grid_width: 4,
+            grid_height: 4,
+            num_colors: 3,
+            graph_edges: Vec::new(),
+            node_positions: Vec::new(),
+            penalties: PenaltyWeights::default_for(&OptimizationProblem::Tsp { distances: Vec::new() }),
+            gain: 0.02,
+            dt: 1e-5,
+            noise: 0.1,
+            steps_per_frame: 20,
+            running: false,
+            term_names: Vec::new(),
+            term_history: Vec::new(),
+            rng: rand::thread_rng(),
+        }
+    }
+
+    /// Reset penalties and integration settings to the defaults of the selected problem kind
+    fn load_defaults(&mut self) {
+        let problem = match self.problem_kind {
+            ProblemKind::Tsp => OptimizationProblem::Tsp { distances: Vec::new() },
+            ProblemKind::GraphColoring => OptimizationProblem::GraphColoring {
+                num_vertices: 0,
+                edges: Vec::new(),
+                num_colors: 0,
+            },
+        };
+        self.penalties = PenaltyWeights::default_for(&problem);
+        (self.gain, self.dt) = match self.problem_kind {
+            ProblemKind::Tsp => (0.02, 1e-5),
+            ProblemKind::GraphColoring => (0.1, 0.01),
+        };
+    }
+
+    /// Build the problem from the current settings
+    fn build_problem(&mut self) -> Result<OptimizationProblem, String> {
+        match self.problem_kind {
+            ProblemKind::Tsp => {
+                if self.cities.len() != self.num_cities {
+                    self.randomize_cities();
+                }
+                Ok(OptimizationProblem::tsp_from_points(&self.cities))
+            },
+            ProblemKind::GraphColoring => {
+                let graph = build_graph(self.graph_type, self.graph_size, self.grid_width, self.grid_height, "")?;
+                self.graph_edges = (0..graph.num_vertices)
+                    .flat_map(|i| graph.neighbors(i).into_iter().filter(move |&j| i < j).map(move |j| (i, j)))
+                    .collect();
+                self.node_positions = layout_positions(self.graph_type, graph.num_vertices, self.grid_width, self.grid_height);
+                Ok(OptimizationProblem::GraphColoring {
+                    num_vertices: graph.num_vertices,
+                    edges: self.graph_edges.clone(),
+                    num_colors: self.num_colors,
+                })
+            },
+        }
+    }
+
+    fn randomize_cities(&mut self) {
+        self.cities = (0..self.num_cities)
+            .map(|_| (self.rng.gen::<f64>(), self.rng.gen::<f64>()))
+            .collect();
+    }
+
+    /// Create the solver for the current problem and start from noisy balanced potentials
+    fn create_solver(&mut self) {
+        let problem = match self.build_problem() {
+            Ok(problem) => problem,
+            Err(e) => {
+                report_error(e);
+                return;
+            }
+        };
+
+        match HopfieldTankSolver::new(problem, self.penalties) {
+            Ok(mut solver) => {
+                solver.gain = self.gain;
+                solver.dt = self.dt;
+                // Only the current state and the penalty terms are shown
+                solver.record_history = false;
+                solver.randomize(self.noise, &mut self.rng);
+                self.solver = Some(solver);
+                self.restart_terms();
+            },
+            Err(e) => report_error(format!("Failed to build network: {}", e)),
+        }
+    }
+
+    /// Start again from new random potentials
+    fn restart(&mut self) {
+        if let Some(solver) = &mut self.solver {
+            solver.randomize(self.noise, &mut self.rng);
+        }
+        self.restart_terms();
+    }
+
+    fn restart_terms(&mut self) {
+        if let Some(solver) = &self.solver {
+            let terms = solver.energy_terms();
+            self.term_names = terms.iter().map(|(name, _)| *name).collect();
+            self.term_history = terms.iter().map(|&(_, value)| vec![value]).collect();
+        }
+    }
+
+    /// Integrate `steps` steps, recording the penalty terms after each
+    fn advance(&mut self, steps: usize) {
+        if let Some(solver) = &mut self.solver {
+            for _ in 0..steps {
+                dynamics::advance(solver, 1, &mut self.rng);
+                for (series, (_, value)) in self.term_history.iter_mut().zip(solver.energy_terms()) {
+                    series.push(value);
+                }
+            }
+        }
+    }
+
+    /// Draw the cities and the tour read off the most active city at each position
+    fn draw_tsp(&self, ui: &mut egui::Ui, solver: &HopfieldTankSolver) {
+        let size = 300.0;
+        let (response, painter) = ui.allocate_painter(egui::vec2(size, size), egui::Sense::hover());
+        let rect = response.rect.shrink(10.0);
+        let to_screen = |(x, y): (f64, f64)| {
+            rect.min + egui::vec2(x as f32 * rect.width(), y as f32 * rect.height())
+        };
+        painter.rect_stroke(response.rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+
+        let (n, _) = solver.shape();
+        let activations = solver.activations();
+        let order: Vec<usize> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .max_by(|&x, &y| activations[x * n + i].total_cmp(&activations[y * n + i]))
+                    .unwrap_or(0)
+            })
+            .collect();
+        let valid = solver.decode_tour().is_some();
+        let stroke = egui::Stroke::new(2.0, if valid { egui::Color32::GREEN } else { egui::Color32::from_rgb(240, 140, 60) });
+
+        for i in 0..n {
+            let (a, b) = (order[i], order[(i + 1) % n]);
+            if let (Some(&p), Some(&q)) = (self.cities.get(a), self.cities.get(b)) {
+                painter.line_segment([to_screen(p), to_screen(q)], stroke);
+            }
+        }
+        for (index, &city) in self.cities.iter().enumerate() {
+            let pos = to_screen(city);
+            painter.circle_filled(pos, 5.0, egui::Color32::WHITE);
+            painter.text(
+                pos + egui::vec2(7.0, -7.0),
+                egui::Align2::LEFT_BOTTOM,
+                index.to_string(),
+                egui::FontId::proportional(12.0),
+                ui.visuals().text_color(),
+            );
+        }
+    }
+
+    /// Draw the graph with each vertex filled in its most active color
+    fn draw_coloring(&self, ui: &mut egui::Ui, solver: &HopfieldTankSolver) {
+        let (response, painter) = ui.allocate_painter(egui::vec2(400.0, 400.0), egui::Sense::hover());
+        let colors = solver.row_winners();
+        let fills: Vec<egui::Color32> = colors.iter().map(|&c| PALETTE[c % PALETTE.len()]).collect();
+        let labels: Vec<String> = colors.iter().map(|c| c.to_string()).collect();
+
+        network::draw_network(
+            &painter,
+            response.rect.min,
+            &self.node_positions,
+            &self.graph_edges,
+            &labels,
+            &fills,
+            NetworkStyle { vertex_radius: 12.0, edge_thickness: 2.0 },
+        );
+
+        // Mark edges whose endpoints share a color
+        for &(u, v) in &self.graph_edges {
+            if colors[u] == colors[v] {
+                if let (Some(&a), Some(&b)) = (self.node_positions.get(u), self.node_positions.get(v)) {
+                    let mid = response.rect.min + (a + b) / 2.0;
+                    painter.circle_filled(mid, 4.0, egui::Color32::RED);
+                }
+            }
+        }
+    }
+
+    /// Draw the activation matrix (rows × columns) as gray levels
+    fn draw_activations(ui: &mut egui::Ui, solver: &HopfieldTankSolver) {
+        let (rows, columns) = solver.shape();
+        let cell = (200.0 / rows.max(columns) as f32).max(4.0);
+        let (response, painter) = ui.allocate_painter(
+            egui::vec2(columns as f32 * cell, rows as f32 * cell),
+            egui::Sense::hover(),
+        );
+        for (index, &v) in solver.activations().iter().enumerate() {
+            let (r, c) = (index / columns, index % columns);
+            let level = (255.0 * (1.0 - v.clamp(0.0, 1.0))) as u8;
+            let min = response.rect.min + egui::vec2(c as f32 * cell, r as f32 * cell);
+            painter.rect_filled(
+                egui::Rect::from_min_size(min, egui::vec2(cell, cell)),
+                0.0,
+                egui::Color32::from_gray(level),
+            );
+        }
+        painter.rect_stroke(response.rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+    }
+}
+
+impl Window for HopfieldTankWindow {
+    fn name(&self) -> &str {
+        "Hopfield-Tank Optimizer"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Train => self.create_solver(),
+            Command::Step if self.solver.is_some() => self.advance(1),
+            Command::Run if self.solver.is_some() => self.running = !self.running,
+            Command::Reset if self.solver.is_some() => self.restart(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Problem");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Type:");
+            let tsp = ui.radio_value(&mut self.problem_kind, ProblemKind::Tsp, "TSP").changed();
+            let coloring = ui.radio_value(&mut self.problem_kind, ProblemKind::GraphColoring, "Graph Coloring").changed();
+            if tsp || coloring {
+                self.load_defaults();
+            }
+        });
+
+        match self.problem_kind {
+            ProblemKind::Tsp => {
+                ui.horizontal(|ui| {
+                    ui.label("Cities:");
+                    ui.add(egui::DragValue::new(&mut self.num_cities).speed(1.0).range(3..=20));
+                    if ui.button("New Cities").clicked() {
+                        self.randomize_cities();
+                    }
+                });
+            },
+            ProblemKind::GraphColoring => {
+                ui.horizontal(|ui| {
+                    ui.label("Graph:");
+                    ui.radio_value(&mut self.graph_type, GraphType::Grid, "Grid");
+                    ui.radio_value(&mut self.graph_type, GraphType::Cycle, "Cycle");
+                    ui.radio_value(&mut self.graph_type, GraphType::Complete, "Complete");
+                    ui.radio_value(&mut self.graph_type, GraphType::Star, "Star");
+                });
+                if self.graph_type == GraphType::Grid {
+                    ui.horizontal(|ui| {
+                        ui.label("Width:");
+                        ui.add(egui::DragValue::new(&mut self.grid_width).speed(1.0).range(2..=10));
+                        ui.label("Height:");
+                        ui.add(egui::DragValue::new(&mut self.grid_height).speed(1.0).range(2..=10));
+                    });
+                } else {
+                    ui.horizontal(|ui| {
+                        ui.label("Vertices:");
+                        ui.add(egui::DragValue::new(&mut self.graph_size).speed(1.0).range(3..=30));
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Colors:");
+                    ui.add(egui::DragValue::new(&mut self.num_colors).speed(1.0).range(1..=PALETTE.len()));
+                });
+            },
+        }
+
+        ui.separator();
+        ui.heading("Network Parameters");
+        ui.separator();
+
+        egui::Grid::new("hopfield_tank_penalties").show(ui, |ui| {
+            ui.label("A:");
+            ui.add(egui::DragValue::new(&mut self.penalties.a).speed(1.0).range(0.0..=10_000.0));
+            ui.label("B:");
+            ui.add_enabled(
+                self.problem_kind == ProblemKind::Tsp,
+                egui::DragValue::new(&mut self.penalties.b).speed(1.0).range(0.0..=10_000.0),
+            );
+            ui.end_row();
+            ui.label("C:");
+            ui.add(egui::DragValue::new(&mut self.penalties.c).speed(1.0).range(0.0..=10_000.0));
+            ui.label("D:");
+            ui.add(egui::DragValue::new(&mut self.penalties.d).speed(1.0).range(0.0..=10_000.0));
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Gain u0:");
+            ui.add(egui::DragValue::new(&mut self.gain).speed(0.001).range(0.001..=10.0));
+            ui.label("dt:");
+            ui.add(egui::DragValue::new(&mut self.dt).speed(1e-6).range(1e-7..=0.1));
+        });
+        ui.add(egui::Slider::new(&mut self.noise, 0.0..=2.0).text("Initial Noise"));
+
+        ui.horizontal(|ui| {
+            if ui.button("Build Network").clicked() {
+                self.create_solver();
+            }
+            if ui.button("Defaults").clicked() {
+                self.load_defaults();
+            }
+        });
+
+        ui.separator();
+
+        if self.solver.is_some() {
+            ui.heading("Relaxation");
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Steps per Frame:");
+                ui.add(egui::DragValue::new(&mut self.steps_per_frame).speed(1.0).range(1..=1000));
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.running, "Run");
+                if ui.button("Step").clicked() {
+                    self.advance(1);
+                }
+                if ui.button("Restart").on_hover_text("Start again from new random potentials").clicked() {
+                    self.restart();
+                }
+            });
+        } else {
+            ui.label("Build the network first.");
+        }
+
+    }
+
+    fn on_close(&mut self) {
+        self.running = false;
+    }
+
+    fn tick(&mut self, _dt: f64) -> bool {
+        if !self.running || self.solver.is_none() {
+            return false;
+        }
+        self.advance(self.steps_per_frame);
+        true
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        let Some(solver) = &self.solver else {
+            ui.vertical_centered(|ui| {
+                ui.label("No network built yet. Use the configuration panel to pose a problem.");
+            });
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| match solver.problem() {
+                OptimizationProblem::Tsp { .. } => {
+                    ui.label("Tour");
+                    self.draw_tsp(ui, solver);
+                    match solver.decode_tour() {
+                        Some(tour) => ui.colored_label(
+                            egui::Color32::GREEN,
+                            format!("Valid tour, length {:.3}", solver.tour_length(&tour)),
+                        ),
+                        None => ui.label("No valid tour yet"),
+                    };
+                },
+                OptimizationProblem::GraphColoring { .. } => {
+                    ui.label("Coloring");
+                    self.draw_coloring(ui, solver);
+                    if let Some((_, conflicts)) = solver.decode_coloring() {
+                        if conflicts == 0 {
+                            ui.colored_label(egui::Color32::GREEN, "Proper coloring");
+                        } else {
+                            ui.label(format!("{} conflicting edges", conflicts));
+                        }
+                    }
+                },
+            });
+
+            ui.vertical(|ui| {
+                ui.label("Activations (rows × columns)");
+                Self::draw_activations(ui, solver);
+                ui.label(format!("Energy: {:.4}", solver.energy()));
+                ui.label(format!("Steps: {}", solver.energy_history.len() - 1));
+            });
+        });
+
+        ui.separator();
+        ui.label("Penalty Terms:");
+        Plot::new("hopfield_tank_terms_plot")
+            .height(200.0)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                for (name, series) in self.term_names.iter().zip(&self.term_history) {
+                    let points: PlotPoints = series.iter().enumerate().map(|(i, &v)| [i as f64, v]).collect();
+                    plot_ui.line(Line::new(points).name(*name));
+                }
+            });
+    }
+}
@@ -0,0 +1,327 @@
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints, VLine};
+use rand::rngs::ThreadRng;
+
+use crate::neural::ising::{IsingDynamics, IsingModel, SweepPoint, CRITICAL_TEMPERATURE};
+use crate::ui::metrics::{self, Metric};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::dynamics;
+use crate::ui::widgets::grid::paint_grid;
+use crate::ui::widgets::visual::SpinGrid;
+use crate::ui::windows::Window;
+
+/// Quantity plotted against temperature after a temperature sweep
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SweepQuantity {
+    Magnetization,
+    Energy,
+    Susceptibility,
+    SpecificHeat,
+}
+
+pub struct IsingWindow {
+    /// The Ising model
+    model: Option<IsingModel>,
+
+    /// Lattice settings
+    width: usize,
+    height: usize,
+
+    /// Simulation parameters
+    sweeps_per_frame: usize,
+    running: bool,
+
+    /// Temperature sweep settings
+    sweep_min_temperature: f64,
+    sweep_max_temperature: f64,
+    sweep_num_temperatures: usize,
+    equilibration_sweeps: usize,
+    measurement_sweeps: usize,
+    sweep_results: Vec<SweepPoint>,
+    sweep_quantity: SweepQuantity,
+
+    /// Display settings
+    lattice_size_px: f32,
+
+    /// Random number generator for spin updates
+    rng: ThreadRng,
+}
+
+impl IsingWindow {
+    pub fn new() -> Self {
+        Self {
+            model: None,
+            width: 64,
+            height: 64,
+            sweeps_per_frame: 1,
+            running: false,
+            sweep_min_temperature: 1.0,
+            sweep_max_temperature: 4.0,
+            sweep_num_temperatures: 16,
+            equilibration_sweeps: 200,
+            measurement_sweeps: 200,
+            sweep_results: Vec::new(),
+            sweep_quantity: SweepQuantity::Magnetization,
+            lattice_size_px: 400.0,
+            rng: rand::thread_rng(),
+        }
+    }
+
+    /// Create a new lattice, keeping the physical parameters of the previous one
+    fn create_model(&mut self) {
+        match IsingModel::new(self.width, self.height) {
+            Ok(mut model) => {
+                if let Some(old) = &self.model {
+                    model.coupling = old.coupling;
+                    model.field = old.field;
+                    model.temperature = old.temperature;
+                    model.dynamics = old.dynamics;
+                }
+                model.randomize(&mut self.rng);
+                self.model = Some(model);
+                self.sweep_results.clear();
+            },
+            Err(e) => report_error(format!("Failed to create lattice: {}", e)),
+        }
+    }
+
+    /// Measure thermal averages over the configured temperature range
+    fn run_temperature_sweep(&mut self) {
+        let Some(model) = &mut self.model else {
+            return;
+        };
+
+        let n = self.sweep_num_temperatures.max(2);
+        let (t_min, t_max) = (self.sweep_min_temperature, self.sweep_max_temperature);
+        // Sweep from hot to cold so each temperature starts near equilibrium
+        let temperatures: Vec<f64> = (0..n)
+            .map(|k| t_max - (t_max - t_min) * k as f64 / (n - 1) as f64)
+            .collect();
+
+        match model.temperature_sweep(&temperatures, self.equilibration_sweeps, self.measurement_sweeps, &mut self.rng) {
+            Ok(mut results) => {
+                results.reverse();
+                self.sweep_results = results;
+            },
+            Err(e) => report_error(format!("Temperature sweep failed: {}", e)),
+        }
+    }
+
+    /// Draw the spin configuration in the grid colors (by default up spins black, down spins white)
+    fn draw_lattice(&self, ui: &mut egui::Ui, model: &IsingModel) {
+        let cell_size = self.lattice_size_px / model.width.max(model.height) as f32;
+        let size = egui::vec2(model.width as f32 * cell_size, model.height as f32 * cell_size);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let spins = SpinGrid { state: &model.spins, width: model.width };
+        paint_grid(&painter, response.rect.min, &spins, cell_size, None);
+        painter.rect_stroke(response.rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+    }
+}
+
+/// Line plot of a time series against its index
+fn series_line(values: &[f64], name: &str) -> Line {
+    let points: PlotPoints = values.iter().enumerate().map(|(i, &v)| [i as f64, v]).collect();
+    Line::new(points).name(name)
+}
+
+impl Window for IsingWindow {
+    fn name(&self) -> &str {
+        "Ising Model"
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Lattice Settings");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Width:");
+            ui.add(egui::DragValue::new(&mut self.width).speed(1.0).range(2..=256));
+            ui.label("Height:");
+            ui.add(egui::DragValue::new(&mut self.height).speed(1.0).range(2..=256));
+        });
+
+        if ui.button("Create Lattice").clicked() {
+            self.create_model();
+        }
+
+        ui.separator();
+
+        let Some(model) = &mut self.model else {
+            ui.label("Create a lattice first.");
+            return;
+        };
+
+        ui.heading("Physical Parameters");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Coupling J:");
+            ui.add(egui::DragValue::new(&mut model.coupling).speed(0.01).range(-2.0..=2.0));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Field h:");
+            ui.add(egui::DragValue::new(&mut model.field).speed(0.01).range(-2.0..=2.0));
+        });
+        ui.add(egui::Slider::new(&mut model.temperature, 0.0..=5.0).text("Temperature T"));
+        ui.label(format!("T_c ≈ {:.3} (J = 1, h = 0)", CRITICAL_TEMPERATURE));
+
+        ui.horizontal(|ui| {
+            ui.label("Dynamics:");
+            ui.radio_value(&mut model.dynamics, IsingDynamics::Glauber, "Glauber");
+            ui.radio_value(&mut model.dynamics, IsingDynamics::Metropolis, "Metropolis");
+        });
+
+        ui.separator();
+        ui.heading("Simulation");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Sweeps per Frame:");
+            ui.add(egui::DragValue::new(&mut self.sweeps_per_frame).speed(1.0).range(1..=100));
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.running, "Run");
+            if ui.button("Sweep").clicked() {
+                model.sweep(&mut self.rng);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Randomize").clicked() {
+                model.randomize(&mut self.rng);
+            }
+            if ui.button("All Up").clicked() {
+                model.fill(true);
+            }
+            if ui.button("All Down").clicked() {
+                model.fill(false);
+            }
+            if ui.button("Clear Plots").clicked() {
+                model.clear_history();
+            }
+        });
+
+        ui.separator();
+
+        ui.collapsing("Temperature Sweep", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("T from");
+                ui.add(egui::DragValue::new(&mut self.sweep_min_temperature).speed(0.05).range(0.05..=10.0));
+                ui.label("to");
+                ui.add(egui::DragValue::new(&mut self.sweep_max_temperature).speed(0.05).range(0.05..=10.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Temperatures:");
+                ui.add(egui::DragValue::new(&mut self.sweep_num_temperatures).speed(1.0).range(2..=100));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Equilibration Sweeps:");
+                ui.add(egui::DragValue::new(&mut self.equilibration_sweeps).speed(10.0).range(0..=10_000));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Measurement Sweeps:");
+                ui.add(egui::DragValue::new(&mut self.measurement_sweeps).speed(10.0).range(1..=10_000));
+            });
+            if ui.button("Run Temperature Sweep")
+                .on_hover_text("Runs to completion; large lattices may take a while")
+                .clicked()
+            {
+                self.run_temperature_sweep();
+            }
+        });
+
+    }
+
+    fn on_close(&mut self) {
+        self.running = false;
+    }
+
+    fn tick(&mut self, _dt: f64) -> bool {
+        if !self.running {
+            return false;
+        }
+        let Some(model) = &mut self.model else {
+            return false;
+        };
+        dynamics::advance(model, self.sweeps_per_frame, &mut self.rng);
+        metrics::publish(Metric::Energy, model.energy_per_spin());
+        metrics::publish(Metric::Magnetization, model.magnetization());
+        true
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        let Some(model) = &self.model else {
+            ui.vertical_centered(|ui| {
+                ui.label("No lattice created yet. Use the configuration panel to create one.");
+            });
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Spin Configuration");
+                self.draw_lattice(ui, model);
+                ui.label(format!(
+                    "m = {:.3}   e = {:.3}   sweeps = {}",
+                    model.magnetization(),
+                    model.energy_per_spin(),
+                    model.magnetization_history.len() - 1
+                ));
+            });
+
+            ui.vertical(|ui| {
+                ui.label("Magnetization per Spin");
+                Plot::new("ising_magnetization_plot")
+                    .width(300.0)
+                    .height(self.lattice_size_px / 2.0)
+                    .include_y(-1.0)
+                    .include_y(1.0)
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(series_line(&model.magnetization_history, "m"));
+                    });
+
+                ui.label("Energy per Spin");
+                Plot::new("ising_energy_plot")
+                    .width(300.0)
+                    .height(self.lattice_size_px / 2.0)
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(series_line(&model.energy_history, "e"));
+                    });
+            });
+        });
+
+        if !self.sweep_results.is_empty() {
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Temperature Sweep:");
+                ui.radio_value(&mut self.sweep_quantity, SweepQuantity::Magnetization, "<|m|>");
+                ui.radio_value(&mut self.sweep_quantity, SweepQuantity::Energy, "<e>");
+                ui.radio_value(&mut self.sweep_quantity, SweepQuantity::Susceptibility, "χ");
+                ui.radio_value(&mut self.sweep_quantity, SweepQuantity::SpecificHeat, "C");
+            });
+
+            let quantity = self.sweep_quantity;
+            let points: PlotPoints = self.sweep_results
+                .iter()
+                .map(|p| {
+                    let value = match quantity {
+                        SweepQuantity::Magnetization => p.magnetization,
+                        SweepQuantity::Energy => p.energy,
+                        SweepQuantity::Susceptibility => p.susceptibility,
+                        SweepQuantity::SpecificHeat => p.specific_heat,
+                    };
+                    [p.temperature, value]
+                })
+                .collect();
+
+            Plot::new("ising_temperature_sweep_plot")
+                .height(200.0)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    plot_ui.line(Line::new(points).name("measured"));
+                    plot_ui.vline(VLine::new(CRITICAL_TEMPERATURE).name("T_c"));
+                });
+        }
+    }
+}
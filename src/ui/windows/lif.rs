@@ -0,0 +1,333 @@
+use eframe::egui;
+use egui_plot::{Legend, Line, MarkerShape, Plot, PlotPoints, Points};
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+use crate::core::metrics::overlap;
+use crate::neural::hopfield::{HopfieldNetwork, TrainingRule};
+use crate::neural::lif::{LifLattice, LifParameters};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::grid::{apply_noise, draw_grid};
+use crate::ui::windows::Window;
+
+/// Simulated time shown in the raster and overlap plots, in ms
+const PLOT_WINDOW_MS: f64 = 1000.0;
+
+pub struct LifWindow {
+    /// The spiking lattice and the Hopfield network its coupling comes from
+    lattice: Option<LifLattice>,
+
+    /// Random patterns (±1, grid_size × grid_size) stored in the coupling
+    grid_size: usize,
+    num_patterns: usize,
+    patterns: Vec<Vec<f64>>,
+    training_rule: TrainingRule,
+
+    /// Membrane and coupling parameters, applied to the lattice as they change
+    params: LifParameters,
+
+    /// Cue settings
+    selected_pattern: usize,
+    cue_noise: f32,
+    cue_strength: f64,
+    cue_duration: f64,
+    cue_remaining: f64, // ms until the applied cue is removed
+
+    /// Simulation
+    running: bool,
+    ms_per_frame: f64,
+
+    /// Overlap of the activity with each pattern over time (ms, overlap)
+    overlap_history: Vec<Vec<[f64; 2]>>,
+
+    /// Random number generator for patterns, cues and input noise
+    rng: ThreadRng,
+}
+
+impl LifWindow {
+    pub fn new() -> Self {
+        let mut window = Self {
+            lattice: None,
+            grid_size: 10,
+            num_patterns: 3,
+            patterns: Vec::new(),
+            training_rule: TrainingRule::Hebbian,
+            params: LifParameters::default(),
+            selected_pattern: 0,
+            cue_noise: 0.2,
+            cue_strength: 5.0,
+            cue_duration: 200.0,
+            cue_remaining: 0.0,
+            running: false,
+            ms_per_frame: 10.0,
+            overlap_history: Vec::new(),
+            rng: rand::thread_rng(),
+        };
+        window.generate_patterns();
+        window
+    }
+
+    /// Draw fresh random patterns; invalidates the lattice
+    fn generate_patterns(&mut self) {
+        let n = self.grid_size * self.grid_size;
+        self.patterns = (0..self.num_patterns)
+            .map(|_| (0..n).map(|_| if self.rng.gen::<bool>() { 1.0 } else { -1.0 }).collect())
+            .collect();
+        self.selected_pattern = self.selected_pattern.min(self.num_patterns.saturating_sub(1));
+        self.lattice = None;
+        self.running = false;
+    }
+
+    /// Train a Hopfield network on the patterns and build the lattice from its weights
+    fn build_lattice(&mut self) {
+        let size = self.grid_size;
+        let mut network = match HopfieldNetwork::try_new(size * size) {
+            Ok(network) => network,
+            Err(e) => {
+                report_error(e);
+                return;
+            },
+        };
+        if let Err(e) = network.train(&self.patterns, self.training_rule) {
+            report_error(format!("Training failed: {}", e));
+            return;
+        }
+        match LifLattice::from_hopfield(size, size, &network, self.params) {
+            Ok(mut lattice) => {
+                lattice.history_ms = PLOT_WINDOW_MS;
+                lattice.reset(&mut self.rng);
+                self.lattice = Some(lattice);
+                self.cue_remaining = 0.0;
+                self.overlap_history = vec![Vec::new(); self.patterns.len()];
+            },
+            Err(e) => report_error(format!("Failed to create lattice: {}", e)),
+        }
+    }
+
+    /// Drive the lattice with a noisy copy of the selected pattern for `cue_duration` ms
+    fn apply_cue(&mut self) {
+        let Some(lattice) = &mut self.lattice else {
+            return;
+        };
+        let Some(pattern) = self.patterns.get(self.selected_pattern) else {
+            return;
+        };
+        let cue = apply_noise(pattern, self.cue_noise, &mut self.rng);
+        match lattice.set_cue(&cue, self.cue_strength) {
+            Ok(()) => self.cue_remaining = self.cue_duration,
+            Err(e) => report_error(format!("Failed to apply cue: {}", e)),
+        }
+    }
+
+    /// Simulate `duration` ms, removing the cue when it runs out and recording overlaps
+    fn advance(&mut self, duration: f64) {
+        let Some(lattice) = &mut self.lattice else {
+            return;
+        };
+        lattice.params = self.params;
+        let steps = (duration / self.params.dt).round().max(1.0) as usize;
+        for _ in 0..steps {
+            lattice.step(&mut self.rng);
+            if self.cue_remaining > 0.0 {
+                self.cue_remaining -= self.params.dt;
+                if self.cue_remaining <= 0.0 {
+                    lattice.clear_cue();
+                }
+            }
+        }
+
+        let state = lattice.activity_state();
+        let time = lattice.time;
+        for (history, pattern) in self.overlap_history.iter_mut().zip(&self.patterns) {
+            history.push([time, overlap(&state, pattern)]);
+            history.retain(|p| p[0] >= time - PLOT_WINDOW_MS);
+        }
+    }
+
+    /// Membrane potentials from reset (dark) to threshold (bright); neurons that just
+    /// fired are drawn white
+    fn draw_potentials(&self, ui: &mut egui::Ui, lattice: &LifLattice, cell_size: f32) {
+        let size = egui::vec2(lattice.width as f32 * cell_size, lattice.height as f32 * cell_size);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let rect = response.rect;
+        let p = &lattice.params;
+        let recent = lattice.time - 2.0 * p.dt;
+        let mut fired = vec![false; lattice.num_neurons()];
+        for spike in lattice.spikes.iter().rev().take_while(|s| s.time > recent) {
+            fired[spike.neuron] = true;
+        }
+
+        for (i, &v) in lattice.potentials.iter().enumerate() {
+            let color = if fired[i] {
+                egui::Color32::WHITE
+            } else {
+                let t = ((v - p.v_reset) / (p.v_threshold - p.v_reset)).clamp(0.0, 1.0) as f32;
+                egui::Color32::from_rgb((40.0 + 200.0 * t) as u8, (40.0 + 80.0 * t) as u8, (120.0 - 80.0 * t) as u8)
+            };
+            let min = rect.min + egui::vec2((i % lattice.width) as f32 * cell_size, (i / lattice.width) as f32 * cell_size);
+            painter.rect_filled(egui::Rect::from_min_size(min, egui::vec2(cell_size, cell_size)), 0.0, color);
+        }
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+    }
+}
+
+impl Window for LifWindow {
+    fn name(&self) -> &str {
+        "Spiking Network"
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Patterns");
+        ui.separator();
+
+        let mut patterns_changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Grid Size:");
+            patterns_changed |= ui.add(egui::DragValue::new(&mut self.grid_size).speed(0.2).range(4..=20)).changed();
+            ui.label("Patterns:");
+            patterns_changed |= ui.add(egui::DragValue::new(&mut self.num_patterns).speed(0.1).range(1..=8)).changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label("Coupling From:");
+            ui.radio_value(&mut self.training_rule, TrainingRule::Hebbian, "Hebbian");
+            ui.radio_value(&mut self.training_rule, TrainingRule::PseudoInverse, "Pseudo-Inverse");
+            ui.radio_value(&mut self.training_rule, TrainingRule::Storkey, "Storkey");
+        });
+        if ui.button("New Patterns").clicked() || patterns_changed {
+            self.generate_patterns();
+        }
+        if ui.button("Build Lattice").on_hover_text("Train a Hopfield network and couple the neurons by its weights").clicked() {
+            self.build_lattice();
+        }
+
+        ui.separator();
+        ui.heading("Neurons");
+        ui.separator();
+
+        let p = &mut self.params;
+        ui.add(egui::Slider::new(&mut p.tau_membrane, 1.0..=100.0).text("τ_m (ms)"));
+        ui.add(egui::Slider::new(&mut p.drive, 0.0..=30.0).text("Drive (mV)"))
+            .on_hover_text(format!("Neurons fire without input above {:.0} mV", p.v_threshold - p.v_rest));
+        ui.add(egui::Slider::new(&mut p.noise, 0.0..=5.0).text("Noise (mV/√ms)"));
+        ui.add(egui::Slider::new(&mut p.coupling_gain, 0.0..=10.0).text("Coupling (mV)"))
+            .on_hover_text("Potential jump caused by a spike through the strongest weight");
+        ui.add(egui::Slider::new(&mut p.refractory, 0.0..=10.0).text("Refractory (ms)"));
+        ui.horizontal(|ui| {
+            ui.label("Time Step (ms):");
+            ui.add(egui::DragValue::new(&mut p.dt).speed(0.01).range(0.01..=1.0));
+        });
+
+        ui.separator();
+        ui.heading("Simulation");
+        ui.separator();
+
+        ui.add_enabled_ui(self.lattice.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("ms per Frame:");
+                ui.add(egui::DragValue::new(&mut self.ms_per_frame).speed(0.5).range(0.5..=100.0));
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.running, "Run");
+                if ui.button("Step 10 ms").clicked() {
+                    self.advance(10.0);
+                }
+                if ui.button("Reset").clicked() {
+                    if let Some(lattice) = &mut self.lattice {
+                        lattice.reset(&mut self.rng);
+                        lattice.clear_cue();
+                    }
+                    self.cue_remaining = 0.0;
+                    self.overlap_history.iter_mut().for_each(Vec::clear);
+                }
+            });
+
+            ui.separator();
+            ui.label("Cue:");
+            ui.horizontal(|ui| {
+                ui.label("Pattern:");
+                ui.add(egui::DragValue::new(&mut self.selected_pattern).speed(0.1).range(0..=self.num_patterns - 1));
+            });
+            ui.add(egui::Slider::new(&mut self.cue_noise, 0.0..=0.5).text("Noise"));
+            ui.add(egui::Slider::new(&mut self.cue_strength, 0.0..=20.0).text("Strength (mV)"));
+            ui.add(egui::Slider::new(&mut self.cue_duration, 10.0..=1000.0).text("Duration (ms)"));
+            if ui.button("Apply Cue").clicked() {
+                self.apply_cue();
+            }
+        });
+
+    }
+
+    fn on_close(&mut self) {
+        self.running = false;
+    }
+
+    fn tick(&mut self, _dt: f64) -> bool {
+        if !self.running || self.lattice.is_none() {
+            return false;
+        }
+        self.advance(self.ms_per_frame);
+        true
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        let Some(lattice) = &self.lattice else {
+            ui.vertical_centered(|ui| {
+                ui.label("No lattice built yet. Use the configuration panel to build one.");
+            });
+            return;
+        };
+
+        let cell_size = 240.0 / self.grid_size as f32;
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Membrane Potentials");
+                self.draw_potentials(ui, lattice, cell_size);
+                ui.label(format!(
+                    "t = {:.0} ms   mean rate = {:.1} Hz{}",
+                    lattice.time,
+                    lattice.mean_rate(),
+                    if lattice.is_cued() { "   (cued)" } else { "" }
+                ));
+            });
+            ui.vertical(|ui| {
+                ui.label("Activity (above mean rate)");
+                draw_grid(ui, &lattice.activity_state(), self.grid_size, self.grid_size, cell_size);
+            });
+            if let Some(pattern) = self.patterns.get(self.selected_pattern) {
+                ui.vertical(|ui| {
+                    ui.label(format!("Pattern {}", self.selected_pattern));
+                    draw_grid(ui, pattern, self.grid_size, self.grid_size, cell_size);
+                });
+            }
+        });
+
+        ui.separator();
+        ui.label("Spike Raster");
+        let start = lattice.time - PLOT_WINDOW_MS;
+        let raster: PlotPoints = lattice.spikes.iter().map(|s| [s.time, s.neuron as f64]).collect();
+        Plot::new("lif_raster_plot")
+            .height(220.0)
+            .include_x(start.max(0.0))
+            .include_x(lattice.time)
+            .include_y(0.0)
+            .include_y(lattice.num_neurons() as f64)
+            .x_axis_label("t (ms)")
+            .y_axis_label("Neuron")
+            .show(ui, |plot_ui| {
+                plot_ui.points(Points::new(raster).shape(MarkerShape::Square).radius(1.0).color(egui::Color32::LIGHT_BLUE));
+            });
+
+        ui.label("Overlap of the Activity with Each Pattern");
+        Plot::new("lif_overlap_plot")
+            .height(160.0)
+            .legend(Legend::default())
+            .include_y(-1.0)
+            .include_y(1.0)
+            .x_axis_label("t (ms)")
+            .show(ui, |plot_ui| {
+                for (k, history) in self.overlap_history.iter().enumerate() {
+                    plot_ui.line(Line::new(PlotPoints::from(history.clone())).name(format!("Pattern {}", k)));
+                }
+            });
+    }
+}
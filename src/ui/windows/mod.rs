@@ -1,8 +1,41 @@
 pub mod hopfield;
 pub mod chip_firing;
+pub mod rotor_router;
+pub mod sandpile;
+pub mod sandpile_memory;
+pub mod ising;
+pub mod cellular;
+pub mod hopfield_tank;
+pub mod boltzmann;
+pub mod bam;
+pub mod potts;
+pub mod lif;
+pub mod gray_scott;
+pub mod graph_analysis;
+pub mod performance;
+pub mod script_console;
+pub mod sweeps;
+pub mod dashboard;
 
 use eframe::egui;
 
+use crate::ui::events::AppEvent;
+use crate::ui::session::{self, SessionAction};
+use crate::ui::undo::Snapshot;
+
+/// Actions that keyboard shortcuts dispatch to the focused window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Advance the simulation by one step
+    Step,
+    /// Run the simulation (until stable or for the configured length)
+    Run,
+    /// Reset the simulation state
+    Reset,
+    /// Train or build the model
+    Train,
+}
+
 /// Common trait for application windows
 pub trait Window {
     /// Draws the main content of the window
@@ -13,4 +46,120 @@ pub trait Window {
     
     /// Returns the window name
     fn name(&self) -> &str;
+    
+    /// Performs a keyboard shortcut command while the window has focus.
+    ///
+    /// # Returns
+    ///
+    /// true if the window supports the command
+    fn handle_command(&mut self, _command: Command) -> bool {
+        false
+    }
+    
+    /// Called when the window is opened, before it is first drawn
+    fn on_open(&mut self) {}
+    
+    /// Called when the window is closed. Windows should stop continuous simulations and
+    /// release buffers that can be rebuilt when they are opened again.
+    fn on_close(&mut self) {}
+    
+    /// Advances continuous simulations by `dt` seconds of wall-clock time. Called once
+    /// per frame for open windows, before any window is drawn.
+    ///
+    /// # Returns
+    ///
+    /// true if the window is animating and needs another frame
+    fn tick(&mut self, _dt: f64) -> bool {
+        false
+    }
+
+    /// Settings that affect the model, as (key, value) pairs recorded in session journals
+    /// whenever they change. A "seed" setting is applied when a recording starts, so the
+    /// random generator restarts from it.
+    fn session_settings(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// Repeats an action from a session journal. Commands go to `handle_command`; windows
+    /// that record settings or other actions apply them here.
+    ///
+    /// # Returns
+    ///
+    /// true if the window performed the action
+    fn replay(&mut self, action: &SessionAction) -> bool {
+        match action {
+            SessionAction::Command(command) => self.handle_command(*command),
+            _ => false,
+        }
+    }
+
+    /// Reacts to an event emitted by another open window (see `ui::events`). Called at
+    /// the start of the frame after the event was emitted, before any window is drawn.
+    fn on_event(&mut self, _event: &AppEvent) {}
+
+    /// Puts back model state captured with `undo::checkpoint` before a destructive
+    /// operation, when the user chooses Edit → Undo.
+    ///
+    /// # Returns
+    ///
+    /// true if the snapshot was restored
+    fn restore(&mut self, _snapshot: Snapshot) -> bool {
+        false
+    }
+
+    /// Whether a background job owns the model, so replayed actions have to wait
+    fn is_busy(&self) -> bool {
+        false
+    }
+
+    /// Named starting configurations offered in the window's Presets menu, as
+    /// (name, description) pairs
+    fn presets(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Replaces the model with the preset `name` from `presets`. Loading is recorded in
+    /// session journals as the action "preset", which windows replay through this method.
+    ///
+    /// # Returns
+    ///
+    /// true if the preset was loaded
+    fn load_preset(&mut self, _name: &str) -> bool {
+        false
+    }
+
+    /// Draws the whole window: the configuration in a resizable panel on the left and
+    /// the content beside it. The configuration is drawn first, so changes made there
+    /// are visible in the same frame.
+    fn show(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let presets = self.presets();
+        if !presets.is_empty() {
+            egui::TopBottomPanel::top(ui.id().with("presets_bar")).show_inside(ui, |ui| {
+                egui::menu::bar(ui, |ui| {
+                    ui.menu_button("Presets", |ui| {
+                        for &(name, description) in presets {
+                            if ui.button(name).on_hover_text(description).clicked() {
+                                session::record(self.name(), SessionAction::perform("preset", name));
+                                self.load_preset(name);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
+            });
+        }
+        // Scoped to the egui window, so instances of one window type keep their own panels
+        let panel_id = ui.id().with("config_panel");
+        egui::SidePanel::left(panel_id)
+            .resizable(true)
+            .default_width(260.0)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    self.show_config(ui);
+                });
+            });
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            self.show_content(ctx, ui);
+        });
+    }
 }
\ No newline at end of file
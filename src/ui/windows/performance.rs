@@ -0,0 +1,267 @@
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+use crate::core::job::{BackgroundJob, JobStatus};
+use crate::graphics::compute::GpuContext;
+use crate::graphics::pipeline::Pipeline;
+use crate::neural::hopfield::gpu::GpuHopfieldSweep;
+use crate::neural::hopfield::{HopfieldNetwork, TrainingRule};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::job::job_progress;
+use crate::ui::widgets::seed::seed_control;
+use crate::ui::windows::{Command, Window};
+
+/// Largest network benchmarked; its weights take 64 MiB in single precision
+const MAX_NEURONS: usize = 4096;
+
+/// Patterns stored in each benchmarked network
+const BENCHMARK_PATTERNS: usize = 3;
+
+/// Result of the GPU half of a measurement
+#[derive(Debug, Clone)]
+struct GpuMeasurement {
+    /// Sweeps per second, including the state upload and readback
+    rate: f64,
+    /// Neurons whose final state differs from the CPU result
+    mismatches: usize,
+}
+
+/// Throughput of both backends for one network size
+#[derive(Debug, Clone)]
+struct BenchmarkPoint {
+    neurons: usize,
+    /// Sweeps per second on the CPU
+    cpu_rate: f64,
+    /// None without a GPU, an error message if the kernel failed
+    gpu: Option<Result<GpuMeasurement, String>>,
+}
+
+/// Sweeps per second of `sweeps` sweeps taking `seconds`
+fn rate(sweeps: usize, seconds: f64) -> f64 {
+    sweeps as f64 / seconds.max(1e-9)
+}
+
+/// Measures both backends on a Hebbian network of `n` neurons, in single precision
+fn measure(n: usize, sweeps: usize, gpu: Option<&GpuContext>, rng: &mut StdRng) -> BenchmarkPoint {
+    let random_state = |rng: &mut StdRng| -> Vec<f64> { (0..n).map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 }).collect() };
+    let patterns: Vec<Vec<f64>> = (0..BENCHMARK_PATTERNS).map(|_| random_state(rng)).collect();
+    let mut network = HopfieldNetwork::new(n);
+    let _ = network.train(&patterns, TrainingRule::Hebbian);
+    let network = network.to_precision::<f32>();
+    let cue = random_state(rng);
+
+    let start = Instant::now();
+    let mut cpu_state = cue.clone();
+    for _ in 0..sweeps {
+        cpu_state = network.update_step_deterministic(&cpu_state).unwrap_or(cpu_state);
+    }
+    let cpu_rate = rate(sweeps, start.elapsed().as_secs_f64());
+
+    let gpu = gpu.map(|context| {
+        let mut kernel = GpuHopfieldSweep::new(context, &network, 1).map_err(|e| e.to_string())?;
+        // The first execution includes compiling the shader
+        kernel.execute(&cue).map_err(|e| e.to_string())?;
+        kernel.sweeps = sweeps;
+        let start = Instant::now();
+        let gpu_state = kernel.execute(&cue).map_err(|e| e.to_string())?;
+        let rate = rate(sweeps, start.elapsed().as_secs_f64());
+        let mismatches = gpu_state.iter().zip(&cpu_state).filter(|(a, b)| a != b).count();
+        Ok(GpuMeasurement { rate, mismatches })
+    });
+    BenchmarkPoint { neurons: n, cpu_rate, gpu }
+}
+
+/// Window comparing identical zero-temperature Hopfield sweeps on the CPU and on the GPU
+/// over a range of network sizes
+pub struct PerformanceWindow {
+    /// Device of the egui renderer, None on backends without wgpu
+    gpu: Option<GpuContext>,
+
+    /// Benchmark settings
+    sizes: String, // Comma-separated numbers of neurons
+    sweeps: usize,
+    seed: u64,
+
+    job: Option<BackgroundJob<Vec<BenchmarkPoint>>>,
+    results: Vec<BenchmarkPoint>,
+}
+
+impl PerformanceWindow {
+    pub fn new(gpu: Option<GpuContext>) -> Self {
+        Self {
+            gpu,
+            sizes: "64, 256, 1024, 2048, 4096".to_string(),
+            sweeps: 20,
+            seed: rand::random::<u32>() as u64,
+            job: None,
+            results: Vec::new(),
+        }
+    }
+
+    fn start_benchmark(&mut self) {
+        let sizes: Result<Vec<usize>, _> = self
+            .sizes
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse::<usize>)
+            .collect();
+        let sizes = match sizes {
+            Ok(sizes) if !sizes.is_empty() && sizes.iter().all(|&n| (1..=MAX_NEURONS).contains(&n)) => sizes,
+            _ => {
+                report_error(format!("Enter network sizes between 1 and {} separated by commas", MAX_NEURONS));
+                return;
+            },
+        };
+
+        let gpu = self.gpu.clone();
+        let sweeps = self.sweeps;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        self.job = Some(BackgroundJob::spawn("Benchmark", sizes.len(), move |ctx| {
+            let mut points = Vec::with_capacity(sizes.len());
+            for (i, &n) in sizes.iter().enumerate() {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                points.push(measure(n, sweeps, gpu.as_ref(), &mut rng));
+                ctx.report(i + 1);
+            }
+            points
+        }));
+    }
+
+    fn poll_job(&mut self) {
+        let Some(job) = &mut self.job else {
+            return;
+        };
+        match job.poll() {
+            JobStatus::Running => {},
+            JobStatus::Finished(points) => {
+                self.results = points;
+                self.job = None;
+            },
+            JobStatus::Failed => {
+                report_error("The benchmark stopped unexpectedly");
+                self.job = None;
+            },
+        }
+    }
+}
+
+impl Window for PerformanceWindow {
+    fn name(&self) -> &str {
+        "Performance"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Run if self.job.is_none() => self.start_benchmark(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Backends");
+        ui.separator();
+        ui.label("CPU: single precision sweeps");
+        match &self.gpu {
+            Some(gpu) => ui.label(format!("GPU: {}", gpu.adapter_name)),
+            None => ui.label("GPU: unavailable (the renderer is not using wgpu)"),
+        };
+
+        ui.separator();
+        ui.heading("Benchmark");
+        ui.separator();
+        ui.add_enabled_ui(self.job.is_none(), |ui| {
+            ui.label("Network Sizes (neurons):");
+            ui.text_edit_singleline(&mut self.sizes);
+            ui.horizontal(|ui| {
+                ui.label("Sweeps:");
+                ui.add(egui::DragValue::new(&mut self.sweeps).speed(1.0).range(1..=1000));
+            });
+            seed_control(ui, &mut self.seed);
+            if ui
+                .button("Run Benchmark")
+                .on_hover_text("Run the same zero-temperature synchronous sweeps of a Hebbian network on both backends")
+                .clicked()
+            {
+                self.start_benchmark();
+            }
+        });
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        ui.heading("CPU vs GPU Hopfield Sweeps");
+        ui.separator();
+
+        self.poll_job();
+        if let Some(job) = &self.job {
+            job_progress(ui, job);
+            ui.separator();
+        }
+        if self.results.is_empty() {
+            ui.label("Run the benchmark to compare the backends.");
+            return;
+        }
+
+        // Rates span orders of magnitude, so they are plotted on a log scale
+        let cpu: Vec<[f64; 2]> = self.results.iter().map(|p| [p.neurons as f64, p.cpu_rate.log10()]).collect();
+        let gpu: Vec<[f64; 2]> = self
+            .results
+            .iter()
+            .filter_map(|p| match &p.gpu {
+                Some(Ok(m)) => Some([p.neurons as f64, m.rate.log10()]),
+                _ => None,
+            })
+            .collect();
+        Plot::new("performance_plot")
+            .height(220.0)
+            .legend(Legend::default())
+            .x_axis_label("Neurons N")
+            .y_axis_label("log₁₀ sweeps / s")
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(PlotPoints::from(cpu.clone())).name("CPU"));
+                plot_ui.points(Points::new(PlotPoints::from(cpu)).radius(3.0).name("CPU"));
+                if !gpu.is_empty() {
+                    plot_ui.line(Line::new(PlotPoints::from(gpu.clone())).name("GPU"));
+                    plot_ui.points(Points::new(PlotPoints::from(gpu)).radius(3.0).name("GPU"));
+                }
+            });
+
+        ui.separator();
+        egui::Grid::new("performance_table").striped(true).num_columns(5).show(ui, |ui| {
+            for header in ["N", "CPU sweeps/s", "GPU sweeps/s", "Speedup", "Mismatched neurons"] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for point in &self.results {
+                ui.label(point.neurons.to_string());
+                ui.label(format!("{:.1}", point.cpu_rate));
+                match &point.gpu {
+                    Some(Ok(m)) => {
+                        ui.label(format!("{:.1}", m.rate));
+                        ui.label(format!("{:.2}×", m.rate / point.cpu_rate));
+                        ui.label(m.mismatches.to_string())
+                            .on_hover_text("Neurons whose final state differs from the CPU result; ties at zero field may round differently");
+                    },
+                    Some(Err(e)) => {
+                        ui.label("failed").on_hover_text(e);
+                        ui.label("—");
+                        ui.label("—");
+                    },
+                    None => {
+                        ui.label("—");
+                        ui.label("—");
+                        ui.label("—");
+                    },
+                }
+                ui.end_row();
+            }
+        });
+        ui.label("GPU rates include uploading the state and reading the result back.");
+    }
+}
@@ -0,0 +1,316 @@
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::neural::potts::{dequantize, quantize, randomize_sites, PottsNetwork};
+use crate::ui::notifications::report_error;
+use crate::ui::session::{self, SessionAction};
+use crate::ui::widgets::grid::draw_gray_grid;
+use crate::ui::widgets::seed::seed_control;
+use crate::ui::windows::{Command, Window};
+
+/// A grayscale image as the gray value at (x, y) in [0, 1]²
+type Image = fn(f64, f64) -> f64;
+
+/// Grayscale test images the network can store
+const IMAGES: [(&str, Image); 6] = [
+    ("Horizontal Gradient", |x, _| x),
+    ("Vertical Gradient", |_, y| 1.0 - y),
+    ("Radial", |x, y| 1.0 - 2.0 * ((x - 0.5).powi(2) + (y - 0.5).powi(2)).sqrt()),
+    ("Rings", |x, y| 0.5 + 0.5 * (18.0 * ((x - 0.5).powi(2) + (y - 0.5).powi(2)).sqrt()).cos()),
+    ("Diagonal Stripes", |x, y| ((x + y) * 3.0).fract()),
+    ("Quadrants", |x, y| match (x < 0.5, y < 0.5) {
+        (true, true) => 0.0,
+        (false, true) => 0.33,
+        (true, false) => 0.67,
+        (false, false) => 1.0,
+    }),
+];
+
+/// Side of the drawn grids in points
+const GRID_VIEW_SIZE: f32 = 160.0;
+
+/// Window for the q-state Potts generalization of the Hopfield network, storing
+/// quantized grayscale images
+pub struct PottsWindow {
+    /// Image settings
+    grid_size: usize,
+    num_states: usize,
+    selected_images: Vec<bool>,
+
+    /// Trained network and the quantized images it stores, with their names
+    network: Option<PottsNetwork>,
+    patterns: Vec<Vec<usize>>,
+    pattern_names: Vec<&'static str>,
+
+    /// Recall settings and the last run, cue first
+    target: usize,
+    noise_level: f64,
+    max_sweeps: usize,
+    run: Vec<Vec<usize>>,
+    display_sweep: usize,
+
+    seed: u64,
+    rng: StdRng,
+}
+
+impl PottsWindow {
+    pub fn new() -> Self {
+        let seed = rand::random::<u32>() as u64;
+        Self {
+            grid_size: 24,
+            num_states: 4,
+            selected_images: vec![true, false, true, false, true, false],
+            network: None,
+            patterns: Vec::new(),
+            pattern_names: Vec::new(),
+            target: 0,
+            noise_level: 0.3,
+            max_sweeps: 30,
+            run: Vec::new(),
+            display_sweep: 0,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Render the selected images at the grid size, quantize them and store them
+    fn train(&mut self) {
+        let size = self.grid_size;
+        let mut network = match PottsNetwork::try_new(size * size, self.num_states) {
+            Ok(network) => network,
+            Err(e) => {
+                report_error(e.to_string());
+                return;
+            },
+        };
+
+        let selected: Vec<_> = IMAGES.iter().zip(&self.selected_images).filter(|(_, &on)| on).map(|(image, _)| image).collect();
+        if selected.is_empty() {
+            report_error("Select at least one image to store");
+            return;
+        }
+        let patterns: Vec<Vec<usize>> = selected
+            .iter()
+            .map(|(_, image)| {
+                let gray: Vec<f64> = (0..size * size)
+                    .map(|i| image((i % size) as f64 / (size - 1).max(1) as f64, (i / size) as f64 / (size - 1).max(1) as f64))
+                    .collect();
+                quantize(&gray, self.num_states)
+            })
+            .collect();
+
+        if let Err(e) = network.train(&patterns) {
+            report_error(e.to_string());
+            return;
+        }
+        self.pattern_names = selected.iter().map(|(name, _)| *name).collect();
+        self.patterns = patterns;
+        self.network = Some(network);
+        self.target = self.target.min(self.patterns.len() - 1);
+        self.run.clear();
+        self.display_sweep = 0;
+    }
+
+    /// Randomize part of the target image and let the network restore it
+    fn recall(&mut self) {
+        let (Some(network), Some(target)) = (&self.network, self.patterns.get(self.target)) else {
+            return;
+        };
+        let cue = randomize_sites(target, self.num_states, self.noise_level, &mut self.rng);
+        match network.run(&cue, self.max_sweeps, &mut self.rng) {
+            Ok(run) => {
+                self.display_sweep = run.len() - 1;
+                self.run = run;
+            },
+            Err(e) => report_error(e.to_string()),
+        }
+    }
+
+    /// Apply a setting from a session journal
+    fn apply_session_setting(&mut self, key: &str, value: &str) -> bool {
+        if key == "images" {
+            self.selected_images = value.chars().map(|c| c == '1').collect();
+            self.selected_images.resize(IMAGES.len(), false);
+            return true;
+        }
+        if key == "noise_level" {
+            let Ok(noise_level) = value.parse() else { return false };
+            self.noise_level = noise_level;
+            return true;
+        }
+        let Ok(number) = value.parse::<u64>() else { return false };
+        match key {
+            "grid_size" => self.grid_size = number as usize,
+            "num_states" => self.num_states = number as usize,
+            "target" => self.target = number as usize,
+            "max_sweeps" => self.max_sweeps = number as usize,
+            "seed" => {
+                self.seed = number;
+                self.rng = StdRng::seed_from_u64(number);
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// A quantized state as gray values, drawn under `title`
+    fn draw_state(&self, ui: &mut egui::Ui, title: &str, state: Option<&Vec<usize>>) {
+        ui.vertical_centered(|ui| {
+            ui.label(title);
+            ui.separator();
+            let size = self.grid_size;
+            match state {
+                Some(state) if state.len() == size * size => {
+                    draw_gray_grid(ui, &dequantize(state, self.num_states), size, size, GRID_VIEW_SIZE / size as f32);
+                },
+                _ => {
+                    ui.label("—");
+                },
+            }
+        });
+    }
+}
+
+impl Window for PottsWindow {
+    fn name(&self) -> &str {
+        "Potts Network"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Train => self.train(),
+            Command::Run | Command::Step => self.recall(),
+            Command::Reset => {
+                self.run.clear();
+                self.display_sweep = 0;
+            },
+        }
+        true
+    }
+
+    fn session_settings(&self) -> Vec<(&'static str, String)> {
+        let images: String = self.selected_images.iter().map(|&on| if on { '1' } else { '0' }).collect();
+        vec![
+            ("grid_size", self.grid_size.to_string()),
+            ("num_states", self.num_states.to_string()),
+            ("images", images),
+            ("target", self.target.to_string()),
+            ("noise_level", self.noise_level.to_string()),
+            ("max_sweeps", self.max_sweeps.to_string()),
+            ("seed", self.seed.to_string()),
+        ]
+    }
+
+    fn replay(&mut self, action: &SessionAction) -> bool {
+        match action {
+            SessionAction::Command(command) => self.handle_command(*command),
+            SessionAction::Set { key, value } => self.apply_session_setting(key, value),
+            SessionAction::Perform { .. } => false,
+        }
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Images");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Grid Size:");
+            ui.add(egui::DragValue::new(&mut self.grid_size).speed(1.0).range(4..=64));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Gray Levels (q):");
+            ui.add(egui::DragValue::new(&mut self.num_states).speed(1.0).range(2..=16))
+                .on_hover_text("States per site; images are quantized to this many gray levels");
+        });
+        for ((name, _), selected) in IMAGES.iter().zip(&mut self.selected_images) {
+            ui.checkbox(selected, *name);
+        }
+        if ui.button("Train").on_hover_text("Store the selected images with the Potts Hebb rule").clicked() {
+            session::record(self.name(), SessionAction::Command(Command::Train));
+            self.train();
+        }
+
+        ui.separator();
+        ui.heading("Recall");
+        ui.separator();
+
+        ui.add_enabled_ui(self.network.is_some(), |ui| {
+            egui::ComboBox::from_id_source("potts_target")
+                .selected_text(self.pattern_names.get(self.target).copied().unwrap_or("—"))
+                .show_ui(ui, |ui| {
+                    for (i, name) in self.pattern_names.iter().enumerate() {
+                        ui.selectable_value(&mut self.target, i, *name);
+                    }
+                });
+            ui.add(egui::Slider::new(&mut self.noise_level, 0.0..=1.0).text("Randomized Sites"));
+            ui.horizontal(|ui| {
+                ui.label("Max Sweeps:");
+                ui.add(egui::DragValue::new(&mut self.max_sweeps).speed(1.0).range(1..=500));
+            });
+            if ui.button("Recall").clicked() {
+                session::record(self.name(), SessionAction::Command(Command::Run));
+                self.recall();
+            }
+        });
+        if seed_control(ui, &mut self.seed) {
+            self.rng = StdRng::seed_from_u64(self.seed);
+        }
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        ui.heading("Potts Network Recall");
+        ui.separator();
+
+        let Some(network) = &self.network else {
+            ui.vertical_centered(|ui| {
+                ui.label("No network trained yet. Select images and press Train.");
+            });
+            return;
+        };
+        ui.label(format!(
+            "{} sites, q = {}, {} stored images",
+            network.size(),
+            network.num_states(),
+            self.patterns.len()
+        ));
+
+        let shown = self.run.get(self.display_sweep.min(self.run.len().saturating_sub(1)));
+        ui.columns(3, |columns| {
+            self.draw_state(&mut columns[0], "Target", self.patterns.get(self.target));
+            self.draw_state(&mut columns[1], "Cue", self.run.first());
+            self.draw_state(&mut columns[2], "Output", shown);
+        });
+
+        if self.run.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Sweep:");
+            ui.add(egui::Slider::new(&mut self.display_sweep, 0..=self.run.len() - 1));
+        });
+        if let Some(state) = self.run.get(self.display_sweep) {
+            let overlaps: Vec<String> = self
+                .pattern_names
+                .iter()
+                .zip(network.overlaps(state))
+                .map(|(name, m)| format!("{} {:.2}", name, m))
+                .collect();
+            ui.label(format!("Overlaps: {}", overlaps.join(", ")));
+        }
+
+        ui.separator();
+        ui.label("Energy per Sweep:");
+        let points: PlotPoints = self
+            .run
+            .iter()
+            .enumerate()
+            .filter_map(|(i, state)| network.energy(state).ok().map(|e| [i as f64, e]))
+            .collect();
+        Plot::new("potts_energy_plot")
+            .height(150.0)
+            .x_axis_label("Sweep")
+            .show(ui, |plot_ui| plot_ui.line(Line::new(points)));
+    }
+}
@@ -0,0 +1,444 @@
+use eframe::egui;
+use rand::rngs::ThreadRng;
+
+use crate::core::graph::GraphType;
+use crate::neural::chip_firing::VertexSelectionStrategy;
+use crate::neural::rotor_router::{RotorRouter, RotorRouterState};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::dynamics;
+use crate::ui::widgets::network::{self, NetworkStyle};
+use crate::ui::windows::chip_firing::{build_graph, layout_positions};
+use crate::ui::windows::{Command, Window};
+
+/// Window for exploring rotor-routing (Eulerian walkers) on the chip firing graph types
+pub struct RotorRouterWindow {
+    /// The rotor-router model
+    router: Option<RotorRouter>,
+
+    /// Undirected edges of the current graph, each listed once (for drawing)
+    edges: Vec<(usize, usize)>,
+
+    /// Configuration for graph creation
+    graph_type: GraphType,
+    graph_size: usize,        // For cycle, complete and star graphs
+    grid_width: usize,        // For grid graphs
+    grid_height: usize,       // For grid graphs
+    custom_edges: String,     // For custom graphs, format: "0,1 1,2 ..."
+    use_sink: bool,
+    sink_vertex: usize,
+
+    /// Simulation parameters
+    max_steps: usize,
+    max_walk_length: usize,
+    step_interval: f64,       // In seconds
+    time_since_step: f64,
+    auto_step: bool,
+
+    /// Display settings
+    display_step: usize,
+    show_rotors: bool,
+    vertex_radius: f32,
+    edge_thickness: f32,
+
+    /// Vertex interaction
+    selected_vertex: Option<usize>,
+
+    /// Path of the most recently dropped walker
+    last_walk: Vec<usize>,
+
+    /// Node positions for network visualization
+    node_positions: Vec<egui::Vec2>,
+
+    /// Random number generator for vertex selection
+    rng: ThreadRng,
+}
+
+impl RotorRouterWindow {
+    pub fn new() -> Self {
+        Self {
+            router: None,
+            edges: Vec::new(),
+            graph_type: GraphType::Grid,
+            graph_size: 10,
+            grid_width: 5,
+            grid_height: 5,
+            custom_edges: String::new(),
+            use_sink: true,
+            sink_vertex: 0,
+            max_steps: 100,
+            max_walk_length: 10_000,
+            step_interval: 0.2,
+            time_since_step: 0.0,
+            auto_step: false,
+            display_step: 0,
+            show_rotors: true,
+            vertex_radius: 15.0,
+            edge_thickness: 2.0,
+            selected_vertex: None,
+            last_walk: Vec::new(),
+            node_positions: Vec::new(),
+            rng: rand::thread_rng(),
+        }
+    }
+
+    /// Create a new rotor-router based on current settings
+    fn create_router(&mut self) {
+        let graph = match build_graph(self.graph_type, self.graph_size, self.grid_width, self.grid_height, &self.custom_edges) {
+            Ok(graph) => graph,
+            Err(e) => {
+                report_error(e);
+                return;
+            }
+        };
+
+        let sink = if self.use_sink {
+            Some(self.sink_vertex.min(graph.num_vertices.saturating_sub(1)))
+        } else {
+            None
+        };
+
+        match RotorRouter::from_graph(&graph, sink) {
+            Ok(router) => {
+                self.edges = (0..graph.num_vertices)
+                    .flat_map(|i| graph.neighbors(i).into_iter().filter(move |&j| i < j).map(move |j| (i, j)))
+                    .collect();
+                self.node_positions = layout_positions(self.graph_type, graph.num_vertices, self.grid_width, self.grid_height);
+                self.router = Some(router);
+                self.selected_vertex = None;
+                self.last_walk.clear();
+                self.display_step = 0;
+            },
+            Err(e) => {
+                report_error(format!("Failed to create rotor-router: {}", e));
+            },
+        }
+    }
+
+    /// Get the state at the current display step
+    fn current_state(&self) -> Option<&RotorRouterState> {
+        let router = self.router.as_ref()?;
+        let step = self.display_step.min(router.history.len().saturating_sub(1));
+        router.history.get(step)
+    }
+
+    /// Route a single chip; auto stepping stops once no chip can move
+    fn step_simulation(&mut self) {
+        if let Some(router) = &mut self.router {
+            if dynamics::advance(router, 1, &mut self.rng) == 0 {
+                self.auto_step = false;
+            }
+            self.display_step = router.history.len() - 1;
+        }
+    }
+
+    /// Route chips until none can move or `max_steps` is reached
+    fn run_until_stable(&mut self) {
+        if let Some(router) = &mut self.router {
+            dynamics::advance(router, self.max_steps, &mut self.rng);
+            self.display_step = router.history.len() - 1;
+        }
+    }
+
+    /// Return the chips and rotors to their initial state
+    fn reset_router(&mut self) {
+        if let Some(router) = &mut self.router {
+            router.reset();
+        }
+        self.last_walk.clear();
+        self.display_step = 0;
+    }
+
+    /// Add a chip to the selected vertex without routing it
+    fn add_chip(&mut self) {
+        if let (Some(router), Some(vertex)) = (&mut self.router, self.selected_vertex) {
+            if vertex < router.num_vertices && Some(vertex) != router.sink {
+                router.chips[vertex] += 1;
+                router.record_state();
+                self.display_step = router.history.len() - 1;
+            }
+        }
+    }
+
+    /// Drop a walker at the selected vertex and route it to the sink
+    fn drop_walker(&mut self) {
+        if let (Some(router), Some(vertex)) = (&mut self.router, self.selected_vertex) {
+            match router.add_walker(vertex, self.max_walk_length) {
+                Ok(path) => {
+                    self.last_walk = path;
+                    self.display_step = router.history.len() - 1;
+                },
+                Err(e) => report_error(format!("Walker error: {}", e)),
+            }
+        }
+    }
+
+    fn draw_network(&self, painter: &egui::Painter, response: &egui::Response) {
+        let (Some(router), Some(state)) = (&self.router, self.current_state()) else {
+            return;
+        };
+        if self.node_positions.len() != router.num_vertices {
+            painter.text(
+                response.rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Error: Node positions mismatch",
+                egui::FontId::default(),
+                egui::Color32::RED,
+            );
+            return;
+        }
+
+        let labels: Vec<String> = (0..router.num_vertices)
+            .map(|i| if Some(i) == router.sink { "S".to_string() } else { state.chips[i].to_string() })
+            .collect();
+        let fills: Vec<egui::Color32> = (0..router.num_vertices)
+            .map(|i| {
+                if Some(i) == self.selected_vertex {
+                    egui::Color32::YELLOW
+                } else if Some(i) == router.sink {
+                    egui::Color32::LIGHT_BLUE
+                } else if state.chips[i] > 0 {
+                    egui::Color32::GREEN
+                } else {
+                    egui::Color32::WHITE
+                }
+            })
+            .collect();
+
+        network::draw_network(
+            painter,
+            response.rect.min,
+            &self.node_positions,
+            &self.edges,
+            &labels,
+            &fills,
+            NetworkStyle { vertex_radius: self.vertex_radius, edge_thickness: self.edge_thickness },
+        );
+
+        // Highlight the path of the last walker
+        for pair in self.last_walk.windows(2) {
+            if let (Some(&a), Some(&b)) = (self.node_positions.get(pair[0]), self.node_positions.get(pair[1])) {
+                painter.line_segment(
+                    [response.rect.min + a, response.rect.min + b],
+                    egui::Stroke::new(self.edge_thickness + 1.0, egui::Color32::from_rgba_unmultiplied(255, 140, 0, 120)),
+                );
+            }
+        }
+
+        if self.show_rotors {
+            for v in 0..router.num_vertices {
+                if Some(v) == router.sink {
+                    continue;
+                }
+                if let Some(&target) = router.rotor_order(v).get(state.rotors[v]) {
+                    network::draw_node_arrow(
+                        painter,
+                        response.rect.min,
+                        self.node_positions[v],
+                        self.node_positions[target],
+                        self.vertex_radius,
+                        egui::Color32::RED,
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Window for RotorRouterWindow {
+    fn name(&self) -> &str {
+        "Rotor Router"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        if self.router.is_none() {
+            return false;
+        }
+        match command {
+            Command::Step => self.step_simulation(),
+            Command::Run => self.run_until_stable(),
+            Command::Reset => self.reset_router(),
+            Command::Train => return false,
+        }
+        true
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        // Graph creation settings
+        ui.heading("Graph Settings");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Graph Type:");
+            ui.radio_value(&mut self.graph_type, GraphType::Grid, "Grid");
+            ui.radio_value(&mut self.graph_type, GraphType::Cycle, "Cycle");
+            ui.radio_value(&mut self.graph_type, GraphType::Complete, "Complete");
+            ui.radio_value(&mut self.graph_type, GraphType::Star, "Star");
+            ui.radio_value(&mut self.graph_type, GraphType::Custom, "Custom");
+        });
+
+        match self.graph_type {
+            GraphType::Grid => {
+                ui.horizontal(|ui| {
+                    ui.label("Width:");
+                    ui.add(egui::DragValue::new(&mut self.grid_width).speed(1.0).range(2..=30));
+                    ui.label("Height:");
+                    ui.add(egui::DragValue::new(&mut self.grid_height).speed(1.0).range(2..=30));
+                });
+            },
+            GraphType::Cycle | GraphType::Complete | GraphType::Star => {
+                ui.horizontal(|ui| {
+                    ui.label("Number of Vertices:");
+                    ui.add(egui::DragValue::new(&mut self.graph_size).speed(1.0).range(3..=50));
+                });
+            },
+            GraphType::Custom => {
+                ui.label("Enter edges as space-separated pairs (e.g., \"0,1 1,2 2,0\"):");
+                ui.text_edit_multiline(&mut self.custom_edges);
+            },
+        }
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.use_sink, "Sink at vertex");
+            ui.add_enabled(self.use_sink, egui::DragValue::new(&mut self.sink_vertex).speed(1.0));
+        });
+
+        if ui.button("Create Graph").clicked() {
+            self.create_router();
+        }
+
+        ui.separator();
+
+        if self.router.is_some() {
+            ui.heading("Simulation Settings");
+            ui.separator();
+
+            if let Some(router) = &mut self.router {
+                ui.horizontal(|ui| {
+                    ui.label("Selection Strategy:");
+                    ui.radio_value(&mut router.selection_strategy, VertexSelectionStrategy::FirstActive, "First Active");
+                    ui.radio_value(&mut router.selection_strategy, VertexSelectionStrategy::RandomActive, "Random Active");
+                });
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Max Steps:");
+                ui.add(egui::DragValue::new(&mut self.max_steps).speed(1.0).range(1..=100_000));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Step Interval (s):");
+                ui.add(egui::Slider::new(&mut self.step_interval, 0.01..=2.0).logarithmic(true));
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Step").clicked() {
+                    self.step_simulation();
+                }
+                ui.checkbox(&mut self.auto_step, "Auto Step");
+                if ui.button("Run Until Stable").clicked() {
+                    self.run_until_stable();
+                }
+                if ui.button("Reset").clicked() {
+                    self.reset_router();
+                }
+            });
+
+            ui.separator();
+            ui.heading("Display Settings");
+            ui.checkbox(&mut self.show_rotors, "Show Rotors");
+            ui.horizontal(|ui| {
+                ui.label("Vertex Radius:");
+                ui.add(egui::Slider::new(&mut self.vertex_radius, 5.0..=30.0));
+            });
+
+            ui.separator();
+
+            if let Some(vertex_idx) = self.selected_vertex {
+                ui.label(format!("Selected Vertex: {}", vertex_idx));
+                ui.horizontal(|ui| {
+                    if ui.button("Add Chip").clicked() {
+                        self.add_chip();
+                    }
+                    if ui.add_enabled(self.use_sink, egui::Button::new("Drop Walker"))
+                        .on_hover_text("Route a single chip from this vertex until it reaches the sink")
+                        .clicked()
+                    {
+                        self.drop_walker();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Max Walk Length:");
+                    ui.add(egui::DragValue::new(&mut self.max_walk_length).speed(10.0).range(1..=1_000_000));
+                });
+            } else {
+                ui.label("Select a vertex in the visualization to interact.");
+            }
+        } else {
+            ui.label("Create a graph first.");
+        }
+
+    }
+
+    fn on_close(&mut self) {
+        self.auto_step = false;
+    }
+
+    fn tick(&mut self, dt: f64) -> bool {
+        if !self.auto_step {
+            return false;
+        }
+        self.time_since_step += dt;
+        if self.time_since_step >= self.step_interval {
+            self.step_simulation();
+            self.time_since_step = 0.0;
+        }
+        true
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        let (response, painter) = ui.allocate_painter(egui::vec2(500.0, 500.0), egui::Sense::click());
+
+        if response.clicked() && self.router.is_some() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                if let Some(idx) = network::node_at(&self.node_positions, response.rect.min, pos, self.vertex_radius) {
+                    self.selected_vertex = Some(idx);
+                }
+            }
+        }
+
+        if let Some(router) = &self.router {
+            if router.history.len() > 1 {
+                ui.horizontal(|ui| {
+                    self.display_step = self.display_step.min(router.history.len() - 1);
+                    ui.label(format!("Step: {} / {}", self.display_step, router.history.len() - 1));
+                    ui.add(egui::Slider::new(&mut self.display_step, 0..=(router.history.len() - 1)).text("View Step"));
+                });
+            } else {
+                self.display_step = 0;
+                ui.label("Step: 0 / 0");
+            }
+            ui.separator();
+
+            self.draw_network(&painter, &response);
+
+            ui.label(format!("Chips on Graph: {}", router.total_chips()));
+            if router.sink.is_some() {
+                ui.label(format!("Absorbed by Sink: {}", router.absorbed));
+            }
+            if let Some(&end) = self.last_walk.last() {
+                ui.label(format!("Last walker: {} moves, ended at {}", self.last_walk.len() - 1, end));
+            }
+            if router.is_stable() {
+                ui.colored_label(egui::Color32::GREEN, "Stable");
+            } else {
+                ui.colored_label(egui::Color32::YELLOW, "Chips in motion");
+            }
+        } else {
+            self.selected_vertex = None;
+            self.display_step = 0;
+            ui.vertical_centered(|ui| {
+                ui.label("No graph created yet. Use the configuration panel to create one.");
+            });
+        }
+    }
+}
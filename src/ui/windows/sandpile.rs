@@ -0,0 +1,375 @@
+use eframe::egui;
+use std::time::Instant;
+
+use crate::core::job::{BackgroundJob, JobStatus};
+use crate::graphics::compute::GpuContext;
+use crate::graphics::pipeline::Pipeline;
+use crate::neural::chip_firing::gpu::GpuChipFiringStabilizer;
+use crate::neural::chip_firing::ChipFiringGraph;
+use crate::ui::metrics::{self, Metric};
+use crate::ui::notifications::report_error;
+use crate::ui::widgets::colormap::{legend, Colormap};
+use crate::ui::widgets::job::job_progress;
+use crate::ui::widgets::state_texture;
+use crate::ui::windows::{Command, Window};
+
+/// Side length of the drawn grid in points
+const DISPLAY_SIZE: f32 = 512.0;
+
+/// Largest grid stabilized on the CPU, where big piles take minutes
+const MAX_CPU_SIZE: usize = 128;
+
+/// Largest grid stabilized on the GPU
+const MAX_GPU_SIZE: usize = 512;
+
+/// Firings after which the CPU gives up
+const MAX_CPU_FIRINGS: usize = 500_000_000;
+
+/// Colors of cells holding 0, 1, 2 and 3 chips; cells with more are unstable and white
+const CHIP_COLORS: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(20, 24, 64),
+    egui::Color32::from_rgb(40, 140, 160),
+    egui::Color32::from_rgb(240, 200, 60),
+    egui::Color32::from_rgb(200, 50, 50),
+];
+
+/// What the cells are colored by
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SandpileView {
+    Chips,
+    /// How often each cell has toppled, on a log scale
+    Topplings,
+}
+
+/// Outcome of a stabilization, on either backend
+struct Stabilized {
+    configuration: Vec<i32>,
+    /// Topplings of each vertex during this stabilization
+    firing_counts: Vec<u64>,
+    /// Supersteps of the GPU kernel, None on the CPU
+    supersteps: Option<usize>,
+    stable: bool,
+    seconds: f64,
+}
+
+/// Result of a stabilization job, with the GPU kernel handed back for reuse
+type StabilizeOutcome = (Option<GpuChipFiringStabilizer>, Result<Stabilized, String>);
+
+/// Stabilizes the configuration of `graph` with `kernel`, creating it first if needed.
+/// The kernel is handed back so the graph is uploaded only once.
+fn stabilize_on_gpu(
+    context: &GpuContext,
+    kernel: Option<GpuChipFiringStabilizer>,
+    graph: &ChipFiringGraph,
+) -> StabilizeOutcome {
+    let kernel = match kernel {
+        Some(kernel) => kernel,
+        None => match GpuChipFiringStabilizer::new(context, graph) {
+            Ok(kernel) => kernel,
+            Err(e) => return (None, Err(e.to_string())),
+        },
+    };
+    let start = Instant::now();
+    let result = kernel.execute(&graph.configuration).map(|result| Stabilized {
+        firing_counts: result.firing_counts.iter().map(|&c| c as u64).collect(),
+        configuration: result.configuration,
+        supersteps: Some(result.supersteps),
+        stable: result.stable,
+        seconds: start.elapsed().as_secs_f64(),
+    });
+    (Some(kernel), result.map_err(|e| e.to_string()))
+}
+
+/// Stabilizes a copy of `graph` with `ChipFiringGraph::stabilize`
+fn stabilize_on_cpu(mut graph: ChipFiringGraph) -> Result<Stabilized, String> {
+    let before = graph.firing_counts.clone();
+    let start = Instant::now();
+    let firings = graph.stabilize(MAX_CPU_FIRINGS).map_err(|e| e.to_string())?;
+    Ok(Stabilized {
+        firing_counts: graph.firing_counts.iter().zip(&before).map(|(after, before)| after - before).collect(),
+        configuration: graph.configuration,
+        supersteps: None,
+        stable: firings < MAX_CPU_FIRINGS,
+        seconds: start.elapsed().as_secs_f64(),
+    })
+}
+
+/// Window for the abelian sandpile on large square grids, stabilized in parallel on
+/// the GPU when one is available
+pub struct SandpileWindow {
+    /// Device of the egui renderer, None on backends without wgpu
+    gpu: Option<GpuContext>,
+    /// Stabilizer for the current graph, taken by a running job
+    kernel: Option<GpuChipFiringStabilizer>,
+
+    /// The grid with its sink; the sink is the last vertex
+    graph: ChipFiringGraph,
+    size: usize,
+
+    /// Chips added by clicking a cell, and by dropping a pile at the center
+    drop_amount: i32,
+    pile_amount: i32,
+    /// Stabilize after every drop
+    auto_stabilize: bool,
+
+    view: SandpileView,
+    /// Texture the grid is uploaded to without a wgpu renderer
+    texture: Option<egui::TextureHandle>,
+
+    job: Option<BackgroundJob<StabilizeOutcome>>,
+    /// Summary of the last stabilization
+    last_result: Option<String>,
+}
+
+impl SandpileWindow {
+    pub fn new(gpu: Option<GpuContext>) -> Self {
+        let size = if gpu.is_some() { 256 } else { 64 };
+        Self {
+            gpu,
+            kernel: None,
+            graph: ChipFiringGraph::new_sandpile(size, size).expect("non-empty grid"),
+            size,
+            drop_amount: 1,
+            pile_amount: 1 << 16,
+            auto_stabilize: true,
+            view: SandpileView::Chips,
+            texture: None,
+            job: None,
+            last_result: None,
+        }
+    }
+
+    fn max_size(&self) -> usize {
+        if self.gpu.is_some() {
+            MAX_GPU_SIZE
+        } else {
+            MAX_CPU_SIZE
+        }
+    }
+
+    fn rebuild(&mut self) {
+        match ChipFiringGraph::new_sandpile(self.size, self.size) {
+            Ok(graph) => {
+                self.graph = graph;
+                self.kernel = None;
+                self.last_result = None;
+            },
+            Err(e) => report_error(format!("Failed to create sandpile: {}", e)),
+        }
+    }
+
+    fn add_chips(&mut self, cell: usize, amount: i32) {
+        if let Err(e) = self.graph.add_chips(cell, amount) {
+            report_error(format!("Failed to add chips: {}", e));
+            return;
+        }
+        self.publish_chips();
+        if self.auto_stabilize {
+            self.start_stabilize();
+        }
+    }
+
+    fn start_stabilize(&mut self) {
+        if self.job.is_some() || self.graph.is_stable() {
+            return;
+        }
+        let graph = self.graph.clone();
+        self.job = Some(match self.gpu.clone() {
+            Some(context) => {
+                let kernel = self.kernel.take();
+                BackgroundJob::spawn("Stabilize on GPU", 1, move |_| stabilize_on_gpu(&context, kernel, &graph))
+            },
+            None => BackgroundJob::spawn("Stabilize on CPU", 1, move |_| (None, stabilize_on_cpu(graph))),
+        });
+    }
+
+    fn poll_job(&mut self) {
+        let Some(job) = &mut self.job else {
+            return;
+        };
+        match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished((kernel, result)) => {
+                self.kernel = kernel;
+                match result {
+                    Ok(stabilized) => self.apply(stabilized),
+                    Err(e) => report_error(format!("Stabilization failed: {}", e)),
+                }
+            },
+            JobStatus::Failed => report_error("Stabilization stopped unexpectedly"),
+        }
+        self.job = None;
+    }
+
+    /// Takes over a finished stabilization; the grid cannot change while it runs
+    fn apply(&mut self, stabilized: Stabilized) {
+        let total: u64 = stabilized.firing_counts.iter().sum();
+        if let Err(e) = self.graph.set_configuration(stabilized.configuration) {
+            report_error(format!("Failed to apply the stable configuration: {}", e));
+            return;
+        }
+        for (counts, &added) in self.graph.firing_counts.iter_mut().zip(&stabilized.firing_counts) {
+            *counts += added;
+        }
+        let backend = match stabilized.supersteps {
+            Some(supersteps) => format!("GPU, {} supersteps", supersteps),
+            None => "CPU".to_string(),
+        };
+        self.last_result = Some(format!(
+            "{}{} topplings in {:.3} s ({})",
+            if stabilized.stable { "" } else { "Gave up after " },
+            total,
+            stabilized.seconds,
+            backend
+        ));
+        self.publish_chips();
+    }
+
+    /// Publishes the chips on the grid, without those lost to the sink
+    fn publish_chips(&self) {
+        let sink = self.size * self.size;
+        metrics::publish(Metric::TotalChips, (self.graph.total_chips() - self.graph.configuration[sink]) as f64);
+    }
+
+    fn grid_image(&self) -> egui::ColorImage {
+        let cells = self.size * self.size;
+        let pixels = match self.view {
+            SandpileView::Chips => self.graph.configuration[..cells]
+                .iter()
+                .map(|&chips| CHIP_COLORS.get(chips.max(0) as usize).copied().unwrap_or(egui::Color32::WHITE))
+                .collect(),
+            SandpileView::Topplings => {
+                let counts = &self.graph.firing_counts[..cells];
+                let max = counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+                counts.iter().map(|&c| Colormap::Viridis.map((c as f64).ln_1p(), 0.0, max.ln_1p())).collect()
+            },
+        };
+        egui::ColorImage { size: [self.size, self.size], pixels }
+    }
+}
+
+impl Window for SandpileWindow {
+    fn name(&self) -> &str {
+        "Sandpile"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Run | Command::Step if self.job.is_none() => self.start_stabilize(),
+            Command::Reset if self.job.is_none() => self.rebuild(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Backend");
+        ui.separator();
+        match &self.gpu {
+            Some(gpu) => ui.label(format!("GPU: {}", gpu.adapter_name)),
+            None => ui.label(format!("CPU (no wgpu renderer), grids up to {}x{}", MAX_CPU_SIZE, MAX_CPU_SIZE)),
+        };
+
+        ui.separator();
+        ui.heading("Grid");
+        ui.separator();
+        let max_size = self.max_size();
+        ui.add_enabled_ui(self.job.is_none(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Size:");
+                ui.add(egui::DragValue::new(&mut self.size).speed(1.0).range(4..=max_size));
+            });
+            if ui.button("Create Empty Grid").clicked() {
+                self.rebuild();
+            }
+        });
+
+        ui.separator();
+        ui.heading("Chips");
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Chips per Click:");
+            ui.add(egui::DragValue::new(&mut self.drop_amount).speed(1.0).range(1..=1_000_000));
+        });
+        ui.checkbox(&mut self.auto_stabilize, "Stabilize after Each Drop");
+        ui.add_enabled_ui(self.job.is_none(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Pile:");
+                ui.add(egui::DragValue::new(&mut self.pile_amount).speed(100.0).range(1..=(1 << 24)));
+                if ui.button("Drop at Center").clicked() {
+                    let center = (self.size / 2) * self.size + self.size / 2;
+                    self.add_chips(center, self.pile_amount);
+                }
+            });
+            if ui.button("Stabilize").clicked() {
+                self.start_stabilize();
+            }
+        });
+
+        ui.separator();
+        ui.heading("Display");
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.view, SandpileView::Chips, "Chips");
+            ui.selectable_value(&mut self.view, SandpileView::Topplings, "Topplings");
+        });
+    }
+
+    fn show_content(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        self.poll_job();
+        if let Some(job) = &self.job {
+            job_progress(ui, job);
+        }
+
+        let image = self.grid_image();
+        ui.label("Click a cell to drop chips on it");
+        let size = egui::vec2(DISPLAY_SIZE, DISPLAY_SIZE);
+        let response = if state_texture::is_available(ctx) {
+            state_texture::state_texture(ui, "sandpile_grid", &image, size, egui::Sense::click())
+        } else {
+            let texture = match &mut self.texture {
+                Some(texture) => {
+                    texture.set(image, egui::TextureOptions::NEAREST);
+                    texture
+                },
+                None => self.texture.insert(ctx.load_texture("sandpile_grid", image, egui::TextureOptions::NEAREST)),
+            };
+            ui.add(egui::Image::new((texture.id(), size)).sense(egui::Sense::click()))
+        };
+        if response.clicked() && self.job.is_none() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let rel = (pos - response.rect.min) / size;
+                let x = ((rel.x * self.size as f32) as usize).min(self.size - 1);
+                let y = ((rel.y * self.size as f32) as usize).min(self.size - 1);
+                self.add_chips(y * self.size + x, self.drop_amount);
+            }
+        }
+
+        match self.view {
+            SandpileView::Chips => {
+                ui.horizontal(|ui| {
+                    for (chips, &color) in CHIP_COLORS.iter().enumerate() {
+                        let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 0.0, color);
+                        ui.label(chips.to_string());
+                    }
+                    ui.label("(white: unstable)");
+                });
+            },
+            SandpileView::Topplings => {
+                let max = self.graph.firing_counts.iter().copied().max().unwrap_or(0);
+                legend(ui, Colormap::Viridis, 0.0, max as f64, "Topplings (log scale):");
+            },
+        }
+
+        let sink = self.size * self.size;
+        ui.label(format!(
+            "{} chips on the grid   {} lost to the boundary",
+            self.graph.total_chips() - self.graph.configuration[sink],
+            self.graph.configuration[sink]
+        ));
+        if let Some(result) = &self.last_result {
+            ui.label(result);
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use eframe::egui;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::core::bridge::GridPattern;
+use crate::core::metrics::overlap;
+use crate::error::RaumError;
+use crate::neural::chip_firing::{ChipFiringGraph, DriveTarget};
+use crate::neural::hopfield::{HopfieldNetwork, TrainingRule};
+use crate::ui::events::{self, EventKind};
+use crate::ui::notifications::report_error;
+use crate::ui::session::{self, SessionAction};
+use crate::ui::widgets::grid::{draw_grid, draw_gray_grid};
+use crate::ui::widgets::seed::seed_control;
+use crate::ui::windows::{Command, Window};
+
+/// Side of the drawn grids in points
+const GRID_VIEW_SIZE: f32 = 140.0;
+
+/// Side of the stored pattern thumbnails in points
+const THUMBNAIL_SIZE: f32 = 70.0;
+
+/// Chips dropped to drive a new sandpile to its critical state, per cell
+const WARMUP_DROPS_PER_CELL: usize = 2;
+
+/// Chips dropped while looking for an avalanche that is large enough
+const MAX_DROPS: usize = 20_000;
+
+/// Firings after which a single avalanche is cut short
+const MAX_FIRINGS: usize = 1_000_000;
+
+/// Sweeps the network gets to clean up an avalanche
+const MAX_SWEEPS: usize = 100;
+
+/// An avalanche fed to the network and the state it settled in
+struct Probe {
+    avalanche: GridPattern,
+    recalled: Vec<f64>,
+    sweeps: usize,
+    /// Overlap of the avalanche and of the recalled state with each stored avalanche
+    overlaps_before: Vec<f64>,
+    overlaps_after: Vec<f64>,
+}
+
+/// Window coupling the abelian sandpile with a Hopfield network on the same grid: the
+/// network stores a few avalanches (the cells they fired), and later avalanches are fed
+/// to it as initial states to see which stored one they are cleaned up to
+pub struct SandpileMemoryWindow {
+    /// Grid and pattern settings
+    side: usize,
+    stored_count: usize,
+    min_avalanche: usize,
+    fire_threshold: u64,
+    rule: TrainingRule,
+
+    /// The driven sandpile, the network and the avalanches it stores
+    sandpile: Option<ChipFiringGraph>,
+    network: Option<HopfieldNetwork>,
+    stored: Vec<GridPattern>,
+    /// Chips dropped since the sandpile was built
+    drops: usize,
+
+    probe: Option<Probe>,
+
+    seed: u64,
+    rng: StdRng,
+}
+
+impl SandpileMemoryWindow {
+    pub fn new() -> Self {
+        let seed = rand::random::<u32>() as u64;
+        Self {
+            side: 16,
+            stored_count: 3,
+            min_avalanche: 20,
+            fire_threshold: 1,
+            rule: TrainingRule::PseudoInverse,
+            sandpile: None,
+            network: None,
+            stored: Vec::new(),
+            drops: 0,
+            probe: None,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// A sandpile with a random stable configuration, driven until it is critical
+    fn build_sandpile(&mut self) -> Result<ChipFiringGraph, RaumError> {
+        let cells = self.side * self.side;
+        let mut sandpile = ChipFiringGraph::new_sandpile(self.side, self.side)?;
+        let mut configuration: Vec<i32> = (0..cells).map(|_| self.rng.gen_range(0..4)).collect();
+        configuration.push(0);
+        sandpile.set_configuration(configuration)?;
+        for _ in 0..WARMUP_DROPS_PER_CELL * cells {
+            sandpile.drop_chip(DriveTarget::Random, &mut self.rng)?;
+            sandpile.stabilize(MAX_FIRINGS)?;
+        }
+        sandpile.clear_history();
+        Ok(sandpile)
+    }
+
+    /// Drops chips on random cells until an avalanche fires at least `min_avalanche`
+    /// cells at least `fire_threshold` times
+    ///
+    /// # Returns
+    ///
+    /// The cells of the avalanche, or None if none was large enough in `MAX_DROPS` drops
+    fn next_avalanche(&mut self) -> Result<Option<GridPattern>, RaumError> {
+        let Some(sandpile) = &mut self.sandpile else {
+            return Ok(None);
+        };
+        for _ in 0..MAX_DROPS {
+            sandpile.reset_activity();
+            sandpile.drop_chip(DriveTarget::Random, &mut self.rng)?;
+            sandpile.stabilize(MAX_FIRINGS)?;
+            self.drops += 1;
+            let pattern = GridPattern::from_firings(&sandpile.firing_counts, self.side, self.side, self.fire_threshold)?;
+            if pattern.active() >= self.min_avalanche {
+                return Ok(Some(pattern));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Drives a new sandpile, collects `stored_count` avalanches and stores them
+    fn collect_patterns(&mut self) -> Result<(), RaumError> {
+        self.sandpile = Some(self.build_sandpile()?);
+        self.drops = 0;
+        self.network = None;
+        self.stored.clear();
+        self.probe = None;
+
+        let mut stored = Vec::new();
+        while stored.len() < self.stored_count {
+            match self.next_avalanche()? {
+                Some(pattern) => stored.push(pattern),
+                None => {
+                    return Err(RaumError::Message(format!(
+                        "No avalanche fired {} cells in {} drops",
+                        self.min_avalanche, MAX_DROPS
+                    )))
+                },
+            }
+        }
+
+        let patterns: Vec<Vec<f64>> = stored.iter().map(|pattern| pattern.values.clone()).collect();
+        let mut network = HopfieldNetwork::try_new(self.side * self.side)?;
+        network.train(&patterns, self.rule)?;
+        events::emit(EventKind::TrainingFinished { patterns: stored.len(), neurons: network.size() });
+        self.network = Some(network);
+        self.stored = stored;
+        Ok(())
+    }
+
+    /// Feeds the next large enough avalanche to the network and lets it settle
+    fn probe_avalanche(&mut self) -> Result<(), RaumError> {
+        if self.network.is_none() {
+            return Err(RaumError::Message("Collect avalanches to store first".to_string()));
+        }
+        let Some(avalanche) = self.next_avalanche()? else {
+            return Err(RaumError::Message(format!(
+                "No avalanche fired {} cells in {} drops",
+                self.min_avalanche, MAX_DROPS
+            )));
+        };
+        let network = self.network.as_ref().expect("checked above");
+        let mut recalled = avalanche.values.clone();
+        let sweeps = network.settle(&mut recalled, MAX_SWEEPS, &mut self.rng);
+        let overlaps_before = self.stored.iter().map(|stored| overlap(&avalanche.values, &stored.values)).collect();
+        let overlaps_after = self.stored.iter().map(|stored| overlap(&recalled, &stored.values)).collect();
+        self.probe = Some(Probe { avalanche, recalled, sweeps, overlaps_before, overlaps_after });
+        Ok(())
+    }
+
+    fn train(&mut self) {
+        if let Err(e) = self.collect_patterns() {
+            report_error(format!("Failed to store avalanches: {}", e));
+        }
+    }
+
+    fn run(&mut self) {
+        if let Err(e) = self.probe_avalanche() {
+            report_error(format!("Failed to probe the network: {}", e));
+        }
+    }
+
+    /// Apply a setting from a session journal
+    fn apply_session_setting(&mut self, key: &str, value: &str) -> bool {
+        if key == "rule" {
+            self.rule = match value {
+                "Hebbian" => TrainingRule::Hebbian,
+                "PseudoInverse" => TrainingRule::PseudoInverse,
+                "Storkey" => TrainingRule::Storkey,
+                _ => return false,
+            };
+            return true;
+        }
+        let Ok(number) = value.parse::<u64>() else { return false };
+        match key {
+            "side" => self.side = number as usize,
+            "stored_count" => self.stored_count = number as usize,
+            "min_avalanche" => self.min_avalanche = number as usize,
+            "fire_threshold" => self.fire_threshold = number,
+            "seed" => {
+                self.seed = number;
+                self.rng = StdRng::seed_from_u64(number);
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// A bipolar state on the grid under `title`, with an optional caption
+    fn draw_state(ui: &mut egui::Ui, title: &str, state: Option<&[f64]>, side: usize, caption: Option<String>) {
+        ui.vertical_centered(|ui| {
+            ui.label(title);
+            ui.separator();
+            match state {
+                Some(state) if state.len() == side * side => {
+                    draw_grid(ui, state, side, side, GRID_VIEW_SIZE / side as f32);
+                    if let Some(caption) = caption {
+                        ui.label(caption);
+                    }
+                },
+                _ => {
+                    ui.label("—");
+                },
+            }
+        });
+    }
+}
+
+impl Window for SandpileMemoryWindow {
+    fn name(&self) -> &str {
+        "Sandpile Memory"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Train => self.train(),
+            Command::Run | Command::Step => self.run(),
+            Command::Reset => self.probe = None,
+        }
+        true
+    }
+
+    fn session_settings(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("side", self.side.to_string()),
+            ("stored_count", self.stored_count.to_string()),
+            ("min_avalanche", self.min_avalanche.to_string()),
+            ("fire_threshold", self.fire_threshold.to_string()),
+            ("rule", format!("{:?}", self.rule)),
+            ("seed", self.seed.to_string()),
+        ]
+    }
+
+    fn replay(&mut self, action: &SessionAction) -> bool {
+        match action {
+            SessionAction::Command(command) => self.handle_command(*command),
+            SessionAction::Set { key, value } => self.apply_session_setting(key, value),
+            SessionAction::Perform { .. } => false,
+        }
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Stored Avalanches");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Grid Size:");
+            ui.add(egui::DragValue::new(&mut self.side).speed(1.0).range(8..=32));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Avalanches:");
+            ui.add(egui::DragValue::new(&mut self.stored_count).speed(1.0).range(1..=10));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Min Cells:");
+            ui.add(egui::DragValue::new(&mut self.min_avalanche).speed(1.0).range(1..=self.side * self.side))
+                .on_hover_text("Smaller avalanches are skipped when collecting and probing");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Firing Threshold:");
+            ui.add(egui::DragValue::new(&mut self.fire_threshold).speed(1.0).range(1..=10))
+                .on_hover_text("A cell is on (+1) in the pattern if it fired at least this often during the avalanche");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Rule:");
+            ui.radio_value(&mut self.rule, TrainingRule::Hebbian, "Hebbian");
+            ui.radio_value(&mut self.rule, TrainingRule::PseudoInverse, "Pseudo-Inverse");
+            ui.radio_value(&mut self.rule, TrainingRule::Storkey, "Storkey");
+        });
+        if ui.button("Collect & Store")
+            .on_hover_text("Drive a new sandpile to criticality and store its next avalanches in the network")
+            .clicked()
+        {
+            session::record(self.name(), SessionAction::Command(Command::Train));
+            self.train();
+        }
+
+        ui.separator();
+        ui.heading("Cleanup");
+        ui.separator();
+
+        ui.add_enabled_ui(self.network.is_some(), |ui| {
+            if ui.button("Probe Next Avalanche")
+                .on_hover_text("Keep driving the sandpile and feed its next avalanche to the network")
+                .clicked()
+            {
+                session::record(self.name(), SessionAction::Command(Command::Run));
+                self.run();
+            }
+        });
+        if seed_control(ui, &mut self.seed) {
+            self.rng = StdRng::seed_from_u64(self.seed);
+        }
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        ui.heading("Associative Cleanup of Sandpile Avalanches");
+        ui.separator();
+
+        let Some(sandpile) = &self.sandpile else {
+            ui.vertical_centered(|ui| {
+                ui.label("No avalanches stored yet. Press Collect & Store.");
+            });
+            return;
+        };
+        let side = self.side;
+        if sandpile.num_vertices != side * side + 1 {
+            ui.label("The grid size changed. Press Collect & Store to rebuild the sandpile.");
+            return;
+        }
+        ui.label(format!("{} avalanches stored, {} chips dropped", self.stored.len(), self.drops));
+
+        let probe = self.probe.as_ref();
+        let closest = probe.and_then(|probe| {
+            probe.overlaps_after.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).map(|(k, &m)| (k, m))
+        });
+        ui.columns(4, |columns| {
+            columns[0].vertical_centered(|ui| {
+                ui.label("Sandpile");
+                ui.separator();
+                let chips: Vec<f64> = sandpile.configuration[..side * side].iter().map(|&c| c as f64 / 3.0).collect();
+                draw_gray_grid(ui, &chips, side, side, GRID_VIEW_SIZE / side as f32);
+                ui.label("0 (black) to 3 chips");
+            });
+            Self::draw_state(
+                &mut columns[1],
+                "Avalanche",
+                probe.map(|probe| probe.avalanche.values.as_slice()),
+                side,
+                probe.map(|probe| format!("{} cells fired", probe.avalanche.active())),
+            );
+            Self::draw_state(
+                &mut columns[2],
+                "Recalled",
+                probe.map(|probe| probe.recalled.as_slice()),
+                side,
+                probe.map(|probe| format!("settled after {} sweeps", probe.sweeps)),
+            );
+            Self::draw_state(
+                &mut columns[3],
+                "Closest Stored",
+                closest.map(|(k, _)| self.stored[k].values.as_slice()),
+                side,
+                closest.map(|(k, m)| format!("#{}, m = {:.2}", k + 1, m)),
+            );
+        });
+
+        ui.separator();
+        ui.label("Stored avalanches, with the overlap of the probe before → after cleanup:");
+        ui.horizontal_wrapped(|ui| {
+            for (k, stored) in self.stored.iter().enumerate() {
+                ui.vertical(|ui| {
+                    ui.label(format!("#{} ({} cells)", k + 1, stored.active()));
+                    draw_grid(ui, &stored.values, side, side, THUMBNAIL_SIZE / side as f32);
+                    if let Some(probe) = probe {
+                        ui.label(format!("{:.2} → {:.2}", probe.overlaps_before[k], probe.overlaps_after[k]));
+                    }
+                });
+            }
+        });
+    }
+}
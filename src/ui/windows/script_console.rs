@@ -0,0 +1,233 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use eframe::egui;
+use rand::Rng;
+
+use crate::core::job::{BackgroundJob, JobStatus};
+use crate::scripting::{ScriptEngine, ScriptError};
+use crate::ui::notifications::report_error;
+use crate::ui::windows::{Command, Window};
+
+/// Example scripts offered in the configuration panel
+const RECIPES: [(&str, &str); 3] = [
+    (
+        "Recall from Noise",
+        r#"// Store three random patterns and recall the first from noisy cues
+seed(1);
+let patterns = [random_pattern(100), random_pattern(100), random_pattern(100)];
+let net = hopfield(100);
+net.train(patterns, "hebbian");
+for p in [0.05, 0.1, 0.2, 0.3, 0.4] {
+    let recalled = net.recall(corrupt(patterns[0], p));
+    print(`noise ${p}: overlap ${overlap(recalled, patterns[0])}`);
+}
+"#,
+    ),
+    (
+        "Capacity Curve",
+        r#"// Mean recall overlap against the number of stored patterns, exported as CSV
+seed(2);
+let n = 100;
+let rows = [];
+for count in 1..=20 {
+    let patterns = [];
+    for i in 0..count { patterns.push(random_pattern(n)); }
+    let net = hopfield(n);
+    net.train(patterns);
+    let total = 0.0;
+    for p in patterns { total += overlap(net.recall(corrupt(p, 0.1)), p); }
+    rows.push([count, total / count]);
+    print(`${count} patterns: ${total / count}`);
+}
+export_csv("capacity.csv", ["patterns", "overlap"], rows);
+"#,
+    ),
+    (
+        "Sandpile Avalanches",
+        r#"// Drive a 10 × 10 sandpile with a sink and record avalanche sizes
+seed(3);
+let g = grid_graph(10, 10);
+g.set_sink(0);
+let sizes = [];
+for i in 0..2000 { sizes.push(g.drop_chip()); }
+let largest = 0;
+for s in sizes { if s > largest { largest = s; } }
+print(`largest avalanche: ${largest} firings, ${g.configuration()[0]} chips lost to the sink`);
+"#,
+    ),
+];
+
+/// Console for Rhai scripts that drive the Hopfield and chip-firing models
+pub struct ScriptConsoleWindow {
+    /// Script being edited
+    source: String,
+
+    /// Seed of the script random number generator until the script calls `seed`
+    seed: u64,
+    random_seed: bool,
+
+    /// Running script and the handles shared with its engine
+    job: Option<BackgroundJob<Result<String, ScriptError>>>,
+    output: Arc<Mutex<Vec<String>>>,
+    interrupt: Arc<AtomicBool>,
+
+    /// Printed lines and results of finished scripts
+    log: Vec<String>,
+}
+
+impl ScriptConsoleWindow {
+    pub fn new() -> Self {
+        Self {
+            source: RECIPES[0].1.to_string(),
+            seed: 0,
+            random_seed: false,
+            job: None,
+            output: Arc::new(Mutex::new(Vec::new())),
+            interrupt: Arc::new(AtomicBool::new(false)),
+            log: Vec::new(),
+        }
+    }
+
+    /// Start the script on a worker thread
+    fn run(&mut self) {
+        if self.job.is_some() {
+            return;
+        }
+        if self.random_seed {
+            self.seed = rand::thread_rng().gen();
+        }
+        let engine = ScriptEngine::new(self.seed);
+        self.output = engine.output();
+        self.interrupt = engine.interrupt_flag();
+        self.log.push(format!("> run (seed {})", self.seed));
+
+        let source = self.source.clone();
+        self.job = Some(BackgroundJob::spawn("Script", 1, move |ctx| {
+            let result = engine.run(&source);
+            ctx.report(1);
+            result
+        }));
+    }
+
+    fn stop(&self) {
+        self.interrupt.store(true, Ordering::Relaxed);
+        if let Some(job) = &self.job {
+            job.cancel();
+        }
+    }
+
+    /// Move printed lines to the log and collect the result of a finished script
+    fn poll(&mut self) {
+        self.log.append(&mut self.output.lock().unwrap());
+        let Some(job) = &mut self.job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(result) => result.map_err(|e| e.to_string()),
+            JobStatus::Failed => Err("Script thread stopped unexpectedly".to_string()),
+        };
+        self.job = None;
+        self.log.append(&mut self.output.lock().unwrap());
+        match result {
+            Ok(value) if value.is_empty() => {},
+            Ok(value) => self.log.push(format!("= {}", value)),
+            Err(e) => report_error(e),
+        }
+    }
+}
+
+impl Window for ScriptConsoleWindow {
+    fn name(&self) -> &str {
+        "Script Console"
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Script");
+        ui.separator();
+
+        ui.label("Recipes:");
+        for (name, source) in RECIPES {
+            if ui.button(name).clicked() {
+                self.source = source.to_string();
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Seed:");
+            ui.add_enabled(!self.random_seed, egui::DragValue::new(&mut self.seed).speed(1.0));
+            ui.checkbox(&mut self.random_seed, "Random");
+        });
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.job.is_none(), egui::Button::new("Run")).clicked() {
+                self.run();
+            }
+            if ui.add_enabled(self.job.is_some(), egui::Button::new("Stop")).clicked() {
+                self.stop();
+            }
+            if ui.button("Clear Output").clicked() {
+                self.log.clear();
+            }
+        });
+
+        ui.separator();
+        egui::CollapsingHeader::new("Functions")
+            .id_source("script_functions")
+            .show(ui, |ui| {
+                ui.label("seed(n), random_pattern(n), corrupt(state, p), overlap(a, b), print(x)");
+                ui.label("hopfield(n): train(patterns[, \"hebbian\" | \"pseudo-inverse\" | \"storkey\"]), recall(state[, max_sweeps]), run(state, sweeps, beta), energy(state), size");
+                ui.label("grid_graph(w, h), cycle_graph(n), complete_graph(n), edge_list_graph(text): add_chips(v, n), set_sink(v), stabilize(), drop_chip(), configuration(), num_vertices, total_chips");
+                ui.label("export_csv(path, header, rows)");
+            });
+
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Run => {
+                self.run();
+                true
+            },
+            _ => false,
+        }
+    }
+
+    fn on_close(&mut self) {
+        self.stop();
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        self.poll();
+        if self.job.is_some() {
+            ui.label("Running...");
+            ui.ctx().request_repaint();
+        }
+
+        let editor_height = ui.available_height() * 0.55;
+        egui::ScrollArea::vertical()
+            .id_source("script_editor")
+            .max_height(editor_height)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.source)
+                        .code_editor()
+                        .desired_width(f32::INFINITY)
+                        .desired_rows(16),
+                );
+            });
+
+        ui.separator();
+        ui.label("Output");
+        egui::ScrollArea::vertical()
+            .id_source("script_output")
+            .stick_to_bottom(true)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for line in &self.log {
+                    ui.monospace(line);
+                }
+            });
+    }
+}
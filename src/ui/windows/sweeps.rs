@@ -0,0 +1,419 @@
+use eframe::egui;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::core::graph::Graph;
+use crate::core::job::{BackgroundJob, JobStatus};
+use crate::core::metrics::overlap;
+use crate::core::sweep::{ParameterRange, Sweep, SweepResults};
+use crate::graphics::export::write_csv;
+use crate::neural::hopfield::{HopfieldNetwork, SweepOrder, TrainingRule};
+use crate::ui::notifications::{report_error, report_info};
+use crate::ui::session::{self, SessionAction};
+use crate::ui::widgets::colormap::{color_cell, legend, Colormap};
+use crate::ui::widgets::job::job_progress;
+use crate::ui::widgets::seed::seed_control;
+use crate::ui::windows::{Command, Window};
+
+/// Overlap with the target above which a trial counts as retrieved
+const RETRIEVAL_OVERLAP: f64 = 0.9;
+
+/// Metrics measured at every combination
+const METRICS: [&str; 2] = ["overlap", "retrieved"];
+
+/// Largest number of combinations a sweep may have
+const MAX_COMBINATIONS: usize = 10_000;
+
+/// Settings of one swept parameter: `steps` values from `from` to `to`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RangeSetting {
+    name: &'static str,
+    label: &'static str,
+    from: f64,
+    to: f64,
+    steps: usize,
+    /// Slider bounds
+    min: f64,
+    max: f64,
+}
+
+impl RangeSetting {
+    fn range(&self) -> ParameterRange {
+        ParameterRange::linear(self.name, self.from, self.to, self.steps)
+    }
+}
+
+type SweepJob = BackgroundJob<SweepResults>;
+
+/// Recall of random patterns at one combination of (beta, noise, connectivity p,
+/// pattern count): the mean final overlap with the target and the fraction of trials
+/// that retrieved it
+fn evaluate_point(point: &[f64], neurons: usize, trials: usize, sweeps: usize, seed: u64) -> Vec<f64> {
+    let (beta, noise, connectivity, count) = (point[0], point[1], point[2], point[3]);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let count = (count.round() as usize).max(1);
+    let patterns: Vec<Vec<f64>> = (0..count)
+        .map(|_| (0..neurons).map(|_| if rng.gen() { 1.0 } else { -1.0 }).collect())
+        .collect();
+
+    let mut net = HopfieldNetwork::new(neurons);
+    if net.train(&patterns, TrainingRule::Hebbian).is_err() {
+        return vec![f64::NAN; METRICS.len()];
+    }
+    if connectivity < 1.0 {
+        let graph = Graph::erdos_renyi(neurons, connectivity.clamp(0.0, 1.0), &mut rng);
+        if net.apply_graph_topology(&graph).is_err() {
+            return vec![f64::NAN; METRICS.len()];
+        }
+    }
+
+    let (mut total_overlap, mut retrieved) = (0.0, 0);
+    for trial in 0..trials {
+        let target = &patterns[trial % count];
+        let cue: Vec<f64> = target.iter().map(|&v| if rng.gen::<f64>() < noise { -v } else { v }).collect();
+        let Ok((history, _)) = net.run_async(&cue, sweeps, beta, SweepOrder::RandomPermutation, &mut rng) else {
+            continue;
+        };
+        let m = history.last().map_or(0.0, |state| overlap(state, target));
+        total_overlap += m;
+        if m >= RETRIEVAL_OVERLAP {
+            retrieved += 1;
+        }
+    }
+    let trials = trials.max(1) as f64;
+    vec![total_overlap / trials, retrieved as f64 / trials]
+}
+
+/// Window running grid searches over the parameters of Hopfield recall on worker
+/// threads, with a heatmap of any two parameters and CSV export of the results
+pub struct SweepsWindow {
+    /// Swept parameters: beta, noise, connectivity p and pattern count, in that order
+    ranges: [RangeSetting; 4],
+    neurons: usize,
+    trials: usize,
+    sweeps: usize,
+    threads: usize,
+
+    job: Option<SweepJob>,
+    results: Option<SweepResults>,
+
+    /// Parameters on the heatmap axes and the metric it shows
+    x_parameter: usize,
+    y_parameter: usize,
+    metric: usize,
+
+    csv_path: String,
+    seed: u64,
+}
+
+impl SweepsWindow {
+    pub fn new() -> Self {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Self {
+            ranges: [
+                RangeSetting { name: "beta", label: "β", from: 0.5, to: 5.0, steps: 6, min: 0.0, max: 20.0 },
+                RangeSetting { name: "noise", label: "Noise", from: 0.0, to: 0.4, steps: 5, min: 0.0, max: 0.5 },
+                RangeSetting { name: "p", label: "Connectivity p", from: 1.0, to: 1.0, steps: 1, min: 0.0, max: 1.0 },
+                RangeSetting { name: "patterns", label: "Patterns", from: 2.0, to: 14.0, steps: 4, min: 1.0, max: 50.0 },
+            ],
+            neurons: 100,
+            trials: 20,
+            sweeps: 20,
+            threads,
+            job: None,
+            results: None,
+            x_parameter: 0,
+            y_parameter: 1,
+            metric: 0,
+            csv_path: "sweep.csv".to_string(),
+            seed: rand::random::<u32>() as u64,
+        }
+    }
+
+    fn sweep(&self) -> Sweep {
+        Sweep {
+            ranges: self.ranges.iter().map(RangeSetting::range).collect(),
+            metrics: METRICS.iter().map(|metric| metric.to_string()).collect(),
+        }
+    }
+
+    fn start_sweep(&mut self) {
+        if self.job.is_some() {
+            return;
+        }
+        let sweep = self.sweep();
+        if sweep.len() > MAX_COMBINATIONS {
+            report_error(format!("The sweep has {} combinations, at most {} are allowed", sweep.len(), MAX_COMBINATIONS));
+            return;
+        }
+        let (neurons, trials, sweeps, threads, seed) = (self.neurons, self.trials, self.sweeps, self.threads, self.seed);
+        self.job = Some(BackgroundJob::spawn("Sweep", sweep.len(), move |ctx| {
+            sweep.run(
+                threads,
+                |index, point| evaluate_point(point, neurons, trials, sweeps, seed.wrapping_add(index as u64)),
+                |done| {
+                    ctx.report(done);
+                    !ctx.is_cancelled()
+                },
+            )
+        }));
+    }
+
+    fn poll_sweep(&mut self) {
+        let Some(job) = &mut self.job else {
+            return;
+        };
+        let result = match job.poll() {
+            JobStatus::Running => return,
+            JobStatus::Finished(results) => Ok(results),
+            JobStatus::Failed => Err("Sweep thread stopped unexpectedly"),
+        };
+        self.job = None;
+        match result {
+            Ok(results) => self.results = Some(results),
+            Err(e) => report_error(format!("Sweep Error: {}", e)),
+        }
+    }
+
+    fn export_csv(&self) {
+        let Some(results) = &self.results else {
+            return;
+        };
+        match write_csv(&self.csv_path, &results.header(), &results.table()) {
+            Ok(()) => report_info(format!("Saved sweep to {}", self.csv_path)),
+            Err(e) => report_error(format!("Failed to save '{}': {}", self.csv_path, e)),
+        }
+    }
+
+    /// Apply a setting from a session journal
+    fn apply_session_setting(&mut self, key: &str, value: &str) -> bool {
+        if let Some(range) = self.ranges.iter_mut().find(|range| range.name == key) {
+            let parts: Vec<&str> = value.split(',').collect();
+            let [from, to, steps] = parts[..] else { return false };
+            let (Ok(from), Ok(to), Ok(steps)) = (from.parse(), to.parse(), steps.parse()) else {
+                return false;
+            };
+            (range.from, range.to, range.steps) = (from, to, steps);
+            return true;
+        }
+        let Ok(number) = value.parse::<u64>() else { return false };
+        match key {
+            "neurons" => self.neurons = number as usize,
+            "trials" => self.trials = number as usize,
+            "sweeps" => self.sweeps = number as usize,
+            "threads" => self.threads = number as usize,
+            "seed" => self.seed = number,
+            _ => return false,
+        }
+        true
+    }
+
+    /// The mean of the selected metric over the two selected parameters
+    fn show_heatmap(&mut self, ui: &mut egui::Ui) {
+        let Some(results) = &self.results else {
+            return;
+        };
+        let names: Vec<&str> = self.ranges.iter().map(|range| range.label).collect();
+        ui.horizontal(|ui| {
+            for (label, selected, id) in [("X:", &mut self.x_parameter, "sweep_x"), ("Y:", &mut self.y_parameter, "sweep_y")] {
+                ui.label(label);
+                egui::ComboBox::from_id_source(id).selected_text(names[*selected]).show_ui(ui, |ui| {
+                    for (i, name) in names.iter().enumerate() {
+                        ui.selectable_value(selected, i, *name);
+                    }
+                });
+            }
+            ui.label("Metric:");
+            egui::ComboBox::from_id_source("sweep_metric").selected_text(METRICS[self.metric]).show_ui(ui, |ui| {
+                for (i, metric) in METRICS.iter().enumerate() {
+                    ui.selectable_value(&mut self.metric, i, *metric);
+                }
+            });
+        });
+
+        let map = results.heatmap(self.x_parameter, self.y_parameter, self.metric);
+        let (min, max) = map.range().unwrap_or((0.0, 1.0));
+        egui::ScrollArea::horizontal().id_source("sweep_heatmap").show(ui, |ui| {
+            egui::Grid::new("sweep_heatmap_grid")
+                .min_col_width(30.0)
+                .spacing([5.0, 5.0])
+                .show(ui, |ui| {
+                    ui.label(format!("{} \\ {}", names[self.y_parameter], names[self.x_parameter]));
+                    for x in &map.x_values {
+                        ui.label(format!("{:.2}", x));
+                    }
+                    ui.end_row();
+                    for (y, line) in map.y_values.iter().zip(&map.values) {
+                        ui.label(format!("{:.2}", y));
+                        for value in line {
+                            match value {
+                                Some(value) => color_cell(ui, Colormap::Viridis.map(*value, min, max), &format!("{:.2}", value)),
+                                None => ui.label("-"),
+                            };
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        legend(ui, Colormap::Viridis, min, max, &format!("Mean {}:", METRICS[self.metric]));
+        if self.x_parameter == self.y_parameter {
+            ui.label("Pick two different parameters to see how they interact.");
+        }
+    }
+}
+
+impl Window for SweepsWindow {
+    fn name(&self) -> &str {
+        "Sweeps"
+    }
+
+    fn handle_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::Run => self.start_sweep(),
+            Command::Reset => self.results = None,
+            Command::Step | Command::Train => return false,
+        }
+        true
+    }
+
+    fn session_settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings: Vec<(&'static str, String)> = self
+            .ranges
+            .iter()
+            .map(|range| (range.name, format!("{},{},{}", range.from, range.to, range.steps)))
+            .collect();
+        settings.extend([
+            ("neurons", self.neurons.to_string()),
+            ("trials", self.trials.to_string()),
+            ("sweeps", self.sweeps.to_string()),
+            ("threads", self.threads.to_string()),
+            ("seed", self.seed.to_string()),
+        ]);
+        settings
+    }
+
+    fn replay(&mut self, action: &SessionAction) -> bool {
+        match action {
+            SessionAction::Command(command) => self.handle_command(*command),
+            SessionAction::Set { key, value } => self.apply_session_setting(key, value),
+            SessionAction::Perform { .. } => false,
+        }
+    }
+
+    fn is_busy(&self) -> bool {
+        self.job.is_some()
+    }
+
+    fn show_config(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Parameters");
+        ui.separator();
+
+        ui.add_enabled_ui(self.job.is_none(), |ui| {
+            egui::Grid::new("sweep_ranges").num_columns(4).show(ui, |ui| {
+                ui.label("");
+                ui.label("From");
+                ui.label("To");
+                ui.label("Steps");
+                ui.end_row();
+                for range in &mut self.ranges {
+                    let speed = (range.max - range.min) / 200.0;
+                    ui.label(range.label);
+                    ui.add(egui::DragValue::new(&mut range.from).speed(speed).range(range.min..=range.max));
+                    ui.add_enabled(range.steps > 1, egui::DragValue::new(&mut range.to).speed(speed).range(range.min..=range.max));
+                    ui.add(egui::DragValue::new(&mut range.steps).speed(0.1).range(1..=50));
+                    ui.end_row();
+                }
+            });
+            ui.label(format!("{} combinations", self.sweep().len()));
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Neurons:");
+                ui.add(egui::DragValue::new(&mut self.neurons).speed(1.0).range(16..=1024));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Trials:");
+                ui.add(egui::DragValue::new(&mut self.trials).speed(1.0).range(1..=500))
+                    .on_hover_text("Noisy cues per combination");
+                ui.label("Sweeps:");
+                ui.add(egui::DragValue::new(&mut self.sweeps).speed(1.0).range(1..=200))
+                    .on_hover_text("Asynchronous sweeps per trial");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Threads:");
+                ui.add(egui::DragValue::new(&mut self.threads).speed(0.1).range(1..=64));
+            });
+            seed_control(ui, &mut self.seed);
+        });
+
+        if ui.add_enabled(self.job.is_none(), egui::Button::new("Run Sweep"))
+            .on_hover_text("Train a network with random patterns at every combination and measure recall")
+            .clicked()
+        {
+            session::record(self.name(), SessionAction::Command(Command::Run));
+            self.start_sweep();
+        }
+
+        ui.separator();
+        ui.heading("Export");
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("CSV:");
+            ui.text_edit_singleline(&mut self.csv_path);
+        });
+        if ui.add_enabled(self.results.is_some(), egui::Button::new("Export CSV")).clicked() {
+            self.export_csv();
+        }
+    }
+
+    fn show_content(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        self.poll_sweep();
+        ui.heading("Parameter Sweep: Hopfield Recall");
+        ui.separator();
+
+        if let Some(job) = &self.job {
+            job_progress(ui, job);
+        }
+        let Some(results) = &self.results else {
+            if self.job.is_none() {
+                ui.vertical_centered(|ui| {
+                    ui.label("No sweep run yet. Set the parameter ranges and press Run Sweep.");
+                });
+            }
+            return;
+        };
+        ui.label(format!("{} of {} combinations evaluated", results.rows.len(), results.sweep.len()));
+        ui.separator();
+
+        self.show_heatmap(ui);
+
+        let Some(results) = &self.results else {
+            return;
+        };
+        ui.separator();
+        egui::CollapsingHeader::new("Results Table").show(ui, |ui| {
+            let header = results.header();
+            let table = results.table();
+            let row_height = ui.text_style_height(&egui::TextStyle::Body);
+            egui::ScrollArea::vertical().id_source("sweep_table").max_height(300.0).show_rows(
+                ui,
+                row_height,
+                table.len(),
+                |ui, rows| {
+                    egui::Grid::new("sweep_table_grid").striped(true).show(ui, |ui| {
+                        for name in &header {
+                            ui.strong(*name);
+                        }
+                        ui.end_row();
+                        for row in &table[rows] {
+                            for value in row {
+                                ui.label(format!("{:.3}", value));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                },
+            );
+        });
+    }
+}
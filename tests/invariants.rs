@@ -0,0 +1,145 @@
+//! Property-based tests of the invariants the Hopfield and chip-firing dynamics rely on
+
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use raum::neural::chip_firing::{ChipFiringGraph, UpdateMode, VertexSelectionStrategy};
+use raum::neural::hopfield::{HopfieldNetwork, TrainingRule};
+
+/// Firing budget for graphs that are guaranteed to stabilize
+const MAX_FIRINGS: usize = 100_000;
+
+fn bipolar(n: usize) -> impl Strategy<Value = Vec<f64>> {
+    prop::collection::vec(any::<bool>(), n).prop_map(|bits| bits.into_iter().map(|b| if b { 1.0 } else { -1.0 }).collect())
+}
+
+/// Network size, one to three stored patterns and a start state
+fn patterns_and_state() -> impl Strategy<Value = (usize, Vec<Vec<f64>>, Vec<f64>)> {
+    (8usize..=32).prop_flat_map(|n| (Just(n), prop::collection::vec(bipolar(n), 1..=3), bipolar(n)))
+}
+
+/// Duplicated or opposite patterns make the pseudo-inverse rule singular
+fn independent(patterns: &[Vec<f64>]) -> bool {
+    patterns.iter().enumerate().all(|(a, p)| {
+        patterns[..a].iter().all(|q| p.iter().zip(q).map(|(x, y)| x * y).sum::<f64>().abs() < p.len() as f64)
+    })
+}
+
+fn rule() -> impl Strategy<Value = TrainingRule> {
+    prop_oneof![Just(TrainingRule::Hebbian), Just(TrainingRule::PseudoInverse)]
+}
+
+/// An undirected multigraph on 2..=12 vertices without self-loops and a configuration on it
+fn graph_and_configuration() -> impl Strategy<Value = (Vec<(usize, usize, u32)>, Vec<i32>)> {
+    (2usize..=12).prop_flat_map(|n| {
+        (
+            prop::collection::vec((0..n, 0..n, 1u32..=3), 1..=3 * n)
+                .prop_map(|edges| edges.into_iter().filter(|&(i, j, _)| i != j).collect()),
+            prop::collection::vec(0i32..=8, n),
+        )
+    })
+}
+
+/// A small grid with a sink in the corner, which stabilizes from any configuration
+fn grid_with_sink() -> impl Strategy<Value = ChipFiringGraph> {
+    (2usize..=6, 2usize..=6).prop_flat_map(|(width, height)| {
+        prop::collection::vec(0i32..=10, width * height).prop_map(move |configuration| {
+            let mut graph = ChipFiringGraph::new_grid(width, height, configuration).unwrap();
+            graph.set_sink(Some(0)).unwrap();
+            graph
+        })
+    })
+}
+
+proptest! {
+    #[test]
+    fn trained_weights_are_symmetric_with_zero_diagonal(
+        (n, patterns, _) in patterns_and_state(),
+        rule in rule(),
+        dilution in prop::option::of(0.0f64..=1.0),
+        seed in any::<u64>(),
+    ) {
+        prop_assume!(rule == TrainingRule::Hebbian || independent(&patterns));
+        let mut net = HopfieldNetwork::new(n);
+        net.train(&patterns, rule).unwrap();
+        if let Some(p) = dilution {
+            net.apply_erdos_renyi_topology(p, &mut StdRng::seed_from_u64(seed));
+        }
+
+        let weights = net.weights();
+        let scale = 1.0 + weights.iter().flatten().fold(0.0f64, |m, w| m.max(w.abs()));
+        for (i, row) in weights.iter().enumerate() {
+            prop_assert_eq!(row[i], 0.0);
+            for (j, &w) in row.iter().enumerate().take(i) {
+                prop_assert!((w - weights[j][i]).abs() <= 1e-9 * scale);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_temperature_async_updates_never_increase_energy(
+        (n, patterns, state) in patterns_and_state(),
+        rule in rule(),
+        seed in any::<u64>(),
+    ) {
+        prop_assume!(rule == TrainingRule::Hebbian || independent(&patterns));
+        let mut net = HopfieldNetwork::new(n);
+        net.train(&patterns, rule).unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // At this beta every neuron with a nonzero field takes its sign
+        let beta = 1e9;
+        let mut state = state;
+        let mut energy = net.energy(&state).unwrap();
+        for _ in 0..5 * n {
+            net.update_step_async(&mut state, beta, &mut rng).unwrap();
+            let next = net.energy(&state).unwrap();
+            prop_assert!(next <= energy + 1e-9 * (1.0 + energy.abs()), "energy rose from {} to {}", energy, next);
+            energy = next;
+        }
+    }
+
+    #[test]
+    fn chips_are_conserved_without_a_sink(
+        (edges, configuration) in graph_and_configuration(),
+        parallel in any::<bool>(),
+        seed in any::<u64>(),
+    ) {
+        let n = configuration.len();
+        let mut graph = ChipFiringGraph::from_weighted_edge_list(&edges, n, configuration).unwrap();
+        graph.update_mode = if parallel { UpdateMode::Parallel } else { UpdateMode::Sequential };
+        graph.selection_strategy = VertexSelectionStrategy::RandomActive;
+        let total = graph.total_chips();
+
+        graph.run(50, &mut StdRng::seed_from_u64(seed)).unwrap();
+        prop_assert_eq!(graph.total_chips(), total);
+        graph.stabilize(1000).unwrap();
+        prop_assert_eq!(graph.total_chips(), total);
+    }
+
+    #[test]
+    fn stabilization_does_not_depend_on_firing_order(
+        graph in grid_with_sink(),
+        seed in any::<u64>(),
+    ) {
+        let mut worklist = graph.clone();
+        worklist.stabilize(MAX_FIRINGS).unwrap();
+        prop_assert!(worklist.is_stable());
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        for (mode, strategy) in [
+            (UpdateMode::Sequential, VertexSelectionStrategy::FirstActive),
+            (UpdateMode::Sequential, VertexSelectionStrategy::RandomActive),
+            (UpdateMode::Parallel, VertexSelectionStrategy::FirstActive),
+        ] {
+            let mut other = graph.clone();
+            other.update_mode = mode;
+            other.selection_strategy = strategy;
+            other.run(MAX_FIRINGS, &mut rng).unwrap();
+            prop_assert!(other.is_stable());
+            prop_assert_eq!(&other.configuration, &worklist.configuration);
+            prop_assert_eq!(&other.firing_counts, &worklist.firing_counts);
+        }
+    }
+}